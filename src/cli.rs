@@ -2,26 +2,30 @@ use std::{
 	fmt::Formatter,
 	fs::{read, File},
 	path::{Path, PathBuf},
-	sync::atomic::{AtomicU64, Ordering},
+	sync::atomic::{AtomicBool, AtomicU64, Ordering},
 };
 
+use anyhow::{Context, Result, bail, ensure};
 use glob::glob;
 
-use crate::workbench::tab::NbtFileFormat;
-use crate::{config, elements::element::NbtElement, error, history::WorkbenchAction, log, mutable_indices, render::widget::{
+use crate::workbench::{
+	marked_line::MarkedLines,
+	tab::{NbtFileFormat, Tab},
+};
+use crate::{config, elements::{element::NbtElement, validate::{self, Diagnostic, Severity}}, error, history::WorkbenchAction, log, mutable_indices, render::widget::{
+	alert::manager::AlertManager,
 	replace_box::{ReplaceBox, SearchReplacement},
-	search_box::{SearchBox, SearchFlags, SearchMode, SearchPredicate, SearchPredicateInner},
-}, util::create_regex, workbench::Workbench};
+	search_box::{SearchBox, SearchFlags, SearchMode, SearchPredicate, SearchPredicateInner, SearchTagType},
+}, serialization::decoder::BedrockHeader, tree::path::NbtPath, util::{self, create_regex}, workbench::Workbench};
 
+/// The matches within one file, each as its path and the SNBT of what it matched
 struct SearchResult {
-	path: PathBuf,
-	lines: Vec<usize>,
+	matches: Vec<String>,
 }
 
 impl std::fmt::Display for SearchResult {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-		writeln!(f, "Found {n} matches in file {path:?} at line numbers:", n = self.lines.len(), path = self.path)?;
-		for &line in &self.lines {
+		for line in &self.matches {
 			writeln!(f, "{line}")?;
 		}
 		Ok(())
@@ -44,7 +48,7 @@ fn get_paths(mut args: Vec<String>) -> (PathBuf, Vec<PathBuf>) {
 			} else if let Some(slash_index) = path.bytes().rposition(|x| x == b'/' || x == b'\\') {
 				PathBuf::from(&path[..=slash_index])
 			} else {
-				panic!("{path}")
+				PathBuf::new()
 			};
 			let paths = paths.filter_map(|result| result.ok()).filter_map(|p| p.strip_prefix(&root).ok().map(|x| x.to_path_buf())).collect::<Vec<_>>();
 			(root, paths)
@@ -57,56 +61,86 @@ fn get_paths(mut args: Vec<String>) -> (PathBuf, Vec<PathBuf>) {
 }
 
 #[must_use]
-fn get_search_predicate(args: &mut Vec<String>) -> SearchPredicate {
-	let Some(query) = args.pop() else {
-		error!("Could not find <query>");
-		std::process::exit(0)
-	};
+fn get_search_tag_type(args: &mut Vec<String>) -> SearchTagType {
+	match get_argument_any(&["--type", "-t"], args) {
+		Some(name) => SearchTagType::from_name(&name).unwrap_or_else(|| {
+			error!("Invalid tag type '{name}', valid ones are: `any`, `byte`, `short`, `int`, `long`, `float`, `double`, `string`, `list`, `compound`, `byte_array`, `int_array`, `long_array`, and `chunk`.");
+			std::process::exit(1);
+		}),
+		None => SearchTagType::Any,
+	}
+}
 
+#[must_use]
+fn get_search_predicate(args: &mut Vec<String>) -> SearchPredicate {
 	let search_flags = match get_argument_any(&["--search", "-s"], args).as_deref() {
 		Some("key") => SearchFlags::Keys,
 		Some("value") => SearchFlags::Values,
 		Some("any") | None => SearchFlags::KeysValues,
 		Some(x) => {
 			error!("Invalid search kind '{x}', valid ones are: `key`, `value`, and `any`.");
-			std::process::exit(1);
+			std::process::exit(2);
 		}
 	};
+	let search_flags = match (get_flag_any(&["--keys"], args), get_flag_any(&["--values"], args)) {
+		(true, false) => SearchFlags::Keys,
+		(false, true) => SearchFlags::Values,
+		_ => search_flags,
+	};
 
-	let exact_match = get_argument_any(&["-em", "--exact-match"], args).is_some();
+	let exact_match = get_flag_any(&["-em", "--exact-match"], args);
+	let tag_type = get_search_tag_type(args);
+	let mode = if get_flag_any(&["--regex"], args) { Some("regex".to_owned()) } else { get_argument_any(&["--mode", "-m"], args) };
 
-	match get_argument_any(&["--mode", "-m"], args).as_deref() {
+	// the flags are taken out first, so they can come after the query too
+	let Some(query) = args.pop() else {
+		error!("Could not find <query>");
+		std::process::exit(2)
+	};
+
+	if query.is_empty() {
+		return SearchPredicate {
+			search_flags,
+			tag_type,
+			inner: SearchPredicateInner::Any,
+		}
+	}
+
+	match mode.as_deref() {
 		Some("normal") | None => SearchPredicate {
 			search_flags,
+			tag_type,
 			inner: if exact_match {
 				SearchPredicateInner::String(query)
 			} else {
 				SearchPredicateInner::StringCaseInsensitive(query.to_lowercase())
 			},
 		},
-		Some("regex") =>
-			if let Some(regex) = create_regex(query, exact_match) {
-				SearchPredicate {
-					search_flags,
-					inner: SearchPredicateInner::Regex(regex),
-				}
-			} else {
-				error!("Invalid regex, valid regexes look like: `/[0-9]+/g`");
-				std::process::exit(1);
+		Some("regex") => match create_regex(query, exact_match) {
+			Ok(regex) => SearchPredicate {
+				search_flags,
+				tag_type,
+				inner: SearchPredicateInner::Regex(regex),
 			},
+			Err(e) => {
+				error!("Invalid regex ({e}), valid regexes look like: `/[0-9]+/g`");
+				std::process::exit(2);
+			}
+		},
 		Some("snbt") => match NbtElement::from_str(&query) {
 			Ok((key, snbt)) => SearchPredicate {
 				search_flags,
+				tag_type,
 				inner: if exact_match { SearchPredicateInner::SnbtExactMatch((key, snbt)) } else { SearchPredicateInner::Snbt((key, snbt)) },
 			},
 			Err(idx) => {
 				error!(r#"Invalid snbt at index {idx}, valid snbt look like: `key:"minecraft:air"` or `{{id:"minecraft:looting",lvl:3s}}` (note that some terminals use "" to contain one parameter and that inner ones will have to be escaped)"#);
-				std::process::exit(1);
+				std::process::exit(2);
 			}
 		},
 		Some(x) => {
 			error!("Invalid mode '{x}', valid ones are: `normal', `regex`, and `snbt`.");
-			std::process::exit(1);
+			std::process::exit(2);
 		}
 	}
 }
@@ -148,6 +182,7 @@ fn get_search_replacement(args: &mut Vec<String>) -> SearchReplacement {
 	};
 
 	config::set_search_flags(search_flags);
+	config::set_search_tag_type(get_search_tag_type(args));
 	config::set_search_exact_match(exact_match);
 	config::set_search_mode(search_mode);
 
@@ -165,8 +200,9 @@ fn file_size(path: impl AsRef<Path>) -> Option<u64> { File::open(path).ok().and_
 
 fn increment_progress_bar(completed: &AtomicU64, size: u64, total: u64, action: &str) {
 	let finished = completed.fetch_add(size, Ordering::Relaxed);
-	print!("\r{action}... ({n} / {total} bytes) ({p:.1}% complete)", n = finished, p = 100.0 * finished as f64 / total as f64);
-	let _ = std::io::Write::flush(&mut std::io::stdout());
+	// progress goes to stderr so it doesn't get mixed into output piped elsewhere
+	eprint!("\r{action}... ({n} / {total} bytes) ({p:.1}% complete)", n = finished, p = 100.0 * finished as f64 / total as f64);
+	let _ = std::io::Write::flush(&mut std::io::stderr());
 }
 
 #[must_use]
@@ -175,6 +211,46 @@ fn get_argument(key: &str, args: &mut Vec<String>) -> Option<String> { Some(args
 #[must_use]
 fn get_argument_any(keys: &[&str], args: &mut Vec<String>) -> Option<String> { keys.iter().filter_map(|key| get_argument(key, args)).next() }
 
+/// Whether any of `keys` was passed as a flag, like `--regex`
+#[must_use]
+fn get_flag_any(keys: &[&str], args: &mut Vec<String>) -> bool {
+	let len = args.len();
+	args.retain(|arg| !keys.contains(&arg.as_str()));
+	args.len() != len
+}
+
+/// Like [`get_argument_any`], but also takes the value from the next argument when written as `--key value`
+#[must_use]
+fn get_option_any(keys: &[&str], args: &mut Vec<String>) -> Option<String> {
+	if let Some(value) = get_argument_any(keys, args) {
+		return Some(value)
+	}
+	let idx = args.iter().position(|arg| keys.contains(&arg.as_str()))?;
+	if idx + 1 >= args.len() {
+		return None
+	}
+	args.remove(idx);
+	Some(args.remove(idx))
+}
+
+/// The default extension and format for a `--format` name
+#[must_use]
+fn get_format(name: &str) -> Option<(&'static str, NbtFileFormat)> {
+	Some(match name {
+		"nbt" => ("nbt", NbtFileFormat::Nbt),
+		"netnbt" => ("nbt", NbtFileFormat::NetworkNbt),
+		"dat" | "gzip" => ("dat", NbtFileFormat::Gzip),
+		"dat_old" => ("dat_old", NbtFileFormat::Gzip),
+		"zlib" => ("zlib", NbtFileFormat::Zlib),
+		"zst" | "zstd" => ("zst", NbtFileFormat::Zstd),
+		"lz4" => ("lz4", NbtFileFormat::Lz4),
+		"snbt" => ("snbt", NbtFileFormat::Snbt),
+		"lnbt" => ("nbt", NbtFileFormat::LittleEndianNbt),
+		"lhnbt" => ("nbt", NbtFileFormat::LittleEndianHeaderNbt(BedrockHeader::DEFAULT_VERSION)),
+		_ => return None,
+	})
+}
+
 pub fn find() -> ! {
 	let mut args = std::env::args().collect::<Vec<_>>();
 	// one for the exe, one for the `find`
@@ -182,25 +258,29 @@ pub fn find() -> ! {
 
 	let predicate = get_search_predicate(&mut args);
 	let (root, paths) = get_paths(args);
+	if paths.is_empty() {
+		error!("Could not find any files to search");
+		std::process::exit(2);
+	}
 
 	let completed = AtomicU64::new(0);
 	let total_size = paths.iter().filter_map(file_size).sum::<u64>();
+	let failed = AtomicBool::new(false);
 
-	print!("Searching... (0 / {total_size} bytes) (0.0% complete)");
-	let _ = std::io::Write::flush(&mut std::io::stdout());
+	eprint!("Searching... (0 / {total_size} bytes) (0.0% complete)");
+	let _ = std::io::Write::flush(&mut std::io::stderr());
 	let results = std::thread::scope(|s| {
 		let mut results = Vec::new();
 		for p in paths {
 			let mut path = root.clone();
 			path.push(p);
 			results.push(s.spawn(|| {
-				let mut workbench = Workbench::new(None).expect("Valid workbench constructable");
-				drop(workbench.tabs.remove(0));
-
+				let path = path;
 				let bytes = match read(&path) {
 					Ok(bytes) => bytes,
 					Err(e) => {
-						error!("File read error: {e}");
+						error!("\rFile read error: {e}");
+						failed.store(true, Ordering::Relaxed);
 						increment_progress_bar(&completed, file_size(&path).unwrap_or(0), total_size, "Searching");
 						return None;
 					}
@@ -208,42 +288,53 @@ pub fn find() -> ! {
 
 				let len = bytes.len() as u64;
 
-				if let Err(e) = workbench.on_open_file(&path, bytes) {
-					error!("File parse error: {e}");
-					increment_progress_bar(&completed, len, total_size, "Searching");
-					return None;
-				}
-
-				let tab = workbench.tabs.remove(0).expect("Expected a tab");
-				let bookmarks = SearchBox::search0(&tab.root, &predicate);
+				let mut root = match Tab::parse_raw(&path, bytes) {
+					Ok((root, _)) => root,
+					Err(e) => {
+						error!("\rFile parse error in {path}: {e:#}", path = path.display());
+						failed.store(true, Ordering::Relaxed);
+						increment_progress_bar(&completed, len, total_size, "Searching");
+						return None;
+					}
+				};
+				root.decode_chunks(&mut MarkedLines::new());
+
+				let name = path.display().to_string();
+				let matches = SearchBox::search_indices(&root, &predicate)
+					.into_iter()
+					.filter_map(|indices| {
+						let location = NbtPath::display_from_indices(&root, &indices, &name)?;
+						let value = indices.iter().try_fold(&root, |element, idx| element.get(idx).map(|(_, child)| child))?;
+						// regions are already prefixed with the file and chunk
+						Some(if root.is_region() { format!("{location}\t{value}") } else { format!("{name} → {location}\t{value}") })
+					})
+					.collect::<Vec<_>>();
 
 				increment_progress_bar(&completed, len, total_size, "Searching");
-				drop(tab);
-				if !bookmarks.is_empty() {
-					Some(SearchResult {
-						path,
-						lines: bookmarks.into_iter().map(|x| x.true_line_number()).collect(),
-					})
-				} else {
-					None
-				}
+				drop(root);
+				if !matches.is_empty() { Some(SearchResult { matches }) } else { None }
 			}));
 		}
 
 		results.into_iter().filter_map(|x| x.join().ok()).filter_map(std::convert::identity).collect::<Vec<_>>()
 	});
 
-	log!("\rSearching ({total_size} / {total_size} bytes) (100.0% complete)");
+	eprintln!("\rSearching ({total_size} / {total_size} bytes) (100.0% complete)");
 
 	if results.is_empty() {
-		log!("No results found.")
+		error!("No results found.");
 	}
 
-	for result in results {
-		log!("{result}")
+	for result in &results {
+		print!("{result}");
 	}
 
-	std::process::exit(0);
+	// like grep, so it can be used in scripts
+	std::process::exit(match (failed.load(Ordering::Relaxed), results.is_empty()) {
+		(true, _) => 2,
+		(false, true) => 1,
+		(false, false) => 0,
+	});
 }
 
 pub fn replace() -> ! {
@@ -256,8 +347,8 @@ pub fn replace() -> ! {
 	let completed = AtomicU64::new(0);
 	let total_size = paths.iter().filter_map(file_size).sum::<u64>();
 
-	print!("Replacing... (0 / {total_size} bytes) (0.0% complete)");
-	let _ = std::io::Write::flush(&mut std::io::stdout());
+	eprint!("Replacing... (0 / {total_size} bytes) (0.0% complete)");
+	let _ = std::io::Write::flush(&mut std::io::stderr());
 	let results = std::thread::scope(|s| {
 		let mut results = vec![];
 		for p in paths {
@@ -284,14 +375,16 @@ pub fn replace() -> ! {
 					return None;
 				}
 
-				let mut tab = workbench.tabs.remove(0).expect("Expected a tab");
+				let mut tab = workbench.tabs.remove(0);
 				let (bulk, errors) = ReplaceBox::replace_by_search_box0(mutable_indices!(tab), &mut tab.root, &replacement);
 				for e in errors {
 					error!("Error while replacing line: {e}");
 				}
 				let actions = if let WorkbenchAction::Bulk { actions } = &bulk { actions.len() } else { 0 };
 
-				if let Err(e) = tab.save(false) {
+				// warnings are logged as they're alerted
+				let mut alerts = AlertManager::new();
+				if let Err(e) = tab.save(false, &mut alerts).and_then(|()| tab.finish_save(true, &mut alerts)) {
 					error!("File write error: {e}");
 				}
 
@@ -306,7 +399,7 @@ pub fn replace() -> ! {
 		results.into_iter().filter_map(|x| x.join().ok()).filter_map(std::convert::identity).collect::<Vec<_>>()
 	});
 
-	log!("\rReplacing ({total_size} / {total_size} bytes) (100.0% complete)");
+	eprintln!("\rReplacing ({total_size} / {total_size} bytes) (100.0% complete)");
 
 	if results.is_empty() {
 		log!("No changes made.")
@@ -327,22 +420,14 @@ pub fn reformat() -> ! {
 	let mut args = std::env::args().collect::<Vec<_>>();
 	args.drain(..2);
 
-	let format_arg = get_argument_any(&["--format", "-f"], &mut args);
-	let (extension, format) = match format_arg.as_deref() {
-		Some(x @ "nbt") => (x, NbtFileFormat::Nbt),
-		Some(x @ ("dat" | "dat_old" | "gzip")) => (if x == "gzip" { "dat" } else { x }, NbtFileFormat::Gzip),
-		Some(x @ "zlib") => (x, NbtFileFormat::Zlib),
-		Some(x @ "snbt") => (x, NbtFileFormat::Snbt),
-		Some(x @ ("lnbt" | "lhnbt")) => ("nbt", if x == "lnbt" { NbtFileFormat::LittleEndianNbt } else { NbtFileFormat::LittleEndianHeaderNbt }),
-		None => {
-			error!("`--format` not specified.");
-			std::process::exit(1);
-		}
-		Some(x) => {
-			error!("Invalid format '{x}'");
-			std::process::exit(1);
-		}
+	let Some(format_arg) = get_argument_any(&["--format", "-f"], &mut args) else {
+		error!("`--format` not specified.");
+		std::process::exit(1);
 	};
+	let (extension, format) = get_format(&format_arg).unwrap_or_else(|| {
+		error!("Invalid format '{format_arg}'");
+		std::process::exit(1);
+	});
 
 	let extension = if let Some(extension) = get_argument_any(&["--out-ext", "-e"], &mut args) { extension } else { extension.to_owned() };
 
@@ -353,8 +438,8 @@ pub fn reformat() -> ! {
 	let completed = AtomicU64::new(0);
 	let total_size = paths.iter().filter_map(file_size).sum::<u64>();
 
-	print!("Reformatting... (0 / {total_size} bytes) (0.0% complete)");
-	let _ = std::io::Write::flush(&mut std::io::stdout());
+	eprint!("Reformatting... (0 / {total_size} bytes) (0.0% complete)");
+	let _ = std::io::Write::flush(&mut std::io::stderr());
 	std::thread::scope(|s| {
 		for p in paths {
 			let mut pa = root.clone();
@@ -382,8 +467,8 @@ pub fn reformat() -> ! {
 					break 'a;
 				}
 
-				let tab = workbench.tabs.remove(0).expect("Expected a tab");
-				if let NbtFileFormat::Nbt | NbtFileFormat::Snbt | NbtFileFormat::Gzip | NbtFileFormat::Zlib = tab.format {
+				let tab = workbench.tabs.remove(0);
+				if let NbtFileFormat::Nbt | NbtFileFormat::NetworkNbt | NbtFileFormat::Snbt | NbtFileFormat::Gzip | NbtFileFormat::Zlib | NbtFileFormat::Zstd | NbtFileFormat::Lz4 = tab.format {
 				} else {
 					error!("Tab had invalid file format {}", tab.format.to_string());
 				}
@@ -410,27 +495,122 @@ pub fn reformat() -> ! {
 		}
 	});
 
-	log!("\rReformatting ({total_size} / {total_size} bytes) (100.0% complete)");
+	eprintln!("\rReformatting ({total_size} / {total_size} bytes) (100.0% complete)");
 
 	std::process::exit(0);
 }
 
+/// Converts a single file without opening a window, so it can be scripted
+pub fn convert() -> ! {
+	let mut args = std::env::args().collect::<Vec<_>>();
+	args.drain(..2);
+
+	match convert0(args) {
+		Ok((input, input_format, output, output_format)) => {
+			log!("Converted {input} ({input_format}) to {output} ({output_format})", input = input.display(), output = output.display());
+			std::process::exit(0);
+		}
+		Err(e) => {
+			error!("{e:#}");
+			std::process::exit(1);
+		}
+	}
+}
+
+fn convert0(mut args: Vec<String>) -> Result<(PathBuf, NbtFileFormat, PathBuf, NbtFileFormat)> {
+	let format_arg = get_option_any(&["--format", "-f"], &mut args).context("`--format` not specified")?;
+	let (extension, format) = match format_arg.as_str() {
+		"mca" | "mcr" => (format_arg.as_str(), NbtFileFormat::Mca),
+		x => get_format(x).with_context(|| format!("Invalid format '{x}'"))?,
+	};
+	let output = get_option_any(&["--out", "-o"], &mut args).map(PathBuf::from);
+	let input = match args.as_slice() {
+		[input] => PathBuf::from(input),
+		[] => bail!("Could not find <path>"),
+		[_, unexpected, ..] => bail!("Unexpected argument '{unexpected}'"),
+	};
+	let output = output.unwrap_or_else(|| input.with_extension(extension));
+
+	let bytes = read(&input).with_context(|| format!("Could not read {}", input.display()))?;
+	let (mut root, input_format) = Tab::parse_raw(&input, bytes).with_context(|| format!("Could not parse {}", input.display()))?;
+	// region files are the only roots which can be written as one, and other binary formats can't hold them
+	ensure!((format == NbtFileFormat::Mca) == root.is_region() || format == NbtFileFormat::Snbt, "{input_format} can't be converted to {format}");
+	root.decode_chunks(&mut MarkedLines::new());
+
+	let out = format.encode(&root);
+	util::write_atomic(&output, &out).with_context(|| format!("Could not write {}", output.display()))?;
+	Ok((input, input_format, output, format))
+}
+
+/// Reads a file and reports anything wrong with it, exiting with 1 if some of it was or would be lost
+pub fn validate() -> ! {
+	let mut args = std::env::args().collect::<Vec<_>>();
+	args.drain(..2);
+
+	let [path] = args.as_slice() else {
+		error!("Expected exactly one <path>");
+		std::process::exit(1);
+	};
+	let path = PathBuf::from(path);
+	let bytes = read(&path).unwrap_or_else(|e| {
+		error!("Could not read {}: {e}", path.display());
+		std::process::exit(1);
+	});
+	let (mut root, format) = Tab::parse_raw(&path, bytes).unwrap_or_else(|e| {
+		error!("Could not parse {}: {e:#}", path.display());
+		std::process::exit(1);
+	});
+	root.decode_chunks(&mut MarkedLines::new());
+
+	log!("Format: {format}");
+	log!("Root: {}", root.display_name());
+	log!("Height: {} ({} including closed elements)", root.height(), root.true_height());
+
+	let name = path.display().to_string();
+	let diagnostics = validate::validate(&root);
+	for Diagnostic { severity, indices, message } in &diagnostics {
+		let location = NbtPath::display_from_indices(&root, indices, &name).unwrap_or_else(|| name.clone());
+		log!("{severity}: {location}: {message}");
+	}
+
+	let errors = diagnostics.iter().filter(|diagnostic| diagnostic.severity == Severity::Error).count();
+	let warnings = diagnostics.len() - errors;
+	log!(
+		"{errors} error{error_suffix}, {warnings} warning{warning_suffix}",
+		error_suffix = if errors == 1 { "" } else { "s" },
+		warning_suffix = if warnings == 1 { "" } else { "s" }
+	);
+	std::process::exit(i32::from(errors > 0));
+}
+
 pub fn help() -> ! {
 	println!(
 		r#"
 Usage:
+  nbtworkbench [--new-window] [<path>...]
   nbtworkbench --version|-v
   nbtworkbench -?|-h|--help|/?
-  nbtworkbench find <path> [(--mode|-m)=(normal|regex|snbt)] [(--search|-s)=(key|value|any)] [--exact-match|-em] <query>
+  nbtworkbench convert <path> (--format|-f)=<format> [(--out|-o)=<out>]
+  nbtworkbench find <path> <query> [--regex|(--mode|-m)=(normal|regex|snbt)] [--keys|--values|(--search|-s)=(key|value|any)] [(--type|-t)=<type>] [--exact-match|-em]
+  nbtworkbench validate <path>
   nbtworkbench reformat (--format|-f)=<format> [(--out-dir|-d)=<out-dir>] [(--out-ext|-e)=<out-ext>] <path>
-  nbtworkbench replace <path> [(--mode|-m)=(normal|regex|snbt)] [(--search|-s)=(key|value|any)] [--exact-match|-em] <find> "<replace>"
+  nbtworkbench replace <path> [(--mode|-m)=(normal|regex|snbt)] [(--search|-s)=(key|value|any)] [(--type|-t)=<type>] [--exact-match|-em] <find> "<replace>"
+
+`find` prints each match as its path, then a tab, then its SNBT. Matches within region files start with the chunk they're in. It exits with 0 if there were matches, 1 if there weren't, and 2 if a file couldn't be read or parsed.
+
+`validate` prints the file's format, root type and height, then every problem with it. It exits with 1 if any of them is an error, which means some of the file was lost when reading it or would be when the game reads it.
 
 Options:
+  --new-window        Opens the files in a new window, rather than as tabs in the one already open.
   --version, -v       Displays the version of nbtworkbench you're running.
   -?, -h, --help, /?  Displays this dialog.
+  --regex             Same as `--mode=regex`.
+  --keys, --values    Same as `--search=key` and `--search=value`.
   --mode, -m          Changes the `find` mode to take the <query> field as either, a containing substring, a regex (match whole), or snbt. [default: normal]
   --search, -s        Searches for results matching the <query> in either, the key, the value, or both (note that substrings and regex search the same pattern in both key and value, while the regex uses it's key field to match equal strings). [default: any]
-  --format, -f        Specifies the format to be reformatted to; either `nbt`, `snbt`, `dat/dat_old/gzip`, `zlib`, 'lnbt' (little endian nbt), or 'lhnbt' (little endian nbt with header).
+  --type, -t          Only matches tags of this type; either `any`, `byte`, `short`, `int`, `long`, `float`, `double`, `string`, `list`, `compound`, `byte_array`, `int_array`, `long_array`, or `chunk`. An empty <query> matches every tag of the type. [default: any]
+  --format, -f        Specifies the format to be reformatted to; either `nbt`, `netnbt` (nbt without the root's name, as in packets since 1.20.2), `snbt`, `dat/dat_old/gzip`, `zlib`, `zst/zstd`, `lz4`, 'lnbt' (little endian nbt), or 'lhnbt' (little endian nbt with header). `convert` also takes `mca/mcr` for region files.
+  --out, -o           Specifies the file `convert` writes to. [default: <path> with the extension of --format]
   --out-dir, -d       Specifies the output directory. [default: ./]
   --out-ext, -e       Specifies the output file extension (if not specified, it will infer from --format)"#
 	);
@@ -1,5 +1,5 @@
 use std::{
-	path::Path,
+	path::{Path, PathBuf},
 	sync::atomic::{AtomicBool, Ordering},
 };
 
@@ -9,18 +9,21 @@ use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+	elements::float_display::{FloatDisplay, NonFiniteSpelling},
 	error,
 	render::{
 		widget::{
 			replace_box::ReplaceBy,
-			search_box::{SearchFlags, SearchMode, SearchOperation},
+			search_box::{SearchFlags, SearchMode, SearchOperation, SearchTagType},
 		},
 		window::Theme,
 	},
-	workbench::SortAlgorithm,
+	serialization::json::JsonNumberPolicy,
+	workbench::{SortAlgorithm, tab::CompressionLevel},
 };
 
 #[derive(Serialize, Deserialize, Default)]
+#[allow(clippy::struct_excessive_bools)]
 struct Config {
 	#[serde(default)]
 	theme: Theme,
@@ -34,6 +37,9 @@ struct Config {
 	#[serde(default)]
 	search_flags: SearchFlags,
 
+	#[serde(default)]
+	search_tag_type: SearchTagType,
+
 	#[serde(default)]
 	search_operation: SearchOperation,
 
@@ -45,8 +51,65 @@ struct Config {
 
 	#[serde(default)]
 	scale: Option<f32>,
+
+	#[serde(default)]
+	json_number_policy: JsonNumberPolicy,
+
+	#[serde(default)]
+	pretty_snbt: bool,
+
+	#[serde(default)]
+	compression_level: CompressionLevel,
+
+	#[serde(default)]
+	backup_on_save: bool,
+
+	#[serde(default = "default_restore_session")]
+	restore_session: bool,
+
+	/// Int arrays of 4 show as UUIDs under any key, not just the ones known to hold UUIDs
+	#[serde(default)]
+	always_show_uuids: bool,
+
+	/// Bytes of 0 or 1 show as `false` or `true`, unless overridden for the element
+	#[serde(default)]
+	bytes_as_booleans: bool,
+
+	#[serde(default)]
+	float_display: FloatDisplay,
+
+	/// Places after the decimal point when floats are shown as [`FloatDisplay::Fixed`]
+	#[serde(default = "default_float_decimals")]
+	float_decimals: usize,
+
+	#[serde(default)]
+	non_finite_spelling: NonFiniteSpelling,
+
+	/// Edited chunks keep the timestamp they were read with rather than being stamped with when they were edited
+	#[serde(default)]
+	keep_chunk_timestamps: bool,
+
+	/// The most undo steps each tab keeps, the oldest are discarded past it
+	#[serde(default = "default_history_max_entries")]
+	history_max_entries: usize,
+
+	/// The most bytes the undo steps of each tab are kept to, roughly, the oldest are discarded past it
+	#[serde(default = "default_history_max_bytes")]
+	history_max_bytes: usize,
+
+	/// Most recently opened first
+	#[serde(default)]
+	recent_files: Vec<PathBuf>,
 }
 
+const fn default_restore_session() -> bool { true }
+
+const fn default_float_decimals() -> usize { 3 }
+
+const fn default_history_max_entries() -> usize { 1000 }
+
+const fn default_history_max_bytes() -> usize { 256 * 1_048_576 /* 256MiB */ }
+
 pub static DISABLE_FILE_WRITES: AtomicBool = AtomicBool::new(false);
 
 static CONFIG: RwLock<Config> = RwLock::new(Config {
@@ -54,12 +117,29 @@ static CONFIG: RwLock<Config> = RwLock::new(Config {
 	sort_algorithm: SortAlgorithm::Type,
 	search_mode: SearchMode::String,
 	search_flags: SearchFlags::Values,
+	search_tag_type: SearchTagType::Any,
 	search_operation: SearchOperation::B,
 	replace_by: ReplaceBy::SearchHits,
 	search_exact_match: false,
 	scale: None,
+	json_number_policy: JsonNumberPolicy::Plain,
+	pretty_snbt: false,
+	compression_level: CompressionLevel::Default,
+	backup_on_save: false,
+	restore_session: true,
+	always_show_uuids: false,
+	bytes_as_booleans: false,
+	float_display: FloatDisplay::Shortest,
+	float_decimals: default_float_decimals(),
+	non_finite_spelling: NonFiniteSpelling::Words,
+	keep_chunk_timestamps: false,
+	history_max_entries: default_history_max_entries(),
+	history_max_bytes: default_history_max_bytes(),
+	recent_files: Vec::new(),
 });
 
+pub const RECENT_FILES_CAPACITY: usize = 15;
+
 #[cfg(not(target_arch = "wasm32"))]
 pub fn read() -> bool {
 	let Some(config_dir) = dirs::config_dir() else { return false };
@@ -156,6 +236,9 @@ fn try_parse_txt(str: &str) -> Result<Config> {
 	}) {
 		config.search_flags = search_flags;
 	}
+	if let Some(search_tag_type) = map.get("search_tag_type").and_then(|s| SearchTagType::from_name(s)) {
+		config.search_tag_type = search_tag_type;
+	}
 	if let Some(search_operation) = map.get("search_operation").and_then(|s| match s.as_str() {
 		"and" => Some(SearchOperation::And),
 		"or" => Some(SearchOperation::Or),
@@ -178,6 +261,46 @@ fn try_parse_txt(str: &str) -> Result<Config> {
 	if let Some(scale) = map.get("scale").and_then(|s| s.strip_prefix("Some(")).and_then(|s| s.strip_suffix(")")).and_then(|s| s.parse::<f32>().ok()) {
 		config.scale = Some(scale);
 	}
+	if let Some(json_number_policy) = map.get("json_number_policy").and_then(|s| match s.as_str() {
+		"plain" => Some(JsonNumberPolicy::Plain),
+		"typed" => Some(JsonNumberPolicy::Typed),
+		_ => None,
+	}) {
+		config.json_number_policy = json_number_policy;
+	}
+	if let Some(pretty_snbt) = map.get("pretty_snbt").and_then(|s| s.parse::<bool>().ok()) {
+		config.pretty_snbt = pretty_snbt;
+	}
+	if let Some(compression_level) = map.get("compression_level").and_then(|s| match s.as_str() {
+		"fast" => Some(CompressionLevel::Fast),
+		"default" => Some(CompressionLevel::Default),
+		"best" => Some(CompressionLevel::Best),
+		_ => None,
+	}) {
+		config.compression_level = compression_level;
+	}
+	if let Some(backup_on_save) = map.get("backup_on_save").and_then(|s| s.parse::<bool>().ok()) {
+		config.backup_on_save = backup_on_save;
+	}
+	config.restore_session = map.get("restore_session").and_then(|s| s.parse::<bool>().ok()).unwrap_or(default_restore_session());
+	if let Some(always_show_uuids) = map.get("always_show_uuids").and_then(|s| s.parse::<bool>().ok()) {
+		config.always_show_uuids = always_show_uuids;
+	}
+	if let Some(bytes_as_booleans) = map.get("bytes_as_booleans").and_then(|s| s.parse::<bool>().ok()) {
+		config.bytes_as_booleans = bytes_as_booleans;
+	}
+	if let Some(float_display) = map.get("float_display").and_then(|s| FloatDisplay::from_name(s)) {
+		config.float_display = float_display;
+	}
+	config.float_decimals = map.get("float_decimals").and_then(|s| s.parse::<usize>().ok()).unwrap_or(default_float_decimals());
+	if let Some(non_finite_spelling) = map.get("non_finite_spelling").and_then(|s| NonFiniteSpelling::from_name(s)) {
+		config.non_finite_spelling = non_finite_spelling;
+	}
+	if let Some(keep_chunk_timestamps) = map.get("keep_chunk_timestamps").and_then(|s| s.parse::<bool>().ok()) {
+		config.keep_chunk_timestamps = keep_chunk_timestamps;
+	}
+	config.history_max_entries = map.get("history_max_entries").and_then(|s| s.parse::<usize>().ok()).unwrap_or(default_history_max_entries());
+	config.history_max_bytes = map.get("history_max_bytes").and_then(|s| s.parse::<usize>().ok()).unwrap_or(default_history_max_bytes());
 
 	Ok(config)
 }
@@ -243,6 +366,15 @@ pub fn set_search_flags(search_flags: SearchFlags) -> SearchFlags {
 	old_search_flags
 }
 
+#[must_use]
+pub fn get_search_tag_type() -> SearchTagType { CONFIG.read().search_tag_type }
+
+pub fn set_search_tag_type(search_tag_type: SearchTagType) -> SearchTagType {
+	let old_search_tag_type = core::mem::replace(&mut CONFIG.write().search_tag_type, search_tag_type);
+	write();
+	old_search_tag_type
+}
+
 #[must_use]
 pub fn get_search_operation() -> SearchOperation { CONFIG.read().search_operation }
 
@@ -278,3 +410,73 @@ pub fn set_scale(scale: Option<f32>) -> Option<f32> {
 	write();
 	old_scale
 }
+
+#[must_use]
+pub fn get_json_number_policy() -> JsonNumberPolicy { CONFIG.read().json_number_policy }
+
+#[must_use]
+pub fn get_pretty_snbt() -> bool { CONFIG.read().pretty_snbt }
+
+#[must_use]
+pub fn get_compression_level() -> CompressionLevel { CONFIG.read().compression_level }
+
+#[must_use]
+pub fn get_backup_on_save() -> bool { CONFIG.read().backup_on_save }
+
+#[must_use]
+pub fn get_restore_session() -> bool { CONFIG.read().restore_session }
+
+#[must_use]
+pub fn get_always_show_uuids() -> bool { CONFIG.read().always_show_uuids }
+
+#[must_use]
+pub fn get_bytes_as_booleans() -> bool { CONFIG.read().bytes_as_booleans }
+
+pub fn set_bytes_as_booleans(bytes_as_booleans: bool) -> bool {
+	let old_bytes_as_booleans = core::mem::replace(&mut CONFIG.write().bytes_as_booleans, bytes_as_booleans);
+	write();
+	old_bytes_as_booleans
+}
+
+#[must_use]
+pub fn get_float_display() -> FloatDisplay { CONFIG.read().float_display }
+
+pub fn set_float_display(float_display: FloatDisplay) -> FloatDisplay {
+	let old_float_display = core::mem::replace(&mut CONFIG.write().float_display, float_display);
+	write();
+	old_float_display
+}
+
+#[must_use]
+pub fn get_float_decimals() -> usize { CONFIG.read().float_decimals }
+
+#[must_use]
+pub fn get_non_finite_spelling() -> NonFiniteSpelling { CONFIG.read().non_finite_spelling }
+
+#[must_use]
+pub fn get_keep_chunk_timestamps() -> bool { CONFIG.read().keep_chunk_timestamps }
+
+#[must_use]
+pub fn get_history_max_entries() -> usize { CONFIG.read().history_max_entries }
+
+#[must_use]
+pub fn get_history_max_bytes() -> usize { CONFIG.read().history_max_bytes }
+
+#[must_use]
+pub fn get_recent_files() -> Vec<PathBuf> { CONFIG.read().recent_files.clone() }
+
+/// Moves `path` to the front of the recent files, forgetting the oldest past [`RECENT_FILES_CAPACITY`]
+pub fn push_recent_file(path: PathBuf) {
+	{
+		let recent_files = &mut CONFIG.write().recent_files;
+		recent_files.retain(|recent| *recent != path);
+		recent_files.insert(0, path);
+		recent_files.truncate(RECENT_FILES_CAPACITY);
+	}
+	write();
+}
+
+pub fn remove_recent_file(path: &Path) {
+	CONFIG.write().recent_files.retain(|recent| recent != path);
+	write();
+}
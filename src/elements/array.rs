@@ -67,6 +67,9 @@ macro_rules! array {
 						f.write_str(concat!("[", $char, ";]"))
 					} else {
 						let len = self.len();
+						if f.try_write_inline(concat!($char, "; "), self.children()) {
+							return
+						}
 						f.write_str(concat!("[", $char, ";\n"));
 						f.increase();
 						for (idx, element) in self.children().enumerate() {
@@ -91,6 +94,19 @@ macro_rules! array {
 				pub type ChildType = $element;
 
 				fn transmute(element: &$crate::elements::element::NbtElement) -> <Self::ChildType as $crate::elements::PrimitiveNbtElementVariant>::InnerType { unsafe { $get_inner_unchecked(element).value } }
+
+				/// The UUID this holds, hyphenated, if it's 4 ints under one of the [`UUID_KEYS`]($crate::elements::array::UUID_KEYS) (or any key with `always_show_uuids` in the config)
+				#[must_use]
+				pub fn uuid(&self, key: Option<&str>) -> Option<String> {
+					if Self::CHILD_ID != $crate::elements::int::NbtInt::ID || self.len() != 4 || !(key.is_some_and(|key| $crate::elements::array::UUID_KEYS.contains(&key)) || $crate::config::get_always_show_uuids()) {
+						return None
+					}
+					let mut ints = [0; 4];
+					for (int, child) in ints.iter_mut().zip(self.children()) {
+						*int = child.as_int()?.value;
+					}
+					Some($crate::util::uuid_to_string(ints))
+				}
 			}
 
 			impl NbtElementVariant for $name {
@@ -106,7 +122,7 @@ macro_rules! array {
 					let mut array = Self::default();
 					while !s.starts_with(']') {
 						let (s2, element) = $crate::elements::element::NbtElement::from_str0(s, $default_snbt_integer)?;
-						let element = $try_into_element(element).ok_or(s.len())?;
+						let element = $try_into_element(element).ok_or_else(|| $crate::elements::snbt_error::expected(s.len(), "a number of the array's type"))?;
 						// SAFETY: there is nothing to update
 						unsafe {
 							array.insert(array.len(), element).map_err(|_| s.len())?;
@@ -116,6 +132,8 @@ macro_rules! array {
 							s = s2.trim_start();
 						} else if s.starts_with(']') {
 							break;
+						} else {
+							let _ = $crate::elements::snbt_error::expected(s.len(), "',' or ']'");
 						}
 					}
 					array.values.shrink_to_fit();
@@ -181,6 +199,9 @@ macro_rules! array {
 						if !self.is_empty() {
 							ctx.draw_toggle(pos - (16, 0), self.open, builder);
 						}
+						if self.uuid(key).is_some() {
+							ctx.check_for_invalid_value(|value| $crate::util::parse_uuid(value).is_none());
+						}
 						ctx.render_errors(pos, builder);
 						if ctx.forbid(pos) {
 							builder.settings(pos + (20, 0), false, $crate::render::assets::JUST_OVERLAPPING_BASE_TEXT_Z);
@@ -189,8 +210,13 @@ macro_rules! array {
 								let _ = write!(builder, "{key}: ");
 							};
 
-							builder.color = $crate::render::color::TextColor::TreeKey.to_raw();
-							let _ = write!(builder, "{}", self.value());
+							if let Some(uuid) = self.uuid(key) {
+								builder.color = $crate::render::color::TextColor::TreePrimitive.to_raw();
+								let _ = write!(builder, "{uuid}");
+							} else {
+								builder.color = $crate::render::color::TextColor::TreeKey.to_raw();
+								let _ = write!(builder, "{}", self.value());
+							}
 						}
 
 						if ctx.draw_held_entry_bar(pos + (16, 16), builder, |x, y| pos == (x - 16, y - 8), |x| self.can_insert(x)) {
@@ -224,9 +250,9 @@ macro_rules! array {
 
 							ctx.line_number();
 							builder.draw_texture_z(pos, $crate::render::assets::BASE_Z, Self::ChildType::UV, (16, 16));
-							ctx.check_for_invalid_value(|value| value.parse::<<Self::ChildType as $crate::elements::PrimitiveNbtElementVariant>::InnerType>().is_err());
+							ctx.check_for_invalid_value(|value| <<Self::ChildType as $crate::elements::PrimitiveNbtElementVariant>::InnerType as $crate::elements::number_base::InBase>::parse_any_base(value).is_none());
 							ctx.render_errors(pos, builder);
-							let str = ::compact_str::format_compact!("{}", Self::transmute(element));
+							let str = $crate::elements::number_base::InBase::to_base_string(Self::transmute(element), ctx.number_base());
 							if ctx.forbid(pos) {
 								builder.settings(pos + (20, 0), false, $crate::render::assets::JUST_OVERLAPPING_BASE_TEXT_Z);
 								builder.color = $crate::render::color::TextColor::TreePrimitive.to_raw();
@@ -339,6 +365,9 @@ macro_rules! array {
 	};
 }
 
+/// Keys the game stores UUIDs under as 4 ints
+pub const UUID_KEYS: [&str; 9] = ["UUID", "Owner", "Thrower", "Target", "AngryAt", "LoveCause", "ConversionPlayer", "WanderingTraderId", "leash"];
+
 array!(
 	byte_array,
 	NbtByteArray,
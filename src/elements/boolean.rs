@@ -0,0 +1,60 @@
+use crate::elements::number_base::InBase;
+
+/// A primitive value which can be shown as `true` or `false` when bytes are shown as booleans, which only bytes of 0 or 1 can
+pub trait AsBoolean: InBase {
+	#[must_use]
+	fn as_boolean(self) -> Option<bool> { None }
+
+	/// [`parse_any_base`](InBase::parse_any_base), where bytes also take `true` and `false` however they're shown
+	#[must_use]
+	fn parse_shown(s: &str) -> Option<Self> { Self::parse_any_base(s) }
+}
+
+impl AsBoolean for i8 {
+	fn as_boolean(self) -> Option<bool> {
+		match self {
+			0 => Some(false),
+			1 => Some(true),
+			_ => None,
+		}
+	}
+
+	fn parse_shown(s: &str) -> Option<Self> {
+		match s {
+			"true" => Some(1),
+			"false" => Some(0),
+			_ => Self::parse_any_base(s),
+		}
+	}
+}
+
+impl AsBoolean for i16 {}
+
+impl AsBoolean for i32 {}
+
+impl AsBoolean for i64 {}
+
+impl AsBoolean for f32 {}
+
+impl AsBoolean for f64 {}
+
+/// Drawn after a byte shown as a boolean, to be clicked to flip it
+#[must_use]
+pub const fn checkbox(value: bool) -> char { if value { '☑' } else { '☐' } }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_as_boolean() {
+		assert_eq!(1_i8.as_boolean(), Some(true));
+		assert_eq!(0_i8.as_boolean(), Some(false));
+		assert_eq!(2_i8.as_boolean(), None);
+		assert_eq!(1_i16.as_boolean(), None);
+
+		assert_eq!(i8::parse_shown("true"), Some(1));
+		assert_eq!(i8::parse_shown("0x7f"), Some(127));
+		assert_eq!(i32::parse_shown("true"), None);
+	}
+}
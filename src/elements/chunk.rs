@@ -1,3 +1,4 @@
+#[cfg(not(target_arch = "wasm32"))] use std::thread::Scope;
 use std::{
 	borrow::Cow,
 	fmt::{Display, Formatter},
@@ -10,14 +11,16 @@ use zune_inflate::{DeflateDecoder, DeflateOptions};
 #[cfg(target_arch = "wasm32")]
 use crate::wasm::{FakeScope as Scope, fake_scope as scope};
 use crate::{
+	config,
 	elements::{
 		ComplexNbtElementVariant, Matches, NbtElement, NbtElementVariant,
 		compound::{CompoundEntry, NbtCompound},
-		result::{NbtParseResult, err, from_opt, from_result, ok},
+		data_version::version_name,
+		result::{NbtParseResult, from_opt, from_result, ok, with_reason},
 	},
 	render::{
 		RenderContext,
-		assets::{CHUNK_GHOST_UV, CHUNK_UV, CONNECTION_UV, HEADER_SIZE, JUST_OVERLAPPING_BASE_TEXT_Z},
+		assets::{CHUNK_GHOST_UV, CHUNK_UV, CONNECTION_UV, CORRUPTED_CHUNK_UV, HEADER_SIZE, JUST_OVERLAPPING_BASE_TEXT_Z, JUST_OVERLAPPING_BASE_Z, ZOffset},
 		color::TextColor,
 		vertex_buffer_builder::VertexBufferBuilder,
 	},
@@ -26,13 +29,15 @@ use crate::{
 		encoder::UncheckedBufWriter,
 		formatter::{PrettyDisplay, PrettyFormatter},
 	},
-	util::{StrExt, Timestamp, Vec2u},
+	tree::indices::OwnedIndices,
+	util::{StrExt, Timestamp, Vec2u, format_unix_time},
 	workbench::tab::ChunkFileFormat,
 };
 
 #[repr(C)]
 pub struct NbtChunk {
 	inner: Box<NbtCompound>,
+	original: Option<Box<OriginalChunk>>,
 	pub last_modified: u32,
 	// need to restrict this file format to only use GZIP, ZLIB, Uncompressed, and LZ4
 	format: ChunkFileFormat,
@@ -40,19 +45,54 @@ pub struct NbtChunk {
 	pub z: u8,
 }
 
+/// What a chunk was read from, so it can be written back as-is if it's unchanged
+#[derive(Clone, PartialEq)]
+enum OriginalChunk {
+	/// The compressed payload, dropped once the chunk is [modified](NbtChunk::mark_modified)
+	Payload(Vec<u8>),
+	/// The compressed payload of a chunk which hasn't been decoded yet
+	Undecoded(Vec<u8>),
+	/// The chunk was stored in an external `.mcc` file which couldn't be found
+	MissingExternal,
+	/// The compressed payload of a chunk which couldn't be decoded
+	Undecodable { payload: Vec<u8>, error: String },
+	/// Whatever there is of the sectors of a chunk whose header doesn't make sense
+	Corrupt { sectors: Vec<u8>, error: String },
+}
+
 impl Matches for NbtChunk {
-	fn matches(&self, other: &Self) -> bool { self.inner.matches(&other.inner) }
+	fn matches(&self, other: &Self) -> bool {
+		match (self.decoded(), other.decoded()) {
+			(Ok(a), Ok(b)) => a.matches(&b),
+			// there's nothing in a chunk which can't be read to match against
+			_ => false,
+		}
+	}
 }
 
 impl PartialEq for NbtChunk {
-	fn eq(&self, other: &Self) -> bool { self.inner.eq(&other.inner) }
+	fn eq(&self, other: &Self) -> bool {
+		if let (Some(OriginalChunk::Undecoded(a)), Some(OriginalChunk::Undecoded(b))) = (self.original.as_deref(), other.original.as_deref())
+			&& self.format == other.format
+			&& a == b
+		{
+			return true;
+		}
+		match (self.decoded(), other.decoded()) {
+			(Ok(a), Ok(b)) => a == b,
+			// only what was read of them can be compared
+			(Err(_), Err(_)) => self.format == other.format && self.original == other.original,
+			_ => false,
+		}
+	}
 }
 
 impl Default for NbtChunk {
 	fn default() -> Self {
 		Self {
 			inner: Box::new(NbtCompound::default()),
-			last_modified: Timestamp::now().elapsed().as_secs() as u32,
+			original: None,
+			last_modified: Self::now(),
 			format: ChunkFileFormat::default(),
 			x: 0,
 			z: 0,
@@ -64,6 +104,7 @@ impl Clone for NbtChunk {
 	fn clone(&self) -> Self {
 		Self {
 			inner: unsafe { Box::try_new(self.inner.deref().clone()).unwrap_unchecked() },
+			original: self.original.clone(),
 			last_modified: self.last_modified,
 			format: self.format,
 			x: self.x,
@@ -75,14 +116,16 @@ impl Clone for NbtChunk {
 impl Display for NbtChunk {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
 		write!(f, "{}|{}{{", self.x, self.z)?;
-		for (idx, CompoundEntry { key, value }) in self.children().enumerate() {
+		// SNBT has no way to hold the payload of a chunk which can't be read, it's only kept in the region file
+		let inner = self.decoded().unwrap_or_default();
+		for (idx, CompoundEntry { key, value }) in inner.children().enumerate() {
 			if key.needs_escape() {
 				write!(f, "{key:?}")?;
 			} else {
 				write!(f, "{key}")?;
 			}
 			write!(f, ":{value}")?;
-			if likely(idx < self.len() - 1) {
+			if likely(idx < inner.len() - 1) {
 				write!(f, ",")?;
 			}
 		}
@@ -93,7 +136,8 @@ impl Display for NbtChunk {
 impl PrettyDisplay for NbtChunk {
 	fn pretty_fmt(&self, f: &mut PrettyFormatter) {
 		f.write_str(&format!("{} | {} ", self.x, self.z));
-		self.inner.pretty_fmt(f)
+		// see `Display`
+		self.decoded().unwrap_or_default().pretty_fmt(f)
 	}
 }
 
@@ -104,6 +148,7 @@ impl Deref for NbtChunk {
 }
 
 impl DerefMut for NbtChunk {
+	/// Doesn't decode the chunk, see [`decode_chunk_at`](crate::tree::actions::decode::decode_chunk_at)
 	fn deref_mut(&mut self) -> &mut Self::Target { &mut self.inner }
 }
 
@@ -129,7 +174,7 @@ impl NbtElementVariant for NbtChunk {
 		s = s2.trim_start();
 
 		let (s, compound) = NbtCompound::from_str0(s)?;
-		Ok((s, Self::new(compound, (x, z), ChunkFileFormat::Zlib, Timestamp::now().elapsed().as_secs() as u32)))
+		Ok((s, Self::new(compound, (x, z), ChunkFileFormat::Zlib, Self::now())))
 	}
 
 	fn from_bytes<'a, D: Decoder<'a>>(decoder: &mut D, idx: usize) -> NbtParseResult<Self>
@@ -146,55 +191,42 @@ impl NbtElementVariant for NbtChunk {
 		let len = (offset as usize & 0xFF) * 4096;
 		// value does include header so we must offset against that
 		let offset = ((offset >> 8) - 2) as usize * 4096;
-		if bytes.len() < offset + len {
-			return err("Offset goes outside bytes");
-		}
-		let data = &bytes[offset..offset + len];
-
-		if let &[a, b, c, d, compression, ref data @ ..] = data {
-			let chunk_len = from_opt((u32::from_be_bytes([a, b, c, d]) as usize).checked_sub(1), "Chunk was inside header.")?;
-			if data.len() < chunk_len {
-				return err("Offset is invalid");
-			}
-			let data = &data[..chunk_len];
-			let (compression, element) = match compression {
-				1 => (
-					ChunkFileFormat::Gzip,
-					NbtElement::from_be_file(&from_result(DeflateDecoder::new_with_options(data, DeflateOptions::default().set_confirm_checksum(false)).decode_gzip())?)?,
-				),
-				2 => (
-					ChunkFileFormat::Zlib,
-					NbtElement::from_be_file(&from_result(DeflateDecoder::new_with_options(data, DeflateOptions::default().set_confirm_checksum(false)).decode_zlib())?)?,
-				),
-				3 => (ChunkFileFormat::Nbt, NbtElement::from_be_file(data)?),
-				4 => (ChunkFileFormat::Lz4, NbtElement::from_be_file(&from_result(lz4_flex::decompress(data, data.len()))?)?),
-				_ => return err("Unknown compression format"),
-			};
-			return ok(NbtChunk::new(from_opt(element.into_compound(), "Chunk was not of type compound")?, pos, compression, last_modified));
-		}
-		err("Invalid chunk data")
+		// a torn chunk only loses itself
+		let corrupt = |sectors: &[u8], error: &str| {
+			let mut chunk = NbtChunk::new(NbtCompound::default(), pos, ChunkFileFormat::default(), last_modified);
+			chunk.original = Some(Box::new(OriginalChunk::Corrupt { sectors: sectors.to_vec(), error: error.to_owned() }));
+			ok(chunk)
+		};
+		let Some(data) = bytes.get(offset..offset + len) else {
+			return corrupt(bytes.get(offset..bytes.len().min(offset + len)).unwrap_or_default(), "its sectors go past the end of the file");
+		};
+		let &[a, b, c, d, compression, ref payload @ ..] = data else {
+			return corrupt(data, "its sectors are too short to hold its header");
+		};
+		let Some(payload) = (u32::from_be_bytes([a, b, c, d]) as usize).checked_sub(1).and_then(|chunk_len| payload.get(..chunk_len)) else {
+			return corrupt(data, "its length doesn't fit within its sectors");
+		};
+		let format = match compression & !Self::EXTERNAL_FLAG {
+			1 => ChunkFileFormat::Gzip,
+			2 => ChunkFileFormat::Zlib,
+			3 => ChunkFileFormat::Nbt,
+			4 => ChunkFileFormat::Lz4,
+			_ => return corrupt(data, &format!("its compression format {compression} is unknown")),
+		};
+		let mut chunk = NbtChunk::new(NbtCompound::default(), pos, format, last_modified);
+		chunk.original = Some(Box::new(if compression & Self::EXTERNAL_FLAG > 0 {
+			// loaded later by the region, since it knows where its `.mcc` files are
+			OriginalChunk::MissingExternal
+		} else {
+			// decoded once it's first opened, most chunks never are
+			OriginalChunk::Undecoded(payload.to_vec())
+		}));
+		ok(chunk)
 	}
 
 	fn to_be_bytes(&self, writer: &mut UncheckedBufWriter) {
-		// todo, mcc files
-		let encoded = self.format.encode(unsafe { (self.inner.as_ref() as *const NbtCompound).cast::<NbtElement>().as_ref_unchecked() });
-		let len = encoded.len() + 1;
-		// plus four for the len field writing, and + 1 for the compression
-		let pad_len = (4096 - (len + 4) % 4096) % 4096;
-		writer.write(&(len as u32).to_be_bytes());
-		writer.write(
-			&match self.format {
-				ChunkFileFormat::Gzip => 1_u8,
-				ChunkFileFormat::Zlib => 2_u8,
-				ChunkFileFormat::Nbt => 3_u8,
-				ChunkFileFormat::Lz4 => 4_u8,
-			}
-			.to_be_bytes(),
-		);
-		writer.write(&encoded);
-		drop(encoded);
-		let pad = vec![0; pad_len];
-		writer.write(&pad);
+		let external = self.to_be_bytes_with_external(writer, false);
+		debug_assert!(external.is_none(), "Chunk at {:?} is too large to be written without its .mcc file", (self.x, self.z));
 	}
 
 	fn to_le_bytes(&self, _writer: &mut UncheckedBufWriter) {}
@@ -215,7 +247,8 @@ impl NbtElementVariant for NbtChunk {
 
 			ctx.line_number();
 			builder.draw_texture(pos, self.uv(), (16, 16));
-			if !self.is_empty() {
+			self.draw_corrupted_tint(pos, JUST_OVERLAPPING_BASE_Z, builder);
+			if !self.is_empty() || self.is_undecoded() {
 				ctx.draw_toggle(pos - (16, 0), self.is_open(), builder);
 			}
 			ctx.check_for_invalid_key(|key| !key.parse::<usize>().is_ok_and(|x| (0..=31).contains(&x)));
@@ -225,6 +258,7 @@ impl NbtElementVariant for NbtChunk {
 				builder.settings(pos + (20, 0), false, JUST_OVERLAPPING_BASE_TEXT_Z);
 				builder.color = TextColor::TreeKey.to_raw();
 				let _ = write!(builder, "{}, {}", self.x, self.z);
+				self.render_status(pos, ctx.mouse(), builder);
 			}
 
 			ctx.offset_pos(0, 16);
@@ -277,6 +311,9 @@ impl NbtElementVariant for NbtChunk {
 				if *remaining_scroll == 0 {
 					builder.draw_texture(pos - (16, 0), CONNECTION_UV, (16, (idx != self.len() - 1) as usize * 7 + 9));
 				}
+				if *remaining_scroll == 0 && self.inner.map.is_duplicate(idx) {
+					ctx.draw_duplicate_key_warning(pos.y, key, builder);
+				}
 				ctx.check_for_key_duplicate(|text, _| self.inner.map.has(text) && key != text, false);
 				if ctx.has_duplicate_key_error() && Some(pos.y) == ctx.selected_text_y() {
 					ctx.set_red_line_number(pos.y, 0);
@@ -301,12 +338,18 @@ impl NbtElementVariant for NbtChunk {
 }
 
 impl NbtChunk {
+	/// Set on the compression type of chunks that are stored in an external `.mcc` file
+	pub const EXTERNAL_FLAG: u8 = 0x80;
+	/// The largest a chunk can be while still being stored in the region, since its sector count has to fit in a byte
+	pub const MAX_INLINE_LEN: usize = 255 * 4096;
+
 	#[must_use]
 	pub fn new(inner: NbtCompound, pos: (u8, u8), compression: ChunkFileFormat, last_modified: u32) -> Self {
 		Self {
 			x: pos.0,
 			z: pos.1,
 			inner: Box::new(inner),
+			original: None,
 			format: compression,
 			last_modified,
 		}
@@ -324,7 +367,289 @@ impl NbtChunk {
 	}
 
 	#[must_use]
-	pub fn is_unloaded(&self) -> bool { self.inner.is_empty() && self.last_modified == 0 }
+	pub fn format(&self) -> ChunkFileFormat { self.format }
+
+	pub fn set_format(&mut self, format: ChunkFileFormat) {
+		if self.format != format {
+			// the payload can only be decoded with the old format
+			self.decode();
+			self.format = format;
+			self.original = None;
+		}
+	}
+
+	/// Writes the chunk into the region, returning its payload instead if it's too large to fit
+	#[must_use]
+	pub fn to_be_bytes_with_external(&self, writer: &mut UncheckedBufWriter, mcregion: bool) -> Option<Vec<u8>> {
+		let format = match self.format {
+			ChunkFileFormat::Nbt | ChunkFileFormat::Lz4 if mcregion => ChunkFileFormat::default(),
+			format => format,
+		};
+		let compression = match format {
+			ChunkFileFormat::Gzip => 1_u8,
+			ChunkFileFormat::Zlib => 2_u8,
+			ChunkFileFormat::Nbt => 3_u8,
+			ChunkFileFormat::Lz4 => 4_u8,
+		};
+		if let Some(OriginalChunk::Corrupt { sectors, .. }) = self.original.as_deref()
+			&& self.inner.is_empty()
+		{
+			// at least a sector, so it's still there to be noticed when the region is next opened, and at most as many as its header can count
+			let sectors = &sectors[..sectors.len().min(Self::MAX_INLINE_LEN)];
+			writer.write(sectors);
+			writer.write_bytes(0, sectors.len().max(1).next_multiple_of(4096) - sectors.len());
+			return None;
+		}
+		let element = unsafe { (self.inner.as_ref() as *const NbtCompound).cast::<NbtElement>().as_ref_unchecked() };
+		let encoded = match self.original.as_deref() {
+			// keep referencing the missing file rather than losing the chunk
+			Some(OriginalChunk::MissingExternal) if self.inner.is_empty() => None,
+			Some(OriginalChunk::Undecoded(original) | OriginalChunk::Undecodable { payload: original, .. }) if self.inner.is_empty() && format == self.format => Some(Cow::Borrowed(original.as_slice())),
+			Some(OriginalChunk::Payload(original)) if format == self.format => Some(Cow::Borrowed(original.as_slice())),
+			_ => Some(Cow::Owned(format.encode(element))),
+		};
+		// plus four for the len field writing, and + 1 for the compression
+		let (encoded, external) = match encoded {
+			Some(encoded) if encoded.len() + 5 > Self::MAX_INLINE_LEN => (None, Some(encoded.into_owned())),
+			encoded => (encoded, None),
+		};
+		let len = encoded.as_ref().map_or(0, |encoded| encoded.len()) + 1;
+		let pad_len = (4096 - (len + 4) % 4096) % 4096;
+		writer.write(&(len as u32).to_be_bytes());
+		writer.write(&(if encoded.is_some() { compression } else { compression | Self::EXTERNAL_FLAG }).to_be_bytes());
+		if let Some(encoded) = encoded {
+			writer.write(&encoded);
+		}
+		writer.write_bytes(0, pad_len);
+		external
+	}
+
+	/// Parses the chunk from its compressed payload
+	pub fn load(&mut self, payload: Vec<u8>) -> NbtParseResult<()> {
+		self.inner = Box::new(Self::parse_payload(self.format, &payload)?);
+		self.original = Some(Box::new(OriginalChunk::Payload(payload)));
+		ok(())
+	}
+
+	fn parse_payload(format: ChunkFileFormat, payload: &[u8]) -> NbtParseResult<NbtCompound> {
+		let element = NbtElement::from_be_file(&Self::decompress(format, payload)?)?;
+		from_opt(element.into_compound(), "Chunk was not of type compound")
+	}
+
+	/// Decodes the chunk if it hasn't been yet, returning whether this call did
+	pub fn decode(&mut self) -> bool {
+		let Some(OriginalChunk::Undecoded(payload)) = self.original.as_deref_mut() else { return false };
+		let payload = core::mem::take(payload);
+		self.original = Some(Box::new(match with_reason(Self::parse_payload(self.format, &payload), "its payload isn't valid") {
+			Ok(inner) => {
+				self.inner = Box::new(inner);
+				OriginalChunk::Payload(payload)
+			}
+			Err(error) => {
+				crate::error!("Chunk {x}, {z} couldn't be decoded: {error}", x = self.x, z = self.z);
+				OriginalChunk::Undecodable { payload, error }
+			}
+		}));
+		true
+	}
+
+	/// The chunk's contents, decoding a copy of them every call if the chunk itself hasn't been yet
+	pub fn decoded(&self) -> Result<Cow<'_, NbtCompound>, String> {
+		match self.original.as_deref() {
+			Some(OriginalChunk::Undecoded(payload)) => with_reason(Self::parse_payload(self.format, payload), "its payload isn't valid").map(Cow::Owned),
+			// anything added to it since has nothing to do with its payload
+			Some(OriginalChunk::Undecodable { error, .. } | OriginalChunk::Corrupt { error, .. }) if self.inner.is_empty() => Err(error.clone()),
+			_ => Ok(Cow::Borrowed(&self.inner)),
+		}
+	}
+
+	/// Where the `xPos` and `zPos` ints holding its position in the world are within it, with their values, at its root or within `Level` before 1.18
+	#[must_use]
+	pub fn world_pos_entries(&self) -> Option<[(OwnedIndices, i32); 2]> {
+		fn get(compound: &NbtCompound, key: &str) -> Option<(usize, i32)> { compound.map.idx_of(key).and_then(|idx| Some((idx, compound.get(idx)?.value.as_int()?.value))) }
+
+		let (prefix, compound) = match self.map.idx_of("Level").and_then(|idx| Some((idx, self.get(idx)?.value.as_compound()?))) {
+			Some((idx, level)) => (vec![idx], level),
+			None => (vec![], &**self),
+		};
+		let [(x_idx, x), (z_idx, z)] = [get(compound, "xPos")?, get(compound, "zPos")?];
+		let indices = |idx: usize| OwnedIndices::from(prefix.iter().copied().chain([idx]).collect::<Vec<_>>());
+		Some([(indices(x_idx), x), (indices(z_idx), z)])
+	}
+
+	/// Where it is in the world going by its `xPos` and `zPos`, see [`Self::world_pos_entries`]
+	#[must_use]
+	pub fn world_pos(&self) -> Option<(i32, i32)> { self.world_pos_entries().map(|[(_, x), (_, z)]| (x, z)) }
+
+	/// Sets the `xPos` and `zPos` it holds to `(x, z)`, if it holds them, before it's put in a region
+	pub fn set_world_pos(&mut self, (x, z): (i32, i32)) {
+		let Some(entries) = self.world_pos_entries() else { return };
+		for ((indices, _), value) in entries.into_iter().zip([x, z]) {
+			let Some((idx, parent)) = indices.split_last() else { continue };
+			// the chunk itself, or its `Level`
+			let compound = match parent.first() {
+				Some(level) => self.inner.get_mut(level).and_then(|entry| entry.value.as_compound_mut()),
+				None => Some(&mut *self.inner),
+			};
+			// ints either way, so no heights change
+			if let Some(int) = compound.and_then(|compound| compound.get_mut(idx)).and_then(|entry| entry.value.as_int_mut()) {
+				int.value = value;
+			}
+		}
+	}
+
+	/// Forgets the payload it was read from and stamps it as modified, unless [`config::get_keep_chunk_timestamps`]
+	pub fn mark_modified(&mut self) {
+		if let Some(OriginalChunk::Payload(_)) = self.original.as_deref() {
+			self.original = None;
+		}
+		if !config::get_keep_chunk_timestamps() {
+			self.last_modified = Self::now();
+		}
+	}
+
+	/// The time as it's stored in a region's timestamp table, in seconds since the unix epoch
+	#[must_use]
+	#[allow(clippy::cast_possible_truncation)] // the table can't hold past 2106
+	pub fn now() -> u32 { Timestamp::now().since_epoch().as_secs() as u32 }
+
+	#[must_use]
+	pub fn is_undecoded(&self) -> bool { matches!(self.original.as_deref(), Some(OriginalChunk::Undecoded(_))) }
+
+	/// Recaches the chunk without decoding it, an undecoded chunk has nothing to recache
+	pub fn recache(&mut self) { self.inner.recache(); }
+
+	/// Shuts the chunk without decoding it, an undecoded chunk is never open
+	///
+	/// # Safety
+	/// - must be assured to update valid caches (bookmarks)
+	pub unsafe fn shut<'a, 'b>(&'b mut self, scope: &'a Scope<'a, 'b>) {
+		if !self.is_undecoded() {
+			unsafe { self.inner.shut(scope) };
+		}
+	}
+
+	#[must_use]
+	pub fn is_missing_external(&self) -> bool { matches!(self.original.as_deref(), Some(OriginalChunk::MissingExternal)) }
+
+	#[must_use]
+	pub fn is_undecodable(&self) -> bool { matches!(self.original.as_deref(), Some(OriginalChunk::Undecodable { .. })) }
+
+	/// Why the chunk couldn't be read, if it couldn't
+	#[must_use]
+	pub fn error(&self) -> Option<&str> {
+		match self.original.as_deref() {
+			Some(OriginalChunk::Undecodable { error, .. } | OriginalChunk::Corrupt { error, .. }) => Some(error),
+			_ => None,
+		}
+	}
+
+	#[must_use]
+	pub fn is_corrupted(&self) -> bool { self.error().is_some() }
+
+	/// When it was last modified and what's keeping it from being shown, after its position on its row at `pos`
+	fn render_status(&self, pos: Vec2u, mouse: Vec2u, builder: &mut VertexBufferBuilder) {
+		use std::fmt::Write as _;
+
+		if self.last_modified != 0 {
+			builder.color = TextColor::Gray.to_raw();
+			let _ = write!(builder, " {}", format_unix_time(u64::from(self.last_modified)));
+		}
+		if self.is_missing_external() {
+			builder.color = TextColor::Red.to_raw();
+			let _ = write!(builder, " (missing .mcc file)");
+		} else if let Some(error) = self.error() {
+			builder.color = TextColor::Red.to_raw();
+			let _ = write!(builder, " (couldn't be read)");
+			let scrolled = mouse + (builder.horizontal_scroll, 0);
+			if (pos.y..pos.y + 16).contains(&scrolled.y) && (pos.x..builder.text_coords.0).contains(&scrolled.x) {
+				builder.color = TextColor::White.to_raw();
+				builder.draw_tooltip(&[&format!("Chunk {}, {} couldn't be read:", self.x, self.z), error], mouse, false);
+			}
+		} else if self.is_undecoded() {
+			builder.color = TextColor::Gray.to_raw();
+			let _ = write!(builder, " (not yet loaded)");
+		}
+	}
+
+	/// Tints the chunk's icon red if it couldn't be read
+	pub fn draw_corrupted_tint(&self, pos: Vec2u, z: ZOffset, builder: &mut VertexBufferBuilder) {
+		if self.is_corrupted() {
+			builder.draw_texture_z(pos, z, CORRUPTED_CHUNK_UV, (16, 16));
+		}
+	}
+
+	/// How many bytes it took up in the region file as it was read, not counting its header or padding
+	#[must_use]
+	pub fn payload_len(&self) -> Option<usize> {
+		match self.original.as_deref()? {
+			OriginalChunk::Payload(payload) | OriginalChunk::Undecoded(payload) | OriginalChunk::Undecodable { payload, .. } => Some(payload.len()),
+			OriginalChunk::Corrupt { sectors, .. } => Some(sectors.len()),
+			OriginalChunk::MissingExternal => None,
+		}
+	}
+
+	/// What hovering the chunk at `region_pos` on the grid layout says about it, with `world` as its position in the world if the region's is known
+	#[must_use]
+	#[allow(clippy::cast_precision_loss)] // only shown to a tenth of a KiB
+	pub fn grid_tooltip(&self, region_pos: (usize, usize), world: Option<(i32, i32)>) -> Vec<String> {
+		fn get<'a>(compound: &'a NbtCompound, key: &str) -> Option<&'a NbtElement> { compound.map.idx_of(key).and_then(|idx| compound.get(idx)).map(|entry| &entry.value) }
+
+		let mut lines = vec![match world {
+			Some((x, z)) => format!("Chunk {}, {} (world {x}, {z})", region_pos.0, region_pos.1),
+			None => format!("Chunk {}, {}", region_pos.0, region_pos.1),
+		}];
+		if self.is_unloaded() {
+			lines.push("Not generated".to_owned());
+			return lines
+		}
+		match self.payload_len() {
+			// plus the length and compression in front of it
+			Some(len) => {
+				let sectors = (len + 5).div_ceil(4096);
+				lines.push(format!("{:.1} KiB on disk in {sectors} sector{}, {}", len as f64 / 1024.0, if sectors == 1 { "" } else { "s" }, self.format.into_str()));
+			}
+			None if self.is_missing_external() => lines.push(format!("Stored in a .mcc file which couldn't be found, {}", self.format.into_str())),
+			None => lines.push(self.format.into_str().to_owned()),
+		}
+		if self.last_modified != 0 {
+			lines.push(format!("Last modified {}", format_unix_time(u64::from(self.last_modified))));
+		}
+		if let Some(error) = self.error() {
+			lines.push(format!("Couldn't be read: {error}"));
+			return lines
+		}
+		let compound = match self.decoded() {
+			Ok(compound) => compound,
+			Err(error) => {
+				lines.push(format!("Couldn't be read: {error}"));
+				return lines
+			}
+		};
+		if let Some(data_version) = get(&compound, "DataVersion").and_then(NbtElement::as_int) {
+			lines.push(format!("DataVersion {} ({})", data_version.value, version_name(data_version.value)));
+		}
+		// within `Level` before 1.18
+		if let Some(status) = get(&compound, "Status").or_else(|| get(&compound, "Level").and_then(NbtElement::as_compound).and_then(|level| get(level, "Status"))).and_then(NbtElement::as_string) {
+			lines.push(format!("Status {}", status.str.as_str()));
+		}
+		if self.is_undecoded() {
+			lines.push("Not yet loaded".to_owned());
+		}
+		lines
+	}
+
+	fn decompress(format: ChunkFileFormat, data: &[u8]) -> NbtParseResult<Cow<'_, [u8]>> {
+		match format {
+			ChunkFileFormat::Gzip => from_result(DeflateDecoder::new_with_options(data, DeflateOptions::default().set_confirm_checksum(false)).decode_gzip()).map(Cow::Owned),
+			ChunkFileFormat::Zlib => from_result(DeflateDecoder::new_with_options(data, DeflateOptions::default().set_confirm_checksum(false)).decode_zlib()).map(Cow::Owned),
+			ChunkFileFormat::Nbt => ok(Cow::Borrowed(data)),
+			ChunkFileFormat::Lz4 => from_result(lz4_flex::decompress(data, data.len())).map(Cow::Owned),
+		}
+	}
+
+	#[must_use]
+	pub fn is_unloaded(&self) -> bool { self.inner.is_empty() && self.last_modified == 0 && self.original.is_none() }
 
 	#[must_use]
 	pub fn is_loaded(&self) -> bool { !self.is_unloaded() }
@@ -332,3 +657,353 @@ impl NbtChunk {
 	#[must_use]
 	pub fn uv(&self) -> Vec2u { if self.is_unloaded() { Self::GHOST_UV } else { Self::UV } }
 }
+
+#[cfg(test)]
+mod tests {
+	use std::{io::Read, path::Path};
+
+	use flate2::Compression;
+
+	use super::*;
+	use crate::{
+		elements::region::NbtRegion,
+		mutable_indices,
+		tree::{
+			OwnedMutableIndices,
+			actions::{add::add_element, move_chunk::{MoveChunkError, move_chunk}, replace::replace_element},
+			indices::OwnedIndices,
+		},
+		workbench::{
+			element_action::ElementAction,
+			marked_line::{MarkedLine, MarkedLines},
+			tab::{FilePath, NbtFileFormat, Tab},
+		},
+	};
+
+	fn chunk_payload(compression: u8, snbt: &str, level: Compression) -> Vec<u8> {
+		let nbt = NbtElement::from_str(snbt).expect("Valid SNBT").1.to_be_file();
+		let mut payload = vec![];
+		match compression {
+			1 => flate2::read::GzEncoder::new(nbt.as_slice(), level).read_to_end(&mut payload),
+			2 => flate2::read::ZlibEncoder::new(nbt.as_slice(), level).read_to_end(&mut payload),
+			_ => unreachable!(),
+		}
+		.expect("Valid compression");
+		payload
+	}
+
+	fn region(chunks: &[(u8, Vec<u8>)]) -> Vec<u8> {
+		let mut region = vec![0_u8; 8192];
+		for (idx, (compression, payload)) in chunks.iter().enumerate() {
+			let sector = region.len() / 4096;
+			let len = payload.len() + 1;
+			let sectors = (len + 4).div_ceil(4096);
+			region[idx * 4..idx * 4 + 4].copy_from_slice(&u32::try_from(sector << 8 | sectors).expect("Valid offset").to_be_bytes());
+			region[4096 + idx * 4..4096 + idx * 4 + 4].copy_from_slice(&1_u32.to_be_bytes());
+			region.extend_from_slice(&u32::try_from(len).expect("Valid length").to_be_bytes());
+			region.push(*compression);
+			region.extend_from_slice(payload);
+			region.resize(region.len().next_multiple_of(4096), 0);
+		}
+		region
+	}
+
+	fn fixture(region: &[u8], path: Option<&Path>) -> (NbtElement, OwnedMutableIndices) { (NbtElement::from_be_mca(region, path).expect("Valid region"), OwnedMutableIndices::default()) }
+
+	fn read_chunk(region: &[u8], idx: usize) -> (u8, &[u8]) {
+		let offset = u32::from_be_bytes(region[idx * 4..idx * 4 + 4].try_into().expect("4 bytes")) as usize;
+		let data = &region[(offset >> 8) * 4096..];
+		let len = u32::from_be_bytes(data[..4].try_into().expect("4 bytes")) as usize;
+		(data[4], &data[5..4 + len])
+	}
+
+	#[test]
+	fn test_chunk_compression_is_preserved() {
+		// deliberately not the compression level nbtworkbench encodes with, so a re-encode would be noticed
+		let chunks = [(1_u8, chunk_payload(1, r#"{"a":1b,"b":"gzip"}"#, Compression::none())), (2_u8, chunk_payload(2, r#"{"a":2b,"b":"zlib"}"#, Compression::fast()))];
+
+		let region = region(&chunks);
+
+		let (mut nbt, mut state) = fixture(&region, None);
+		// edits are made on top of what's in it
+		nbt.as_region_mut().expect("Region").chunks[1].as_chunk_mut().expect("Chunk").decode();
+		let mi = mutable_indices!(state);
+		let _ = replace_element(&mut nbt, (Some("c".into()), NbtElement::from_str("3b").expect("Valid SNBT").1), OwnedIndices::from([1, 1]), mi).expect("Replaced in chunk");
+		let saved = nbt.to_be_file();
+
+		assert_eq!(read_chunk(&saved, 0), (1, chunks[0].1.as_slice()));
+		let (compression, payload) = read_chunk(&saved, 1);
+		assert_eq!(compression, 2);
+		assert_ne!(payload, chunks[1].1.as_slice());
+		// only the edited chunk is stamped as modified
+		let timestamp = |idx: usize| u32::from_be_bytes(saved[4096 + idx * 4..4096 + idx * 4 + 4].try_into().expect("4 bytes"));
+		assert_eq!(timestamp(0), 1);
+		assert!(timestamp(1) > 1);
+
+		let reopened = NbtElement::from_be_mca(&saved, None).expect("Valid region");
+		let reopened = reopened.as_region().expect("Region");
+		assert_eq!(reopened.chunks[0], nbt.as_region().expect("Region").chunks[0]);
+		assert_eq!(reopened.chunks[1], nbt.as_region().expect("Region").chunks[1]);
+	}
+
+	#[test]
+	fn test_external_chunks() {
+		let dir = std::env::temp_dir().join(format!("nbtworkbench-test-external-chunks-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).expect("Created directory");
+		let path = dir.join("r.-1.2.mca");
+		// chunk 0's .mcc file is missing, chunk 1's is at (-31, 64)
+		let external = chunk_payload(2, r#"{"a":1b}"#, Compression::fast());
+		std::fs::write(dir.join("c.-31.64.mcc"), &external).expect("Wrote .mcc file");
+		let region = region(&[(0x82, vec![]), (0x82, vec![])]);
+
+		let nbt = NbtElement::from_be_mca(&region, Some(&path)).expect("Valid region");
+		let nbt = nbt.as_region().expect("Region");
+		assert!(nbt.chunks[0].as_chunk().expect("Chunk").is_missing_external());
+		assert!(Some(&*nbt.chunks[1].as_chunk().expect("Chunk").inner) == NbtElement::from_str(r#"{"a":1b}"#).expect("Valid SNBT").1.as_compound());
+
+		// now small enough to be stored in the region, so the .mcc file should be removed
+		let mut writer = UncheckedBufWriter::new();
+		let externals = nbt.to_be_bytes_with_external_chunks(&mut writer);
+		nbt.write_external_chunks(&path, externals).expect("Wrote .mcc files");
+		let saved = writer.finish();
+		assert_eq!(read_chunk(&saved, 0), (0x82, [].as_slice()));
+		assert_eq!(read_chunk(&saved, 1), (2, external.as_slice()));
+		assert!(!dir.join("c.-31.64.mcc").exists());
+
+		std::fs::remove_dir_all(&dir).expect("Removed directory");
+	}
+
+	#[test]
+	fn test_undecodable_chunk_is_kept() {
+		let chunks = [(2_u8, chunk_payload(2, r#"{"a":1b}"#, Compression::fast())), (2_u8, b"not a zlib stream".to_vec()), (1_u8, chunk_payload(1, r#"{"a":3b}"#, Compression::fast()))];
+		let region = region(&chunks);
+
+		let mut nbt = NbtElement::from_be_mca(&region, None).expect("Valid region");
+		nbt.decode_chunks(&mut MarkedLines::new());
+		let nbt_region = nbt.as_region().expect("Region");
+		assert!(!nbt_region.chunks[0].as_chunk().expect("Chunk").is_undecodable());
+		assert!(nbt_region.chunks[1].as_chunk().expect("Chunk").is_undecodable());
+		assert!(Some(&*nbt_region.chunks[2].as_chunk().expect("Chunk").inner) == NbtElement::from_str(r#"{"a":3b}"#).expect("Valid SNBT").1.as_compound());
+
+		assert_eq!(nbt.to_be_file(), region);
+	}
+
+	#[test]
+	fn test_corrupted_chunks_are_kept_until_deleted() {
+		let chunks = [(2_u8, chunk_payload(2, r#"{"a":1b}"#, Compression::fast())), (9_u8, b"not a compression format".to_vec()), (2_u8, b"not a zlib stream".to_vec())];
+		let mut region = region(&chunks);
+		// two sectors long, starting past the end of the file
+		region[12..16].copy_from_slice(&(100_u32 << 8 | 2).to_be_bytes());
+
+		let (mut nbt, mut state) = fixture(&region, None);
+		nbt.decode_chunks(&mut MarkedLines::new());
+		let chunk = |nbt: &NbtElement, idx: usize| nbt.as_region().expect("Region").chunks[idx].as_chunk().expect("Chunk").clone();
+		assert!(!chunk(&nbt, 0).is_corrupted());
+		assert!(chunk(&nbt, 1).error().is_some_and(|error| error.contains("compression")));
+		assert!(chunk(&nbt, 2).is_undecodable() && chunk(&nbt, 2).is_corrupted());
+		assert!(chunk(&nbt, 3).error().is_some_and(|error| error.contains("end of the file")));
+		assert!(nbt.get(1).expect("Chunk").1.actions().contains(&ElementAction::DeleteCorruptedChunk));
+		assert!(!nbt.get(0).expect("Chunk").1.actions().contains(&ElementAction::DeleteCorruptedChunk));
+
+		// only the chunk past the end moves, to an empty sector after the rest
+		let saved = nbt.to_be_file();
+		let mut expected = region.clone();
+		expected[12..16].copy_from_slice(&saved[12..16]);
+		assert_eq!(saved[..region.len()], expected);
+		assert_eq!(read_chunk(&saved, 1), (9, chunks[1].1.as_slice()));
+		let reopened = NbtElement::from_be_mca(&saved, None).expect("Valid region");
+		assert!(chunk(&reopened, 3).is_corrupted());
+
+		let mi = mutable_indices!(state);
+		assert!(ElementAction::DeleteCorruptedChunk.apply(&mut nbt, OwnedIndices::from([0]), "r.0.0.mca", mi).is_err());
+		let action = ElementAction::DeleteCorruptedChunk.apply(&mut nbt, OwnedIndices::from([1]), "r.0.0.mca", mi).expect("Corrupted chunk").expect("Deleted");
+		assert!(chunk(&nbt, 1).is_unloaded());
+		assert_eq!(nbt.to_be_file()[4..8], [0; 4]);
+
+		let mut path = FilePath::new("r.0.0.mca").expect("Valid path");
+		let _ = action.undo(&mut nbt, mi, &mut path, &mut NbtFileFormat::Nbt, &mut None).expect("Can be undone");
+		assert_eq!(read_chunk(&nbt.to_be_file(), 1), (9, chunks[1].1.as_slice()));
+	}
+
+	#[test]
+	fn test_corrupt_chunk_fits_its_header() {
+		let mut chunk = NbtChunk::new(NbtCompound::default(), (0, 0), ChunkFileFormat::default(), 1);
+		chunk.original = Some(Box::new(OriginalChunk::Corrupt { sectors: vec![1; NbtChunk::MAX_INLINE_LEN + 4096], error: String::new() }));
+		let mut writer = UncheckedBufWriter::new();
+		assert!(chunk.to_be_bytes_with_external(&mut writer, false).is_none());
+		assert_eq!(writer.finish().len(), NbtChunk::MAX_INLINE_LEN);
+	}
+
+	#[test]
+	fn test_region_is_compacted() {
+		let chunks = [(2_u8, chunk_payload(2, r#"{"a":1b}"#, Compression::fast())), (2_u8, chunk_payload(2, r#"{"a":2b}"#, Compression::fast())), (1_u8, chunk_payload(1, r#"{"a":3b}"#, Compression::fast()))];
+		let mut region = region(&chunks);
+		// chunk 1 deleted by a program which left its sector behind, and chunk 2 given a second sector it doesn't need
+		region[4..8].fill(0);
+		region[8..12].copy_from_slice(&(4_u32 << 8 | 2).to_be_bytes());
+		region.resize(region.len() + 4096, 0);
+		for (idx, timestamp) in [(0, 1000_u32), (1, 0), (2, 3000)] {
+			region[4096 + idx * 4..4096 + idx * 4 + 4].copy_from_slice(&timestamp.to_be_bytes());
+		}
+
+		let saved = NbtElement::from_be_mca(&region, None).expect("Valid region").to_be_file();
+		assert_eq!(region.len() - saved.len(), 2 * 4096);
+		assert_eq!(saved[..12], [0, 0, 2, 1, 0, 0, 0, 0, 0, 0, 3, 1]);
+		assert_eq!(saved[4096..4108], region[4096..4108]);
+		assert_eq!(read_chunk(&saved, 0), (2, chunks[0].1.as_slice()));
+		assert_eq!(read_chunk(&saved, 2), (1, chunks[2].1.as_slice()));
+	}
+
+	#[test]
+	fn test_chunks_are_decoded_lazily() {
+		let chunks = [(2_u8, chunk_payload(2, r#"{"a":1b,"b":{"c":2b}}"#, Compression::fast())), (1_u8, chunk_payload(1, r#"{"a":3b}"#, Compression::fast()))];
+		let region = region(&chunks);
+
+		let mut nbt = NbtElement::from_be_mca(&region, None).expect("Valid region");
+		let chunk = nbt.as_region().expect("Region").chunks[0].as_chunk().expect("Chunk");
+		assert!(chunk.is_undecoded());
+		assert_eq!(chunk.true_height(), 1);
+		assert!(Some(&*chunk.decoded().expect("Valid payload")) == NbtElement::from_str(r#"{"a":1b,"b":{"c":2b}}"#).expect("Valid SNBT").1.as_compound());
+		assert_eq!(nbt.to_be_file(), region);
+
+		// the bookmark on the second chunk moves down by the lines the first one gained
+		let mut bookmarks = MarkedLines::new();
+		let _ = bookmarks.toggle(MarkedLine::new(3, 2));
+		nbt.decode_chunks(&mut bookmarks);
+		let chunk = nbt.as_region().expect("Region").chunks[0].as_chunk().expect("Chunk");
+		assert!(!chunk.is_undecoded());
+		assert_eq!(chunk.true_height(), 4);
+		assert_eq!(bookmarks.iter().map(|bookmark| bookmark.true_line_number()).collect::<Vec<_>>(), [6]);
+		assert_eq!(nbt.to_be_file(), region);
+	}
+
+	#[test]
+	fn test_sorting_undecoded_chunk() {
+		let chunks = [(2_u8, chunk_payload(2, r#"{"b":1b,"a":{"c":2b}}"#, Compression::fast())), (2_u8, b"not a zlib stream".to_vec()), (1_u8, chunk_payload(1, r#"{"a":3b}"#, Compression::fast()))];
+		let (mut nbt, mut state) = fixture(&region(&chunks), None);
+		let chunk = |nbt: &NbtElement, idx: usize| nbt.as_region().expect("Region").chunks[idx].as_chunk().expect("Chunk").clone();
+		assert!(chunk(&nbt, 1).decoded().is_err_and(|error| error.contains("payload")));
+
+		// on the third chunk, which is pushed down by the lines the first one gains
+		let _ = state.bookmarks.toggle(MarkedLine::new(4, 3));
+		let mi = mutable_indices!(state);
+		let _ = ElementAction::SortCompoundByName.apply(&mut nbt, OwnedIndices::from([0]), "r.0.0.mca", mi).expect("Sorted").expect("Reordered");
+		assert!(!chunk(&nbt, 0).is_undecoded());
+		assert_eq!(chunk(&nbt, 0).to_string(), "0|0{a:{c:2b},b:1b}");
+		assert_eq!(nbt.true_height(), 1 + 4 + 1 + 1 + 1021);
+		assert_eq!(mi.bookmarks.iter().map(|bookmark| bookmark.true_line_number()).collect::<Vec<_>>(), [7]);
+	}
+
+	#[test]
+	#[allow(clippy::cast_precision_loss)]
+	fn test_grid_tooltip() {
+		let payload = chunk_payload(2, r#"{"DataVersion":3953,"Status":"minecraft:full"}"#, Compression::fast());
+		let region = region(&[(2_u8, payload.clone()), (9_u8, vec![])]);
+		let nbt = NbtElement::from_be_mca(&region, None).expect("Valid region");
+		let chunk = |idx: usize| nbt.as_region().expect("Region").chunks[idx].as_chunk().expect("Chunk").clone();
+
+		let world = |idx: usize| NbtRegion::world_chunk_pos(Path::new("r.-1.2.mca"), idx);
+		assert_eq!(world(33), Some((-31, 65)));
+		assert_eq!(chunk(0).payload_len(), Some(payload.len()));
+		assert_eq!(chunk(0).grid_tooltip((0, 0), world(0)), [
+			"Chunk 0, 0 (world -32, 64)".to_owned(),
+			format!("{:.1} KiB on disk in 1 sector, ZLib", payload.len() as f64 / 1024.0),
+			"Last modified 1970-01-01 00:00:01 UTC".to_owned(),
+			"DataVersion 3953 (1.21)".to_owned(),
+			"Status minecraft:full".to_owned(),
+			"Not yet loaded".to_owned(),
+		]);
+		assert!(chunk(1).grid_tooltip((1, 0), None).last().is_some_and(|line| line.starts_with("Couldn't be read")));
+		assert_eq!(chunk(2).grid_tooltip((2, 0), None), ["Chunk 2, 0", "Not generated"]);
+	}
+
+	#[test]
+	fn test_mcregion_round_trip() {
+		let chunks = [(2_u8, chunk_payload(2, r#"{"Level":{"xPos":0,"zPos":0}}"#, Compression::fast())), (1_u8, chunk_payload(1, r#"{"Level":{"xPos":1,"zPos":0}}"#, Compression::fast()))];
+		let region = region(&chunks);
+
+		let mut nbt = NbtElement::from_be_mca(&region, Some(Path::new("r.0.0.mcr"))).expect("Valid region");
+		assert!(nbt.as_region().expect("Region").is_mcregion());
+		assert_eq!(nbt.to_be_file(), region);
+
+		// McRegion can't read lz4 compressed chunks
+		nbt.as_region_mut().expect("Region").chunks[1].as_chunk_mut().expect("Chunk").set_format(ChunkFileFormat::Lz4);
+		let saved = nbt.to_be_file();
+		assert_eq!(read_chunk(&saved, 0), (2, chunks[0].1.as_slice()));
+		assert_eq!(read_chunk(&saved, 1).0, 2);
+		let reopened = NbtElement::from_be_mca(&saved, Some(Path::new("r.0.0.mcr"))).expect("Valid region");
+		assert!(reopened.as_region().expect("Region").chunks[1] == nbt.as_region().expect("Region").chunks[1]);
+	}
+
+	#[test]
+	fn test_move_chunk() {
+		let chunks = [(2_u8, chunk_payload(2, r#"{"DataVersion":3955,"xPos":-32,"zPos":64}"#, Compression::fast())), (2_u8, chunk_payload(2, r#"{"xPos":-31,"zPos":64}"#, Compression::fast()))];
+		let region = region(&chunks);
+		let path = Path::new("r.-1.2.mca");
+
+		let (mut nbt, mut state) = fixture(&region, Some(path));
+		let mi = mutable_indices!(state);
+		assert!(matches!(move_chunk(&mut nbt, 0, 1, path, mi), Err(MoveChunkError::Occupied { x: 1, z: 0 })));
+		assert!(matches!(move_chunk(&mut nbt, 2, 3, path, mi), Err(MoveChunkError::NoChunk { idx: 2 })));
+
+		let action = move_chunk(&mut nbt, 0, 32 + 3, path, mi).expect("Moved to an empty slot").into_action();
+		let moved = nbt.as_region().expect("Region").chunks[32 + 3].as_chunk().expect("Chunk");
+		assert_eq!(moved.world_pos(), Some((-29, 65)));
+		assert!(nbt.as_region().expect("Region").chunks[0].as_chunk().is_some_and(NbtChunk::is_unloaded));
+		let saved = nbt.to_be_file();
+		assert_eq!(u32::from_be_bytes(saved[..4].try_into().expect("4 bytes")), 0);
+		let reopened = NbtElement::from_be_mca(&saved, Some(path)).expect("Valid region");
+		let reopened = reopened.as_region().expect("Region").chunks[32 + 3].as_chunk().expect("Chunk");
+		let mut reopened = reopened.clone();
+		assert!(reopened.decode());
+		assert_eq!(reopened.world_pos(), Some((-29, 65)));
+
+		let _ = action.undo(&mut nbt, mi, &mut FilePath::new(path).expect("Valid path"), &mut NbtFileFormat::Nbt, &mut None).expect("Undone");
+		let region = nbt.as_region().expect("Region");
+		assert_eq!(region.chunks[0].as_chunk().and_then(NbtChunk::world_pos), Some((-32, 64)));
+		assert!(region.chunks[32 + 3].as_chunk().is_some_and(NbtChunk::is_unloaded));
+	}
+
+	#[test]
+	fn test_empty_chunk() {
+		let region = NbtRegion::default();
+		let chunk = region.empty_chunk((-341, 127));
+		assert_eq!(chunk.world_pos(), Some((-341, 127)));
+		assert!(chunk.map.idx_of("sections").is_some());
+
+		let mut region = NbtRegion::default();
+		let old = NbtElement::from_str("{DataVersion:1343,Level:{xPos:0,zPos:0}}").expect("Valid SNBT").1.into_compound().expect("Compound");
+		region.chunks[0] = NbtElement::Chunk(NbtChunk::new(old, (0, 0), ChunkFileFormat::Zlib, 1));
+		let chunk = region.empty_chunk((1, 0));
+		assert_eq!(chunk.world_pos(), Some((1, 0)));
+		assert!(chunk.map.idx_of("Level").is_some());
+	}
+
+	#[test]
+	fn test_export_and_import_chunk() {
+		let chunks = [(2_u8, chunk_payload(2, r#"{"DataVersion":3955,"xPos":-32,"zPos":64,"sections":[{"Y":0b}]}"#, Compression::fast()))];
+		let region = region(&chunks);
+		let path = Path::new("r.-1.2.mca");
+		let nbt = NbtElement::from_be_mca(&region, Some(path)).expect("Valid region");
+		let compound = nbt.as_region().expect("Region").chunks[0].as_chunk().expect("Chunk").decoded().expect("Valid payload").into_owned();
+
+		for (name, format) in [("c.-32.64.dat", NbtFileFormat::Gzip), ("c.-32.64.snbt", NbtFileFormat::Snbt), ("c.-32.64.nbt", NbtFileFormat::Nbt), ("c.-32.64.zst", NbtFileFormat::Zstd)] {
+			assert_eq!(NbtFileFormat::from_path(Path::new(name)), Some(format));
+			let exported = format.encode(&NbtElement::Compound(compound.clone()));
+			let imported = Tab::parse_chunk(name, exported).expect("Valid chunk");
+
+			let mut nbt = NbtElement::Region(NbtRegion::default());
+			let mut state = OwnedMutableIndices::default();
+			let mi = mutable_indices!(state);
+			let _ = add_element(&mut nbt, (None, NbtElement::Chunk(NbtRegion::imported_chunk(imported, (-32, 64)))), OwnedIndices::from([0]), mi).expect("Added to an empty slot");
+			let chunk = nbt.as_region().expect("Region").chunks[0].as_chunk().expect("Chunk");
+			assert!(**chunk == compound, "{name} round trips");
+		}
+
+		// moved to where it's imported
+		let chunk = NbtRegion::imported_chunk(compound.clone(), (-31, 65));
+		assert_eq!(chunk.world_pos(), Some((-31, 65)));
+		assert!(Tab::parse_chunk(path, region).is_err());
+		assert!(Tab::parse_chunk("c.0.0.snbt", b"[1b]".to_vec()).is_err());
+	}
+}
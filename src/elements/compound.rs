@@ -8,14 +8,14 @@ use std::{
 	slice::{Iter, IterMut},
 };
 
-use compact_str::CompactString;
+use compact_str::{CompactString, format_compact};
 use hashbrown::hash_table::{Entry::*, HashTable};
 
 #[cfg(target_arch = "wasm32")]
 use crate::wasm::{FakeScope as Scope, fake_scope as scope};
 use crate::{
 	config,
-	elements::{ComplexNbtElementVariant, Matches, NbtElement, NbtElementAndKey, NbtElementAndKeyRef, NbtElementAndKeyRefMut, NbtElementVariant, result::NbtParseResult},
+	elements::{ComplexNbtElementVariant, Matches, NbtElement, NbtElementAndKey, NbtElementAndKeyRef, NbtElementAndKeyRefMut, NbtElementVariant, number_base::NumberBase, result::NbtParseResult, snbt_error},
 	hash,
 	render::{
 		RenderContext,
@@ -132,17 +132,17 @@ impl NbtElementVariant for NbtCompound {
 		s = s.strip_prefix('{').ok_or(s.len())?.trim_start();
 		let mut compound = Self::default();
 		while !s.starts_with('}') {
-			let (key, s2) = s.snbt_string_read()?;
-			s = s2.trim_start().strip_prefix(':').ok_or(s2.len())?.trim_start();
+			let (key, s2) = s.snbt_string_read().map_err(|x| snbt_error::expected(x, "a key"))?;
+			s = s2.trim_start().strip_prefix(':').ok_or_else(|| snbt_error::expected(s2.trim_start().len(), "':'"))?.trim_start();
 			let (s2, value) = NbtElement::from_str0(s, NbtElement::parse_int)?;
-			compound.map.insert(CompoundEntry::new(key, value));
+			compound.map.push(CompoundEntry::new(key, value));
 			s = s2.trim_start();
 			if let Some(s2) = s.strip_prefix(',') {
 				s = s2.trim_start();
 			} else if s.starts_with('}') {
 				break;
 			} else {
-				return Err(s.len())
+				return Err(snbt_error::expected(s.len(), "',' or '}'"))
 			}
 		}
 		let s = s.strip_prefix('}').ok_or(s.len())?;
@@ -166,7 +166,7 @@ impl NbtElementVariant for NbtCompound {
 				decoder.assert_len(2)?;
 				let key = decoder.string()?;
 				let value = NbtElement::from_bytes(current_element, decoder)?;
-				compound.map.insert(CompoundEntry::new(key, value));
+				compound.map.push(CompoundEntry::new(key, value));
 				if is_err(&decoder.assert_len(1)) {
 					break // wow mojang, saving one byte, so cool of you
 				};
@@ -281,6 +281,9 @@ impl NbtElementVariant for NbtCompound {
 				if *remaining_scroll == 0 {
 					builder.draw_texture(pos - (16, 0), CONNECTION_UV, (16, (idx != self.len() - 1) as usize * 7 + 9));
 				}
+				if *remaining_scroll == 0 && self.map.is_duplicate(idx) {
+					ctx.draw_duplicate_key_warning(pos.y, key, builder);
+				}
 				ctx.check_for_key_duplicate(|text, _| self.map.has(text) && key != text, false);
 				if ctx.has_duplicate_key_error() && ctx.selected_text_y() == Some(pos.y) {
 					ctx.set_red_line_number(pos.y, 0);
@@ -306,6 +309,18 @@ impl NbtElementVariant for NbtCompound {
 	fn value(&self) -> Cow<'_, str> { Cow::Owned(format!("{} {}", self.len(), if self.len() == 1 { "entry" } else { "entries" })) }
 }
 
+impl NbtCompound {
+	/// Like [`insert`](ComplexNbtElementVariant::insert), but keeping the entry's key even if another entry has it, for putting back what was taken out of it
+	///
+	/// # Safety
+	/// * must be assured to update valid caches
+	pub unsafe fn reinsert(&mut self, idx: usize, entry: CompoundEntry) {
+		self.height += entry.value.height() as u32;
+		self.true_height += entry.value.true_height() as u32;
+		self.map.insert_duplicate_at(entry, idx.min(self.len()));
+	}
+}
+
 impl ComplexNbtElementVariant for NbtCompound {
 	type Entry = CompoundEntry;
 	const ROOT_UV: Vec2u = COMPOUND_ROOT_UV;
@@ -354,6 +369,9 @@ impl ComplexNbtElementVariant for NbtCompound {
 
 	unsafe fn remove(&mut self, idx: usize) -> Option<Self::Entry> {
 		let entry = self.map.shift_remove_idx(idx)?;
+		// as `insert` adds them, so removing tells apart from replacing, see `remove_element`
+		self.height = self.height.saturating_sub(entry.value.height() as u32);
+		self.true_height = self.true_height.saturating_sub(entry.value.true_height() as u32);
 		Some(entry)
 	}
 
@@ -364,8 +382,13 @@ impl ComplexNbtElementVariant for NbtCompound {
 
 		let old_entry = unsafe { self.remove(idx) };
 
-		// always Ok(None)
-		let _ = unsafe { self.insert(idx, entry) };
+		// a duplicate read from a file keeps its key when only its value changes
+		if old_entry.as_ref().is_some_and(|old_entry| old_entry.key == entry.key) {
+			unsafe { self.reinsert(idx, entry) };
+		} else {
+			// always Ok(None)
+			let _ = unsafe { self.insert(idx, entry) };
+		}
 
 		Ok(old_entry)
 	}
@@ -410,7 +433,7 @@ impl ComplexNbtElementVariant for NbtCompound {
 		for CompoundEntry { key, value: child } in self.children() {
 			height += child.height() as u32;
 			true_height += child.true_height() as u32;
-			end_x = end_x.max(NbtElement::DEPTH_INCREMENT_WIDTH + SelectedText::PREFIXING_SPACE_WIDTH + key.width() + const { width_ascii(": ") } + child.value_width());
+			end_x = end_x.max(NbtElement::DEPTH_INCREMENT_WIDTH + SelectedText::PREFIXING_SPACE_WIDTH + key.width() + const { width_ascii(": ") } + child.keyed_value_width(Some(key), NumberBase::Decimal, false));
 			end_x = end_x.max(NbtElement::DEPTH_INCREMENT_WIDTH + child.end_x());
 		}
 
@@ -528,14 +551,50 @@ impl CompoundEntry {
 }
 
 impl CompoundMap {
+	/// The first entry with `key`, as later ones are [duplicates](Self::is_duplicate)
+	#[must_use]
+	pub fn idx_of(&self, key: &str) -> Option<usize> { self.indices.iter_hash(hash!(key)).copied().filter(|&idx| unsafe { self.entries.get_unchecked(idx).key.as_str() == key }).min() }
+
+	/// Whether an earlier entry has the same key as the one at `idx`, which only files read off disk can have
 	#[must_use]
-	pub fn idx_of(&self, key: &str) -> Option<usize> { self.indices.find(hash!(key), |&idx| unsafe { self.entries.get_unchecked(idx).key.as_str() == key }).copied() }
+	pub fn is_duplicate(&self, idx: usize) -> bool { self.entries.get(idx).is_some_and(|entry| self.idx_of(&entry.key) != Some(idx)) }
 
 	#[must_use]
 	pub fn has(&self, key: &str) -> bool { self.idx_of(key.as_ref()).is_some() }
 
+	/// `key` with a `_copy` suffix which no entry has yet, numbered from the second copy on
+	#[must_use]
+	pub fn copy_key(&self, key: &str) -> CompactString {
+		let mut copy = format_compact!("{key}_copy");
+		let mut n = 2_usize;
+		while self.has(&copy) {
+			copy = format_compact!("{key}_copy{n}");
+			n += 1;
+		}
+		copy
+	}
+
 	pub fn insert(&mut self, entry: CompoundEntry) -> Option<NbtElement> { self.insert_full(entry).1 }
 
+	/// Adds `entry` after the others even if one of them has its key, so a file with the same key twice keeps both when it's read
+	pub fn push(&mut self, entry: CompoundEntry) {
+		let idx = self.entries.len();
+		self.indices.insert_unique(hash!(entry.key), idx, |&idx| hash!(unsafe { self.entries.get_unchecked(idx) }.key));
+		self.entries.push(entry);
+	}
+
+	/// Like [`Self::push`], but at `idx` rather than after the others
+	pub fn insert_duplicate_at(&mut self, entry: CompoundEntry, idx: usize) {
+		for index in &mut self.indices {
+			if *index >= idx {
+				*index += 1;
+			}
+		}
+		let hash = hash!(entry.key);
+		self.entries.insert(idx, entry);
+		self.indices.insert_unique(hash, idx, |&idx| hash!(unsafe { self.entries.get_unchecked(idx) }.key));
+	}
+
 	#[must_use]
 	pub fn len(&self) -> usize { self.entries.len() }
 
@@ -591,10 +650,6 @@ impl CompoundMap {
 			}
 		};
 
-		unsafe {
-			core::ptr::write(ptr, idx);
-		}
-
 		match idx.cmp(&end) {
 			Ordering::Less =>
 				for index in self.indices.iter_mut() {
@@ -613,12 +668,17 @@ impl CompoundMap {
 				},
 		}
 
+		// only once the others are shifted, so the entry's own index isn't shifted with them
+		unsafe {
+			core::ptr::write(ptr, idx);
+		}
+
 		prev
 	}
 
 	/// # Safety
 	///
-	/// * compound must not contain this key already somewhere else
+	/// * compound must not contain this key already somewhere else, unless it's meant to be a [duplicate](Self::is_duplicate)
 	///
 	/// * idx must be valid
 	pub unsafe fn update_key_idx_unchecked(&mut self, idx: usize, key: CompactString) -> CompactString {
@@ -660,7 +720,8 @@ impl CompoundMap {
 	#[must_use]
 	pub fn create_sort_mapping<F: FnMut(&CompoundEntry, &CompoundEntry) -> Ordering>(&self, mut f: F) -> Box<[usize]> {
 		let mut mapping = (0..self.len()).collect::<Vec<_>>();
-		mapping.sort_unstable_by(|&a, &b| f(unsafe { self.entries.get_unchecked(a) }, unsafe { self.entries.get_unchecked(b) }));
+		// stable, so entries with the same key stay in the order they were read
+		mapping.sort_by(|&a, &b| f(unsafe { self.entries.get_unchecked(a) }, unsafe { self.entries.get_unchecked(b) }));
 		// SAFETY: definitely a valid mapping that was generated
 		unsafe { util::invert_mapping_unchecked(&mapping) }
 	}
@@ -674,4 +735,13 @@ impl CompoundMap {
 			Some(unsafe { self.update_key_idx_unchecked(idx, key) })
 		}
 	}
+
+	/// Like [`Self::update_key`], but allowing a key another entry has, so it's only [`None`] if there's no entry at `idx`
+	pub fn restore_key(&mut self, idx: usize, key: CompactString) -> Option<CompactString> {
+		match self.entries.get(idx) {
+			Some(entry) if entry.key == key => Some(key),
+			Some(_) => Some(unsafe { self.update_key_idx_unchecked(idx, key) }),
+			None => None,
+		}
+	}
 }
@@ -0,0 +1,259 @@
+use thiserror::Error;
+
+use crate::elements::{
+	ComplexNbtElementVariant, NbtElementVariant, PrimitiveNbtElementVariant,
+	array::{NbtByteArray, NbtIntArray, NbtLongArray},
+	byte::NbtByte,
+	double::NbtDouble,
+	element::{NbtElement, NbtPattern, id_to_string_name},
+	float::NbtFloat,
+	int::NbtInt,
+	list::NbtList,
+	long::NbtLong,
+	short::NbtShort,
+	string::NbtString,
+};
+
+/// A type an element can be converted to with [`convert`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConversionTarget {
+	Byte,
+	Short,
+	Int,
+	Long,
+	Float,
+	Double,
+	String,
+	ByteArray,
+	IntArray,
+	LongArray,
+	List,
+}
+
+impl ConversionTarget {
+	pub const ALL: [Self; 11] = [Self::Byte, Self::Short, Self::Int, Self::Long, Self::Float, Self::Double, Self::String, Self::ByteArray, Self::IntArray, Self::LongArray, Self::List];
+
+	#[must_use]
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Byte => "Byte",
+			Self::Short => "Short",
+			Self::Int => "Int",
+			Self::Long => "Long",
+			Self::Float => "Float",
+			Self::Double => "Double",
+			Self::String => "String",
+			Self::ByteArray => "Byte Array",
+			Self::IntArray => "Int Array",
+			Self::LongArray => "Long Array",
+			Self::List => "List",
+		}
+	}
+
+	/// The type `element` already is, if it's one it could be converted to
+	#[must_use]
+	pub fn of(element: &NbtElement) -> Option<Self> {
+		use NbtPattern as Nbt;
+
+		Some(match element.as_pattern() {
+			Nbt::Byte(_) => Self::Byte,
+			Nbt::Short(_) => Self::Short,
+			Nbt::Int(_) => Self::Int,
+			Nbt::Long(_) => Self::Long,
+			Nbt::Float(_) => Self::Float,
+			Nbt::Double(_) => Self::Double,
+			Nbt::String(_) => Self::String,
+			Nbt::ByteArray(_) => Self::ByteArray,
+			Nbt::IntArray(_) => Self::IntArray,
+			Nbt::LongArray(_) => Self::LongArray,
+			Nbt::List(_) => Self::List,
+			_ => return None,
+		})
+	}
+
+	const fn is_array(self) -> bool { matches!(self, Self::ByteArray | Self::IntArray | Self::LongArray) }
+
+	/// The id of the elements an array of this type holds
+	const fn array_child_id(self) -> Option<u8> {
+		match self {
+			Self::ByteArray => Some(NbtByte::ID),
+			Self::IntArray => Some(NbtInt::ID),
+			Self::LongArray => Some(NbtLong::ID),
+			_ => None,
+		}
+	}
+
+	#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+	fn convert_number(self, number: Number) -> Option<NbtElement> {
+		// `as` saturates from floats, so integers are clamped to match
+		Some(match (self, number) {
+			(Self::Byte, Number::Integer(value)) => NbtElement::Byte(NbtByte { value: value.clamp(i8::MIN.into(), i8::MAX.into()) as i8 }),
+			(Self::Byte, Number::Float(value)) => NbtElement::Byte(NbtByte { value: value as i8 }),
+			(Self::Short, Number::Integer(value)) => NbtElement::Short(NbtShort { value: value.clamp(i16::MIN.into(), i16::MAX.into()) as i16 }),
+			(Self::Short, Number::Float(value)) => NbtElement::Short(NbtShort { value: value as i16 }),
+			(Self::Int, Number::Integer(value)) => NbtElement::Int(NbtInt { value: value.clamp(i32::MIN.into(), i32::MAX.into()) as i32 }),
+			(Self::Int, Number::Float(value)) => NbtElement::Int(NbtInt { value: value as i32 }),
+			(Self::Long, Number::Integer(value)) => NbtElement::Long(NbtLong { value }),
+			(Self::Long, Number::Float(value)) => NbtElement::Long(NbtLong { value: value as i64 }),
+			(Self::Float, Number::Integer(value)) => NbtElement::Float(NbtFloat { value: value as f32 }),
+			(Self::Float, Number::Float(value)) => NbtElement::Float(NbtFloat { value: value as f32 }),
+			(Self::Double, Number::Integer(value)) => NbtElement::Double(NbtDouble { value: value as f64 }),
+			(Self::Double, Number::Float(value)) => NbtElement::Double(NbtDouble { value }),
+			_ => return None,
+		})
+	}
+}
+
+#[derive(Copy, Clone)]
+enum Number {
+	Integer(i64),
+	Float(f64),
+}
+
+impl Number {
+	/// Strings count as the number they hold, if they hold one
+	fn of(element: &NbtElement) -> Option<Self> {
+		use NbtPattern as Nbt;
+
+		Some(match element.as_pattern() {
+			Nbt::Byte(byte) => Self::Integer(byte.value.into()),
+			Nbt::Short(short) => Self::Integer(short.value.into()),
+			Nbt::Int(int) => Self::Integer(int.value.into()),
+			Nbt::Long(long) => Self::Integer(long.value),
+			Nbt::Float(float) => Self::Float(float.value.into()),
+			Nbt::Double(double) => Self::Float(double.value),
+			Nbt::String(string) => {
+				let str = string.str.as_str().trim();
+				str.parse().map(Self::Integer).or_else(|_| str.parse().map(Self::Float)).ok()?
+			}
+			_ => return None,
+		})
+	}
+}
+
+/// Whether [`convert`] would convert `element` to `target`, without converting it
+#[must_use]
+pub fn can_convert(element: &NbtElement, target: ConversionTarget) -> bool {
+	let Some(source) = ConversionTarget::of(element) else { return false };
+	// lists only go to and from arrays
+	if source == ConversionTarget::List || target == ConversionTarget::List {
+		return source.is_array() || target.is_array()
+	}
+	if source == target || source.is_array() != target.is_array() {
+		return false
+	}
+	match (source, target) {
+		(_, ConversionTarget::String) | (ConversionTarget::ByteArray | ConversionTarget::IntArray | ConversionTarget::LongArray, _) => true,
+		_ => Number::of(element).is_some(),
+	}
+}
+
+/// `element` as `target`, keeping as much of its value as the new type can hold
+///
+/// Numbers are clamped to the range of the new type, strings are parsed, and arrays have each of their values converted.
+/// Lists become arrays only when they hold nothing but that array's type, in which case their values are kept as they are
+pub fn convert(element: &NbtElement, target: ConversionTarget) -> Result<NbtElement, ConversionError> {
+	if !can_convert(element, target) {
+		return Err(ConversionError::Unsupported { from: element.display_name(), to: target.name() })
+	}
+	if let Some(list) = element.as_list() {
+		return list_to_array(list, target)
+	}
+	if target == ConversionTarget::List {
+		let values = element.values().ok_or(ConversionError::Unsupported { from: element.display_name(), to: target.name() })?;
+		let mut list = NbtList::new(values.cloned().collect());
+		// the parent's height is updated from it
+		list.recache();
+		return Ok(NbtElement::List(list))
+	}
+	if target.is_array() {
+		let values = element.values().ok_or(ConversionError::Unsupported { from: element.display_name(), to: target.name() })?;
+		let element_target = match target {
+			ConversionTarget::ByteArray => ConversionTarget::Byte,
+			ConversionTarget::IntArray => ConversionTarget::Int,
+			_ => ConversionTarget::Long,
+		};
+		let values = values
+			.map(|value| element_target.convert_number(Number::of(value)?))
+			.collect::<Option<Vec<_>>>()
+			.ok_or(ConversionError::Unsupported { from: element.display_name(), to: target.name() })?;
+		return Ok(array(values, target))
+	}
+	if target == ConversionTarget::String {
+		return Ok(NbtElement::String(NbtString::new(element.value().0.as_ref().into())))
+	}
+	Number::of(element)
+		.and_then(|number| target.convert_number(number))
+		.ok_or(ConversionError::Unsupported { from: element.display_name(), to: target.name() })
+}
+
+fn list_to_array(list: &NbtList, target: ConversionTarget) -> Result<NbtElement, ConversionError> {
+	let Some(child_id) = target.array_child_id() else { return Err(ConversionError::Unsupported { from: ConversionTarget::List.name(), to: target.name() }) };
+	if !list.is_empty() {
+		if list.is_heterogeneous() {
+			return Err(ConversionError::Heterogeneous { to: target.name() })
+		}
+		if list.child_id() != child_id {
+			return Err(ConversionError::WrongElementType {
+				expected: id_to_string_name(child_id, 2),
+				found: id_to_string_name(list.child_id(), 2),
+				to: target.name(),
+			})
+		}
+	}
+	// already of the type the array holds, so nothing needs converting
+	Ok(array(list.elements.to_vec(), target))
+}
+
+fn array(values: Vec<NbtElement>, target: ConversionTarget) -> NbtElement {
+	match target {
+		ConversionTarget::ByteArray => NbtElement::ByteArray(NbtByteArray::new(values)),
+		ConversionTarget::IntArray => NbtElement::IntArray(NbtIntArray::new(values)),
+		_ => NbtElement::LongArray(NbtLongArray::new(values)),
+	}
+}
+
+#[derive(Error, Debug)]
+pub enum ConversionError {
+	#[error("Could not convert {from} to {to}")]
+	Unsupported { from: &'static str, to: &'static str },
+	#[error("Only lists holding a single type can be converted to {to}, this one holds several")]
+	Heterogeneous { to: &'static str },
+	#[error("Only lists of {expected} can be converted to {to}, this one holds {found}")]
+	WrongElementType { expected: &'static str, found: &'static str, to: &'static str },
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn converted(snbt: &str, target: ConversionTarget) -> Option<String> {
+		let (_, element) = NbtElement::from_str(snbt).expect("Valid SNBT");
+		convert(&element, target).ok().map(|element| element.to_string())
+	}
+
+	#[test]
+	fn test_convert() {
+		assert_eq!(converted("300", ConversionTarget::Byte).as_deref(), Some("127b"));
+		assert_eq!(converted("2.75d", ConversionTarget::Int).as_deref(), Some("2"));
+		assert_eq!(converted("\" 42 \"", ConversionTarget::Short).as_deref(), Some("42s"));
+		assert_eq!(converted("\"forty-two\"", ConversionTarget::Short), None);
+		assert_eq!(converted("5L", ConversionTarget::String).as_deref(), Some("\"5\""));
+		assert_eq!(converted("[I;1,-200,3]", ConversionTarget::ByteArray).as_deref(), Some("[B;1b,-128b,3b]"));
+		assert_eq!(converted("[I;1,2]", ConversionTarget::Int), None);
+		assert_eq!(converted("1b", ConversionTarget::Byte), None);
+	}
+
+	#[test]
+	fn test_convert_list() {
+		assert_eq!(converted("[1,-2,3]", ConversionTarget::IntArray).as_deref(), Some("[I;1,-2,3]"));
+		assert_eq!(converted("[L;4L,5L]", ConversionTarget::List).as_deref(), Some("[4L,5L]"));
+		assert_eq!(converted("[]", ConversionTarget::ByteArray).as_deref(), Some("[B;]"));
+		assert_eq!(converted("[1,-2,3]", ConversionTarget::Int), None);
+
+		let (_, list) = NbtElement::from_str("[1b,2b]").expect("Valid SNBT");
+		assert!(matches!(convert(&list, ConversionTarget::LongArray), Err(ConversionError::WrongElementType { .. })));
+		let (_, list) = NbtElement::from_str("[1b,\"a\"]").expect("Valid SNBT");
+		assert!(matches!(convert(&list, ConversionTarget::ByteArray), Err(ConversionError::Heterogeneous { .. })));
+	}
+}
@@ -0,0 +1,130 @@
+use std::borrow::Cow;
+
+use crate::elements::{ComplexNbtElementVariant, compound::NbtCompound, element::NbtElement};
+
+/// The `DataVersion` of each release, oldest first, to be extended as new ones come out
+///
+/// Snapshots and pre-releases fall between the release before them and theirs
+const RELEASES: &[(i32, &str)] = &[
+	(169, "1.9"),
+	(175, "1.9.1"),
+	(176, "1.9.2"),
+	(183, "1.9.3"),
+	(184, "1.9.4"),
+	(510, "1.10"),
+	(511, "1.10.1"),
+	(512, "1.10.2"),
+	(819, "1.11"),
+	(921, "1.11.1"),
+	(922, "1.11.2"),
+	(1139, "1.12"),
+	(1241, "1.12.1"),
+	(1343, "1.12.2"),
+	(1519, "1.13"),
+	(1628, "1.13.1"),
+	(1631, "1.13.2"),
+	(1952, "1.14"),
+	(1957, "1.14.1"),
+	(1963, "1.14.2"),
+	(1968, "1.14.3"),
+	(1976, "1.14.4"),
+	(2225, "1.15"),
+	(2227, "1.15.1"),
+	(2230, "1.15.2"),
+	(2566, "1.16"),
+	(2567, "1.16.1"),
+	(2578, "1.16.2"),
+	(2580, "1.16.3"),
+	(2584, "1.16.4"),
+	(2586, "1.16.5"),
+	(2724, "1.17"),
+	(2730, "1.17.1"),
+	(2860, "1.18"),
+	(2865, "1.18.1"),
+	(2975, "1.18.2"),
+	(3105, "1.19"),
+	(3117, "1.19.1"),
+	(3120, "1.19.2"),
+	(3218, "1.19.3"),
+	(3337, "1.19.4"),
+	(3463, "1.20"),
+	(3465, "1.20.1"),
+	(3578, "1.20.2"),
+	(3698, "1.20.3"),
+	(3700, "1.20.4"),
+	(3837, "1.20.5"),
+	(3839, "1.20.6"),
+	(3953, "1.21"),
+	(3955, "1.21.1"),
+	(4080, "1.21.2"),
+	(4082, "1.21.3"),
+	(4189, "1.21.4"),
+	(4325, "1.21.5"),
+	(4435, "1.21.6"),
+	(4438, "1.21.7"),
+	(4440, "1.21.8"),
+];
+
+/// The Minecraft version `data_version` is from, as a release, a snapshot of the release after it, or unknown past the newest release this build knows of
+#[must_use]
+pub fn version_name(data_version: i32) -> Cow<'static, str> {
+	match RELEASES.binary_search_by_key(&data_version, |&(data_version, _)| data_version) {
+		Ok(idx) => Cow::Borrowed(RELEASES[idx].1),
+		Err(idx) => match RELEASES.get(idx) {
+			// before 1.9 there was no `DataVersion`
+			Some(_) if idx == 0 => Cow::Borrowed("unknown"),
+			Some(&(_, next)) => Cow::Owned(format!("{next} snapshot")),
+			None => Cow::Borrowed("unknown (newer than this build)"),
+		},
+	}
+}
+
+/// The `DataVersion` of a file, at its root or within `Data` as in a `level.dat`
+#[must_use]
+pub fn root_data_version(root: &NbtElement) -> Option<i32> {
+	fn get<'a>(compound: &'a NbtCompound, key: &str) -> Option<&'a NbtElement> { compound.map.idx_of(key).and_then(|idx| compound.get(idx)).map(|entry| &entry.value) }
+
+	let compound = root.as_compound()?;
+	get(compound, "DataVersion").or_else(|| get(compound, "Data").and_then(NbtElement::as_compound).and_then(|data| get(data, "DataVersion"))).and_then(NbtElement::as_int).map(|int| int.value)
+}
+
+/// A primitive value which can be a `DataVersion`, which only ints can
+pub trait AsDataVersion: Copy {
+	#[must_use]
+	fn as_data_version(self) -> Option<i32> { None }
+}
+
+impl AsDataVersion for i8 {}
+
+impl AsDataVersion for i16 {}
+
+impl AsDataVersion for i32 {
+	fn as_data_version(self) -> Option<i32> { Some(self) }
+}
+
+impl AsDataVersion for i64 {}
+
+impl AsDataVersion for f32 {}
+
+impl AsDataVersion for f64 {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_version_name() {
+		assert!(RELEASES.is_sorted_by_key(|&(data_version, _)| data_version));
+		assert_eq!(version_name(3955), "1.21.1");
+		assert_eq!(version_name(169), "1.9");
+		assert_eq!(version_name(3954), "1.21.1 snapshot");
+		assert_eq!(version_name(100), "unknown");
+		assert_eq!(version_name(i32::MAX), "unknown (newer than this build)");
+
+		let (_, level) = NbtElement::from_str("{Data:{DataVersion:3465}}").expect("Valid SNBT");
+		assert_eq!(root_data_version(&level), Some(3465));
+		let (_, chunk) = NbtElement::from_str("{DataVersion:3953,xPos:0}").expect("Valid SNBT");
+		assert_eq!(root_data_version(&chunk), Some(3953));
+		assert_eq!(root_data_version(&NbtElement::from_str("[1]").expect("Valid SNBT").1), None);
+	}
+}
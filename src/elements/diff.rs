@@ -0,0 +1,262 @@
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::{
+	elements::{
+		chunk::NbtChunk,
+		compound::CompoundEntry,
+		element::{NbtElement, NbtPattern},
+	},
+	render::assets::{ADDED_STRIPE_UV, BOOKMARK_UV, CHANGED_STRIPE_UV, HIDDEN_BOOKMARK_UV, INVALID_STRIPE_UV},
+	util::Vec2u,
+	workbench::marked_line::{MarkedLine, MarkedLines},
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DifferenceKind {
+	/// Only in the second tree
+	Added,
+	/// Only in the first tree, put back into the merged one to be seen
+	Removed,
+	/// In both, with another value or type
+	Changed,
+}
+
+impl DifferenceKind {
+	/// Of the stripe its line is tinted with, which is uniform in at least the 8x8 from there
+	#[must_use]
+	pub const fn uv(self) -> Vec2u {
+		match self {
+			Self::Added => ADDED_STRIPE_UV,
+			Self::Removed => INVALID_STRIPE_UV,
+			Self::Changed => CHANGED_STRIPE_UV,
+		}
+	}
+}
+
+/// An element of the tree merged by [`compare`] which isn't the same in both trees
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+	pub kind: DifferenceKind,
+	pub true_line_number: usize,
+	/// What the value was, or how many values of an array differ
+	pub summary: Option<String>,
+}
+
+impl Difference {
+	/// Of the element itself, before [`Difference::offset`] puts it under its parent
+	const fn of_element(kind: DifferenceKind, summary: Option<String>) -> Self { Self { kind, true_line_number: 0, summary } }
+
+	const fn offset(mut self, offset: usize) -> Self {
+		self.true_line_number += offset;
+		self
+	}
+}
+
+/// `after` with everything only in `before` put back where it was, and what differs between them in the order of their lines
+///
+/// Compounds are compared by key and everything else by index. Arrays aren't compared value by value, they're only summarized by how many values differ.
+/// Chunks have to be decoded first for their entries to be compared, see [`NbtElement::decode_chunks`]
+#[must_use]
+pub fn compare(before: &NbtElement, after: &NbtElement) -> (NbtElement, Vec<Difference>) {
+	let (root, differences) = merge(before, after);
+	// the root is on the first line
+	(root, differences.into_iter().map(|difference| difference.offset(1)).collect())
+}
+
+/// The lines of `differences` as bookmarks, so they're gone through and shown on the scrollbar like any others
+#[must_use]
+pub fn bookmarks(root: &NbtElement, differences: &[Difference]) -> MarkedLines {
+	let mut lines = differences.iter().map(|difference| difference.true_line_number).peekable();
+	let mut bookmarks = Vec::with_capacity(differences.len());
+	let mut queue = vec![(root, true)];
+	let mut true_line_number = 1;
+	let mut line_number = 0;
+	while let Some((element, parent_open)) = queue.pop()
+		&& let Some(&next) = lines.peek()
+	{
+		// nothing within differs, so there's no need to go through it
+		if next >= true_line_number + element.true_height() {
+			true_line_number += element.true_height();
+			if parent_open {
+				line_number += element.height();
+			}
+			continue
+		}
+
+		if next == true_line_number {
+			lines.next();
+			bookmarks.push(MarkedLine::with_uv(true_line_number, line_number, if parent_open { BOOKMARK_UV } else { HIDDEN_BOOKMARK_UV }));
+		}
+		match element.children() {
+			Some(Ok(iter)) => queue.extend(iter.rev().map(|child| (child, element.is_open()))),
+			Some(Err(iter)) => queue.extend(iter.rev().map(|CompoundEntry { value: child, .. }| (child, element.is_open()))),
+			None => {}
+		}
+		true_line_number += 1;
+		if parent_open {
+			line_number += 1;
+		}
+	}
+	MarkedLines::from(bookmarks)
+}
+
+fn merge(before: &NbtElement, after: &NbtElement) -> (NbtElement, Vec<Difference>) {
+	use NbtPattern as Nbt;
+
+	let changed = |summary: String| (after.clone(), vec![Difference::of_element(DifferenceKind::Changed, Some(summary))]);
+
+	if core::mem::discriminant(&before.as_pattern()) != core::mem::discriminant(&after.as_pattern()) {
+		return changed(format!("Was a {}", before.display_name()))
+	}
+	match after.as_pattern() {
+		Nbt::ByteArray(_) | Nbt::IntArray(_) | Nbt::LongArray(_) => {
+			let (Some(Ok(before_values)), Some(Ok(after_values))) = (before.children(), after.children()) else { return (after.clone(), Vec::new()) };
+			let (before_len, after_len) = (before_values.len(), after_values.len());
+			let differing = before_values.zip(after_values).filter(|(before, after)| before != after).count() + before_len.abs_diff(after_len);
+			if differing == 0 {
+				return (after.clone(), Vec::new())
+			}
+			let values = match after.as_pattern() {
+				Nbt::ByteArray(_) => "bytes",
+				Nbt::IntArray(_) => "ints",
+				_ => "longs",
+			};
+			if before_len == after_len {
+				changed(format!("{differing} of {after_len} {values} differ"))
+			} else {
+				changed(format!("{differing} of {after_len} {values} differ, there were {before_len}"))
+			}
+		}
+		Nbt::Compound(_) | Nbt::Chunk(_) => merge_by_key(before, after),
+		Nbt::List(_) | Nbt::Region(_) => merge_by_index(before, after),
+		_ if before == after => (after.clone(), Vec::new()),
+		_ => changed(format!("Was {}", before.value().0)),
+	}
+}
+
+fn merge_by_key(before: &NbtElement, after: &NbtElement) -> (NbtElement, Vec<Difference>) {
+	let (Some(Err(before_entries)), Some(Err(after_entries))) = (before.children(), after.children()) else { return (after.clone(), Vec::new()) };
+	let (before_entries, after_entries) = (before_entries.as_slice(), after_entries.as_slice());
+	let before_indices = before_entries.iter().enumerate().map(|(idx, entry)| (entry.key.as_str(), idx)).collect::<FxHashMap<_, _>>();
+	let after_keys = after_entries.iter().map(|entry| entry.key.as_str()).collect::<FxHashSet<_>>();
+
+	// children are swapped for their merged selves in place, so only the heights need recaching after
+	let mut merged = after.clone();
+	let mut children = Vec::with_capacity(after_entries.len());
+	for (idx, CompoundEntry { key, value }) in after_entries.iter().enumerate() {
+		let Some(&before_idx) = before_indices.get(key.as_str()) else {
+			children.push(vec![Difference::of_element(DifferenceKind::Added, None)]);
+			continue
+		};
+		let (child, differences) = merge(&before_entries[before_idx].value, value);
+		if !differences.is_empty()
+			&& let Some((_, slot)) = merged.get_mut(idx)
+		{
+			*slot = child;
+		}
+		children.push(differences);
+	}
+	// put back as near to where they were as the entries since added and removed allow
+	for (before_idx, CompoundEntry { key, value }) in before_entries.iter().enumerate().filter(|(_, entry)| !after_keys.contains(entry.key.as_str())) {
+		let idx = before_idx.min(children.len());
+		// SAFETY: the heights are recached below
+		if unsafe { merged.insert(idx, (Some(key.clone()), value.clone())) }.is_ok() {
+			children.insert(idx, vec![Difference::of_element(DifferenceKind::Removed, None)]);
+		}
+	}
+	merged.recache();
+	let differences = under(&merged, children);
+	(merged, differences)
+}
+
+/// Regions always have a slot for every chunk, so chunks which aren't in one of them are the ones with an unloaded chunk in its place
+fn merge_by_index(before: &NbtElement, after: &NbtElement) -> (NbtElement, Vec<Difference>) {
+	let (Some(Ok(before_values)), Some(Ok(after_values))) = (before.children(), after.children()) else { return (after.clone(), Vec::new()) };
+	let (before_values, after_values) = (before_values.as_slice(), after_values.as_slice());
+	let is_absent = |element: Option<&NbtElement>| element.is_none_or(|element| element.as_chunk().is_some_and(NbtChunk::is_unloaded));
+
+	let mut merged = after.clone();
+	let mut children = Vec::with_capacity(after_values.len().max(before_values.len()));
+	for (idx, value) in after_values.iter().enumerate() {
+		let before_value = before_values.get(idx);
+		children.push(match (is_absent(before_value), is_absent(Some(value))) {
+			(true, true) => Vec::new(),
+			(true, false) => vec![Difference::of_element(DifferenceKind::Added, None)],
+			(false, true) => {
+				if let Some((_, slot)) = merged.get_mut(idx) {
+					*slot = before_values[idx].clone();
+				}
+				vec![Difference::of_element(DifferenceKind::Removed, None)]
+			}
+			(false, false) => {
+				let (child, differences) = merge(&before_values[idx], value);
+				if !differences.is_empty()
+					&& let Some((_, slot)) = merged.get_mut(idx)
+				{
+					*slot = child;
+				}
+				differences
+			}
+		});
+	}
+	for value in before_values.iter().skip(after_values.len()) {
+		// SAFETY: the heights are recached below
+		if unsafe { merged.insert(children.len(), (None, value.clone())) }.is_ok() {
+			children.push(vec![Difference::of_element(DifferenceKind::Removed, None)]);
+		}
+	}
+	merged.recache();
+	let differences = under(&merged, children);
+	(merged, differences)
+}
+
+/// The differences of each child of `parent`, moved from being relative to the child to being relative to `parent`
+fn under(parent: &NbtElement, children: Vec<Vec<Difference>>) -> Vec<Difference> {
+	let mut true_line_number = 1;
+	let mut differences = Vec::new();
+	for (idx, child_differences) in children.into_iter().enumerate() {
+		differences.extend(child_differences.into_iter().map(|difference| difference.offset(true_line_number)));
+		true_line_number += parent.get(idx).map_or(1, |(_, child)| child.true_height());
+	}
+	differences
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn kinds(before: &str, after: &str) -> (String, Vec<(DifferenceKind, usize)>) {
+		let (_, before) = NbtElement::from_str(before).expect("Valid SNBT");
+		let (_, after) = NbtElement::from_str(after).expect("Valid SNBT");
+		let (merged, differences) = compare(&before, &after);
+		(merged.to_string(), differences.into_iter().map(|difference| (difference.kind, difference.true_line_number)).collect())
+	}
+
+	#[test]
+	fn test_identical() {
+		assert_eq!(kinds("{a:1b,b:[1,2]}", "{a:1b,b:[1,2]}").1, []);
+	}
+
+	#[test]
+	fn test_compound() {
+		// both trees are sorted as they're parsed, so every entry is of the same type
+		let (merged, differences) = kinds("{a:1b,b:2b,c:3b}", "{a:1b,c:4b,e:5b}");
+		assert_eq!(merged, "{a:1b,b:2b,c:4b,e:5b}");
+		assert_eq!(differences, [(DifferenceKind::Removed, 3), (DifferenceKind::Changed, 4), (DifferenceKind::Added, 5)]);
+	}
+
+	#[test]
+	fn test_list() {
+		let (merged, differences) = kinds("[{a:1b},{a:2b},{a:3b}]", "[{a:1b},{a:4b}]");
+		assert_eq!(merged, "[{a:1b},{a:4b},{a:3b}]");
+		assert_eq!(differences, [(DifferenceKind::Changed, 5), (DifferenceKind::Removed, 6)]);
+	}
+
+	#[test]
+	fn test_array_summary() {
+		let (_, before) = NbtElement::from_str("{a:[I;1,2,3,4]}").expect("Valid SNBT");
+		let (_, after) = NbtElement::from_str("{a:[I;1,9,3,4,5]}").expect("Valid SNBT");
+		let (_, differences) = compare(&before, &after);
+		assert_eq!(differences, [Difference { kind: DifferenceKind::Changed, true_line_number: 2, summary: Some("2 of 5 ints differ, there were 4".to_owned()) }]);
+	}
+}
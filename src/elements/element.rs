@@ -2,15 +2,19 @@
 use std::{
 	borrow::Cow,
 	fmt::{Debug, Display, Error, Formatter},
+	io::Write,
 	mem::{ManuallyDrop, MaybeUninit},
 	ops::{Index, IndexMut},
+	path::Path,
 	slice::{Iter, IterMut},
+	sync::Arc,
 };
 
 use compact_str::CompactString;
 
 #[cfg(target_arch = "wasm32")] use crate::wasm::FakeScope as Scope;
 use crate::{
+	config,
 	elements::{
 		ComplexNbtElementVariant, Matches, NbtElementAndKey, NbtElementAndKeyRef, NbtElementAndKeyRefMut, NbtElementVariant, PrimitiveNbtElementVariant,
 		array::{NbtByteArray, NbtIntArray, NbtLongArray},
@@ -19,12 +23,16 @@ use crate::{
 		compound::{CompoundEntry, NbtCompound},
 		double::NbtDouble,
 		float::NbtFloat,
+		float_display::{self, FloatDisplay, InFloatDisplay},
 		int::NbtInt,
 		list::NbtList,
 		long::NbtLong,
+		boolean::AsBoolean,
+		number_base::{InBase, NumberBase},
 		region::NbtRegion,
 		result::NbtParseResult,
 		short::NbtShort,
+		snbt_error::{self, SnbtError},
 		string::NbtString,
 	},
 	render::{RenderContext, color::TextColor, vertex_buffer_builder::VertexBufferBuilder},
@@ -32,6 +40,7 @@ use crate::{
 		decoder::{BigEndianDecoder, Decoder},
 		encoder::UncheckedBufWriter,
 		formatter::{PrettyDisplay, PrettyFormatter},
+		json::{JsonNumberPolicy, JsonParseOptions},
 	},
 	tree::{
 		indices::{Indices, OwnedIndices},
@@ -39,9 +48,9 @@ use crate::{
 		traverse::{TraversalError, TraversalInformation, TraversalInformationMut},
 	},
 	util::{self, StrExt, Vec2u, width_ascii},
-	workbench::{element_action::ElementAction, marked_line::MarkedLines, DropResult},
+	workbench::{element_action::ElementAction, marked_line::MarkedLines, tab::loading::LoadProgress, DropResult},
 };
-use crate::serialization::decoder::LittleEndianDecoder;
+use crate::serialization::decoder::{BedrockHeader, LittleEndianDecoder};
 
 #[repr(C)]
 pub union NbtElement {
@@ -182,10 +191,12 @@ impl NbtElement {
 	// todo: add error type
 	pub fn from_str(mut s: &str) -> Result<NbtElementAndKey, usize> {
 		let total_len = s.len();
-		s = s.trim();
+		// only the start is trimmed, so what's left to parse is counted from the same end as the text
+		s = s.trim_start();
+		snbt_error::reset();
 
 		if s.is_empty() {
-			return Err(total_len - s.len())
+			return Err(total_len - snbt_error::expected(s.len(), "a value"))
 		}
 
 		let prefix = s.snbt_string_read().ok().and_then(|(prefix, s2)| {
@@ -196,16 +207,78 @@ impl NbtElement {
 		});
 		let (s, element) = Self::from_str0(s, Self::parse_int).map(|(s, x)| (s.trim_start(), x)).map_err(|x| total_len - x)?;
 		if !s.is_empty() {
-			return Err(total_len - s.len())
+			return Err(total_len - snbt_error::expected(s.len(), "the end of the text"))
 		}
 		Ok((prefix, element))
 	}
 
+	/// Parses SNBT, saying where it failed and what was expected there when it doesn't
+	///
+	/// # Errors
+	/// The line and column it failed at, along with what was expected there
+	pub fn from_snbt(s: &str) -> Result<NbtElementAndKey, SnbtError> { Self::from_str(s).map_err(|idx| SnbtError::of(s, idx)) }
+
+	/// Parses JSON, inferring the narrowest type for numbers and turning arrays of integers into int / long arrays
+	///
+	/// # Errors
+	/// The index the JSON failed to parse at
+	pub fn from_json(s: &str) -> Result<Self, usize> { crate::serialization::json::from_json(s, JsonParseOptions::default()) }
+
+	/// Parses SNBT, falling back to JSON for text copied out of other tools
+	///
+	/// # Errors
+	/// The index the SNBT failed to parse at, if it wasn't JSON either
+	pub fn from_str_or_json(s: &str) -> Result<NbtElementAndKey, usize> { Self::from_str(s).or_else(|idx| Self::from_json(s).map(|element| (None, element)).map_err(|_| idx)) }
+
+	/// Parses a run of SNBT entries, each a value or a `key: value` pair, separated by newlines, commas or both, or a single JSON value
+	///
+	/// Every entry before the first one which couldn't be parsed is returned along with where that one is in the run and the index it failed at
+	pub fn entries_from_str(s: &str) -> (Vec<NbtElementAndKey>, Option<(usize, usize)>) {
+		let is_separator = |c: char| c == ',' || c.is_whitespace();
+
+		if let Ok(kv) = Self::from_str_or_json(s) {
+			return (vec![kv], None)
+		}
+
+		let total_len = s.len();
+		let mut entries = vec![];
+		let mut rest = s.trim_start_matches(is_separator);
+		snbt_error::reset();
+		while !rest.is_empty() {
+			let mut s = rest;
+			let key = s.snbt_string_read().ok().and_then(|(key, s2)| {
+				s2.trim_start().strip_prefix(':').filter(|s| !s.is_empty()).map(|s2| {
+					s = s2.trim_start();
+					key
+				})
+			});
+			match Self::from_str0(s, Self::parse_int) {
+				// the next entry has to be separated from this one
+				Ok((s, element)) if s.is_empty() || s.starts_with(is_separator) => {
+					entries.push((key, element));
+					rest = s.trim_start_matches(is_separator);
+				}
+				Ok((s, _)) => {
+					let entry = entries.len();
+					return (entries, Some((entry, total_len - snbt_error::expected(s.len(), "',' or a new line"))))
+				}
+				Err(x) => {
+					let entry = entries.len();
+					return (entries, Some((entry, total_len - x)))
+				}
+			}
+		}
+		(entries, None)
+	}
+
 	pub(super) fn from_str0(s: &str, parse_ambiguous_integer: impl FnOnce(&str, bool, bool, u32, &str) -> Result<Self, usize>) -> Result<(&str, Self), usize> {
 		if let Some(s) = s.strip_prefix("false") {
 			return Ok((s, Self::Byte(NbtByte { value: 0 })))
 		} else if let Some(s) = s.strip_prefix("true") {
 			return Ok((s, Self::Byte(NbtByte { value: 1 })))
+		} else if let Some((s, value, suffix)) = float_display::strip_non_finite(s) {
+			#[allow(clippy::cast_possible_truncation)]
+			return Ok((s, if suffix == Some('f') { Self::Float(NbtFloat { value: value as f32 }) } else { Self::Double(NbtDouble { value }) }))
 		} else if let Ok((s, x)) = NbtByteArray::from_str0(s) {
 			return Ok((s, Self::ByteArray(x)))
 		} else if let Ok((s, x)) = NbtIntArray::from_str0(s) {
@@ -224,39 +297,10 @@ impl NbtElement {
 			return Ok(result)
 		}
 
-		Err(s.len())
+		Err(snbt_error::expected(s.len(), "a value"))
 	}
 
 	fn try_parse_num(mut s: &str, parse_ambiguous_integer: impl FnOnce(&str, bool, bool, u32, &str) -> Result<Self, usize>) -> Result<Option<(&str, Self)>, usize> {
-		if let Some(s2) = s.strip_prefix("NaN") {
-			s = s2.trim_start();
-			return if let Some(s2) = s.strip_prefix('f') {
-				Ok(Some((s2.trim_start(), Self::Float(NbtFloat { value: f32::NAN }))))
-			} else {
-				Ok(Some((s2.strip_prefix('d').unwrap_or(s2).trim_start(), Self::Double(NbtDouble { value: f64::NAN }))))
-			};
-		}
-
-		if let Some(s2) = s.strip_prefix("Infinity").or_else(|| s.strip_prefix("inf")) {
-			s = s2.trim_start();
-			return if let Some(s2) = s.strip_prefix('f') {
-				Ok(Some((s2.trim_start(), Self::Float(NbtFloat { value: f32::INFINITY }))))
-			} else {
-				Ok(Some((s2.strip_prefix('d').unwrap_or(s2).trim_start(), Self::Double(NbtDouble { value: f64::INFINITY }))))
-			};
-		}
-
-		if let Some(s2) = s.strip_prefix("-Infinity").or_else(|| s.strip_prefix("-inf")) {
-			s = s2.trim_start();
-			return if let Some(s2) = s.strip_prefix('f') {
-				Ok(Some((s2.trim_start(), Self::Float(NbtFloat { value: f32::NEG_INFINITY }))))
-			} else if let Some(s2) = s.strip_prefix('d') {
-				Ok(Some((s2.trim_start(), Self::Double(NbtDouble { value: f64::NEG_INFINITY }))))
-			} else {
-				Ok(Some((s2.trim_start(), Self::Double(NbtDouble { value: f64::NEG_INFINITY }))))
-			};
-		}
-
 		let (num_end_idx, suffix_len, unsigned, base, positive) = 'a: {
 			let mut d = s;
 			let mut num_end_idx = 0;
@@ -277,17 +321,19 @@ impl NbtElement {
 				let hex_part = d.bytes().take_while(|&b| b.is_ascii_hexdigit() || b == b'_').count();
 				num_end_idx += hex_part;
 				let unsigned = !d.starts_with('s');
-				if d.starts_with('u') || d.starts_with('s') {
+				if Self::has_signedness_prefix(d) {
 					suffix_len += 1;
 				}
 				(num_end_idx, suffix_len, unsigned, 16, positive)
-			} else if let Some(d2) = d.strip_prefix("0b") {
+			} else if let Some(d2) = d.strip_prefix("0b")
+				&& d2.starts_with(['0', '1'])
+			{
 				s = d2;
 				d = s;
 				let binary_part = d.bytes().take_while(|&b| b == b'0' || b == b'1' || b == b'_').count();
 				num_end_idx += binary_part;
 				let unsigned = !d.starts_with('s');
-				if d.starts_with('u') || d.starts_with('s') {
+				if Self::has_signedness_prefix(d) {
 					suffix_len += 1;
 				}
 				(num_end_idx, suffix_len, unsigned, 2, positive)
@@ -303,6 +349,7 @@ impl NbtElement {
 					num_end_idx += 1;
 					d = d2;
 					let frac_part = d.bytes().take_while(|&b| b.is_ascii_digit() || b == b'_').count();
+					d = &d[frac_part..];
 					num_end_idx += frac_part;
 					if let Some(s2) = d.strip_prefix('e').or(d.strip_prefix('E')) {
 						num_end_idx += 1;
@@ -317,8 +364,8 @@ impl NbtElement {
 					(num_end_idx, suffix_len, false, 10, positive)
 				} else {
 					// ints
-					let unsigned = d.starts_with('u');
-					if d.starts_with('u') || d.starts_with('s') {
+					let unsigned = Self::has_signedness_prefix(d) && d.starts_with('u');
+					if Self::has_signedness_prefix(d) {
 						suffix_len += 1;
 					}
 					(num_end_idx, suffix_len, unsigned, 10, positive)
@@ -358,40 +405,50 @@ impl NbtElement {
 		Ok(None)
 	}
 
+	/// `u` / `s` only mark signedness when followed by a type, a lone `s` is the short suffix
+	fn has_signedness_prefix(s: &str) -> bool { matches!(s.as_bytes(), [b'u' | b's', b'b' | b'B' | b's' | b'S' | b'i' | b'I' | b'l' | b'L', ..]) }
+
+	/// Signed numbers are parsed with their sign so the minimum value doesn't overflow
+	fn with_sign(num_str: &str, positive: bool) -> Cow<'_, str> { if positive { Cow::Borrowed(num_str) } else { Cow::Owned(format!("-{num_str}")) } }
+
 	pub(super) fn parse_byte(num_str: &str, unsigned: bool, positive: bool, base: u32, s: &str) -> Result<Self, usize> {
 		let value = if unsigned {
-			u8::from_str_radix(&num_str, base).map_err(|_| s.len())? as i8
+			let value = u8::from_str_radix(&num_str, base).map_err(|_| s.len())? as i8;
+			if positive { value } else { value.wrapping_neg() }
 		} else {
-			i8::from_str_radix(&num_str, base).map_err(|_| s.len())?
+			i8::from_str_radix(&Self::with_sign(num_str, positive), base).map_err(|_| s.len())?
 		};
-		Ok(Self::Byte(NbtByte { value: if positive { value } else { -value } }))
+		Ok(Self::Byte(NbtByte { value }))
 	}
 
 	pub(super) fn parse_short(num_str: &str, unsigned: bool, positive: bool, base: u32, s: &str) -> Result<Self, usize> {
 		let value = if unsigned {
-			u16::from_str_radix(&num_str, base).map_err(|_| s.len())? as i16
+			let value = u16::from_str_radix(&num_str, base).map_err(|_| s.len())? as i16;
+			if positive { value } else { value.wrapping_neg() }
 		} else {
-			i16::from_str_radix(&num_str, base).map_err(|_| s.len())?
+			i16::from_str_radix(&Self::with_sign(num_str, positive), base).map_err(|_| s.len())?
 		};
-		Ok(Self::Short(NbtShort { value: if positive { value } else { -value } }))
+		Ok(Self::Short(NbtShort { value }))
 	}
 
 	pub(super) fn parse_int(num_str: &str, unsigned: bool, positive: bool, base: u32, s: &str) -> Result<Self, usize> {
 		let value = if unsigned {
-			u32::from_str_radix(&num_str, base).map_err(|_| s.len())? as i32
+			let value = u32::from_str_radix(&num_str, base).map_err(|_| s.len())? as i32;
+			if positive { value } else { value.wrapping_neg() }
 		} else {
-			i32::from_str_radix(&num_str, base).map_err(|_| s.len())?
+			i32::from_str_radix(&Self::with_sign(num_str, positive), base).map_err(|_| s.len())?
 		};
-		Ok(Self::Int(NbtInt { value: if positive { value } else { -value } }))
+		Ok(Self::Int(NbtInt { value }))
 	}
 
 	pub(super) fn parse_long(num_str: &str, unsigned: bool, positive: bool, base: u32, s: &str) -> Result<Self, usize> {
 		let value = if unsigned {
-			u64::from_str_radix(&num_str, base).map_err(|_| s.len())? as i64
+			let value = u64::from_str_radix(&num_str, base).map_err(|_| s.len())? as i64;
+			if positive { value } else { value.wrapping_neg() }
 		} else {
-			i64::from_str_radix(&num_str, base).map_err(|_| s.len())?
+			i64::from_str_radix(&Self::with_sign(num_str, positive), base).map_err(|_| s.len())?
 		};
-		Ok(Self::Long(NbtLong { value: if positive { value } else { -value } }))
+		Ok(Self::Long(NbtLong { value }))
 	}
 
 	pub(super) fn array_try_into_byte(self) -> Option<Self> {
@@ -421,6 +478,7 @@ impl NbtElement {
 			Nbt::Byte(&NbtByte { value }) => Self::Long(NbtLong { value: value as i64 }),
 			Nbt::Short(&NbtShort { value }) => Self::Long(NbtLong { value: value as i64 }),
 			Nbt::Int(&NbtInt { value }) => Self::Long(NbtLong { value: value as i64 }),
+			Nbt::Long(_) => self,
 			_ => return None,
 		})
 	}
@@ -469,7 +527,10 @@ impl NbtElement {
 		})
 	}
 
-	pub fn from_be_file(bytes: &[u8]) -> NbtParseResult<Self> {
+	pub fn from_be_file(bytes: &[u8]) -> NbtParseResult<Self> { Self::from_be_file_named(bytes).map(|(nbt, _)| nbt) }
+
+	/// [`Self::from_be_file`], along with whether the root has the empty name files give it, which the network format since 1.20.2 leaves out
+	pub fn from_be_file_named(bytes: &[u8]) -> NbtParseResult<(Self, bool)> {
 		use super::result::*;
 
 		let mut decoder = BigEndianDecoder::new(bytes);
@@ -478,26 +539,50 @@ impl NbtElement {
 			return err("Big-endian NBT file didn't start with Compound")
 		}
 		// fix for >= 1.20.2 protocol since they removed the empty field
-		if is_ok(&decoder.assert_len(2)) && unsafe { decoder.u16() } != 0_u16.to_be() {
-			decoder.skip(-2_isize as usize);
-		}
+		let named = is_ok(&decoder.assert_len(2)) && {
+			let named = unsafe { decoder.u16() } == 0_u16.to_be();
+			if !named {
+				decoder.skip(-2_isize as usize);
+			}
+			named
+		};
 		let nbt = Self::Compound(NbtCompound::from_bytes(&mut decoder, ())?);
 		if is_ok(&decoder.assert_len(1)) {
 			return err("Format should take all the bytes");
 		}
-		ok(nbt)
+		ok((nbt, named))
 	}
 
-	pub fn from_be_mca(bytes: &[u8]) -> NbtParseResult<Self> {
+	/// Parses a region file, `path` is where the region was read from and is used to find its chunks stored in external `.mcc` files.
+	/// Legacy `.mcr` files share the same layout, so they're only told apart by their `.mcr` extension.
+	pub fn from_be_mca(bytes: &[u8], path: Option<&Path>) -> NbtParseResult<Self> { Self::from_be_mca_with_progress(bytes, path, None) }
+
+	/// [`Self::from_be_mca`], counting decoded chunks in `progress` and stopping early once it's cancelled
+	#[must_use]
+	pub fn from_be_mca_with_progress(bytes: &[u8], path: Option<&Path>, progress: Option<Arc<LoadProgress>>) -> NbtParseResult<Self> {
 		let mut decoder = BigEndianDecoder::new(bytes);
-		NbtRegion::from_bytes(&mut decoder, ()).map(Self::Region)
+		let mut region = NbtRegion::from_bytes(&mut decoder, progress)?;
+		region.set_mcregion(path.and_then(Path::extension).is_some_and(|extension| extension.eq_ignore_ascii_case("mcr")));
+		#[cfg(not(target_arch = "wasm32"))]
+		if let Some(path) = path {
+			region.load_external_chunks(path);
+		}
+		#[cfg(target_arch = "wasm32")]
+		let _ = path;
+		super::result::ok(Self::Region(region))
 	}
 
+	/// Little-endian NBT, with the [`BedrockHeader`] before it if it has one
+	#[must_use]
+	pub fn from_le_file(bytes: &[u8]) -> NbtParseResult<(Self, Option<BedrockHeader>)> { Self::from_le_decoder(LittleEndianDecoder::new(bytes)) }
+
+	/// [`Self::from_le_file`], taking what looks like a [`BedrockHeader`] as one even when the length it declares is [wildly off](BedrockHeader::is_wildly_off)
 	#[must_use]
-	pub fn from_le_file(bytes: &[u8]) -> NbtParseResult<(Self, bool)> {
+	pub fn from_le_file_with_header(bytes: &[u8]) -> NbtParseResult<(Self, Option<BedrockHeader>)> { Self::from_le_decoder(LittleEndianDecoder::with_header(bytes)) }
+
+	fn from_le_decoder(mut decoder: LittleEndianDecoder) -> NbtParseResult<(Self, Option<BedrockHeader>)> {
 		use super::result::*;
 
-		let mut decoder = LittleEndianDecoder::new(bytes);
 		decoder.assert_len(1)?;
 		let kind = unsafe { decoder.u8() };
 		let result = match kind {
@@ -505,13 +590,13 @@ impl NbtElement {
 				decoder.assert_len(2)?;
 				let skip = unsafe { decoder.u16() } as usize;
 				decoder.skip(skip);
-				ok((Self::Compound(NbtCompound::from_bytes(&mut decoder, ())?), decoder.has_header()))
+				ok((Self::Compound(NbtCompound::from_bytes(&mut decoder, ())?), decoder.header()))
 			}
 			NbtList::ID => {
 				decoder.assert_len(2)?;
 				let skip = unsafe { decoder.u16() } as usize;
 				decoder.skip(skip);
-				ok((Self::List(NbtList::from_bytes(&mut decoder, ())?), decoder.has_header()))
+				ok((Self::List(NbtList::from_bytes(&mut decoder, ())?), decoder.header()))
 			}
 			_ => err("Little-endian should start with either Compound or List"),
 		};
@@ -569,22 +654,73 @@ impl NbtElement {
 	#[must_use]
 	pub fn to_be_file(&self) -> Vec<u8> {
 		let mut writer = UncheckedBufWriter::new();
+		self.write_be_file(&mut writer);
+		writer.finish()
+	}
+
+	/// Streams [`Self::to_be_file`] into `sink` without holding the whole file in memory
+	///
+	/// # Errors
+	/// * If writing to `sink` fails
+	pub fn to_be_file_to(&self, sink: &mut dyn Write) -> std::io::Result<()> {
+		let mut writer = UncheckedBufWriter::with_sink(sink);
+		self.write_be_file(&mut writer);
+		writer.finish_sink()
+	}
+
+	/// [`Self::to_be_file`] in the network format since 1.20.2, without the root's name
+	#[must_use]
+	pub fn to_be_network_file(&self) -> Vec<u8> {
+		let mut writer = UncheckedBufWriter::new();
+		self.write_be_network_file(&mut writer);
+		writer.finish()
+	}
+
+	/// Streams [`Self::to_be_network_file`] into `sink` without holding the whole file in memory
+	///
+	/// # Errors
+	/// * If writing to `sink` fails
+	pub fn to_be_network_file_to(&self, sink: &mut dyn Write) -> std::io::Result<()> {
+		let mut writer = UncheckedBufWriter::with_sink(sink);
+		self.write_be_network_file(&mut writer);
+		writer.finish_sink()
+	}
+
+	fn write_be_file(&self, writer: &mut UncheckedBufWriter) {
 		if self.is_compound() {
 			writer.write(&[NbtCompound::ID, 0x00, 0x00]);
 		}
-		self.to_be_bytes(&mut writer);
-		writer.finish()
+		self.to_be_bytes(writer);
+	}
+
+	fn write_be_network_file(&self, writer: &mut UncheckedBufWriter) {
+		if self.is_compound() {
+			writer.write(&[NbtCompound::ID]);
+		}
+		self.to_be_bytes(writer);
+	}
+
+	/// Multi-line SNBT, with each level of nesting indented by `indent`
+	#[must_use]
+	pub fn to_pretty_string(&self, indent: &str) -> String {
+		let mut formatter = PrettyFormatter::with_indent(indent);
+		self.pretty_fmt(&mut formatter);
+		formatter.finish()
 	}
 
 	#[must_use]
-	pub fn to_le_file(&self, header: bool) -> Vec<u8> {
+	pub fn to_json(&self, policy: JsonNumberPolicy) -> String { crate::serialization::json::to_json(self, policy) }
+
+	/// Little-endian NBT, after a [`BedrockHeader`] of `header_version` and the length of the NBT written now if there is one
+	#[must_use]
+	pub fn to_le_file(&self, header_version: Option<u32>) -> Vec<u8> {
 		let mut writer = UncheckedBufWriter::new();
 		writer.write(&[self.id(), 0x00, 0x00]);
 		self.to_le_bytes(&mut writer);
 		let raw = writer.finish();
-		if header {
+		if let Some(version) = header_version {
 			let mut header = UncheckedBufWriter::new();
-			header.write(&[0x08, 0x00, 0x00, 0x00]);
+			header.write(&version.to_le_bytes());
 			header.write(&(raw.len() as u32).to_le_bytes());
 			header.write(&raw);
 			header.finish()
@@ -714,6 +850,14 @@ impl NbtElement {
 
 /// Mutable Indices-based operations
 impl NbtElement {
+	/// Decodes every chunk which hasn't been yet if this is a region, for when the whole tree is needed (like when searching it)
+	pub fn decode_chunks(&mut self, bookmarks: &mut MarkedLines) {
+		if let Some(region) = self.as_region_mut() {
+			// the root is on the first line
+			region.decode_chunks(1, bookmarks);
+		}
+	}
+
 	pub fn recache(&mut self) {
 		use NbtPatternMut as Nbt;
 
@@ -777,6 +921,17 @@ impl NbtElement {
 	#[must_use]
 	pub fn is_empty(&self) -> bool { self.len().is_some_and(|x| x == 0) }
 
+	/// Roughly how many bytes it takes up in memory, along with its children and their keys
+	#[must_use]
+	pub fn approximate_size(&self) -> usize {
+		let size = size_of::<Self>() + self.as_string().map_or(0, |string| string.str.as_str().len());
+		match self.children() {
+			Some(Ok(children)) => size + children.map(Self::approximate_size).sum::<usize>(),
+			Some(Err(entries)) => size + entries.map(|entry| entry.key.len() + entry.value.approximate_size()).sum::<usize>(),
+			None => size,
+		}
+	}
+
 	#[must_use]
 	pub fn height(&self) -> usize {
 		use NbtPattern as Nbt;
@@ -985,6 +1140,37 @@ impl NbtElement {
 		}
 	}
 
+	/// [`value`](Self::value) as it's shown, with integers in `base`, bytes of 0 or 1 as `false` or `true` if `booleans`, int arrays holding a UUID showing it, see [`NbtIntArray::uuid`], and strings too long for a row only showing a [preview](NbtString::preview)
+	#[must_use]
+	pub fn keyed_value(&self, key: Option<&str>, base: NumberBase, booleans: bool) -> (Cow<'_, str>, TextColor) {
+		use NbtPattern as Nbt;
+
+		if let Some(uuid) = self.as_int_array().and_then(|array| array.uuid(key)) {
+			return (Cow::Owned(uuid), TextColor::TreePrimitive)
+		}
+		match self.as_pattern() {
+			Nbt::Byte(x) if let Some(boolean) = x.value.as_boolean().filter(|_| booleans) => (Cow::Borrowed(if boolean { "true" } else { "false" }), TextColor::TreePrimitive),
+			Nbt::Byte(x) if base != NumberBase::Decimal => (Cow::Owned(x.value.to_base_string(base)), TextColor::TreePrimitive),
+			Nbt::Short(x) if base != NumberBase::Decimal => (Cow::Owned(x.value.to_base_string(base)), TextColor::TreePrimitive),
+			Nbt::Int(x) if base != NumberBase::Decimal => (Cow::Owned(x.value.to_base_string(base)), TextColor::TreePrimitive),
+			Nbt::Long(x) if base != NumberBase::Decimal => (Cow::Owned(x.value.to_base_string(base)), TextColor::TreePrimitive),
+			Nbt::Float(x) if config::get_float_display() != FloatDisplay::Shortest => (Cow::Owned(x.value.to_shown_string(base, config::get_float_display(), config::get_float_decimals())), TextColor::TreePrimitive),
+			Nbt::Double(x) if config::get_float_display() != FloatDisplay::Shortest => (Cow::Owned(x.value.to_shown_string(base, config::get_float_display(), config::get_float_decimals())), TextColor::TreePrimitive),
+			Nbt::String(x) => (x.preview(), TextColor::TreeString),
+			_ => self.value(),
+		}
+	}
+
+	#[must_use]
+	pub fn keyed_value_width(&self, key: Option<&str>, base: NumberBase, booleans: bool) -> usize {
+		let shown_float = (self.is_float() || self.is_double()) && config::get_float_display() != FloatDisplay::Shortest;
+		if base != NumberBase::Decimal || self.is_int_array() || (booleans && self.is_byte()) || shown_float {
+			self.keyed_value(key, base, booleans).0.width()
+		} else {
+			self.value_width()
+		}
+	}
+
 	#[must_use]
 	pub fn value_width(&self) -> usize {
 		use NbtPattern as Nbt;
@@ -998,7 +1184,7 @@ impl NbtElement {
 			Nbt::Float(x) => f32_width(x.value),
 			Nbt::Double(x) => f64_width(x.value),
 			Nbt::ByteArray(x) => usize_width(x.len()) + const { width_ascii(" ") } + id_to_string_name_width(NbtByte::ID, x.len()),
-			Nbt::String(x) => x.str.width(),
+			Nbt::String(x) => x.preview().width(),
 			Nbt::List(x) => usize_width(x.len()) + const { width_ascii(" ") } + id_to_string_name_width(x.child_id(), x.len()),
 			Nbt::Compound(x) => usize_width(x.len()) + const { width_ascii(" ") } + id_to_string_name_width(Self::NULL_ID, x.len()),
 			Nbt::IntArray(x) => usize_width(x.len()) + const { width_ascii(" ") } + id_to_string_name_width(NbtInt::ID, x.len()),
@@ -1096,6 +1282,8 @@ impl NbtElement {
 			Nbt::Byte(_) => &[
 				ElementAction::CopyRaw,
 				ElementAction::CopyFormatted,
+				ElementAction::CopyJson,
+				ElementAction::CopyPath,
 				#[cfg(not(target_arch = "wasm32"))]
 				ElementAction::OpenInTxt,
 				ElementAction::InvertBookmarks,
@@ -1103,6 +1291,8 @@ impl NbtElement {
 			Nbt::Short(_) => &[
 				ElementAction::CopyRaw,
 				ElementAction::CopyFormatted,
+				ElementAction::CopyJson,
+				ElementAction::CopyPath,
 				#[cfg(not(target_arch = "wasm32"))]
 				ElementAction::OpenInTxt,
 				ElementAction::InvertBookmarks,
@@ -1110,6 +1300,8 @@ impl NbtElement {
 			Nbt::Int(_) => &[
 				ElementAction::CopyRaw,
 				ElementAction::CopyFormatted,
+				ElementAction::CopyJson,
+				ElementAction::CopyPath,
 				#[cfg(not(target_arch = "wasm32"))]
 				ElementAction::OpenInTxt,
 				ElementAction::InvertBookmarks,
@@ -1117,6 +1309,8 @@ impl NbtElement {
 			Nbt::Long(_) => &[
 				ElementAction::CopyRaw,
 				ElementAction::CopyFormatted,
+				ElementAction::CopyJson,
+				ElementAction::CopyPath,
 				#[cfg(not(target_arch = "wasm32"))]
 				ElementAction::OpenInTxt,
 				ElementAction::InvertBookmarks,
@@ -1124,6 +1318,8 @@ impl NbtElement {
 			Nbt::Float(_) => &[
 				ElementAction::CopyRaw,
 				ElementAction::CopyFormatted,
+				ElementAction::CopyJson,
+				ElementAction::CopyPath,
 				#[cfg(not(target_arch = "wasm32"))]
 				ElementAction::OpenInTxt,
 				ElementAction::InvertBookmarks,
@@ -1131,6 +1327,8 @@ impl NbtElement {
 			Nbt::Double(_) => &[
 				ElementAction::CopyRaw,
 				ElementAction::CopyFormatted,
+				ElementAction::CopyJson,
+				ElementAction::CopyPath,
 				#[cfg(not(target_arch = "wasm32"))]
 				ElementAction::OpenInTxt,
 				ElementAction::InvertBookmarks,
@@ -1138,6 +1336,8 @@ impl NbtElement {
 			Nbt::ByteArray(_) => &[
 				ElementAction::CopyRaw,
 				ElementAction::CopyFormatted,
+				ElementAction::CopyJson,
+				ElementAction::CopyPath,
 				#[cfg(not(target_arch = "wasm32"))]
 				ElementAction::OpenInTxt,
 				#[cfg(not(target_arch = "wasm32"))]
@@ -1148,15 +1348,19 @@ impl NbtElement {
 			Nbt::String(_) => &[
 				ElementAction::CopyRaw,
 				ElementAction::CopyFormatted,
+				ElementAction::CopyJson,
+				ElementAction::CopyPath,
 				#[cfg(not(target_arch = "wasm32"))]
 				ElementAction::OpenInTxt,
 				ElementAction::InvertBookmarks,
 			],
 			#[cfg(not(target_arch = "wasm32"))]
 			Nbt::List(x) => {
-				const FULL: [ElementAction; 6] = [
+				const FULL: [ElementAction; 8] = [
 					ElementAction::CopyRaw,
 					ElementAction::CopyFormatted,
+					ElementAction::CopyJson,
+					ElementAction::CopyPath,
 					ElementAction::OpenInTxt,
 					ElementAction::InsertFromClipboard,
 					ElementAction::InvertBookmarks,
@@ -1166,10 +1370,14 @@ impl NbtElement {
 				if matches!(id, NbtByte::ID | NbtShort::ID | NbtInt::ID | NbtLong::ID) { &FULL } else { &FULL[..FULL.len() - 1] }
 			}
 			#[cfg(target_arch = "wasm32")]
-			Nbt::List(_) => &[ElementAction::CopyRaw, ElementAction::CopyFormatted, ElementAction::InsertFromClipboard, ElementAction::InvertBookmarks],
+			Nbt::List(_) => &[ElementAction::CopyRaw, ElementAction::CopyFormatted, ElementAction::CopyJson, ElementAction::CopyPath, ElementAction::InsertFromClipboard, ElementAction::InvertBookmarks],
 			Nbt::Compound(_) => &[
 				ElementAction::CopyRaw,
 				ElementAction::CopyFormatted,
+				ElementAction::CopyJson,
+				// the wheel only has room for 8, so [Ctrl + Alt + C] is the only way to copy the path of these natively
+				#[cfg(target_arch = "wasm32")]
+				ElementAction::CopyPath,
 				#[cfg(not(target_arch = "wasm32"))]
 				ElementAction::OpenInTxt,
 				ElementAction::SortCompoundByName,
@@ -1180,6 +1388,8 @@ impl NbtElement {
 			Nbt::IntArray(_) => &[
 				ElementAction::CopyRaw,
 				ElementAction::CopyFormatted,
+				ElementAction::CopyJson,
+				ElementAction::CopyPath,
 				#[cfg(not(target_arch = "wasm32"))]
 				ElementAction::OpenInTxt,
 				#[cfg(not(target_arch = "wasm32"))]
@@ -1190,6 +1400,8 @@ impl NbtElement {
 			Nbt::LongArray(_) => &[
 				ElementAction::CopyRaw,
 				ElementAction::CopyFormatted,
+				ElementAction::CopyJson,
+				ElementAction::CopyPath,
 				#[cfg(not(target_arch = "wasm32"))]
 				ElementAction::OpenInTxt,
 				#[cfg(not(target_arch = "wasm32"))]
@@ -1197,9 +1409,15 @@ impl NbtElement {
 				ElementAction::InsertFromClipboard,
 				ElementAction::InvertBookmarks,
 			],
+			// there's nothing in it to copy or sort
+			Nbt::Chunk(chunk) if chunk.is_corrupted() => &[ElementAction::CopyPath, ElementAction::DeleteCorruptedChunk, ElementAction::InvertBookmarks],
 			Nbt::Chunk(_) => &[
 				ElementAction::CopyRaw,
 				ElementAction::CopyFormatted,
+				ElementAction::CopyJson,
+				// the wheel only has room for 8, so [Ctrl + Alt + C] is the only way to copy the path of these natively
+				#[cfg(target_arch = "wasm32")]
+				ElementAction::CopyPath,
 				#[cfg(not(target_arch = "wasm32"))]
 				ElementAction::OpenInTxt,
 				ElementAction::SortCompoundByName,
@@ -1210,6 +1428,8 @@ impl NbtElement {
 			Nbt::Region(_) => &[
 				ElementAction::CopyRaw,
 				ElementAction::CopyFormatted,
+				ElementAction::CopyJson,
+				ElementAction::CopyPath,
 				#[cfg(not(target_arch = "wasm32"))]
 				ElementAction::OpenInTxt,
 				ElementAction::InvertBookmarks,
@@ -1315,6 +1535,22 @@ impl NbtElement {
 		})
 	}
 
+	/// Like [`Self::insert`], but an entry of a compound keeps its key even if another entry has it, for putting back what was taken out so duplicates read from a file aren't renamed
+	///
+	/// # Safety
+	/// * must be assured to update valid caches
+	pub unsafe fn reinsert(&mut self, idx: usize, kv: NbtElementAndKey) -> Result<Option<NbtElementAndKey>, NbtElementAndKey> {
+		use NbtPatternMut as Nbt;
+
+		let (Some(key), value) = kv else { return unsafe { self.insert(idx, kv) } };
+		match self.as_pattern_mut() {
+			Nbt::Compound(compound) if compound.can_insert(&value) => unsafe { compound.reinsert(idx, CompoundEntry::new(key, value)) },
+			Nbt::Chunk(chunk) if chunk.can_insert(&value) => unsafe { chunk.reinsert(idx, CompoundEntry::new(key, value)) },
+			_ => return unsafe { self.insert(idx, (Some(key), value)) },
+		}
+		Ok(None)
+	}
+
 	/// # Safety
 	/// - must be assured to update valid caches
 	pub unsafe fn replace_key_value(&mut self, idx: usize, kv: NbtElementAndKey) -> Result<Option<NbtElementAndKey>, NbtElementAndKey> {
@@ -1392,29 +1628,64 @@ impl NbtElement {
 		match self.as_pattern_mut() {
 			Nbt::Byte(byte) => {
 				let before = byte.value().into_owned();
-				if value.parse().map(|x| byte.value = x).is_ok() { Ok(before) } else { Err(value) }
+				if let Some(x) = AsBoolean::parse_shown(&value) {
+					byte.value = x;
+					Ok(before)
+				} else {
+					Err(value)
+				}
 			}
 			Nbt::Short(short) => {
 				let before = short.value().into_owned();
-				if value.parse().map(|x| short.value = x).is_ok() { Ok(before) } else { Err(value) }
+				if let Some(x) = InBase::parse_any_base(&value) {
+					short.value = x;
+					Ok(before)
+				} else {
+					Err(value)
+				}
 			}
 			Nbt::Int(int) => {
 				let before = int.value().into_owned();
-				if value.parse().map(|x| int.value = x).is_ok() { Ok(before) } else { Err(value) }
+				if let Some(x) = InBase::parse_any_base(&value) {
+					int.value = x;
+					Ok(before)
+				} else {
+					Err(value)
+				}
 			}
 			Nbt::Long(long) => {
 				let before = long.value().into_owned();
-				if value.parse().map(|x| long.value = x).is_ok() { Ok(before) } else { Err(value) }
+				if let Some(x) = InBase::parse_any_base(&value) {
+					long.value = x;
+					Ok(before)
+				} else {
+					Err(value)
+				}
 			}
+			// left as it was when the text is too, so a NaN keeps its payload and the file saves bit for bit the same
 			Nbt::Float(float) => {
 				let before = float.value().into_owned();
-				if value.parse().map(|x| float.value = x).is_ok() { Ok(before) } else { Err(value) }
+				if value == before || value.parse().map(|x| float.value = x).is_ok() { Ok(before) } else { Err(value) }
 			}
 			Nbt::Double(double) => {
 				let before = double.value().into_owned();
-				if value.parse().map(|x| double.value = x).is_ok() { Ok(before) } else { Err(value) }
+				if value == before || value.parse().map(|x| double.value = x).is_ok() { Ok(before) } else { Err(value) }
+			}
+			// only shown as a UUID under some keys, but any 4 ints can be written as one
+			Nbt::IntArray(array) if array.len() == 4 => {
+				let Some(ints) = util::parse_uuid(&value) else { return Err(value) };
+				let before = util::uuid_to_string(core::array::from_fn(|idx| array.get(idx).and_then(Self::as_int).map_or(0, |int| int.value)));
+				for (child, value) in array.children_mut().zip(ints) {
+					*child = Self::Int(NbtInt { value });
+				}
+				array.recache();
+				Ok(before)
+			}
+			Nbt::String(_) => {
+				// the string is as wide as the element itself, so writing it in place would clobber the id
+				let before = core::mem::replace(self, Self::String(NbtString::new(value.into())));
+				Ok(before.as_string().map_or_else(String::new, |string| string.str.as_str().to_owned()))
 			}
-			Nbt::String(string) => Ok(core::mem::replace(string, NbtString::new(value.into())).str.as_str().to_owned()),
 			_ => {
 				std::hint::cold_path();
 				return Err(value)
@@ -1422,6 +1693,26 @@ impl NbtElement {
 		}
 	}
 
+	/// Where `value` stops being something [`Self::set_value`] takes, for when it didn't
+	#[must_use]
+	pub fn value_error(&self, value: &str) -> SnbtError {
+		use NbtPattern as Nbt;
+
+		let expected = match self.as_pattern() {
+			Nbt::Byte(_) => "a byte or a boolean",
+			Nbt::Short(_) => "a short",
+			Nbt::Int(_) => "an int",
+			Nbt::Long(_) => "a long",
+			Nbt::Float(_) => "a float",
+			Nbt::Double(_) => "a double",
+			// a UUID's dashes aren't SNBT, so where that fails says nothing about it
+			Nbt::IntArray(_) => return SnbtError::at(value, 0, "a UUID"),
+			_ => "a value",
+		};
+		// failing at the start only says it isn't SNBT, which says less than what it should be
+		Self::from_snbt(value).err().filter(|e| e.offset > 0).unwrap_or_else(|| SnbtError::at(value, 0, expected))
+	}
+
 	#[must_use]
 	pub fn update_key(&mut self, idx: usize, key: CompactString) -> Option<Option<CompactString>> {
 		use NbtPatternMut as Nbt;
@@ -1433,6 +1724,18 @@ impl NbtElement {
 		})
 	}
 
+	/// Like [`Self::update_key`], but allowing a key another entry has, for putting back the key of a duplicate read from a file
+	#[must_use]
+	pub fn restore_key(&mut self, idx: usize, key: CompactString) -> Option<Option<CompactString>> {
+		use NbtPatternMut as Nbt;
+
+		Some(match self.as_pattern_mut() {
+			Nbt::Compound(compound) => compound.map.restore_key(idx, key),
+			Nbt::Chunk(chunk) => chunk.map.restore_key(idx, key),
+			_ => return None,
+		})
+	}
+
 	#[must_use]
 	pub fn get_mut(&mut self, idx: usize) -> Option<NbtElementAndKeyRefMut> {
 		use NbtPatternMut as Nbt;
@@ -1846,13 +2149,13 @@ macro_rules! type_conversions {
 			pub unsafe fn $as_unchecked_mut(&mut self) -> &mut $t { unsafe { &mut self.$field } }
 
 			#[must_use]
-			pub fn $into(self) -> Option<$t> { unsafe { self.$is().then_some(self.$into_unchecked()) } }
+			pub fn $into(self) -> Option<$t> { self.$is().then(|| unsafe { self.$into_unchecked() }) }
 
 			#[must_use]
-			pub fn $as_ref(&self) -> Option<&$t> { unsafe { self.$is().then_some(self.$as_unchecked()) } }
+			pub fn $as_ref(&self) -> Option<&$t> { self.$is().then(|| unsafe { self.$as_unchecked() }) }
 
 			#[must_use]
-			pub fn $as_mut(&mut self) -> Option<&mut $t> { unsafe { self.$is().then_some(self.$as_unchecked_mut()) } }
+			pub fn $as_mut(&mut self) -> Option<&mut $t> { if self.$is() { Some(unsafe { self.$as_unchecked_mut() }) } else { None } }
 
 			#[must_use]
 			pub fn $is(&self) -> bool { self.id() == <$t>::ID }
@@ -1889,3 +2192,43 @@ impl NbtElement {
 	#[must_use]
 	pub fn into_nonnull(self) -> Option<Self> { (!self.is_null()).then_some(self) }
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_entries_from_str() {
+		let snbt = |(entries, err): (Vec<NbtElementAndKey>, Option<(usize, usize)>)| (entries.into_iter().map(|(key, element)| format!("{}{element}", key.map_or_else(String::new, |key| format!("{key}:")))).collect::<Vec<_>>(), err);
+
+		assert_eq!(snbt(NbtElement::entries_from_str("foo: 1b")), (vec!["foo:1b".to_owned()], None));
+		assert_eq!(snbt(NbtElement::entries_from_str("foo: 1b\nbar: {a: 2s},\n3")), (vec!["foo:1b".to_owned(), "bar:{a:2s}".to_owned(), "3".to_owned()], None));
+		assert_eq!(snbt(NbtElement::entries_from_str("1b, 2b\n[3b")), (vec!["1b".to_owned(), "2b".to_owned()], Some((2, 7))));
+		assert_eq!(snbt(NbtElement::entries_from_str(r#"{"a": [1, 2]}"#)), (vec!["{a:[1,2]}".to_owned()], None));
+	}
+
+	#[test]
+	fn test_non_finite_round_trip() {
+		let (_, root) = NbtElement::from_str(r#"{a:NaNd,b:Infinityf,c:-infinity,d:nan,e:information,f:"NaN",g:1.0e309d,h:-1.0e39f,i:[inf,-INF]}"#).expect("Valid SNBT");
+		// in whatever order the config sorts them
+		let check = |root: &NbtElement| {
+			let snbt = root.to_string();
+			for entry in ["a:NaNd", "b:Infinityf", "c:-Infinityd", "d:NaNd", "e:information", r#"f:"NaN""#, "g:Infinityd", "h:-Infinityf", "i:[Infinityd,-Infinityd]"] {
+				assert!(snbt.contains(entry), "{entry} in {snbt}");
+			}
+		};
+		check(&root);
+		let (_, reparsed) = NbtElement::from_str(&root.to_string()).expect("Written SNBT is valid");
+		check(&reparsed);
+
+		let from_binary = NbtElement::from_be_file(&root.to_be_file()).expect("Valid NBT");
+		check(&from_binary);
+
+		let mut double = NbtElement::Double(NbtDouble { value: 0.0 });
+		assert!(double.set_value("Infinity".to_owned()).is_ok());
+		assert!(double.set_value("-INF".to_owned()).is_ok());
+		assert_eq!(double.value().0, "-Infinity");
+		assert!(double.set_value("nan".to_owned()).is_ok());
+		assert!(double.matches(&NbtElement::Double(NbtDouble { value: -f64::NAN })));
+	}
+}
@@ -0,0 +1,193 @@
+use std::fmt::{Formatter, Result as FmtResult};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	config,
+	elements::number_base::{InBase, NumberBase},
+	util::valid_unescaped_char,
+};
+
+/// How floats and doubles are written in the tree, the value editor always starts from the shortest string which parses back to the same bits
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum FloatDisplay {
+	/// The fewest digits which parse back to the same value
+	#[default]
+	Shortest,
+	/// Every significant digit the type holds, 9 for floats and 17 for doubles
+	Full,
+	/// Rounded to the configured places after the decimal point
+	Fixed,
+}
+
+impl FloatDisplay {
+	#[must_use]
+	pub const fn cycle(self) -> Self {
+		match self {
+			Self::Shortest => Self::Full,
+			Self::Full => Self::Fixed,
+			Self::Fixed => Self::Shortest,
+		}
+	}
+
+	#[must_use]
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Shortest => "shortest round-trip",
+			Self::Full => "full precision",
+			Self::Fixed => "fixed decimals",
+		}
+	}
+
+	#[must_use]
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"shortest" => Some(Self::Shortest),
+			"full" => Some(Self::Full),
+			"fixed" => Some(Self::Fixed),
+			_ => None,
+		}
+	}
+}
+
+/// How NaN and the infinities are written in SNBT, which has no numbers for them
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum NonFiniteSpelling {
+	/// `NaN`, `Infinity` and `-Infinity`, which are read back here but not by Minecraft
+	#[default]
+	Words,
+	/// Infinities as numbers too large to hold, like `1.0e309d`, which Minecraft reads back as infinities too. NaN is still `NaN`, since no number is read as one
+	Overflowing,
+}
+
+impl NonFiniteSpelling {
+	#[must_use]
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"words" => Some(Self::Words),
+			"overflowing" => Some(Self::Overflowing),
+			_ => None,
+		}
+	}
+}
+
+/// A primitive value as it's shown in the tree, where only floats and doubles follow the [`FloatDisplay`]
+pub trait InFloatDisplay: InBase + PartialEq {
+	#[must_use]
+	fn to_shown_string(self, base: NumberBase, _display: FloatDisplay, _decimals: usize) -> String { self.to_base_string(base) }
+
+	/// Written without its suffix, where NaN and the infinities follow the [`NonFiniteSpelling`]
+	fn write_snbt(self, f: &mut Formatter<'_>) -> FmtResult { write!(f, "{self}") }
+
+	/// The bits as they're stored, for floats and doubles where the decimal shown may not say exactly
+	#[must_use]
+	fn raw_bits(self) -> Option<String> { None }
+
+	/// Equal, except that every NaN is alike when searching
+	#[must_use]
+	fn matches_value(self, other: Self) -> bool { self == other }
+}
+
+impl InFloatDisplay for i8 {}
+
+impl InFloatDisplay for i16 {}
+
+impl InFloatDisplay for i32 {}
+
+impl InFloatDisplay for i64 {}
+
+macro_rules! float_display {
+	($($t:ty => $digits:literal, $hex_digits:literal, $overflowing:literal),*) => {
+		$(
+			impl InFloatDisplay for $t {
+				fn to_shown_string(self, _: NumberBase, display: FloatDisplay, decimals: usize) -> String {
+					match display {
+						_ if !self.is_finite() => non_finite_word(self.is_nan(), self.is_sign_positive()).to_owned(),
+						FloatDisplay::Shortest => self.to_string(),
+						FloatDisplay::Full => {
+							let scientific = format!("{:.*e}", $digits - 1, self);
+							let exponent = scientific.split_once('e').and_then(|(_, exponent)| exponent.parse::<isize>().ok()).unwrap_or(0);
+							// past these it's mostly zeroes, so it's clearer written with an exponent
+							if (-5..16).contains(&exponent) {
+								format!("{:.*}", ($digits - 1 - exponent).max(0).cast_unsigned(), self)
+							} else {
+								scientific
+							}
+						}
+						FloatDisplay::Fixed => format!("{self:.decimals$}"),
+					}
+				}
+
+				fn write_snbt(self, f: &mut Formatter<'_>) -> FmtResult {
+					if self.is_finite() {
+						write!(f, "{self}")
+					} else if self.is_infinite() && config::get_non_finite_spelling() == NonFiniteSpelling::Overflowing {
+						write!(f, "{}{}", if self.is_sign_positive() { "" } else { "-" }, $overflowing)
+					} else {
+						f.write_str(non_finite_word(self.is_nan(), self.is_sign_positive()))
+					}
+				}
+
+				fn raw_bits(self) -> Option<String> { Some(format!("0x{:0width$x}", self.to_bits(), width = $hex_digits)) }
+
+				#[allow(clippy::float_cmp)] // exactly equal is what's meant
+				fn matches_value(self, other: Self) -> bool { self == other || (self.is_nan() && other.is_nan()) }
+			}
+		)*
+	};
+}
+
+float_display!(f32 => 9, 8, "1.0e39", f64 => 17, 16, "1.0e309");
+
+const fn non_finite_word(nan: bool, positive: bool) -> &'static str {
+	match (nan, positive) {
+		(true, _) => "NaN",
+		(false, true) => "Infinity",
+		(false, false) => "-Infinity",
+	}
+}
+
+/// Strips `NaN`, `Infinity` or `inf` in any case and with either sign from the start of `s`, and the `f` or `d` after it if there is one
+///
+/// Nothing is stripped when it's only the start of a longer word like `information`, which is still an unquoted string
+#[must_use]
+pub fn strip_non_finite(s: &str) -> Option<(&str, f64, Option<char>)> {
+	let (negative, unsigned) = match s.as_bytes().first() {
+		Some(b'-') => (true, &s[1..]),
+		Some(b'+') => (false, &s[1..]),
+		_ => (false, s),
+	};
+	let (value, rest) = [("infinity", f64::INFINITY), ("inf", f64::INFINITY), ("nan", f64::NAN)]
+		.into_iter()
+		.find_map(|(word, value)| unsigned.get(..word.len()).filter(|start| start.eq_ignore_ascii_case(word)).map(|_| (value, &unsigned[word.len()..])))?;
+	let (suffix, rest) = match rest.as_bytes().first() {
+		Some(b'f' | b'F') => (Some('f'), &rest[1..]),
+		Some(b'd' | b'D') => (Some('d'), &rest[1..]),
+		_ => (None, rest),
+	};
+	if rest.bytes().next().is_some_and(valid_unescaped_char) {
+		return None
+	}
+	Some((rest, if negative { -value } else { value }, suffix))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_float_display() {
+		let awkward = 0.1_f64 + 0.2;
+		assert_eq!(awkward.to_shown_string(NumberBase::Decimal, FloatDisplay::Shortest, 3), "0.30000000000000004");
+		assert_eq!(0.1_f64.to_shown_string(NumberBase::Decimal, FloatDisplay::Full, 3), "0.10000000000000001");
+		assert_eq!(0.1_f32.to_shown_string(NumberBase::Decimal, FloatDisplay::Full, 3), "0.100000001");
+		assert_eq!(1e300_f64.to_shown_string(NumberBase::Decimal, FloatDisplay::Full, 3), "1.0000000000000001e300");
+		assert_eq!(awkward.to_shown_string(NumberBase::Decimal, FloatDisplay::Fixed, 3), "0.300");
+		assert_eq!(f64::NAN.to_shown_string(NumberBase::Decimal, FloatDisplay::Fixed, 3), "NaN");
+		assert_eq!(5_i8.to_shown_string(NumberBase::Hex, FloatDisplay::Full, 3), "0x5");
+
+		assert_eq!(1.0_f32.raw_bits().as_deref(), Some("0x3f800000"));
+		assert_eq!(awkward.raw_bits().as_deref(), Some("0x3fd3333333333334"));
+		assert_eq!(1_i32.raw_bits(), None);
+	}
+}
@@ -9,7 +9,7 @@ use std::{
 #[cfg(target_arch = "wasm32")]
 use crate::wasm::{FakeScope as Scope, fake_scope as scope};
 use crate::{
-	elements::{ComplexNbtElementVariant, Matches, NbtElement, NbtElementVariant, compound::NbtCompound, element::id_to_string_name, result::NbtParseResult},
+	elements::{ComplexNbtElementVariant, Matches, NbtElement, NbtElementVariant, compound::NbtCompound, element::id_to_string_name, number_base::NumberBase, result::NbtParseResult, snbt_error},
 	render::{
 		RenderContext,
 		assets::{CONNECTION_UV, JUST_OVERLAPPING_BASE_TEXT_Z, LIST_GHOST_UV, LIST_UV},
@@ -98,6 +98,9 @@ impl PrettyDisplay for NbtList {
 		} else {
 			let len = self.len();
 			let heterogeneous = self.is_heterogeneous();
+			if !heterogeneous && f.try_write_inline("", self.children()) {
+				return
+			}
 			f.write_str("[\n");
 			f.increase();
 			for (idx, element) in self.children().enumerate() {
@@ -154,12 +157,15 @@ impl NbtElementVariant for NbtList {
 			let (s2, mut element) = NbtElement::from_str0(s, NbtElement::parse_int)?;
 			// SAFETY: no caches have been made
 			element = unsafe { element.try_compound_singleton_into_inner().unwrap_or_else(|element| element) };
-			unsafe { list.insert(list.len(), element) }.map_err(|_| s.len())?;
+			unsafe { list.insert(list.len(), element) }.map_err(|_| snbt_error::expected(s.len(), "a value of the same type as the rest of the list"))?;
 			s = s2.trim_start();
 			if let Some(s2) = s.strip_prefix(',') {
 				s = s2.trim_start();
 			} else if s.starts_with(']') {
 				break;
+			} else {
+				// a missing comma is let through, but if what follows doesn't parse, it's what was most likely meant
+				let _ = snbt_error::expected(s.len(), "',' or ']'");
 			}
 		}
 		let s = s.strip_prefix(']').ok_or(s.len())?;
@@ -404,7 +410,7 @@ impl ComplexNbtElementVariant for NbtList {
 		for child in self.children() {
 			height += child.height() as u32;
 			true_height += child.true_height() as u32;
-			end_x = usize::max(end_x, NbtElement::DEPTH_INCREMENT_WIDTH + SelectedText::PREFIXING_SPACE_WIDTH + child.value_width());
+			end_x = usize::max(end_x, NbtElement::DEPTH_INCREMENT_WIDTH + SelectedText::PREFIXING_SPACE_WIDTH + child.keyed_value_width(None, NumberBase::Decimal, false));
 			end_x = usize::max(end_x, NbtElement::DEPTH_INCREMENT_WIDTH + child.end_x());
 		}
 
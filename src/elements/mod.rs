@@ -1,11 +1,22 @@
 pub mod array;
+pub mod boolean;
 pub mod chunk;
 pub mod compound;
+pub mod convert;
+pub mod data_version;
+pub mod diff;
 pub mod element;
+pub mod float_display;
 pub mod list;
+pub mod number_base;
 pub mod primitive;
 pub mod region;
+pub mod region_heatmap;
+pub mod region_integrity;
+pub mod snbt_error;
 pub mod string;
+pub mod validate;
+pub mod values_text;
 
 #[cfg(not(target_arch = "wasm32"))] use std::thread::Scope;
 use std::{borrow::Cow, fmt::Display, slice};
@@ -111,6 +122,28 @@ pub mod result {
 		#[cfg(not(debug_assertions))]
 		return result.ok();
 	}
+
+	/// Logs why the parse failed (the reason is only known on debug) so it can be skipped over
+	#[must_use]
+	pub fn log_err<T>(result: NbtParseResult<T>, context: impl std::fmt::Display) -> Option<T> {
+		#[cfg(debug_assertions)]
+		return result.inspect_err(|e| crate::error!("{context}: {e}")).ok();
+		#[cfg(not(debug_assertions))]
+		{
+			if result.is_none() {
+				crate::error!("{context}");
+			}
+			return result;
+		}
+	}
+
+	/// Why the parse failed as a message to show, which is only more than `context` on debug
+	pub fn with_reason<T>(result: NbtParseResult<T>, context: &'static str) -> Result<T, String> {
+		#[cfg(debug_assertions)]
+		return result.map_err(|e| format!("{context}: {e}"));
+		#[cfg(not(debug_assertions))]
+		return result.ok_or_else(|| context.to_owned());
+	}
 }
 
 pub trait Matches {
@@ -0,0 +1,114 @@
+use std::fmt::Display;
+
+/// How integers are written in the tree and the value editor, SNBT is always written in decimal
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub enum NumberBase {
+	#[default]
+	Decimal,
+	Hex,
+	Binary,
+}
+
+impl NumberBase {
+	#[must_use]
+	pub const fn cycle(self) -> Self {
+		match self {
+			Self::Decimal => Self::Hex,
+			Self::Hex => Self::Binary,
+			Self::Binary => Self::Decimal,
+		}
+	}
+
+	#[must_use]
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Decimal => "decimal",
+			Self::Hex => "hex",
+			Self::Binary => "binary",
+		}
+	}
+}
+
+/// A primitive value as it's shown, where only integers have other bases and floats are always decimal
+pub trait InBase: Copy + Display + Sized {
+	#[must_use]
+	fn to_base_string(self, base: NumberBase) -> String;
+
+	/// Parses decimal, or `0x` / `0b` prefixed hex and binary whatever the base it's shown in
+	#[must_use]
+	fn parse_any_base(s: &str) -> Option<Self>;
+}
+
+macro_rules! integer_in_base {
+	($($t:ty => $u:ty),*) => {
+		$(
+			impl InBase for $t {
+				fn to_base_string(self, base: NumberBase) -> String {
+					match base {
+						NumberBase::Decimal => self.to_string(),
+						// as the bits are stored, so negative numbers show their two's complement
+						NumberBase::Hex => format!("0x{:x}", self.cast_unsigned()),
+						NumberBase::Binary => format!("0b{:b}", self.cast_unsigned()),
+					}
+				}
+
+				fn parse_any_base(s: &str) -> Option<Self> {
+					let (negative, unsigned) = s.strip_prefix('-').map_or((false, s), |s| (true, s));
+					let (radix, digits) = if let Some(digits) = unsigned.strip_prefix("0x").or_else(|| unsigned.strip_prefix("0X")) {
+						(16, digits)
+					} else if let Some(digits) = unsigned.strip_prefix("0b").or_else(|| unsigned.strip_prefix("0B")) {
+						(2, digits)
+					} else {
+						return s.parse().ok()
+					};
+					// `from_str_radix` would take a sign of its own
+					if digits.starts_with(['+', '-']) {
+						return None
+					}
+					let bits = <$u>::from_str_radix(digits, radix).ok()?;
+					if negative {
+						Self::try_from(-i128::from(bits)).ok()
+					} else {
+						// every pattern of bits is a value, so `0xff` is -1 as a byte
+						Some(bits.cast_signed())
+					}
+				}
+			}
+		)*
+	};
+}
+
+integer_in_base!(i8 => u8, i16 => u16, i32 => u32, i64 => u64);
+
+macro_rules! float_in_base {
+	($($t:ty),*) => {
+		$(
+			impl InBase for $t {
+				fn to_base_string(self, _: NumberBase) -> String { self.to_string() }
+
+				fn parse_any_base(s: &str) -> Option<Self> { s.parse().ok() }
+			}
+		)*
+	};
+}
+
+float_in_base!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_number_base() {
+		assert_eq!((-1_i8).to_base_string(NumberBase::Hex), "0xff");
+		assert_eq!(5_i16.to_base_string(NumberBase::Binary), "0b101");
+		assert_eq!(1.5_f32.to_base_string(NumberBase::Hex), "1.5");
+
+		assert_eq!(i8::parse_any_base("0xff"), Some(-1));
+		assert_eq!(i8::parse_any_base("-0x80"), Some(i8::MIN));
+		assert_eq!(i8::parse_any_base("0x100"), None);
+		assert_eq!(i32::parse_any_base("0B1010"), Some(10));
+		assert_eq!(i64::parse_any_base("-12"), Some(-12));
+		assert_eq!(i16::parse_any_base("0x-1"), None);
+	}
+}
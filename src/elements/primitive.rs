@@ -8,12 +8,12 @@ macro_rules! primitive {
 			}
 
 			impl $crate::elements::Matches for $name {
-				fn matches(&self, other: &Self) -> bool { self.value == other.value }
+				fn matches(&self, other: &Self) -> bool { $crate::elements::float_display::InFloatDisplay::matches_value(self.value, other.value) }
 			}
 
 			impl ::std::fmt::Display for $name {
 				fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
-					write!(f, "{}", self.value)?;
+					$crate::elements::float_display::InFloatDisplay::write_snbt(self.value, f)?;
 					if let Some(s) = $s {
 						write!(f, "{s}")?;
 					}
@@ -57,7 +57,7 @@ macro_rules! primitive {
 
 					ctx.line_number();
 					builder.draw_texture(ctx.pos(), Self::UV, (16, 16));
-					ctx.check_for_invalid_value(|value| value.parse::<<Self as $crate::elements::PrimitiveNbtElementVariant>::InnerType>().is_err());
+					ctx.check_for_invalid_value(|value| <<Self as $crate::elements::PrimitiveNbtElementVariant>::InnerType as $crate::elements::boolean::AsBoolean>::parse_shown(value).is_none());
 					ctx.render_errors(ctx.pos(), builder);
 					if ctx.forbid(ctx.pos()) {
 						builder.settings(ctx.pos() + (20, 0), false, $crate::render::assets::JUST_OVERLAPPING_BASE_TEXT_Z);
@@ -67,13 +67,38 @@ macro_rules! primitive {
 						};
 
 						builder.color = $crate::render::color::TextColor::TreePrimitive.to_raw();
-						let _ = write!(builder, "{}", self.value);
+						match $crate::elements::boolean::AsBoolean::as_boolean(self.value).filter(|_| ctx.shows_boolean()) {
+							Some(boolean) => {
+								let _ = write!(builder, "{boolean} {}", $crate::elements::boolean::checkbox(boolean));
+							}
+							None => {
+								let value = $crate::elements::float_display::InFloatDisplay::to_shown_string(self.value, ctx.number_base(), ctx.float_display(), ctx.float_decimals());
+								let _ = write!(builder, "{value}");
+								if name == Some("DataVersion")
+									&& let Some(data_version) = $crate::elements::data_version::AsDataVersion::as_data_version(self.value)
+								{
+									builder.color = $crate::render::color::TextColor::Gray.to_raw();
+									let _ = write!(builder, " ({})", $crate::elements::data_version::version_name(data_version));
+								}
+								let width = $crate::util::StrExt::width(value.as_str()) + name.map_or(0, |key| $crate::util::StrExt::width(key) + $crate::util::StrExt::width(": "));
+								let start = ctx.pos() + (20, 0);
+								let mouse = ctx.mouse() + (builder.horizontal_scroll, 0);
+								if let Some(bits) = $crate::elements::float_display::InFloatDisplay::raw_bits(self.value)
+									&& (start.y..start.y + 16).contains(&mouse.y)
+									&& (start.x..start.x + width).contains(&mouse.x)
+								{
+									builder.draw_tooltip(&[&format!("Bits: {bits}")], ctx.mouse(), false);
+								}
+							}
+						}
 					}
 
 					ctx.offset_pos(0, 16);
 				}
 
-				fn value(&self) -> ::std::borrow::Cow<'_, str> { ::std::borrow::Cow::Owned(format!("{}", self.value)) }
+				fn value(&self) -> ::std::borrow::Cow<'_, str> {
+					::std::borrow::Cow::Owned($crate::elements::float_display::InFloatDisplay::to_shown_string(self.value, $crate::elements::number_base::NumberBase::Decimal, $crate::elements::float_display::FloatDisplay::Shortest, 0))
+				}
 			}
 
 			impl $crate::elements::PrimitiveNbtElementVariant for $name {
@@ -5,16 +5,18 @@ use std::{
 	fmt::{Display, Formatter},
 	hint::likely,
 	mem::MaybeUninit,
+	path::{Path, PathBuf},
 	slice::{Iter, IterMut},
+	sync::Arc,
 };
 
 #[cfg(target_arch = "wasm32")]
 use crate::wasm::{FakeScope as Scope, fake_scope as scope};
 use crate::{
-	elements::{ComplexNbtElementVariant, Matches, NbtElement, NbtElementVariant, chunk::NbtChunk, result::NbtParseResult},
+	elements::{ComplexNbtElementVariant, Matches, NbtElement, NbtElementVariant, chunk::NbtChunk, compound::NbtCompound, region_heatmap::RegionHeatmap, result::NbtParseResult},
 	render::{
 		RenderContext,
-		assets::{CONNECTION_UV, HEADER_SIZE, JUST_OVERLAPPING_BASE_TEXT_Z, JUST_OVERLAPPING_BOOKMARK_Z, LINE_NUMBER_CONNECTOR_Z, LINE_NUMBER_SEPARATOR_UV, REGION_GRID_UV, REGION_UV},
+		assets::{CHUNK_MARKER_Z, CONNECTION_UV, JUST_OVERLAPPING_BASE_TEXT_Z, JUST_OVERLAPPING_BOOKMARK_Z, LINE_NUMBER_CONNECTOR_Z, LINE_NUMBER_SEPARATOR_UV, REGION_GRID_UV, REGION_UV},
 		color::TextColor,
 		vertex_buffer_builder::VertexBufferBuilder,
 	},
@@ -24,7 +26,10 @@ use crate::{
 		formatter::{PrettyDisplay, PrettyFormatter},
 	},
 	util::Vec2u,
-	workbench::marked_line::MarkedLines,
+	workbench::{
+		marked_line::MarkedLines,
+		tab::{ChunkFileFormat, loading::LoadProgress},
+	},
 };
 
 #[repr(C)]
@@ -130,85 +135,203 @@ impl NbtRegion {
 	#[must_use]
 	pub fn is_grid_layout(&self) -> bool { (self.flags & 0b10) > 0 }
 
+	/// Whether this is a legacy `.mcr` region file, which only supports gzip and zlib compressed chunks and no `.mcc` files
+	#[must_use]
+	pub fn is_mcregion(&self) -> bool { (self.flags & 0b100) > 0 }
+
+	pub fn set_mcregion(&mut self, mcregion: bool) { self.flags = (self.flags & !0b100) | (u8::from(mcregion) << 2) }
+
 	#[must_use]
 	pub fn loaded_chunks(&self) -> usize { self.loaded_chunks as usize }
-}
 
-impl NbtElementVariant for NbtRegion {
-	const ID: u8 = 64 | 0;
-	const UV: Vec2u = REGION_UV;
-	const GHOST_UV: Vec2u = REGION_UV;
+	/// Where the region file at `path` is in the world in regions, going by its `r.<x>.<z>.mca` name
+	#[must_use]
+	pub fn region_pos(path: &Path) -> Option<(i32, i32)> {
+		let name = path.file_name()?.to_str()?;
+		let mut parts = name.strip_prefix("r.")?.split('.');
+		let (x, z, _extension) = (parts.next()?.parse::<i32>().ok()?, parts.next()?.parse::<i32>().ok()?, parts.next()?);
+		if parts.next().is_some() {
+			return None
+		}
+		Some((x, z))
+	}
 
-	fn from_str0(mut s: &str) -> Result<(&str, Self), usize>
-	where Self: Sized {
-		s = s.strip_prefix("Region").ok_or(s.len())?.trim_start();
-		s = s.strip_prefix('{').ok_or(s.len())?.trim_start();
-		let mut region = Self::default();
-		while !s.starts_with('}') {
-			let (s2, child) = NbtElement::from_str0(s, NbtElement::parse_int)?;
-			// SAFETY: no caches to outdate
-			if let Some(chunk) = child.as_chunk()
-				&& let Ok(_) = unsafe { region.insert(chunk.pos(), child) }
-			{
-				s = s2.trim_start();
-				if let Some(s2) = s.strip_prefix(',') {
-					s = s2.trim_start();
-				} else if s.starts_with('}') {
-					break;
-				} else {
-					return Err(s.len())
-				}
-			} else {
-				// invalid insertion
-				return Err(s.len())
-			}
+	/// Where the chunk at `idx` is in the world, going by the `r.<x>.<z>.mca` name of the region file at `path`
+	#[must_use]
+	pub fn world_chunk_pos(path: &Path, idx: usize) -> Option<(i32, i32)> {
+		let (x, z) = Self::region_pos(path)?;
+		Some((x * 32 + i32::try_from(idx % Self::CHUNK_BANDWIDTH).ok()?, z * 32 + i32::try_from(idx / Self::CHUNK_BANDWIDTH).ok()?))
+	}
+
+	/// Where the chunk at `idx` is in the world, going by the name of the region file at `path` or else the `xPos` and `zPos` of the chunks which have been decoded, see [`NbtChunk::world_pos`]
+	#[must_use]
+	pub fn world_pos_of(&self, path: &Path, idx: usize) -> Option<(i32, i32)> {
+		Self::world_chunk_pos(path, idx).or_else(|| {
+			let (other_idx, (x, z)) = self.children().enumerate().find_map(|(idx, chunk)| Some((idx, chunk.as_chunk().filter(|chunk| !chunk.is_undecoded())?.world_pos()?)))?;
+			let offset = |local: usize, other_local: usize| i32::try_from(local).ok()?.checked_sub(i32::try_from(other_local).ok()?);
+			Some((x.checked_add(offset(idx % 32, other_idx % 32)?)?, z.checked_add(offset(idx / 32, other_idx / 32)?)?))
+		})
+	}
+
+	/// A chunk with nothing generated in it yet at `world`, of the `DataVersion` of the region's first decoded chunk which has one
+	#[must_use]
+	pub fn empty_chunk(&self, world: (i32, i32)) -> NbtChunk {
+		// the last version before chunks were moved out of `Level`, in 1.18
+		const LEVEL_DATA_VERSION: i32 = 2843;
+		// 1.21.1, for a region with nothing to go by
+		const DEFAULT_DATA_VERSION: i32 = 3955;
+
+		let data_version = self
+			.children()
+			.filter_map(NbtElement::as_chunk)
+			.filter(|chunk| !chunk.is_undecoded())
+			.find_map(|chunk| chunk.map.idx_of("DataVersion").and_then(|idx| chunk.get(idx)).and_then(|entry| entry.value.as_int()).map(|int| int.value))
+			.unwrap_or(DEFAULT_DATA_VERSION);
+		let (x, z) = world;
+		let snbt = if data_version <= LEVEL_DATA_VERSION {
+			format!(r#"{{DataVersion:{data_version},Level:{{xPos:{x},zPos:{z},Status:"empty",LastUpdate:0L,InhabitedTime:0L,Sections:[],TileEntities:[],Entities:[]}}}}"#)
+		} else {
+			format!(r#"{{DataVersion:{data_version},xPos:{x},yPos:-4,zPos:{z},Status:"minecraft:empty",LastUpdate:0L,InhabitedTime:0L,sections:[],block_entities:[]}}"#)
+		};
+		let compound = NbtElement::from_str(&snbt).ok().and_then(|(_, element)| element.into_compound()).unwrap_or_default();
+		NbtChunk::new(compound, (0, 0), ChunkFileFormat::Zlib, NbtChunk::now())
+	}
+
+	/// `compound` as a chunk to add at `world` in the world, with the `xPos` and `zPos` it holds moved there, see [`NbtChunk::set_world_pos`]
+	#[must_use]
+	pub fn imported_chunk(compound: NbtCompound, world: (i32, i32)) -> NbtChunk {
+		let mut chunk = NbtChunk::new(compound, (0, 0), ChunkFileFormat::Zlib, NbtChunk::now());
+		chunk.set_world_pos(world);
+		chunk
+	}
+
+	/// The chunk position in `text`, such as `-341, 127`, `x=-341 z=127` or `(3, 4)`
+	#[must_use]
+	pub fn parse_chunk_pos(text: &str) -> Option<(i32, i32)> {
+		let text = text.trim();
+		let text = text.strip_prefix('(').and_then(|text| text.strip_suffix(')')).unwrap_or(text);
+		let mut parts = text.split([',', ' ']).filter(|part| !part.is_empty());
+		let x = parts.next()?;
+		let z = parts.next()?;
+		if parts.next().is_some() {
+			return None
 		}
-		s = s.strip_prefix('}').ok_or(s.len())?.trim_start();
-		region.recache();
-		Ok((s, region))
+		let x = x.strip_prefix("x=").unwrap_or(x).parse::<i32>().ok()?;
+		let z = z.strip_prefix("z=").unwrap_or(z).parse::<i32>().ok()?;
+		Some((x, z))
 	}
 
-	fn from_bytes<'a, D: Decoder<'a>>(decoder: &mut D, _: Self::ExtraParseInfo) -> NbtParseResult<Self>
-	where Self: Sized {
-		use super::result::*;
+	/// The path of the `c.<x>.<z>.mcc` file for the chunk at `idx`, which sits next to the `r.<x>.<z>.mca` region file at `path`
+	#[must_use]
+	pub fn external_chunk_path(path: &Path, idx: usize) -> Option<PathBuf> {
+		let (chunk_x, chunk_z) = Self::world_chunk_pos(path, idx)?;
+		Some(path.with_file_name(format!("c.{chunk_x}.{chunk_z}.mcc")))
+	}
 
-		decoder.assert_len(8192)?;
+	/// Loads the chunks stored outside of the region file at `path`, chunks whose `.mcc` file can't be read are left as placeholders
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn load_external_chunks(&mut self, path: &Path) {
+		for (idx, chunk) in self.chunks.iter_mut().enumerate() {
+			let chunk = unsafe { chunk.as_chunk_unchecked_mut() };
+			if !chunk.is_missing_external() {
+				continue;
+			}
+			let Some(payload) = Self::external_chunk_path(path, idx).and_then(|path| std::fs::read(path).ok()) else { continue };
+			// a chunk that fails to parse stays a placeholder, same as a missing one
+			let _ = chunk.load(payload);
+		}
+		self.recache();
+	}
+
+	/// Decodes every chunk which hasn't been yet, moving the bookmarks past each one down by the lines it gained
+	pub fn decode_chunks(&mut self, true_line_number: usize, bookmarks: &mut MarkedLines) {
+		let mut undecoded = Vec::new();
+		// one for the region
+		let mut chunk_true_line_number = true_line_number + 1;
+		for (idx, chunk) in self.children().enumerate() {
+			if chunk.as_chunk().is_some_and(NbtChunk::is_undecoded) {
+				undecoded.push((idx, chunk_true_line_number));
+			}
+			chunk_true_line_number += chunk.true_height();
+		}
+		if undecoded.is_empty() {
+			return;
+		}
 
 		scope(|s| {
-			let mut region = Self::default();
+			let mut iter = self.children_mut().array_chunks::<{ Self::CHUNK_BANDWIDTH }>();
+			for elements in iter.by_ref() {
+				s.spawn(|| {
+					for element in elements {
+						if let Some(chunk) = element.as_chunk_mut() {
+							chunk.decode();
+						}
+					}
+				});
+			}
+		});
 
-			let bytes = decoder.rest();
-			let mut threads = Vec::with_capacity(1024);
+		// backwards so the bookmarks past a chunk are still where they were before it was decoded
+		for (idx, chunk_true_line_number) in undecoded.into_iter().rev() {
+			let gained = self.chunks[idx].true_height() - 1;
+			bookmarks[chunk_true_line_number + 1..].increment(0, gained);
+		}
+		self.recache();
+	}
+
+	/// Reads every chunk with a worker per core, stopping early if the load gets cancelled
+	#[cfg(not(target_arch = "wasm32"))]
+	fn read_chunks<'a, D: Decoder<'a>>(decoder: &mut D, progress: Option<&LoadProgress>) -> NbtParseResult<Vec<(usize, NbtParseResult<NbtChunk>)>> {
+		use std::num::NonZeroUsize;
 
-			for idx in 0..1024 {
+		use super::result::{from_opt, ok};
+
+		let workers = std::thread::available_parallelism().map_or(1, NonZeroUsize::get).min(1024);
+		scope(|s| {
+			let mut threads = Vec::with_capacity(workers);
+			for worker in 0..workers {
 				let d2: &mut D = unsafe { (decoder as *const D).cast_mut().as_mut_unchecked() };
-				threads.push(s.spawn(move || NbtChunk::from_bytes(d2, idx)));
+				// interleaved so the workers share the populated part of the region evenly
+				threads.push(s.spawn(move || (worker..1024).step_by(workers).map_while(|idx| Self::read_chunk(d2, idx, progress)).collect::<Vec<_>>()));
 			}
 
-			for (idx, thread) in threads.into_iter().enumerate() {
-				let child = from_opt(thread.join().ok(), "Thread panicked")??;
-				region.chunks[idx] = NbtElement::Chunk(child);
+			let mut chunks = Vec::with_capacity(1024);
+			for thread in threads {
+				chunks.extend(from_opt(thread.join().ok(), "Thread panicked")?);
 			}
+			ok(chunks)
+		})
+	}
 
-			decoder.skip(bytes.len());
-			region.recache();
+	/// Reads every chunk one after another
+	#[cfg(target_arch = "wasm32")]
+	fn read_chunks<'a, D: Decoder<'a>>(decoder: &mut D, progress: Option<&LoadProgress>) -> NbtParseResult<Vec<(usize, NbtParseResult<NbtChunk>)>> { super::result::ok((0..1024).map_while(|idx| Self::read_chunk(decoder, idx, progress)).collect()) }
 
-			ok(region)
-		})
+	fn read_chunk<'a, D: Decoder<'a>>(decoder: &mut D, idx: usize, progress: Option<&LoadProgress>) -> Option<(usize, NbtParseResult<NbtChunk>)> {
+		if progress.is_some_and(LoadProgress::is_cancelled) {
+			return None;
+		}
+		let chunk = NbtChunk::from_bytes(decoder, idx);
+		if let Some(progress) = progress {
+			progress.increment();
+		}
+		Some((idx, chunk))
 	}
 
-	fn to_be_bytes(&self, writer: &mut UncheckedBufWriter) {
+	/// Writes the region, returning the index and payload of every chunk too large to fit within it
+	#[must_use]
+	pub fn to_be_bytes_with_external_chunks(&self, writer: &mut UncheckedBufWriter) -> Vec<(usize, Vec<u8>)> {
 		scope(move |s| {
 			let mut chunks = Vec::with_capacity(1024);
 			for chunk in self.chunks.iter() {
 				let chunk = unsafe { chunk.as_chunk_unchecked() };
 				chunks.push(s.spawn(move || {
 					if chunk.is_unloaded() {
-						(vec![], 0)
+						(vec![], 0, None)
 					} else {
 						let mut writer = UncheckedBufWriter::new();
-						chunk.to_be_bytes(&mut writer);
-						(writer.finish(), chunk.last_modified)
+						let external = chunk.to_be_bytes_with_external(&mut writer, self.is_mcregion());
+						(writer.finish(), chunk.last_modified, external)
 					}
 				}));
 			}
@@ -216,27 +339,133 @@ impl NbtElementVariant for NbtRegion {
 			let mut offsets = [0; 1024];
 			let mut timestamps = [0; 1024];
 			let mut new_chunks = Vec::with_capacity(chunks.len());
-			for (chunk, (offset, timestamp)) in chunks.into_iter().zip(offsets.iter_mut().zip(timestamps.iter_mut())) {
-				let Ok((chunk, last_modified)) = chunk.join() else {
-					return;
+			let mut externals = vec![];
+			for (idx, (chunk, (offset, timestamp))) in chunks.into_iter().zip(offsets.iter_mut().zip(timestamps.iter_mut())).enumerate() {
+				let Ok((chunk, last_modified, external)) = chunk.join() else {
+					return vec![];
 				};
 				let sectors = (chunk.len() / 4096) as u32;
 				if sectors > 0 {
 					*offset = (o.to_be() >> 8) | (sectors << 24);
 					o += sectors;
-					*timestamp = last_modified;
+					*timestamp = last_modified.to_be();
 					new_chunks.push(chunk);
 				} else {
 					*offset = 0;
 					*timestamp = 0;
 				}
+				if let Some(external) = external {
+					externals.push((idx, external));
+				}
 			}
 			writer.write(unsafe { core::slice::from_raw_parts(offsets.as_ptr().cast::<u8>(), 4096) });
 			writer.write(unsafe { core::slice::from_raw_parts(timestamps.as_ptr().cast::<u8>(), 4096) });
 			for chunk in new_chunks {
 				writer.write(&chunk);
 			}
-		});
+			externals
+		})
+	}
+
+	/// Writes the `.mcc` files for `externals` next to the region file at `path`, and removes the ones left over by chunks which fit in the region again
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn write_external_chunks(&self, path: &Path, externals: Vec<(usize, Vec<u8>)>) -> anyhow::Result<()> {
+		use anyhow::{Context, ensure};
+
+		use crate::util::write_atomic;
+
+		ensure!(!self.is_mcregion() || externals.is_empty(), "McRegion files can't store chunks larger than {} bytes", NbtChunk::MAX_INLINE_LEN);
+		let mut is_external = [false; 1024];
+		for (idx, payload) in externals {
+			let external_path = Self::external_chunk_path(path, idx).context("Region file must be named r.<x>.<z>.mca to store oversized chunks")?;
+			write_atomic(&external_path, &payload).with_context(|| format!("Failed to write {}", external_path.display()))?;
+			is_external[idx] = true;
+		}
+		for (idx, chunk) in self.chunks.iter().enumerate() {
+			let chunk = unsafe { chunk.as_chunk_unchecked() };
+			if is_external[idx] || chunk.is_missing_external() {
+				continue;
+			}
+			if let Some(external_path) = Self::external_chunk_path(path, idx)
+				&& external_path.exists()
+			{
+				std::fs::remove_file(&external_path).with_context(|| format!("Failed to remove {}", external_path.display()))?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl NbtElementVariant for NbtRegion {
+	type ExtraParseInfo = Option<Arc<LoadProgress>>;
+
+	const ID: u8 = 64 | 0;
+	const UV: Vec2u = REGION_UV;
+	const GHOST_UV: Vec2u = REGION_UV;
+
+	fn from_str0(mut s: &str) -> Result<(&str, Self), usize>
+	where Self: Sized {
+		s = s.strip_prefix("Region").ok_or(s.len())?.trim_start();
+		s = s.strip_prefix('{').ok_or(s.len())?.trim_start();
+		let mut region = Self::default();
+		while !s.starts_with('}') {
+			let (s2, child) = NbtElement::from_str0(s, NbtElement::parse_int)?;
+			// SAFETY: no caches to outdate
+			if let Some(chunk) = child.as_chunk()
+				&& let Ok(_) = unsafe { region.insert(chunk.pos(), child) }
+			{
+				s = s2.trim_start();
+				if let Some(s2) = s.strip_prefix(',') {
+					s = s2.trim_start();
+				} else if s.starts_with('}') {
+					break;
+				} else {
+					return Err(s.len())
+				}
+			} else {
+				// invalid insertion
+				return Err(s.len())
+			}
+		}
+		s = s.strip_prefix('}').ok_or(s.len())?.trim_start();
+		region.recache();
+		Ok((s, region))
+	}
+
+	fn from_bytes<'a, D: Decoder<'a>>(decoder: &mut D, progress: Self::ExtraParseInfo) -> NbtParseResult<Self>
+	where Self: Sized {
+		use super::result::*;
+
+		decoder.assert_len(8192)?;
+
+		let progress = progress.as_deref();
+		if let Some(progress) = progress {
+			progress.set_total(1024);
+		}
+
+		let mut region = Self::default();
+		let len = decoder.rest().len();
+
+		let chunks = Self::read_chunks(decoder, progress)?;
+		if progress.is_some_and(LoadProgress::is_cancelled) {
+			return err("Cancelled");
+		}
+		for (idx, chunk) in chunks {
+			// a broken chunk only loses its own slot, not the rest of the region
+			let chunk = log_err(chunk, format_args!("Chunk #{idx} couldn't be decoded")).unwrap_or_else(|| NbtChunk::unloaded_from_pos(idx));
+			region.chunks[idx] = NbtElement::Chunk(chunk);
+		}
+
+		decoder.skip(len);
+		region.recache();
+
+		ok(region)
+	}
+
+	fn to_be_bytes(&self, writer: &mut UncheckedBufWriter) {
+		// only saving to a file knows where the `.mcc` files of oversized chunks go
+		let externals = self.to_be_bytes_with_external_chunks(writer);
+		debug_assert!(externals.is_empty(), "{} chunks are too large to be written without their .mcc files", externals.len());
 	}
 
 	fn to_le_bytes(&self, _writer: &mut UncheckedBufWriter) {}
@@ -284,6 +513,8 @@ impl NbtElementVariant for NbtRegion {
 		if self.is_open() {
 			if self.is_grid_layout() {
 				let initial_x_offset = ctx.pos().x;
+				let heatmap = ctx.region_heatmap();
+				let heatmap_range = heatmap.range(self);
 				for z in 0..32 {
 					if ctx.pos().y > builder.window_height() {
 						break;
@@ -309,15 +540,16 @@ impl NbtElementVariant for NbtRegion {
 						ctx.skip_line_numbers(chunk.true_height() - 1);
 
 						builder.draw_texture_z(ctx.pos(), JUST_OVERLAPPING_BOOKMARK_Z, chunk.uv(), (16, 16));
-
-						if ctx.mouse.x > ctx.left_margin() && ctx.mouse.y > HEADER_SIZE {
-							let mx = ((ctx.mouse.x - ctx.left_margin()) & !15) + ctx.left_margin();
-							let my = ((ctx.mouse.y - HEADER_SIZE) & !15) + HEADER_SIZE;
-							if ctx.pos() == (mx, my) {
-								let text = chunk.value();
-								builder.color = TextColor::White.to_raw();
-								builder.draw_tooltip(&[&text], ctx.pos(), false);
-							}
+						chunk.draw_corrupted_tint(ctx.pos(), CHUNK_MARKER_Z, builder);
+						if let Some(range) = heatmap_range
+							&& let Some(value) = heatmap.value(chunk)
+						{
+							// inside the chunk's outline, under its marker
+							builder.draw_color_z(ctx.pos() + (2, 2), JUST_OVERLAPPING_BOOKMARK_Z, (12, 12), RegionHeatmap::color(value, range));
+						}
+						if chunk.is_undecoded() {
+							// the corner of the ghost texture, to tell it apart without drawing attention to it
+							builder.draw_texture_z(ctx.pos() + (12, 12), CHUNK_MARKER_Z, NbtChunk::GHOST_UV + (12, 12), (4, 4));
 						}
 
 						let pos = ctx.pos();
@@ -0,0 +1,149 @@
+use std::fmt::Write as _;
+
+use crate::{
+	elements::{ComplexNbtElementVariant, chunk::NbtChunk, compound::NbtCompound, element::NbtElement, region::NbtRegion},
+	render::{
+		assets::{JUST_OVERLAPPING_BASE_TEXT_Z, JUST_OVERLAPPING_BASE_Z},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+	},
+	util::{Timestamp, Vec2u},
+};
+
+/// What the cells of a region's grid layout are colored by, from blue for the least of it among the region's chunks to red for the most
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub enum RegionHeatmap {
+	#[default]
+	Off,
+	/// Of its payload as it was read, to find the chunks bloated with entities or items
+	Size,
+	LastModified,
+	/// Only of chunks which have been decoded, see [`Tab::set_region_heatmap`](crate::workbench::tab::Tab::set_region_heatmap)
+	DataVersion,
+}
+
+impl RegionHeatmap {
+	/// From blue through cyan, green and yellow to red
+	const GRADIENT: [u32; 5] = [0x3060FF, 0x30D0D0, 0x40D040, 0xF0E030, 0xFF3030];
+
+	#[must_use]
+	pub const fn cycle(self) -> Self {
+		match self {
+			Self::Off => Self::Size,
+			Self::Size => Self::LastModified,
+			Self::LastModified => Self::DataVersion,
+			Self::DataVersion => Self::Off,
+		}
+	}
+
+	#[must_use]
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Off => "off",
+			Self::Size => "compressed size",
+			Self::LastModified => "last modified",
+			Self::DataVersion => "DataVersion",
+		}
+	}
+
+	/// How much of it `chunk` has, if it has any to color it by
+	#[must_use]
+	pub fn value(self, chunk: &NbtChunk) -> Option<i64> {
+		if chunk.is_unloaded() || chunk.is_corrupted() {
+			return None
+		}
+		match self {
+			Self::Off => None,
+			Self::Size => chunk.payload_len().and_then(|len| i64::try_from(len).ok()),
+			Self::LastModified => Some(i64::from(chunk.last_modified)),
+			// decoding it here would decompress every chunk each frame
+			Self::DataVersion if chunk.is_undecoded() => None,
+			Self::DataVersion => {
+				let compound: &NbtCompound = chunk;
+				compound.map.idx_of("DataVersion").and_then(|idx| compound.get(idx)).and_then(|entry| entry.value.as_int()).map(|data_version| i64::from(data_version.value))
+			}
+		}
+	}
+
+	/// The least and the most of it among the chunks of `region`, if any of them have it
+	#[must_use]
+	pub fn range(self, region: &NbtRegion) -> Option<(i64, i64)> {
+		region.children().filter_map(NbtElement::as_chunk).filter_map(|chunk| self.value(chunk)).fold(None, |range, value| match range {
+			Some((min, max)) => Some((value.min(min), value.max(max))),
+			None => Some((value, value)),
+		})
+	}
+
+	/// Where `value` is on the gradient from `range`'s least to its most
+	#[must_use]
+	pub fn color(value: i64, (min, max): (i64, i64)) -> u32 {
+		let steps = Self::GRADIENT.len() - 1;
+		let span = max.abs_diff(min);
+		// in 1024ths of a step through the gradient, with a single value at its start
+		let progress = if span == 0 { 0 } else { u128::from(value.clamp(min, max).abs_diff(min)) * (steps as u128 * 1024) / u128::from(span) };
+		let progress = usize::try_from(progress).unwrap_or(usize::MAX);
+		// the last step goes all the way to its end rather than wrapping back to its start
+		let step = (progress / 1024).min(steps - 1);
+		let fraction = u32::try_from(progress - step * 1024).unwrap_or(1024);
+		let (from, to) = (Self::GRADIENT[step], Self::GRADIENT[step + 1]);
+		[16, 8, 0].into_iter().fold(0, |color, shift| {
+			let (from, to) = ((from >> shift) & 0xFF, (to >> shift) & 0xFF);
+			color | ((from * (1024 - fraction) + to * fraction) / 1024) << shift
+		})
+	}
+
+	/// `value` as it's read on the legend
+	#[allow(clippy::cast_precision_loss)] // only shown to a tenth of a KiB
+	fn label(self, value: i64) -> String {
+		match self {
+			Self::Off => String::new(),
+			Self::Size => format!("{:.1} KiB", value as f64 / 1024.0),
+			Self::LastModified => {
+				let ago = i64::try_from(Timestamp::now().since_epoch().as_secs()).unwrap_or(i64::MAX).saturating_sub(value).max(0);
+				match [(86400, "day"), (3600, "hour"), (60, "minute")].into_iter().find(|&(unit, _)| ago >= unit) {
+					Some((unit, name)) => format!("{n} {name}{s} ago", n = ago / unit, s = if ago / unit == 1 { "" } else { "s" }),
+					None => "Just now".to_owned(),
+				}
+			}
+			Self::DataVersion => value.to_string(),
+		}
+	}
+
+	/// What the colors mean, with `range` from [`Self::range`], drawn from `pos` down
+	pub fn render_legend(self, builder: &mut VertexBufferBuilder, pos: Vec2u, range: Option<(i64, i64)>) {
+		builder.settings(pos, false, JUST_OVERLAPPING_BASE_TEXT_Z);
+		builder.color = TextColor::White.to_raw();
+		let _ = write!(builder, "Heatmap of {}", self.name());
+		let Some((min, max)) = range else {
+			builder.settings(pos + (0, 16), false, JUST_OVERLAPPING_BASE_TEXT_Z);
+			builder.color = TextColor::Gray.to_raw();
+			let _ = write!(builder, "No chunks to color");
+			return
+		};
+		let steps = Self::GRADIENT.len() - 1;
+		for step in 0..=steps {
+			let value = min.saturating_add_unsigned(u64::try_from(u128::from(max.abs_diff(min)) * step as u128 / steps as u128).unwrap_or(u64::MAX));
+			let y = pos.y + 16 * (step + 1);
+			builder.draw_color_z((pos.x + 2, y + 2), JUST_OVERLAPPING_BASE_Z, (12, 12), Self::color(value, (min, max)));
+			builder.settings((pos.x + 20, y), false, JUST_OVERLAPPING_BASE_TEXT_Z);
+			builder.color = TextColor::Gray.to_raw();
+			let _ = write!(builder, "{}", self.label(value));
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_heatmap_colors() {
+		assert_eq!(RegionHeatmap::color(10, (10, 50)), 0x3060FF);
+		assert_eq!(RegionHeatmap::color(30, (10, 50)), 0x40D040);
+		assert_eq!(RegionHeatmap::color(50, (10, 50)), 0xFF3030);
+		// halfway between cyan and green
+		assert_eq!(RegionHeatmap::color(25, (10, 50)), 0x38D088);
+		assert_eq!(RegionHeatmap::color(7, (7, 7)), 0x3060FF);
+		assert_eq!(RegionHeatmap::color(i64::MAX, (i64::MIN, i64::MAX)), 0xFF3030);
+	}
+}
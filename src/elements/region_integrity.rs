@@ -0,0 +1,206 @@
+use std::fmt::{Display, Formatter};
+
+use crate::{
+	elements::{ComplexNbtElementVariant, NbtElementVariant, chunk::NbtChunk, compound::NbtCompound, element::NbtElement, region::NbtRegion},
+	serialization::decoder::{BigEndianDecoder, Decoder},
+};
+
+/// Something wrong with where a region's header says a chunk is, as a server that crashed mid-write can leave behind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegionIssue {
+	/// Of the chunk in the header
+	pub idx: usize,
+	pub kind: RegionIssueKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionIssueKind {
+	/// Its sectors start within the header itself
+	InHeader,
+	/// Its sectors share some with the chunk at this index, which starts before it
+	Overlapping(usize),
+	/// Its sectors go past the end of the file
+	PastEnd,
+	/// It has an offset but no sectors
+	NoSectors,
+	/// Its payload says it's at the same position as the chunk at `other`
+	DuplicatePosition { other: usize, x: i32, z: i32 },
+}
+
+impl Display for RegionIssue {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let idx = self.idx;
+		match self.kind {
+			RegionIssueKind::InHeader => write!(f, "Chunk #{idx}: its sectors start within the header"),
+			RegionIssueKind::Overlapping(other) => write!(f, "Chunk #{idx}: its sectors overlap those of chunk #{other}"),
+			RegionIssueKind::PastEnd => write!(f, "Chunk #{idx}: its sectors go past the end of the file"),
+			RegionIssueKind::NoSectors => write!(f, "Chunk #{idx}: it has an offset but no sectors"),
+			RegionIssueKind::DuplicatePosition { other, x, z } => write!(f, "Chunk #{idx}: it says it's at {x}, {z} just like chunk #{other}"),
+		}
+	}
+}
+
+/// Every inconsistency in the header of the region file `bytes`, in the order of the chunks they're about
+///
+/// A file too short to hold a header has nothing to check, since it won't parse as a region anyway
+#[must_use]
+pub fn check(bytes: &[u8]) -> Vec<RegionIssue> {
+	let Some(offsets) = bytes.first_chunk::<4096>() else { return Vec::new() };
+	let sectors = bytes.len().div_ceil(4096);
+	let mut issues = Vec::new();
+	let mut ranges = Vec::new();
+	for (idx, offset) in offsets.array_chunks::<4>().enumerate() {
+		let offset = u32::from_be_bytes(*offset) as usize;
+		if offset == 0 {
+			continue
+		}
+		let (start, len) = (offset >> 8, offset & 0xFF);
+		let kind = if len == 0 {
+			RegionIssueKind::NoSectors
+		} else if start < 2 {
+			RegionIssueKind::InHeader
+		} else if start + len > sectors {
+			RegionIssueKind::PastEnd
+		} else {
+			ranges.push((start, start + len, idx));
+			continue
+		};
+		issues.push(RegionIssue { idx, kind });
+	}
+
+	ranges.sort_unstable();
+	// the range reaching furthest so far, since a long one can overlap many after it
+	let mut furthest: Option<(usize, usize)> = None;
+	for &(start, end, idx) in &ranges {
+		match furthest {
+			Some((furthest_end, other)) if start < furthest_end => {
+				issues.push(RegionIssue { idx, kind: RegionIssueKind::Overlapping(other) });
+				if end > furthest_end {
+					furthest = Some((end, idx));
+				}
+			}
+			_ => furthest = Some((end, idx)),
+		}
+	}
+
+	let mut decoder = BigEndianDecoder::new(bytes);
+	let mut seen: Vec<((i32, i32), usize)> = Vec::new();
+	ranges.sort_unstable_by_key(|&(_, _, idx)| idx);
+	for (_, _, idx) in ranges {
+		// `into_iter` since the parse result is an `Option` on release
+		let Some(chunk) = NbtChunk::from_bytes(&mut decoder, idx).into_iter().next() else { continue };
+		let Some((x, z)) = chunk.decoded().ok().and_then(|compound| position(&compound)) else { continue };
+		match seen.iter().find(|&&(pos, _)| pos == (x, z)) {
+			Some(&(_, other)) => issues.push(RegionIssue { idx, kind: RegionIssueKind::DuplicatePosition { other, x, z } }),
+			None => seen.push(((x, z), idx)),
+		}
+	}
+
+	issues.sort_by_key(|issue| issue.idx);
+	issues
+}
+
+/// `region` with every chunk moved to where its payload says it is, leaving out the ones which don't decode
+///
+/// Where two chunks say they're at the same position, the one already there is kept, or otherwise the one modified last
+#[must_use]
+pub fn repair(region: &NbtRegion) -> NbtRegion {
+	// the index it came from and when it was last modified, to choose between chunks at the same position
+	let mut placed: Vec<Option<(NbtChunk, usize, u32)>> = (0..1024).map(|_| None).collect();
+	for (idx, element) in region.children().enumerate() {
+		let Some(chunk) = element.as_chunk() else { continue };
+		if chunk.is_unloaded() || chunk.is_corrupted() {
+			continue
+		}
+		let mut chunk = chunk.clone();
+		// its payload is in a `.mcc` file which was never found, so there's nothing to go by but where it was
+		let target = if chunk.is_missing_external() {
+			idx
+		} else {
+			chunk.decode();
+			let Ok(compound) = chunk.decoded() else { continue };
+			position(&compound).map_or(idx, |(x, z)| z.rem_euclid(32) as usize * 32 + x.rem_euclid(32) as usize)
+		};
+		let last_modified = chunk.last_modified;
+		if placed[target].as_ref().is_none_or(|&(_, other_idx, other_last_modified)| (target == idx, last_modified) > (target == other_idx, other_last_modified)) {
+			placed[target] = Some((chunk, idx, last_modified));
+		}
+	}
+
+	let mut repaired = region.clone();
+	for (idx, chunk) in placed.into_iter().enumerate() {
+		let chunk = chunk.map_or_else(|| NbtChunk::unloaded_from_pos(idx), |(chunk, _, _)| chunk);
+		// SAFETY: the region is recached once every chunk is in place
+		let _ = unsafe { repaired.replace(idx, NbtElement::Chunk(chunk)) };
+	}
+	repaired.recache();
+	repaired
+}
+
+/// The chunk coordinates a chunk's payload says it's at, from `xPos` and `zPos` (within `Level` for older chunks) or the `Position` of entity chunks
+fn position(compound: &NbtCompound) -> Option<(i32, i32)> {
+	fn get<'a>(compound: &'a NbtCompound, key: &str) -> Option<&'a NbtElement> { compound.map.idx_of(key).and_then(|idx| compound.get(idx)).map(|entry| &entry.value) }
+	let from = |compound: &NbtCompound| Some((get(compound, "xPos")?.as_int()?.value, get(compound, "zPos")?.as_int()?.value));
+	from(compound).or_else(|| get(compound, "Level").and_then(NbtElement::as_compound).and_then(from)).or_else(|| {
+		let position = get(compound, "Position")?.as_int_array()?;
+		Some((position.get(0)?.as_int()?.value, position.get(1)?.as_int()?.value))
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Read;
+
+	use flate2::Compression;
+
+	use super::*;
+
+	fn payload(x: i32, z: i32) -> Vec<u8> {
+		let nbt = NbtElement::from_str(&format!("{{xPos:{x},zPos:{z},Status:\"minecraft:full\"}}")).expect("Valid SNBT").1.to_be_file();
+		let mut payload = vec![];
+		flate2::read::ZlibEncoder::new(nbt.as_slice(), Compression::fast()).read_to_end(&mut payload).expect("Valid compression");
+		payload
+	}
+
+	/// A region with each chunk at the given header index and sectors, written in order from sector 2
+	fn region(chunks: &[(usize, Vec<u8>)], offsets: &[(usize, u32)]) -> Vec<u8> {
+		let mut region = vec![0_u8; 8192];
+		for (idx, payload) in chunks {
+			let sector = region.len() / 4096;
+			let len = payload.len() + 1;
+			region[idx * 4..idx * 4 + 4].copy_from_slice(&u32::try_from(sector << 8 | (len + 4).div_ceil(4096)).expect("Valid offset").to_be_bytes());
+			region[4096 + idx * 4..4096 + idx * 4 + 4].copy_from_slice(&u32::try_from(*idx + 1).expect("Valid timestamp").to_be_bytes());
+			region.extend_from_slice(&u32::try_from(len).expect("Valid length").to_be_bytes());
+			region.push(2);
+			region.extend_from_slice(payload);
+			region.resize(region.len().next_multiple_of(4096), 0);
+		}
+		for &(idx, offset) in offsets {
+			region[idx * 4..idx * 4 + 4].copy_from_slice(&offset.to_be_bytes());
+		}
+		region
+	}
+
+	#[test]
+	fn test_region_integrity() {
+		// chunk 40 is where it says it is, 3 is at 8, 0 but in the wrong slot, 5 says it's at 8, 1 just like 40, and 7 points at the sectors of 3 with no timestamp
+		let bytes = region(&[(3, payload(8, 0)), (5, payload(8, 1)), (40, payload(8, 1))], &[(7, 2 << 8 | 1), (9, 3 << 8), (11, 90 << 8 | 1), (12, 1 << 8 | 1)]);
+		let kinds = check(&bytes).into_iter().map(|issue| (issue.idx, issue.kind)).collect::<Vec<_>>();
+		assert_eq!(kinds, [
+			(7, RegionIssueKind::Overlapping(3)),
+			(7, RegionIssueKind::DuplicatePosition { other: 3, x: 8, z: 0 }),
+			(9, RegionIssueKind::NoSectors),
+			(11, RegionIssueKind::PastEnd),
+			(12, RegionIssueKind::InHeader),
+			(40, RegionIssueKind::DuplicatePosition { other: 5, x: 8, z: 1 }),
+		]);
+
+		let root = NbtElement::from_be_mca(&bytes, None).expect("Valid region");
+		let repaired = NbtElement::Region(repair(root.as_region().expect("Region")));
+		assert_eq!(check(&repaired.to_be_file()), []);
+		let region = repaired.as_region().expect("Region");
+		let loaded = region.children().enumerate().filter(|(_, chunk)| chunk.as_chunk().is_some_and(NbtChunk::is_loaded)).map(|(idx, chunk)| (idx, chunk.as_chunk().map(|chunk| chunk.last_modified))).collect::<Vec<_>>();
+		// 40 is already where it says it is, and 3 was modified after the copy of it at 7
+		assert_eq!(loaded, [(8, Some(4)), (40, Some(41))]);
+	}
+}
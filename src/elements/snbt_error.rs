@@ -0,0 +1,137 @@
+use std::cell::Cell;
+
+use thiserror::Error;
+
+use crate::util::{CharExt, StrExt};
+
+thread_local! {
+	/// The failure furthest into the SNBT being parsed on this thread, as the bytes left after it and what was expected there
+	static FURTHEST: Cell<Option<(usize, &'static str)>> = const { Cell::new(None) };
+}
+
+/// Notes that `expected` wasn't found with `remaining` bytes left, if that's further in than anything noted before
+///
+/// Returns `remaining`, so it can be used wherever the parsers return their error.
+#[must_use]
+pub fn expected(remaining: usize, expected: &'static str) -> usize {
+	if FURTHEST.get().is_none_or(|(furthest, _)| remaining < furthest) {
+		FURTHEST.set(Some((remaining, expected)));
+	}
+	remaining
+}
+
+/// Forgets every failure noted so far, to be called before a new parse starts
+pub fn reset() { FURTHEST.set(None); }
+
+/// Where SNBT failed to parse and what was expected there
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("expected {expected} at line {line}, column {column}")]
+pub struct SnbtError {
+	pub offset: usize,
+	pub line: usize,
+	pub column: usize,
+	pub expected: &'static str,
+	line_text: String,
+}
+
+impl SnbtError {
+	/// The most pixels of the failing line shown either side of the caret
+	const PREVIEW_WIDTH: usize = 96;
+
+	/// Where parsing `snbt` failed, given the index the parser returned and whatever it noted through [`expected`] since it was [`reset`]
+	#[must_use]
+	pub fn of(snbt: &str, idx: usize) -> Self { Self::of_part(snbt, 0, snbt, idx) }
+
+	/// As [`Self::of`], for `snbt` starting `start` bytes into `text`, so the line and column are for all of `text`
+	#[must_use]
+	pub fn of_part(text: &str, start: usize, snbt: &str, idx: usize) -> Self {
+		let (offset, expected) = match FURTHEST.get() {
+			Some((remaining, expected)) if remaining <= snbt.len() && snbt.len() - remaining >= idx => (snbt.len() - remaining, expected),
+			_ => (idx, "a value"),
+		};
+		Self::at(text, start + offset, expected)
+	}
+
+	/// The failure at `offset` bytes into `text`
+	#[must_use]
+	pub fn at(text: &str, offset: usize, expected: &'static str) -> Self {
+		let mut offset = offset.min(text.len());
+		while !text.is_char_boundary(offset) {
+			offset -= 1;
+		}
+		let line_start = text[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+		let line_end = text[offset..].find('\n').map_or(text.len(), |idx| offset + idx);
+		Self {
+			offset,
+			line: text[..line_start].matches('\n').count() + 1,
+			column: text[line_start..offset].chars().count() + 1,
+			expected,
+			line_text: text[line_start..line_end].trim_end_matches('\r').to_owned(),
+		}
+	}
+
+	/// The line it failed on, cut down around where, with a caret under the character it failed at
+	///
+	/// The caret is lined up by how wide the characters are drawn, so this is meant to be shown in an alert.
+	#[must_use]
+	pub fn preview(&self) -> String {
+		let column = self.column - 1;
+		let line = self.line_text.trim_start();
+		let column = column.saturating_sub(self.line_text.chars().count() - line.chars().count());
+		let (before, after) = line.split_at(line.char_indices().nth(column).map_or(line.len(), |(idx, _)| idx));
+
+		let mut width = 0;
+		let start = before
+			.char_indices()
+			.rev()
+			.find(|&(_, char)| {
+				width += char.width();
+				width > Self::PREVIEW_WIDTH
+			})
+			.map_or(0, |(idx, char)| idx + char.len_utf8());
+		let mut width = 0;
+		let end = after
+			.char_indices()
+			.find(|&(_, char)| {
+				width += char.width();
+				width > Self::PREVIEW_WIDTH
+			})
+			.map_or(after.len(), |(idx, _)| idx);
+
+		let before = if start > 0 { format!("…{}", &before[start..]) } else { before.to_owned() };
+		let after = if end < after.len() { format!("{}…", &after[..end]) } else { after.to_owned() };
+		let under = after.chars().next().map_or(' '.width(), CharExt::width);
+		let padding = (before.width() + under / 2).saturating_sub('^'.width() / 2) / ' '.width();
+		format!("{before}{after}\n{:padding$}^", "")
+	}
+
+	/// The message followed by the [preview](Self::preview) of where it is
+	#[must_use]
+	pub fn diagnostic(&self) -> String { format!("{self}\n{}", self.preview()) }
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::elements::element::NbtElement;
+
+	#[test]
+	fn test_snbt_error() {
+		let e = NbtElement::from_snbt("{\n\ta: 1b,\n\tb: [1, 2]\n\tc: \"x\"\n}").expect_err("Invalid SNBT");
+		assert_eq!((e.line, e.column, e.expected), (4, 2, "',' or '}'"));
+		assert_eq!(e.to_string(), "expected ',' or '}' at line 4, column 2");
+		assert_eq!(e.preview().lines().next(), Some("c: \"x\""));
+		assert!(e.preview().ends_with('^'));
+
+		// furthest in, rather than where the value it's in started
+		assert_eq!(NbtElement::from_snbt("{a:{b:[1b,2b}}").expect_err("Invalid SNBT").to_string(), "expected ',' or ']' at line 1, column 13");
+		assert_eq!(NbtElement::from_snbt("{a:\"b}").expect_err("Invalid SNBT").expected, "a closing quote");
+		assert_eq!(NbtElement::from_snbt("{a 1b}").expect_err("Invalid SNBT").expected, "':'");
+		assert_eq!(NbtElement::from_snbt("{a:}").expect_err("Invalid SNBT").column, 4);
+		assert_eq!(NbtElement::from_snbt("1b 2b").expect_err("Invalid SNBT").expected, "the end of the text");
+		assert_eq!(NbtElement::from_snbt(" ").expect_err("Invalid SNBT").expected, "a value");
+
+		let (_, int) = NbtElement::from_str("1").expect("Valid SNBT");
+		assert_eq!(int.value_error("1.5").expected, "an int");
+		assert_eq!(int.value_error("12 x").column, 4);
+	}
+}
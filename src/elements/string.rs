@@ -23,7 +23,7 @@ use crate::{
 		encoder::UncheckedBufWriter,
 		formatter::{PrettyDisplay, PrettyFormatter},
 	},
-	util::{StrExt, Vec2u},
+	util::{CharExt, StrExt, Vec2u},
 };
 use crate::render::assets::BASE_Z;
 
@@ -42,7 +42,7 @@ impl Display for NbtString {
 }
 
 impl PrettyDisplay for NbtString {
-	fn pretty_fmt(&self, f: &mut PrettyFormatter) { f.write_str(self.str.as_str()) }
+	fn pretty_fmt(&self, f: &mut PrettyFormatter) { f.write_str(&self.to_string()) }
 }
 
 impl NbtElementVariant for NbtString {
@@ -84,7 +84,7 @@ impl NbtElementVariant for NbtString {
 				let _ = write!(builder, "{name}: ");
 			}
 			builder.color = TextColor::TreeString.to_raw();
-			let _ = write!(builder, "{}", self.str.as_str());
+			let _ = write!(builder, "{}", self.preview());
 		}
 
 		ctx.offset_pos(0, 16);
@@ -93,6 +93,39 @@ impl NbtElementVariant for NbtString {
 	fn value(&self) -> Cow<'_, str> { Cow::Borrowed(self.str.as_str()) }
 }
 
+impl NbtString {
+	/// Strings wider than this, or with a newline, only have a [preview](Self::preview) shown in the tree and are edited in a [`StringEditor`](crate::render::widget::string_editor::StringEditor)
+	pub const MAX_INLINE_WIDTH: usize = 512;
+	/// How much of the first line a preview shows
+	const PREVIEW_WIDTH: usize = 256;
+	const PREVIEW_MARKER: &'static str = "… ⏎";
+
+	#[must_use]
+	pub fn is_multiline(&self) -> bool {
+		let str = self.str.as_str();
+		str.contains('\n') || str.width() > Self::MAX_INLINE_WIDTH
+	}
+
+	/// The start of the first line, marked as going on, or the whole string if it isn't [multiline](Self::is_multiline)
+	#[must_use]
+	pub fn preview(&self) -> Cow<'_, str> {
+		let str = self.str.as_str();
+		if !self.is_multiline() {
+			return Cow::Borrowed(str)
+		}
+		let line = str.split('\n').next().unwrap_or_default();
+		let mut width = 0;
+		let end = line
+			.char_indices()
+			.find(|&(_, char)| {
+				width += char.width();
+				width > Self::PREVIEW_WIDTH
+			})
+			.map_or(line.len(), |(idx, _)| idx);
+		Cow::Owned(format!("{}{}", &line[..end], Self::PREVIEW_MARKER))
+	}
+}
+
 impl PrimitiveNbtElementVariant for NbtString {
 	type InnerType = TwentyThree;
 
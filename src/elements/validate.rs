@@ -0,0 +1,154 @@
+use std::fmt::{Display, Formatter};
+
+use fxhash::FxHashSet;
+
+use crate::{
+	elements::{
+		NbtElementAndKeyRef,
+		compound::CompoundEntry,
+		element::{NbtElement, NbtPattern},
+	},
+	tree::indices::OwnedIndices,
+};
+
+/// Something wrong with a tree that didn't stop it from being opened
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+	pub severity: Severity,
+	/// Of the element it's about
+	pub indices: OwnedIndices,
+	pub message: String,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+	/// The file can be read, but maybe not by every version of the game
+	Warning,
+	/// Some of the file was lost when reading it, or would be when writing it
+	Error,
+}
+
+impl Display for Severity {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Warning => write!(f, "warning"),
+			Self::Error => write!(f, "error"),
+		}
+	}
+}
+
+/// What's wrong with an entry whose key an earlier entry of the same compound has
+#[must_use]
+pub fn duplicate_key(key: &str) -> String { format!("Duplicate key {key:?}, only one of them would be kept by the game") }
+
+/// Every problem with `root` and its children, in the order they appear in the tree
+///
+/// Chunks which haven't been decoded yet aren't looked into, see [`NbtElement::decode_chunks`]
+#[must_use]
+pub fn validate(root: &NbtElement) -> Vec<Diagnostic> {
+	let mut diagnostics = Vec::new();
+	// whether the element is a later duplicate of a key in the same compound
+	let mut queue: Vec<(NbtElementAndKeyRef, OwnedIndices, bool)> = vec![((None, root), OwnedIndices::new(), false)];
+	while let Some(((key, value), indices, duplicate)) = queue.pop() {
+		let mut diagnose = |severity, message: String| diagnostics.push(Diagnostic { severity, indices: indices.clone(), message });
+
+		if let Some(key) = key {
+			if duplicate {
+				diagnose(Severity::Error, duplicate_key(key));
+			}
+			// strings are read lossily, so invalid ones end up with replacement characters
+			if key.contains(char::REPLACEMENT_CHARACTER) {
+				diagnose(Severity::Warning, format!("Key {key:?} wasn't valid modified UTF-8, the invalid bytes were replaced"));
+			}
+		}
+
+		match value.as_pattern() {
+			NbtPattern::String(string) if string.str.as_str().contains(char::REPLACEMENT_CHARACTER) => diagnose(Severity::Warning, "String wasn't valid modified UTF-8, the invalid bytes were replaced".to_owned()),
+			NbtPattern::List(list) if !list.elements.is_empty() && list.is_heterogeneous() => diagnose(Severity::Warning, "List has elements of different types, which only Minecraft 1.21.5 and later can read".to_owned()),
+			NbtPattern::Chunk(chunk) if let Some(error) = chunk.error() => diagnose(Severity::Error, format!("Chunk {x}, {z} couldn't be read, {error}", x = chunk.x, z = chunk.z)),
+			NbtPattern::Chunk(chunk) if chunk.is_missing_external() => diagnose(Severity::Error, format!("Chunk {x}, {z} is stored in an external .mcc file which couldn't be found", x = chunk.x, z = chunk.z)),
+			_ => {}
+		}
+
+		match value.children() {
+			Some(Ok(iter)) =>
+				for (idx, child) in iter.enumerate().rev() {
+					let mut indices = indices.clone();
+					indices.push(idx);
+					queue.push(((None, child), indices, false));
+				},
+			Some(Err(iter)) => {
+				let mut keys = FxHashSet::default();
+				let duplicates = iter.as_slice().iter().map(|entry| !keys.insert(entry.key.as_str())).collect::<Vec<_>>();
+				for ((idx, CompoundEntry { key, value: child }), duplicate) in iter.enumerate().zip(duplicates).rev() {
+					let mut indices = indices.clone();
+					indices.push(idx);
+					queue.push(((Some(key), child), indices, duplicate));
+				}
+			}
+			None => {}
+		}
+	}
+	diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::workbench::marked_line::MarkedLines;
+
+	fn messages(root: &NbtElement) -> Vec<(Severity, Vec<usize>)> { validate(root).into_iter().map(|diagnostic| (diagnostic.severity, diagnostic.indices.iter().collect())).collect() }
+
+	#[test]
+	fn test_valid_file() {
+		let root = NbtElement::from_be_file(include_bytes!("../assets/test.nbt")).expect("Included test nbt contains valid data");
+		assert!(validate(&root).is_empty());
+	}
+
+	#[test]
+	fn test_invalid_string() {
+		// `{a:"\xFF\xFE",b:1b}`, the string bytes aren't valid in any UTF-8
+		let file = b"\x0A\x00\x00\x08\x00\x01a\x00\x02\xFF\xFE\x01\x00\x01b\x01\x00";
+		let root = NbtElement::from_be_file(file).expect("Valid NBT");
+		assert_eq!(messages(&root), [(Severity::Warning, vec![0])]);
+	}
+
+	#[test]
+	fn test_heterogeneous_list() {
+		let (_, root) = NbtElement::from_str(r#"[[1b,"a"],[],[1b,2b]]"#).expect("Valid SNBT");
+		assert_eq!(messages(&root), [(Severity::Warning, vec![0])]);
+	}
+
+	#[test]
+	fn test_duplicate_key() {
+		let (_, root) = NbtElement::from_str("{a:1b,a:3b,b:2b}").expect("Valid SNBT");
+		assert_eq!(messages(&root), [(Severity::Error, vec![1])]);
+
+		// `{a:1b,a:2b}`, both kept and written back as they were read
+		let file = b"\x0A\x00\x00\x01\x00\x01a\x01\x01\x00\x01a\x02\x00";
+		let root = NbtElement::from_be_file(file).expect("Valid NBT");
+		let compound = root.as_compound().expect("Compound");
+		assert_eq!(compound.map.len(), 2);
+		assert_eq!(compound.map.idx_of("a"), Some(0));
+		assert!(!compound.map.is_duplicate(0) && compound.map.is_duplicate(1));
+		assert_eq!(messages(&root), [(Severity::Error, vec![1])]);
+		assert_eq!(root.to_be_file(), file);
+	}
+
+	#[test]
+	fn test_undecodable_chunk() {
+		// one chunk, zlib compressed, whose payload is a zlib header followed by garbage
+		let mut region = vec![0_u8; 8192];
+		region[0..4].copy_from_slice(&(2_u32 << 8 | 1).to_be_bytes());
+		region[4096..4100].copy_from_slice(&1_u32.to_be_bytes());
+		let payload = [0x78, 0x9C, 0xFF, 0xFF, 0xFF, 0xFF];
+		region.extend_from_slice(&u32::try_from(payload.len() + 1).expect("Valid length").to_be_bytes());
+		region.push(2);
+		region.extend_from_slice(&payload);
+		region.resize(12288, 0);
+
+		let mut root = NbtElement::from_be_mca(&region, None).expect("Valid region");
+		root.decode_chunks(&mut MarkedLines::new());
+		assert_eq!(messages(&root), [(Severity::Error, vec![0])]);
+	}
+}
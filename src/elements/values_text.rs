@@ -0,0 +1,204 @@
+use std::ops::Range;
+
+use thiserror::Error;
+
+use crate::elements::{
+	ComplexNbtElementVariant, NbtElementVariant,
+	array::{NbtByteArray, NbtIntArray, NbtLongArray, UUID_KEYS},
+	byte::NbtByte,
+	chunk::NbtChunk,
+	double::NbtDouble,
+	element::{NbtElement, NbtPattern, id_to_string_name},
+	float::NbtFloat,
+	int::NbtInt,
+	list::NbtList,
+	long::NbtLong,
+	number_base::{InBase, NumberBase},
+	short::NbtShort,
+};
+use crate::util::{format_unix_time, parse_unix_time};
+
+/// Keys the game expects an array of an exact length under, such as the 1024 biomes of a chunk
+pub const FIXED_LENGTH_KEYS: [&str; 13] = [
+	"Biomes",
+	"HeightMap",
+	"Blocks",
+	"Data",
+	"Add",
+	"BlockLight",
+	"SkyLight",
+	"MOTION_BLOCKING",
+	"MOTION_BLOCKING_NO_LEAVES",
+	"OCEAN_FLOOR",
+	"OCEAN_FLOOR_WG",
+	"WORLD_SURFACE",
+	"WORLD_SURFACE_WG",
+];
+
+/// Whether the game cares how many values the array under `key` holds
+#[must_use]
+pub fn is_fixed_length(key: &str) -> bool { FIXED_LENGTH_KEYS.contains(&key) || UUID_KEYS.contains(&key) }
+
+/// The id of the values `element` holds, if it's an array or a list of nothing but one type of number
+fn value_id(element: &NbtElement) -> Option<u8> {
+	use NbtPattern as Nbt;
+
+	match element.as_pattern() {
+		Nbt::ByteArray(_) => Some(NbtByte::ID),
+		Nbt::IntArray(_) => Some(NbtInt::ID),
+		Nbt::LongArray(_) => Some(NbtLong::ID),
+		Nbt::List(list) if !list.is_heterogeneous() && [NbtByte::ID, NbtShort::ID, NbtInt::ID, NbtLong::ID, NbtFloat::ID, NbtDouble::ID].contains(&list.child_id()) => Some(list.child_id()),
+		_ => None,
+	}
+}
+
+/// Whether `element` can be edited with [`values_as_text`] and [`parse_values_text`]
+#[must_use]
+pub fn is_editable_as_text(element: &NbtElement) -> bool { value_id(element).is_some() }
+
+/// The values of `element` as comma-separated text, with integers written in `base`
+#[must_use]
+pub fn values_as_text(element: &NbtElement, base: NumberBase) -> Option<String> {
+	use NbtPattern as Nbt;
+
+	value_id(element)?;
+	let values = element.values()?;
+	let values = values
+		.map(|value| match value.as_pattern() {
+			Nbt::Byte(byte) => byte.value.to_base_string(base),
+			Nbt::Short(short) => short.value.to_base_string(base),
+			Nbt::Int(int) => int.value.to_base_string(base),
+			Nbt::Long(long) => long.value.to_base_string(base),
+			Nbt::Float(float) => float.value.to_base_string(base),
+			Nbt::Double(double) => double.value.to_base_string(base),
+			_ => String::new(),
+		})
+		.collect::<Vec<_>>();
+	Some(values.join(", "))
+}
+
+/// When `chunk` was last modified as text for [`parse_timestamp_text`]
+#[must_use]
+pub fn timestamp_as_text(chunk: &NbtChunk) -> String { format_unix_time(u64::from(chunk.last_modified)) }
+
+/// `chunk` with the time in `text` as when it was last modified, as a UTC date or seconds since the unix epoch
+///
+/// # Errors
+/// * If `text` isn't a time a region's timestamp table can hold
+pub fn parse_timestamp_text(chunk: &NbtChunk, text: &str) -> Result<NbtElement, ValuesTextError> {
+	let last_modified = parse_unix_time(text).and_then(|secs| u32::try_from(secs).ok()).ok_or_else(|| ValuesTextError::InvalidValue {
+		token: text.to_owned(),
+		expected: "date",
+		range: 0..text.len(),
+	})?;
+	let mut chunk = chunk.clone();
+	chunk.last_modified = last_modified;
+	Ok(NbtElement::Chunk(chunk))
+}
+
+/// The values in `text` as an element of the same type as `element`
+///
+/// Values are separated by commas, whitespace or both, a trailing comma is fine, and each one may have the SNBT suffix of its type. A chunk is edited by its timestamp instead, see [`parse_timestamp_text`]
+pub fn parse_values_text(element: &NbtElement, text: &str) -> Result<NbtElement, ValuesTextError> {
+	if let Some(chunk) = element.as_chunk() {
+		return parse_timestamp_text(chunk, text)
+	}
+	let id = value_id(element).ok_or(ValuesTextError::Unsupported { found: element.display_name() })?;
+	let values = tokens(text)
+		.map(|range| {
+			let token = &text[range.clone()];
+			parse_value(id, token).ok_or_else(|| ValuesTextError::InvalidValue {
+				token: token.to_owned(),
+				expected: id_to_string_name(id, 1),
+				range,
+			})
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	Ok(match id {
+		_ if element.is_list() => {
+			let mut list = NbtList::new(values);
+			// the parent's height is updated from it
+			list.recache();
+			NbtElement::List(list)
+		}
+		NbtByte::ID => NbtElement::ByteArray(NbtByteArray::new(values)),
+		NbtInt::ID => NbtElement::IntArray(NbtIntArray::new(values)),
+		_ => NbtElement::LongArray(NbtLongArray::new(values)),
+	})
+}
+
+/// The byte ranges of each value in `text`
+fn tokens(text: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+	let is_separator = |c: char| c == ',' || c.is_whitespace();
+	let mut start = 0;
+	core::iter::from_fn(move || {
+		start += text[start..].find(|c| !is_separator(c))?;
+		let end = text[start..].find(is_separator).map_or(text.len(), |len| start + len);
+		let range = start..end;
+		start = end;
+		Some(range)
+	})
+}
+
+fn parse_value(id: u8, token: &str) -> Option<NbtElement> {
+	// `0xfb` is a hex byte before it's `0xf` with a suffix
+	fn parse<T: InBase>(token: &str, suffix: char) -> Option<T> { T::parse_any_base(token).or_else(|| T::parse_any_base(token.strip_suffix([suffix, suffix.to_ascii_uppercase()])?)) }
+
+	Some(match id {
+		NbtByte::ID => NbtElement::Byte(NbtByte { value: parse(token, 'b')? }),
+		NbtShort::ID => NbtElement::Short(NbtShort { value: parse(token, 's')? }),
+		NbtInt::ID => NbtElement::Int(NbtInt { value: i32::parse_any_base(token)? }),
+		NbtLong::ID => NbtElement::Long(NbtLong { value: parse(token, 'l')? }),
+		NbtFloat::ID => NbtElement::Float(NbtFloat { value: parse(token, 'f')? }),
+		NbtDouble::ID => NbtElement::Double(NbtDouble { value: parse(token, 'd')? }),
+		_ => return None,
+	})
+}
+
+#[derive(Error, Debug)]
+pub enum ValuesTextError {
+	#[error("Only arrays and lists of numbers can be edited as text, found {found}")]
+	Unsupported { found: &'static str },
+	#[error("{token:?} is not a valid {expected}")]
+	InvalidValue { token: String, expected: &'static str, range: Range<usize> },
+}
+
+#[cfg(test)]
+mod tests {
+	use std::assert_matches::assert_matches;
+
+	use super::*;
+	use crate::{elements::compound::NbtCompound, workbench::tab::ChunkFileFormat};
+
+	fn parsed(snbt: &str, text: &str) -> Result<String, Option<Range<usize>>> {
+		let (_, element) = NbtElement::from_str(snbt).expect("Valid SNBT");
+		parse_values_text(&element, text).map(|element| element.to_string()).map_err(|e| match e {
+			ValuesTextError::InvalidValue { range, .. } => Some(range),
+			ValuesTextError::Unsupported { .. } => None,
+		})
+	}
+
+	#[test]
+	fn test_values_text() {
+		let (_, element) = NbtElement::from_str("[I;1,-2,3]").expect("Valid SNBT");
+		assert_eq!(values_as_text(&element, NumberBase::Decimal).as_deref(), Some("1, -2, 3"));
+		assert_eq!(values_as_text(&element, NumberBase::Hex).as_deref(), Some("0x1, 0xfffffffe, 0x3"));
+
+		assert_eq!(parsed("[I;1,2]", "4, 5,\n6 7,").as_deref(), Ok("[I;4,5,6,7]"));
+		assert_eq!(parsed("[B;]", "0xfb, 2b").as_deref(), Ok("[B;-5b,2b]"));
+		assert_eq!(parsed("[L;]", "").as_deref(), Ok("[L;]"));
+		assert_eq!(parsed("[1.5d]", "2, 3.25D").as_deref(), Ok("[2d,3.25d]"));
+		assert_eq!(parsed("[B;]", "1, 300, x"), Err(Some(3..6)));
+		assert_eq!(parsed("[I;]", "1i"), Err(Some(0..2)));
+		assert_eq!(parsed("[\"a\"]", "1"), Err(None));
+	}
+
+	#[test]
+	fn test_timestamp_text() {
+		let chunk = NbtChunk::new(NbtCompound::default(), (3, 4), ChunkFileFormat::default(), 1_709_210_096);
+		assert_eq!(timestamp_as_text(&chunk), "2024-02-29 12:34:56 UTC");
+		let edited = parse_values_text(&NbtElement::Chunk(chunk.clone()), "2024-03-01").expect("Valid date");
+		assert_eq!(edited.as_chunk().map(|chunk| (chunk.last_modified, chunk.x, chunk.z)), Some((1_709_251_200, 3, 4)));
+		assert_matches!(parse_timestamp_text(&chunk, "4294967296"), Err(ValuesTextError::InvalidValue { range, .. }) if range == (0..10));
+	}
+}
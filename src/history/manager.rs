@@ -1,59 +1,501 @@
-use std::fmt::{Debug, Formatter};
+use std::{
+	fmt::{Debug, Formatter},
+	time::Duration,
+};
+
 use anyhow::{Context, Result};
 
 use crate::{
+	config,
 	elements::element::NbtElement,
 	history::WorkbenchAction,
 	tree::MutableIndices,
-	util::LinkedQueue,
-	workbench::{tab::FilePath, HeldEntry},
+	util::{LinkedQueue, Timestamp},
+	workbench::{
+		HeldEntry,
+		tab::{FilePath, NbtFileFormat},
+	},
 };
 
 pub struct HistoryMananger {
-	undos: LinkedQueue<WorkbenchAction>,
-	redos: LinkedQueue<WorkbenchAction>,
-	unsaved_changes: bool,
+	undos: LinkedQueue<HistoryEntry>,
+	redos: LinkedQueue<HistoryEntry>,
+	/// The [position](Self::position) the file was last saved at, none once there's no undoing or redoing back to it
+	saved_at: Option<usize>,
+	/// Bumped on every change to the tree, so a background save can tell if it's still up to date
+	generation: usize,
+	/// Roughly how many bytes every action kept takes up, see [`WorkbenchAction::approximate_size`]
+	size: usize,
+	/// Whether the oldest actions were discarded to stay within the limits since it was last [taken](Self::take_discarded)
+	discarded: bool,
+}
+
+/// An action along with what it's listed as in the history panel
+struct HistoryEntry {
+	action: WorkbenchAction,
+	/// What the action did when it was first appended, kept as it's undone and redone
+	description: String,
+	/// When it was appended or last had an edit coalesced into it, entries pushed by undoing and redoing are never coalesced into
+	appended: Timestamp,
+	/// The action's [size](WorkbenchAction::approximate_size), worked out once as it's kept
+	size: usize,
 }
 
 impl Debug for HistoryMananger {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-		write!(f, "undos = {}, redos = {}, unsaved_changes = {}", self.undos.len(), self.redos.len(), self.unsaved_changes)
+		write!(f, "undos = {}, redos = {}, saved_at = {:?}, size = {}", self.undos.len(), self.redos.len(), self.saved_at, self.size)
 	}
 }
 
 impl HistoryMananger {
+	/// How soon another edit to the same value has to follow for both to undo as one
+	pub const COALESCE_WINDOW: Duration = Duration::from_secs(1);
+
 	#[must_use]
 	pub const fn new() -> Self {
 		Self {
 			undos: LinkedQueue::new(),
 			redos: LinkedQueue::new(),
-			unsaved_changes: false,
+			saved_at: Some(0),
+			generation: 0,
+			size: 0,
+			discarded: false,
+		}
+	}
+
+	pub fn on_save(&mut self) { self.saved_at = Some(self.position()); }
+
+	/// Only counts as saved if nothing changed since `generation`
+	pub fn on_save_at(&mut self, generation: usize) {
+		if self.generation == generation {
+			self.on_save();
 		}
 	}
 
-	pub fn on_save(&mut self) { self.unsaved_changes = false; }
+	#[must_use]
+	pub fn generation(&self) -> usize { self.generation }
 
+	/// Forgets where the file was saved if it's at `position` or past it, for when what's there is changed or dropped
+	fn forget_save_point_from(&mut self, position: usize) {
+		if self.saved_at.is_some_and(|saved_at| saved_at >= position) {
+			self.saved_at = None;
+		}
+	}
+
+	/// Wraps `action` to be pushed, counting it towards the [size](Self::size)
+	fn entry(&mut self, action: WorkbenchAction, description: String) -> HistoryEntry {
+		let size = action.approximate_size();
+		self.size += size;
+		HistoryEntry {
+			size,
+			action,
+			description,
+			appended: Timestamp::UNIX_EPOCH,
+		}
+	}
+
+	/// Adds `action` to be undone next, or folds it into the last action if it only edits the same value again within [`Self::COALESCE_WINDOW`]
+	///
+	/// Nothing is coalesced past the state last saved, so undoing can still get back to it.
 	pub fn append(&mut self, mut action: WorkbenchAction) {
 		action.shrink_to_fit();
-		self.undos.push(action);
+		// whatever was undone is replaced by it, saved or not
+		self.forget_save_point_from(self.position() + 1);
+		let coalesces = self.redos.is_empty()
+			&& self.has_unsaved_changes()
+			&& self.undos.get().is_some_and(|last| last.appended.elapsed() < Self::COALESCE_WINDOW && last.action.coalesces_with(&action));
+		if coalesces && let Some(last) = self.undos.get_mut() {
+			// it already undoes to the value from before the first edit
+			last.appended = Timestamp::now();
+		} else {
+			let description = action.describe();
+			let mut entry = self.entry(action, description);
+			entry.appended = Timestamp::now();
+			self.undos.push(entry);
+		}
+		self.clear_redos();
+		self.generation += 1;
+		self.evict(config::get_history_max_entries(), config::get_history_max_bytes());
+	}
+
+	fn clear_redos(&mut self) {
+		self.size -= self.redos.iter().map(|entry| entry.size).sum::<usize>();
 		self.redos.clear();
-		self.unsaved_changes = true;
 	}
 
-	pub fn undo<'m1, 'm2: 'm1>(&mut self, root: &mut NbtElement, mi: &'m1 mut MutableIndices<'m2>, path: &mut FilePath, held_entry: &mut Option<HeldEntry>) -> Result<()> {
-		let action = self.undos.pop().context("No actions to undo")?;
-		let undo_action = action.undo(root, mi, path, held_entry)?;
-		self.redos.push(undo_action);
+	/// Discards the oldest actions until there are at most `max_entries` taking up at most around `max_bytes`, though the last action is always kept
+	///
+	/// Every position moves down with where the history starts, so the save point does too, unless it was where the history started.
+	fn evict(&mut self, max_entries: usize, max_bytes: usize) {
+		while self.undos.len() > 1 && (self.undos.len() + self.redos.len() > max_entries || self.size > max_bytes) {
+			let Some(entry) = self.undos.pop_oldest() else { break };
+			self.size -= entry.size;
+			self.saved_at = self.saved_at.and_then(|saved_at| saved_at.checked_sub(1));
+			self.discarded = true;
+		}
+	}
+
+	/// Whether the oldest actions were discarded since this was last called
+	pub fn take_discarded(&mut self) -> bool { core::mem::take(&mut self.discarded) }
+
+	/// Roughly how many bytes the actions kept take up
+	#[must_use]
+	pub fn size(&self) -> usize { self.size }
+
+	pub fn undo<'m1, 'm2: 'm1>(&mut self, root: &mut NbtElement, mi: &'m1 mut MutableIndices<'m2>, path: &mut FilePath, format: &mut NbtFileFormat, held_entry: &mut Option<HeldEntry>) -> Result<()> {
+		let entry = self.undos.pop().context("No actions to undo")?;
+		self.size -= entry.size;
+		// left partway, which can't be told to match what was saved
+		let undo_action = entry.action.undo(root, mi, path, format, held_entry).inspect_err(|_| self.saved_at = None)?;
+		let entry = self.entry(undo_action, entry.description);
+		self.redos.push(entry);
+		self.generation += 1;
+		Ok(())
+	}
+
+	/// Undoes the last action without it being redoable, for when taking it back leaves things as if it never happened
+	pub fn undo_forgotten<'m1, 'm2: 'm1>(&mut self, root: &mut NbtElement, mi: &'m1 mut MutableIndices<'m2>, path: &mut FilePath, format: &mut NbtFileFormat, held_entry: &mut Option<HeldEntry>) -> Result<()> {
+		let entry = self.undos.pop().context("No actions to undo")?;
+		self.size -= entry.size;
+		// there's no redoing back to what it left
+		self.forget_save_point_from(self.position() + 1);
+		let _ = entry.action.undo(root, mi, path, format, held_entry).inspect_err(|_| self.saved_at = None)?;
+		self.generation += 1;
 		Ok(())
 	}
 
-	pub fn redo<'m1, 'm2: 'm1>(&mut self, root: &mut NbtElement, mi: &'m1 mut MutableIndices<'m2>, path: &mut FilePath, held_entry: &mut Option<HeldEntry>) -> Result<()> {
-		let action = self.redos.pop().context("No actions to undo")?;
-		let undo_action = action.undo(root, mi, path, held_entry)?;
-		self.undos.push(undo_action);
+	#[must_use]
+	pub fn last(&self) -> Option<&WorkbenchAction> { self.undos.get().map(|entry| &entry.action) }
+
+	/// Counts a change which isn't kept as an action, so it's only saved rather than undone
+	///
+	/// Since undoing won't take it back, nothing in the history matches what was saved anymore.
+	pub fn mark_changed(&mut self) {
+		self.saved_at = None;
+		self.generation += 1;
+	}
+
+	/// Counts a change as part of the last action, which already undoes it, rather than appending another
+	pub fn extend_last(&mut self) {
+		self.forget_save_point_from(self.position());
+		self.clear_redos();
+		self.generation += 1;
+	}
+
+	pub fn redo<'m1, 'm2: 'm1>(&mut self, root: &mut NbtElement, mi: &'m1 mut MutableIndices<'m2>, path: &mut FilePath, format: &mut NbtFileFormat, held_entry: &mut Option<HeldEntry>) -> Result<()> {
+		let entry = self.redos.pop().context("No actions to redo")?;
+		self.size -= entry.size;
+		let undo_action = entry.action.undo(root, mi, path, format, held_entry).inspect_err(|_| self.saved_at = None)?;
+		let entry = self.entry(undo_action, entry.description);
+		self.undos.push(entry);
+		self.generation += 1;
 		Ok(())
 	}
 
+	/// Whether undoing and redoing have left it anywhere but where it was last saved
 	#[must_use]
-	pub fn has_unsaved_changes(&self) -> bool { self.unsaved_changes }
+	pub fn has_unsaved_changes(&self) -> bool { self.saved_at != Some(self.position()) }
+
+	/// How many actions there are to undo, which is where [`Self::steps`] puts the current state
+	#[must_use]
+	pub fn position(&self) -> usize { self.undos.len() }
+
+	/// Every state that can be [sought](Self::seek) to, newest first, from the furthest redo down to before the first action kept
+	#[must_use]
+	pub fn steps(&self) -> Vec<HistoryStep<'_>> {
+		let position = self.position();
+		let mut steps = Vec::with_capacity(self.undos.len() + self.redos.len() + 1);
+		let redos = self.redos.iter().collect::<Vec<_>>();
+		for (idx, entry) in redos.into_iter().enumerate().rev() {
+			let position = position + idx + 1;
+			steps.push(HistoryStep {
+				position,
+				description: Some(&entry.description),
+				undone: true,
+				saved: self.saved_at == Some(position),
+			});
+		}
+		for (idx, entry) in self.undos.iter().enumerate() {
+			let position = position - idx;
+			steps.push(HistoryStep {
+				position,
+				description: Some(&entry.description),
+				undone: false,
+				saved: self.saved_at == Some(position),
+			});
+		}
+		steps.push(HistoryStep { position: 0, description: None, undone: false, saved: self.saved_at == Some(0) });
+		steps
+	}
+
+	/// Undoes or redoes until there are `position` actions to undo, as with a [step](HistoryStep::position) from [`Self::steps`]
+	///
+	/// # Errors
+	/// * If there aren't that many actions, or one of them couldn't be undone or redone, which stops it there
+	pub fn seek<'m1, 'm2: 'm1>(&mut self, position: usize, root: &mut NbtElement, mi: &'m1 mut MutableIndices<'m2>, path: &mut FilePath, format: &mut NbtFileFormat, held_entry: &mut Option<HeldEntry>) -> Result<()> {
+		while self.undos.len() > position {
+			self.undo(root, mi, path, format, held_entry)?;
+		}
+		while self.undos.len() < position {
+			self.redo(root, mi, path, format, held_entry)?;
+		}
+		Ok(())
+	}
+}
+
+/// A state in the history, as listed in the history panel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryStep<'a> {
+	/// How many actions there are to undo once it's [sought](HistoryMananger::seek) to
+	pub position: usize,
+	/// What the action getting to it did, none for where the history starts
+	pub description: Option<&'a str>,
+	/// Whether it's been undone and is now ahead of the current state
+	pub undone: bool,
+	/// Whether it's what was last saved
+	pub saved: bool,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		mutable_indices,
+		tree::{
+			OwnedMutableIndices,
+			actions::{add::add_element, remove::remove_element, rename::rename_element, replace::replace_element},
+			indices::OwnedIndices,
+		},
+	};
+
+	fn fixture(snbt: &str) -> (NbtElement, OwnedMutableIndices, FilePath, NbtFileFormat, HistoryMananger) {
+		let (_, root) = NbtElement::from_str(snbt).expect("Valid SNBT");
+		(root, OwnedMutableIndices::default(), FilePath::new("test.nbt").expect("Valid path"), NbtFileFormat::Nbt, HistoryMananger::new())
+	}
+
+	#[test]
+	fn test_undo_forgotten() {
+		let (mut root, mut state, mut path, mut format, mut history) = fixture("{a:1b,b:2b}");
+		let mi = mutable_indices!(state);
+
+		let result = remove_element(&mut root, OwnedIndices::from([1]), mi).expect("`b` can be removed");
+		let mut held_entry = Some(HeldEntry::from_indices(result.kv, result.indices));
+		history.append(WorkbenchAction::RemoveToHeldEntry);
+		assert!(history.has_unsaved_changes());
+
+		history.undo_forgotten(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("`b` can be put back");
+		assert_eq!(root.to_string(), "{a:1b,b:2b}");
+		assert!(held_entry.is_none());
+		assert!(history.last().is_none());
+		assert!(!history.has_unsaved_changes());
+		assert!(history.undo(&mut root, mi, &mut path, &mut format, &mut held_entry).is_err());
+		assert!(history.redo(&mut root, mi, &mut path, &mut format, &mut held_entry).is_err());
+	}
+
+	#[test]
+	fn test_undo_keeps_duplicate_keys() {
+		let (mut root, mut state, mut path, mut format, mut history) = fixture("{a:1b,a:3b,b:2b}");
+		let mi = mutable_indices!(state);
+		let mut held_entry = None;
+
+		history.append(remove_element(&mut root, OwnedIndices::from([1]), mi).expect("The second `a` can be removed").into_action());
+		assert_eq!(root.to_string(), "{a:1b,b:2b}");
+		history.undo(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("The second `a` can be put back");
+		assert_eq!(root.to_string(), "{a:1b,a:3b,b:2b}");
+
+		let (_, value) = NbtElement::from_str("4b").expect("Valid SNBT");
+		history.append(replace_element(&mut root, (Some("a".into()), value), OwnedIndices::from([1]), mi).expect("The second `a` can be replaced").into_action());
+		assert_eq!(root.to_string(), "{a:1b,a:4b,b:2b}");
+
+		history.append(rename_element(&mut root, OwnedIndices::from([1]), Some("c".into()), None, &mut path).expect("The second `a` can be renamed").into_action());
+		assert_eq!(root.to_string(), "{a:1b,c:4b,b:2b}");
+		history.undo(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("The rename can be undone");
+		assert_eq!(root.to_string(), "{a:1b,a:4b,b:2b}");
+		assert!(rename_element(&mut root, OwnedIndices::from([2]), Some("a".into()), None, &mut path).is_err());
+
+		// only what came off disk keeps the same key twice
+		let (_, value) = NbtElement::from_str("5b").expect("Valid SNBT");
+		add_element(&mut root, (Some("a".into()), value), OwnedIndices::from([3]), mi).expect("Another `a` can be added");
+		assert_eq!(root.to_string(), r#"{a:1b,a:4b,b:2b,"a - Copy":5b}"#);
+	}
+
+	#[test]
+	fn test_undo_format_and_path() {
+		let (mut root, mut state, mut path, mut format, mut history) = fixture("{a:1b}");
+		let mi = mutable_indices!(state);
+		let mut held_entry = None;
+
+		history.append(WorkbenchAction::Format { format: core::mem::replace(&mut format, NbtFileFormat::Snbt) });
+		assert!(history.has_unsaved_changes());
+		history.undo(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("The format can be switched back");
+		assert_eq!(format, NbtFileFormat::Nbt);
+		assert!(!history.has_unsaved_changes());
+		history.redo(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("The format can be switched again");
+		assert_eq!(format, NbtFileFormat::Snbt);
+		assert!(history.has_unsaved_changes());
+
+		// saved as another file
+		let old_path = path.set_path("test.snbt").expect("Valid path");
+		history.append(WorkbenchAction::Path { path: old_path });
+		history.on_save();
+		history.undo(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("The path can be put back");
+		assert_eq!(path.name(), "test.nbt");
+		assert!(history.has_unsaved_changes());
+		history.redo(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("The path can be changed again");
+		assert_eq!(path.name(), "test.snbt");
+		assert!(!history.has_unsaved_changes());
+	}
+
+	#[test]
+	fn test_history_steps() {
+		let (mut root, mut state, mut path, mut format, mut history) = fixture("{a:[1,2,3],Health:1b,b:2b,c:3b}");
+		let mi = mutable_indices!(state);
+		let mut held_entry = None;
+
+		history.append(rename_element(&mut root, OwnedIndices::from([1]), Some("HealF".into()), None, &mut path).expect("`Health` can be renamed").into_action());
+		history.on_save();
+		let (_, value) = NbtElement::from_str("[4]").expect("Valid SNBT");
+		history.append(replace_element(&mut root, (Some("a".into()), value), OwnedIndices::from([0]), mi).expect("`a` can be replaced").into_action());
+		let removed = [3, 2].map(|idx| remove_element(&mut root, OwnedIndices::from([idx]), mi).expect("`b` and `c` can be removed").into_action());
+		history.append(WorkbenchAction::bulk(removed).expect("Two removals"));
+		assert_eq!(root.to_string(), "{a:[4],HealF:1b}");
+
+		let describe = |history: &HistoryMananger| history.steps().into_iter().map(|step| (step.position, step.description.map(str::to_owned), step.undone, step.saved)).collect::<Vec<_>>();
+		assert_eq!(describe(&history), [
+			(3, Some("Deleted 2 elements".to_owned()), false, false),
+			(2, Some("Replaced 3 values".to_owned()), false, false),
+			(1, Some("Renamed 'Health'".to_owned()), false, true),
+			(0, None, false, false),
+		]);
+
+		history.seek(1, &mut root, mi, &mut path, &mut format, &mut held_entry).expect("Can go back to the save");
+		assert_eq!(root.to_string(), "{a:[1,2,3],HealF:1b,b:2b,c:3b}");
+		assert!(!history.has_unsaved_changes());
+		assert_eq!(describe(&history), [
+			(3, Some("Deleted 2 elements".to_owned()), true, false),
+			(2, Some("Replaced 3 values".to_owned()), true, false),
+			(1, Some("Renamed 'Health'".to_owned()), false, true),
+			(0, None, false, false),
+		]);
+
+		history.seek(3, &mut root, mi, &mut path, &mut format, &mut held_entry).expect("Can go forward again");
+		assert_eq!(root.to_string(), "{a:[4],HealF:1b}");
+		assert!(history.has_unsaved_changes());
+		history.seek(0, &mut root, mi, &mut path, &mut format, &mut held_entry).expect("Can go back to the start");
+		assert_eq!(root.to_string(), "{a:[1,2,3],Health:1b,b:2b,c:3b}");
+		assert!(history.seek(4, &mut root, mi, &mut path, &mut format, &mut held_entry).is_err());
+		assert_eq!(history.position(), 3);
+	}
+
+	#[test]
+	fn test_coalescing() {
+		let (mut root, mut state, mut path, mut format, mut history) = fixture("{a:1b,b:2b}");
+		let mi = mutable_indices!(state);
+		let mut held_entry = None;
+		let mut edit = |history: &mut HistoryMananger, root: &mut NbtElement, idx: usize, value: &str| history.append(rename_element(root, OwnedIndices::from([idx]), None, Some(value.to_owned()), &mut path).expect("Valid value").into_action());
+
+		for value in ["3", "4", "5"] {
+			edit(&mut history, &mut root, 0, value);
+		}
+		assert_eq!(history.position(), 1);
+		assert_eq!(root.to_string(), "{a:5b,b:2b}");
+
+		// another element, or the same one too late, is its own step
+		edit(&mut history, &mut root, 1, "6");
+		assert_eq!(history.position(), 2);
+		history.undos.get_mut().expect("Just appended").appended = Timestamp::now() - HistoryMananger::COALESCE_WINDOW;
+		edit(&mut history, &mut root, 1, "7");
+		assert_eq!(history.position(), 3);
+
+		// never past a save
+		history.on_save();
+		edit(&mut history, &mut root, 1, "8");
+		assert_eq!(history.position(), 4);
+		history.undo(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("The edit after saving can be undone");
+		assert_eq!(root.to_string(), "{a:5b,b:7b}");
+		assert!(!history.has_unsaved_changes());
+
+		// nor structural actions
+		history.append(remove_element(&mut root, OwnedIndices::from([1]), mi).expect("`b` can be removed").into_action());
+		history.append(remove_element(&mut root, OwnedIndices::from([0]), mi).expect("`a` can be removed").into_action());
+		assert_eq!(history.position(), 5);
+
+		history.seek(1, &mut root, mi, &mut path, &mut format, &mut held_entry).expect("Can go back to the first burst");
+		assert_eq!(root.to_string(), "{a:5b,b:2b}");
+		history.undo(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("The burst undoes at once");
+		assert_eq!(root.to_string(), "{a:1b,b:2b}");
+	}
+
+	#[test]
+	fn test_eviction() {
+		let (mut root, mut state, mut path, mut format, mut history) = fixture("{a:1b,b:2b,c:3b,d:4b}");
+		let mi = mutable_indices!(state);
+		let mut held_entry = None;
+
+		history.append(remove_element(&mut root, OwnedIndices::from([3]), mi).expect("`d` can be removed").into_action());
+		history.on_save();
+		for idx in [2, 1] {
+			history.append(remove_element(&mut root, OwnedIndices::from([idx]), mi).expect("Can be removed").into_action());
+		}
+		let size = history.size();
+		assert!(size > 0);
+		assert!(!history.take_discarded());
+
+		history.evict(2, usize::MAX);
+		assert!(history.take_discarded());
+		assert!(!history.take_discarded());
+		assert_eq!(history.position(), 2);
+		assert!(history.size() < size);
+		// the save is still where the history starts
+		history.seek(0, &mut root, mi, &mut path, &mut format, &mut held_entry).expect("Can go back to the start");
+		assert_eq!(root.to_string(), "{a:1b,b:2b,c:3b}");
+		assert!(!history.has_unsaved_changes());
+
+		// and by bytes, always keeping the last action
+		history.seek(2, &mut root, mi, &mut path, &mut format, &mut held_entry).expect("Can go forward again");
+		history.evict(usize::MAX, 0);
+		assert_eq!(history.position(), 1);
+		assert_eq!(history.size(), history.undos.iter().map(|entry| entry.size).sum::<usize>());
+		// past the save, so nothing left is clean
+		history.undo(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("The last action is kept");
+		assert_eq!(root.to_string(), "{a:1b,b:2b}");
+		assert!(history.has_unsaved_changes());
+		assert!(history.steps().iter().all(|step| !step.saved));
+		assert!(history.undo(&mut root, mi, &mut path, &mut format, &mut held_entry).is_err());
+	}
+
+	#[test]
+	fn test_save_point() {
+		let (mut root, mut state, mut path, mut format, mut history) = fixture("{a:1b,b:2b,c:3b}");
+		let mi = mutable_indices!(state);
+		let mut held_entry = None;
+		assert!(!history.has_unsaved_changes());
+
+		// save -> undo -> redo is clean again
+		history.append(remove_element(&mut root, OwnedIndices::from([2]), mi).expect("`c` can be removed").into_action());
+		history.on_save();
+		assert!(!history.has_unsaved_changes());
+		history.undo(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("`c` can be put back");
+		assert!(history.has_unsaved_changes());
+		history.redo(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("`c` can be removed again");
+		assert!(!history.has_unsaved_changes());
+
+		// save -> undo -> new edit is never clean again
+		history.undo(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("`c` can be put back");
+		history.append(remove_element(&mut root, OwnedIndices::from([1]), mi).expect("`b` can be removed").into_action());
+		assert_eq!(root.to_string(), "{a:1b,c:3b}");
+		assert!(history.has_unsaved_changes());
+		history.undo(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("`b` can be put back");
+		assert!(history.has_unsaved_changes());
+		history.redo(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("`b` can be removed again");
+		assert!(history.has_unsaved_changes());
+		assert!(history.steps().iter().all(|step| !step.saved));
+
+		// nor after a change kept outside of it
+		history.on_save();
+		history.mark_changed();
+		history.undo(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("`b` can be put back");
+		history.redo(&mut root, mi, &mut path, &mut format, &mut held_entry).expect("`b` can be removed again");
+		assert!(history.has_unsaved_changes());
+	}
 }
@@ -1,3 +1,5 @@
+use std::path::{Path, PathBuf};
+
 use anyhow::Result;
 use compact_str::CompactString;
 use thiserror::Error;
@@ -7,9 +9,9 @@ use crate::{
 	tree::{
 		MutableIndices,
 		actions::{
-			add::{AddElementError, AddElementResult, add_element},
+			add::{AddElementError, AddElementResult, restore_element},
 			remove::{RemoveElementError, RemoveElementResult, remove_element},
-			rename::{RenameElementError, rename_element},
+			rename::{RenameElementError, restore_name},
 			reorder::{ReorderElementError, reorder_element},
 			replace::{ReplaceElementError, ReplaceElementResult, replace_element},
 			swap::{SwapElementErrorSameDepth, swap_element_same_depth},
@@ -17,7 +19,10 @@ use crate::{
 		indices::OwnedIndices,
 	},
 	util::LinkedQueue,
-	workbench::{tab::FilePath, HeldEntry},
+	workbench::{
+		HeldEntry,
+		tab::{FilePath, FilePathError, NbtFileFormat},
+	},
 };
 
 pub mod manager;
@@ -66,6 +71,14 @@ pub enum WorkbenchAction {
 		held_entry: HeldEntry,
 	},
 	CreateHeldEntry,
+	/// The tab's format was switched from this one
+	Format {
+		format: NbtFileFormat,
+	},
+	/// The tab was saved as another file, moving it from this path
+	Path {
+		path: PathBuf,
+	},
 	Bulk {
 		actions: Box<[Self]>,
 	},
@@ -84,6 +97,8 @@ impl WorkbenchAction {
 			Self::RemoveToHeldEntry => (),
 			Self::DiscardHeldEntry { .. } => (),
 			Self::CreateHeldEntry => (),
+			Self::Format { .. } => (),
+			Self::Path { path } => path.shrink_to_fit(),
 			Self::Bulk { actions } =>
 				for action in actions {
 					action.shrink_to_fit();
@@ -91,12 +106,12 @@ impl WorkbenchAction {
 		}
 	}
 
-	pub fn undo<'m1, 'm2: 'm1>(self, root: &mut NbtElement, mi: &'m1 mut MutableIndices<'m2>, path: &mut FilePath, held_entry: &mut Option<HeldEntry>) -> Result<Self, WorkbenchActionError> {
+	pub fn undo<'m1, 'm2: 'm1>(self, root: &mut NbtElement, mi: &'m1 mut MutableIndices<'m2>, path: &mut FilePath, format: &mut NbtFileFormat, held_entry: &mut Option<HeldEntry>) -> Result<Self, WorkbenchActionError> {
 		Ok(match self {
 			Self::Add { indices } => remove_element(root, indices, mi)?.into_action(),
-			Self::Remove { kv, indices } => add_element(root, kv, indices, mi)?.into_action(),
+			Self::Remove { kv, indices } => restore_element(root, kv, indices, mi)?.into_action(),
 			Self::Replace { indices, kv: value } => replace_element(root, value, indices, mi)?.into_action(),
-			Self::Rename { indices, key, value } => rename_element(root, indices, key, value, path)?.into_action(),
+			Self::Rename { indices, key, value } => restore_name(root, indices, key, value, path)?.into_action(),
 			Self::Swap { parent, a, b } => swap_element_same_depth(root, parent, a, b, mi)?.into_action(),
 			Self::Reorder { indices, mapping } => reorder_element(root, indices, mapping, mi)?.into_action(),
 			Self::AddFromHeldEntry { indices, mut indices_history, old_kv } => {
@@ -115,13 +130,13 @@ impl WorkbenchAction {
 					(indices, kv)
 				};
 				indices_history.push(indices);
-				*held_entry = Some(HeldEntry { kv, indices_history });
+				*held_entry = Some(HeldEntry { kv, indices_history, pasted: None, copy: false });
 				Self::RemoveToHeldEntry
 			}
 			Self::RemoveToHeldEntry => {
-				let HeldEntry { kv, mut indices_history } = held_entry.take().ok_or(WorkbenchActionError::RemoveToHeldEntry(RemoveToHeldEntryError::ExpectedHeldEntry))?;
+				let HeldEntry { kv, mut indices_history, .. } = held_entry.take().ok_or(WorkbenchActionError::RemoveToHeldEntry(RemoveToHeldEntryError::ExpectedHeldEntry))?;
 				if let Some(indices) = indices_history.pop() {
-					let AddElementResult { indices, old_kv } = add_element(root, kv, indices, mi)?;
+					let AddElementResult { indices, old_kv } = restore_element(root, kv, indices, mi)?;
 					Self::AddFromHeldEntry { indices, indices_history, old_kv }
 				} else {
 					Self::DiscardHeldEntry { held_entry: HeldEntry::from_aether(kv) }
@@ -138,18 +153,95 @@ impl WorkbenchAction {
 				let held_entry = held_entry.take().ok_or(WorkbenchActionError::CreateHeldEntry(CreateHeldEntryError::ExpectedHeldEntry))?;
 				Self::DiscardHeldEntry { held_entry }
 			}
+			Self::Format { format: old_format } => Self::Format { format: core::mem::replace(format, old_format) },
+			Self::Path { path: old_path } => Self::Path { path: path.set_path(old_path)? },
 			Self::Bulk { actions } => Self::Bulk {
 				actions: actions
 					.into_vec()
 					.into_iter()
 					.rev()
-					.map(|action| action.undo(root, mi, path, held_entry))
+					.map(|action| action.undo(root, mi, path, format, held_entry))
 					.collect::<Result<Vec<_>, WorkbenchActionError>>()?
 					.into_boxed_slice(),
 			},
 		})
 	}
 
+	/// Roughly how many bytes it keeps in memory, which is mostly whatever elements it holds onto to put back
+	#[must_use]
+	pub fn approximate_size(&self) -> usize {
+		let indices_size = |indices: &OwnedIndices| indices.len() * size_of::<usize>();
+		let kv_size = |(key, value): &NbtElementAndKey| key.as_ref().map_or(0, CompactString::len) + value.approximate_size();
+		size_of::<Self>()
+			+ match self {
+				Self::Add { indices } => indices_size(indices),
+				Self::Remove { kv, indices } | Self::Replace { indices, kv } => indices_size(indices) + kv_size(kv),
+				Self::Rename { indices, key, value } => indices_size(indices) + key.as_ref().map_or(0, CompactString::len) + value.as_ref().map_or(0, String::len),
+				Self::Swap { parent, .. } => indices_size(parent),
+				Self::Reorder { indices, mapping } => indices_size(indices) + mapping.len() * size_of::<usize>(),
+				Self::AddFromHeldEntry { indices, indices_history, old_kv } => indices_size(indices) + indices_history.iter().map(indices_size).sum::<usize>() + old_kv.as_ref().map_or(0, kv_size),
+				Self::DiscardHeldEntry { held_entry } => kv_size(&held_entry.kv) + held_entry.indices_history.iter().map(indices_size).sum::<usize>() + held_entry.pasted.iter().flatten().map(kv_size).sum::<usize>(),
+				Self::RemoveToHeldEntry | Self::CreateHeldEntry | Self::Format { .. } => 0,
+				Self::Path { path } => path.as_os_str().len(),
+				Self::Bulk { actions } => actions.iter().map(Self::approximate_size).sum(),
+			}
+	}
+
+	/// Whether `next` only edits the same value again, so undoing this can take back both
+	///
+	/// Anything which adds, removes or moves elements is always its own step.
+	#[must_use]
+	pub fn coalesces_with(&self, next: &Self) -> bool {
+		matches!((self, next), (Self::Rename { indices: a, key: None, value: Some(_) }, Self::Rename { indices: b, key: None, value: Some(_) }) if a.iter().eq(b.iter()))
+	}
+
+	/// What was done, in a few words for the history panel
+	///
+	/// Only makes sense for an action as it was first appended, since undoing one gives back its opposite.
+	#[must_use]
+	pub fn describe(&self) -> String {
+		match self {
+			Self::Add { .. } => "Added an element".to_owned(),
+			Self::Remove { kv: (Some(key), _), .. } => format!("Deleted '{key}'"),
+			Self::Remove { kv: (None, value), .. } => format!("Deleted {}", with_article(value.display_name())),
+			Self::Rename { indices, key: Some(path), .. } if indices.is_root() => format!("Renamed the file from '{}'", Path::new(path.as_str()).file_name().map_or(path.as_str().into(), |name| name.to_string_lossy())),
+			Self::Rename { key: Some(key), .. } => format!("Renamed '{key}'"),
+			Self::Rename { value: Some(value), .. } => format!("Changed a value from {value}"),
+			Self::Rename { .. } => "Renamed an element".to_owned(),
+			Self::Swap { .. } => "Moved an element".to_owned(),
+			Self::Replace { indices, .. } if indices.is_root() => "Replaced the root".to_owned(),
+			Self::Replace { kv: (_, value), .. } if value.is_list() || value.is_byte_array() || value.is_int_array() || value.is_long_array() => format!("Replaced {} values", value.len().unwrap_or(0)),
+			Self::Replace { kv: (Some(key), _), .. } => format!("Replaced '{key}'"),
+			Self::Replace { kv: (None, value), .. } => format!("Replaced {}", with_article(value.display_name())),
+			Self::Reorder { mapping, .. } => format!("Sorted {} elements", mapping.len()),
+			Self::AddFromHeldEntry { old_kv: Some(_), .. } => "Dropped the held element over another".to_owned(),
+			Self::AddFromHeldEntry { .. } => "Dropped the held element".to_owned(),
+			Self::RemoveToHeldEntry => "Picked up an element".to_owned(),
+			Self::DiscardHeldEntry { .. } => "Discarded the held element".to_owned(),
+			Self::CreateHeldEntry => "Held a new element".to_owned(),
+			Self::Format { format } => format!("Switched the format from {format}"),
+			Self::Path { path } => format!("Saved as another file, from '{}'", path.file_name().unwrap_or(path.as_os_str()).to_string_lossy()),
+			Self::Bulk { actions } => match actions.first().and_then(Self::bulk_summary) {
+				Some((verb, noun)) if actions.iter().all(|action| action.bulk_summary() == Some((verb, noun))) => format!("{verb} {} {noun}", actions.len()),
+				_ => format!("Made {} changes", actions.len()),
+			},
+		}
+	}
+
+	/// How a bulk action made only of this kind of action is summarized, as what was done and to what
+	fn bulk_summary(&self) -> Option<(&'static str, &'static str)> {
+		Some(match self {
+			Self::Add { .. } => ("Added", "elements"),
+			Self::Remove { .. } => ("Deleted", "elements"),
+			Self::Rename { value: Some(_), .. } => ("Replaced", "values"),
+			Self::Rename { .. } => ("Renamed", "keys"),
+			Self::Swap { .. } => ("Moved", "elements"),
+			Self::Replace { .. } => ("Replaced", "elements"),
+			Self::Reorder { .. } => ("Sorted", "elements"),
+			_ => return None,
+		})
+	}
+
 	#[must_use]
 	pub fn bulk(actions: impl Into<Box<[WorkbenchAction]>>) -> Option<Self> {
 		let actions = actions.into();
@@ -164,6 +256,11 @@ impl WorkbenchAction {
 	}
 }
 
+/// `name` with "a" or "an" in front, whichever it's read with
+fn with_article(name: &str) -> String {
+	if name.starts_with(['A', 'E', 'I', 'O', 'U']) { format!("an {name}") } else { format!("a {name}") }
+}
+
 #[derive(Error, Debug)]
 pub enum WorkbenchActionError {
 	#[error(transparent)]
@@ -186,6 +283,8 @@ pub enum WorkbenchActionError {
 	DiscardHeldEntry(#[from] DiscardHeldEntryError),
 	#[error(transparent)]
 	CreateHeldEntry(#[from] CreateHeldEntryError),
+	#[error(transparent)]
+	Path(#[from] FilePathError),
 }
 
 #[derive(Error, Debug)]
@@ -35,6 +35,7 @@ pub mod elements;
 pub mod history;
 pub mod render;
 pub mod serialization;
+#[cfg(not(target_arch = "wasm32"))] pub mod single_instance;
 pub mod tree;
 pub mod util;
 #[cfg(target_arch = "wasm32")] pub mod wasm;
@@ -80,9 +81,10 @@ macro_rules! hash {
 #[macro_export]
 macro_rules! get_interaction_information {
 	($self:ident) => {{
+		let mouse = $self.pane_mouse();
 		let tab = $self.tabs.active_tab_mut();
 		let consts = tab.consts();
-		$crate::workbench::Workbench::get_interaction_information_raw(consts, $self.mouse, &mut tab.root)
+		$crate::workbench::Workbench::get_interaction_information_raw(consts, mouse, &mut tab.root)
 	}};
 }
 
@@ -105,7 +107,7 @@ macro_rules! log {
 #[macro_export]
 macro_rules! mutable_indices {
 	($tab:ident) => {
-		&mut $crate::tree::MutableIndices::new(&mut $tab.subscription, &mut $tab.selected_text, &mut $tab.bookmarks)
+		&mut $crate::tree::MutableIndices::new(&mut $tab.subscription, &mut $tab.selected_text, &mut $tab.bookmarks, &mut $tab.selection, &mut $tab.boolean_overrides)
 	};
 }
 
@@ -129,8 +131,6 @@ pub fn window_properties() -> parking_lot::MutexGuard<'static, render::window::W
 /// * smart screen
 /// * add multi-cursor
 /// * [chunk](elements::chunk::NbtChunk) section rendering
-/// # Minor Features
-/// * [`last_modified`](elements::chunk::NbtChunk) field actually gets the ability to be set
 /// # Major Features
 /// * macros
 #[cfg(not(target_arch = "wasm32"))]
@@ -143,15 +143,23 @@ pub fn main() -> ! {
 	config::read();
 
 	match std::env::args().nth(1).as_deref() {
+		Some("convert") => cli::convert(),
 		Some("find") => cli::find(),
 		Some("replace") => cli::replace(),
 		Some("reformat") => cli::reformat(),
+		Some("validate") => cli::validate(),
 		Some("--version" | "-v") => {
 			println!("{}", env!("CARGO_PKG_VERSION"));
 			std::process::exit(0);
 		}
 		Some("-?" | "/?" | "--help" | "-h") => cli::help(),
-		_ => pollster::block_on(render::window::run()),
+		_ => {
+			let paths = workbench::Workbench::file_arguments();
+			if !paths.is_empty() && !workbench::Workbench::is_new_window() && single_instance::forward(&paths) {
+				std::process::exit(0);
+			}
+			pollster::block_on(render::window::run())
+		}
 	}
 }
 
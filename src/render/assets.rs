@@ -14,7 +14,7 @@ pub const HEADER_SIZE: usize = 48;
 pub const DARK_ATLAS_ENCODED: &[u8] = include_bytes!("../assets/dark_atlas.png");
 pub const LIGHT_ATLAS_ENCODED: &[u8] = include_bytes!("../assets/light_atlas.png");
 pub const ATLAS_WIDTH: usize = 256;
-pub const ATLAS_HEIGHT: usize = 256;
+pub const ATLAS_HEIGHT: usize = 272;
 pub const UNICODE_LEN: usize = 1_818_624;
 
 pub const ICON_WIDTH: usize = 64;
@@ -64,10 +64,13 @@ pub const SAVE_GRAYSCALE_UV: Vec2u = Vec2u::new(32, 64);
 pub const NBT_FILE_TYPE_UV: Vec2u = Vec2u::new(32, 80);
 pub const GZIP_FILE_TYPE_UV: Vec2u = Vec2u::new(48, 80);
 pub const ZLIB_FILE_TYPE_UV: Vec2u = Vec2u::new(64, 80);
+pub const ZSTD_FILE_TYPE_UV: Vec2u = Vec2u::new(184, 144);
+pub const LZ4_FILE_TYPE_UV: Vec2u = Vec2u::new(200, 144);
 pub const SNBT_FILE_TYPE_UV: Vec2u = Vec2u::new(80, 80);
 pub const MCA_FILE_TYPE_UV: Vec2u = Vec2u::new(96, 80);
 pub const LITTLE_ENDIAN_NBT_FILE_TYPE_UV: Vec2u = Vec2u::new(152, 160);
 pub const LITTLE_ENDIAN_HEADER_NBT_FILE_TYPE_UV: Vec2u = Vec2u::new(168, 160);
+pub const READ_ONLY_UV: Vec2u = Vec2u::new(0, 208);
 pub const OPEN_FOLDER_UV: Vec2u = Vec2u::new(112, 80);
 pub const UNSELECTED_TOGGLE_ON_UV: Vec2u = Vec2u::new(0, 64);
 pub const UNSELECTED_TOGGLE_OFF_UV: Vec2u = Vec2u::new(8, 64);
@@ -88,8 +91,16 @@ pub const LIGHT_STRIPE_UV: Vec2u = Vec2u::new(96, 96);
 pub const DARK_STRIPE_UV: Vec2u = Vec2u::new(96, 112);
 pub const HOVERED_STRIPE_UV: Vec2u = Vec2u::new(112, 128);
 pub const INVALID_STRIPE_UV: Vec2u = Vec2u::new(112, 112);
+/// 8x8, unlike the other stripes
+pub const ADDED_STRIPE_UV: Vec2u = Vec2u::new(0, 256);
+/// 8x8, unlike the other stripes
+pub const CHANGED_STRIPE_UV: Vec2u = Vec2u::new(8, 256);
+/// 8x8, unlike the other stripes
+pub const SELECTED_STRIPE_UV: Vec2u = Vec2u::new(16, 256);
 pub const COPY_RAW_UV: Vec2u = Vec2u::new(3, 131);
 pub const COPY_FORMATTED_UV: Vec2u = Vec2u::new(19, 131);
+pub const COPY_JSON_UV: Vec2u = Vec2u::new(219, 147);
+pub const COPY_PATH_UV: Vec2u = Vec2u::new(235, 147);
 #[cfg(not(target_arch = "wasm32"))]
 pub const OPEN_ARRAY_IN_HEX_UV: Vec2u = Vec2u::new(35, 131);
 #[cfg(not(target_arch = "wasm32"))]
@@ -132,6 +143,8 @@ pub const COMPOUND_GHOST_UV: Vec2u = Vec2u::new(48, 48);
 pub const INT_ARRAY_GHOST_UV: Vec2u = Vec2u::new(112, 16);
 pub const LONG_ARRAY_GHOST_UV: Vec2u = Vec2u::new(0, 48);
 pub const CHUNK_GHOST_UV: Vec2u = Vec2u::new(64, 48);
+/// Drawn over the icon of a chunk which couldn't be read, the same translucent red as [`STEAL_ANIMATION_OVERLAY_UV`]
+pub const CORRUPTED_CHUNK_UV: Vec2u = Vec2u::new(64, 144);
 
 pub const ALERT_UV: Vec2u = Vec2u::new(112, 144);
 pub const NOTIFICATION_UV: Vec2u = Vec2u::new(112, 184);
@@ -155,6 +168,7 @@ pub const AND_SELECTION_OPERATION_UV: Vec2u = Vec2u::new(48, 176);
 pub const OR_SELECTION_OPERATION_UV: Vec2u = Vec2u::new(32, 176);
 pub const XOR_SELECTION_OPERATION_UV: Vec2u = Vec2u::new(16, 176);
 pub const INVERT_BOOKMARKS_UV: Vec2u = Vec2u::new(51, 163);
+pub const DELETE_CORRUPTED_CHUNK_UV: Vec2u = Vec2u::new(24, 256);
 pub const REPLACE_SELECTION_OPERATION_UV: Vec2u = Vec2u::new(64, 160);
 pub const REPLACE_BY_BOOKMARKED_LINES: Vec2u = Vec2u::new(0, 176);
 pub const REPLACE_BY_SEARCH_HITS: Vec2u = Vec2u::new(0, 192);
@@ -170,23 +184,66 @@ pub enum ZOffset {
 	JUST_OVERLAPPING_BASE_Z      = 81,
 	BASE_TEXT_Z                  = 90,
 	JUST_OVERLAPPING_BASE_TEXT_Z = 91,
+	UNSAVED_TAB_DOT_Z            = 92,
 	TOGGLE_Z                     = 100,
 	LINE_NUMBER_Z                = 130,
 	LINE_NUMBER_CONNECTOR_Z      = 131,
 	BOOKMARK_Z                   = 140,
 	JUST_OVERLAPPING_BOOKMARK_Z  = 141,
+	CHUNK_MARKER_Z               = 142,
+	HOVERED_GRID_CHUNK_Z         = 143,
 	SELECTED_TEXT_Z              = 170,
 	SELECTED_TEXT_SELECTION_Z    = 171,
+	KEY_SUGGESTIONS_Z            = 172,
+	KEY_SUGGESTIONS_SELECTION_Z  = 173,
+	KEY_SUGGESTIONS_TEXT_Z       = 174,
+	BREADCRUMBS_Z                = 175,
+	BREADCRUMBS_TEXT_Z           = 176,
+	HEX_VIEW_Z                   = 177,
+	HEX_VIEW_SELECTION_Z         = 178,
+	HEX_VIEW_TEXT_Z              = 179,
+	HISTORY_PANEL_Z              = 180,
+	HISTORY_PANEL_SELECTION_Z    = 181,
+	HISTORY_PANEL_TEXT_Z         = 182,
 	ACTION_WHEEL_Z               = 190,
 	SCROLLBAR_BOOKMARK_Z         = 199,
 	SCROLLBAR_Z                  = 200,
 	REPLACE_BOX_Z                = 210,
 	REPLACE_BOX_SELECTION_Z      = 211,
+	STRING_EDITOR_Z              = 212,
+	STRING_EDITOR_SELECTION_Z    = 213,
+	STRING_EDITOR_TEXT_Z         = 214,
 	HELD_ENTRY_Z                 = 220,
 	HELD_ENTRY_TEXT_Z            = 221,
+	HELD_TAB_Z                   = 222,
+	HELD_TAB_TEXT_Z              = 223,
+	HELD_UNSAVED_TAB_DOT_Z       = 224,
+	ELEMENT_MENU_Z               = 225,
+	ELEMENT_MENU_SELECTION_Z     = 226,
+	ELEMENT_MENU_TEXT_Z          = 227,
+	RELOAD_BANNER_Z              = 230,
+	RELOAD_BANNER_TEXT_Z         = 231,
+	RECENT_FILES_Z               = 232,
+	RECENT_FILES_SELECTION_Z     = 233,
+	RECENT_FILES_TEXT_Z          = 234,
+	TAB_MENU_Z                   = 235,
+	TAB_MENU_SELECTION_Z         = 236,
+	TAB_MENU_TEXT_Z              = 237,
 	NOTIFICATION_Z               = 240,
 	NOTIFICATION_TEXT_Z          = 241,
+	TAB_CYCLE_Z                  = 242,
+	TAB_CYCLE_SELECTION_Z        = 243,
+	TAB_CYCLE_TEXT_Z             = 244,
+	CLOSE_PROMPT_Z               = 245,
+	CLOSE_PROMPT_TEXT_Z          = 246,
+	DROP_OVERLAY_Z               = 247,
+	DROP_OVERLAY_TEXT_Z          = 248,
+	KEY_CONFLICT_PROMPT_Z        = 249,
+	KEY_CONFLICT_PROMPT_TEXT_Z   = 250,
 	DEBUG_TEXT_Z                 = 251,
+	CLIPBOARD_RING_Z             = 252,
+	CLIPBOARD_RING_SELECTION_Z   = 253,
+	CLIPBOARD_RING_TEXT_Z        = 254,
 	TOOLTIP_Z                    = 255,
 }
 
@@ -203,7 +260,7 @@ pub fn atlas(theme: Theme) -> &'static [u8] {
 }
 
 pub fn icon() -> Vec<u8> {
-	let original = match (Timestamp::now().elapsed().as_millis() & 7) as u8 {
+	let original = match (Timestamp::now().since_epoch().as_millis() & 7) as u8 {
 		// it's a good random only because its used once
 		0 => OTHERSIDE_MUSIC_DISC_ICON,
 		1 => PIGSTEP_MUSIC_DISC_ICON,
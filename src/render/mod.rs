@@ -6,12 +6,15 @@ pub mod vertex_buffer_builder;
 pub mod widget;
 pub mod window;
 
+use std::ops::Range;
+
 use crate::{
-	elements::element::NbtElement,
+	config,
+	elements::{diff::Difference, element::NbtElement, float_display::FloatDisplay, number_base::NumberBase, region_heatmap::RegionHeatmap, validate},
 	render::{
 		assets::{
-			BASE_TEXT_Z, BASE_Z, BOOKMARK_UV, BOOKMARK_Z, END_LINE_NUMBER_SEPARATOR_UV, HEADER_SIZE, HIDDEN_BOOKMARK_UV, INSERTION_CHUNK_UV, INSERTION_UV, INVALID_STRIPE_UV, LINE_NUMBER_SEPARATOR_UV, LINE_NUMBER_Z, SCROLLBAR_BOOKMARK_Z,
-			SELECTED_TOGGLE_OFF_UV, SELECTED_TOGGLE_ON_UV, TEXT_UNDERLINE_UV, TOGGLE_Z, UNSELECTED_TOGGLE_OFF_UV, UNSELECTED_TOGGLE_ON_UV,
+			BASE_TEXT_Z, BASE_Z, BOOKMARK_UV, BOOKMARK_Z, CHANGED_STRIPE_UV, END_LINE_NUMBER_SEPARATOR_UV, HEADER_SIZE, HIDDEN_BOOKMARK_UV, INSERTION_CHUNK_UV, INSERTION_UV, INVALID_STRIPE_UV, LINE_NUMBER_SEPARATOR_UV, LINE_NUMBER_Z, SCROLLBAR_BOOKMARK_Z,
+			SELECTED_STRIPE_UV, SELECTED_TOGGLE_OFF_UV, SELECTED_TOGGLE_ON_UV, TEXT_UNDERLINE_UV, TOGGLE_Z, UNSELECTED_TOGGLE_OFF_UV, UNSELECTED_TOGGLE_ON_UV,
 		},
 		color::TextColor,
 		vertex_buffer_builder::VertexBufferBuilder,
@@ -40,12 +43,19 @@ pub struct RenderContext<'a> {
 	// sorted least to greatest
 	line_numbers: Vec<usize>,
 	freehand: bool,
+	number_base: NumberBase,
+	region_heatmap: RegionHeatmap,
+	bytes_as_booleans: bool,
+	/// From [`BooleanOverrides::true_lines`](crate::workbench::boolean_overrides::BooleanOverrides::true_lines)
+	boolean_lines: Vec<(usize, bool)>,
+	float_display: FloatDisplay,
+	float_decimals: usize,
 }
 
 impl<'a> RenderContext<'a> {
 	#[must_use]
 	#[allow(clippy::type_complexity)] // forbidden is fine to be like that, c'mon
-	pub fn new(selected_text_y: Option<usize>, selected_key: Option<Box<str>>, selected_value: Option<Box<str>>, selecting_key: bool, ghost: Option<(&'a NbtElement, Vec2u)>, left_margin: usize, mouse: Vec2u, freehand: bool) -> Self {
+	pub fn new(selected_text_y: Option<usize>, selected_key: Option<Box<str>>, selected_value: Option<Box<str>>, selecting_key: bool, ghost: Option<(&'a NbtElement, Vec2u)>, left_margin: usize, mouse: Vec2u, freehand: bool, number_base: NumberBase, region_heatmap: RegionHeatmap, boolean_lines: Vec<(usize, bool)>) -> Self {
 		Self {
 			selecting_key,
 			selected_text_y,
@@ -64,6 +74,12 @@ impl<'a> RenderContext<'a> {
 			y_offset: HEADER_SIZE,
 			line_numbers: vec![],
 			freehand,
+			number_base,
+			region_heatmap,
+			bytes_as_booleans: config::get_bytes_as_booleans(),
+			boolean_lines,
+			float_display: config::get_float_display(),
+			float_decimals: config::get_float_decimals(),
 		}
 	}
 
@@ -84,6 +100,28 @@ impl<'a> RenderContext<'a> {
 	#[must_use]
 	pub const fn left_margin(&self) -> usize { self.left_margin }
 
+	#[must_use]
+	pub const fn number_base(&self) -> NumberBase { self.number_base }
+
+	#[must_use]
+	pub const fn region_heatmap(&self) -> RegionHeatmap { self.region_heatmap }
+
+	#[must_use]
+	pub const fn float_display(&self) -> FloatDisplay { self.float_display }
+
+	#[must_use]
+	pub const fn float_decimals(&self) -> usize { self.float_decimals }
+
+	/// Whether a byte of 0 or 1 on the line being drawn is shown as a boolean
+	#[must_use]
+	pub fn shows_boolean(&self) -> bool {
+		let line_number = self.line_number - 1;
+		match self.boolean_lines.binary_search_by_key(&line_number, |&(true_line_number, _)| true_line_number) {
+			Ok(idx) => self.boolean_lines[idx].1,
+			Err(_) => self.bytes_as_booleans,
+		}
+	}
+
 	#[must_use]
 	pub const fn has_invalid_key_error(&self) -> bool { self.invalid_key_error }
 
@@ -183,6 +221,51 @@ impl<'a> RenderContext<'a> {
 		self.draw_error_underline_width(x + x_shift, y, overridden_width, builder);
 	}
 
+	/// Tints each line of the selected elements, from [`Selection::true_line_ranges`](crate::workbench::selection::Selection::true_line_ranges)
+	pub fn render_selection(&self, builder: &mut VertexBufferBuilder, ranges: &[Range<usize>]) {
+		let horizontal_scroll_before = core::mem::replace(&mut builder.horizontal_scroll, 0);
+		let mut y = HEADER_SIZE;
+		for &line_number in &self.line_numbers {
+			let idx = ranges.partition_point(|range| range.end <= line_number);
+			if ranges.get(idx).is_some_and(|range| range.contains(&line_number)) {
+				builder.draw_texture_region_z((0, y), BASE_Z, SELECTED_STRIPE_UV + (1, 1), (builder.window_width(), 16), (6, 6));
+			}
+			y += 16;
+		}
+		builder.horizontal_scroll = horizontal_scroll_before;
+	}
+
+	/// Tints each line which differs, see [`Tab::compare`](crate::workbench::tab::Tab::compare)
+	pub fn render_differences(&self, builder: &mut VertexBufferBuilder, differences: &[Difference], skip_tooltips: bool) {
+		let horizontal_scroll_before = core::mem::replace(&mut builder.horizontal_scroll, 0);
+		let mut y = HEADER_SIZE;
+		for &line_number in &self.line_numbers {
+			if let Ok(idx) = differences.binary_search_by_key(&line_number, |difference| difference.true_line_number) {
+				let difference = &differences[idx];
+				builder.draw_texture_region_z((0, y), BASE_Z, difference.kind.uv() + (1, 1), (builder.window_width(), 16), (6, 6));
+				if !skip_tooltips
+					&& let Some(summary) = &difference.summary
+					&& (y..y + 16).contains(&self.mouse.y)
+					&& self.mouse.x >= self.left_margin
+				{
+					builder.draw_tooltip(&[summary.as_str()], self.mouse, false);
+				}
+			}
+			y += 16;
+		}
+		builder.horizontal_scroll = horizontal_scroll_before;
+	}
+
+	/// Tints the line at `y` of an entry whose key an earlier entry of its compound also has, saying so while it's hovered
+	pub fn draw_duplicate_key_warning(&self, y: usize, key: &str, builder: &mut VertexBufferBuilder) {
+		let horizontal_scroll_before = core::mem::replace(&mut builder.horizontal_scroll, 0);
+		builder.draw_texture_region_z((self.left_margin, y), BASE_Z, CHANGED_STRIPE_UV + (1, 1), (builder.window_width().saturating_sub(self.left_margin), 16), (6, 6));
+		builder.horizontal_scroll = horizontal_scroll_before;
+		if (y..y + 16).contains(&self.mouse.y) && self.mouse.x >= self.left_margin {
+			builder.draw_tooltip(&[&validate::duplicate_key(key)], self.mouse, false);
+		}
+	}
+
 	pub fn skip_line_numbers(&mut self, n: usize) { self.line_number = self.line_number.wrapping_add(n); }
 
 	pub fn line_number(&mut self) {
@@ -238,16 +321,46 @@ impl<'a> RenderContext<'a> {
 		}
 	}
 
+	/// Where the cells of the grid layout of an open region start, one in for the region and one more for its chunks, with a row for the region above them
+	fn grid_start(&self) -> Vec2u { Vec2u::new(self.left_margin + 16 + 16, HEADER_SIZE + 16) }
+
+	/// The index of the chunk under the mouse on the grid layout of an open region, and where it's drawn
+	#[must_use]
+	pub fn grid_chunk_at(&self, builder: &VertexBufferBuilder) -> Option<(usize, Vec2u)> {
+		let start = self.grid_start();
+		let mouse = Vec2u::new(self.mouse.x + builder.horizontal_scroll, self.mouse.y + builder.scroll());
+		if mouse.x < start.x || mouse.y < start.y || self.mouse.x < self.left_margin || self.mouse.y < HEADER_SIZE {
+			return None
+		}
+		let (x, z) = ((mouse.x - start.x) / 16, (mouse.y - start.y) / 16);
+		if x < 32 && z < 32 { Some((z * 32 + x, self.grid_chunk_pos(builder, z * 32 + x)?)) } else { None }
+	}
+
+	/// Where the chunk at `idx` is drawn on the grid layout of an open region, unless it's been scrolled past the top of the window
+	#[must_use]
+	pub fn grid_chunk_pos(&self, builder: &VertexBufferBuilder, idx: usize) -> Option<Vec2u> {
+		let start = self.grid_start();
+		Some(Vec2u::new(start.x + idx % 32 * 16, (start.y + idx / 32 * 16).checked_sub(builder.scroll())?))
+	}
+
 	pub fn render_grid_line_numbers(&self, builder: &mut VertexBufferBuilder, mut bookmarks: &MarkedLineSlice) {
 		use std::fmt::Write as _;
 
 		let scroll = builder.scroll();
+		// the row of the chunk under the mouse, after the region's
+		let hovered_line_number = self.grid_chunk_at(builder).map(|(idx, _)| idx / 32 + 2);
 
 		let last_line_number = (self.line_numbers.len() > 1) as usize * 32 + 1;
 		for line_number in 1..=last_line_number {
 			let uv = if line_number == last_line_number { END_LINE_NUMBER_SEPARATOR_UV } else { LINE_NUMBER_SEPARATOR_UV };
 			if 16 * line_number >= scroll + 16 {
-				let color = if line_number % 2 == 1 { 0x777777 } else { TextColor::Gray.to_raw() };
+				let color = if Some(line_number) == hovered_line_number {
+					TextColor::White.to_raw()
+				} else if line_number % 2 == 1 {
+					0x777777
+				} else {
+					TextColor::Gray.to_raw()
+				};
 				let color = core::mem::replace(&mut builder.color, color);
 				builder.settings((self.left_margin - line_number.ilog10() as usize * 8 - 16, HEADER_SIZE + 16 * line_number - 16 - scroll), false, BASE_TEXT_Z);
 				let _ = write!(builder, "{line_number}");
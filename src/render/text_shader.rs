@@ -52,6 +52,10 @@ wgsl! {
 
 	@fragment
 	fn fragment(input: VertexOutput) -> @location(0) vec4<f32> {
+		// drawn by `VertexBufferBuilder::draw_color_z`
+		if (input.character == 0x10000u) {
+			return input.color;
+		}
 		let x = u32(input.uv[0] * 16.0);
 		let y = u32(input.uv[1] * 16.0);
 		let bit_index = input.character * 256u + y * 16 + x;
@@ -20,6 +20,8 @@ pub struct VertexBufferBuilder {
 	window_dims: PhysicalSize<f32>,
 	scroll: usize,
 	pub horizontal_scroll: usize,
+	/// Nothing is drawn right of this x, after [`VertexBufferBuilder::horizontal_scroll`] is applied
+	pub clip_right: usize,
 	pub text_coords: (usize, usize),
 	dropshadow: bool,
 	text_z: ZOffset,
@@ -28,6 +30,16 @@ pub struct VertexBufferBuilder {
 	negative_two_over_height: f32,
 	tooltips: Vec<(Box<[String]>, Vec2u, bool, u32)>,
 	scale: f32,
+	viewport: Option<Viewport>,
+}
+
+/// A part of the window drawn into as if it were the whole window, see [`VertexBufferBuilder::set_viewport`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Viewport {
+	pub x: usize,
+	pub width: usize,
+	/// Used instead of the window's, since each part can show a different tab
+	pub scroll: usize,
 }
 
 impl core::fmt::Write for VertexBufferBuilder {
@@ -51,6 +63,8 @@ impl core::fmt::Write for VertexBufferBuilder {
 impl VertexBufferBuilder {
 	pub const CHAR_WIDTH: &'static [u8] = include_bytes!("../assets/char_widths.hex");
 	pub const CHAR_HEIGHT: usize = 16;
+	/// Past every glyph, so the text shader fills the quad with its color instead
+	const SOLID_COLOR_CHAR: u32 = 0x1_0000;
 
 	pub fn new(size: PhysicalSize<u32>, scroll: usize, scale: f32) -> Self {
 		Self {
@@ -63,6 +77,7 @@ impl VertexBufferBuilder {
 			window_dims: size.cast(),
 			scroll,
 			horizontal_scroll: 0,
+			clip_right: usize::MAX,
 			text_coords: (0, 0),
 			dropshadow: false,
 			text_z: BASE_TEXT_Z,
@@ -71,10 +86,24 @@ impl VertexBufferBuilder {
 			negative_two_over_height: -2.0 / size.height as f32,
 			tooltips: vec![],
 			scale,
+			viewport: None,
+		}
+	}
+
+	pub const fn scroll(&self) -> usize {
+		match self.viewport {
+			Some(viewport) => viewport.scroll,
+			None => self.scroll,
 		}
 	}
 
-	pub const fn scroll(&self) -> usize { self.scroll }
+	/// Moves everything drawn after into `viewport`, which is cut at both of its sides and is what [`VertexBufferBuilder::window_width`] and [`VertexBufferBuilder::scroll`] are of, or back to the whole window with `None`
+	///
+	/// Returns the viewport before, to be set back once done
+	pub fn set_viewport(&mut self, viewport: Option<Viewport>) -> Option<Viewport> { core::mem::replace(&mut self.viewport, viewport) }
+
+	/// Nothing is drawn right of this x, after [`VertexBufferBuilder::horizontal_scroll`] is applied
+	fn clip(&self) -> usize { self.viewport.map_or(self.clip_right, |viewport| self.clip_right.min(viewport.width)) }
 
 	pub fn settings(&mut self, pos: impl Into<(usize, usize)>, dropshadow: bool, z: ZOffset) {
 		self.text_coords = pos.into();
@@ -112,7 +141,9 @@ impl VertexBufferBuilder {
 
 	pub fn draw_tooltip(&mut self, text: &[&str], pos: impl Into<(usize, usize)>, force_draw_right: bool) {
 		let color = self.color;
-		self.tooltips.push((text.iter().map(|s| s.to_string()).collect::<Vec<_>>().into_boxed_slice(), Vec2u::from(pos.into()), force_draw_right, color));
+		// tooltips are drawn last, once the viewport is gone
+		let pos = Vec2u::from(pos.into()) + (self.viewport.map_or(0, |viewport| viewport.x), 0);
+		self.tooltips.push((text.iter().map(|s| s.to_string()).collect::<Vec<_>>().into_boxed_slice(), pos, force_draw_right, color));
 	}
 
 	pub fn reset(&mut self) {
@@ -123,6 +154,8 @@ impl VertexBufferBuilder {
 		self.vertices_len = 0;
 		self.text_vertices_len = 0;
 		self.horizontal_scroll = 0;
+		self.clip_right = usize::MAX;
+		self.viewport = None;
 		self.text_coords = (0, 0);
 		self.dropshadow = false;
 		self.text_z = BASE_TEXT_Z;
@@ -171,11 +204,18 @@ impl VertexBufferBuilder {
 	}
 
 	pub fn draw_unicode_z_color(&mut self, x: usize, y: usize, z: ZOffset, char: u16, color: u32) {
+		// glyphs can't be cut, so any which don't fully fit are left out
+		if unlikely((x + Self::CHAR_WIDTH[char as usize] as usize).saturating_sub(self.horizontal_scroll) > self.clip()) {
+			return
+		}
+		if unlikely(self.viewport.is_some() && x < self.horizontal_scroll) {
+			return
+		}
 		unsafe {
 			if unlikely(self.text_vertices.capacity() - self.text_vertices.len() < 16) {
 				self.extend_text_buffers();
 			}
-			let x = (x as isize - self.horizontal_scroll as isize) as f32 * self.scale;
+			let x = (x as isize - self.horizontal_scroll as isize + self.viewport.map_or(0, |viewport| viewport.x) as isize) as f32 * self.scale;
 			let y = y as f32 * self.scale;
 			let z_and_color = f32::from_bits(((255 - z as u8) as u32) | (color << 8));
 			let char = f32::from_bits(char as u32);
@@ -185,6 +225,56 @@ impl VertexBufferBuilder {
 			let y1 = y.mul_add(self.negative_two_over_height, 1.0);
 			let y0 = self.negative_two_over_height.mul_add(Self::CHAR_HEIGHT as f32 * self.scale, y1);
 
+			self.push_text_quad((x0, x1, y0, y1), z_and_color, char);
+		}
+	}
+
+	/// A quad of just `color`, drawn along with the text since only its vertices have a color
+	pub fn draw_color_z(&mut self, pos: impl Into<Vec2u>, z: ZOffset, dims: impl Into<Vec2u>, color: u32) {
+		let pos = pos.into();
+		let mut dims = dims.into();
+		let mut start = pos.x;
+		let end = (start + dims.x).saturating_sub(self.horizontal_scroll);
+		let clip = self.clip();
+		if unlikely(end > clip) {
+			let cut = end - clip;
+			if cut >= dims.x {
+				return
+			}
+			dims.x -= cut;
+		}
+		if unlikely(self.viewport.is_some() && start < self.horizontal_scroll) {
+			let cut = self.horizontal_scroll - start;
+			if cut >= dims.x {
+				return
+			}
+			dims.x -= cut;
+			start += cut;
+		}
+		unsafe {
+			if unlikely(self.text_vertices.capacity() - self.text_vertices.len() < 16) {
+				self.extend_text_buffers();
+			}
+			let x = (start as isize - self.horizontal_scroll as isize + self.viewport.map_or(0, |viewport| viewport.x) as isize) as f32 * self.scale;
+			let y = pos.y as f32 * self.scale;
+			let z_and_color = f32::from_bits(((255 - z as u8) as u32) | (color << 8));
+			let char = f32::from_bits(Self::SOLID_COLOR_CHAR);
+
+			let x0 = x.mul_add(self.two_over_width, -1.0);
+			let x1 = self.two_over_width.mul_add(dims.x as f32 * self.scale, x0);
+			let y1 = y.mul_add(self.negative_two_over_height, 1.0);
+			let y0 = self.negative_two_over_height.mul_add(dims.y as f32 * self.scale, y1);
+
+			self.push_text_quad((x0, x1, y0, y1), z_and_color, char);
+		}
+	}
+
+	/// Adds a quad spanning `x0..x1` and `y0..y1` in clip space to the text vertices
+	///
+	/// # Safety
+	/// There must be room for its 16 vertices and 6 indices
+	unsafe fn push_text_quad(&mut self, (x0, x1, y0, y1): (f32, f32, f32, f32), z_and_color: f32, char: f32) {
+		unsafe {
 			let len = self.text_vertices_len;
 			let vec = &mut self.text_vertices;
 
@@ -243,7 +333,7 @@ impl VertexBufferBuilder {
 	}
 
 	#[must_use]
-	pub fn window_width(&self) -> usize { (self.window_dims.width / self.scale) as usize }
+	pub fn window_width(&self) -> usize { self.viewport.map_or((self.window_dims.width / self.scale) as usize, |viewport| viewport.width) }
 
 	#[must_use]
 	pub fn window_height(&self) -> usize { (self.window_dims.height / self.scale) as usize }
@@ -277,16 +367,39 @@ impl VertexBufferBuilder {
 		unsafe {
 			let pos = pos.into();
 			let uv = uv.into();
-			let dims = dims.into();
+			let mut dims = dims.into();
 			let uv_dims = uv_dims.into();
-			let x = (pos.x as isize - self.horizontal_scroll as isize) as f32 * self.scale;
+			let mut uv_width = uv_dims.x as f32;
+			let mut u = uv.x as f32;
+			let mut start = pos.x;
+			let end = (start + dims.x).saturating_sub(self.horizontal_scroll);
+			let clip = self.clip();
+			if unlikely(end > clip) {
+				let cut = end - clip;
+				if cut >= dims.x {
+					return
+				}
+				// the texture is cut rather than squished
+				uv_width *= (dims.x - cut) as f32 / dims.x as f32;
+				dims.x -= cut;
+			}
+			if unlikely(self.viewport.is_some() && start < self.horizontal_scroll) {
+				let cut = self.horizontal_scroll - start;
+				if cut >= dims.x {
+					return
+				}
+				let uv_cut = uv_width * cut as f32 / dims.x as f32;
+				u += uv_cut;
+				uv_width -= uv_cut;
+				dims.x -= cut;
+				start += cut;
+			}
+			let x = (start as isize - self.horizontal_scroll as isize + self.viewport.map_or(0, |viewport| viewport.x) as isize) as f32 * self.scale;
 			let y = pos.y as f32 * self.scale;
 			let z = 1.0 - z as u8 as f32 / 256.0;
-			let u = uv.x as f32;
 			let v = uv.y as f32;
 			let width = dims.x as f32 * self.scale;
 			let height = dims.y as f32 * self.scale;
-			let uv_width = uv_dims.x as f32;
 			let uv_height = uv_dims.y as f32;
 
 			let x0 = self.two_over_width.mul_add(x, -1.0);
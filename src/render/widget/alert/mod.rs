@@ -42,6 +42,9 @@ impl Alert {
 	#[must_use]
 	pub fn error(error: impl Debug) -> Self { Self::new("Error!", TextColor::Red, format!("{error:?}")) }
 
+	#[must_use]
+	pub fn warning(warning: impl Debug) -> Self { Self::new("Warning!", TextColor::Yellow, format!("{warning:?}")) }
+
 	pub fn log(&self) {
 		if self.title == "Error!" && self.title_color == TextColor::Red.to_raw() {
 			error!("ALERT ERROR: {}", self.original_message)
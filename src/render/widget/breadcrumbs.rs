@@ -0,0 +1,176 @@
+use compact_str::CompactString;
+
+use crate::{
+	config,
+	elements::element::NbtElement,
+	render::{
+		assets::{BREADCRUMBS_TEXT_Z, BREADCRUMBS_Z, DARK_STRIPE_UV, HEADER_SIZE},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+		window::Theme,
+	},
+	tree::{
+		indices::{Indices, OwnedIndices},
+		path::NbtPathNode,
+	},
+	util::{StrExt, Vec2u},
+	workbench::tab::Tab,
+};
+
+/// The path from the root to the selected (or otherwise hovered) element, drawn over the topmost row once the tree is scrolled
+#[derive(Default)]
+pub struct Breadcrumbs {
+	indices: OwnedIndices,
+}
+
+struct Segment {
+	label: String,
+	/// How many of the indices lead to this segment's element
+	depth: usize,
+	/// Elements without keys are written right after their parent, like `Sections[4]`
+	joined: bool,
+}
+
+impl Breadcrumbs {
+	const START_X: usize = 4;
+	/// Leaves room for the scrollbar
+	const END_X: usize = 12;
+	const SEPARATOR: &'static str = " › ";
+	const ELLIPSIS: &'static str = "…";
+
+	#[must_use]
+	pub const fn new() -> Self { Self { indices: OwnedIndices::new() } }
+
+	#[must_use]
+	pub fn is_visible(tab: &Tab) -> bool { tab.consts().scroll > 0 }
+
+	#[must_use]
+	pub fn is_within_bounds(mouse: Vec2u) -> bool { (HEADER_SIZE..HEADER_SIZE + 16).contains(&mouse.y) }
+
+	/// Follows the selected text, or the hovered row if there is none
+	pub fn update(&mut self, tab: &Tab, mouse: Vec2u) {
+		if let Some(text) = &tab.selected_text {
+			self.indices.clone_from(&text.indices);
+		} else if mouse.y >= HEADER_SIZE
+			&& !(Self::is_visible(tab) && Self::is_within_bounds(mouse))
+			&& let Ok(info) = tab.root.traverse((mouse.y + tab.consts().scroll - HEADER_SIZE) / 16, None)
+		{
+			self.indices = info.indices;
+		}
+	}
+
+	/// `None` if the indices no longer point to anything, like after the element was removed
+	fn segments(&self, root: &NbtElement) -> Option<Vec<Segment>> {
+		let mut segments = Vec::with_capacity(self.indices.len() + 1);
+		segments.push(Segment {
+			label: "root".to_owned(),
+			depth: 0,
+			joined: false,
+		});
+		let mut element = root;
+		for (depth, idx) in self.indices.iter().enumerate() {
+			let (key, child) = element.get(idx)?;
+			let label = match (key, child.as_chunk()) {
+				(Some(key), _) => NbtPathNode::Key(CompactString::from(key)).to_string(),
+				(None, Some(chunk)) => format!("[{x},{z}]", x = chunk.x, z = chunk.z),
+				(None, None) => format!("[{idx}]"),
+			};
+			segments.push(Segment { label, depth: depth + 1, joined: key.is_none() });
+			element = child;
+		}
+		Some(segments)
+	}
+
+	/// The segments which fit in `width` alongside the x they start at, and whether leading ones were cut off for an ellipsis
+	fn layout(&self, root: &NbtElement, width: usize) -> Option<(bool, Vec<(usize, Segment)>)> {
+		fn segment_width(segment: &Segment, first: bool) -> usize { segment.label.width() + if first || segment.joined { 0 } else { Breadcrumbs::SEPARATOR.width() } }
+
+		let mut segments = self.segments(root)?;
+		let available = width.saturating_sub(Self::START_X + Self::END_X);
+		let mut total = segments.iter().enumerate().map(|(idx, segment)| segment_width(segment, idx == 0)).sum::<usize>();
+		let truncated = total > available;
+		if truncated {
+			// the ellipsis stands in for the first segment, so everything after it keeps its separator
+			total = total - segments[0].label.width() + Self::ELLIPSIS.width();
+			let mut skip = 1;
+			while skip + 1 < segments.len() && total > available {
+				total -= segment_width(&segments[skip], false);
+				skip += 1;
+			}
+			segments.drain(..skip);
+		}
+
+		let mut x = Self::START_X + if truncated { Self::ELLIPSIS.width() } else { 0 };
+		Some((
+			truncated,
+			segments
+				.into_iter()
+				.enumerate()
+				.map(|(idx, segment)| {
+					let start = x + segment_width(&segment, idx == 0 && !truncated) - segment.label.width();
+					x = start + segment.label.width();
+					(start, segment)
+				})
+				.collect(),
+		))
+	}
+
+	/// The indices of the segment under `mouse`, for [`Tab::collapse_to`]
+	#[must_use]
+	pub fn segment_at(&self, root: &NbtElement, mouse: Vec2u, width: usize) -> Option<&Indices> {
+		let (_, segments) = self.layout(root, width)?;
+		let (_, segment) = segments.into_iter().find(|(x, segment)| (*x..*x + segment.label.width()).contains(&mouse.x))?;
+		Some(self.indices.split_at(segment.depth).0)
+	}
+
+	pub fn render(&self, builder: &mut VertexBufferBuilder, root: &NbtElement, mouse: Vec2u) {
+		use std::fmt::Write;
+
+		let Some((truncated, segments)) = self.layout(root, builder.window_width()) else { return };
+
+		builder.draw_texture_region_z((0, HEADER_SIZE), BREADCRUMBS_Z, DARK_STRIPE_UV + (1, 1), (builder.window_width(), 16), (14, 14));
+
+		let color = match config::get_theme() {
+			Theme::Light => TextColor::Black,
+			Theme::Dark => TextColor::White,
+		};
+		if truncated {
+			builder.settings((Self::START_X, HEADER_SIZE), false, BREADCRUMBS_TEXT_Z);
+			builder.color = TextColor::Gray.to_raw();
+			let _ = write!(builder, "{}", Self::ELLIPSIS);
+		}
+		let hovered = Self::is_within_bounds(mouse);
+		for (idx, (x, segment)) in segments.iter().enumerate() {
+			if (idx > 0 || truncated) && !segment.joined {
+				builder.settings((x - Self::SEPARATOR.width(), HEADER_SIZE), false, BREADCRUMBS_TEXT_Z);
+				builder.color = TextColor::Gray.to_raw();
+				let _ = write!(builder, "{}", Self::SEPARATOR);
+			}
+			builder.settings((*x, HEADER_SIZE), false, BREADCRUMBS_TEXT_Z);
+			builder.color = if hovered && (*x..*x + segment.label.width()).contains(&mouse.x) { TextColor::Gold } else { color }.to_raw();
+			let _ = write!(builder, "{}", segment.label);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_layout() {
+		let root = NbtElement::from_str(r#"{Level: {Sections: [{}, {palette: ["stone", "dirt"]}]}}"#).expect("Valid SNBT").1;
+		let mut breadcrumbs = Breadcrumbs::new();
+		breadcrumbs.indices = OwnedIndices::from(vec![0, 0, 1, 0, 1]);
+		let labels = |width| breadcrumbs.layout(&root, width).map(|(truncated, segments)| (truncated, segments.into_iter().map(|(_, segment)| segment.label).collect::<Vec<_>>()));
+
+		assert_eq!(labels(usize::MAX), Some((false, ["root", "Level", "Sections", "[1]", "palette", "[1]"].map(str::to_owned).to_vec())));
+		let (truncated, narrow) = labels(100).expect("Valid indices");
+		assert!(truncated);
+		assert_eq!(narrow.last().map(String::as_str), Some("[1]"));
+		assert!(narrow.len() < 6);
+
+		breadcrumbs.indices = OwnedIndices::from(vec![0, 5]);
+		assert!(breadcrumbs.layout(&root, usize::MAX).is_none());
+	}
+}
@@ -7,5 +7,6 @@ pub mod replace_by;
 pub mod search_flags;
 pub mod search_mode;
 pub mod search_operation;
+pub mod search_tag_type;
 pub mod sort_algorithm;
 pub mod theme;
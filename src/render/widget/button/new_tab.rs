@@ -10,7 +10,7 @@ use crate::{
 		widget::{Widget, WidgetContext, WidgetContextMut},
 	},
 	util::{AxisAlignedBoundingBox, Vec2u},
-	workbench::tab::Tab,
+	workbench::tab::NewTabRoot,
 };
 
 pub struct NewTabButton;
@@ -23,12 +23,24 @@ impl Widget for NewTabButton {
 
 	fn bounds(&self, _window_dims: PhysicalSize<u32>) -> AxisAlignedBoundingBox { AxisAlignedBoundingBox::new(16, 32, 26, 46) }
 
-	fn is_valid_mouse_button(button: MouseButton) -> bool { matches!(button, MouseButton::Left) }
+	fn is_valid_mouse_button(button: MouseButton) -> bool { matches!(button, MouseButton::Left | MouseButton::Right | MouseButton::Middle) }
 
-	fn on_mouse_down(&mut self, _button: MouseButton, ctx: &mut WidgetContextMut) -> ActionResult {
-		let shift = ctx.shift;
+	fn on_mouse_down(&mut self, button: MouseButton, ctx: &mut WidgetContextMut) -> ActionResult {
+		if button == MouseButton::Right {
+			ctx.toggle_recent_files();
+			return ActionResult::Success(())
+		}
+		let root = if button == MouseButton::Middle && ctx.shift {
+			NewTabRoot::Scratch
+		} else if button == MouseButton::Middle {
+			NewTabRoot::List
+		} else if ctx.shift {
+			NewTabRoot::Region
+		} else {
+			NewTabRoot::Compound
+		};
 		let window_dims = ctx.tabs.active_tab().window_dims;
-		ctx.tabs.add(Tab::new_empty_tab(shift, window_dims));
+		ctx.tabs.add_empty(root, window_dims);
 		ActionResult::Success(())
 	}
 
@@ -40,9 +52,9 @@ impl Widget for NewTabButton {
 		if is_within_bounds {
 			builder.draw_texture(bounds.low(), SELECTION_UV, (16, 16));
 			if ctx.shift {
-				builder.draw_tooltip(&["Create New Region File (Ctrl + Shift + N)"], mouse, false);
+				builder.draw_tooltip(&["Create New Region File (Ctrl + Shift + N)", "Middle click for an SNBT scratch tab (Ctrl + Shift + Alt + N)", "Right click for recent files"], mouse, false);
 			} else {
-				builder.draw_tooltip(&["Create New NBT File (Ctrl + N)"], mouse, false);
+				builder.draw_tooltip(&["Create New NBT File (Ctrl + N)", "Middle click for a List (Ctrl + Alt + N)", "Right click for recent files"], mouse, false);
 			}
 		}
 	}
@@ -22,10 +22,14 @@ impl Widget for OpenFileButton {
 
 	fn bounds(&self, _window_dims: PhysicalSize<u32>) -> AxisAlignedBoundingBox { AxisAlignedBoundingBox::new(0, 16, 26, 46) }
 
-	fn is_valid_mouse_button(button: MouseButton) -> bool { matches!(button, MouseButton::Left) }
+	fn is_valid_mouse_button(button: MouseButton) -> bool { matches!(button, MouseButton::Left | MouseButton::Right) }
 
-	fn on_mouse_down(&mut self, _button: MouseButton, ctx: &mut WidgetContextMut) -> ActionResult {
-		ctx.open_file_request();
+	fn on_mouse_down(&mut self, button: MouseButton, ctx: &mut WidgetContextMut) -> ActionResult {
+		if button == MouseButton::Right {
+			ctx.toggle_url_box();
+		} else {
+			ctx.open_file_request();
+		}
 		ActionResult::Success(())
 	}
 
@@ -36,7 +40,7 @@ impl Widget for OpenFileButton {
 		builder.draw_texture(aabb.low(), OPEN_FOLDER_UV, (16, 16));
 		if is_within_bounds {
 			builder.draw_texture(aabb.low(), SELECTION_UV, (16, 16));
-			builder.draw_tooltip(&["Open File (Ctrl + O)"], mouse, false);
+			builder.draw_tooltip(&["Open File (Ctrl + O)", "Right click to open a URL (Ctrl + Shift + O)"], mouse, false);
 		}
 	}
 }
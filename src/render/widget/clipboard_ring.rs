@@ -0,0 +1,201 @@
+use std::collections::VecDeque;
+
+use winit::dpi::PhysicalSize;
+
+use crate::{
+	elements::NbtElementAndKey,
+	render::{
+		assets::{CLIPBOARD_RING_SELECTION_Z, CLIPBOARD_RING_TEXT_Z, CLIPBOARD_RING_Z, DARK_STRIPE_UV, HOVERED_STRIPE_UV},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+	},
+	util::{AxisAlignedBoundingBox, StrExt, Vec2u, truncate_to_width},
+};
+
+/// The elements copied most recently, kept as they are rather than as SNBT, to be picked from with Ctrl + Shift + V and held
+///
+/// Shared by every tab, and gone once the app is closed
+#[derive(Default)]
+pub struct ClipboardRing {
+	/// Most recent first
+	entries: VecDeque<ClipboardRingEntry>,
+	picker: Option<Picker>,
+}
+
+struct ClipboardRingEntry {
+	/// Never empty, more than one when a selection was copied
+	entries: Vec<NbtElementAndKey>,
+	/// The first entry's value, cut short, worked out once since it doesn't change
+	preview: String,
+	color: TextColor,
+}
+
+struct Picker {
+	pos: Vec2u,
+	highlighted: usize,
+}
+
+impl ClipboardRing {
+	/// One for each digit key to pick it with
+	pub const CAPACITY: usize = 9;
+	const PADDING: usize = 4;
+	const GAP: usize = 6;
+	const PREVIEW_WIDTH: usize = 192;
+	const ELLIPSIS: &'static str = "…";
+	const EMPTY: &'static str = "Nothing copied yet";
+
+	#[must_use]
+	pub const fn new() -> Self { Self { entries: VecDeque::new(), picker: None } }
+
+	/// Remembers `entries` as the most recent, unless they're the same as the most recent already
+	pub fn push(&mut self, entries: Vec<NbtElementAndKey>) {
+		let Some((_, first)) = entries.first() else { return };
+		if self.entries.front().is_some_and(|entry| entry.entries == entries) {
+			return
+		}
+		let (value, color) = first.value();
+		let preview = truncate_to_width(&value, Self::PREVIEW_WIDTH, Self::ELLIPSIS);
+		self.entries.push_front(ClipboardRingEntry { entries, preview, color });
+		self.entries.truncate(Self::CAPACITY);
+	}
+
+	#[must_use]
+	pub const fn is_open(&self) -> bool { self.picker.is_some() }
+
+	/// Opens the picker at `mouse`, moved back into the window if it would go past its edges
+	pub fn open(&mut self, mouse: Vec2u, window_dims: PhysicalSize<u32>) {
+		let size = self.size();
+		let pos = Vec2u::new(mouse.x.min((window_dims.width as usize).saturating_sub(size.x)), mouse.y.min((window_dims.height as usize).saturating_sub(size.y)));
+		self.picker = Some(Picker { pos, highlighted: 0 });
+	}
+
+	pub fn close(&mut self) { self.picker = None; }
+
+	fn row_width(entry: &ClipboardRingEntry) -> usize {
+		let (key, _) = &entry.entries[0];
+		let more = Self::more_text(entry);
+		"0".width() + Self::GAP + 16 + Self::GAP + key.as_deref().map_or(0, |key| key.width() + Self::GAP) + entry.preview.width() + more.map_or(0, |more| Self::GAP + more.width())
+	}
+
+	fn more_text(entry: &ClipboardRingEntry) -> Option<String> { (entry.entries.len() > 1).then(|| format!("+{} more", entry.entries.len() - 1)) }
+
+	fn size(&self) -> Vec2u {
+		let width = self.entries.iter().map(Self::row_width).max().unwrap_or(Self::EMPTY.width());
+		Vec2u::new(width + Self::PADDING * 2, self.entries.len().max(1) * 16)
+	}
+
+	fn bounds(&self) -> Option<AxisAlignedBoundingBox> {
+		let picker = self.picker.as_ref()?;
+		let size = self.size();
+		Some(AxisAlignedBoundingBox::new(picker.pos.x, picker.pos.x + size.x, picker.pos.y, picker.pos.y + size.y))
+	}
+
+	#[must_use]
+	pub fn is_within_bounds(&self, mouse: Vec2u) -> bool { self.bounds().is_some_and(|bounds| bounds.contains(mouse)) }
+
+	/// Moves the highlighted row down or up one, wrapping around either end
+	pub fn move_highlight(&mut self, down: bool) {
+		let len = self.entries.len();
+		if let Some(picker) = &mut self.picker
+			&& len > 0
+		{
+			picker.highlighted = (if down { picker.highlighted + 1 } else { picker.highlighted + len - 1 }) % len;
+		}
+	}
+
+	#[must_use]
+	pub fn highlighted(&self) -> Option<usize> { self.picker.as_ref().map(|picker| picker.highlighted) }
+
+	/// The row under `mouse`
+	#[must_use]
+	pub fn row_at(&self, mouse: Vec2u) -> Option<usize> {
+		let bounds = self.bounds()?;
+		if !bounds.contains(mouse) {
+			return None
+		}
+		let idx = (mouse.y - bounds.low().y) / 16;
+		(idx < self.entries.len()).then_some(idx)
+	}
+
+	/// Closes the picker, giving back a copy of the entries at `idx` if there are any
+	pub fn pick(&mut self, idx: usize) -> Option<Vec<NbtElementAndKey>> {
+		self.close();
+		self.entries.get(idx).map(|entry| entry.entries.clone())
+	}
+
+	pub fn render(&self, builder: &mut VertexBufferBuilder, mouse: Vec2u) {
+		use std::fmt::Write;
+
+		let (Some(picker), Some(bounds)) = (&self.picker, self.bounds()) else { return };
+		builder.draw_texture_region_z(bounds.low(), CLIPBOARD_RING_Z, DARK_STRIPE_UV + (1, 1), bounds.high() - bounds.low(), (14, 14));
+		if self.entries.is_empty() {
+			builder.settings(bounds.low() + (Self::PADDING, 0), false, CLIPBOARD_RING_TEXT_Z);
+			builder.color = TextColor::Gray.to_raw();
+			let _ = write!(builder, "{}", Self::EMPTY);
+			return
+		}
+
+		let hovered = self.row_at(mouse);
+		for (idx, entry) in self.entries.iter().enumerate() {
+			let y = bounds.low().y + idx * 16;
+			if hovered.unwrap_or(picker.highlighted) == idx {
+				builder.draw_texture_region_z((bounds.low().x, y), CLIPBOARD_RING_SELECTION_Z, HOVERED_STRIPE_UV, (bounds.high().x - bounds.low().x, 16), (16, 16));
+			}
+			let mut x = bounds.low().x + Self::PADDING;
+			builder.settings((x, y), false, CLIPBOARD_RING_TEXT_Z);
+			builder.color = TextColor::Gray.to_raw();
+			let _ = write!(builder, "{}", idx + 1);
+			x += "0".width() + Self::GAP;
+			let (key, element) = &entry.entries[0];
+			builder.draw_texture_z((x, y), CLIPBOARD_RING_TEXT_Z, element.uv(), (16, 16));
+			x += 16 + Self::GAP;
+			if let Some(key) = key {
+				builder.settings((x, y), false, CLIPBOARD_RING_TEXT_Z);
+				builder.color = TextColor::TreeKey.to_raw();
+				let _ = write!(builder, "{key}");
+				x += key.width() + Self::GAP;
+			}
+			builder.settings((x, y), false, CLIPBOARD_RING_TEXT_Z);
+			builder.color = entry.color.to_raw();
+			let _ = write!(builder, "{}", entry.preview);
+			if let Some(more) = Self::more_text(entry) {
+				builder.settings((x + entry.preview.width() + Self::GAP, y), false, CLIPBOARD_RING_TEXT_Z);
+				builder.color = TextColor::Gray.to_raw();
+				let _ = write!(builder, "{more}");
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::elements::element::NbtElement;
+
+	fn entry(snbt: &str) -> Vec<NbtElementAndKey> { NbtElement::entries_from_str(snbt).0 }
+
+	#[test]
+	fn test_clipboard_ring() {
+		let mut ring = ClipboardRing::new();
+		ring.push(entry("a: 1b"));
+		ring.push(entry("a: 1b"));
+		assert_eq!(ring.entries.len(), 1);
+
+		for n in 0..ClipboardRing::CAPACITY {
+			ring.push(entry(&format!("b: {n}b, c: \"{}\"", "x".repeat(100))));
+		}
+		assert_eq!(ring.entries.len(), ClipboardRing::CAPACITY);
+		assert!(ring.entries.iter().all(|entry| entry.entries.len() == 2));
+		assert_eq!(ring.entries[0].preview, "8");
+
+		ring.open(Vec2u::new(0, 0), PhysicalSize::new(1000, 1000));
+		ring.move_highlight(false);
+		assert_eq!(ring.highlighted(), Some(ClipboardRing::CAPACITY - 1));
+		assert_eq!(ring.pick(0), Some(entry(&format!("b: 8b, c: \"{}\"", "x".repeat(100)))));
+		assert!(!ring.is_open());
+
+		assert!(truncate_to_width(&"x".repeat(100), 64, "…").ends_with('…'));
+		assert_eq!(truncate_to_width("short", 64, "…"), "short");
+		assert_eq!(truncate_to_width("two\nlines", 64, "…"), "two…");
+	}
+}
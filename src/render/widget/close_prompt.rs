@@ -0,0 +1,84 @@
+use winit::dpi::PhysicalSize;
+
+use crate::{
+	render::{
+		assets::{CLOSE_PROMPT_TEXT_Z, CLOSE_PROMPT_Z, DARK_STRIPE_UV},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+	},
+	util::{AxisAlignedBoundingBox, StrExt, Vec2u},
+	workbench::tab::Tab,
+};
+
+/// Asks whether to save, discard or keep a tab's unsaved changes before closing it, since the browser has no dialog for that
+pub struct ClosePrompt {
+	idx: usize,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum ClosePromptButton {
+	Save,
+	Discard,
+	Cancel,
+}
+
+impl ClosePrompt {
+	const PADDING: usize = 4;
+	const SEPARATOR: &'static str = "   ";
+	const BUTTONS: [(&'static str, ClosePromptButton); 3] = [("Save", ClosePromptButton::Save), ("Discard", ClosePromptButton::Discard), ("Cancel", ClosePromptButton::Cancel)];
+
+	#[must_use]
+	pub const fn new(idx: usize) -> Self { Self { idx } }
+
+	/// Of the tab being closed
+	#[must_use]
+	pub const fn idx(&self) -> usize { self.idx }
+
+	fn message(tab: &Tab) -> String { format!("{} has unsaved changes", tab.path.name()) }
+
+	fn bounds(tab: &Tab, window_dims: PhysicalSize<u32>) -> AxisAlignedBoundingBox {
+		let buttons_width = Self::BUTTONS.iter().map(|(text, _)| text.width()).sum::<usize>() + Self::SEPARATOR.width() * (Self::BUTTONS.len() - 1);
+		let width = Self::message(tab).width().max(buttons_width) + Self::PADDING * 2;
+		let height = 32 + Self::PADDING * 2;
+		let x = (window_dims.width as usize).saturating_sub(width) / 2;
+		let y = (window_dims.height as usize).saturating_sub(height) / 2;
+		AxisAlignedBoundingBox::new(x, x + width, y, y + height)
+	}
+
+	fn buttons(tab: &Tab, window_dims: PhysicalSize<u32>) -> [(Vec2u, &'static str, ClosePromptButton); 3] {
+		let bounds = Self::bounds(tab, window_dims);
+		let mut x = bounds.low().x + Self::PADDING;
+		let y = bounds.low().y + Self::PADDING + 16;
+		Self::BUTTONS.map(|(text, button)| {
+			let pos = Vec2u::new(x, y);
+			x += text.width() + Self::SEPARATOR.width();
+			(pos, text, button)
+		})
+	}
+
+	#[must_use]
+	pub fn button_at(tab: &Tab, mouse: Vec2u, window_dims: PhysicalSize<u32>) -> Option<ClosePromptButton> {
+		Self::buttons(tab, window_dims)
+			.into_iter()
+			.find(|(pos, text, _)| AxisAlignedBoundingBox::new(pos.x, pos.x + text.width(), pos.y, pos.y + 16).contains(mouse))
+			.map(|(_, _, button)| button)
+	}
+
+	pub fn render(builder: &mut VertexBufferBuilder, tab: &Tab, mouse: Vec2u) {
+		use std::fmt::Write;
+
+		let window_dims = builder.window_dims();
+		let bounds = Self::bounds(tab, window_dims);
+		builder.draw_texture_region_z(bounds.low(), CLOSE_PROMPT_Z, DARK_STRIPE_UV + (1, 1), bounds.high() - bounds.low(), (14, 14));
+
+		builder.settings(bounds.low() + (Self::PADDING, Self::PADDING), false, CLOSE_PROMPT_TEXT_Z);
+		builder.color = TextColor::Yellow.to_raw();
+		let _ = write!(builder, "{}", Self::message(tab));
+		let hovered = Self::button_at(tab, mouse, window_dims);
+		for (pos, text, button) in Self::buttons(tab, window_dims) {
+			builder.settings(pos, false, CLOSE_PROMPT_TEXT_Z);
+			builder.color = if hovered == Some(button) { TextColor::Gold } else { TextColor::White }.to_raw();
+			let _ = write!(builder, "{text}");
+		}
+	}
+}
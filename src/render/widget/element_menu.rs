@@ -0,0 +1,240 @@
+use winit::dpi::PhysicalSize;
+
+use crate::{
+	elements::{
+		boolean::AsBoolean,
+		chunk::NbtChunk,
+		convert::{ConversionTarget, can_convert},
+		element::NbtElement,
+		values_text::is_editable_as_text,
+	},
+	render::{
+		assets::{DARK_STRIPE_UV, ELEMENT_MENU_SELECTION_Z, ELEMENT_MENU_TEXT_Z, ELEMENT_MENU_Z, HOVERED_STRIPE_UV},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+	},
+	tree::{indices::OwnedIndices, navigate::NavigationInformation},
+	util::{AxisAlignedBoundingBox, StrExt, Vec2u},
+	workbench::element_action::ElementAction,
+};
+
+/// Actions on an element, dropped down from its row with a right click past its text
+#[derive(Default)]
+pub struct ElementMenu {
+	open: Option<OpenElementMenu>,
+}
+
+struct OpenElementMenu {
+	indices: OwnedIndices,
+	pos: Vec2u,
+	/// Decided as it opens, since the element can't change while it's open
+	enabled: [bool; ElementMenu::ENTRIES.len()],
+	conversions: Vec<ConversionTarget>,
+	/// The entry whose choices are shown beside it
+	submenu: Option<usize>,
+	window_width: usize,
+}
+
+#[derive(Copy, Clone)]
+pub enum ElementMenuAction {
+	CopySnbt,
+	CopyKey,
+	CopyValue,
+	CopyValueSnbt,
+	CopyPath,
+	PasteInto,
+	Duplicate,
+	Delete,
+	Rename,
+	ConvertTo(ConversionTarget),
+	SortByName,
+	SortByType,
+	SortRecursively,
+	Bookmark,
+	ExpandAll,
+	CollapseAll,
+	HexView,
+	EditAsText,
+	BooleanDisplay,
+	EditTimestamp,
+	MoveChunk,
+	NewChunk,
+	ExportChunk,
+	ImportChunk,
+}
+
+impl ElementMenu {
+	const PADDING: usize = 4;
+	/// Room for the arrow of the entries with a submenu
+	const ARROW_WIDTH: usize = 10;
+	const ENTRIES: [&'static str; 22] = ["Copy SNBT", "Copy key", "Copy value", "Copy value SNBT", "Copy path", "Paste into", "Duplicate", "Delete", "Rename", "Convert type", "Sort", "Bookmark line", "Expand all", "Collapse all", "Hex view", "Edit as text…", "Boolean display", "Edit timestamp…", "Move chunk…", "New chunk at…", "Export chunk…", "Import chunk from file…"];
+	const CONVERT: usize = 9;
+	const SORT: usize = 10;
+	const SORTS: [&'static str; 3] = ["By name", "By type", "By name, recursively"];
+
+	#[must_use]
+	pub const fn new() -> Self { Self { open: None } }
+
+	#[must_use]
+	pub const fn is_open(&self) -> bool { self.open.is_some() }
+
+	/// Opens it at `mouse` for the element at `indices`, moved back into the window if it would go past its edges
+	pub fn open(&mut self, root: &NbtElement, indices: OwnedIndices, mouse: Vec2u, window_dims: PhysicalSize<u32>) {
+		let Ok(NavigationInformation { key, element, .. }) = root.navigate(&indices) else { return };
+		let is_root = indices.is_root();
+		// lists and arrays can only hold the one type, and the root can't change type
+		let convertible = root.navigate_parent(&indices).is_ok_and(|info| info.parent.is_compound() || info.parent.is_chunk());
+		let conversions = if convertible { ConversionTarget::ALL.into_iter().filter(|&target| can_convert(element, target)).collect::<Vec<_>>() } else { Vec::new() };
+		let expandable = element.is_complex() && (element.true_height() > 1 || element.as_chunk().is_some_and(NbtChunk::is_undecoded));
+		let enabled = [
+			true,
+			key.is_some(),
+			element.is_primitive() || element.as_int_array().is_some_and(|array| array.uuid(key).is_some()),
+			true,
+			true,
+			element.actions().contains(&ElementAction::InsertFromClipboard),
+			!is_root && !element.is_chunk(),
+			!is_root,
+			key.is_some(),
+			!conversions.is_empty(),
+			element.actions().contains(&ElementAction::SortCompoundByName),
+			true,
+			expandable,
+			expandable,
+			element.is_byte_array(),
+			// strings open in the string editor, where JSON can be formatted
+			is_editable_as_text(element) || element.is_string(),
+			element.as_byte().is_some_and(|byte| byte.value.as_boolean().is_some()),
+			element.is_chunk(),
+			element.as_chunk().is_some_and(NbtChunk::is_loaded),
+			element.is_region(),
+			element.as_chunk().is_some_and(|chunk| chunk.is_loaded() && !chunk.is_corrupted()),
+			element.is_region(),
+		];
+		let (window_width, window_height) = (window_dims.width as usize, window_dims.height as usize);
+		let width = Self::width(&Self::ENTRIES) + Self::ARROW_WIDTH;
+		let height = Self::ENTRIES.len() * 16;
+		let pos = Vec2u::new(mouse.x.min(window_width.saturating_sub(width)), mouse.y.min(window_height.saturating_sub(height)));
+		self.open = Some(OpenElementMenu {
+			indices,
+			pos,
+			enabled,
+			conversions,
+			submenu: None,
+			window_width,
+		});
+	}
+
+	pub fn close(&mut self) { self.open = None; }
+
+	fn width(entries: &[&str]) -> usize { entries.iter().map(|entry| entry.width()).max().unwrap_or(0) + Self::PADDING * 2 }
+
+	fn bounds(pos: Vec2u) -> AxisAlignedBoundingBox {
+		let width = Self::width(&Self::ENTRIES) + Self::ARROW_WIDTH;
+		AxisAlignedBoundingBox::new(pos.x, pos.x + width, pos.y, pos.y + Self::ENTRIES.len() * 16)
+	}
+
+	fn submenu_entries(open: &OpenElementMenu, entry: usize) -> Vec<&'static str> {
+		if entry == Self::CONVERT { open.conversions.iter().map(|target| target.name()).collect() } else { Self::SORTS.to_vec() }
+	}
+
+	/// Beside its entry, on whichever side of the menu has room for it
+	fn submenu_bounds(open: &OpenElementMenu, entry: usize) -> AxisAlignedBoundingBox {
+		let bounds = Self::bounds(open.pos);
+		let entries = Self::submenu_entries(open, entry);
+		let width = Self::width(&entries);
+		let x = if bounds.high().x + width <= open.window_width { bounds.high().x } else { bounds.low().x.saturating_sub(width) };
+		let y = open.pos.y + entry * 16;
+		AxisAlignedBoundingBox::new(x, x + width, y, y + entries.len() * 16)
+	}
+
+	#[must_use]
+	pub fn is_within_bounds(&self, mouse: Vec2u) -> bool {
+		self.open.as_ref().is_some_and(|open| Self::bounds(open.pos).contains(mouse) || open.submenu.is_some_and(|entry| Self::submenu_bounds(open, entry).contains(mouse)))
+	}
+
+	/// The action clicked and the element it's for, where clicking an entry with a submenu shows its choices instead
+	pub fn click(&mut self, mouse: Vec2u) -> Option<(OwnedIndices, ElementMenuAction)> {
+		let open = self.open.as_mut()?;
+		if let Some(entry) = open.submenu {
+			let bounds = Self::submenu_bounds(open, entry);
+			if bounds.contains(mouse) {
+				let idx = (mouse.y - bounds.low().y) / 16;
+				let action = if entry == Self::CONVERT {
+					ElementMenuAction::ConvertTo(*open.conversions.get(idx)?)
+				} else {
+					match idx {
+						0 => ElementMenuAction::SortByName,
+						1 => ElementMenuAction::SortByType,
+						_ => ElementMenuAction::SortRecursively,
+					}
+				};
+				return Some((open.indices.clone(), action))
+			}
+		}
+		if !Self::bounds(open.pos).contains(mouse) {
+			return None
+		}
+		let entry = (mouse.y - open.pos.y) / 16;
+		if !open.enabled.get(entry).copied().unwrap_or(false) {
+			return None
+		}
+		let action = match entry {
+			0 => ElementMenuAction::CopySnbt,
+			1 => ElementMenuAction::CopyKey,
+			2 => ElementMenuAction::CopyValue,
+			3 => ElementMenuAction::CopyValueSnbt,
+			4 => ElementMenuAction::CopyPath,
+			5 => ElementMenuAction::PasteInto,
+			6 => ElementMenuAction::Duplicate,
+			7 => ElementMenuAction::Delete,
+			8 => ElementMenuAction::Rename,
+			Self::CONVERT | Self::SORT => {
+				open.submenu = if open.submenu == Some(entry) { None } else { Some(entry) };
+				return None
+			}
+			11 => ElementMenuAction::Bookmark,
+			12 => ElementMenuAction::ExpandAll,
+			13 => ElementMenuAction::CollapseAll,
+			14 => ElementMenuAction::HexView,
+			15 => ElementMenuAction::EditAsText,
+			16 => ElementMenuAction::BooleanDisplay,
+			17 => ElementMenuAction::EditTimestamp,
+			18 => ElementMenuAction::MoveChunk,
+			19 => ElementMenuAction::NewChunk,
+			20 => ElementMenuAction::ExportChunk,
+			_ => ElementMenuAction::ImportChunk,
+		};
+		Some((open.indices.clone(), action))
+	}
+
+	pub fn render(&self, builder: &mut VertexBufferBuilder, mouse: Vec2u) {
+		let Some(open) = &self.open else { return };
+		let bounds = Self::bounds(open.pos);
+		let enabled = open.enabled.iter().copied();
+		Self::render_entries(builder, mouse, bounds, Self::ENTRIES.iter().copied().zip(enabled).enumerate().map(|(idx, (entry, enabled))| (entry, enabled, idx == Self::CONVERT || idx == Self::SORT, open.submenu == Some(idx))));
+		if let Some(entry) = open.submenu {
+			Self::render_entries(builder, mouse, Self::submenu_bounds(open, entry), Self::submenu_entries(open, entry).into_iter().map(|entry| (entry, true, false, false)));
+		}
+	}
+
+	/// Each entry is its text, whether it can be clicked, whether it has a submenu and whether that's shown
+	fn render_entries<'a>(builder: &mut VertexBufferBuilder, mouse: Vec2u, bounds: AxisAlignedBoundingBox, entries: impl Iterator<Item = (&'a str, bool, bool, bool)>) {
+		use std::fmt::Write;
+
+		builder.draw_texture_region_z(bounds.low(), ELEMENT_MENU_Z, DARK_STRIPE_UV + (1, 1), bounds.high() - bounds.low(), (14, 14));
+		for (idx, (entry, enabled, has_submenu, submenu_open)) in entries.enumerate() {
+			let (x, y) = (bounds.low().x, bounds.low().y + idx * 16);
+			if submenu_open || enabled && AxisAlignedBoundingBox::new(x, bounds.high().x, y, y + 16).contains(mouse) {
+				builder.draw_texture_region_z((x, y), ELEMENT_MENU_SELECTION_Z, HOVERED_STRIPE_UV, (bounds.high().x - x, 16), (16, 16));
+			}
+			builder.settings((x + Self::PADDING, y), false, ELEMENT_MENU_TEXT_Z);
+			builder.color = if enabled { TextColor::White } else { TextColor::DarkGray }.to_raw();
+			let _ = write!(builder, "{entry}");
+			if has_submenu {
+				builder.settings((bounds.high().x - Self::PADDING - ">".width(), y), false, ELEMENT_MENU_TEXT_Z);
+				let _ = write!(builder, ">");
+			}
+		}
+	}
+}
@@ -0,0 +1,171 @@
+use std::ops::{Deref, DerefMut};
+
+use winit::{dpi::PhysicalSize, event::MouseButton, keyboard::KeyCode};
+
+use crate::{
+	action_result::{ActionResult, IntoFailingActionResult},
+	config,
+	elements::region::NbtRegion,
+	render::{
+		assets::{DARK_STRIPE_UV, REPLACE_BOX_SELECTION_Z, REPLACE_BOX_Z},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+		widget::{
+			alert::manager::{AlertManager, Alertable},
+			search_box::{SEARCH_BOX_END_X, SEARCH_BOX_START_X},
+			text::{Cachelike, GoToBoxKeyResult, Text, get_cursor_idx},
+		},
+		window::Theme,
+	},
+	util::{StrExt, Vec2u},
+	workbench::tab::Tab,
+};
+
+/// Jumps to the element at an NBT path, or in a region to a chunk by its position, drawn over the row the replace box uses
+pub struct GoToBox(Text<GoToBoxAdditional, GoToBoxCache>);
+
+impl Deref for GoToBox {
+	type Target = Text<GoToBoxAdditional, GoToBoxCache>;
+
+	fn deref(&self) -> &Self::Target { &self.0 }
+}
+
+impl DerefMut for GoToBox {
+	fn deref_mut(&mut self) -> &mut Self::Target { &mut self.0 }
+}
+
+#[derive(Clone)]
+pub struct GoToBoxAdditional {
+	selected: bool,
+	pub horizontal_scroll: usize,
+}
+
+#[derive(Clone, Eq)]
+pub struct GoToBoxCache {
+	value: String,
+	cursor: usize,
+	selection: Option<usize>,
+}
+
+impl PartialEq for GoToBoxCache {
+	fn eq(&self, other: &Self) -> bool { self.value == other.value }
+}
+
+impl Cachelike<GoToBoxAdditional> for GoToBoxCache {
+	fn new(text: &Text<GoToBoxAdditional, Self>) -> Self
+	where Self: Sized {
+		Self {
+			value: text.value.clone(),
+			cursor: text.cursor,
+			selection: text.selection,
+		}
+	}
+
+	fn revert(self, text: &mut Text<GoToBoxAdditional, Self>)
+	where Self: Sized {
+		let Self { value, cursor, selection } = self;
+
+		text.value = value;
+		text.cursor = cursor;
+		text.selection = selection;
+	}
+}
+
+impl GoToBox {
+	pub const fn uninit() -> Self { Self(Text::uninit()) }
+
+	pub fn new() -> Self { Self(Text::new(String::new(), 0, true, GoToBoxAdditional { selected: false, horizontal_scroll: 0 })) }
+
+	/// With what can be gone to in a region as its placeholder when `in_region`
+	pub fn render(&self, builder: &mut VertexBufferBuilder, in_region: bool) {
+		use std::fmt::Write;
+
+		let pos = Vec2u::new(SEARCH_BOX_START_X, 47);
+
+		builder.draw_texture_region_z(pos, REPLACE_BOX_Z, DARK_STRIPE_UV, (builder.window_width() - SEARCH_BOX_END_X - pos.x, 22), (16, 16));
+
+		builder.horizontal_scroll = self.horizontal_scroll;
+
+		if self.value.is_empty() {
+			builder.settings(pos + (0, 3), false, REPLACE_BOX_Z);
+			builder.color = TextColor::Gray.to_raw();
+			let _ = write!(builder, "{}", if in_region { "Go to chunk -341, 127 or [0].Level.Status..." } else { "Go to Data.Inventory[0].id..." });
+		}
+		let color = match config::get_theme() {
+			Theme::Light => TextColor::Black,
+			Theme::Dark => TextColor::White,
+		};
+		self.0.render(builder, color, pos + (0, 3), REPLACE_BOX_Z, REPLACE_BOX_SELECTION_Z);
+
+		builder.horizontal_scroll = 0;
+	}
+
+	#[must_use]
+	pub fn is_within_bounds(mouse: Vec2u, window_dims: PhysicalSize<u32>) -> bool {
+		let pos = Vec2u::new(SEARCH_BOX_START_X, 47);
+
+		(pos.x..window_dims.width as usize - SEARCH_BOX_END_X - 1).contains(&mouse.x) && (47..71).contains(&mouse.y)
+	}
+
+	pub fn deselect(&mut self) {
+		self.selected = false;
+		self.cursor = 0;
+		self.selection = None;
+	}
+
+	pub fn select(&mut self, x: usize, button: MouseButton) {
+		if button == MouseButton::Right {
+			self.value.clear();
+			self.cursor = 0;
+			self.selection = None;
+			self.horizontal_scroll = 0;
+			self.0.post_input();
+		} else {
+			self.cursor = get_cursor_idx(&self.value, (x + self.horizontal_scroll) as isize);
+			self.selection = None;
+		}
+		self.selected = true;
+	}
+
+	#[must_use]
+	pub fn is_selected(&self) -> bool { self.selected }
+
+	pub fn post_input(&mut self, window_dims: PhysicalSize<u32>) {
+		self.0.post_input();
+		let field_width = window_dims.width as usize - SEARCH_BOX_END_X - SEARCH_BOX_START_X;
+		let precursor_width = self.value.split_at(self.cursor).0.width();
+		// 8px space just to look cleaner
+		let horizontal_scroll = (precursor_width + 8).saturating_sub(field_width);
+		self.horizontal_scroll = horizontal_scroll;
+	}
+
+	pub fn on_key_press(&mut self, key: KeyCode, ch: Option<char>, flags: u8, tab: &mut Tab, alerts: &mut AlertManager, window_dims: PhysicalSize<u32>) -> ActionResult {
+		if !self.is_selected() {
+			return ActionResult::Pass
+		}
+
+		match GoToBoxKeyResult::from(self.0.on_key_press(key, ch, flags)) {
+			GoToBoxKeyResult::NoAction => ActionResult::Pass,
+			GoToBoxKeyResult::GenericAction => {
+				self.post_input(window_dims);
+				ActionResult::Success(())
+			}
+			GoToBoxKeyResult::Escape => {
+				self.post_input(window_dims);
+				self.deselect();
+				ActionResult::Success(())
+			}
+			GoToBoxKeyResult::GoTo => {
+				self.post_input(window_dims);
+				match tab.root.as_region().and_then(|_| NbtRegion::parse_chunk_pos(&self.value)) {
+					Some(pos) => tab.go_to_chunk(pos),
+					None => tab.go_to_path(&self.value),
+				}
+				.alert_err(alerts)
+				.failure_on_err()?;
+				self.deselect();
+				ActionResult::Success(())
+			}
+		}
+	}
+}
@@ -0,0 +1,296 @@
+use std::ops::Range;
+
+use winit::keyboard::KeyCode;
+
+use crate::{
+	elements::{ComplexNbtElementVariant, array::NbtByteArray, element::NbtElement},
+	flags,
+	render::{
+		assets::{DARK_STRIPE_UV, HEADER_SIZE, HEX_VIEW_SELECTION_Z, HEX_VIEW_TEXT_Z, HEX_VIEW_Z, HOVERED_STRIPE_UV, SELECTION_UV},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+	},
+	tree::indices::{Indices, OwnedIndices},
+	util::{AxisAlignedBoundingBox, Vec2u, width_ascii},
+};
+
+/// A byte array shown as rows of 16 bytes, with their offsets and as text, in place of the tree of its tab
+///
+/// Only where it is and what's selected are kept here, the bytes are read from the tree every time so undoing and redoing show through
+pub struct HexView {
+	indices: OwnedIndices,
+	/// The first row shown
+	scroll: usize,
+	/// The byte typed over, which is one past the last to add to the end
+	cursor: usize,
+	/// The other end of the selection, which runs from it to the cursor
+	anchor: Option<usize>,
+	/// The high nibble typed into the byte at the cursor, waiting on the low one
+	nibble: Option<u8>,
+}
+
+impl HexView {
+	/// Byte arrays with at least this many bytes open in a hex view rather than in the tree
+	pub const DEFAULT_THRESHOLD: usize = 1024;
+	const BYTES_PER_ROW: usize = 16;
+	const PADDING: usize = 8;
+	const TREE_VIEW: &'static str = "< Tree view";
+	const BYTE_WIDTH: usize = width_ascii("00 ");
+	/// Of the widest printable ASCII character, so the text lines up under itself
+	const ASCII_WIDTH: usize = {
+		let mut max = 0;
+		let mut c = b' ';
+		while c < 0x7F {
+			let width = VertexBufferBuilder::CHAR_WIDTH[c as usize] as usize;
+			if width > max {
+				max = width;
+			}
+			c += 1;
+		}
+		max
+	};
+	const BYTES_X: usize = Self::PADDING + width_ascii("00000000") + Self::PADDING * 2;
+	const TEXT_X: usize = Self::BYTES_X + Self::BYTES_PER_ROW * Self::BYTE_WIDTH + Self::PADDING;
+
+	#[must_use]
+	pub const fn new(indices: OwnedIndices) -> Self {
+		Self {
+			indices,
+			scroll: 0,
+			cursor: 0,
+			anchor: None,
+			nibble: None,
+		}
+	}
+
+	/// Of the byte array shown
+	#[must_use]
+	pub fn indices(&self) -> &Indices { &self.indices }
+
+	/// [`None`] once what's at its indices isn't a byte array, in which case it should be closed
+	#[must_use]
+	pub fn array<'a>(&self, root: &'a NbtElement) -> Option<&'a NbtByteArray> { root.navigate(&self.indices).ok().and_then(|info| info.element.as_byte_array()) }
+
+	#[must_use]
+	pub const fn cursor(&self) -> usize { self.cursor }
+
+	/// The bytes selected, if there's a selection
+	#[must_use]
+	pub fn selection(&self, len: usize) -> Option<Range<usize>> {
+		let anchor = self.anchor?;
+		Some(anchor.min(self.cursor)..(anchor.max(self.cursor) + 1).min(len))
+	}
+
+	/// The bytes selected, or the one at the cursor
+	#[must_use]
+	pub fn selection_or_cursor(&self, len: usize) -> Range<usize> { self.selection(len).unwrap_or(self.cursor.min(len)..(self.cursor + 1).min(len)) }
+
+	fn rows(len: usize) -> usize { len / Self::BYTES_PER_ROW + 1 }
+
+	/// Below the row with the button back to the tree
+	fn visible_rows(window_height: usize) -> usize { window_height.saturating_sub(HEADER_SIZE + 16) / 16 }
+
+	fn max_scroll(len: usize, window_height: usize) -> usize { Self::rows(len).saturating_sub(Self::visible_rows(window_height)) }
+
+	/// Moves the cursor to `idx`, selecting everything between it and where the selection started if `extend`
+	pub fn move_cursor(&mut self, idx: usize, len: usize, extend: bool, window_height: usize) {
+		if extend {
+			self.anchor.get_or_insert(self.cursor);
+		} else {
+			self.anchor = None;
+		}
+		self.cursor = idx.min(len);
+		self.nibble = None;
+		let row = self.cursor / Self::BYTES_PER_ROW;
+		let visible_rows = Self::visible_rows(window_height).max(1);
+		if row < self.scroll {
+			self.scroll = row;
+		} else if row >= self.scroll + visible_rows {
+			self.scroll = row + 1 - visible_rows;
+		}
+	}
+
+	pub fn select_all(&mut self, len: usize) {
+		self.anchor = Some(0);
+		self.cursor = len.saturating_sub(1);
+		self.nibble = None;
+	}
+
+	/// Moves the cursor with the arrows, Home, End, Page Up and Page Down, where Shift selects along the way
+	#[must_use]
+	pub fn on_navigation_key(&mut self, key: KeyCode, flags: u8, len: usize, window_height: usize) -> bool {
+		if flags & !flags!(Ctrl + Shift) != 0 {
+			return false
+		}
+		let (ctrl, extend) = (flags & flags!(Ctrl) > 0, flags & flags!(Shift) > 0);
+		let page = Self::visible_rows(window_height).max(1) * Self::BYTES_PER_ROW;
+		let row_start = self.cursor - self.cursor % Self::BYTES_PER_ROW;
+		let idx = match key {
+			KeyCode::ArrowLeft => self.cursor.saturating_sub(1),
+			KeyCode::ArrowRight => self.cursor + 1,
+			KeyCode::ArrowUp => self.cursor.saturating_sub(Self::BYTES_PER_ROW),
+			KeyCode::ArrowDown => self.cursor + Self::BYTES_PER_ROW,
+			KeyCode::Home if ctrl => 0,
+			KeyCode::Home => row_start,
+			KeyCode::End if ctrl => len,
+			KeyCode::End => row_start + Self::BYTES_PER_ROW - 1,
+			KeyCode::PageUp => self.cursor.saturating_sub(page),
+			KeyCode::PageDown => self.cursor + page,
+			_ => return false,
+		};
+		self.move_cursor(idx, len, extend, window_height);
+		true
+	}
+
+	/// Types a hex digit into the byte at the cursor, returning the byte once both of its nibbles are typed
+	#[must_use]
+	pub fn type_nibble(&mut self, nibble: u8) -> Option<i8> {
+		self.anchor = None;
+		if let Some(high) = self.nibble.take() {
+			Some(((high << 4) | nibble).cast_signed())
+		} else {
+			self.nibble = Some(nibble);
+			None
+		}
+	}
+
+	#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+	pub fn on_scroll(&mut self, scroll: f32, len: usize, window_height: usize) {
+		let rows = (scroll.abs() * 3.0).round() as usize;
+		self.scroll = if scroll.is_sign_negative() { self.scroll.saturating_sub(rows) } else { self.scroll + rows }.min(Self::max_scroll(len, window_height));
+	}
+
+	fn tree_view_bounds() -> AxisAlignedBoundingBox { AxisAlignedBoundingBox::new(Self::PADDING, Self::PADDING + width_ascii(Self::TREE_VIEW), HEADER_SIZE, HEADER_SIZE + 16) }
+
+	/// Whether `mouse` is over the button going back to the tree
+	#[must_use]
+	pub fn is_on_tree_view_button(mouse: Vec2u) -> bool { Self::tree_view_bounds().contains(mouse) }
+
+	/// The byte clicked in either the hex or the text, where past the last row is one past the last byte
+	#[must_use]
+	pub fn byte_at(&self, mouse: Vec2u, len: usize, window_height: usize) -> Option<usize> {
+		let y = mouse.y.checked_sub(HEADER_SIZE + 16)?;
+		let row = self.scroll.min(Self::max_scroll(len, window_height)) + y / 16;
+		let column = if (Self::BYTES_X..Self::BYTES_X + Self::BYTES_PER_ROW * Self::BYTE_WIDTH).contains(&mouse.x) {
+			(mouse.x - Self::BYTES_X) / Self::BYTE_WIDTH
+		} else if (Self::TEXT_X..Self::TEXT_X + Self::BYTES_PER_ROW * Self::ASCII_WIDTH).contains(&mouse.x) {
+			(mouse.x - Self::TEXT_X) / Self::ASCII_WIDTH
+		} else {
+			return None
+		};
+		Some((row * Self::BYTES_PER_ROW + column).min(len))
+	}
+
+	/// `bytes` as a string of hex digits without anything between them, as copied from the hex view
+	#[must_use]
+	pub fn to_hex_string(bytes: impl Iterator<Item = i8>) -> String {
+		use std::fmt::Write;
+
+		let mut hex = String::new();
+		for byte in bytes {
+			let _ = write!(hex, "{:02x}", byte.cast_unsigned());
+		}
+		hex
+	}
+
+	/// The bytes of a string of hex digits, where whitespace between them is ignored
+	#[must_use]
+	pub fn parse_hex_string(s: &str) -> Option<Vec<i8>> {
+		let digits = s.chars().filter(|c| !c.is_ascii_whitespace()).collect::<String>();
+		if digits.len() % 2 != 0 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+			return None
+		}
+		(0..digits.len()).step_by(2).map(|idx| u8::from_str_radix(&digits[idx..idx + 2], 16).ok().map(u8::cast_signed)).collect()
+	}
+
+	pub fn render(&self, builder: &mut VertexBufferBuilder, root: &NbtElement, mouse: Vec2u) {
+		use std::fmt::Write;
+
+		let Some(array) = self.array(root) else { return };
+		let len = array.len();
+		let (window_width, window_height) = (builder.window_width(), builder.window_height());
+		builder.draw_texture_region_z((0, HEADER_SIZE), HEX_VIEW_Z, DARK_STRIPE_UV + (1, 1), (window_width, window_height.saturating_sub(HEADER_SIZE)), (14, 14));
+
+		builder.settings((Self::PADDING, HEADER_SIZE), false, HEX_VIEW_TEXT_Z);
+		builder.color = if Self::is_on_tree_view_button(mouse) { TextColor::Gold } else { TextColor::White }.to_raw();
+		let _ = write!(builder, "{}", Self::TREE_VIEW);
+		builder.color = TextColor::Gray.to_raw();
+		let _ = write!(builder, "   {len} bytes, at 0x{:x}", self.cursor.min(len));
+		if let Some(selection) = self.selection(len).filter(|selection| !selection.is_empty()) {
+			let _ = write!(builder, ", {} selected", selection.len());
+		}
+
+		let selection = self.selection(len).unwrap_or(0..0);
+		let hovered = self.byte_at(mouse, len, window_height);
+		let scroll = self.scroll.min(Self::max_scroll(len, window_height));
+		for (row, y) in (scroll..Self::rows(len)).zip((HEADER_SIZE + 16..window_height).step_by(16)) {
+			let start = row * Self::BYTES_PER_ROW;
+			builder.settings((Self::PADDING, y), false, HEX_VIEW_TEXT_Z);
+			builder.color = TextColor::DarkGray.to_raw();
+			let _ = write!(builder, "{start:08x}");
+			for (column, idx) in (start..=len.min(start + Self::BYTES_PER_ROW - 1)).enumerate() {
+				let (x, text_x) = (Self::BYTES_X + column * Self::BYTE_WIDTH, Self::TEXT_X + column * Self::ASCII_WIDTH);
+				if selection.contains(&idx) {
+					builder.draw_texture_region_z((x - 2, y), HEX_VIEW_SELECTION_Z, SELECTION_UV + (1, 1), (Self::BYTE_WIDTH - 1, 16), (14, 14));
+					builder.draw_texture_region_z((text_x, y), HEX_VIEW_SELECTION_Z, SELECTION_UV + (1, 1), (Self::ASCII_WIDTH, 16), (14, 14));
+				} else if hovered == Some(idx) {
+					builder.draw_texture_region_z((x - 2, y), HEX_VIEW_SELECTION_Z, HOVERED_STRIPE_UV, (Self::BYTE_WIDTH - 1, 16), (16, 16));
+					builder.draw_texture_region_z((text_x, y), HEX_VIEW_SELECTION_Z, HOVERED_STRIPE_UV, (Self::ASCII_WIDTH, 16), (16, 16));
+				}
+				if idx == self.cursor {
+					builder.draw_texture_region_z((x - 3, y), HEX_VIEW_SELECTION_Z, SELECTION_UV, (2, 16), (1, 16));
+					builder.draw_texture_region_z((text_x - 1, y), HEX_VIEW_SELECTION_Z, SELECTION_UV, (2, 16), (1, 16));
+				}
+				// the space to add to the end
+				let Some(byte) = array.get(idx).and_then(NbtElement::as_byte).map(|byte| byte.value.cast_unsigned()) else { continue };
+				builder.settings((x, y), false, HEX_VIEW_TEXT_Z);
+				if idx == self.cursor
+					&& let Some(high) = self.nibble
+				{
+					builder.color = TextColor::Yellow.to_raw();
+					let _ = write!(builder, "{high:x}_");
+				} else {
+					builder.color = if byte == 0 { TextColor::DarkGray } else { TextColor::TreePrimitive }.to_raw();
+					let _ = write!(builder, "{byte:02x}");
+				}
+				builder.settings((text_x, y), false, HEX_VIEW_TEXT_Z);
+				if byte.is_ascii_graphic() || byte == b' ' {
+					builder.color = TextColor::TreeString.to_raw();
+					let _ = write!(builder, "{}", byte as char);
+				} else {
+					builder.color = TextColor::DarkGray.to_raw();
+					let _ = write!(builder, ".");
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_hex_string() {
+		assert_eq!(HexView::to_hex_string([0, 15, -1, 74].into_iter()), "000fff4a");
+		assert_eq!(HexView::parse_hex_string("00 0f\nFF4a"), Some(vec![0, 15, -1, 74]));
+		assert_eq!(HexView::parse_hex_string("0f0"), None);
+		assert_eq!(HexView::parse_hex_string("0g"), None);
+		assert_eq!(HexView::parse_hex_string("+f"), None);
+	}
+
+	#[test]
+	fn test_hex_view_cursor() {
+		let mut view = HexView::new(OwnedIndices::new());
+		let window_height = HEADER_SIZE + 16 * 3;
+		assert!(view.on_navigation_key(KeyCode::End, flags!(), 20, window_height));
+		assert_eq!(view.cursor(), 15);
+		assert!(view.on_navigation_key(KeyCode::ArrowDown, flags!(Shift), 20, window_height));
+		assert_eq!(view.selection(20), Some(15..20));
+		// one past the last byte, to add to the end
+		assert!(view.on_navigation_key(KeyCode::End, flags!(Ctrl), 20, window_height));
+		assert_eq!(view.selection_or_cursor(20), 20..20);
+		assert_eq!(view.type_nibble(0xa), None);
+		assert_eq!(view.type_nibble(0xb), Some(0xab_u8.cast_signed()));
+	}
+}
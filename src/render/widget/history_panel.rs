@@ -0,0 +1,120 @@
+use winit::dpi::PhysicalSize;
+
+use crate::{
+	history::manager::{HistoryMananger, HistoryStep},
+	render::{
+		assets::{DARK_STRIPE_UV, HEADER_SIZE, HISTORY_PANEL_SELECTION_Z, HISTORY_PANEL_TEXT_Z, HISTORY_PANEL_Z, HOVERED_STRIPE_UV, SELECTION_UV},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+	},
+	util::{AxisAlignedBoundingBox, StrExt, Vec2u, truncate_to_width},
+};
+
+/// The active tab's history down the right of the window, newest first, toggled with Ctrl + Shift + H
+///
+/// Clicking a step undoes or redoes as many actions as it takes to get back to it, and roughly how much memory they take up is shown above them
+#[derive(Default)]
+pub struct HistoryPanel {
+	open: bool,
+	/// The first row shown
+	scroll: usize,
+}
+
+impl HistoryPanel {
+	pub const WIDTH: usize = 280;
+	const PADDING: usize = 6;
+	const GAP: usize = 6;
+	const TITLE_HEIGHT: usize = 20;
+	const ELLIPSIS: &'static str = "…";
+	const SAVED: &'static str = "saved";
+	const START: &'static str = "Start";
+
+	#[must_use]
+	pub const fn new() -> Self { Self { open: false, scroll: 0 } }
+
+	#[must_use]
+	pub const fn is_open(&self) -> bool { self.open }
+
+	pub fn toggle(&mut self) {
+		self.open = !self.open;
+		self.scroll = 0;
+	}
+
+	fn bounds(window_dims: PhysicalSize<u32>) -> AxisAlignedBoundingBox {
+		let width = window_dims.width as usize;
+		let height = (window_dims.height as usize).max(HEADER_SIZE);
+		AxisAlignedBoundingBox::new(width.saturating_sub(Self::WIDTH), width, HEADER_SIZE, height)
+	}
+
+	/// How many steps fit under the title
+	fn rows(window_dims: PhysicalSize<u32>) -> usize { ((window_dims.height as usize).saturating_sub(HEADER_SIZE + Self::TITLE_HEIGHT) / 16).max(1) }
+
+	#[must_use]
+	pub fn is_within_bounds(&self, mouse: Vec2u, window_dims: PhysicalSize<u32>) -> bool { self.open && Self::bounds(window_dims).contains(mouse) }
+
+	/// The index into [`HistoryMananger::steps`] of the row under `mouse`
+	#[must_use]
+	pub fn row_at(&self, mouse: Vec2u, len: usize, window_dims: PhysicalSize<u32>) -> Option<usize> {
+		if !self.is_within_bounds(mouse, window_dims) || mouse.y < HEADER_SIZE + Self::TITLE_HEIGHT {
+			return None
+		}
+		let idx = self.scroll + (mouse.y - HEADER_SIZE - Self::TITLE_HEIGHT) / 16;
+		(idx < len).then_some(idx)
+	}
+
+	#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+	pub fn on_scroll(&mut self, scroll: f32, len: usize, window_dims: PhysicalSize<u32>) {
+		let rows = (scroll.abs() * 3.0).round() as usize;
+		self.scroll = if scroll.is_sign_negative() { self.scroll.saturating_sub(rows) } else { self.scroll + rows }.min(len.saturating_sub(Self::rows(window_dims)));
+	}
+
+	/// `bytes` in KiB or MiB, to a tenth
+	#[allow(clippy::cast_precision_loss)] // only shown to a tenth
+	fn format_size(bytes: usize) -> String {
+		if bytes < 1_048_576 {
+			format!("{:.1} KiB", bytes as f64 / 1024.0)
+		} else {
+			format!("{:.1} MiB", bytes as f64 / 1_048_576.0)
+		}
+	}
+
+	pub fn render(&self, builder: &mut VertexBufferBuilder, history: &HistoryMananger, mouse: Vec2u, window_dims: PhysicalSize<u32>) {
+		use std::fmt::Write;
+
+		if !self.open {
+			return
+		}
+		let bounds = Self::bounds(window_dims);
+		builder.draw_texture_region_z(bounds.low(), HISTORY_PANEL_Z, DARK_STRIPE_UV + (1, 1), bounds.high() - bounds.low(), (14, 14));
+		builder.settings(bounds.low() + (Self::PADDING, 2), false, HISTORY_PANEL_TEXT_Z);
+		builder.color = TextColor::TreeKey.to_raw();
+		let _ = write!(builder, "History");
+		let usage = Self::format_size(history.size());
+		builder.settings((bounds.high().x.saturating_sub(Self::PADDING + usage.width()), bounds.low().y + 2), false, HISTORY_PANEL_TEXT_Z);
+		builder.color = TextColor::Gray.to_raw();
+		let _ = write!(builder, "{usage}");
+
+		let steps = history.steps();
+		let position = history.position();
+		let hovered = self.row_at(mouse, steps.len(), window_dims);
+		let width = bounds.high().x - bounds.low().x;
+		for (idx, HistoryStep { position: step_position, description, undone, saved }) in steps.into_iter().enumerate().skip(self.scroll).take(Self::rows(window_dims)) {
+			let y = bounds.low().y + Self::TITLE_HEIGHT + (idx - self.scroll) * 16;
+			if step_position == position {
+				builder.draw_texture_region_z((bounds.low().x, y), HISTORY_PANEL_SELECTION_Z, SELECTION_UV + (1, 1), (width, 16), (14, 14));
+			} else if hovered == Some(idx) {
+				builder.draw_texture_region_z((bounds.low().x, y), HISTORY_PANEL_SELECTION_Z, HOVERED_STRIPE_UV, (width, 16), (16, 16));
+			}
+			let saved_width = if saved { Self::SAVED.width() + Self::GAP } else { 0 };
+			let text = truncate_to_width(description.unwrap_or(Self::START), width.saturating_sub(Self::PADDING * 2 + saved_width), Self::ELLIPSIS);
+			builder.settings((bounds.low().x + Self::PADDING, y), false, HISTORY_PANEL_TEXT_Z);
+			builder.color = if undone || description.is_none() { TextColor::Gray } else { TextColor::White }.to_raw();
+			let _ = write!(builder, "{text}");
+			if saved {
+				builder.settings((bounds.high().x.saturating_sub(Self::PADDING + Self::SAVED.width()), y), false, HISTORY_PANEL_TEXT_Z);
+				builder.color = TextColor::Green.to_raw();
+				let _ = write!(builder, "{}", Self::SAVED);
+			}
+		}
+	}
+}
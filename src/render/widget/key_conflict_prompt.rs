@@ -0,0 +1,206 @@
+use compact_str::{CompactString, format_compact};
+use winit::dpi::PhysicalSize;
+
+use crate::{
+	elements::{
+		NbtElementAndKey,
+		compound::CompoundMap,
+		element::{NbtElement, NbtPattern},
+	},
+	render::{
+		assets::{DARK_STRIPE_UV, KEY_CONFLICT_PROMPT_TEXT_Z, KEY_CONFLICT_PROMPT_Z},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+	},
+	tree::indices::OwnedIndices,
+	util::{AxisAlignedBoundingBox, StrExt, Vec2u},
+};
+
+/// Asks what to do with entries being dropped into a compound which already has their keys, one key at a time
+pub struct KeyConflictPrompt {
+	/// Where the held entry is being dropped
+	indices: OwnedIndices,
+	/// The first key which hasn't been resolved yet
+	key: CompactString,
+	/// How many keys conflict, including the ones already resolved
+	total: usize,
+	/// What was chosen for each key before this one
+	resolutions: Vec<KeyConflictResolution>,
+	apply_to_all: bool,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum KeyConflictResolution {
+	/// Removes the entry already there, as part of the same action as the drop
+	Replace,
+	/// Renames the dropped entry with a numeric suffix
+	KeepBoth,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum KeyConflictPromptButton {
+	Replace,
+	KeepBoth,
+	Cancel,
+	ApplyToAll,
+}
+
+impl KeyConflictPrompt {
+	const PADDING: usize = 4;
+	const SEPARATOR: &'static str = "   ";
+	const BUTTONS: [(&'static str, KeyConflictPromptButton); 3] = [("Replace existing", KeyConflictPromptButton::Replace), ("Keep both", KeyConflictPromptButton::KeepBoth), ("Cancel", KeyConflictPromptButton::Cancel)];
+
+	/// Opens it for the first of `conflicts`, if there are any left once `resolutions` are taken into account
+	#[must_use]
+	pub fn new(indices: OwnedIndices, mut conflicts: Vec<CompactString>, resolutions: Vec<KeyConflictResolution>) -> Option<Self> {
+		let total = conflicts.len();
+		let key = conflicts.drain(..).nth(resolutions.len())?;
+		Some(Self {
+			indices,
+			key,
+			total,
+			resolutions,
+			apply_to_all: false,
+		})
+	}
+
+	/// Chooses `resolution` for the current key, or every key left if applying to all, returning where to drop and what was chosen for each key
+	#[must_use]
+	pub fn resolve(self, resolution: KeyConflictResolution) -> (OwnedIndices, Vec<KeyConflictResolution>) {
+		let Self { indices, total, mut resolutions, apply_to_all, .. } = self;
+		let count = if apply_to_all { total - resolutions.len() } else { 1 };
+		resolutions.extend(core::iter::repeat_n(resolution, count));
+		(indices, resolutions)
+	}
+
+	pub fn toggle_apply_to_all(&mut self) { self.apply_to_all = !self.apply_to_all; }
+
+	fn has_apply_to_all(&self) -> bool { self.total - self.resolutions.len() > 1 }
+
+	fn message(&self) -> String {
+		if self.total > 1 {
+			format!("{:?} is already here ({} of {})", self.key, self.resolutions.len() + 1, self.total)
+		} else {
+			format!("{:?} is already here", self.key)
+		}
+	}
+
+	fn apply_to_all_text(&self) -> String { format!("[{}] Apply to all {} left", if self.apply_to_all { 'x' } else { ' ' }, self.total - self.resolutions.len()) }
+
+	fn bounds(&self, window_dims: PhysicalSize<u32>) -> AxisAlignedBoundingBox {
+		let buttons_width = Self::BUTTONS.iter().map(|(text, _)| text.width()).sum::<usize>() + Self::SEPARATOR.width() * (Self::BUTTONS.len() - 1);
+		let width = self.message().width().max(buttons_width).max(self.apply_to_all_text().width()) + Self::PADDING * 2;
+		let height = if self.has_apply_to_all() { 48 } else { 32 } + Self::PADDING * 2;
+		let x = (window_dims.width as usize).saturating_sub(width) / 2;
+		let y = (window_dims.height as usize).saturating_sub(height) / 2;
+		AxisAlignedBoundingBox::new(x, x + width, y, y + height)
+	}
+
+	fn buttons(&self, window_dims: PhysicalSize<u32>) -> Vec<(Vec2u, String, KeyConflictPromptButton)> {
+		let bounds = self.bounds(window_dims);
+		let mut x = bounds.low().x + Self::PADDING;
+		let y = bounds.low().y + Self::PADDING + 16;
+		let mut buttons = Self::BUTTONS
+			.map(|(text, button)| {
+				let pos = Vec2u::new(x, y);
+				x += text.width() + Self::SEPARATOR.width();
+				(pos, text.to_owned(), button)
+			})
+			.to_vec();
+		if self.has_apply_to_all() {
+			buttons.push((Vec2u::new(bounds.low().x + Self::PADDING, y + 16), self.apply_to_all_text(), KeyConflictPromptButton::ApplyToAll));
+		}
+		buttons
+	}
+
+	#[must_use]
+	pub fn button_at(&self, mouse: Vec2u, window_dims: PhysicalSize<u32>) -> Option<KeyConflictPromptButton> {
+		self.buttons(window_dims)
+			.into_iter()
+			.find(|(pos, text, _)| AxisAlignedBoundingBox::new(pos.x, pos.x + text.width(), pos.y, pos.y + 16).contains(mouse))
+			.map(|(_, _, button)| button)
+	}
+
+	pub fn render(&self, builder: &mut VertexBufferBuilder, mouse: Vec2u) {
+		use std::fmt::Write;
+
+		let window_dims = builder.window_dims();
+		let bounds = self.bounds(window_dims);
+		builder.draw_texture_region_z(bounds.low(), KEY_CONFLICT_PROMPT_Z, DARK_STRIPE_UV + (1, 1), bounds.high() - bounds.low(), (14, 14));
+
+		builder.settings(bounds.low() + (Self::PADDING, Self::PADDING), false, KEY_CONFLICT_PROMPT_TEXT_Z);
+		builder.color = TextColor::Yellow.to_raw();
+		let _ = write!(builder, "{}", self.message());
+		let hovered = self.button_at(mouse, window_dims);
+		for (pos, text, button) in self.buttons(window_dims) {
+			builder.settings(pos, false, KEY_CONFLICT_PROMPT_TEXT_Z);
+			builder.color = if hovered == Some(button) { TextColor::Gold } else { TextColor::White }.to_raw();
+			let _ = write!(builder, "{text}");
+		}
+	}
+}
+
+/// The keys of a compound or chunk
+#[must_use]
+pub fn compound_map(element: &NbtElement) -> Option<&CompoundMap> {
+	match element.as_pattern() {
+		NbtPattern::Compound(compound) => Some(&compound.map),
+		NbtPattern::Chunk(chunk) => Some(&chunk.map),
+		_ => None,
+	}
+}
+
+/// The keys of `entries` which would already be in `map` by the time each is added, in order
+///
+/// Keys which haven't been resolved yet are assumed to be replaced, since either way the key is there afterward
+#[must_use]
+pub fn conflicting_keys<'a>(map: &CompoundMap, entries: impl IntoIterator<Item = &'a NbtElementAndKey>, resolutions: &[KeyConflictResolution]) -> Vec<CompactString> {
+	let mut added = Vec::<CompactString>::new();
+	let mut conflicts = Vec::new();
+	for key in entries.into_iter().filter_map(|(key, _)| key.as_ref()) {
+		let has = |key: &str| map.has(key) || added.iter().any(|added| added == key);
+		if has(key) {
+			if resolutions.get(conflicts.len()) == Some(&KeyConflictResolution::KeepBoth) {
+				added.push(unique_key(key, has));
+			}
+			conflicts.push(key.clone());
+		} else {
+			added.push(key.clone());
+		}
+	}
+	conflicts
+}
+
+/// `key` with the first numeric suffix from 2 that `has` doesn't have
+#[must_use]
+pub fn unique_key(key: &str, has: impl Fn(&str) -> bool) -> CompactString {
+	let mut n = 2_usize;
+	loop {
+		let unique = format_compact!("{key}_{n}");
+		if !has(&unique) {
+			return unique
+		}
+		n += 1;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_conflicting_keys() {
+		let (_, compound) = NbtElement::from_str(r#"{"Pos":[1d,2d,3d],"Pos_2":1b,"id":"a"}"#).expect("Valid SNBT");
+		let map = compound_map(&compound).expect("Compound");
+		let (entries, _) = NbtElement::entries_from_str("Pos: 1b\nMotion: 2b\nid: \"b\"\nMotion: 3b");
+
+		assert_eq!(conflicting_keys(map, &entries, &[]), ["Pos", "id", "Motion"]);
+		assert_eq!(unique_key("Pos", |key| map.has(key)), "Pos_3");
+
+		let prompt = KeyConflictPrompt::new(OwnedIndices::new(), conflicting_keys(map, &entries, &[]), vec![KeyConflictResolution::KeepBoth]).expect("Conflicts left");
+		assert_eq!(prompt.key, "id");
+		let mut prompt = prompt;
+		prompt.toggle_apply_to_all();
+		assert_eq!(prompt.resolve(KeyConflictResolution::Replace).1, [KeyConflictResolution::KeepBoth, KeyConflictResolution::Replace, KeyConflictResolution::Replace]);
+	}
+}
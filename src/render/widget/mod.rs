@@ -1,11 +1,27 @@
 pub mod alert;
+pub mod breadcrumbs;
 pub mod button;
+pub mod clipboard_ring;
+#[cfg(target_arch = "wasm32")] pub mod close_prompt;
+pub mod element_menu;
+pub mod go_to_box;
+pub mod hex_view;
+pub mod history_panel;
+pub mod key_conflict_prompt;
 pub mod notification;
+pub mod recent_files;
+pub mod region_issues_banner;
+pub mod reload_banner;
 pub mod replace_box;
+pub mod scratch_pad;
 pub mod search_box;
 pub mod selected_line;
 pub mod selected_text;
+pub mod string_editor;
+pub mod tab_menu;
 pub mod text;
+pub mod url_box;
+pub mod values_box;
 
 use fxhash::FxHashSet;
 use winit::dpi::PhysicalSize;
@@ -96,6 +112,8 @@ impl<'a> WidgetContext<'a> {
 #[derive(Default)]
 pub struct WidgetAccumulatedResult {
     pub open_file_requests: usize,
+    pub toggle_recent_files: bool,
+    pub toggle_url_box: bool,
 }
 
 #[allow(dead_code)]
@@ -125,6 +143,10 @@ impl<'w> WidgetContextMut<'w> {
 
     pub fn open_file_request(&mut self) { self.accumulated.open_file_requests += 1; }
 
+    pub fn toggle_recent_files(&mut self) { self.accumulated.toggle_recent_files = !self.accumulated.toggle_recent_files; }
+
+    pub fn toggle_url_box(&mut self) { self.accumulated.toggle_url_box = !self.accumulated.toggle_url_box; }
+
     pub fn take_accumulated(&mut self) -> WidgetAccumulatedResult { core::mem::take(&mut self.accumulated) }
 
     #[must_use]
@@ -18,6 +18,23 @@ pub enum NotificationKind {
 	Scale,
 	Find,
 	Replace,
+	Bookmark,
+	Watch,
+	Session,
+	ReadOnly,
+	Save,
+	Compare,
+	Expand,
+	Duplicate,
+	Convert,
+	NumberBase,
+	RegionHeatmap,
+	Booleans,
+	FloatDisplay,
+	HexView,
+	ValuesText,
+	HeldEntry,
+	History,
 }
 
 pub struct Notification {
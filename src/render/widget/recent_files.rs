@@ -0,0 +1,125 @@
+use std::path::PathBuf;
+
+use crate::{
+	config,
+	render::{
+		assets::{DARK_STRIPE_UV, HOVERED_STRIPE_UV, RECENT_FILES_SELECTION_Z, RECENT_FILES_TEXT_Z, RECENT_FILES_Z},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+	},
+	util::{AxisAlignedBoundingBox, StrExt, Vec2u},
+};
+
+/// The files opened most recently, dropped down from the new tab button with a right click
+#[derive(Default)]
+pub struct RecentFilesMenu {
+	open: bool,
+}
+
+pub enum RecentFilesMenuAction {
+	Open(PathBuf),
+	Remove(PathBuf),
+}
+
+struct Entry {
+	path: PathBuf,
+	name: String,
+	/// The directory it's in
+	location: String,
+	exists: bool,
+}
+
+impl RecentFilesMenu {
+	const X: usize = 16;
+	const Y: usize = 46;
+	const PADDING: usize = 4;
+	const GAP: usize = 8;
+	const REMOVE: &'static str = "×";
+	const EMPTY: &'static str = "No recent files";
+
+	#[must_use]
+	pub const fn new() -> Self { Self { open: false } }
+
+	#[must_use]
+	pub const fn is_open(&self) -> bool { self.open }
+
+	pub const fn toggle(&mut self) { self.open = !self.open; }
+
+	pub const fn close(&mut self) { self.open = false; }
+
+	fn entries() -> Vec<Entry> {
+		config::get_recent_files()
+			.into_iter()
+			.map(|path| Entry {
+				name: path.file_name().map_or_else(|| path.to_string_lossy().into_owned(), |name| name.to_string_lossy().into_owned()),
+				location: path.parent().map(|parent| parent.to_string_lossy().into_owned()).unwrap_or_default(),
+				exists: path.is_file(),
+				path,
+			})
+			.collect()
+	}
+
+	fn width(entries: &[Entry]) -> usize {
+		let widest = entries.iter().map(|entry| entry.name.width() + Self::GAP + entry.location.width() + Self::GAP + Self::REMOVE.width()).max().unwrap_or(Self::EMPTY.width());
+		widest + Self::PADDING * 2
+	}
+
+	fn bounds(entries: &[Entry]) -> AxisAlignedBoundingBox { AxisAlignedBoundingBox::new(Self::X, Self::X + Self::width(entries), Self::Y, Self::Y + entries.len().max(1) * 16) }
+
+	#[must_use]
+	pub fn is_within_bounds(&self, mouse: Vec2u) -> bool { self.open && Self::bounds(&Self::entries()).contains(mouse) }
+
+	/// Opening a file which no longer exists does nothing, so it can only be removed
+	#[must_use]
+	pub fn click(&self, mouse: Vec2u) -> Option<RecentFilesMenuAction> {
+		let entries = Self::entries();
+		let bounds = Self::bounds(&entries);
+		if !self.open || !bounds.contains(mouse) {
+			return None
+		}
+		let entry = entries.into_iter().nth((mouse.y - Self::Y) / 16)?;
+		let remove_x = bounds.high().x - Self::PADDING - Self::REMOVE.width();
+		if mouse.x >= remove_x {
+			Some(RecentFilesMenuAction::Remove(entry.path))
+		} else if entry.exists {
+			Some(RecentFilesMenuAction::Open(entry.path))
+		} else {
+			None
+		}
+	}
+
+	pub fn render(&self, builder: &mut VertexBufferBuilder, mouse: Vec2u) {
+		use std::fmt::Write;
+
+		if !self.open {
+			return
+		}
+
+		let entries = Self::entries();
+		let bounds = Self::bounds(&entries);
+		builder.draw_texture_region_z(bounds.low(), RECENT_FILES_Z, DARK_STRIPE_UV + (1, 1), bounds.high() - bounds.low(), (14, 14));
+		if entries.is_empty() {
+			builder.settings((Self::X + Self::PADDING, Self::Y), false, RECENT_FILES_TEXT_Z);
+			builder.color = TextColor::Gray.to_raw();
+			let _ = write!(builder, "{}", Self::EMPTY);
+			return
+		}
+
+		let remove_x = bounds.high().x - Self::PADDING - Self::REMOVE.width();
+		for (idx, entry) in entries.iter().enumerate() {
+			let y = Self::Y + idx * 16;
+			if AxisAlignedBoundingBox::new(bounds.low().x, bounds.high().x, y, y + 16).contains(mouse) {
+				builder.draw_texture_region_z((Self::X, y), RECENT_FILES_SELECTION_Z, HOVERED_STRIPE_UV, (bounds.high().x - Self::X, 16), (16, 16));
+			}
+			builder.settings((Self::X + Self::PADDING, y), false, RECENT_FILES_TEXT_Z);
+			builder.color = if entry.exists { TextColor::White } else { TextColor::DarkGray }.to_raw();
+			let _ = write!(builder, "{}", entry.name);
+			builder.settings((Self::X + Self::PADDING + entry.name.width() + Self::GAP, y), false, RECENT_FILES_TEXT_Z);
+			builder.color = if entry.exists { TextColor::Gray } else { TextColor::DarkGray }.to_raw();
+			let _ = write!(builder, "{}", entry.location);
+			builder.settings((remove_x, y), false, RECENT_FILES_TEXT_Z);
+			builder.color = if (remove_x..bounds.high().x).contains(&mouse.x) && (y..y + 16).contains(&mouse.y) { TextColor::Red } else { TextColor::Gray }.to_raw();
+			let _ = write!(builder, "{}", Self::REMOVE);
+		}
+	}
+}
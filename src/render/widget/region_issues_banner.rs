@@ -0,0 +1,103 @@
+use winit::dpi::PhysicalSize;
+
+use crate::{
+	render::{
+		assets::{DARK_STRIPE_UV, HEADER_SIZE, RELOAD_BANNER_TEXT_Z, RELOAD_BANNER_Z},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+	},
+	util::{StrExt, Vec2u},
+	workbench::tab::Tab,
+};
+
+/// Tells of the problems found in the header of a region file when it was read, which can be listed above it or repaired, see [`region_integrity`](crate::elements::region_integrity)
+pub struct RegionIssuesBanner;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum RegionIssuesBannerButton {
+	Details,
+	Repair,
+	Ignore,
+}
+
+impl RegionIssuesBanner {
+	const START_X: usize = 4;
+	const DETAILS: &'static str = "Details";
+	const HIDE_DETAILS: &'static str = "Hide details";
+	const REPAIR: &'static str = "Repair";
+	const SEPARATOR: &'static str = " / ";
+	const IGNORE: &'static str = "Ignore";
+
+	/// Under the [`ReloadBanner`](super::reload_banner::ReloadBanner) while that's shown, since reloading checks the file again anyway
+	#[must_use]
+	pub fn is_visible(tab: &Tab) -> bool { !tab.region_issues.is_empty() && !tab.changed_on_disk }
+
+	#[must_use]
+	pub fn is_within_bounds(tab: &Tab, mouse: Vec2u, window_dims: PhysicalSize<u32>) -> bool { mouse.y >= (window_dims.height as usize).saturating_sub(16 * (1 + Self::details_rows(tab, window_dims.height as usize))) }
+
+	fn message(tab: &Tab) -> String {
+		let len = tab.region_issues.len();
+		format!("Region header has {len} problem{} — ", if len == 1 { "" } else { "s" })
+	}
+
+	/// Of the list above the banner, with the last saying how many more there are when they don't all fit
+	fn details_rows(tab: &Tab, window_height: usize) -> usize {
+		if !tab.region_issues_open {
+			return 0
+		}
+		tab.region_issues.len().min(window_height.saturating_sub(HEADER_SIZE + 16) / 16)
+	}
+
+	fn buttons(tab: &Tab) -> [(usize, &'static str, RegionIssuesBannerButton); 3] {
+		let details_x = Self::START_X + Self::message(tab).width();
+		let details = if tab.region_issues_open { Self::HIDE_DETAILS } else { Self::DETAILS };
+		let repair_x = details_x + details.width() + Self::SEPARATOR.width();
+		let ignore_x = repair_x + Self::REPAIR.width() + Self::SEPARATOR.width();
+		[
+			(details_x, details, RegionIssuesBannerButton::Details),
+			(repair_x, Self::REPAIR, RegionIssuesBannerButton::Repair),
+			(ignore_x, Self::IGNORE, RegionIssuesBannerButton::Ignore),
+		]
+	}
+
+	#[must_use]
+	pub fn button_at(tab: &Tab, mouse: Vec2u, window_dims: PhysicalSize<u32>) -> Option<RegionIssuesBannerButton> {
+		if mouse.y < (window_dims.height as usize).saturating_sub(16) {
+			return None
+		}
+		Self::buttons(tab).into_iter().find(|(x, text, _)| (*x..*x + text.width()).contains(&mouse.x)).map(|(_, _, button)| button)
+	}
+
+	pub fn render(builder: &mut VertexBufferBuilder, tab: &Tab, mouse: Vec2u) {
+		use std::fmt::Write;
+
+		let y = builder.window_height().saturating_sub(16);
+		let rows = Self::details_rows(tab, builder.window_height());
+		builder.draw_texture_region_z((0, y - rows * 16), RELOAD_BANNER_Z, DARK_STRIPE_UV + (1, 1), (builder.window_width(), 16 * (rows + 1)), (14, 14));
+
+		for (row, issue) in tab.region_issues.iter().take(rows).enumerate() {
+			builder.settings((Self::START_X, y - (rows - row) * 16), false, RELOAD_BANNER_TEXT_Z);
+			builder.color = TextColor::Gray.to_raw();
+			if row + 1 == rows && tab.region_issues.len() > rows {
+				let _ = write!(builder, "…and {} more", tab.region_issues.len() - row);
+			} else {
+				let _ = write!(builder, "{issue}");
+			}
+		}
+
+		builder.settings((Self::START_X, y), false, RELOAD_BANNER_TEXT_Z);
+		builder.color = TextColor::Yellow.to_raw();
+		let _ = write!(builder, "{}", Self::message(tab));
+		let hovered = Self::button_at(tab, mouse, builder.window_dims());
+		for (idx, (x, text, button)) in Self::buttons(tab).into_iter().enumerate() {
+			if idx > 0 {
+				builder.settings((x - Self::SEPARATOR.width(), y), false, RELOAD_BANNER_TEXT_Z);
+				builder.color = TextColor::Gray.to_raw();
+				let _ = write!(builder, "{}", Self::SEPARATOR);
+			}
+			builder.settings((x, y), false, RELOAD_BANNER_TEXT_Z);
+			builder.color = if hovered == Some(button) { TextColor::Gold } else { TextColor::White }.to_raw();
+			let _ = write!(builder, "{text}");
+		}
+	}
+}
@@ -0,0 +1,70 @@
+use winit::dpi::PhysicalSize;
+
+use crate::{
+	render::{
+		assets::{DARK_STRIPE_UV, RELOAD_BANNER_TEXT_Z, RELOAD_BANNER_Z},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+	},
+	util::{StrExt, Vec2u},
+	workbench::tab::Tab,
+};
+
+/// Asks whether to reload a tab whose file was written to by another program while it had unsaved changes
+pub struct ReloadBanner;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum ReloadBannerButton {
+	Reload,
+	Ignore,
+}
+
+impl ReloadBanner {
+	const START_X: usize = 4;
+	const MESSAGE: &'static str = "File changed on disk — ";
+	const RELOAD: &'static str = "Reload";
+	const SEPARATOR: &'static str = " / ";
+	const IGNORE: &'static str = "Ignore";
+
+	#[must_use]
+	pub fn is_visible(tab: &Tab) -> bool { tab.changed_on_disk }
+
+	#[must_use]
+	pub fn is_within_bounds(mouse: Vec2u, window_dims: PhysicalSize<u32>) -> bool { mouse.y >= (window_dims.height as usize).saturating_sub(16) }
+
+	fn buttons() -> [(usize, &'static str, ReloadBannerButton); 2] {
+		let reload_x = Self::START_X + Self::MESSAGE.width();
+		let ignore_x = reload_x + Self::RELOAD.width() + Self::SEPARATOR.width();
+		[(reload_x, Self::RELOAD, ReloadBannerButton::Reload), (ignore_x, Self::IGNORE, ReloadBannerButton::Ignore)]
+	}
+
+	#[must_use]
+	pub fn button_at(mouse: Vec2u, window_dims: PhysicalSize<u32>) -> Option<ReloadBannerButton> {
+		if !Self::is_within_bounds(mouse, window_dims) {
+			return None
+		}
+		Self::buttons().into_iter().find(|(x, text, _)| (*x..*x + text.width()).contains(&mouse.x)).map(|(_, _, button)| button)
+	}
+
+	pub fn render(builder: &mut VertexBufferBuilder, mouse: Vec2u) {
+		use std::fmt::Write;
+
+		let y = builder.window_height().saturating_sub(16);
+		builder.draw_texture_region_z((0, y), RELOAD_BANNER_Z, DARK_STRIPE_UV + (1, 1), (builder.window_width(), 16), (14, 14));
+
+		builder.settings((Self::START_X, y), false, RELOAD_BANNER_TEXT_Z);
+		builder.color = TextColor::Yellow.to_raw();
+		let _ = write!(builder, "{}", Self::MESSAGE);
+		let hovered = Self::button_at(mouse, builder.window_dims());
+		for (idx, (x, text, button)) in Self::buttons().into_iter().enumerate() {
+			if idx > 0 {
+				builder.settings((x - Self::SEPARATOR.width(), y), false, RELOAD_BANNER_TEXT_Z);
+				builder.color = TextColor::Gray.to_raw();
+				let _ = write!(builder, "{}", Self::SEPARATOR);
+			}
+			builder.settings((x, y), false, RELOAD_BANNER_TEXT_Z);
+			builder.color = if hovered == Some(button) { TextColor::Gold } else { TextColor::White }.to_raw();
+			let _ = write!(builder, "{text}");
+		}
+	}
+}
@@ -23,7 +23,7 @@ use crate::{
 		widget::{
 			alert::manager::AlertManager,
 			notification::{Notification, NotificationKind, manager::NotificationManager},
-			search_box::{SEARCH_BOX_END_X, SEARCH_BOX_START_X, SearchBox, SearchFlags, SearchMode},
+			search_box::{SEARCH_BOX_END_X, SEARCH_BOX_START_X, SearchBox, SearchFlags, SearchMode, SearchTagType},
 			text::{Cachelike, ReplaceBoxKeyResult, Text, get_cursor_idx},
 		},
 		window::Theme,
@@ -270,6 +270,7 @@ impl ReplaceBox {
 				ActionResult::Success(())
 			}
 			ReplaceBoxKeyResult::ReplaceAll => {
+				tab.ensure_writable(notifications)?;
 				let (notification, bulk) = self.replace(mutable_indices!(tab), &mut tab.root, search_box);
 				if let Some(bulk) = bulk {
 					tab.history.append(bulk);
@@ -298,6 +299,9 @@ impl ReplaceBox {
 
 		let start = Timestamp::now();
 		let Some(replacement) = SearchReplacement::new(search_box.value.clone(), self.value.clone()) else {
+			if let Some(e) = search_box.regex_error() {
+				return (Notification::new(format!("Invalid regex ({e})"), TextColor::Red, NotificationKind::Replace), None)
+			}
 			return (Notification::new(format!("Invalid replacement syntax ({})", self.value), TextColor::Red, NotificationKind::Replace), None)
 		};
 		let (bulk, errors) = Self::replace_by_search_box0(mi, root, &replacement);
@@ -338,6 +342,7 @@ impl ReplaceBox {
 		// `NbtCompound` / `NbtList` / `*Array` (parent) (guaranteed to not be dropped)
 		// and the element at `current_indices` (guaranteed to not be dropped; will move memory address to the resulting WorkbenchAction)
 		// therefore, in the case of all writes, (as long as `element` isn't read after it is replaced)
+		root.decode_chunks(mi.bookmarks);
 		let alternative_root: &'root2 mut NbtElement = unsafe { (&raw const root).cast::<&'root2 mut NbtElement>().read() };
 
 		let mut current_indices = OwnedIndices::new();
@@ -448,7 +453,7 @@ impl ReplaceBox {
 	pub fn replace_by_bookmarked_lines0<'m1, 'm2: 'm1>(old_mi: &'m1 mut MutableIndices<'m2>, root: &mut NbtElement, replacement: &BookmarkedBasedSearchReplacement) -> (WorkbenchAction, Vec<ReplacementError>) {
 		// the `rev` is done so that pop (O(1) time) removes the first element rather than the last
 		let mut bookmark_indices = old_mi.bookmarks.iter().rev().map(|bookmark| indices_for_true(bookmark.true_line_number(), root)).collect::<Vec<_>>();
-		let mut mutable_indices = MutableIndices::new(old_mi.subscription, old_mi.selected_text, old_mi.bookmarks);
+		let mut mutable_indices = MutableIndices::new(old_mi.subscription, old_mi.selected_text, old_mi.bookmarks, old_mi.selection, old_mi.boolean_overrides);
 		mutable_indices.temp = bookmark_indices.iter_mut().collect::<Vec<_>>();
 
 		let mut fake_path = FilePath::new("dummy.nbt").expect("Expected dummy value to be valid");
@@ -470,6 +475,7 @@ impl ReplaceBox {
 
 pub struct SearchReplacement {
 	search_flags: SearchFlags,
+	tag_type: SearchTagType,
 	inner: SearchReplacementInner,
 }
 
@@ -483,6 +489,7 @@ impl SearchReplacement {
 	pub fn new(find: String, replacement: String) -> Option<Self> {
 		let search_mode = config::get_search_mode();
 		let search_flags = config::get_search_flags();
+		let tag_type = config::get_search_tag_type();
 		let exact_match = config::get_search_exact_match();
 		Some(match search_mode {
 			SearchMode::String => Self {
@@ -492,12 +499,14 @@ impl SearchReplacement {
 					case_sensitive: exact_match,
 				},
 				search_flags,
+				tag_type,
 			},
 			SearchMode::Regex =>
-				if let Some(regex) = create_regex(find, exact_match) {
+				if let Ok(regex) = create_regex(find, exact_match) {
 					Self {
 						inner: SearchReplacementInner::Regex { regex, replacement },
 						search_flags,
+						tag_type,
 					}
 				} else {
 					return None
@@ -513,6 +522,7 @@ impl SearchReplacement {
 					Self {
 						inner: SearchReplacementInner::Snbt { find, replacement, exact_match },
 						search_flags,
+						tag_type,
 					}
 				} else {
 					return None
@@ -521,6 +531,9 @@ impl SearchReplacement {
 	}
 
 	pub fn matches(&self, kv: NbtElementAndKeyRef) -> bool {
+		if !self.tag_type.matches(kv.1) {
+			return false
+		}
 		let flags = self.search_flags as u8 + 1;
 		let value_flag = (flags & 0b01) > 0;
 		let key_flag = (flags & 0b10) > 0;
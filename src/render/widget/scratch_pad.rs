@@ -0,0 +1,221 @@
+use std::time::Duration;
+
+use winit::keyboard::KeyCode;
+
+use crate::{
+	elements::{element::NbtElement, snbt_error::SnbtError},
+	flags,
+	render::{
+		assets::{DARK_STRIPE_UV, HEADER_SIZE, STRING_EDITOR_TEXT_Z, STRING_EDITOR_Z},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+		widget::{
+			string_editor::{StringEditorCache, click_wrapped, move_vertically, render_wrapped, scroll_lines, scroll_to_cursor},
+			text::{StringEditorKeyResult, Text},
+		},
+	},
+	serialization::formatter::PrettyFormatter,
+	util::{AxisAlignedBoundingBox, StrExt, Timestamp, Vec2u},
+};
+
+/// The SNBT of a scratch tab, typed into the left half of it with the tree it parses to on the right
+///
+/// The text is what's kept, the tree is parsed from it again shortly after it's changed and only written back to it when asked
+pub struct ScratchPad {
+	text: Text<(), StringEditorCache>,
+	/// The first wrapped line shown
+	scroll: usize,
+	/// Whether keys go to the text rather than the tree
+	focused: bool,
+	/// When the text was changed from what was last parsed
+	changed: Option<Timestamp>,
+	/// Whether the text changed since the tab last [took](Self::take_edited) it, as that's what's saved
+	edited: bool,
+	/// What the tree was last parsed from
+	parsed: String,
+	/// Why the text didn't parse, the tree is left as it last did
+	error: Option<SnbtError>,
+}
+
+impl Default for ScratchPad {
+	fn default() -> Self { Self::new() }
+}
+
+impl ScratchPad {
+	/// How long the text has to be left alone before it's parsed
+	pub const PARSE_DELAY: Duration = Duration::from_millis(300);
+	const PADDING: usize = 6;
+	const TITLE_HEIGHT: usize = 20;
+	const REWRITE: &'static str = "Rewrite from tree";
+
+	#[must_use]
+	pub fn new() -> Self {
+		let value = "{}".to_owned();
+		Self {
+			text: Text::new(value.clone(), 1, true, ()),
+			scroll: 0,
+			focused: true,
+			changed: None,
+			edited: false,
+			parsed: value,
+			error: None,
+		}
+	}
+
+	/// How much of a pane `width` wide it takes up, the tree is drawn to the right of it
+	#[must_use]
+	pub const fn width(width: usize) -> usize { width / 2 }
+
+	/// Where it's drawn in a pane of `dims`
+	fn bounds(dims: Vec2u) -> AxisAlignedBoundingBox { AxisAlignedBoundingBox::new(0, Self::width(dims.x), HEADER_SIZE, dims.y.max(HEADER_SIZE)) }
+
+	/// Where the first line of text starts
+	fn text_pos() -> Vec2u { Vec2u::new(Self::PADDING, HEADER_SIZE + Self::TITLE_HEIGHT) }
+
+	fn text_width(dims: Vec2u) -> usize { Self::width(dims.x).saturating_sub(Self::PADDING * 2) }
+
+	/// How many lines fit
+	fn rows(dims: Vec2u) -> usize { (dims.y.saturating_sub(Self::text_pos().y) / 16).max(1) }
+
+	/// Where the "Rewrite from tree" button is drawn in the title row
+	fn rewrite_bounds(dims: Vec2u) -> AxisAlignedBoundingBox {
+		let x = Self::width(dims.x).saturating_sub(Self::PADDING + Self::REWRITE.width());
+		AxisAlignedBoundingBox::new(x, Self::width(dims.x).saturating_sub(Self::PADDING), HEADER_SIZE, HEADER_SIZE + Self::TITLE_HEIGHT)
+	}
+
+	#[must_use]
+	pub fn contains(mouse: Vec2u, dims: Vec2u) -> bool { Self::bounds(dims).contains(mouse) }
+
+	#[must_use]
+	pub const fn is_focused(&self) -> bool { self.focused }
+
+	pub fn unfocus(&mut self) { self.focused = false; }
+
+	/// The SNBT typed, which is what's saved
+	#[must_use]
+	pub fn value(&self) -> &str { &self.text.value }
+
+	/// Why the text last failed to parse, if it did
+	#[must_use]
+	pub const fn error(&self) -> Option<&SnbtError> { self.error.as_ref() }
+
+	/// Focuses it and moves the cursor to where `mouse` is over the text, selecting up to it with `extend`
+	///
+	/// Returns whether "Rewrite from tree" was clicked, which is left to the tab as it has the tree
+	pub fn click(&mut self, mouse: Vec2u, extend: bool, dims: Vec2u) -> bool {
+		self.focused = true;
+		if Self::rewrite_bounds(dims).contains(mouse) {
+			return true
+		}
+		let pos = Self::text_pos();
+		if mouse.y >= pos.y {
+			click_wrapped(&mut self.text, Vec2u::new(mouse.x.saturating_sub(pos.x), mouse.y - pos.y), self.scroll, extend, Self::text_width(dims));
+		}
+		false
+	}
+
+	pub fn on_scroll(&mut self, scroll: f32, dims: Vec2u) { scroll_lines(&self.text, &mut self.scroll, scroll, Self::text_width(dims), Self::rows(dims)); }
+
+	/// Enter adds a newline and \[Escape\] hands the keys back to the tree, anything the text doesn't use is passed on so the usual shortcuts still work
+	pub fn on_key_press(&mut self, key: KeyCode, ch: Option<char>, flags: u8, dims: Vec2u) -> StringEditorKeyResult {
+		let before = self.text.value.clone();
+		let result = match key {
+			KeyCode::Escape if flags == flags!() => {
+				self.focused = false;
+				return StringEditorKeyResult::Cancel
+			}
+			KeyCode::Enter | KeyCode::NumpadEnter if flags == flags!() => StringEditorKeyResult::from(self.text.on_key_press(key, None, flags!(Shift))),
+			KeyCode::ArrowUp | KeyCode::ArrowDown if flags & !flags!(Shift) == flags!() => {
+				move_vertically(&mut self.text, key == KeyCode::ArrowDown, flags == flags!(Shift), Self::text_width(dims));
+				StringEditorKeyResult::GenericAction
+			}
+			_ => StringEditorKeyResult::from(self.text.on_key_press(key, ch, flags)),
+		};
+		if result == StringEditorKeyResult::GenericAction {
+			self.post_input(&before, dims);
+		}
+		result
+	}
+
+	/// Types what an input method commits at the cursor
+	pub fn insert_str(&mut self, str: &str, dims: Vec2u) {
+		let before = self.text.value.clone();
+		self.text.insert_str(str);
+		self.post_input(&before, dims);
+	}
+
+	fn post_input(&mut self, before: &str, dims: Vec2u) {
+		self.text.post_input();
+		scroll_to_cursor(&self.text, &mut self.scroll, Self::text_width(dims), Self::rows(dims));
+		if self.text.value != before {
+			self.edited = true;
+		}
+		if self.text.value != self.parsed {
+			self.changed = Some(Timestamp::now());
+		}
+	}
+
+	/// Whether the text changed since this was last called
+	pub fn take_edited(&mut self) -> bool { core::mem::take(&mut self.edited) }
+
+	/// Replaces the text with the SNBT of `root`, as an edit which can be undone like any other
+	pub fn rewrite(&mut self, root: &NbtElement) {
+		let value = root.to_pretty_string(PrettyFormatter::DEFAULT_INDENT);
+		self.text.save_state_in_history();
+		self.text.value.clone_from(&value);
+		self.text.cursor = 0;
+		self.text.selection = None;
+		self.text.post_input();
+		self.scroll = 0;
+		self.parsed = value;
+		self.changed = None;
+		self.edited = true;
+		self.error = None;
+	}
+
+	/// The tree the text parses to, once it's been left alone for [`Self::PARSE_DELAY`] since it was changed
+	///
+	/// Text which doesn't parse leaves the error to be shown and returns [`None`], as does text which hasn't changed.
+	pub fn parse_if_due(&mut self) -> Option<NbtElement> {
+		if self.changed.is_none_or(|changed| changed.elapsed() < Self::PARSE_DELAY) {
+			return None
+		}
+		self.changed = None;
+		self.parsed.clone_from(&self.text.value);
+		match NbtElement::from_snbt(&self.text.value) {
+			Ok((_, root)) => {
+				self.error = None;
+				Some(root)
+			}
+			Err(e) => {
+				self.error = Some(e);
+				None
+			}
+		}
+	}
+
+	pub fn render(&self, builder: &mut VertexBufferBuilder) {
+		use std::fmt::Write;
+
+		let dims = Vec2u::new(builder.window_width(), builder.window_height());
+		let bounds = Self::bounds(dims);
+		builder.draw_texture_region_z(bounds.low(), STRING_EDITOR_Z, DARK_STRIPE_UV + (1, 1), bounds.high() - bounds.low(), (14, 14));
+
+		builder.settings((Self::PADDING, HEADER_SIZE + 2), false, STRING_EDITOR_TEXT_Z);
+		builder.color = TextColor::TreeKey.to_raw();
+		let _ = write!(builder, "SNBT");
+		if let Some(error) = &self.error {
+			builder.color = TextColor::Red.to_raw();
+			let _ = write!(builder, "   {error}");
+		} else if self.focused {
+			builder.color = TextColor::Gray.to_raw();
+			let _ = write!(builder, "   [Escape] to edit the tree");
+		}
+		let button = Self::rewrite_bounds(dims);
+		builder.settings(button.low() + (0, 2), false, STRING_EDITOR_TEXT_Z);
+		builder.color = TextColor::Yellow.to_raw();
+		let _ = write!(builder, "{}", Self::REWRITE);
+
+		render_wrapped(&self.text, builder, Self::text_pos(), Self::text_width(dims), self.scroll, Self::rows(dims), self.focused, self.error.as_ref().map(|error| error.offset));
+	}
+}
@@ -10,7 +10,21 @@ use winit::dpi::PhysicalSize;
 use crate::{
 	action_result::ActionResult,
 	config,
-	elements::{Matches, NbtElementAndKey, NbtElementAndKeyRef, compound::CompoundEntry, element::NbtElement},
+	elements::{
+		Matches, NbtElementAndKey, NbtElementAndKeyRef, NbtElementVariant,
+		array::{NbtByteArray, NbtIntArray, NbtLongArray},
+		byte::NbtByte,
+		chunk::NbtChunk,
+		compound::{CompoundEntry, NbtCompound},
+		double::NbtDouble,
+		element::NbtElement,
+		float::NbtFloat,
+		int::NbtInt,
+		list::NbtList,
+		long::NbtLong,
+		short::NbtShort,
+		string::NbtString,
+	},
 	flags,
 	render::{
 		assets::{
@@ -20,14 +34,15 @@ use crate::{
 		color::TextColor,
 		vertex_buffer_builder::VertexBufferBuilder,
 		widget::{
-			alert::manager::AlertManager,
+			alert::manager::{AlertManager, Alertable},
 			notification::{Notification, NotificationKind, manager::NotificationManager},
 			replace_box::ReplaceBox,
 			text::{Cachelike, SearchBoxKeyResult, Text, get_cursor_idx},
 		},
 		window::Theme,
 	},
-	util::{StrExt, Timestamp, Vec2u, create_regex},
+	tree::indices::OwnedIndices,
+	util::{CreateRegexError, StrExt, Timestamp, Vec2u, create_regex, truncate_to_width},
 	workbench::{
 		marked_line::{MarkedLine, MarkedLines},
 		tab::Tab,
@@ -40,10 +55,13 @@ pub const SEARCH_BOX_END_X: usize = 2;
 
 pub struct SearchPredicate {
 	pub search_flags: SearchFlags,
+	pub tag_type: SearchTagType,
 	pub inner: SearchPredicateInner,
 }
 
 pub enum SearchPredicateInner {
+	/// An empty query, which leaves only the tag type to filter by
+	Any,
 	String(String),
 	StringCaseInsensitive(String),
 	Regex(Regex),
@@ -233,12 +251,148 @@ impl SearchMode {
 	}
 }
 
+#[derive(Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchTagType {
+	#[default]
+	Any,
+	Byte,
+	Short,
+	Int,
+	Long,
+	Float,
+	Double,
+	String,
+	List,
+	Compound,
+	ByteArray,
+	IntArray,
+	LongArray,
+	Chunk,
+}
+
+impl Display for SearchTagType {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", match self {
+			Self::Any => "Any",
+			Self::Byte => "Byte",
+			Self::Short => "Short",
+			Self::Int => "Int",
+			Self::Long => "Long",
+			Self::Float => "Float",
+			Self::Double => "Double",
+			Self::String => "String",
+			Self::List => "List",
+			Self::Compound => "Compound",
+			Self::ByteArray => "Byte Array",
+			Self::IntArray => "Int Array",
+			Self::LongArray => "Long Array",
+			Self::Chunk => "Chunk",
+		})
+	}
+}
+
+impl SearchTagType {
+	const ALL: [Self; 14] = [
+		Self::Any,
+		Self::Byte,
+		Self::Short,
+		Self::Int,
+		Self::Long,
+		Self::Float,
+		Self::Double,
+		Self::String,
+		Self::List,
+		Self::Compound,
+		Self::ByteArray,
+		Self::IntArray,
+		Self::LongArray,
+		Self::Chunk,
+	];
+
+	/// Parses the `snake_case` name used in the config and on the command line
+	#[must_use]
+	pub fn from_name(name: &str) -> Option<Self> {
+		Some(match name {
+			"any" => Self::Any,
+			"byte" => Self::Byte,
+			"short" => Self::Short,
+			"int" => Self::Int,
+			"long" => Self::Long,
+			"float" => Self::Float,
+			"double" => Self::Double,
+			"string" => Self::String,
+			"list" => Self::List,
+			"compound" => Self::Compound,
+			"byte_array" => Self::ByteArray,
+			"int_array" => Self::IntArray,
+			"long_array" => Self::LongArray,
+			"chunk" => Self::Chunk,
+			_ => return None,
+		})
+	}
+
+	#[must_use]
+	pub fn cycle(self) -> Self { Self::ALL[(self as usize + 1) % Self::ALL.len()] }
+
+	#[must_use]
+	pub fn rev_cycle(self) -> Self { Self::ALL[(self as usize + Self::ALL.len() - 1) % Self::ALL.len()] }
+
+	#[must_use]
+	pub fn uv(self) -> Vec2u {
+		match self {
+			// a faded compound, as in every kind of tag
+			Self::Any => NbtCompound::GHOST_UV,
+			Self::Byte => NbtByte::UV,
+			Self::Short => NbtShort::UV,
+			Self::Int => NbtInt::UV,
+			Self::Long => NbtLong::UV,
+			Self::Float => NbtFloat::UV,
+			Self::Double => NbtDouble::UV,
+			Self::String => NbtString::UV,
+			Self::List => NbtList::UV,
+			Self::Compound => NbtCompound::UV,
+			Self::ByteArray => NbtByteArray::UV,
+			Self::IntArray => NbtIntArray::UV,
+			Self::LongArray => NbtLongArray::UV,
+			Self::Chunk => NbtChunk::UV,
+		}
+	}
+
+	#[must_use]
+	pub fn matches(self, element: &NbtElement) -> bool {
+		match self {
+			Self::Any => true,
+			Self::Byte => element.is_byte(),
+			Self::Short => element.is_short(),
+			Self::Int => element.is_int(),
+			Self::Long => element.is_long(),
+			Self::Float => element.is_float(),
+			Self::Double => element.is_double(),
+			Self::String => element.is_string(),
+			Self::List => element.is_list(),
+			Self::Compound => element.is_compound(),
+			Self::ByteArray => element.is_byte_array(),
+			Self::IntArray => element.is_int_array(),
+			Self::LongArray => element.is_long_array(),
+			Self::Chunk => element.is_chunk(),
+		}
+	}
+}
+
 impl SearchPredicate {
 	#[must_use]
 	fn new(value: String) -> Option<Self> {
 		let search_mode = config::get_search_mode();
 		let search_flags = config::get_search_flags();
+		let tag_type = config::get_search_tag_type();
 		let exact_match = config::get_search_exact_match();
+		if value.is_empty() {
+			return Some(Self {
+				search_flags,
+				tag_type,
+				inner: SearchPredicateInner::Any,
+			})
+		}
 		Some(match search_mode {
 			SearchMode::String => Self {
 				inner: if exact_match {
@@ -247,12 +401,14 @@ impl SearchPredicate {
 					SearchPredicateInner::StringCaseInsensitive(value.to_lowercase())
 				},
 				search_flags,
+				tag_type,
 			},
 			SearchMode::Regex =>
-				if let Some(regex) = create_regex(value, exact_match) {
+				if let Ok(regex) = create_regex(value, exact_match) {
 					Self {
 						inner: SearchPredicateInner::Regex(regex),
 						search_flags,
+						tag_type,
 					}
 				} else {
 					return None
@@ -267,6 +423,7 @@ impl SearchPredicate {
 					Self {
 						inner: if exact_match { SearchPredicateInner::SnbtExactMatch((key, value)) } else { SearchPredicateInner::Snbt((key, value)) },
 						search_flags,
+						tag_type,
 					}
 				} else {
 					return None
@@ -276,8 +433,12 @@ impl SearchPredicate {
 
 	#[must_use]
 	fn matches(&self, kv: NbtElementAndKeyRef) -> bool {
+		if !self.tag_type.matches(kv.1) {
+			return false
+		}
 		let flags = self.search_flags as u8 + 1;
 		match &self.inner {
+			SearchPredicateInner::Any => true,
 			SearchPredicateInner::String(matcher) => {
 				let (value, color) = kv.1.value();
 				((flags & 0b01) > 0 && color != TextColor::TreeKey && value.contains(matcher)) || ((flags & 0b10) > 0 && kv.0.is_some_and(|k| k.contains(matcher)))
@@ -331,6 +492,8 @@ pub struct SearchBoxAdditional {
 	pub horizontal_scroll: usize,
 	// todo: make `MutliclickSelectionData` struct
 	pub last_interaction: (usize, Timestamp),
+	// boxed so that a zeroed one is `None`, see `Text::uninit`
+	regex_error: Option<Box<CreateRegexError>>,
 }
 
 pub struct SearchBox(Text<SearchBoxAdditional, SearchBoxCache>);
@@ -351,6 +514,7 @@ impl SearchBox {
 			selected: false,
 			horizontal_scroll: 0,
 			last_interaction: (0, Timestamp::UNIX_EPOCH),
+			regex_error: None,
 		}))
 	}
 
@@ -388,6 +552,16 @@ impl SearchBox {
 		}
 
 		builder.horizontal_scroll = 0;
+
+		if let Some(e) = self.regex_error() {
+			// just left of the buttons, cut short rather than running off the left of the box
+			let buttons_x = builder.window_width() - SEARCH_BOX_END_X - 17 - 16 - 16 - 16 - 16;
+			let message = truncate_to_width(&e.to_string(), buttons_x.saturating_sub(pos.x + 4), "…");
+			let x = buttons_x.saturating_sub(message.width() + 4).max(pos.x);
+			builder.settings(Vec2u::new(x, pos.y + 3), false, SEARCH_BOX_Z);
+			builder.color = TextColor::Red.to_raw();
+			let _ = write!(builder, "{message}");
+		}
 	}
 
 	#[must_use]
@@ -409,6 +583,7 @@ impl SearchBox {
 			self.cursor = 0;
 			self.selection = None;
 			self.horizontal_scroll = 0;
+			self.regex_error = None;
 			self.0.post_input();
 		} else {
 			self.cursor = get_cursor_idx(&self.value, (x + self.horizontal_scroll) as isize);
@@ -421,10 +596,13 @@ impl SearchBox {
 	#[must_use]
 	pub fn search(&self, bookmarks: &mut MarkedLines, root: &NbtElement, count_only: bool) -> Notification {
 		let start = Timestamp::now();
-		let new_bookmarks = if self.value.is_empty() {
+		let new_bookmarks = if self.value.is_empty() && config::get_search_tag_type() == SearchTagType::Any {
 			MarkedLines::new()
 		} else {
 			let Some(predicate) = SearchPredicate::new(self.value.clone()) else {
+				if let Some(e) = self.regex_error() {
+					return Notification::new(format!("Invalid regex ({e})"), TextColor::Red, NotificationKind::Find)
+				}
 				return Notification::new(format!("Invalid search syntax ({})", self.value), TextColor::Red, NotificationKind::Find)
 			};
 			Self::search0(root, &predicate)
@@ -479,19 +657,54 @@ impl SearchBox {
 		unsafe { MarkedLines::from_unchecked(new_bookmarks) }
 	}
 
+	/// Like [`Self::search0`], but with the indices of each match rather than its line
+	#[must_use]
+	pub fn search_indices(root: &NbtElement, predicate: &SearchPredicate) -> Vec<OwnedIndices> {
+		let mut matches = Vec::new();
+		let mut queue: Vec<(NbtElementAndKeyRef, OwnedIndices)> = vec![((None, root), OwnedIndices::new())];
+		while let Some(((key, value), indices)) = queue.pop() {
+			if predicate.matches((key, value)) {
+				matches.push(indices.clone());
+			}
+
+			match value.children() {
+				Some(Ok(iter)) =>
+					for (idx, child) in iter.enumerate().rev() {
+						let mut indices = indices.clone();
+						indices.push(idx);
+						queue.push(((None, child), indices));
+					},
+				Some(Err(iter)) =>
+					for (idx, CompoundEntry { key, value: child }) in iter.enumerate().rev() {
+						let mut indices = indices.clone();
+						indices.push(idx);
+						queue.push(((Some(key), child), indices));
+					},
+				None => {}
+			}
+		}
+		matches
+	}
+
 	#[must_use]
 	pub fn is_selected(&self) -> bool { self.selected }
 
+	/// Why the query isn't a valid regex, if searching by regex
+	#[must_use]
+	pub fn regex_error(&self) -> Option<&CreateRegexError> { self.regex_error.as_deref().filter(|_| matches!(config::get_search_mode(), SearchMode::Regex)) }
+
 	pub fn post_input(&mut self, window_dims: PhysicalSize<u32>) {
 		self.0.post_input();
-		let field_width = window_dims.width as usize - SEARCH_BOX_END_X - SEARCH_BOX_START_X - 17 - 16 - 16;
+		// case sensitivity can't make a regex invalid
+		self.regex_error = if self.value.is_empty() { None } else { create_regex(self.value.clone(), true).err().map(Box::new) };
+		let field_width = window_dims.width as usize - SEARCH_BOX_END_X - SEARCH_BOX_START_X - 17 - 16 - 16 - 16 - 16;
 		let precursor_width = self.value.split_at(self.cursor).0.width();
 		// 8px space just to look cleaner
 		let horizontal_scroll = (precursor_width + 8).saturating_sub(field_width);
 		self.horizontal_scroll = horizontal_scroll;
 	}
 
-	pub fn on_key_press(&mut self, key: KeyCode, ch: Option<char>, flags: u8, replace_box: &mut ReplaceBox, tab: &mut Tab, _alerts: &mut AlertManager, notifications: &mut NotificationManager, window_dims: PhysicalSize<u32>) -> ActionResult {
+	pub fn on_key_press(&mut self, key: KeyCode, ch: Option<char>, flags: u8, replace_box: &mut ReplaceBox, tab: &mut Tab, alerts: &mut AlertManager, notifications: &mut NotificationManager, window_dims: PhysicalSize<u32>) -> ActionResult {
 		#[must_use]
 		fn on_key_press0(this: &mut SearchBox, key: KeyCode, ch: Option<char>, flags: u8) -> SearchBoxKeyResult {
 			if !this.is_selected() {
@@ -505,6 +718,12 @@ impl SearchBox {
 
 			if let KeyCode::Enter | KeyCode::NumpadEnter = key
 				&& flags == flags!(Shift)
+			{
+				return SearchBoxKeyResult::PreviousHit;
+			}
+
+			if let KeyCode::Enter | KeyCode::NumpadEnter = key
+				&& flags == flags!(Ctrl)
 			{
 				return SearchBoxKeyResult::Search;
 			}
@@ -518,9 +737,14 @@ impl SearchBox {
 			this.0.on_key_press(key, ch, flags).into()
 		}
 
+		let old_value = self.value.clone();
 		match on_key_press0(self, key, ch, flags) {
 			SearchBoxKeyResult::NoAction => ActionResult::Pass,
 			SearchBoxKeyResult::GenericAction => {
+				if self.value != old_value {
+					// so that the next jump searches for the new query
+					tab.last_search_hit = None;
+				}
 				self.post_input(window_dims);
 				ActionResult::Success(())
 			}
@@ -536,11 +760,61 @@ impl SearchBox {
 				ActionResult::Success(())
 			}
 			result @ (SearchBoxKeyResult::Search | SearchBoxKeyResult::SearchCountOnly) => {
+				tab.root.decode_chunks(&mut tab.bookmarks);
 				let notification = self.search(&mut tab.bookmarks, &tab.root, result == SearchBoxKeyResult::SearchCountOnly);
 				notifications.notify(notification);
+				tab.last_search_hit = None;
+				self.post_input(window_dims);
+				ActionResult::Success(())
+			}
+			result @ (SearchBoxKeyResult::NextHit | SearchBoxKeyResult::PreviousHit) => {
+				let searched = tab.last_search_hit.is_none();
+				if searched {
+					tab.root.decode_chunks(&mut tab.bookmarks);
+					notifications.notify(self.search(&mut tab.bookmarks, &tab.root, false));
+				}
+				if let Some(Some((idx, hits))) = tab.jump_to_search_hit(result == SearchBoxKeyResult::NextHit).alert_err(alerts)
+					&& !searched
+				{
+					notifications.notify(Notification::new(format!("Hit {n} of {hits}", n = idx + 1), TextColor::White, NotificationKind::Find));
+				}
 				self.post_input(window_dims);
 				ActionResult::Success(())
 			}
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::{SearchBox, SearchFlags, SearchPredicate, SearchPredicateInner, SearchTagType};
+	use crate::elements::element::NbtElement;
+
+	#[test]
+	fn test_search_by_tag_type() {
+		let (_, nbt) = NbtElement::from_str("{a:1.5f,b:{c:2.5f,d:[1.0f]},e:{}}").expect("Valid SNBT");
+		let search = |tag_type, inner| SearchBox::search0(&nbt, &SearchPredicate { search_flags: SearchFlags::Values, tag_type, inner }).len();
+
+		assert_eq!(search(SearchTagType::Float, SearchPredicateInner::Any), 3);
+		assert_eq!(search(SearchTagType::Compound, SearchPredicateInner::Any), 3);
+		assert_eq!(search(SearchTagType::Double, SearchPredicateInner::Any), 0);
+		assert_eq!(search(SearchTagType::Float, SearchPredicateInner::StringCaseInsensitive("2.5".to_owned())), 1);
+		// empty compounds
+		let empty = NbtElement::from_str("{}").expect("Valid SNBT");
+		assert_eq!(search(SearchTagType::Compound, SearchPredicateInner::SnbtExactMatch(empty)), 1);
+	}
+
+	#[test]
+	fn test_search_indices() {
+		// lists, since compounds may be sorted when parsed
+		let (_, nbt) = NbtElement::from_str("[[1.5f],[[2.5f],[1.0f]]]").expect("Valid SNBT");
+		let predicate = SearchPredicate {
+			search_flags: SearchFlags::Values,
+			tag_type: SearchTagType::Float,
+			inner: SearchPredicateInner::Any,
+		};
+		let indices = SearchBox::search_indices(&nbt, &predicate).into_iter().map(|indices| indices.iter().collect::<Vec<_>>()).collect::<Vec<_>>();
+
+		assert_eq!(indices, [vec![0, 0], vec![1, 0, 0], vec![1, 1, 0]]);
+	}
+}
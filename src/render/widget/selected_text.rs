@@ -3,30 +3,29 @@ use std::{
 	ops::{Deref, DerefMut},
 };
 
-use compact_str::ToCompactString;
+use compact_str::{CompactString, ToCompactString};
 use thiserror::Error;
 use uuid::Uuid;
 use winit::keyboard::KeyCode;
 
 use crate::{
 	action_result::{ActionResult, IntoFailingActionResult},
-	elements::element::NbtElement,
+	elements::{ComplexNbtElementVariant, element::NbtElement, string::NbtString},
 	flags,
 	history::{manager::HistoryMananger, WorkbenchAction}
 	,
 	render::{
-		assets::{BASE_TEXT_Z, HEADER_SIZE, SELECTED_TEXT_SELECTION_Z, SELECTED_TEXT_Z, SELECTION_UV},
+		assets::{BASE_TEXT_Z, DARK_STRIPE_UV, HEADER_SIZE, HOVERED_STRIPE_UV, KEY_SUGGESTIONS_SELECTION_Z, KEY_SUGGESTIONS_TEXT_Z, KEY_SUGGESTIONS_Z, SELECTED_TEXT_SELECTION_Z, SELECTED_TEXT_Z, SELECTION_UV},
 		color::TextColor,
 		vertex_buffer_builder::VertexBufferBuilder,
 		widget::{
 			alert::manager::{AlertManager, Alertable},
-			text::{Cachelike, SelectedTextKeyResult, Text},
+			text::{Cachelike, SelectedTextKeyResult, Text, get_cursor_idx},
 		},
 	},
 	tree::{
 		actions::{
 			close::{close_element, CloseElementError},
-			expand::expand_element,
 			open::open_element,
 			rename::{rename_element, RenameElementError},
 			swap::{swap_element_same_depth, SwapElementErrorSameDepth},
@@ -34,12 +33,13 @@ use crate::{
 		},
 		indices::{Indices, OwnedIndices},
 		line_number_at,
-		navigate::{NavigationError, NavigationInformation, ParentNavigationError, ParentNavigationInformationMut},
+		navigate::{NavigationError, NavigationInformation, ParentNavigationError, ParentNavigationInformation, ParentNavigationInformationMut},
 		traverse::{TraversalError, TraversalInformation},
 		MutableIndices,
 	},
-	util::{CharExt, StrExt},
+	util::{CharExt, StrExt, Vec2u},
 	workbench::{
+		boolean_overrides::BooleanOverrides,
 		marked_line::MarkedLines,
 		tab::{FilePath, TabConstants},
 	},
@@ -106,6 +106,55 @@ pub struct SelectedTextAdditional {
 	pub valuefix: Option<(String, TextColor)>,
 	pub cached_cursor_x: Option<usize>,
 	pub uuid: Uuid,
+	pub suggestions: KeySuggestions,
+}
+
+/// The keys a key being typed could be completed to, shown in a dropdown under it
+#[derive(Clone, Default)]
+pub struct KeySuggestions {
+	pub keys: Vec<CompactString>,
+	pub highlighted: usize,
+}
+
+impl KeySuggestions {
+	pub const MAX_SHOWN: usize = 8;
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool { self.keys.is_empty() }
+
+	#[must_use]
+	pub fn highlighted(&self) -> Option<&str> { self.keys.get(self.highlighted).map(CompactString::as_str) }
+
+	pub fn clear(&mut self) {
+		self.keys.clear();
+		self.highlighted = 0;
+	}
+}
+
+/// The keys of the other compounds in the list holding the key at `indices`'s compound, which that compound doesn't have yet
+#[must_use]
+pub fn sibling_keys(root: &NbtElement, indices: &Indices) -> Vec<CompactString> {
+	let Ok(ParentNavigationInformation { parent, parent_indices, .. }) = root.navigate_parent(indices) else { return vec![] };
+	let Some(compound) = parent.as_compound() else { return vec![] };
+	let Some((_, list_indices)) = parent_indices.split_last() else { return vec![] };
+	let Some(list) = root.navigate(list_indices).ok().and_then(|info| info.element.as_list()) else { return vec![] };
+
+	let mut keys = list
+		.children()
+		.filter_map(NbtElement::as_compound)
+		.flat_map(|sibling| sibling.map.entries.iter().map(|entry| &entry.key))
+		.filter(|key| !compound.map.has(key))
+		.cloned()
+		.collect::<Vec<_>>();
+	keys.sort_unstable();
+	keys.dedup();
+	keys
+}
+
+/// The `keys` which start with `typed`, ignoring case, other than `typed` itself
+#[must_use]
+pub fn matching_keys(keys: Vec<CompactString>, typed: &str) -> Vec<CompactString> {
+	keys.into_iter().filter(|key| key != typed && key.get(..typed.len()).is_some_and(|start| start.eq_ignore_ascii_case(typed))).collect()
 }
 
 impl SelectedText {
@@ -138,6 +187,7 @@ impl SelectedText {
 						valuefix,
 						cached_cursor_x,
 						uuid: Uuid::new_v4(),
+						suggestions: KeySuggestions::default(),
 					})));
 				}
 
@@ -152,6 +202,7 @@ impl SelectedText {
 						valuefix,
 						cached_cursor_x,
 						uuid: Uuid::new_v4(),
+						suggestions: KeySuggestions::default(),
 					})));
 				}
 
@@ -176,6 +227,7 @@ impl SelectedText {
 							valuefix,
 							cached_cursor_x,
 							uuid: Uuid::new_v4(),
+							suggestions: KeySuggestions::default(),
 						})));
 					}
 				}
@@ -209,6 +261,7 @@ impl SelectedText {
 						valuefix: None,
 						cached_cursor_x,
 						uuid: Uuid::new_v4(),
+						suggestions: KeySuggestions::default(),
 					})));
 				}
 
@@ -225,6 +278,7 @@ impl SelectedText {
 						valuefix: None,
 						cached_cursor_x,
 						uuid: Uuid::new_v4(),
+						suggestions: KeySuggestions::default(),
 					})));
 				}
 
@@ -246,6 +300,7 @@ impl SelectedText {
 							valuefix: None,
 							cached_cursor_x,
 							uuid: Uuid::new_v4(),
+							suggestions: KeySuggestions::default(),
 						})));
 					}
 				}
@@ -264,6 +319,7 @@ impl SelectedText {
 				valuefix: value.map(|(x, color, _)| (x, color)),
 				cached_cursor_x,
 				uuid: Uuid::new_v4(),
+				suggestions: KeySuggestions::default(),
 			})))
 		} else {
 			Err(SelectedTextConstructionError::OutOfBounds {
@@ -306,7 +362,7 @@ impl SelectedText {
 		) -> Result<SelectedTextKeyResult, SelectedTextInputError> {
 			if key == KeyCode::ArrowUp {
 				if flags & !flags!(Ctrl) == 0 {
-					return Ok(SelectedTextKeyResult::Action(Some(this.move_up(consts, flags == flags!(Ctrl), root, path)?)))
+					return Ok(SelectedTextKeyResult::Action(Some(this.move_up(consts, flags == flags!(Ctrl), root, path, mi.boolean_overrides)?)))
 				} else if flags == flags!(Ctrl + Shift) {
 					return Ok(SelectedTextKeyResult::Action(Some(this.shift_up(consts, root, mi)?)))
 				}
@@ -314,7 +370,7 @@ impl SelectedText {
 
 			if key == KeyCode::ArrowDown {
 				if flags & !flags!(Ctrl) == 0 {
-					return Ok(SelectedTextKeyResult::Action(Some(this.move_down(consts, flags == flags!(Ctrl), root, path)?)))
+					return Ok(SelectedTextKeyResult::Action(Some(this.move_down(consts, flags == flags!(Ctrl), root, path, mi.boolean_overrides)?)))
 				} else if flags == flags!(Ctrl + Shift) {
 					return Ok(SelectedTextKeyResult::Action(Some(this.shift_down(consts, root, mi)?)))
 				}
@@ -324,7 +380,7 @@ impl SelectedText {
 				if flags & !flags!(Ctrl) == 0 && this.selection.is_none() && this.cursor == 0 && this.keyfix.is_some() {
 					return Ok(SelectedTextKeyResult::Action(Some(this.move_to_keyfix(consts, root, path)?)))
 				}
-				if flags & !flags!(Shift) == flags!(Alt) {
+				if flags == flags!(Alt) {
 					this.force_close(root, mi.bookmarks)?;
 					return Ok(SelectedTextKeyResult::Action(None))
 				}
@@ -334,23 +390,37 @@ impl SelectedText {
 				if flags & !flags!(Ctrl) == 0 && this.selection.is_none() && this.cursor == this.value.len() && this.valuefix.is_some() {
 					return Ok(SelectedTextKeyResult::Action(Some(this.move_to_valuefix(consts, root, path)?)))
 				}
-				if (flags) & !flags!(Shift) == flags!(Alt) {
-					this.force_open((flags & !flags!(Alt)) == flags!(Shift), root, mi.bookmarks)?;
+				if flags == flags!(Alt) {
+					this.force_open(root, mi.bookmarks)?;
 					return Ok(SelectedTextKeyResult::Action(None))
 				}
 			}
 
+			// the tree's history is left alone while it's being edited, even once there's nothing left to undo in the text
+			if this.editable && (key == KeyCode::KeyZ && flags & !flags!(Shift) == flags!(Ctrl) || key == KeyCode::KeyY && flags == flags!(Ctrl)) {
+				return Ok(match this.0.on_key_press(key, ch, flags).into() {
+					SelectedTextKeyResult::NoAction => SelectedTextKeyResult::Action(None),
+					result => result,
+				})
+			}
+
 			Ok(this.0.on_key_press(key, ch, flags).into())
 		}
+		if self.on_suggestion_key(key, flags) {
+			return ActionResult::Success(false)
+		}
+
 		let result = on_key_press0(self, key, ch, flags, consts, root, path, mi);
 		match result.alert_err(alerts).failure_on_err()? {
 			SelectedTextKeyResult::NoAction => ActionResult::Pass,
 			SelectedTextKeyResult::Action(None) => {
 				self.post_input();
+				self.refresh_suggestions(root);
 				ActionResult::Success(false)
 			}
 			SelectedTextKeyResult::Action(Some(action)) => {
 				self.post_input();
+				self.suggestions.clear();
 				history.append(action);
 				ActionResult::Success(false)
 			}
@@ -361,16 +431,66 @@ impl SelectedText {
 			}
 			SelectedTextKeyResult::GenericAction => {
 				self.post_input();
+				self.refresh_suggestions(root);
 				ActionResult::Success(false)
 			}
 		}
 	}
 
 	#[must_use]
-	pub fn cursor_x(&self, left_margin: usize) -> usize { self.indices.end_x(left_margin) + Self::PREFIXING_SPACE_WIDTH + self.prefix.0.width() + self.keyfix.as_ref().map_or(0, |x| x.0.width()) + self.value.split_at(self.cursor).0.width() }
+	pub fn cursor_x(&self, left_margin: usize) -> usize { self.value_x(left_margin) + self.value.split_at(self.cursor).0.width() }
+
+	/// Where the value starts, after the key or prefix before it
+	#[must_use]
+	pub fn value_x(&self, left_margin: usize) -> usize { self.indices.end_x(left_margin) + Self::PREFIXING_SPACE_WIDTH + self.prefix.0.width() + self.keyfix.as_ref().map_or(0, |x| x.0.width()) }
+
+	/// The index in the value closest to `x`, which is scrolled horizontally like the tree
+	#[must_use]
+	#[allow(clippy::cast_possible_wrap)]
+	pub fn cursor_idx_at(&self, x: usize, left_margin: usize) -> usize { get_cursor_idx(&self.value, x as isize - self.value_x(left_margin) as isize) }
 
 	pub fn post_input(&mut self) { self.0.post_input() }
 
+	/// Whether the key is being edited rather than the value
+	#[must_use]
+	pub fn is_key(&self) -> bool { self.prefix.0.is_empty() && !self.suffix.0.is_empty() }
+
+	/// Suggests the keys of sibling compounds which start with what's been typed, if it's a key that's being edited
+	pub fn refresh_suggestions(&mut self, root: &NbtElement) {
+		self.suggestions.clear();
+		if self.editable && self.is_key() {
+			self.suggestions.keys = matching_keys(sibling_keys(root, &self.indices), &self.value);
+		}
+	}
+
+	/// Moves through, accepts or hides the suggested keys while they're shown, returning whether `key` went to them
+	fn on_suggestion_key(&mut self, key: KeyCode, flags: u8) -> bool {
+		if self.suggestions.is_empty() || flags != flags!() {
+			return false
+		}
+
+		let len = self.suggestions.keys.len();
+		match key {
+			KeyCode::Tab | KeyCode::Enter | KeyCode::NumpadEnter => self.accept_suggestion(),
+			KeyCode::ArrowUp => self.suggestions.highlighted = (self.suggestions.highlighted + len - 1) % len,
+			KeyCode::ArrowDown => self.suggestions.highlighted = (self.suggestions.highlighted + 1) % len,
+			KeyCode::Escape => self.suggestions.clear(),
+			_ => return false,
+		}
+		true
+	}
+
+	/// Replaces the key with the highlighted suggestion
+	pub fn accept_suggestion(&mut self) {
+		if let Some(key) = self.suggestions.highlighted().map(str::to_owned) {
+			self.value = key;
+			self.cursor = self.value.len();
+			self.selection = None;
+		}
+		self.suggestions.clear();
+		self.post_input();
+	}
+
 	pub fn recache_cached_cursor_x(&mut self, consts: TabConstants) {
 		let TabConstants { left_margin, .. } = consts;
 
@@ -411,7 +531,7 @@ impl SelectedText {
 		builder.color = self.prefix.1.to_raw();
 		let _ = write!(builder, "{}", self.prefix.0);
 
-		builder.settings((x + prefix_width + self.value.width(), y), false, BASE_TEXT_Z);
+		builder.settings((x + prefix_width + self.display_width(), y), false, BASE_TEXT_Z);
 
 		builder.color = self.suffix.1.to_raw();
 		let _ = write!(builder, "{}", self.suffix.0);
@@ -420,9 +540,36 @@ impl SelectedText {
 			builder.color = valuefix_color.to_raw();
 			let _ = write!(builder, "{valuefix}");
 		}
+
+		self.render_suggestions(builder, (x + prefix_width, y + 16).into());
+	}
+
+	fn render_suggestions(&self, builder: &mut VertexBufferBuilder, pos: Vec2u) {
+		const PADDING: usize = 3;
+
+		if self.suggestions.is_empty() {
+			return
+		}
+
+		// scrolls to keep the highlighted key in view
+		let start = self.suggestions.highlighted.saturating_sub(KeySuggestions::MAX_SHOWN - 1);
+		let shown = &self.suggestions.keys[start..(start + KeySuggestions::MAX_SHOWN).min(self.suggestions.keys.len())];
+		let width = shown.iter().map(|key| key.width()).max().unwrap_or(0) + PADDING * 2;
+		let x = pos.x - PADDING;
+
+		builder.draw_texture_region_z((x, pos.y), KEY_SUGGESTIONS_Z, DARK_STRIPE_UV + (1, 1), (width, shown.len() * 16), (14, 14));
+		for (idx, key) in shown.iter().enumerate() {
+			let y = pos.y + idx * 16;
+			if start + idx == self.suggestions.highlighted {
+				builder.draw_texture_region_z((x, y), KEY_SUGGESTIONS_SELECTION_Z, HOVERED_STRIPE_UV, (width, 16), (16, 16));
+			}
+			builder.settings((pos.x, y), false, KEY_SUGGESTIONS_TEXT_Z);
+			builder.color = TextColor::TreeKey.to_raw();
+			let _ = write!(builder, "{key}");
+		}
 	}
 
-	pub fn for_y(consts: TabConstants, root: &NbtElement, path: &FilePath, y: usize, mouse_x: usize, snap_to_ends: bool, cached_cursor_x: Option<usize>) -> Result<SelectedText, SelectedTextConstructionError> {
+	pub fn for_y(consts: TabConstants, root: &NbtElement, path: &FilePath, boolean_overrides: &BooleanOverrides, y: usize, mouse_x: usize, snap_to_ends: bool, cached_cursor_x: Option<usize>) -> Result<SelectedText, SelectedTextConstructionError> {
 		fn header(consts: TabConstants, root: &NbtElement, path: &FilePath, offset: usize, cached_cursor_x: Option<usize>) -> Result<SelectedText, SelectedTextConstructionError> {
 			let TabConstants { left_margin, .. } = consts;
 			let name = path.name();
@@ -459,7 +606,11 @@ impl SelectedText {
 			})
 		}
 		let k = key.map(|x| (x.to_owned(), TextColor::TreeKey, true));
-		let v = Some(element.value()).map(|(a, c)| (a.into_owned(), c, c != TextColor::TreeKey));
+		// only a preview of strings too long for their row is shown, so they're edited in a `StringEditor` instead
+		let editable_value = !element.as_string().is_some_and(NbtString::is_multiline);
+		// floats start from the shortest string that parses back to the same bits however they're shown, so saving without an edit changes nothing
+		let value = if element.is_float() || element.is_double() { element.value() } else { element.keyed_value(key, consts.number_base, boolean_overrides.shows_boolean(&indices)) };
+		let v = Some(value).map(|(a, c)| (a.into_owned(), c, c != TextColor::TreeKey && editable_value));
 		let mouse_x = if snap_to_ends {
 			let min_x = target_x;
 			let max_x = k.as_ref().map_or(0, |(k, _, b)| (*b as usize) * (k.width() + ": ".width() * v.is_some() as usize)) + v.as_ref().map_or(0, |(v, _, b)| (*b as usize) * v.width()) + target_x;
@@ -476,8 +627,7 @@ impl SelectedText {
 			return Err(SaveSelectedTextError::NonEditable)
 		}
 
-		let key = self.prefix.0.is_empty() && !self.suffix.0.is_empty();
-		let (key, value) = if key { (Some(self.value.to_compact_string()), None) } else { (None, Some(self.value.clone())) };
+		let (key, value) = if self.is_key() { (Some(self.value.to_compact_string()), None) } else { (None, Some(self.value.clone())) };
 		Ok(rename_element(root, self.indices.clone(), key, value, path)?.into_action())
 	}
 	pub fn move_to_keyfix(&mut self, consts: TabConstants, root: &mut NbtElement, path: &mut FilePath) -> Result<WorkbenchAction, MoveToKeyfixError> {
@@ -534,7 +684,7 @@ impl SelectedText {
 		Ok(action)
 	}
 
-	fn move_text(&mut self, consts: TabConstants, root: &mut NbtElement, path: &mut FilePath, mut f: impl FnMut(usize, &NbtElement, &Indices) -> Result<usize, MoveSelectedTextError>) -> Result<WorkbenchAction, MoveSelectedTextError> {
+	fn move_text(&mut self, consts: TabConstants, root: &mut NbtElement, path: &mut FilePath, boolean_overrides: &BooleanOverrides, mut f: impl FnMut(usize, &NbtElement, &Indices) -> Result<usize, MoveSelectedTextError>) -> Result<WorkbenchAction, MoveSelectedTextError> {
 		let TabConstants { left_margin, .. } = consts;
 
 		let y = (self.y - HEADER_SIZE) / 16;
@@ -542,7 +692,7 @@ impl SelectedText {
 
 		let mouse_x = self.cached_cursor_x.unwrap_or_else(|| self.cursor_x(left_margin));
 
-		let new_selected_text = SelectedText::for_y(consts, root, path, new_y, mouse_x, true, Some(mouse_x))?;
+		let new_selected_text = SelectedText::for_y(consts, root, path, boolean_overrides, new_y, mouse_x, true, Some(mouse_x))?;
 
 		let action = self.save(root, path)?;
 
@@ -551,8 +701,8 @@ impl SelectedText {
 		Ok(action)
 	}
 
-	pub fn move_up(&mut self, consts: TabConstants, ctrl: bool, root: &mut NbtElement, path: &mut FilePath) -> Result<WorkbenchAction, MoveSelectedTextError> {
-		self.move_text(consts, root, path, |y, root, indices| {
+	pub fn move_up(&mut self, consts: TabConstants, ctrl: bool, root: &mut NbtElement, path: &mut FilePath, boolean_overrides: &BooleanOverrides) -> Result<WorkbenchAction, MoveSelectedTextError> {
+		self.move_text(consts, root, path, boolean_overrides, |y, root, indices| {
 			Ok(
 				if ctrl
 					&& let Some(last_idx) = indices.last()
@@ -567,8 +717,8 @@ impl SelectedText {
 		})
 	}
 
-	pub fn move_down(&mut self, consts: TabConstants, ctrl: bool, root: &mut NbtElement, path: &mut FilePath) -> Result<WorkbenchAction, MoveSelectedTextError> {
-		self.move_text(consts, root, path, |y, root, indices| {
+	pub fn move_down(&mut self, consts: TabConstants, ctrl: bool, root: &mut NbtElement, path: &mut FilePath, boolean_overrides: &BooleanOverrides) -> Result<WorkbenchAction, MoveSelectedTextError> {
+		self.move_text(consts, root, path, boolean_overrides, |y, root, indices| {
 			Ok(if ctrl && let Some((last_idx, parent_indices)) = indices.split_last() {
 				let NavigationInformation { element: parent, line_number, .. } = root.navigate(&parent_indices).map_err(|e| MoveSelectedTextError::Navigation(e))?;
 				let len = parent.len().ok_or_else(|| {
@@ -595,9 +745,7 @@ impl SelectedText {
 
 	pub fn force_close(&self, root: &mut NbtElement, bookmarks: &mut MarkedLines) -> Result<(), CloseElementError> { close_element(root, &self.indices, bookmarks) }
 
-	pub fn force_open(&self, expand: bool, root: &mut NbtElement, bookmarks: &mut MarkedLines) -> Result<(), AmbiguiousOpenElementError> {
-		if expand { Ok(expand_element(root, &self.indices, bookmarks)?) } else { Ok(open_element(root, &self.indices, bookmarks)?) }
-	}
+	pub fn force_open(&self, root: &mut NbtElement, bookmarks: &mut MarkedLines) -> Result<(), AmbiguiousOpenElementError> { Ok(open_element(root, &self.indices, bookmarks)?) }
 }
 
 #[derive(Error, Debug)]
@@ -618,6 +766,14 @@ pub enum SaveSelectedTextError {
 	NonEditable,
 }
 
+#[derive(Error, Debug)]
+pub enum SetSelectedTextError {
+	#[error(transparent)]
+	Construction(#[from] SelectedTextConstructionError),
+	#[error(transparent)]
+	Save(#[from] SaveSelectedTextError),
+}
+
 #[derive(Error, Debug)]
 pub enum MoveSelectedTextError {
 	#[error(transparent)]
@@ -673,3 +829,44 @@ pub enum SelectedTextInputError {
 	#[error(transparent)]
 	MoveSelectedText(#[from] MoveSelectedTextError),
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::elements::number_base::NumberBase;
+
+	#[test]
+	fn test_sibling_keys() {
+		let (_, root) = NbtElement::from_str(r#"{"entities":[{"id":"a","Count":1b},{"id":"b","CustomName":"x","Color":2b},{"Tags":[]}]}"#).expect("Valid SNBT");
+		let keys = sibling_keys(&root, &OwnedIndices::from([0, 0, 1]));
+		assert_eq!(keys, ["Color", "CustomName", "Tags"]);
+		assert_eq!(matching_keys(keys, "c"), ["Color", "CustomName"]);
+		// there's no list around the compound
+		let (_, root) = NbtElement::from_str(r#"{"a":{"b":1b}}"#).expect("Valid SNBT");
+		assert!(sibling_keys(&root, &OwnedIndices::from([0, 0])).is_empty());
+		assert!(matching_keys(vec!["Count".into()], "Count").is_empty());
+	}
+
+	#[test]
+	fn test_unedited_floats_save_identically() {
+		// `Display` writes these out without an exponent
+		let snbt = format!("{{a:{}d,b:{}d,c:-0.0d,d:{}d,e:0.1f,f:1.5d,g:{}f}}", 0.1 + 0.2, 1e-320, f64::MAX, f32::MIN_POSITIVE);
+		let (_, root) = NbtElement::from_str(&snbt).expect("Valid SNBT");
+		let mut bytes = root.to_be_file();
+		// `f` becomes a NaN with a payload, which SNBT can't write
+		let sentinel = 1.5_f64.to_be_bytes();
+		let at = bytes.windows(sentinel.len()).position(|window| window == sentinel).expect("Sentinel is written");
+		bytes[at..at + sentinel.len()].copy_from_slice(&0x7ff8_0000_dead_beef_u64.to_be_bytes());
+
+		let mut root = NbtElement::from_be_file(&bytes).expect("Valid NBT");
+		let mut path = FilePath::new("test.nbt").expect("Valid path");
+		let consts = TabConstants { left_margin: 0, scroll: 0, horizontal_scroll: 0, number_base: NumberBase::Decimal };
+		for y in 1..root.height() {
+			let selected_text = SelectedText::for_y(consts, &root, &path, &BooleanOverrides::new(), y, usize::MAX / 2, true, None).expect("Line is there");
+			let (_, element) = root.get(y - 1).expect("Entry is there");
+			assert_eq!(selected_text.value, element.value().0, "Starts from the round-trip string");
+			let _ = selected_text.save(&mut root, &mut path).expect("Unedited value is valid");
+		}
+		assert_eq!(root.to_be_file(), bytes);
+	}
+}
@@ -0,0 +1,405 @@
+use std::ops::{Deref, DerefMut, Range};
+
+use winit::keyboard::KeyCode;
+
+use crate::{
+	config,
+	elements::element::NbtElement,
+	flags,
+	history::WorkbenchAction,
+	serialization::json::{format_json, is_json_text, minify_json},
+	render::{
+		assets::{DARK_STRIPE_UV, HEADER_SIZE, SELECTION_UV, STRING_EDITOR_SELECTION_Z, STRING_EDITOR_TEXT_Z, STRING_EDITOR_Z, TEXT_UNDERLINE_UV},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+		widget::text::{Cachelike, StringEditorKeyResult, Text, get_cursor_idx},
+		window::Theme,
+	},
+	tree::{
+		actions::rename::{RenameElementError, rename_element},
+		indices::{Indices, OwnedIndices},
+	},
+	util::{AxisAlignedBoundingBox, CharExt, StrExt, Vec2u},
+	workbench::tab::FilePath,
+};
+
+/// Edits a string with newlines or too wide for its row, word-wrapped over the tree of the tab it's in
+pub struct StringEditor {
+	text: Text<(), StringEditorCache>,
+	indices: OwnedIndices,
+	/// What the string is under, shown above it
+	title: String,
+	/// The first wrapped line shown
+	scroll: usize,
+	/// Whether it held a JSON object or array when opened, so it can be formatted
+	is_json: bool,
+	/// Set once it's been formatted, to be minified again when saved
+	formatted_json: Option<FormattedJson>,
+	/// Where the formatted JSON was found to be invalid when last saved
+	json_error: Option<usize>,
+}
+
+struct FormattedJson {
+	/// The string before it was formatted, saved as is when nothing's changed since so escapes and spacing are kept
+	original: String,
+	formatted: String,
+}
+
+impl Deref for StringEditor {
+	type Target = Text<(), StringEditorCache>;
+
+	fn deref(&self) -> &Self::Target { &self.text }
+}
+
+impl DerefMut for StringEditor {
+	fn deref_mut(&mut self) -> &mut Self::Target { &mut self.text }
+}
+
+#[derive(Clone, Eq)]
+pub struct StringEditorCache {
+	value: String,
+	cursor: usize,
+	selection: Option<usize>,
+}
+
+impl PartialEq for StringEditorCache {
+	fn eq(&self, other: &Self) -> bool { self.value == other.value }
+}
+
+impl Cachelike<()> for StringEditorCache {
+	fn new(text: &Text<(), Self>) -> Self
+	where Self: Sized {
+		Self {
+			value: text.value.clone(),
+			cursor: text.cursor,
+			selection: text.selection,
+		}
+	}
+
+	fn revert(self, text: &mut Text<(), Self>)
+	where Self: Sized {
+		let Self { value, cursor, selection } = self;
+
+		text.value = value;
+		text.cursor = cursor;
+		text.selection = selection;
+	}
+}
+
+impl StringEditor {
+	/// Room between it and the edges of the pane
+	const MARGIN: usize = 16;
+	/// Room between its edges and the text
+	const PADDING: usize = 6;
+	const TITLE_HEIGHT: usize = 20;
+	const FORMAT_JSON: &'static str = "Format JSON";
+
+	#[must_use]
+	pub fn new(indices: OwnedIndices, key: Option<&str>, value: String) -> Self {
+		let title = key.map_or_else(|| format!("[{}]", indices.last().unwrap_or(0)), str::to_owned);
+		Self {
+			is_json: is_json_text(&value),
+			text: Text::new(value, 0, true, ()),
+			indices,
+			title,
+			scroll: 0,
+			formatted_json: None,
+			json_error: None,
+		}
+	}
+
+	#[must_use]
+	pub fn indices(&self) -> &Indices { &self.indices }
+
+	/// Where it's drawn in a pane of `dims`
+	fn bounds(dims: Vec2u) -> AxisAlignedBoundingBox { AxisAlignedBoundingBox::new(Self::MARGIN, dims.x.saturating_sub(Self::MARGIN).max(Self::MARGIN), HEADER_SIZE + 8, dims.y.saturating_sub(8).max(HEADER_SIZE + 8)) }
+
+	/// Where the first line of text starts
+	fn text_pos(dims: Vec2u) -> Vec2u { Self::bounds(dims).low() + (Self::PADDING, Self::TITLE_HEIGHT) }
+
+	fn text_width(dims: Vec2u) -> usize { (Self::bounds(dims).high().x - Self::bounds(dims).low().x).saturating_sub(Self::PADDING * 2) }
+
+	/// How many lines fit
+	fn rows(dims: Vec2u) -> usize { ((Self::bounds(dims).high().y.saturating_sub(Self::text_pos(dims).y)) / 16).max(1) }
+
+	/// Where the "Format JSON" button is drawn in the title row
+	fn format_json_bounds(dims: Vec2u) -> AxisAlignedBoundingBox {
+		let bounds = Self::bounds(dims);
+		let x = bounds.high().x.saturating_sub(Self::PADDING + Self::FORMAT_JSON.width());
+		AxisAlignedBoundingBox::new(x, bounds.high().x - Self::PADDING, bounds.low().y, bounds.low().y + Self::TITLE_HEIGHT)
+	}
+
+	/// Spreads the JSON held over indented lines, it's minified again when saved
+	pub fn format_json(&mut self, dims: Vec2u) {
+		if !self.is_json || self.formatted_json.is_some() {
+			return
+		}
+		let Ok(formatted) = format_json(&self.value) else { return };
+		let original = core::mem::replace(&mut self.text.value, formatted.clone());
+		self.formatted_json = Some(FormattedJson { original, formatted });
+		self.cursor = 0;
+		self.selection = None;
+		self.scroll = 0;
+		self.text.post_input();
+		self.scroll_to_cursor(dims);
+	}
+
+	/// Minifies formatted JSON back to what's saved, or marks where it's invalid and keeps it open
+	fn prepare_save(&mut self) -> bool {
+		let Some(FormattedJson { original, formatted }) = &self.formatted_json else { return true };
+		if self.value == *formatted || self.value == *original {
+			self.text.value.clone_from(original);
+			return true
+		}
+		match minify_json(&self.value) {
+			Ok(minified) => {
+				self.text.value = minified;
+				true
+			}
+			Err(rest) => {
+				self.json_error = Some(self.value.len() - rest);
+				false
+			}
+		}
+	}
+
+	fn scroll_to_cursor(&mut self, dims: Vec2u) { scroll_to_cursor(&self.text, &mut self.scroll, Self::text_width(dims), Self::rows(dims)); }
+
+	pub fn on_scroll(&mut self, scroll: f32, dims: Vec2u) { scroll_lines(&self.text, &mut self.scroll, scroll, Self::text_width(dims), Self::rows(dims)); }
+
+	/// Moves the cursor to where `mouse` is over the text, selecting up to it with `extend`
+	pub fn click(&mut self, mouse: Vec2u, extend: bool, dims: Vec2u) {
+		let pos = Self::text_pos(dims);
+		if self.is_json && self.formatted_json.is_none() && Self::format_json_bounds(dims).contains(mouse) {
+			self.format_json(dims);
+			return
+		}
+		if !Self::bounds(dims).contains(mouse) || mouse.y < pos.y {
+			return
+		}
+		click_wrapped(&mut self.text, Vec2u::new(mouse.x.saturating_sub(pos.x), mouse.y - pos.y), self.scroll, extend, Self::text_width(dims));
+	}
+
+	/// Enter adds a newline where the single-line text would be finished, and \[Ctrl + Enter\] saves it instead, unless it's formatted JSON which isn't valid anymore
+	pub fn on_key_press(&mut self, key: KeyCode, ch: Option<char>, flags: u8, dims: Vec2u) -> StringEditorKeyResult {
+		let result = match key {
+			KeyCode::Enter | KeyCode::NumpadEnter if flags == flags!(Ctrl) => return if self.prepare_save() { StringEditorKeyResult::Save } else { StringEditorKeyResult::GenericAction },
+			KeyCode::KeyF if flags == flags!(Ctrl + Shift) => {
+				self.format_json(dims);
+				return StringEditorKeyResult::GenericAction
+			}
+			KeyCode::Enter | KeyCode::NumpadEnter if flags == flags!() => StringEditorKeyResult::from(self.text.on_key_press(key, None, flags!(Shift))),
+			KeyCode::ArrowUp | KeyCode::ArrowDown if flags & !flags!(Shift) == flags!() => {
+				move_vertically(&mut self.text, key == KeyCode::ArrowDown, flags == flags!(Shift), Self::text_width(dims));
+				StringEditorKeyResult::GenericAction
+			}
+			_ => StringEditorKeyResult::from(self.text.on_key_press(key, ch, flags)),
+		};
+		if result == StringEditorKeyResult::GenericAction {
+			self.json_error = None;
+			self.text.post_input();
+			self.scroll_to_cursor(dims);
+		}
+		result
+	}
+
+	/// Types what an input method commits at the cursor
+	pub fn insert_str(&mut self, str: &str, dims: Vec2u) {
+		self.text.insert_str(str);
+		self.json_error = None;
+		self.text.post_input();
+		self.scroll_to_cursor(dims);
+	}
+
+	/// Sets the string to what's been typed, as editing it in its row would
+	pub fn save(self, root: &mut NbtElement, path: &mut FilePath) -> Result<WorkbenchAction, RenameElementError> { Ok(rename_element(root, self.indices, None, Some(self.text.value), path)?.into_action()) }
+
+	pub fn render(&self, builder: &mut VertexBufferBuilder) {
+		use std::fmt::Write;
+
+		let dims = Vec2u::new(builder.window_width(), builder.window_height());
+		let bounds = Self::bounds(dims);
+		builder.draw_texture_region_z(bounds.low(), STRING_EDITOR_Z, DARK_STRIPE_UV + (1, 1), bounds.high() - bounds.low(), (14, 14));
+
+		builder.settings(bounds.low() + (Self::PADDING, 2), false, STRING_EDITOR_TEXT_Z);
+		builder.color = TextColor::TreeKey.to_raw();
+		let _ = write!(builder, "{}", self.title);
+		if let Some(position) = self.json_error {
+			let before = &self.value[..position];
+			let line = before.matches('\n').count() + 1;
+			let column = before.rsplit('\n').next().unwrap_or(before).chars().count() + 1;
+			builder.color = TextColor::Red.to_raw();
+			let _ = write!(builder, "   Invalid JSON at line {line}, column {column}");
+		} else {
+			builder.color = TextColor::Gray.to_raw();
+			let _ = write!(builder, "   [Ctrl + Enter] to save, [Escape] to cancel");
+		}
+		if self.is_json && self.formatted_json.is_none() {
+			let button = Self::format_json_bounds(dims);
+			builder.settings(button.low() + (0, 2), false, STRING_EDITOR_TEXT_Z);
+			builder.color = TextColor::Yellow.to_raw();
+			let _ = write!(builder, "{}", Self::FORMAT_JSON);
+		}
+
+		render_wrapped(&self.text, builder, Self::text_pos(dims), Self::text_width(dims), self.scroll, Self::rows(dims), true, self.json_error);
+	}
+}
+
+/// The index of the wrapped line the cursor is on, where it's on the later of two lines split without a newline between them
+fn line_of(lines: &[Range<usize>], cursor: usize) -> usize { lines.iter().rposition(|line| line.start <= cursor).unwrap_or(0) }
+
+/// Scrolls just far enough that the line with the cursor is one of the `rows` shown
+pub(super) fn scroll_to_cursor(text: &Text<(), StringEditorCache>, scroll: &mut usize, width: usize, rows: usize) {
+	let lines = wrap_lines(&text.value, width);
+	let line = line_of(&lines, text.cursor);
+	if line < *scroll {
+		*scroll = line;
+	} else if line >= *scroll + rows {
+		*scroll = line + 1 - rows;
+	}
+}
+
+/// Scrolls by three lines a notch, no further than the last line being at the bottom
+pub(super) fn scroll_lines(text: &Text<(), StringEditorCache>, scroll: &mut usize, delta: f32, width: usize, rows: usize) {
+	let max = wrap_lines(&text.value, width).len().saturating_sub(rows);
+	#[allow(clippy::cast_possible_truncation)]
+	let delta = (delta * 3.0).round() as isize;
+	*scroll = scroll.saturating_add_signed(delta).min(max);
+}
+
+/// Moves the cursor to the line above or below, keeping as close as it can to where it was across, or to the start or end of the text past the first and last lines
+#[allow(clippy::cast_possible_wrap)]
+pub(super) fn move_vertically(text: &mut Text<(), StringEditorCache>, down: bool, extend: bool, width: usize) {
+	let lines = wrap_lines(&text.value, width);
+	let line = line_of(&lines, text.cursor);
+	let x = text.value[lines[line].start..text.cursor].width();
+	let target = if down { line + 1 } else { line.wrapping_sub(1) };
+	let cursor = match lines.get(target) {
+		Some(range) => range.start + get_cursor_idx(&text.value[range.clone()], x as isize),
+		None if down => text.value.len(),
+		None => 0,
+	};
+	move_cursor(text, cursor, extend);
+}
+
+/// Moves the cursor to `offset` from where the first line shown starts, selecting up to it with `extend`
+#[allow(clippy::cast_possible_wrap)]
+pub(super) fn click_wrapped(text: &mut Text<(), StringEditorCache>, offset: Vec2u, scroll: usize, extend: bool, width: usize) {
+	let lines = wrap_lines(&text.value, width);
+	let line = (offset.y / 16 + scroll).min(lines.len() - 1);
+	let range = lines[line].clone();
+	let cursor = range.start + get_cursor_idx(&text.value[range], offset.x as isize);
+	move_cursor(text, cursor, extend);
+}
+
+fn move_cursor(text: &mut Text<(), StringEditorCache>, cursor: usize, extend: bool) {
+	if extend {
+		text.selection = text.selection.or(Some(text.cursor));
+	} else {
+		text.selection = None;
+	}
+	text.cursor = cursor;
+	if text.selection == Some(text.cursor) {
+		text.selection = None;
+	}
+	text.interact();
+}
+
+/// Draws the `rows` wrapped lines from `scroll` on at `pos`, with the selection, what's being composed and the cursor when `focused`, and a line under the character at `underline`
+#[allow(clippy::too_many_arguments)]
+pub(super) fn render_wrapped(text: &Text<(), StringEditorCache>, builder: &mut VertexBufferBuilder, pos: Vec2u, width: usize, scroll: usize, rows: usize, focused: bool, underline: Option<usize>) {
+	use std::fmt::Write;
+
+	let color = match config::get_theme() {
+		Theme::Light => TextColor::Black,
+		Theme::Dark => TextColor::White,
+	};
+	let lines = wrap_lines(&text.value, width);
+	let cursor_line = line_of(&lines, text.cursor);
+	let selection = text.selection.map(|selection| selection.min(text.cursor)..selection.max(text.cursor));
+	for (line, range) in lines.iter().enumerate().skip(scroll).take(rows) {
+		let y = pos.y + (line - scroll) * 16;
+		let line_text = &text.value[range.clone()];
+		builder.settings((pos.x, y), false, STRING_EDITOR_TEXT_Z);
+		builder.color = color.to_raw();
+		match text.preedit.as_deref().filter(|preedit| line == cursor_line && !preedit.is_empty()) {
+			Some(preedit) => {
+				let (left, right) = text.value[range.clone()].split_at(text.cursor - range.start);
+				let _ = write!(builder, "{left}{preedit}{right}");
+				builder.draw_texture_region_z((pos.x + left.width(), y + 14), STRING_EDITOR_SELECTION_Z, TEXT_UNDERLINE_UV, (preedit.width(), 2), (16, 2));
+			}
+			None => {
+				let _ = write!(builder, "{line_text}");
+			}
+		}
+		if let Some(selection) = &selection
+			&& selection.start <= range.end
+			&& selection.end >= range.start
+		{
+			let start = text.value[range.start..selection.start.max(range.start)].width();
+			// the newline after the line is shown selected as a space
+			let end = if selection.end > range.end { line_text.width() + ' '.width() } else { text.value[range.start..selection.end].width() };
+			if end > start {
+				builder.draw_texture_region_z((pos.x + start, y), STRING_EDITOR_SELECTION_Z, SELECTION_UV + (1, 1), (end - start, 16), (14, 14));
+			}
+		}
+		if focused && line == cursor_line && text.is_cursor_shown() {
+			let x = text.value[range.start..text.cursor].width() + text.preedit.as_deref().map_or(0, StrExt::width);
+			builder.draw_texture_region_z((pos.x + x, y), STRING_EDITOR_SELECTION_Z, SELECTION_UV, (2, 16), (1, 16));
+		}
+		if let Some(position) = underline
+			&& line_of(&lines, position) == line
+		{
+			let x = text.value[range.start..position].width();
+			// past the end there's no character to mark, so it's as wide as a digit
+			let width = text.value[position..].chars().next().filter(|&char| char != '\n').map_or(8, CharExt::width);
+			builder.draw_texture_region_z((pos.x + x, y + 14), STRING_EDITOR_SELECTION_Z, TEXT_UNDERLINE_UV, (width, 2), (16, 2));
+		}
+	}
+}
+
+/// The byte ranges of `value` split into lines no wider than `width`, at newlines and after spaces where it can, and anywhere in words too wide for a line of their own
+#[must_use]
+pub fn wrap_lines(value: &str, width: usize) -> Vec<Range<usize>> {
+	let mut lines = Vec::new();
+	let mut line_start = 0;
+	loop {
+		let line_end = value[line_start..].find('\n').map_or(value.len(), |idx| line_start + idx);
+		let (mut start, mut x, mut last_break) = (line_start, 0, None);
+		for (idx, char) in value[line_start..line_end].char_indices().map(|(idx, char)| (line_start + idx, char)) {
+			let char_width = char.width();
+			if x + char_width > width && idx > start {
+				let end = last_break.filter(|&end| end > start).unwrap_or(idx);
+				lines.push(start..end);
+				x = value[end..idx].width();
+				start = end;
+				last_break = None;
+			}
+			x += char_width;
+			if char == ' ' {
+				last_break = Some(idx + 1);
+			}
+		}
+		lines.push(start..line_end);
+		if line_end == value.len() {
+			break lines
+		}
+		line_start = line_end + 1;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_wrap_lines() {
+		// digits are 8px wide and spaces 5px
+		let empty = wrap_lines("", 80);
+		assert_eq!((empty.len(), empty[0].clone()), (1, 0..0));
+		assert_eq!(wrap_lines("12 34\n\n5", 80), [0..5, 6..6, 7..8]);
+		assert_eq!(wrap_lines("123 456 789", 48), [0..4, 4..8, 8..11]);
+		assert_eq!(wrap_lines("1234567890", 32), [0..4, 4..8, 8..10]);
+		assert_eq!(wrap_lines("12\n", 80), [0..2, 3..3]);
+	}
+}
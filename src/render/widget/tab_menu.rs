@@ -0,0 +1,138 @@
+use crate::{
+	render::{
+		assets::{DARK_STRIPE_UV, HOVERED_STRIPE_UV, TAB_MENU_SELECTION_Z, TAB_MENU_TEXT_Z, TAB_MENU_Z},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+	},
+	util::{AxisAlignedBoundingBox, StrExt, Vec2u},
+	workbench::tab::NbtFileFormat,
+};
+
+/// Actions on a tab, dropped down from it with a right click
+#[derive(Default)]
+pub struct TabMenu {
+	/// The tab it's for and how far along the window it was opened
+	open: Option<(usize, usize)>,
+}
+
+#[derive(Copy, Clone)]
+pub enum TabMenuAction {
+	Duplicate(usize),
+	/// With the tab it's for as the one before and the active one as the one after
+	CompareWithActive(usize),
+	/// Shows it in a pane beside the active tab
+	SplitWithActive(usize),
+	CloseSplit,
+	CloseOthers(usize),
+	CloseToTheRight(usize),
+	/// Parses its file anew as the format, for when it was guessed wrong
+	OpenAs(usize, NbtFileFormat),
+}
+
+impl TabMenu {
+	const Y: usize = 21;
+	const PADDING: usize = 4;
+	const ENTRIES: [&'static str; 6] = ["Duplicate tab", "Compare with active tab", "Split with active tab", "Close other tabs", "Close tabs to the right", "Open as…"];
+	/// The entry which shows [`Self::OPEN_AS`] beside it while hovered
+	const OPEN_AS_ENTRY: usize = 5;
+	/// The formats which can be mistaken for each other, compressed files and regions are always told apart
+	const OPEN_AS: [(&'static str, NbtFileFormat); 3] = [("Big-endian NBT", NbtFileFormat::Nbt), ("Little-endian NBT", NbtFileFormat::LittleEndianNbt), ("SNBT", NbtFileFormat::Snbt)];
+	/// In place of splitting for either of the tabs already split
+	const CLOSE_SPLIT: &'static str = "Close split view";
+
+	#[must_use]
+	pub const fn new() -> Self { Self { open: None } }
+
+	#[must_use]
+	pub const fn is_open(&self) -> bool { self.open.is_some() }
+
+	pub const fn open(&mut self, idx: usize, x: usize) { self.open = Some((idx, x)); }
+
+	pub const fn close(&mut self) { self.open = None; }
+
+	fn bounds(x: usize) -> AxisAlignedBoundingBox {
+		let width = Self::ENTRIES.iter().map(|entry| entry.width()).max().unwrap_or(0) + Self::PADDING * 2;
+		AxisAlignedBoundingBox::new(x, x + width, Self::Y, Self::Y + Self::ENTRIES.len() * 16)
+	}
+
+	/// Beside the "Open as…" entry, level with it
+	fn open_as_bounds(x: usize) -> AxisAlignedBoundingBox {
+		let x = Self::bounds(x).high().x;
+		let y = Self::Y + Self::OPEN_AS_ENTRY * 16;
+		let width = Self::OPEN_AS.iter().map(|(name, _)| name.width()).max().unwrap_or(0) + Self::PADDING * 2;
+		AxisAlignedBoundingBox::new(x, x + width, y, y + Self::OPEN_AS.len() * 16)
+	}
+
+	/// Whether the formats to open as are shown, while the mouse is over their entry or them
+	fn is_open_as_shown(x: usize, mouse: Vec2u) -> bool {
+		let y = Self::Y + Self::OPEN_AS_ENTRY * 16;
+		AxisAlignedBoundingBox::new(x, Self::bounds(x).high().x, y, y + 16).contains(mouse) || Self::open_as_bounds(x).contains(mouse)
+	}
+
+	/// Whether the tab at `idx` is one of the two shown, with `split_idx` being the other pane's tab
+	fn is_split(idx: usize, active_idx: usize, split_idx: Option<usize>) -> bool { split_idx.is_some_and(|split_idx| idx == split_idx || idx == active_idx) }
+
+	/// Entries with no tabs to close, compare or split with can't be clicked
+	fn action(idx: usize, entry: usize, tab_count: usize, active_idx: usize, split_idx: Option<usize>) -> Option<TabMenuAction> {
+		match entry {
+			0 => Some(TabMenuAction::Duplicate(idx)),
+			1 if idx != active_idx => Some(TabMenuAction::CompareWithActive(idx)),
+			2 if Self::is_split(idx, active_idx, split_idx) => Some(TabMenuAction::CloseSplit),
+			2 if idx != active_idx => Some(TabMenuAction::SplitWithActive(idx)),
+			3 if tab_count > 1 => Some(TabMenuAction::CloseOthers(idx)),
+			4 if idx + 1 < tab_count => Some(TabMenuAction::CloseToTheRight(idx)),
+			_ => None,
+		}
+	}
+
+	#[must_use]
+	pub fn is_within_bounds(&self, mouse: Vec2u) -> bool { self.open.is_some_and(|(_, x)| Self::bounds(x).contains(mouse) || Self::is_open_as_shown(x, mouse)) }
+
+	#[must_use]
+	pub fn click(&self, mouse: Vec2u, tab_count: usize, active_idx: usize, split_idx: Option<usize>) -> Option<TabMenuAction> {
+		let (idx, x) = self.open?;
+		if idx >= tab_count {
+			return None
+		}
+		if Self::open_as_bounds(x).contains(mouse) {
+			return Self::OPEN_AS.get((mouse.y - Self::open_as_bounds(x).low().y) / 16).map(|&(_, format)| TabMenuAction::OpenAs(idx, format))
+		}
+		if !Self::bounds(x).contains(mouse) {
+			return None
+		}
+		Self::action(idx, (mouse.y - Self::Y) / 16, tab_count, active_idx, split_idx)
+	}
+
+	pub fn render(&self, builder: &mut VertexBufferBuilder, mouse: Vec2u, tab_count: usize, active_idx: usize, split_idx: Option<usize>) {
+		use std::fmt::Write;
+
+		let Some((idx, x)) = self.open else { return };
+		let bounds = Self::bounds(x);
+		builder.draw_texture_region_z(bounds.low(), TAB_MENU_Z, DARK_STRIPE_UV + (1, 1), bounds.high() - bounds.low(), (14, 14));
+		for (entry_idx, entry) in Self::ENTRIES.iter().enumerate() {
+			let y = Self::Y + entry_idx * 16;
+			let enabled = Self::action(idx, entry_idx, tab_count, active_idx, split_idx).is_some();
+			let entry = if entry_idx == 2 && Self::is_split(idx, active_idx, split_idx) { Self::CLOSE_SPLIT } else { entry };
+			if enabled && AxisAlignedBoundingBox::new(bounds.low().x, bounds.high().x, y, y + 16).contains(mouse) {
+				builder.draw_texture_region_z((x, y), TAB_MENU_SELECTION_Z, HOVERED_STRIPE_UV, (bounds.high().x - x, 16), (16, 16));
+			}
+			builder.settings((x + Self::PADDING, y), false, TAB_MENU_TEXT_Z);
+			builder.color = if enabled { TextColor::White } else { TextColor::DarkGray }.to_raw();
+			let _ = write!(builder, "{entry}");
+		}
+
+		if Self::is_open_as_shown(x, mouse) {
+			let bounds = Self::open_as_bounds(x);
+			builder.draw_texture_region_z(bounds.low(), TAB_MENU_Z, DARK_STRIPE_UV + (1, 1), bounds.high() - bounds.low(), (14, 14));
+			for (entry_idx, (name, _)) in Self::OPEN_AS.iter().enumerate() {
+				let y = bounds.low().y + entry_idx * 16;
+				if AxisAlignedBoundingBox::new(bounds.low().x, bounds.high().x, y, y + 16).contains(mouse) {
+					builder.draw_texture_region_z((bounds.low().x, y), TAB_MENU_SELECTION_Z, HOVERED_STRIPE_UV, (bounds.high().x - bounds.low().x, 16), (16, 16));
+				}
+				builder.settings((bounds.low().x + Self::PADDING, y), false, TAB_MENU_TEXT_Z);
+				builder.color = TextColor::White.to_raw();
+				let _ = write!(builder, "{name}");
+			}
+		}
+	}
+}
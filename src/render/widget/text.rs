@@ -4,18 +4,19 @@ use std::{
 	time::Duration,
 };
 
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
 use winit::keyboard::KeyCode;
 
 use crate::{
 	flags,
 	history::WorkbenchAction,
 	render::{
-		assets::{SELECTION_UV, ZOffset},
+		assets::{SELECTION_UV, TEXT_UNDERLINE_UV, ZOffset},
 		color::TextColor,
 		vertex_buffer_builder::VertexBufferBuilder,
 		widget::text::KeyResult::{Escape, Finish, GenericAction, NoAction},
 	},
-	util::{CharExt, LinkedQueue, StrExt, Timestamp, Vec2u, get_clipboard, is_jump_char_boundary, is_utf8_char_boundary, set_clipboard},
+	util::{LinkedQueue, StrExt, Timestamp, Vec2u, get_clipboard, is_jump_char_boundary, set_clipboard},
 };
 
 pub const TEXT_DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(250);
@@ -37,6 +38,8 @@ pub enum SearchBoxKeyResult {
 	MoveToReplaceBox,
 	Search,
 	SearchCountOnly,
+	NextHit,
+	PreviousHit,
 }
 
 #[derive(PartialEq, Eq)]
@@ -48,6 +51,38 @@ pub enum ReplaceBoxKeyResult {
 	ReplaceAll,
 }
 
+#[derive(PartialEq, Eq)]
+pub enum GoToBoxKeyResult {
+	NoAction,
+	GenericAction,
+	Escape,
+	GoTo,
+}
+
+#[derive(PartialEq, Eq)]
+pub enum UrlBoxKeyResult {
+	NoAction,
+	GenericAction,
+	Escape,
+	Open,
+}
+
+#[derive(PartialEq, Eq)]
+pub enum ValuesBoxKeyResult {
+	NoAction,
+	GenericAction,
+	Escape,
+	Apply,
+}
+
+#[derive(PartialEq, Eq)]
+pub enum StringEditorKeyResult {
+	NoAction,
+	GenericAction,
+	Cancel,
+	Save,
+}
+
 #[derive(PartialEq, Eq)]
 pub enum KeyResult {
 	NoAction,
@@ -73,7 +108,7 @@ impl From<KeyResult> for SearchBoxKeyResult {
 			NoAction => Self::NoAction,
 			GenericAction => Self::GenericAction,
 			Escape => Self::Escape,
-			Finish => Self::Search,
+			Finish => Self::NextHit,
 		}
 	}
 }
@@ -89,75 +124,88 @@ impl From<KeyResult> for ReplaceBoxKeyResult {
 	}
 }
 
-pub trait Cachelike<Additional: Clone>: PartialEq + Clone {
-	fn new(text: &Text<Additional, Self>) -> Self
-	where Self: Sized;
-
-	fn revert(self, text: &mut Text<Additional, Self>)
-	where Self: Sized;
+impl From<KeyResult> for GoToBoxKeyResult {
+	fn from(value: KeyResult) -> Self {
+		match value {
+			NoAction => Self::NoAction,
+			GenericAction => Self::GenericAction,
+			Escape => Self::Escape,
+			Finish => Self::GoTo,
+		}
+	}
 }
 
-pub fn get_cursor_left_jump_idx(mut cursor: usize, bytes: &[u8]) -> usize {
-	if cursor > 0 {
-		cursor -= 1;
-		if bytes[cursor].is_ascii_whitespace() {
-			while cursor > 0 {
-				let byte = bytes[cursor];
-				if byte.is_ascii_whitespace() {
-					cursor -= 1;
-				} else {
-					break;
-				}
-			}
+impl From<KeyResult> for UrlBoxKeyResult {
+	fn from(value: KeyResult) -> Self {
+		match value {
+			NoAction => Self::NoAction,
+			GenericAction => Self::GenericAction,
+			Escape => Self::Escape,
+			Finish => Self::Open,
 		}
+	}
+}
 
-		let last_byte = bytes[cursor];
-		while cursor > 0 {
-			let byte = bytes[cursor];
-			if is_jump_char_boundary(byte) != is_jump_char_boundary(last_byte) {
-				cursor += 1;
-				while cursor < bytes.len() && !is_utf8_char_boundary(bytes[cursor]) {
-					cursor += 1;
-				}
-				break;
-			}
-			cursor -= 1;
-			while !is_utf8_char_boundary(bytes[cursor]) {
-				cursor -= 1;
-			}
+impl From<KeyResult> for ValuesBoxKeyResult {
+	fn from(value: KeyResult) -> Self {
+		match value {
+			NoAction => Self::NoAction,
+			GenericAction => Self::GenericAction,
+			Escape => Self::Escape,
+			Finish => Self::Apply,
 		}
 	}
-	cursor
 }
 
-pub fn get_cursor_right_jump_idx(mut cursor: usize, bytes: &[u8]) -> usize {
-	if cursor < bytes.len() {
-		if bytes[cursor].is_ascii_whitespace() {
-			while cursor < bytes.len() {
-				let byte = bytes[cursor];
-				if byte.is_ascii_whitespace() {
-					cursor += 1;
-				} else {
-					break;
-				}
-			}
+impl From<KeyResult> for StringEditorKeyResult {
+	fn from(value: KeyResult) -> Self {
+		match value {
+			NoAction => Self::NoAction,
+			GenericAction => Self::GenericAction,
+			Escape => Self::Cancel,
+			Finish => Self::Save,
 		}
+	}
+}
 
-		let last_byte = bytes[cursor];
-		while cursor < bytes.len() {
-			let byte = bytes[cursor];
-			if is_jump_char_boundary(byte) != is_jump_char_boundary(last_byte) {
-				break;
-			}
-			cursor += 1;
-			while cursor < bytes.len() && !is_utf8_char_boundary(bytes[cursor]) {
-				cursor += 1;
-			}
+pub trait Cachelike<Additional: Clone>: PartialEq + Clone {
+	fn new(text: &Text<Additional, Self>) -> Self
+	where Self: Sized;
+
+	fn revert(self, text: &mut Text<Additional, Self>)
+	where Self: Sized;
+}
+
+/// Where \[Ctrl + ←\] moves to from `cursor`, past any whitespace and then to the start of the word or run of symbols before it
+#[must_use]
+pub fn get_cursor_left_jump_idx(cursor: usize, str: &str) -> usize {
+	let mut chars = str[..cursor].char_indices().rev().skip_while(|(_, char)| char.is_whitespace()).peekable();
+	let Some(&(mut idx, last)) = chars.peek() else { return 0 };
+	for (char_idx, char) in chars {
+		if is_jump_char_boundary(char) != is_jump_char_boundary(last) {
+			break;
 		}
+		idx = char_idx;
 	}
-	cursor
+	idx
 }
 
+/// Where \[Ctrl + →\] moves to from `cursor`, past any whitespace and then to the end of the word or run of symbols after it
+#[must_use]
+pub fn get_cursor_right_jump_idx(cursor: usize, str: &str) -> usize {
+	let mut chars = str[cursor..].char_indices().map(|(idx, char)| (cursor + idx, char)).skip_while(|(_, char)| char.is_whitespace()).peekable();
+	let Some(&(_, first)) = chars.peek() else { return str.len() };
+	chars.find(|&(_, char)| is_jump_char_boundary(char) != is_jump_char_boundary(first)).map_or(str.len(), |(idx, _)| idx)
+}
+
+/// The start of the grapheme before `cursor`, so an accent or emoji made of several chars is moved over and deleted as one
+#[must_use]
+pub fn get_cursor_prev_idx(cursor: usize, str: &str) -> usize { GraphemeCursor::new(cursor, str.len(), true).prev_boundary(str, 0).ok().flatten().unwrap_or(0) }
+
+/// The end of the grapheme after `cursor`
+#[must_use]
+pub fn get_cursor_next_idx(cursor: usize, str: &str) -> usize { GraphemeCursor::new(cursor, str.len(), true).next_boundary(str, 0).ok().flatten().unwrap_or(str.len()) }
+
 #[derive(Clone)]
 pub struct Text<Additional: Clone, Cache: Cachelike<Additional>> {
 	pub value: String,
@@ -165,6 +213,8 @@ pub struct Text<Additional: Clone, Cache: Cachelike<Additional>> {
 	pub selection: Option<usize>,
 	pub editable: bool,
 	pub additional: Additional,
+	/// What the input method is composing at the cursor, before it's committed
+	pub preedit: Option<String>,
 	drag_selectable: bool,
 	last_interaction: Timestamp,
 	undos: LinkedQueue<Cache>,
@@ -192,6 +242,7 @@ impl<Additional: Clone, Cache: Cachelike<Additional>> Text<Additional, Cache> {
 			undos: LinkedQueue::new(),
 			redos: LinkedQueue::new(),
 			additional,
+			preedit: None,
 			drag_selectable: true,
 		};
 		this.save_state_in_history();
@@ -208,6 +259,7 @@ impl<Additional: Clone, Cache: Cachelike<Additional>> Text<Additional, Cache> {
 			undos: LinkedQueue::new(),
 			redos: LinkedQueue::new(),
 			additional: unsafe { core::mem::MaybeUninit::zeroed().assume_init() },
+			preedit: None,
 			drag_selectable: true,
 		}
 	}
@@ -219,6 +271,22 @@ impl<Additional: Clone, Cache: Cachelike<Additional>> Text<Additional, Cache> {
 
 	pub fn interact(&mut self) { self.last_interaction = Timestamp::now(); }
 
+	/// Types `str` over the selection or at the cursor, as an input method commits it
+	pub fn insert_str(&mut self, str: &str) {
+		self.preedit = None;
+		if !self.editable {
+			return
+		}
+		let (start, end) = self.selection.take().map_or((self.cursor, self.cursor), |selection| (selection.min(self.cursor), selection.max(self.cursor)));
+		self.value.replace_range(start..end, str);
+		self.cursor = start + str.len();
+		self.interact();
+	}
+
+	/// How wide it's drawn, with what's being composed
+	#[must_use]
+	pub fn display_width(&self) -> usize { self.value.width() + self.preedit.as_deref().map_or(0, StrExt::width) }
+
 	#[must_use]
 	pub fn on_key_press(&mut self, key: KeyCode, mut char: Option<char>, flags: u8) -> KeyResult {
 		if key == KeyCode::Escape && flags == flags!() {
@@ -358,21 +426,14 @@ impl<Additional: Clone, Cache: Cachelike<Additional>> Text<Additional, Cache> {
 			let (left, right) = self.value.split_at(self.cursor);
 			if flags & flags!(Ctrl) > 0 {
 				if !left.is_empty() {
-					let new = get_cursor_left_jump_idx(self.cursor, self.value.as_bytes());
+					let new = get_cursor_left_jump_idx(self.cursor, &self.value);
 					let (left, _) = left.split_at(new);
 					self.value = format!("{left}{right}");
 					self.cursor = new;
 				}
 			} else {
 				if !left.is_empty() {
-					let mut end = left.len() - 1;
-					while end > 0 {
-						if is_utf8_char_boundary(left.as_bytes()[end]) {
-							break;
-						}
-						end -= 1;
-					}
-					let (left, _) = left.split_at(end);
+					let (left, _) = left.split_at(get_cursor_prev_idx(self.cursor, &self.value));
 					self.cursor = left.len();
 					self.value = format!("{left}{right}");
 				}
@@ -385,21 +446,12 @@ impl<Additional: Clone, Cache: Cachelike<Additional>> Text<Additional, Cache> {
 			let (left, right) = self.value.split_at(self.cursor);
 			if flags & flags!(Ctrl) > 0 {
 				if !right.is_empty() {
-					let new = get_cursor_right_jump_idx(self.cursor, self.value.as_bytes());
-					let (_, right) = right.split_at(new);
+					let (_, right) = self.value.split_at(get_cursor_right_jump_idx(self.cursor, &self.value));
 					self.value = format!("{left}{right}");
-					self.cursor = new;
 				}
 			} else {
 				if !right.is_empty() {
-					let mut start = 1;
-					while start < right.len() {
-						if is_utf8_char_boundary(right.as_bytes()[start]) {
-							break;
-						}
-						start += 1;
-					}
-					let (_, right) = right.split_at(start);
+					let (_, right) = self.value.split_at(get_cursor_next_idx(self.cursor, &self.value));
 					self.cursor = left.len();
 					self.value = format!("{left}{right}");
 				}
@@ -416,21 +468,7 @@ impl<Additional: Clone, Cache: Cachelike<Additional>> Text<Additional, Cache> {
 					return GenericAction;
 				}
 
-				let mut new = self.cursor;
-				if flags & flags!(Ctrl) > 0 {
-					new = get_cursor_left_jump_idx(self.cursor, self.value.as_bytes());
-				} else {
-					if new > 0 {
-						new -= 1;
-						while new > 0 {
-							if is_utf8_char_boundary(self.value.as_bytes()[new]) {
-								break;
-							}
-
-							new -= 1;
-						}
-					}
-				}
+				let new = if flags & flags!(Ctrl) > 0 { get_cursor_left_jump_idx(self.cursor, &self.value) } else { get_cursor_prev_idx(self.cursor, &self.value) };
 
 				if flags & flags!(Shift) > 0 {
 					if self.selection.is_none() {
@@ -458,21 +496,7 @@ impl<Additional: Clone, Cache: Cachelike<Additional>> Text<Additional, Cache> {
 					return GenericAction;
 				}
 
-				let mut new = self.cursor;
-				if flags & flags!(Ctrl) > 0 {
-					new = get_cursor_right_jump_idx(self.cursor, self.value.as_bytes());
-				} else {
-					if new < self.value.len() {
-						new += 1;
-						while new < self.value.len() {
-							if is_utf8_char_boundary(self.value.as_bytes()[new]) {
-								break;
-							}
-
-							new += 1;
-						}
-					}
-				}
+				let new = if flags & flags!(Ctrl) > 0 { get_cursor_right_jump_idx(self.cursor, &self.value) } else { get_cursor_next_idx(self.cursor, &self.value) };
 
 				if flags & flags!(Shift) > 0 {
 					if self.selection.is_none() {
@@ -527,7 +551,15 @@ impl<Additional: Clone, Cache: Cachelike<Additional>> Text<Additional, Cache> {
 		builder.settings((x, y), false, z);
 
 		builder.color = color.to_raw();
-		let _ = write!(builder, "{}", self.value);
+		let preedit = self.preedit.as_deref().filter(|_| self.editable).unwrap_or_default();
+		if preedit.is_empty() {
+			let _ = write!(builder, "{}", self.value);
+		} else {
+			// the rest of the text makes room for it, as it would once committed
+			let (left, right) = self.value.split_at(self.cursor);
+			let _ = write!(builder, "{left}{preedit}{right}");
+			builder.draw_texture_region_z((x + left.width(), y + 14), selection_z, TEXT_UNDERLINE_UV, (preedit.width(), 2), (16, 2));
+		}
 
 		if self.editable {
 			let cursor_prefixing = self.value.split_at(self.cursor).0;
@@ -540,16 +572,23 @@ impl<Additional: Clone, Cache: Cachelike<Additional>> Text<Additional, Cache> {
 				let end = self.value.split_at(end).0.width();
 				builder.draw_texture_region_z((start + x, y), selection_z, SELECTION_UV + (1, 1), (end - start - 1, 16), (14, 14));
 				if time_since_last_interaction < CURSOR_BLINK_RATE || time_since_last_interaction.subsec_millis() < CURSOR_BLINK_RATE.subsec_micros() {
-					builder.draw_texture_region_z((x + cursor_prefixing.width() - 1, y), selection_z, SELECTION_UV, (2, 16), (1, 16));
+					builder.draw_texture_region_z((x + cursor_prefixing.width() + preedit.width() - 1, y), selection_z, SELECTION_UV, (2, 16), (1, 16));
 				}
 			} else {
 				if time_since_last_interaction < CURSOR_BLINK_RATE || time_since_last_interaction.subsec_millis() < CURSOR_BLINK_RATE.subsec_millis() {
-					builder.draw_texture_region_z((x + cursor_prefixing.width(), y), selection_z, SELECTION_UV, (2, 16), (1, 16));
+					builder.draw_texture_region_z((x + cursor_prefixing.width() + preedit.width(), y), selection_z, SELECTION_UV, (2, 16), (1, 16));
 				}
 			}
 		}
 	}
 
+	/// Whether the cursor is drawn right now, as it blinks once it's been left alone for a while
+	#[must_use]
+	pub fn is_cursor_shown(&self) -> bool {
+		let time_since_last_interaction = self.last_interaction.elapsed();
+		time_since_last_interaction < CURSOR_BLINK_RATE || time_since_last_interaction.subsec_millis() < CURSOR_BLINK_RATE.subsec_millis()
+	}
+
 	pub fn save_state_in_history(&mut self) { self.undos.push(Cache::new(&self)); }
 
 	pub fn post_input(&mut self) { self.cache(); }
@@ -586,8 +625,8 @@ impl<Additional: Clone, Cache: Cachelike<Additional>> Text<Additional, Cache> {
 #[inline]
 #[must_use]
 pub fn get_cursor_idx(str: &str, mut x: isize) -> usize {
-	for (i, char) in str.char_indices() {
-		let width = char.width() as isize;
+	for (i, grapheme) in str.grapheme_indices(true) {
+		let width = grapheme.width() as isize;
 		if x <= width / 2 {
 			return i
 		}
@@ -595,3 +634,72 @@ pub fn get_cursor_idx(str: &str, mut x: isize) -> usize {
 	}
 	str.len()
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[derive(Clone, PartialEq)]
+	struct TestCache(String, usize);
+
+	impl Cachelike<()> for TestCache {
+		fn new(text: &Text<(), Self>) -> Self { Self(text.value.clone(), text.cursor) }
+
+		fn revert(self, text: &mut Text<(), Self>) { (text.value, text.cursor) = (self.0, self.1); }
+	}
+
+	fn text(value: &str) -> Text<(), TestCache> { Text::new(value.to_owned(), value.len(), true, ()) }
+
+	#[test]
+	fn test_graphemes() {
+		// "e" with a combining acute accent, a family emoji joined by zero-width joiners and a flag of two regional indicators
+		let value = "e\u{301}👨‍👩‍👧🇯🇵";
+		let family = 3..3 + "👨‍👩‍👧".len();
+		assert_eq!(get_cursor_prev_idx(value.len(), value), family.end);
+		assert_eq!(get_cursor_prev_idx(family.end, value), family.start);
+		assert_eq!(get_cursor_prev_idx(family.start, value), 0);
+		assert_eq!(get_cursor_next_idx(0, value), 3);
+
+		let mut key = text("café\u{301}名前");
+		assert!(key.on_key_press(KeyCode::Backspace, None, flags!()) == GenericAction);
+		assert_eq!(key.value, "café\u{301}名");
+		let _ = key.on_key_press(KeyCode::ArrowLeft, None, flags!());
+		let _ = key.on_key_press(KeyCode::Backspace, None, flags!());
+		assert_eq!((key.value.as_str(), key.cursor), ("caf名", 3));
+
+		let mut value = text("🇯🇵🇯🇵");
+		let _ = value.on_key_press(KeyCode::ArrowLeft, None, flags!());
+		assert_eq!(value.cursor, "🇯🇵".len());
+		let _ = value.on_key_press(KeyCode::Delete, None, flags!());
+		assert_eq!(value.value, "🇯🇵");
+		value.insert_str("日本語");
+		assert_eq!((value.value.as_str(), value.cursor), ("🇯🇵日本語", value.value.len()));
+	}
+
+	#[test]
+	fn test_jump_idx() {
+		let value = "名前 = \"tête\u{301}-à-tête\"";
+		assert_eq!(get_cursor_left_jump_idx(value.len(), value), value.len() - 1);
+		assert_eq!(get_cursor_left_jump_idx("名前 ".len(), value), 0);
+		assert_eq!(get_cursor_right_jump_idx(0, value), "名前".len());
+		let start = value.find('t').expect("It's there");
+		assert_eq!(get_cursor_right_jump_idx(start, value), start + "tête\u{301}".len());
+
+		let mut value = text("😀 abc def");
+		let _ = value.on_key_press(KeyCode::Delete, None, flags!(Ctrl));
+		assert_eq!(value.value, "😀 abc def");
+		value.cursor = "😀".len();
+		let _ = value.on_key_press(KeyCode::Delete, None, flags!(Ctrl));
+		assert_eq!((value.value.as_str(), value.cursor), ("😀 def", "😀".len()));
+	}
+
+	#[test]
+	fn test_cursor_idx() {
+		// each of these is drawn as one character, so a click can't land inside them
+		let value = "a\u{301}👨‍👩‍👧";
+		for x in 0..64 {
+			let idx = get_cursor_idx(value, x);
+			assert!([0, "a\u{301}".len(), value.len()].contains(&idx), "{idx} at {x}");
+		}
+	}
+}
@@ -0,0 +1,176 @@
+use std::ops::{Deref, DerefMut};
+
+use winit::{dpi::PhysicalSize, event::MouseButton, keyboard::KeyCode};
+
+use anyhow::anyhow;
+
+use crate::{
+	action_result::ActionResult,
+	config,
+	render::{
+		assets::{DARK_STRIPE_UV, REPLACE_BOX_SELECTION_Z, REPLACE_BOX_Z},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+		widget::{
+			alert::{Alert, manager::AlertManager},
+			search_box::{SEARCH_BOX_END_X, SEARCH_BOX_START_X},
+			text::{Cachelike, UrlBoxKeyResult, Text, get_cursor_idx},
+		},
+		window::Theme,
+	},
+	util::{StrExt, Vec2u},
+};
+
+/// Takes the URL of a file to open, drawn over the row the replace box uses
+pub struct UrlBox(Text<UrlBoxAdditional, UrlBoxCache>);
+
+impl Deref for UrlBox {
+	type Target = Text<UrlBoxAdditional, UrlBoxCache>;
+
+	fn deref(&self) -> &Self::Target { &self.0 }
+}
+
+impl DerefMut for UrlBox {
+	fn deref_mut(&mut self) -> &mut Self::Target { &mut self.0 }
+}
+
+#[derive(Clone)]
+pub struct UrlBoxAdditional {
+	selected: bool,
+	pub horizontal_scroll: usize,
+}
+
+#[derive(Clone, Eq)]
+pub struct UrlBoxCache {
+	value: String,
+	cursor: usize,
+	selection: Option<usize>,
+}
+
+impl PartialEq for UrlBoxCache {
+	fn eq(&self, other: &Self) -> bool { self.value == other.value }
+}
+
+impl Cachelike<UrlBoxAdditional> for UrlBoxCache {
+	fn new(text: &Text<UrlBoxAdditional, Self>) -> Self
+	where Self: Sized {
+		Self {
+			value: text.value.clone(),
+			cursor: text.cursor,
+			selection: text.selection,
+		}
+	}
+
+	fn revert(self, text: &mut Text<UrlBoxAdditional, Self>)
+	where Self: Sized {
+		let Self { value, cursor, selection } = self;
+
+		text.value = value;
+		text.cursor = cursor;
+		text.selection = selection;
+	}
+}
+
+impl UrlBox {
+	pub const fn uninit() -> Self { Self(Text::uninit()) }
+
+	pub fn new() -> Self { Self(Text::new(String::new(), 0, true, UrlBoxAdditional { selected: false, horizontal_scroll: 0 })) }
+
+	pub fn render(&self, builder: &mut VertexBufferBuilder) {
+		use std::fmt::Write;
+
+		let pos = Vec2u::new(SEARCH_BOX_START_X, 47);
+
+		builder.draw_texture_region_z(pos, REPLACE_BOX_Z, DARK_STRIPE_UV, (builder.window_width() - SEARCH_BOX_END_X - pos.x, 22), (16, 16));
+
+		builder.horizontal_scroll = self.horizontal_scroll;
+
+		if self.value.is_empty() {
+			builder.settings(pos + (0, 3), false, REPLACE_BOX_Z);
+			builder.color = TextColor::Gray.to_raw();
+			let _ = write!(builder, "Open https://...");
+		}
+		let color = match config::get_theme() {
+			Theme::Light => TextColor::Black,
+			Theme::Dark => TextColor::White,
+		};
+		self.0.render(builder, color, pos + (0, 3), REPLACE_BOX_Z, REPLACE_BOX_SELECTION_Z);
+
+		builder.horizontal_scroll = 0;
+	}
+
+	#[must_use]
+	pub fn is_within_bounds(mouse: Vec2u, window_dims: PhysicalSize<u32>) -> bool {
+		let pos = Vec2u::new(SEARCH_BOX_START_X, 47);
+
+		(pos.x..window_dims.width as usize - SEARCH_BOX_END_X - 1).contains(&mouse.x) && (47..71).contains(&mouse.y)
+	}
+
+	pub fn deselect(&mut self) {
+		self.selected = false;
+		self.cursor = 0;
+		self.selection = None;
+	}
+
+	pub fn select(&mut self, x: usize, button: MouseButton) {
+		if button == MouseButton::Right {
+			self.value.clear();
+			self.cursor = 0;
+			self.selection = None;
+			self.horizontal_scroll = 0;
+			self.0.post_input();
+		} else {
+			self.cursor = get_cursor_idx(&self.value, (x + self.horizontal_scroll) as isize);
+			self.selection = None;
+		}
+		self.selected = true;
+	}
+
+	#[must_use]
+	pub fn is_selected(&self) -> bool { self.selected }
+
+	pub fn post_input(&mut self, window_dims: PhysicalSize<u32>) {
+		self.0.post_input();
+		let field_width = window_dims.width as usize - SEARCH_BOX_END_X - SEARCH_BOX_START_X;
+		let precursor_width = self.value.split_at(self.cursor).0.width();
+		// 8px space just to look cleaner
+		let horizontal_scroll = (precursor_width + 8).saturating_sub(field_width);
+		self.horizontal_scroll = horizontal_scroll;
+	}
+
+	/// The URL to open once it's been entered
+	pub fn on_key_press(&mut self, key: KeyCode, ch: Option<char>, flags: u8, alerts: &mut AlertManager, window_dims: PhysicalSize<u32>) -> ActionResult<Option<String>> {
+		if !self.is_selected() {
+			return ActionResult::Pass
+		}
+
+		match UrlBoxKeyResult::from(self.0.on_key_press(key, ch, flags)) {
+			UrlBoxKeyResult::NoAction => ActionResult::Pass,
+			UrlBoxKeyResult::GenericAction => {
+				self.post_input(window_dims);
+				ActionResult::Success(None)
+			}
+			UrlBoxKeyResult::Escape => {
+				self.post_input(window_dims);
+				self.deselect();
+				ActionResult::Success(None)
+			}
+			UrlBoxKeyResult::Open => {
+				self.post_input(window_dims);
+				let url = self.value.trim();
+				if !url.starts_with("http://") && !url.starts_with("https://") {
+					alerts.alert(Alert::error(anyhow!("{url:?} is not an http:// or https:// URL")));
+					return ActionResult::Failure(())
+				}
+				let url = url.to_owned();
+				self.value.clear();
+				self.cursor = 0;
+				self.selection = None;
+				self.horizontal_scroll = 0;
+				self.0.post_input();
+				self.deselect();
+				ActionResult::Success(Some(url))
+			}
+		}
+	}
+}
@@ -0,0 +1,299 @@
+use std::ops::{Deref, DerefMut, Range};
+
+use compact_str::CompactString;
+use winit::{dpi::PhysicalSize, event::MouseButton, keyboard::KeyCode};
+
+use crate::{
+	action_result::ActionResult,
+	config,
+	elements::{
+		compound::NbtCompound,
+		element::NbtElement,
+		region::NbtRegion,
+		values_text::{ValuesTextError, parse_values_text},
+	},
+	render::{
+		assets::{DARK_STRIPE_UV, REPLACE_BOX_SELECTION_Z, REPLACE_BOX_Z, TEXT_UNDERLINE_UV},
+		color::TextColor,
+		vertex_buffer_builder::VertexBufferBuilder,
+		widget::{
+			search_box::{SEARCH_BOX_END_X, SEARCH_BOX_START_X},
+			text::{Cachelike, Text, ValuesBoxKeyResult, get_cursor_idx},
+		},
+		window::Theme,
+	},
+	tree::indices::OwnedIndices,
+	util::{StrExt, Vec2u},
+	workbench::tab::{NbtFileFormat, Tab},
+};
+
+/// Edits the values of an array or a list of numbers as comma-separated text, a chunk's timestamp or position, or the key a list at the root is wrapped under, drawn over the row the replace box uses
+pub struct ValuesBox {
+	text: Text<ValuesBoxAdditional, ValuesBoxCache>,
+	/// The element being edited, only set while it's open
+	indices: Option<OwnedIndices>,
+	kind: ValuesBoxKind,
+	/// The chunk read from a file to add to the region, for [`ValuesBoxKind::ImportChunk`]
+	imported: Option<NbtCompound>,
+	/// The byte range of the first value which isn't valid
+	error: Option<Range<usize>>,
+}
+
+impl Deref for ValuesBox {
+	type Target = Text<ValuesBoxAdditional, ValuesBoxCache>;
+
+	fn deref(&self) -> &Self::Target { &self.text }
+}
+
+impl DerefMut for ValuesBox {
+	fn deref_mut(&mut self) -> &mut Self::Target { &mut self.text }
+}
+
+#[derive(Clone)]
+pub struct ValuesBoxAdditional {
+	pub horizontal_scroll: usize,
+}
+
+#[derive(Clone, Eq)]
+pub struct ValuesBoxCache {
+	value: String,
+	cursor: usize,
+	selection: Option<usize>,
+}
+
+impl PartialEq for ValuesBoxCache {
+	fn eq(&self, other: &Self) -> bool { self.value == other.value }
+}
+
+impl Cachelike<ValuesBoxAdditional> for ValuesBoxCache {
+	fn new(text: &Text<ValuesBoxAdditional, Self>) -> Self
+	where Self: Sized {
+		Self {
+			value: text.value.clone(),
+			cursor: text.cursor,
+			selection: text.selection,
+		}
+	}
+
+	fn revert(self, text: &mut Text<ValuesBoxAdditional, Self>)
+	where Self: Sized {
+		let Self { value, cursor, selection } = self;
+
+		text.value = value;
+		text.cursor = cursor;
+		text.selection = selection;
+	}
+}
+
+/// What a [`ValuesBox`] is open to edit
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum ValuesBoxKind {
+	/// The values of an array or a list of numbers
+	Values,
+	/// When a chunk was last modified
+	Timestamp,
+	/// Where a chunk is moved to in its region
+	MoveChunk,
+	/// Where an empty chunk is added to the region
+	NewChunk,
+	/// Where a chunk read from a file is added to the region
+	ImportChunk,
+	/// The key of the compound a list at the root is wrapped in, before going on with what needed it
+	WrapRoot(WrapRootThen),
+}
+
+/// What was held off on until the list at the root of a tab is wrapped in a compound, see [`Tab::format_warning`]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum WrapRootThen {
+	/// Switching the tab to the format
+	Format(NbtFileFormat),
+	/// Saving it, through a dialog for where to if `force_dialog`
+	Save { force_dialog: bool },
+}
+
+impl ValuesBoxKind {
+	const fn placeholder(self) -> &'static str {
+		match self {
+			Self::Values => "Values, separated by commas...",
+			Self::Timestamp => "Date in UTC, or seconds since 1970...",
+			Self::MoveChunk => "Move to chunk -341, 127...",
+			Self::NewChunk => "New chunk at -341, 127...",
+			Self::ImportChunk => "Import to chunk -341, 127...",
+			Self::WrapRoot(_) => "Wrap the list in a compound under key...",
+		}
+	}
+}
+
+/// What's been entered in a [`ValuesBox`], once it's valid
+pub enum ValuesBoxEdit {
+	/// The element at the indices replaced with another
+	Replace(OwnedIndices, NbtElement),
+	/// The chunk at the indices moved to the position, as it was entered
+	MoveChunk(OwnedIndices, (i32, i32)),
+	/// A chunk added to the region at the position, as it was entered, which is empty unless it was read from a file
+	NewChunk((i32, i32), Option<NbtCompound>),
+	/// The list at the root wrapped in a compound under the key, then going on with what needed it
+	WrapRoot(CompactString, WrapRootThen),
+}
+
+impl ValuesBox {
+	pub const fn uninit() -> Self { Self { text: Text::uninit(), indices: None, kind: ValuesBoxKind::Values, imported: None, error: None } }
+
+	pub fn new() -> Self { Self { text: Text::new(String::new(), 0, true, ValuesBoxAdditional { horizontal_scroll: 0 }), indices: None, kind: ValuesBoxKind::Values, imported: None, error: None } }
+
+	/// Opens it to edit `kind` for the element at `indices`, holding `values` with the cursor at their end
+	pub fn open(&mut self, indices: OwnedIndices, kind: ValuesBoxKind, values: String, window_dims: PhysicalSize<u32>) {
+		self.text = Text::new(values, 0, true, ValuesBoxAdditional { horizontal_scroll: 0 });
+		self.indices = Some(indices);
+		self.kind = kind;
+		self.imported = None;
+		self.error = None;
+		self.cursor = self.value.len();
+		self.post_input(window_dims);
+	}
+
+	/// Opens it on where to add `chunk` to the region at the root
+	pub fn open_import(&mut self, chunk: NbtCompound, window_dims: PhysicalSize<u32>) {
+		self.open(OwnedIndices::new(), ValuesBoxKind::ImportChunk, String::new(), window_dims);
+		self.imported = Some(chunk);
+	}
+
+	/// Closes it without changing the element
+	pub fn close(&mut self) {
+		self.indices = None;
+		self.imported = None;
+		self.error = None;
+		self.value.clear();
+		self.cursor = 0;
+		self.selection = None;
+		self.horizontal_scroll = 0;
+	}
+
+	#[must_use]
+	pub fn is_open(&self) -> bool { self.indices.is_some() }
+
+	pub fn render(&self, builder: &mut VertexBufferBuilder) {
+		use std::fmt::Write;
+
+		let pos = Vec2u::new(SEARCH_BOX_START_X, 47);
+
+		builder.draw_texture_region_z(pos, REPLACE_BOX_Z, DARK_STRIPE_UV, (builder.window_width() - SEARCH_BOX_END_X - pos.x, 22), (16, 16));
+
+		builder.horizontal_scroll = self.horizontal_scroll;
+
+		if self.value.is_empty() {
+			builder.settings(pos + (0, 3), false, REPLACE_BOX_Z);
+			builder.color = TextColor::Gray.to_raw();
+			let _ = write!(builder, "{}", self.kind.placeholder());
+		}
+		let color = match config::get_theme() {
+			Theme::Light => TextColor::Black,
+			Theme::Dark => TextColor::White,
+		};
+		self.text.render(builder, color, pos + (0, 3), REPLACE_BOX_Z, REPLACE_BOX_SELECTION_Z);
+		if let Some(error) = self.error.clone()
+			&& let Some(token) = self.value.get(error.clone())
+		{
+			let x = pos.x + self.value[..error.start].width();
+			builder.draw_texture_region_z((x, pos.y + 17), REPLACE_BOX_SELECTION_Z, TEXT_UNDERLINE_UV, (token.width(), 2), (16, 2));
+		}
+
+		builder.horizontal_scroll = 0;
+	}
+
+	#[must_use]
+	pub fn is_within_bounds(mouse: Vec2u, window_dims: PhysicalSize<u32>) -> bool {
+		let pos = Vec2u::new(SEARCH_BOX_START_X, 47);
+
+		(pos.x..window_dims.width as usize - SEARCH_BOX_END_X - 1).contains(&mouse.x) && (47..71).contains(&mouse.y)
+	}
+
+	pub fn select(&mut self, x: usize, button: MouseButton) {
+		if button == MouseButton::Right {
+			self.value.clear();
+			self.cursor = 0;
+			self.selection = None;
+			self.horizontal_scroll = 0;
+			self.error = None;
+			self.text.post_input();
+		} else {
+			self.cursor = get_cursor_idx(&self.value, (x + self.horizontal_scroll) as isize);
+			self.selection = None;
+		}
+	}
+
+	pub fn post_input(&mut self, window_dims: PhysicalSize<u32>) {
+		// only one line is shown, and newlines separate values just as spaces do
+		if self.value.contains(['\n', '\r']) {
+			self.value = self.value.replace(['\n', '\r'], " ");
+		}
+		self.text.post_input();
+		let field_width = window_dims.width as usize - SEARCH_BOX_END_X - SEARCH_BOX_START_X;
+		let precursor_width = self.value.split_at(self.cursor).0.width();
+		// 8px space just to look cleaner
+		let horizontal_scroll = (precursor_width + 8).saturating_sub(field_width);
+		self.horizontal_scroll = horizontal_scroll;
+	}
+
+	/// What's entered as the edit it's open for, or the range of the first part of it which isn't valid
+	fn parse(&self, tab: &Tab) -> Option<Result<ValuesBoxEdit, Range<usize>>> {
+		let indices = self.indices.clone()?;
+		let pos = || NbtRegion::parse_chunk_pos(&self.value).ok_or(0..self.value.len());
+		Some(match self.kind {
+			ValuesBoxKind::Values | ValuesBoxKind::Timestamp => {
+				let element = tab.root.navigate(&indices).ok()?.element;
+				match parse_values_text(element, &self.value) {
+					Ok(element) => Ok(ValuesBoxEdit::Replace(indices, element)),
+					Err(ValuesTextError::InvalidValue { range, .. }) => Err(range),
+					// it's changed type since
+					Err(ValuesTextError::Unsupported { .. }) => return None,
+				}
+			}
+			ValuesBoxKind::MoveChunk => pos().map(|pos| ValuesBoxEdit::MoveChunk(indices, pos)),
+			// the chunk is only taken out of it once it's applied
+			ValuesBoxKind::NewChunk | ValuesBoxKind::ImportChunk => pos().map(|pos| ValuesBoxEdit::NewChunk(pos, None)),
+			ValuesBoxKind::WrapRoot(then) => Ok(ValuesBoxEdit::WrapRoot(CompactString::from(self.value.as_str()), then)),
+		})
+	}
+
+	/// What's been entered, once it's entered and valid
+	pub fn on_key_press(&mut self, key: KeyCode, ch: Option<char>, flags: u8, tab: &Tab, window_dims: PhysicalSize<u32>) -> ActionResult<Option<ValuesBoxEdit>> {
+		if !self.is_open() {
+			return ActionResult::Pass
+		}
+
+		match ValuesBoxKeyResult::from(self.text.on_key_press(key, ch, flags)) {
+			ValuesBoxKeyResult::NoAction => ActionResult::Pass,
+			ValuesBoxKeyResult::GenericAction => {
+				self.post_input(window_dims);
+				self.error = self.parse(tab).and_then(Result::err);
+				ActionResult::Success(None)
+			}
+			ValuesBoxKeyResult::Escape => {
+				self.close();
+				ActionResult::Success(None)
+			}
+			ValuesBoxKeyResult::Apply => {
+				self.post_input(window_dims);
+				match self.parse(tab) {
+					Some(Ok(mut edit)) => {
+						if let ValuesBoxEdit::NewChunk(_, chunk) = &mut edit {
+							*chunk = self.imported.take();
+						}
+						self.close();
+						ActionResult::Success(Some(edit))
+					}
+					Some(Err(range)) => {
+						self.error = Some(range);
+						ActionResult::Failure(())
+					}
+					// it's gone or changed type since
+					_ => {
+						self.close();
+						ActionResult::Failure(())
+					}
+				}
+			}
+		}
+	}
+}
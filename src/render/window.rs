@@ -58,7 +58,8 @@ pub async fn run() -> ! {
 
 			#[cfg(target_arch = "wasm32")]
 			crate::wasm::on_input();
-			if self.workbench.should_ignore_event() {
+			// files dropped while a dialog was open still arrive once it's closed
+			if self.workbench.should_ignore_event() && !matches!(event, WindowEvent::DroppedFile(_) | WindowEvent::HoveredFile(_) | WindowEvent::HoveredFileCancelled) {
 				return;
 			}
 			if State::input(event.clone(), self.workbench) == ActionResult::Pass {
@@ -121,6 +122,8 @@ pub async fn run() -> ! {
 		builder = builder.with_drag_and_drop(true);
 	}
 	let window = Arc::new(event_loop.create_window(builder).expect("Unable to construct window"));
+	// so composed text such as Japanese arrives as `Ime` events
+	window.set_ime_allowed(true);
 	#[cfg(target_arch = "wasm32")]
 	let window_size = {
 		web_sys::window()
@@ -552,11 +555,22 @@ impl<'window> State<'window> {
 				}
 				ActionResult::Success(())
 			}
-			WindowEvent::DroppedFile(file) if let Some(data) = std::fs::read(&file).alert_err(&mut workbench.alerts) => {
-				workbench.on_open_file(&file, data).alert_err(&mut workbench.alerts);
+			WindowEvent::HoveredFile(file) => {
+				workbench.hovered_files.push(file);
+				ActionResult::Success(())
+			}
+			WindowEvent::HoveredFileCancelled => {
+				workbench.hovered_files.clear();
+				ActionResult::Success(())
+			}
+			// one event per file, so several dropped at once each get a tab
+			WindowEvent::DroppedFile(file) => {
+				workbench.hovered_files.clear();
+				workbench.open_path_in_background(file);
 				ActionResult::Success(())
 			}
 			WindowEvent::KeyboardInput { event, .. } => workbench.on_key_input(event),
+			WindowEvent::Ime(ime) => workbench.on_ime(ime),
 			WindowEvent::CursorMoved { position, .. } => workbench.on_mouse_move(position),
 			WindowEvent::CursorLeft { .. } => workbench.on_mouse_move(PhysicalPosition::new(0.0, 0.0)),
 			WindowEvent::MouseWheel { delta, .. } => workbench.on_scroll(delta),
@@ -794,4 +808,13 @@ impl WindowProperties {
 		}
 		self
 	}
+
+	/// Brings the window to the front, like when another launch forwards its files to it
+	pub fn focus(&self) -> &Self {
+		if let Self::Real(window) = self {
+			window.set_minimized(false);
+			window.focus_window();
+		}
+		self
+	}
 }
@@ -4,7 +4,13 @@ use compact_str::CompactString;
 
 use crate::{
 	config,
-	elements::{compound::CompoundMap, result::NbtParseResult},
+	elements::{
+		NbtElementVariant,
+		compound::{CompoundMap, NbtCompound},
+		list::NbtList,
+		result::NbtParseResult,
+	},
+	serialization::mutf8,
 };
 
 pub trait Decoder<'a>: Send {
@@ -123,17 +129,47 @@ impl<'a> Decoder<'a> for BigEndianDecoder<'a> {
 		let len = self.u16() as usize;
 		self.assert_len(len)?;
 
-		let out = CompactString::from_utf8_lossy(core::slice::from_raw_parts(self.data, len));
+		// Java writes modified UTF-8
+		let out = mutf8::decode(core::slice::from_raw_parts(self.data, len));
 		self.data = self.data.add(len);
 		ok(out)
 	}
 }
 
+/// The 8 bytes before the NBT of a Bedrock `level.dat`, the version of the world's storage and the length of the rest of the file
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BedrockHeader {
+	pub version: u32,
+	pub len: u32,
+}
+
+impl BedrockHeader {
+	pub const LEN: usize = 8;
+	/// Written for files which didn't have a header to keep the version of
+	pub const DEFAULT_VERSION: u32 = 8;
+
+	/// The header `data` starts with, if the NBT after it starts with a compound or list and its version is small enough to be one
+	///
+	/// NBT without a header starts with its root's type and name, which never reads as a version this small unless it's an empty compound
+	#[must_use]
+	pub fn of(data: &[u8]) -> Option<Self> {
+		let (header, rest) = data.split_first_chunk::<{ Self::LEN }>()?;
+		let (version, len) = header.split_at(4);
+		let version = u32::from_le_bytes(version.try_into().ok()?);
+		let len = u32::from_le_bytes(len.try_into().ok()?);
+		(u16::try_from(version).is_ok() && matches!(rest.first(), Some(&(NbtCompound::ID | NbtList::ID)))).then_some(Self { version, len })
+	}
+
+	/// Whether the length it declares is more than a tenth off from the `payload_len` actually after it, so the file is damaged or was never a `level.dat`
+	#[must_use]
+	pub fn is_wildly_off(self, payload_len: usize) -> bool { (self.len as usize).abs_diff(payload_len) > payload_len / 10 }
+}
+
 pub struct LittleEndianDecoder<'a> {
 	data: *const u8,
 	end: *const u8,
 	_marker: PhantomData<&'a ()>,
-	has_header: bool,
+	header: Option<BedrockHeader>,
 }
 
 unsafe impl Send for LittleEndianDecoder<'_> {}
@@ -143,27 +179,37 @@ impl<'a> LittleEndianDecoder<'a> {
 	fn remaining_len(&self) -> usize { self.end as usize - self.data as usize }
 
 	#[must_use]
-	pub fn has_header(&self) -> bool { self.has_header }
-}
+	pub fn has_header(&self) -> bool { self.header.is_some() }
 
-#[allow(improper_ctypes_definitions)]
-impl<'a> Decoder<'a> for LittleEndianDecoder<'a> {
-	fn new(data: &'a [u8]) -> Self {
-		use crate::elements::result::*;
+	#[must_use]
+	pub fn header(&self) -> Option<BedrockHeader> { self.header }
 
+	/// Reads `data` as starting with a [`BedrockHeader`] however far off its length is, for loading a damaged `level.dat` anyway
+	#[must_use]
+	pub fn with_header(data: &'a [u8]) -> Self {
 		let mut this = Self {
 			end: unsafe { data.as_ptr().add(data.len()) },
 			data: data.as_ptr(),
 			_marker: PhantomData,
-			has_header: false,
+			header: BedrockHeader::of(data),
 		};
-		unsafe {
-			if is_ok(&this.assert_len(8)) && this.data.add(4).cast::<u32>().read_unaligned() as usize == this.remaining_len() - 8 {
-				// what the hell is this version for
-				let _version = this.u32();
-				let _remaining_length = this.u32() as usize;
-				this.has_header = true;
-			}
+		if this.header.is_some() {
+			this.data = unsafe { this.data.add(BedrockHeader::LEN) };
+		}
+		this
+	}
+}
+
+#[allow(improper_ctypes_definitions)]
+impl<'a> Decoder<'a> for LittleEndianDecoder<'a> {
+	fn new(data: &'a [u8]) -> Self {
+		let mut this = Self::with_header(data);
+		// a length this far off means it only looked like a header
+		if let Some(header) = this.header
+			&& header.is_wildly_off(this.remaining_len())
+		{
+			this.data = data.as_ptr();
+			this.header = None;
 		}
 		this
 	}
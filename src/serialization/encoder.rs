@@ -1,29 +1,36 @@
 use std::{
 	alloc::{Layout, alloc, dealloc, realloc},
 	hint::likely,
-	io::Write,
+	io::{self, Write},
 	mem::MaybeUninit,
 };
 
-pub struct UncheckedBufWriter {
+use crate::serialization::mutf8;
+
+pub struct UncheckedBufWriter<'a> {
 	buf: *mut MaybeUninit<u8>,
 	buf_len: usize,
 	inner: *mut u8,
 	inner_len: usize,
+	/// When set, full buffers are written here instead of being accumulated in `inner`
+	sink: Option<&'a mut dyn Write>,
+	sink_error: Option<io::Error>,
 }
 
-impl Default for UncheckedBufWriter {
+impl Default for UncheckedBufWriter<'_> {
 	fn default() -> Self {
 		Self {
 			buf: unsafe { alloc(Layout::array::<u8>(Self::BUFFER_WIDTH).unwrap_unchecked()).cast::<MaybeUninit<u8>>() },
 			buf_len: 0,
 			inner: core::ptr::null_mut(),
 			inner_len: 0,
+			sink: None,
+			sink_error: None,
 		}
 	}
 }
 
-impl Drop for UncheckedBufWriter {
+impl Drop for UncheckedBufWriter<'_> {
 	fn drop(&mut self) {
 		unsafe {
 			dealloc(self.buf.cast::<u8>(), Layout::array::<u8>(Self::BUFFER_WIDTH).unwrap_unchecked());
@@ -31,7 +38,7 @@ impl Drop for UncheckedBufWriter {
 	}
 }
 
-impl Write for UncheckedBufWriter {
+impl Write for UncheckedBufWriter<'_> {
 	fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
 		UncheckedBufWriter::write(self, buf);
 		Ok(buf.len())
@@ -48,11 +55,18 @@ impl Write for UncheckedBufWriter {
 	}
 }
 
-impl UncheckedBufWriter {
+impl<'a> UncheckedBufWriter<'a> {
 	const BUFFER_WIDTH: usize = 1 << 24;
 
 	pub fn new() -> Self { Self::default() }
 
+	/// Streams everything written into `sink` rather than keeping it in memory, finish with [`Self::finish_sink`]
+	pub fn with_sink(sink: &'a mut dyn Write) -> Self {
+		let mut writer = Self::default();
+		writer.sink = Some(sink);
+		writer
+	}
+
 	pub const fn remaining(&self) -> usize { Self::BUFFER_WIDTH - 1 - self.buf_len }
 
 	pub fn write(&mut self, bytes: &[u8]) {
@@ -60,6 +74,8 @@ impl UncheckedBufWriter {
 			if likely(bytes.len() < self.remaining()) {
 				self.buf.add(self.buf_len).cast::<u8>().copy_from_nonoverlapping(bytes.as_ptr(), bytes.len());
 				self.buf_len += bytes.len();
+			} else if self.sink.is_some() {
+				self.write_to_sink_cold(bytes);
 			} else {
 				self.write_pushing_cold(bytes);
 			}
@@ -71,15 +87,19 @@ impl UncheckedBufWriter {
 			if likely(count < self.remaining()) {
 				self.buf.add(self.buf_len).cast::<u8>().write_bytes(byte, count);
 				self.buf_len += count;
+			} else if self.sink.is_some() {
+				self.write_bytes_to_sink_cold(byte, count);
 			} else {
 				self.write_bytes_pushing_cold(byte, count);
 			}
 		}
 	}
 
+	/// In the modified UTF-8 Java reads, little-endian strings are plain UTF-8 since they're from Bedrock
 	pub fn write_be_str(&mut self, str: &str) {
-		self.write(&(str.len() as u16).to_be_bytes());
-		self.write(str.as_bytes());
+		let bytes = mutf8::encode(str);
+		self.write(&(bytes.len() as u16).to_be_bytes());
+		self.write(&bytes);
 	}
 
 	pub fn write_le_str(&mut self, str: &str) {
@@ -121,11 +141,53 @@ impl UncheckedBufWriter {
 		self.buf_len = 0;
 	}
 
+	/// Writes out the buffer to the sink, the first error is kept for [`Self::finish_sink`] and everything after it is discarded
+	fn drain_to_sink(&mut self) {
+		if let Some(sink) = self.sink.as_deref_mut()
+			&& self.sink_error.is_none()
+			&& let Err(e) = sink.write_all(unsafe { core::slice::from_raw_parts(self.buf.cast::<u8>(), self.buf_len) })
+		{
+			self.sink_error = Some(e);
+		}
+		self.buf_len = 0;
+	}
+
+	#[cold]
+	#[inline(never)]
+	fn write_to_sink_cold(&mut self, bytes: &[u8]) {
+		self.drain_to_sink();
+		if bytes.len() < self.remaining() {
+			self.write(bytes);
+		} else if let Some(sink) = self.sink.as_deref_mut()
+			&& self.sink_error.is_none()
+			&& let Err(e) = sink.write_all(bytes)
+		{
+			self.sink_error = Some(e);
+		}
+	}
+
+	#[cold]
+	#[inline(never)]
+	fn write_bytes_to_sink_cold(&mut self, byte: u8, mut count: usize) {
+		while count > 0 {
+			self.drain_to_sink();
+			let len = count.min(self.remaining());
+			unsafe { self.buf.add(self.buf_len).cast::<u8>().write_bytes(byte, len) };
+			self.buf_len += len;
+			count -= len;
+		}
+	}
+
 	pub fn flush(&mut self) {
 		if self.buf_len == 0 {
 			return
 		}
 
+		if self.sink.is_some() {
+			self.drain_to_sink();
+			return
+		}
+
 		unsafe {
 			let malloc_size = (self.inner_len + Self::BUFFER_WIDTH - 1) & !(Self::BUFFER_WIDTH - 1);
 			self.inner = if self.inner.is_null() {
@@ -138,6 +200,16 @@ impl UncheckedBufWriter {
 		}
 	}
 
+	/// # Errors
+	/// * The first error the sink returned while writing
+	pub fn finish_sink(mut self) -> io::Result<()> {
+		self.drain_to_sink();
+		if let Some(e) = self.sink_error.take() {
+			return Err(e)
+		}
+		self.sink.as_deref_mut().map_or(Ok(()), Write::flush)
+	}
+
 	#[must_use]
 	pub fn finish(mut self) -> Vec<u8> {
 		self.flush();
@@ -1,18 +1,25 @@
-use crate::serialization::encoder::UncheckedBufWriter;
+use std::fmt::Write;
 
-pub struct PrettyFormatter {
-	buf: UncheckedBufWriter,
+use crate::{elements::element::NbtElement, serialization::encoder::UncheckedBufWriter};
+
+pub struct PrettyFormatter<'a> {
+	buf: UncheckedBufWriter<'static>,
 	current_depth: usize,
+	indent: &'a str,
 }
 
-impl PrettyFormatter {
-	const INDENT: u8 = b' ';
-	const INDENT_QUANTITY: usize = 4;
+impl<'a> PrettyFormatter<'a> {
+	pub const DEFAULT_INDENT: &'static str = "    ";
+	/// Lists of primitives up to this wide are kept on one line
+	pub const INLINE_WIDTH: usize = 80;
+
+	pub fn new() -> Self { Self::with_indent(Self::DEFAULT_INDENT) }
 
-	pub fn new() -> Self {
+	pub fn with_indent(indent: &'a str) -> Self {
 		Self {
 			buf: UncheckedBufWriter::new(),
 			current_depth: 0,
+			indent,
 		}
 	}
 
@@ -20,7 +27,34 @@ impl PrettyFormatter {
 
 	pub fn increase(&mut self) { self.current_depth += 1; }
 
-	pub fn indent(&mut self) { self.buf.write_bytes(Self::INDENT, self.current_depth * Self::INDENT_QUANTITY); }
+	pub fn indent(&mut self) {
+		for _ in 0..self.current_depth {
+			self.buf.write(self.indent.as_bytes());
+		}
+	}
+
+	/// Writes `[<prefix>a, b, c]` on a single line if every value is a primitive and it fits within [`Self::INLINE_WIDTH`]
+	pub fn try_write_inline<'b>(&mut self, prefix: &str, mut values: impl Iterator<Item = &'b NbtElement> + Clone) -> bool {
+		if !values.clone().all(NbtElement::is_primitive) {
+			return false
+		}
+		let mut line = format!("[{prefix}");
+		if let Some(first) = values.next() {
+			let _ = write!(line, "{first}");
+		}
+		for value in values {
+			let _ = write!(line, ", {value}");
+			if line.len() + 1 > Self::INLINE_WIDTH {
+				return false
+			}
+		}
+		line.push(']');
+		if line.len() > Self::INLINE_WIDTH {
+			return false
+		}
+		self.write_str(&line);
+		true
+	}
 
 	pub fn decrease(&mut self) { self.current_depth = self.current_depth.saturating_sub(1); }
 
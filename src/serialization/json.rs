@@ -0,0 +1,612 @@
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::elements::{
+	ComplexNbtElementVariant,
+	array::{NbtByteArray, NbtIntArray, NbtLongArray},
+	byte::NbtByte,
+	chunk::NbtChunk,
+	compound::{CompoundEntry, NbtCompound},
+	double::NbtDouble,
+	element::{NbtElement, NbtPattern},
+	float::NbtFloat,
+	int::NbtInt,
+	list::NbtList,
+	long::NbtLong,
+	short::NbtShort,
+	string::NbtString,
+};
+
+/// How numbers are written, since JSON has no way to tell a byte from a long
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JsonNumberPolicy {
+	/// Plain JSON numbers, the NBT type is lost
+	#[default]
+	Plain,
+	/// Wrapped as `{"type":"long","value":1}` so the NBT type is kept
+	Typed,
+}
+
+#[must_use]
+pub fn to_json(element: &NbtElement, policy: JsonNumberPolicy) -> String {
+	let mut buf = String::new();
+	// writing to a `String` can't fail
+	let _ = write_element(&mut buf, element, policy);
+	buf
+}
+
+/// Warns that `element` has NaNs or infinities in it, which [`to_json`] writes as strings
+#[must_use]
+pub fn non_finite_warning(element: &NbtElement) -> Option<anyhow::Error> {
+	let count = count_non_finite(element);
+	(count > 0).then(|| anyhow::anyhow!("{count} {} written as strings, since JSON has no numbers for NaN or infinity", if count == 1 { "number was" } else { "numbers were" }))
+}
+
+fn count_non_finite(element: &NbtElement) -> usize {
+	use NbtPattern as Nbt;
+
+	match element.as_pattern() {
+		Nbt::Float(x) => usize::from(!x.value.is_finite()),
+		Nbt::Double(x) => usize::from(!x.value.is_finite()),
+		Nbt::List(_) => element.children().and_then(Result::ok).into_iter().flatten().map(count_non_finite).sum(),
+		Nbt::Compound(_) => element.children().and_then(Result::err).into_iter().flatten().map(|entry| count_non_finite(&entry.value)).sum(),
+		Nbt::Chunk(chunk) => chunk.decoded().map_or(0, |compound| compound.children().map(|entry| count_non_finite(&entry.value)).sum()),
+		Nbt::Region(region) => region.chunks.iter().map(count_non_finite).sum(),
+		_ => 0,
+	}
+}
+
+fn write_element(buf: &mut String, element: &NbtElement, policy: JsonNumberPolicy) -> std::fmt::Result {
+	use NbtPattern as Nbt;
+
+	match element.as_pattern() {
+		Nbt::Byte(x) => write_number(buf, "byte", x.value, policy),
+		Nbt::Short(x) => write_number(buf, "short", x.value, policy),
+		Nbt::Int(x) => write_number(buf, "int", x.value, policy),
+		Nbt::Long(x) => write_number(buf, "long", x.value, policy),
+		Nbt::Float(x) => write_float(buf, "float", f64::from(x.value), x.value, policy),
+		Nbt::Double(x) => write_float(buf, "double", x.value, x.value, policy),
+		Nbt::ByteArray(_) => write_array(buf, "byte_array", element, policy),
+		Nbt::IntArray(_) => write_array(buf, "int_array", element, policy),
+		Nbt::LongArray(_) => write_array(buf, "long_array", element, policy),
+		Nbt::String(x) => write_string(buf, x.str.as_str()),
+		Nbt::List(_) => write_list(buf, element.children().and_then(Result::ok).into_iter().flatten(), policy),
+		Nbt::Compound(_) => write_object(buf, element.children().and_then(Result::err).into_iter().flatten(), policy),
+		// there's nothing to write of a chunk which can't be read
+		Nbt::Chunk(chunk) => write_object(buf, chunk.decoded().unwrap_or_default().children(), policy),
+		Nbt::Region(region) => {
+			buf.write_char('{')?;
+			for (idx, chunk) in region.chunks.iter().filter(|chunk| chunk.as_chunk().is_some_and(NbtChunk::is_loaded)).enumerate() {
+				if idx > 0 {
+					buf.write_char(',')?;
+				}
+				let NbtPattern::Chunk(NbtChunk { x, z, .. }) = chunk.as_pattern() else { continue };
+				write!(buf, "\"{x}, {z}\":")?;
+				write_element(buf, chunk, policy)?;
+			}
+			buf.write_char('}')
+		}
+	}
+}
+
+fn write_number(buf: &mut String, ty: &str, value: impl std::fmt::Display, policy: JsonNumberPolicy) -> std::fmt::Result {
+	match policy {
+		JsonNumberPolicy::Plain => write!(buf, "{value}"),
+		JsonNumberPolicy::Typed => write!(buf, "{{\"type\":\"{ty}\",\"value\":{value}}}"),
+	}
+}
+
+/// JSON has no NaN or infinities, so those are written as strings
+fn write_float(buf: &mut String, ty: &str, value: f64, display: impl std::fmt::Display, policy: JsonNumberPolicy) -> std::fmt::Result {
+	if value.is_finite() {
+		write_number(buf, ty, display, policy)
+	} else {
+		let value = if value.is_nan() {
+			"\"NaN\""
+		} else if value.is_sign_positive() {
+			"\"Infinity\""
+		} else {
+			"\"-Infinity\""
+		};
+		write_number(buf, ty, value, policy)
+	}
+}
+
+fn write_array(buf: &mut String, ty: &str, element: &NbtElement, policy: JsonNumberPolicy) -> std::fmt::Result {
+	if policy == JsonNumberPolicy::Typed {
+		write!(buf, "{{\"type\":\"{ty}\",\"value\":")?;
+	}
+	// the type of the values is already known from the array
+	write_list(buf, element.children().and_then(Result::ok).into_iter().flatten(), JsonNumberPolicy::Plain)?;
+	if policy == JsonNumberPolicy::Typed {
+		buf.write_char('}')?;
+	}
+	Ok(())
+}
+
+fn write_list<'a>(buf: &mut String, values: impl Iterator<Item = &'a NbtElement>, policy: JsonNumberPolicy) -> std::fmt::Result {
+	buf.write_char('[')?;
+	for (idx, value) in values.enumerate() {
+		if idx > 0 {
+			buf.write_char(',')?;
+		}
+		write_element(buf, value, policy)?;
+	}
+	buf.write_char(']')
+}
+
+fn write_object<'a>(buf: &mut String, entries: impl Iterator<Item = &'a CompoundEntry>, policy: JsonNumberPolicy) -> std::fmt::Result {
+	buf.write_char('{')?;
+	for (idx, CompoundEntry { key, value }) in entries.enumerate() {
+		if idx > 0 {
+			buf.write_char(',')?;
+		}
+		write_string(buf, key)?;
+		buf.write_char(':')?;
+		write_element(buf, value, policy)?;
+	}
+	buf.write_char('}')
+}
+
+/// Escapes everything JSON requires, unpaired surrogates can't show up here since they're already replaced while decoding the string
+fn write_string(buf: &mut String, str: &str) -> std::fmt::Result {
+	buf.write_char('"')?;
+	for char in str.chars() {
+		match char {
+			'"' => buf.write_str("\\\"")?,
+			'\\' => buf.write_str("\\\\")?,
+			'\n' => buf.write_str("\\n")?,
+			'\r' => buf.write_str("\\r")?,
+			'\t' => buf.write_str("\\t")?,
+			'\u{8}' => buf.write_str("\\b")?,
+			'\u{c}' => buf.write_str("\\f")?,
+			char if char.is_control() => write!(buf, "\\u{:04x}", char as u32)?,
+			char => buf.write_char(char)?,
+		}
+	}
+	buf.write_char('"')
+}
+
+/// How JSON values are turned into NBT
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct JsonParseOptions {
+	/// Use the narrowest type a number fits in, otherwise integers are ints (or longs if they don't fit) and decimals are doubles
+	pub narrowest_numbers: bool,
+	/// Turn arrays of only integers into an int array (or long array if any don't fit)
+	pub numeric_arrays: bool,
+}
+
+impl Default for JsonParseOptions {
+	fn default() -> Self {
+		Self {
+			narrowest_numbers: true,
+			numeric_arrays: true,
+		}
+	}
+}
+
+enum JsonValue {
+	Null,
+	Bool(bool),
+	Integer(i64),
+	Decimal(f64),
+	String(String),
+	Array(Vec<JsonValue>),
+	Object(Vec<(String, JsonValue)>),
+}
+
+/// Parses JSON into NBT, objects written with [`JsonNumberPolicy::Typed`] are read back with their original type.
+///
+/// # Errors
+/// The index the JSON is invalid at, counted from the end like `NbtElement::from_str`
+pub fn from_json(s: &str, options: JsonParseOptions) -> Result<NbtElement, usize> {
+	let (rest, value) = read_value(s.trim_start())?;
+	if !rest.trim_start().is_empty() {
+		return Err(rest.len())
+	}
+	to_nbt(value, options).ok_or(s.len())
+}
+
+fn read_value(s: &str) -> Result<(&str, JsonValue), usize> {
+	if let Some(s) = s.strip_prefix("null") {
+		Ok((s, JsonValue::Null))
+	} else if let Some(s) = s.strip_prefix("true") {
+		Ok((s, JsonValue::Bool(true)))
+	} else if let Some(s) = s.strip_prefix("false") {
+		Ok((s, JsonValue::Bool(false)))
+	} else if s.starts_with('"') {
+		read_string(s).map(|(s, str)| (s, JsonValue::String(str)))
+	} else if let Some(mut s) = s.strip_prefix('[') {
+		let mut values = vec![];
+		s = s.trim_start();
+		if let Some(s) = s.strip_prefix(']') {
+			return Ok((s, JsonValue::Array(values)))
+		}
+		loop {
+			let (s2, value) = read_value(s)?;
+			values.push(value);
+			s = s2.trim_start();
+			if let Some(s2) = s.strip_prefix(',') {
+				s = s2.trim_start();
+			} else {
+				return s.strip_prefix(']').map(|s| (s, JsonValue::Array(values))).ok_or(s.len())
+			}
+		}
+	} else if let Some(mut s) = s.strip_prefix('{') {
+		let mut entries = vec![];
+		s = s.trim_start();
+		if let Some(s) = s.strip_prefix('}') {
+			return Ok((s, JsonValue::Object(entries)))
+		}
+		loop {
+			let (s2, key) = read_string(s)?;
+			s = s2.trim_start().strip_prefix(':').ok_or(s2.len())?.trim_start();
+			let (s2, value) = read_value(s)?;
+			entries.push((key, value));
+			s = s2.trim_start();
+			if let Some(s2) = s.strip_prefix(',') {
+				s = s2.trim_start();
+			} else {
+				return s.strip_prefix('}').map(|s| (s, JsonValue::Object(entries))).ok_or(s.len())
+			}
+		}
+	} else {
+		read_number(s)
+	}
+}
+
+fn read_number(s: &str) -> Result<(&str, JsonValue), usize> {
+	let bytes = s.as_bytes();
+	let mut idx = usize::from(bytes.first() == Some(&b'-'));
+	let digits = |idx: usize| bytes[idx..].iter().take_while(|b| b.is_ascii_digit()).count();
+	let int_len = digits(idx);
+	if int_len == 0 {
+		return Err(s.len())
+	}
+	idx += int_len;
+	let mut integer = true;
+	if bytes.get(idx) == Some(&b'.') {
+		integer = false;
+		let frac_len = digits(idx + 1);
+		if frac_len == 0 {
+			return Err(s.len() - idx)
+		}
+		idx += 1 + frac_len;
+	}
+	if let Some(b'e' | b'E') = bytes.get(idx) {
+		integer = false;
+		idx += 1;
+		if let Some(b'+' | b'-') = bytes.get(idx) {
+			idx += 1;
+		}
+		let exponent_len = digits(idx);
+		if exponent_len == 0 {
+			return Err(s.len() - idx)
+		}
+		idx += exponent_len;
+	}
+	let (number, rest) = s.split_at(idx);
+	// integers too large for a long are kept as a double, same as javascript would
+	let value = match number.parse::<i64>() {
+		Ok(value) if integer => JsonValue::Integer(value),
+		_ => JsonValue::Decimal(number.parse::<f64>().map_err(|_| s.len())?),
+	};
+	Ok((rest, value))
+}
+
+fn read_string(s: &str) -> Result<(&str, String), usize> {
+	let mut chars = s.strip_prefix('"').ok_or(s.len())?.char_indices();
+	let mut str = String::new();
+	let rest = |chars: &std::str::CharIndices| chars.as_str().len();
+	while let Some((_, char)) = chars.next() {
+		match char {
+			'"' => return Ok((chars.as_str(), str)),
+			'\\' => {
+				let escaped = match chars.next().ok_or(0_usize)?.1 {
+					'"' => '"',
+					'\\' => '\\',
+					'/' => '/',
+					'b' => '\u{8}',
+					'f' => '\u{c}',
+					'n' => '\n',
+					'r' => '\r',
+					't' => '\t',
+					'u' => {
+						let read_unit = |chars: &mut std::str::CharIndices| -> Result<u16, usize> {
+							let hex = chars.as_str().get(..4).ok_or(rest(chars))?;
+							let unit = u16::from_str_radix(hex, 16).map_err(|_| rest(chars))?;
+							chars.nth(3);
+							Ok(unit)
+						};
+						let unit = read_unit(&mut chars)?;
+						let mut units = vec![unit];
+						// surrogate pairs are written as two escapes
+						if (0xD800..0xDC00).contains(&unit) && chars.as_str().starts_with("\\u") {
+							chars.nth(1);
+							units.push(read_unit(&mut chars)?);
+						}
+						// unpaired surrogates have no `char`, so they're replaced rather than failing the whole parse
+						for char in char::decode_utf16(units) {
+							str.push(char.unwrap_or(char::REPLACEMENT_CHARACTER));
+						}
+						continue;
+					}
+					_ => return Err(rest(&chars)),
+				};
+				str.push(escaped);
+			}
+			char if char.is_control() => return Err(rest(&chars)),
+			char => str.push(char),
+		}
+	}
+	Err(0)
+}
+
+/// Whether `s` is a JSON object or array, like the text components of item names and signs
+#[must_use]
+pub fn is_json_text(s: &str) -> bool { s.trim_start().starts_with(['{', '[']) && minify_json(s).is_ok() }
+
+/// Rewrites JSON with every value on its own line, indented by two spaces, keeping strings and numbers exactly as they're written
+///
+/// # Errors
+/// The index the JSON is invalid at, counted from the end like [`from_json`]
+pub fn format_json(s: &str) -> Result<String, usize> { reformat_json(s, true) }
+
+/// Rewrites JSON without any whitespace between values, keeping strings and numbers exactly as they're written
+///
+/// # Errors
+/// The index the JSON is invalid at, counted from the end like [`from_json`]
+pub fn minify_json(s: &str) -> Result<String, usize> { reformat_json(s, false) }
+
+fn reformat_json(s: &str, pretty: bool) -> Result<String, usize> {
+	let mut buf = String::with_capacity(s.len());
+	let rest = write_reformatted(&mut buf, s.trim_start(), pretty, 0)?;
+	if !rest.trim_start().is_empty() {
+		return Err(rest.trim_start().len())
+	}
+	Ok(buf)
+}
+
+fn write_reformatted<'a>(buf: &mut String, s: &'a str, pretty: bool, depth: usize) -> Result<&'a str, usize> {
+	let newline = |buf: &mut String, depth: usize| {
+		if pretty {
+			buf.push('\n');
+			for _ in 0..depth {
+				buf.push_str("  ");
+			}
+		}
+	};
+	let (open, close) = match s.as_bytes().first() {
+		Some(b'[') => ('[', ']'),
+		Some(b'{') => ('{', '}'),
+		// only read to be sure it's valid, what's written is the original text so escapes and exponents stay as they are
+		_ => {
+			let (rest, _) = read_value(s)?;
+			buf.push_str(&s[..s.len() - rest.len()]);
+			return Ok(rest)
+		}
+	};
+	buf.push(open);
+	let mut s = s[1..].trim_start();
+	if let Some(rest) = s.strip_prefix(close) {
+		buf.push(close);
+		return Ok(rest)
+	}
+	loop {
+		newline(buf, depth + 1);
+		if open == '{' {
+			let (rest, _) = read_string(s)?;
+			buf.push_str(&s[..s.len() - rest.len()]);
+			let rest = rest.trim_start();
+			s = rest.strip_prefix(':').ok_or(rest.len())?.trim_start();
+			buf.push_str(if pretty { ": " } else { ":" });
+		}
+		s = write_reformatted(buf, s, pretty, depth + 1)?.trim_start();
+		if let Some(rest) = s.strip_prefix(',') {
+			buf.push(',');
+			s = rest.trim_start();
+		} else {
+			let rest = s.strip_prefix(close).ok_or(s.len())?;
+			newline(buf, depth);
+			buf.push(close);
+			return Ok(rest)
+		}
+	}
+}
+
+#[allow(clippy::float_cmp)]
+fn to_nbt(value: JsonValue, options: JsonParseOptions) -> Option<NbtElement> {
+	Some(match value {
+		JsonValue::Null => return None,
+		JsonValue::Bool(value) => NbtElement::Byte(NbtByte { value: i8::from(value) }),
+		JsonValue::Integer(value) =>
+			if options.narrowest_numbers {
+				if let Ok(value) = i8::try_from(value) {
+					NbtElement::Byte(NbtByte { value })
+				} else if let Ok(value) = i16::try_from(value) {
+					NbtElement::Short(NbtShort { value })
+				} else if let Ok(value) = i32::try_from(value) {
+					NbtElement::Int(NbtInt { value })
+				} else {
+					NbtElement::Long(NbtLong { value })
+				}
+			} else if let Ok(value) = i32::try_from(value) {
+				NbtElement::Int(NbtInt { value })
+			} else {
+				NbtElement::Long(NbtLong { value })
+			},
+		JsonValue::Decimal(value) => {
+			#[allow(clippy::cast_possible_truncation)]
+			let float = value as f32;
+			if options.narrowest_numbers && f64::from(float) == value {
+				NbtElement::Float(NbtFloat { value: float })
+			} else {
+				NbtElement::Double(NbtDouble { value })
+			}
+		}
+		JsonValue::String(str) => NbtElement::String(NbtString { str: str.into() }),
+		JsonValue::Array(values) => {
+			if options.numeric_arrays && !values.is_empty() && values.iter().all(|value| matches!(value, JsonValue::Integer(_))) {
+				let values = values.into_iter().filter_map(|value| if let JsonValue::Integer(value) = value { Some(value) } else { None }).collect::<Vec<_>>();
+				let mut array = if values.iter().all(|&value| i32::try_from(value).is_ok()) {
+					NbtElement::IntArray(NbtIntArray::new(values.into_iter().filter_map(|value| i32::try_from(value).ok()).map(|value| NbtElement::Int(NbtInt { value })).collect()))
+				} else {
+					NbtElement::LongArray(NbtLongArray::new(values.into_iter().map(|value| NbtElement::Long(NbtLong { value })).collect()))
+				};
+				array.recache();
+				return Some(array)
+			}
+			let mut elements = values.into_iter().filter_map(|value| to_nbt(value, options)).collect::<Vec<_>>();
+			widen_numbers(&mut elements);
+			let mut list = NbtElement::List(NbtList::new(elements));
+			list.recache();
+			list
+		}
+		JsonValue::Object(entries) => {
+			if let Some(element) = from_typed(&entries) {
+				return Some(element)
+			}
+			NbtElement::Compound(NbtCompound::new(entries.into_iter().filter_map(|(key, value)| Some(CompoundEntry::new(key.into(), to_nbt(value, options)?))).collect()))
+		}
+	})
+}
+
+/// Reads back the `{"type":"long","value":1}` objects written by [`JsonNumberPolicy::Typed`]
+fn from_typed(entries: &[(String, JsonValue)]) -> Option<NbtElement> {
+	let [(type_key, JsonValue::String(ty)), (value_key, value)] = entries else { return None };
+	if type_key != "type" || value_key != "value" {
+		return None
+	}
+	let integer = |value: &JsonValue| if let JsonValue::Integer(value) = value { Some(*value) } else { None };
+	#[allow(clippy::cast_precision_loss)]
+	let decimal = |value: &JsonValue| match value {
+		JsonValue::Integer(value) => Some(*value as f64),
+		JsonValue::Decimal(value) => Some(*value),
+		JsonValue::String(str) => match str.as_str() {
+			"NaN" => Some(f64::NAN),
+			"Infinity" => Some(f64::INFINITY),
+			"-Infinity" => Some(f64::NEG_INFINITY),
+			_ => None,
+		},
+		_ => None,
+	};
+	let array = |value: &JsonValue| if let JsonValue::Array(values) = value { values.iter().map(integer).collect::<Option<Vec<_>>>() } else { None };
+	let mut element = match ty.as_str() {
+		"byte" => NbtElement::Byte(NbtByte { value: i8::try_from(integer(value)?).ok()? }),
+		"short" => NbtElement::Short(NbtShort { value: i16::try_from(integer(value)?).ok()? }),
+		"int" => NbtElement::Int(NbtInt { value: i32::try_from(integer(value)?).ok()? }),
+		"long" => NbtElement::Long(NbtLong { value: integer(value)? }),
+		#[allow(clippy::cast_possible_truncation)]
+		"float" => NbtElement::Float(NbtFloat { value: decimal(value)? as f32 }),
+		"double" => NbtElement::Double(NbtDouble { value: decimal(value)? }),
+		"byte_array" => NbtElement::ByteArray(NbtByteArray::new(array(value)?.into_iter().map(|value| i8::try_from(value).ok().map(|value| NbtElement::Byte(NbtByte { value }))).collect::<Option<Vec<_>>>()?)),
+		"int_array" => NbtElement::IntArray(NbtIntArray::new(array(value)?.into_iter().map(|value| i32::try_from(value).ok().map(|value| NbtElement::Int(NbtInt { value }))).collect::<Option<Vec<_>>>()?)),
+		"long_array" => NbtElement::LongArray(NbtLongArray::new(array(value)?.into_iter().map(|value| NbtElement::Long(NbtLong { value })).collect())),
+		_ => return None,
+	};
+	element.recache();
+	Some(element)
+}
+
+/// Lists of numbers with different types are widened to the largest of them, so `[1, 300]` is a list of shorts rather than a byte and a short
+fn widen_numbers(elements: &mut [NbtElement]) {
+	let rank = |element: &NbtElement| match element.as_pattern() {
+		NbtPattern::Byte(_) => Some(0),
+		NbtPattern::Short(_) => Some(1),
+		NbtPattern::Int(_) => Some(2),
+		NbtPattern::Long(_) => Some(3),
+		NbtPattern::Float(_) => Some(4),
+		NbtPattern::Double(_) => Some(5),
+		_ => None,
+	};
+	let Some(widest) = elements.iter().map(rank).collect::<Option<Vec<_>>>().and_then(|ranks| ranks.into_iter().max()) else { return };
+	for element in elements {
+		#[allow(clippy::cast_precision_loss)]
+		let (integer, decimal) = match element.as_pattern() {
+			NbtPattern::Byte(x) => (i64::from(x.value), f64::from(x.value)),
+			NbtPattern::Short(x) => (i64::from(x.value), f64::from(x.value)),
+			NbtPattern::Int(x) => (i64::from(x.value), f64::from(x.value)),
+			NbtPattern::Long(x) => (x.value, x.value as f64),
+			NbtPattern::Float(x) => (0, f64::from(x.value)),
+			NbtPattern::Double(x) => (0, x.value),
+			_ => return,
+		};
+		#[allow(clippy::cast_possible_truncation)]
+		let widened = match widest {
+			1 => NbtElement::Short(NbtShort { value: i16::try_from(integer).unwrap_or_default() }),
+			2 => NbtElement::Int(NbtInt { value: i32::try_from(integer).unwrap_or_default() }),
+			3 => NbtElement::Long(NbtLong { value: integer }),
+			4 => NbtElement::Float(NbtFloat { value: decimal as f32 }),
+			5 => NbtElement::Double(NbtDouble { value: decimal }),
+			_ => continue,
+		};
+		*element = widened;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_to_json() {
+		let mut compound = NbtCompound::default();
+		compound.map.insert(CompoundEntry::new("a".into(), NbtElement::Byte(NbtByte { value: 1 })));
+		let mut list = NbtList::default();
+		// SAFETY: there is nothing to update
+		unsafe {
+			let _ = list.insert(0, NbtElement::Long(NbtLong { value: 1 }));
+			let _ = list.insert(1, NbtElement::Long(NbtLong { value: 2 }));
+		}
+		compound.map.insert(CompoundEntry::new("b".into(), NbtElement::List(list)));
+		compound.map.insert(CompoundEntry::new("c".into(), NbtElement::String(NbtString { str: "x\"y\n\u{1}".into() })));
+		compound.map.insert(CompoundEntry::new("d".into(), NbtElement::Double(NbtDouble { value: f64::NAN })));
+		compound.map.insert(CompoundEntry::new("e".into(), NbtElement::Float(NbtFloat { value: -1.5 })));
+		let nbt = NbtElement::Compound(compound);
+
+		assert_eq!(to_json(&nbt, JsonNumberPolicy::Plain), r#"{"a":1,"b":[1,2],"c":"x\"y\n\u0001","d":"NaN","e":-1.5}"#);
+		assert!(non_finite_warning(&nbt).is_some());
+		assert_eq!(
+			to_json(&nbt, JsonNumberPolicy::Typed),
+			r#"{"a":{"type":"byte","value":1},"b":[{"type":"long","value":1},{"type":"long","value":2}],"c":"x\"y\n\u0001","d":{"type":"double","value":"NaN"},"e":{"type":"float","value":-1.5}}"#
+		);
+	}
+
+	#[test]
+	fn test_from_json() {
+		let json = r#" {"a": 1, "b": [1, 300], "c": [1, 2147483648], "d": 1.5, "e": 0.1, "f": "\u00e9\ud83d\ude00\ud800", "g": null, "h": true, "i": []} "#;
+		let nbt = from_json(json, JsonParseOptions::default()).expect("Valid JSON");
+		assert_eq!(to_json(&nbt, JsonNumberPolicy::Typed), r#"{"a":{"type":"byte","value":1},"b":{"type":"int_array","value":[1,300]},"c":{"type":"long_array","value":[1,2147483648]},"d":{"type":"float","value":1.5},"e":{"type":"double","value":0.1},"f":"é😀�","h":{"type":"byte","value":1},"i":[]}"#);
+
+		let options = JsonParseOptions { narrowest_numbers: false, numeric_arrays: false };
+		let nbt = from_json(r#"{"a":1,"b":[1,300,1.5]}"#, options).expect("Valid JSON");
+		assert_eq!(to_json(&nbt, JsonNumberPolicy::Typed), r#"{"a":{"type":"int","value":1},"b":[{"type":"double","value":1},{"type":"double","value":300},{"type":"double","value":1.5}]}"#);
+
+		assert!(from_json(r#"{"a":1,}"#, JsonParseOptions::default()).is_err());
+		assert!(from_json(r#"{"a":1x}"#, JsonParseOptions::default()).is_err());
+	}
+
+	#[test]
+	fn test_json_round_trip() {
+		let json = r#"{"a":{"type":"short","value":1},"b":{"type":"byte_array","value":[1,-2]},"c":[{"type":"float","value":"-Infinity"}],"d":"x\"y\n\u0001"}"#;
+		let nbt = from_json(json, JsonParseOptions::default()).expect("Valid JSON");
+		assert_eq!(to_json(&nbt, JsonNumberPolicy::Typed), json);
+	}
+
+	#[test]
+	fn test_reformat_json() {
+		let json = r#"{"text":"caf\u00e9","extra":[{"text":"\"hi\"","bold":true},1e3],"empty":{}}"#;
+		let formatted = format_json(json).expect("Valid JSON");
+		assert_eq!(formatted, "{\n  \"text\": \"caf\\u00e9\",\n  \"extra\": [\n    {\n      \"text\": \"\\\"hi\\\"\",\n      \"bold\": true\n    },\n    1e3\n  ],\n  \"empty\": {}\n}");
+		assert_eq!(minify_json(&formatted).as_deref(), Ok(json));
+		assert!(is_json_text(json));
+		assert!(!is_json_text("\"text\""));
+		assert!(!is_json_text("{text}"));
+
+		// counted from the end
+		let broken = "{\n  \"text\": \"hi\"\n  \"color\": \"red\"\n}";
+		assert_eq!(minify_json(broken).map_err(|rest| broken.len() - rest), Err(broken.find("\"color").expect("It's there")));
+		assert_eq!(minify_json("[1,2] 3"), Err(1));
+	}
+}
@@ -1,3 +1,5 @@
 pub mod decoder;
 pub mod encoder;
 pub mod formatter;
+pub mod json;
+pub mod mutf8;
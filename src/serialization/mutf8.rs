@@ -0,0 +1,104 @@
+use std::borrow::Cow;
+
+use compact_str::CompactString;
+
+/// Decodes the modified UTF-8 Java writes strings in, where characters past U+FFFF are a pair of 3-byte surrogates and NUL is `0xC0 0x80`
+///
+/// Plain UTF-8 is read as it is too, since that's how it used to be written here, and anything else invalid (unpaired surrogates included) is replaced with U+FFFD
+#[must_use]
+pub fn decode(bytes: &[u8]) -> CompactString {
+	// nearly every string has nothing written differently to UTF-8, neither byte shows up otherwise outside of U+D000 to U+D7FF
+	if !bytes.iter().any(|&byte| byte == 0xC0 || byte == 0xED) {
+		return CompactString::from_utf8_lossy(bytes)
+	}
+
+	let mut out = String::with_capacity(bytes.len());
+	// of the UTF-8 not yet pushed
+	let mut start = 0;
+	let mut idx = 0;
+	while idx < bytes.len() {
+		let (char, len) = match bytes[idx..] {
+			[0xC0, 0x80, ..] => ('\0', 2),
+			[0xED, high @ 0xA0..=0xAF, high_low @ 0x80..=0xBF, 0xED, low @ 0xB0..=0xBF, low_low @ 0x80..=0xBF, ..] => {
+				let high = (u32::from(high & 0x0F) << 6) | u32::from(high_low & 0x3F);
+				let low = (u32::from(low & 0x0F) << 6) | u32::from(low_low & 0x3F);
+				(char::from_u32(0x1_0000 + (high << 10) + low).unwrap_or(char::REPLACEMENT_CHARACTER), 6)
+			}
+			[0xED, 0xA0..=0xBF, 0x80..=0xBF, ..] => (char::REPLACEMENT_CHARACTER, 3),
+			_ => {
+				idx += 1;
+				continue
+			}
+		};
+		out.push_str(&String::from_utf8_lossy(&bytes[start..idx]));
+		out.push(char);
+		idx += len;
+		start = idx;
+	}
+	out.push_str(&String::from_utf8_lossy(&bytes[start..]));
+	CompactString::from(out)
+}
+
+/// Encodes `str` as the modified UTF-8 Java reads strings in, see [`decode`]
+#[must_use]
+#[allow(clippy::cast_possible_truncation)] // each is masked to fit
+pub fn encode(str: &str) -> Cow<'_, [u8]> {
+	// only NUL and 4-byte characters are written differently to UTF-8
+	if !str.bytes().any(|byte| byte == 0 || byte >= 0xF0) {
+		return Cow::Borrowed(str.as_bytes())
+	}
+
+	let mut out = Vec::with_capacity(str.len() + 2);
+	for char in str.chars() {
+		match char {
+			'\0' => out.extend_from_slice(&[0xC0, 0x80]),
+			char if char.len_utf16() == 2 =>
+				for unit in char.encode_utf16(&mut [0; 2]) {
+					out.extend_from_slice(&[0xE0 | (*unit >> 12) as u8, 0x80 | ((*unit >> 6) & 0x3F) as u8, 0x80 | (*unit & 0x3F) as u8]);
+				},
+			char => out.extend_from_slice(char.encode_utf8(&mut [0; 4]).as_bytes()),
+		}
+	}
+	Cow::Owned(out)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::elements::element::NbtElement;
+
+	#[test]
+	fn test_mutf8() {
+		assert_eq!(&*encode("Steve"), b"Steve");
+		assert_eq!(&*encode("a\0b"), b"a\xC0\x80b");
+		assert_eq!(&*encode("😀"), b"\xED\xA0\xBD\xED\xB8\x80");
+		assert_eq!(&*encode("é𝄞"), b"\xC3\xA9\xED\xA0\xB4\xED\xB4\x9E");
+
+		for str in ["Steve", "a\0b", "😀 and 🎉", "é𝄞\0", "\u{D7FF}\u{E000}\u{10FFFF}"] {
+			assert_eq!(decode(&encode(str)), str);
+		}
+		// as written before modified UTF-8 was
+		assert_eq!(decode("😀".as_bytes()), "😀");
+		// unpaired surrogates
+		assert_eq!(decode(b"a\xED\xA0\xBDb"), "a\u{FFFD}b");
+		assert_eq!(decode(b"\xED\xB8\x80"), "\u{FFFD}");
+	}
+
+	#[test]
+	fn test_mutf8_file() {
+		// written like Java's `DataOutput::writeUTF`, with 4-byte characters in a key, a value and a list, and a NUL
+		let file = include_bytes!("../assets/mutf8.nbt");
+		let root = NbtElement::from_be_file(file).expect("Valid NBT");
+		let value = |key: &str| root.as_compound().and_then(|compound| compound.map.idx_of(key)).and_then(|idx| root.get(idx)).map(|(_, value)| value.to_string());
+		assert_eq!(value("CustomName").as_deref(), Some(r#""Steve 😀""#));
+		assert_eq!(value("🎉").as_deref(), Some("1b"));
+		assert_eq!(value("Tags").as_deref(), Some(r#"["a\0b","𝄞 clef",plain]"#));
+		assert_eq!(value("Lore").as_deref(), Some(r#""é ü ✓""#));
+
+		let bytes = root.to_be_file();
+		assert!(!bytes.iter().any(|&byte| byte >= 0xF0), "No 4-byte UTF-8");
+		assert!(bytes.windows(6).any(|window| window == b"\xED\xA0\xBD\xED\xB8\x80"));
+		assert!(bytes.windows(4).any(|window| window == b"a\xC0\x80b"));
+		assert_eq!(NbtElement::from_be_file(&bytes).expect("Valid NBT"), root);
+	}
+}
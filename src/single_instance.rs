@@ -0,0 +1,154 @@
+use std::{
+	io::{Read, Write},
+	path::PathBuf,
+	sync::mpsc::{Receiver, Sender, channel},
+};
+
+/// Opens the files of any later launches in this window instead of theirs, unless they're launched with `--new-window`
+pub struct InstanceListener {
+	rx: Receiver<Vec<PathBuf>>,
+}
+
+impl InstanceListener {
+	/// # Errors
+	/// * If another instance is already listening, or the socket / pipe couldn't be created
+	pub fn spawn() -> std::io::Result<Self> {
+		let (tx, rx) = channel();
+		imp::listen(tx)?;
+		Ok(Self { rx })
+	}
+
+	/// The files forwarded by a later launch, which may be none if it was launched without any
+	#[must_use]
+	pub fn poll(&self) -> Option<Vec<PathBuf>> { self.rx.try_recv().ok() }
+}
+
+/// Hands `paths` to the instance that's already running, returning whether there was one to hand them to
+#[must_use]
+pub fn forward(paths: &[PathBuf]) -> bool {
+	let Ok(mut stream) = imp::connect() else { return false };
+	// the other instance was started somewhere else, so relative paths wouldn't lead to the same files
+	let message = paths.iter().filter_map(|path| std::fs::canonicalize(path).ok()).map(|path| path.to_string_lossy().into_owned()).collect::<Vec<_>>().join("\n");
+	// none of them exist, so this launch should be the one to say so
+	if message.is_empty() {
+		return false
+	}
+	stream.write_all(message.as_bytes()).is_ok()
+}
+
+fn read_paths(mut stream: impl Read, tx: &Sender<Vec<PathBuf>>) {
+	let mut message = String::new();
+	if stream.read_to_string(&mut message).is_ok() {
+		let _ = tx.send(message.lines().filter(|line| !line.is_empty()).map(PathBuf::from).collect());
+	}
+}
+
+fn user() -> String { std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default() }
+
+#[cfg(unix)]
+mod imp {
+	use std::{
+		fs::DirBuilder,
+		io::ErrorKind,
+		os::unix::{
+			fs::{DirBuilderExt, MetadataExt},
+			net::{UnixListener, UnixStream},
+		},
+		path::PathBuf,
+		sync::mpsc::Sender,
+	};
+
+	/// In a directory only this user can get into, since anyone could connect to a socket left in the shared temp dir and open files in this window, or bind it first to be sent the paths of the files being opened
+	fn socket_path() -> std::io::Result<PathBuf> {
+		// only accessible to its user already
+		if let Some(dir) = dirs::runtime_dir() {
+			return Ok(dir.join("nbtworkbench.sock"))
+		}
+		let dir = std::env::temp_dir().join(format!("nbtworkbench-{}", super::user()));
+		match DirBuilder::new().mode(0o700).create(&dir) {
+			Ok(()) => {}
+			Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+			Err(e) => return Err(e),
+		}
+		// made by someone else beforehand, or had its permissions changed since
+		let metadata = std::fs::symlink_metadata(&dir)?;
+		if !metadata.is_dir() || metadata.uid() != unsafe { libc::geteuid() } || metadata.mode() & 0o077 != 0 {
+			return Err(std::io::Error::new(ErrorKind::PermissionDenied, format!("{} isn't a directory only this user can access", dir.display())))
+		}
+		Ok(dir.join("nbtworkbench.sock"))
+	}
+
+	pub fn connect() -> std::io::Result<UnixStream> { UnixStream::connect(socket_path()?) }
+
+	pub fn listen(tx: Sender<Vec<PathBuf>>) -> std::io::Result<()> {
+		let path = socket_path()?;
+		let listener = match UnixListener::bind(&path) {
+			Ok(listener) => listener,
+			Err(e) if e.kind() == ErrorKind::AddrInUse => {
+				if connect().is_ok() {
+					return Err(e)
+				}
+				// left behind by an instance that didn't get to clean up after itself
+				std::fs::remove_file(&path)?;
+				UnixListener::bind(&path)?
+			}
+			Err(e) => return Err(e),
+		};
+		std::thread::Builder::new().name("Instance listener".to_owned()).spawn(move || {
+			for stream in listener.incoming().flatten() {
+				super::read_paths(stream, &tx);
+			}
+		})?;
+		Ok(())
+	}
+}
+
+#[cfg(windows)]
+mod imp {
+	use std::{
+		ffi::OsStr,
+		fs::{File, OpenOptions},
+		os::windows::{
+			ffi::OsStrExt,
+			io::{AsRawHandle, FromRawHandle},
+		},
+		path::PathBuf,
+		sync::mpsc::Sender,
+	};
+
+	use winapi::{
+		shared::winerror::ERROR_PIPE_CONNECTED,
+		um::{
+			errhandlingapi::GetLastError,
+			handleapi::INVALID_HANDLE_VALUE,
+			namedpipeapi::{ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe},
+			winbase::{FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_INBOUND, PIPE_READMODE_BYTE, PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT},
+		},
+	};
+
+	fn pipe_name() -> String { format!(r"\\.\pipe\nbtworkbench-{}", super::user()) }
+
+	pub fn connect() -> std::io::Result<File> { OpenOptions::new().write(true).open(pipe_name()) }
+
+	pub fn listen(tx: Sender<Vec<PathBuf>>) -> std::io::Result<()> {
+		let name = OsStr::new(&pipe_name()).encode_wide().chain(core::iter::once(0)).collect::<Vec<u16>>();
+		// fails if another instance already created it
+		let handle = unsafe { CreateNamedPipeW(name.as_ptr(), PIPE_ACCESS_INBOUND | FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT, PIPE_UNLIMITED_INSTANCES, 0, 4096, 0, core::ptr::null_mut()) };
+		if handle == INVALID_HANDLE_VALUE {
+			return Err(std::io::Error::last_os_error())
+		}
+		let pipe = unsafe { File::from_raw_handle(handle.cast()) };
+		std::thread::Builder::new().name("Instance listener".to_owned()).spawn(move || {
+			loop {
+				let handle = pipe.as_raw_handle().cast();
+				if unsafe { ConnectNamedPipe(handle, core::ptr::null_mut()) } == 0 && unsafe { GetLastError() } != ERROR_PIPE_CONNECTED {
+					return
+				}
+				super::read_paths(&pipe, &tx);
+				// the same pipe is reused for the next launch
+				unsafe { DisconnectNamedPipe(handle) };
+			}
+		})?;
+		Ok(())
+	}
+}
@@ -5,8 +5,9 @@ use crate::{
 	history::WorkbenchAction,
 	tree::{
 		MutableIndices,
+		actions::decode::{DecodeChunkError, decode_chunk_at},
 		indices::OwnedIndices,
-		navigate::{ParentNavigationError, ParentNavigationInformationMut},
+		navigate::{NavigationInformationMut, ParentNavigationError},
 	},
 };
 
@@ -17,12 +18,37 @@ pub fn add_element<'m1, 'm2: 'm1>(
 	indices: OwnedIndices,
 	mi: &'m1 mut MutableIndices<'m2>
 ) -> Result<AddElementResult, AddElementError> {
-	let ParentNavigationInformationMut {
-		true_line_number, parent, idx, parent_indices, ..
-	} = root.navigate_parent_mut(&indices)?;
+	add_element0(root, kv, indices, mi, false)
+}
+
+/// Like [`add_element`], but putting back what was removed, so a duplicate key read from a file is kept rather than renamed, see [`NbtElement::reinsert`]
+#[rustfmt::skip]
+pub fn restore_element<'m1, 'm2: 'm1>(
+	root: &mut NbtElement,
+	kv: NbtElementAndKey,
+	indices: OwnedIndices,
+	mi: &'m1 mut MutableIndices<'m2>
+) -> Result<AddElementResult, AddElementError> {
+	add_element0(root, kv, indices, mi, true)
+}
+
+#[rustfmt::skip]
+fn add_element0<'m1, 'm2: 'm1>(
+	root: &mut NbtElement,
+	kv: NbtElementAndKey,
+	indices: OwnedIndices,
+	mi: &'m1 mut MutableIndices<'m2>,
+	restore: bool,
+) -> Result<AddElementResult, AddElementError> {
+	let (idx, parent_indices) = indices.split_last().ok_or(ParentNavigationError::EmptyIndices)?;
+	// added to on top of what's already in it
+	decode_chunk_at(root, parent_indices, mi.bookmarks)?;
+	// navigated to the parent rather than the child, since one past its last child is where to add to the end
+	let NavigationInformationMut { element: parent, true_line_number, .. } = root.navigate_mut(parent_indices).map_err(ParentNavigationError::from)?;
+	let true_line_number = true_line_number + 1 + (0..idx.min(parent.len().unwrap_or(0))).map(|jdx| parent[jdx].true_height()).sum::<usize>();
 	let (old_parent_height, old_parent_true_height) = parent.heights();
 	// SAFETY: we have updated all the relevant data
-	let old_value = match unsafe { parent.insert(idx, kv) } {
+	let old_value = match unsafe { if restore { parent.reinsert(idx, kv) } else { parent.insert(idx, kv) } } {
 		Ok(Some(old)) => Some(old),
 		Ok(None) => None,
 		Err(kv) =>
@@ -45,13 +71,14 @@ pub fn add_element<'m1, 'm2: 'm1>(
 	mi.bookmarks[true_line_number..].increment(diff, true_diff);
 
 	mi.apply(|indices, _ci| {
-		if parent_indices.encompasses_or_equal(indices) {
-			if indices[parent_indices.len()] <= idx && !been_replaced {
+		if parent_indices.encompasses(indices) {
+			if indices[parent_indices.len()] >= idx && !been_replaced {
 				indices[parent_indices.len()] += 1;
 			}
 		}
 	});
 
+	super::mark_chunk_modified(root, &parent_indices);
 	root.recache_along_indices(&parent_indices);
 
 	Ok(AddElementResult { indices, old_kv: old_value })
@@ -74,6 +101,8 @@ impl AddElementResult {
 pub enum AddElementError {
 	#[error(transparent)]
 	Navigation(#[from] ParentNavigationError),
+	#[error(transparent)]
+	DecodeChunk(#[from] DecodeChunkError),
 	#[error("Failed to insert {child} at index {idx} for parent {parent} under indices {indices}")]
 	FailedInsertion { idx: usize, indices: OwnedIndices, parent: &'static str, child: &'static str },
 }
@@ -0,0 +1,31 @@
+use thiserror::Error;
+
+use crate::{
+	elements::element::NbtElement,
+	tree::{
+		indices::Indices,
+		navigate::{NavigationError, NavigationInformationMut},
+	},
+	workbench::marked_line::MarkedLines,
+};
+
+/// Decodes the element at `indices` if it's a chunk that hasn't been yet, for whatever needs its entries without opening it
+#[rustfmt::skip]
+pub fn decode_chunk_at(
+	root: &mut NbtElement,
+	indices: &Indices,
+	bookmarks: &mut MarkedLines
+) -> Result<(), DecodeChunkError> {
+	let NavigationInformationMut { element, true_line_number, .. } = root.navigate_mut(indices)?;
+	if super::decode_chunk(element, bookmarks, true_line_number) {
+		root.recache_along_indices(indices);
+	}
+
+	Ok(())
+}
+
+#[derive(Error, Debug)]
+pub enum DecodeChunkError {
+	#[error(transparent)]
+	Navigation(#[from] NavigationError),
+}
@@ -0,0 +1,75 @@
+use compact_str::CompactString;
+use thiserror::Error;
+
+use crate::{
+	elements::element::NbtElement,
+	tree::{
+		MutableIndices,
+		actions::add::{AddElementError, AddElementResult, add_element},
+		indices::Indices,
+		navigate::{NavigationError, NavigationInformation, ParentNavigationError},
+	},
+};
+
+/// Adds a copy of the element at `indices` right after it, where a key gets a `_copy` suffix so it doesn't collide with the original's
+#[rustfmt::skip]
+pub fn duplicate_element<'m1, 'm2: 'm1>(
+	root: &mut NbtElement,
+	indices: &Indices,
+	mi: &'m1 mut MutableIndices<'m2>
+) -> Result<AddElementResult, DuplicateElementError> {
+	let Some((idx, parent_indices)) = indices.split_last() else { return Err(DuplicateElementError::Root) };
+	let NavigationInformation { key, element, .. } = root.navigate(indices)?;
+	if element.is_chunk() {
+		return Err(DuplicateElementError::Chunk)
+	}
+	let element = element.clone();
+	let parent = root.navigate_parent(indices)?.parent;
+	let key = match (key, parent.as_compound().map(|compound| &compound.map).or_else(|| parent.as_chunk().map(|chunk| &chunk.map))) {
+		(Some(key), Some(map)) => Some(map.copy_key(key)),
+		(key, _) => key.map(CompactString::from),
+	};
+	let mut destination = parent_indices.to_owned();
+	destination.push(idx + 1);
+	Ok(add_element(root, (key, element), destination, mi)?)
+}
+
+#[derive(Error, Debug)]
+pub enum DuplicateElementError {
+	#[error("The root can't be duplicated")]
+	Root,
+	#[error("Chunks can't be duplicated, the copy would have the same coordinates")]
+	Chunk,
+	#[error(transparent)]
+	Navigation(#[from] NavigationError),
+	#[error(transparent)]
+	ParentNavigation(#[from] ParentNavigationError),
+	#[error(transparent)]
+	Add(#[from] AddElementError),
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		mutable_indices,
+		tree::{OwnedMutableIndices, indices::OwnedIndices},
+	};
+
+	fn fixture(snbt: &str) -> (NbtElement, OwnedMutableIndices) { (NbtElement::from_str(snbt).expect("Valid SNBT").1, OwnedMutableIndices::default()) }
+
+	#[test]
+	fn test_duplicate() {
+		let (mut root, mut state) = fixture("{a:1b,a_copy:2b}");
+		let mi = mutable_indices!(state);
+
+		let result = duplicate_element(&mut root, &OwnedIndices::from([0]), mi).expect("`a` can be duplicated");
+		assert_eq!(result.indices.iter().collect::<Vec<_>>(), [1]);
+		assert_eq!(root.to_string(), "{a:1b,a_copy2:1b,a_copy:2b}");
+		assert!(matches!(duplicate_element(&mut root, Indices::EMPTY, mi), Err(DuplicateElementError::Root)));
+
+		let (_, mut root) = NbtElement::from_str("[3b,4b]").expect("Valid SNBT");
+		duplicate_element(&mut root, &OwnedIndices::from([0]), mi).expect("`3b` can be duplicated");
+		assert_eq!(root.to_string(), "[3b,3b,4b]");
+	}
+}
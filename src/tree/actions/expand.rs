@@ -16,6 +16,7 @@ pub fn expand_element(
 	bookmarks: &mut MarkedLines
 ) -> Result<(), ExpandElementError> {
 	let NavigationInformationMut { element, true_line_number, line_number, .. } = root.navigate_mut(&indices)?;
+	super::decode_chunks(element, bookmarks, true_line_number);
 	let true_height = element.true_height();
 	let height_before = element.height();
 	#[cfg(not(target_arch = "wasm32"))]
@@ -0,0 +1,149 @@
+#[cfg(not(target_arch = "wasm32"))] use std::thread::scope;
+
+use thiserror::Error;
+
+#[cfg(target_arch = "wasm32")]
+use crate::wasm::fake_scope as scope;
+use crate::{
+	elements::{chunk::NbtChunk, compound::CompoundEntry, element::NbtElement},
+	tree::{
+		indices::Indices,
+		navigate::{NavigationError, NavigationInformationMut},
+	},
+	workbench::marked_line::MarkedLines,
+};
+
+/// Opens everything down to `depth` levels below the element at `indices` and closes everything deeper, so `1` leaves only its children showing
+///
+/// Each element is recached once its children are done rather than the whole path up to it per toggle, so it's one walk however much is opened
+#[rustfmt::skip]
+pub fn expand_element_to_depth(
+	root: &mut NbtElement,
+	indices: &Indices,
+	depth: usize,
+	bookmarks: &mut MarkedLines
+) -> Result<(), ExpandElementToDepthError> {
+	let NavigationInformationMut { element, true_line_number, line_number, .. } = root.navigate_mut(indices)?;
+	if element.is_primitive() {
+		return Err(ExpandElementToDepthError::ElementIsPrimitive {
+			element: element.display_name(),
+			true_line_number,
+			line_number,
+		})
+	}
+	// the chunks of a region are only looked into from two levels down
+	if depth > usize::from(element.is_region()) {
+		super::decode_chunks(element, bookmarks, true_line_number);
+	}
+	let height_before = element.height();
+	// SAFETY: the heights are recached as it goes and the bookmarks right after
+	unsafe { set_depth(element, depth) };
+	let height_after = element.height();
+
+	recache_bookmarks(element, bookmarks, line_number, true_line_number);
+	let true_height = element.true_height();
+	if height_after >= height_before {
+		bookmarks[true_line_number + true_height..].increment(height_after - height_before, 0);
+	} else {
+		bookmarks[true_line_number + true_height..].decrement(height_before - height_after, 0);
+	}
+
+	root.recache_along_indices(indices);
+
+	Ok(())
+}
+
+unsafe fn set_depth(element: &mut NbtElement, depth: usize) {
+	if depth == 0 {
+		if element.is_open() {
+			scope(|scope| unsafe { element.shut(scope) });
+			// shutting leaves the height as if only the children were closed
+			element.recache();
+		}
+		return
+	}
+	if element.as_chunk().is_some_and(NbtChunk::is_undecoded) {
+		return
+	}
+	if !element.is_open() {
+		unsafe { element.toggle() };
+	}
+	// the chunks stay closed on the grid layout
+	if element.as_region().is_none_or(|region| !region.is_grid_layout()) {
+		match element.children_mut() {
+			Some(Ok(iter)) =>
+				for child in iter {
+					unsafe { set_depth(child, depth - 1) };
+				},
+			Some(Err(iter)) =>
+				for CompoundEntry { value: child, .. } in iter {
+					unsafe { set_depth(child, depth - 1) };
+				},
+			None => {}
+		}
+	}
+	element.recache();
+}
+
+/// Gives the bookmarks within `element` the lines they're on now that what's open within it changed
+fn recache_bookmarks(element: &NbtElement, bookmarks: &mut MarkedLines, line_number: usize, true_line_number: usize) {
+	let Some(children) = element.values() else { return };
+	let open = element.is_open();
+	let mut child_line_number = line_number + 1;
+	let mut child_true_line_number = true_line_number + 1;
+	for child in children {
+		let (height, true_height) = child.heights();
+		let range = child_true_line_number..child_true_line_number + true_height;
+		// nothing within is bookmarked, so there's no need to go through it
+		if !bookmarks[range.clone()].is_empty() {
+			if open {
+				if let Some(bookmark) = bookmarks.get_mut(child_true_line_number) {
+					*bookmark = bookmark.open(child_line_number);
+				}
+				recache_bookmarks(child, bookmarks, child_line_number, child_true_line_number);
+			} else {
+				for bookmark in &mut bookmarks[range] {
+					*bookmark = bookmark.hidden(line_number);
+				}
+			}
+		}
+		if open {
+			child_line_number += height;
+		}
+		child_true_line_number += true_height;
+	}
+}
+
+#[derive(Error, Debug)]
+pub enum ExpandElementToDepthError {
+	#[error(transparent)]
+	Navigation(#[from] NavigationError),
+	#[error("{element} @ (line number = {line_number}, true line number = {true_line_number}) is primitive and therefore cannot be expanded.")]
+	ElementIsPrimitive { element: &'static str, line_number: usize, true_line_number: usize },
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::workbench::marked_line::MarkedLine;
+
+	#[test]
+	fn test_expand_to_depth() {
+		let (_, mut root) = NbtElement::from_str("{a:{b:{c:1b}},d:{e:{f:2b}}}").expect("Valid SNBT");
+		// on `f`, hidden until `e` is open
+		let mut bookmarks = MarkedLines::from(vec![MarkedLine::new(7, 1)]);
+
+		expand_element_to_depth(&mut root, Indices::EMPTY, 2, &mut bookmarks).expect("Root is complex");
+		// everything but `b` and `e`'s children
+		assert_eq!(root.height(), 5);
+		assert_eq!(bookmarks.iter().map(|bookmark| (bookmark.line_number(), bookmark.uv())).collect::<Vec<_>>(), [(4, crate::render::assets::HIDDEN_BOOKMARK_UV)]);
+
+		expand_element_to_depth(&mut root, Indices::EMPTY, usize::MAX, &mut bookmarks).expect("Root is complex");
+		assert_eq!(root.height(), 7);
+		assert_eq!(bookmarks.iter().map(|bookmark| (bookmark.line_number(), bookmark.uv())).collect::<Vec<_>>(), [(6, crate::render::assets::BOOKMARK_UV)]);
+
+		expand_element_to_depth(&mut root, Indices::EMPTY, 1, &mut bookmarks).expect("Root is complex");
+		assert_eq!(root.height(), 3);
+		assert_eq!(bookmarks.iter().map(|bookmark| bookmark.line_number()).collect::<Vec<_>>(), [2]);
+	}
+}
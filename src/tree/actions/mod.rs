@@ -3,23 +3,54 @@
 use thiserror::Error;
 
 use crate::{
-	elements::element::NbtElement,
-	tree::actions::{expand::ExpandElementError, open::OpenElementError},
+	elements::{chunk::NbtChunk, element::NbtElement},
+	tree::{
+		actions::{expand::ExpandElementError, open::OpenElementError},
+		indices::Indices,
+	},
 	workbench::marked_line::MarkedLines,
 };
 
 pub mod add;
+pub mod decode;
+pub mod duplicate;
+pub mod move_chunk;
+pub mod nudge;
 pub mod remove;
 pub mod rename;
 pub mod reorder;
 pub mod replace;
+pub mod sort;
+pub mod splice;
 pub mod swap;
 
 pub mod close;
 pub mod expand;
+pub mod expand_to_depth;
 pub mod expand_to_indices;
 pub mod open;
 
+/// Decodes `element` if it's a chunk that hasn't been yet, so that it can be opened, returning whether it was
+fn decode_chunk(element: &mut NbtElement, bookmarks: &mut MarkedLines, true_line_number: usize) -> bool {
+	let decoded = element.as_chunk_mut().is_some_and(NbtChunk::decode);
+	if decoded {
+		bookmarks[true_line_number + 1..].increment(0, element.true_height() - 1);
+	}
+	decoded
+}
+
+/// Decodes `element` and every chunk within it which hasn't been yet, so that it can be expanded
+fn decode_chunks(element: &mut NbtElement, bookmarks: &mut MarkedLines, true_line_number: usize) {
+	if let Some(region) = element.as_region_mut() {
+		// the chunks stay closed on the grid layout
+		if !region.is_grid_layout() {
+			region.decode_chunks(true_line_number, bookmarks);
+		}
+	} else {
+		decode_chunk(element, bookmarks, true_line_number);
+	}
+}
+
 fn recache_bookmarks_on_open(element: &NbtElement, bookmarks: &mut MarkedLines, height_gained: usize, mut line_number: usize, mut true_line_number: usize) -> Result<(), RecacheBookmarkError> {
 	line_number += 1;
 	true_line_number += 1;
@@ -42,6 +73,16 @@ fn recache_bookmarks_on_open(element: &NbtElement, bookmarks: &mut MarkedLines,
 	Ok(())
 }
 
+/// Lets the chunk that the element at `indices` is in (or is) know its contents changed, so it's encoded anew rather than written back as it was read
+fn mark_chunk_modified(root: &mut NbtElement, indices: &Indices) {
+	if let Some(idx) = indices.first()
+		&& let Some(region) = root.as_region_mut()
+		&& let Some(chunk) = region.chunks.get_mut(idx).and_then(NbtElement::as_chunk_mut)
+	{
+		chunk.mark_modified();
+	}
+}
+
 #[derive(Error, Debug)]
 pub enum RecacheBookmarkError {
 	#[error("{element} was primitive when expected to recache the bookmarks of the children")]
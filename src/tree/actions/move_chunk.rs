@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::{
+	elements::{chunk::NbtChunk, element::NbtElement, int::NbtInt},
+	history::WorkbenchAction,
+	tree::{
+		MutableIndices,
+		actions::{
+			decode::{DecodeChunkError, decode_chunk_at},
+			replace::{ReplaceElementError, replace_element},
+			swap::{SwapElementErrorSameDepth, swap_element_same_depth},
+		},
+		indices::OwnedIndices,
+	},
+};
+
+/// Moves the chunk at `from` in the region at the root to the empty slot `to` as one action, with the `xPos` and `zPos` it holds moved along with it to where `to` is in the world, see [`NbtRegion::world_pos_of`](crate::elements::region::NbtRegion::world_pos_of)
+#[rustfmt::skip]
+pub fn move_chunk<'m1, 'm2: 'm1>(
+	root: &mut NbtElement,
+	from: usize,
+	to: usize,
+	path: &Path,
+	mi: &'m1 mut MutableIndices<'m2>
+) -> Result<MoveChunkResult, MoveChunkError> {
+	let Some(region) = root.as_region() else { return Err(MoveChunkError::NotRegion { root: root.display_name() }) };
+	let is_loaded = |idx: usize| region.chunks.get(idx).and_then(NbtElement::as_chunk).map(NbtChunk::is_loaded);
+	match (is_loaded(from), is_loaded(to)) {
+		(Some(true), Some(false)) => {}
+		(Some(false) | None, _) => return Err(MoveChunkError::NoChunk { idx: from }),
+		(_, None) => return Err(MoveChunkError::OutOfBounds { idx: to }),
+		(_, Some(true)) => return Err(MoveChunkError::Occupied { x: to % 32, z: to / 32 }),
+	}
+
+	// its position is only found once it's decoded
+	decode_chunk_at(root, &OwnedIndices::from([from]), mi.bookmarks)?;
+	let mut actions = Vec::with_capacity(3);
+	let (entries, world) = root.as_region().map(|region| (region.chunks[from].as_chunk().and_then(NbtChunk::world_pos_entries), region.world_pos_of(path, to))).unwrap_or_default();
+	for ((indices, old), new) in entries.into_iter().flatten().zip(world.into_iter().flat_map(|(x, z)| [x, z])) {
+		if old == new {
+			continue;
+		}
+		let indices = OwnedIndices::from([from].into_iter().chain(indices.iter()).collect::<Vec<_>>());
+		let key = root.navigate(&indices).ok().and_then(|info| info.key.map(Into::into));
+		actions.push(replace_element(root, (key, NbtElement::Int(NbtInt { value: new })), indices, mi)?.into_action());
+	}
+	actions.push(swap_element_same_depth(root, OwnedIndices::new(), from, to, mi)?.into_action());
+
+	Ok(MoveChunkResult { actions })
+}
+
+pub struct MoveChunkResult {
+	actions: Vec<WorkbenchAction>,
+}
+
+impl MoveChunkResult {
+	pub fn into_action(self) -> WorkbenchAction { WorkbenchAction::Bulk { actions: self.actions.into_boxed_slice() } }
+}
+
+#[derive(Error, Debug)]
+pub enum MoveChunkError {
+	#[error("Only chunks in a region can be moved, not in a {root}")]
+	NotRegion { root: &'static str },
+	#[error("There is no chunk to move at index {idx}")]
+	NoChunk { idx: usize },
+	#[error("Index {idx} is past the end of the region")]
+	OutOfBounds { idx: usize },
+	#[error("Chunk {x}, {z} is already in the region")]
+	Occupied { x: usize, z: usize },
+	#[error(transparent)]
+	DecodeChunk(#[from] DecodeChunkError),
+	#[error(transparent)]
+	Replace(#[from] ReplaceElementError),
+	#[error(transparent)]
+	Swap(#[from] SwapElementErrorSameDepth),
+}
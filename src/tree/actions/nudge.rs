@@ -0,0 +1,125 @@
+use thiserror::Error;
+
+use crate::{
+	elements::element::{NbtElement, NbtPattern},
+	tree::{
+		actions::rename::{RenameElementError, RenameElementResult, rename_element},
+		indices::OwnedIndices,
+		navigate::NavigationError,
+	},
+	workbench::tab::FilePath,
+};
+
+/// How far [`nudge_element`] moves a number
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum NudgeStep {
+	One,
+	Ten,
+	/// Only for floats and doubles
+	Tenth,
+}
+
+impl NudgeStep {
+	const fn integer(self) -> Option<i64> {
+		match self {
+			Self::One => Some(1),
+			Self::Ten => Some(10),
+			Self::Tenth => None,
+		}
+	}
+
+	const fn fractional(self) -> f64 {
+		match self {
+			Self::One => 1.0,
+			Self::Ten => 10.0,
+			Self::Tenth => 0.1,
+		}
+	}
+
+	/// Places after the decimal point the step itself needs
+	const fn places(self) -> usize {
+		match self {
+			Self::One | Self::Ten => 0,
+			Self::Tenth => 1,
+		}
+	}
+}
+
+/// Adds `step` to the number at `indices`, or takes it away if not `up`, where integers stop at the most and least their type can hold
+///
+/// Floats and doubles are rounded to the places after the decimal point they or the step already had, so nudging `0.1` up by a tenth gives `0.2` rather than `0.20000000000000004`
+#[rustfmt::skip]
+pub fn nudge_element(
+	root: &mut NbtElement,
+	indices: OwnedIndices,
+	step: NudgeStep,
+	up: bool,
+	path: &mut FilePath
+) -> Result<RenameElementResult, NudgeElementError> {
+	let element = root.navigate(&indices)?.element;
+	let integer = |value: i64, min: i64, max: i64| -> Result<String, NudgeElementError> {
+		let step = step.integer().ok_or(NudgeElementError::Fractional { child: element.display_name() })?;
+		Ok(if up { value.saturating_add(step) } else { value.saturating_sub(step) }.clamp(min, max).to_string())
+	};
+	let delta = if up { step.fractional() } else { -step.fractional() };
+	let value = match element.as_pattern() {
+		NbtPattern::Byte(byte) => integer(byte.value.into(), i8::MIN.into(), i8::MAX.into())?,
+		NbtPattern::Short(short) => integer(short.value.into(), i16::MIN.into(), i16::MAX.into())?,
+		NbtPattern::Int(int) => integer(int.value.into(), i32::MIN.into(), i32::MAX.into())?,
+		NbtPattern::Long(long) => integer(long.value, i64::MIN, i64::MAX)?,
+		#[allow(clippy::cast_possible_truncation)]
+		NbtPattern::Float(float) => {
+			let places = places(&float.value.to_string()).max(step.places());
+			let value = (f64::from(float.value) + delta) as f32;
+			format!("{value:.places$}").parse::<f32>().unwrap_or(value).to_string()
+		}
+		NbtPattern::Double(double) => {
+			let places = places(&double.value.to_string()).max(step.places());
+			let value = double.value + delta;
+			format!("{value:.places$}").parse::<f64>().unwrap_or(value).to_string()
+		}
+		_ => return Err(NudgeElementError::NotANumber { child: element.display_name() }),
+	};
+	Ok(rename_element(root, indices, None, Some(value), path)?)
+}
+
+/// Digits after the decimal point of a number written out in full
+fn places(number: &str) -> usize { number.split_once('.').map_or(0, |(_, fraction)| fraction.len()) }
+
+#[derive(Error, Debug)]
+pub enum NudgeElementError {
+	#[error("{child} isn't a number, so it can't be nudged")]
+	NotANumber { child: &'static str },
+	#[error("{child} can only be nudged by whole numbers")]
+	Fractional { child: &'static str },
+	#[error(transparent)]
+	Navigation(#[from] NavigationError),
+	#[error(transparent)]
+	Rename(#[from] RenameElementError),
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_nudge() {
+		let (_, mut root) = NbtElement::from_str("{a:127b,b:0.1d,c:-1.5f,d:\"x\",e:5L}").expect("Valid SNBT");
+		let mut path = FilePath::new("test.nbt").expect("Valid path");
+		let mut nudge = |key: &str, step: NudgeStep, up: bool| {
+			let idx = root.as_compound().and_then(|compound| compound.map.idx_of(key)).expect("Key exists");
+			nudge_element(&mut root, OwnedIndices::from([idx]), step, up, &mut path)
+		};
+
+		assert_eq!(nudge("a", NudgeStep::One, true).expect("Bytes can be nudged").value.as_deref(), Some("127"));
+		nudge("a", NudgeStep::Ten, false).expect("Bytes can be nudged");
+		nudge("b", NudgeStep::Tenth, true).expect("Doubles can be nudged");
+		nudge("b", NudgeStep::Tenth, true).expect("Doubles can be nudged");
+		nudge("c", NudgeStep::One, true).expect("Floats can be nudged");
+		assert!(matches!(nudge("d", NudgeStep::One, true), Err(NudgeElementError::NotANumber { .. })));
+		assert!(matches!(nudge("e", NudgeStep::Tenth, true), Err(NudgeElementError::Fractional { .. })));
+		nudge("e", NudgeStep::Ten, false).expect("Longs can be nudged");
+		let value = |key: &str| root.as_compound().and_then(|compound| compound.map.idx_of(key)).and_then(|idx| root.get(idx)).map(|(_, value)| value.to_string());
+		assert_eq!(["a", "b", "c", "e"].map(value), [Some("117b"), Some("0.3d"), Some("-0.5f"), Some("-5L")].map(|value| value.map(str::to_owned)));
+	}
+}
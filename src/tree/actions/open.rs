@@ -17,10 +17,11 @@ pub fn open_element(
 	bookmarks: &mut MarkedLines
 ) -> Result<(), OpenElementError> {
 	let NavigationInformationMut { element, true_line_number, line_number, .. } = root.navigate_mut(&indices)?;
-	let height_before = element.height();
 	if element.is_open() {
 		return Ok(())
 	};
+	super::decode_chunk(element, bookmarks, true_line_number);
+	let height_before = element.height();
 	unsafe { element.toggle() };
 	let height_after = element.height();
 	let height_gained = height_after - height_before;
@@ -25,11 +25,11 @@ pub fn remove_element<'m1, 'm2: 'm1>(
 		Some(x) => x,
 		None => return Err(RemoveElementError::FailedRemoval { idx, parent: parent.display_name(), indices }),
 	};
-	let (height, true_height) = value.heights();
+	let (_height, true_height) = value.heights();
 	let (parent_height, parent_true_height) = parent.heights();
 	let (diff, true_diff) = (old_parent_height.wrapping_sub(parent_height), old_parent_true_height.wrapping_sub(parent_true_height));
-	// exists because of regions
-	let been_replaced = !(height == diff && true_height == true_diff);
+	// exists because of regions, where only the true height tells as the parent may be closed
+	let been_replaced = true_height != true_diff;
 	mi.bookmarks.remove(true_line_number..true_line_number + true_height);
 	mi.bookmarks[true_line_number..].decrement(diff, true_diff);
 
@@ -43,6 +43,7 @@ pub fn remove_element<'m1, 'm2: 'm1>(
 		}
 	});
 
+	super::mark_chunk_modified(root, &parent_indices);
 	root.recache_along_indices(&parent_indices);
 
 	Ok(RemoveElementResult {
@@ -4,13 +4,12 @@ use compact_str::{CompactString, ToCompactString};
 use thiserror::Error;
 
 use crate::{
-	elements::element::NbtElement,
+	elements::{element::NbtElement, snbt_error::SnbtError},
 	history::WorkbenchAction,
 	tree::{
 		indices::OwnedIndices,
 		navigate::{ParentNavigationError, ParentNavigationInformationMut},
 	},
-	window_properties,
 	workbench::tab::{FilePath, FilePathError},
 };
 
@@ -21,6 +20,30 @@ pub fn rename_element(
 	key: Option<CompactString>,
 	value: Option<String>,
 	path: &mut FilePath
+) -> Result<RenameElementResult, RenameElementError> {
+	rename_element0(root, indices, key, value, path, false)
+}
+
+/// Like [`rename_element`], but putting back what it was named before, so a duplicate key read from a file can be had again, see [`NbtElement::restore_key`]
+#[rustfmt::skip]
+pub fn restore_name(
+	root: &mut NbtElement,
+	indices: OwnedIndices,
+	key: Option<CompactString>,
+	value: Option<String>,
+	path: &mut FilePath
+) -> Result<RenameElementResult, RenameElementError> {
+	rename_element0(root, indices, key, value, path, true)
+}
+
+#[rustfmt::skip]
+fn rename_element0(
+	root: &mut NbtElement,
+	indices: OwnedIndices,
+	key: Option<CompactString>,
+	value: Option<String>,
+	path: &mut FilePath,
+	restore: bool,
 ) -> Result<RenameElementResult, RenameElementError> {
 	if key.is_none() && value.is_none() {
 		return Ok(RenameElementResult { indices, key, value });
@@ -29,7 +52,7 @@ pub fn rename_element(
 	match root.navigate_parent_mut(&indices) {
 		Ok(ParentNavigationInformationMut { parent, idx, .. }) => {
 			let old_key = if let Some(key) = key {
-				if let Some(result) = parent.update_key(idx, key.clone()) {
+				if let Some(result) = if restore { parent.restore_key(idx, key.clone()) } else { parent.update_key(idx, key.clone()) } {
 					match result {
 						Some(key) => Some(key),
 						None => return Err(RenameElementError::DuplicateKey { idx, indices, key }),
@@ -46,12 +69,16 @@ pub fn rename_element(
 				let child = &mut parent[idx];
 				match child.set_value(value) {
 					Ok(old_value) => Some(old_value),
-					Err(value) => return Err(RenameElementError::InvalidValue { value, child: child.display_name() }),
+					Err(value) => return Err(RenameElementError::InvalidValue { error: child.value_error(&value), value, child: child.display_name() }),
 				}
 			} else {
 				None
 			};
 
+			if let Some((_, parent_indices)) = indices.split_last() {
+				super::mark_chunk_modified(root, parent_indices);
+			}
+
 			Ok(RenameElementResult { indices, key: old_key, value: old_value })
 		}
 		Err(ParentNavigationError::EmptyIndices) => {
@@ -59,7 +86,6 @@ pub fn rename_element(
 				&& value.is_none()
 			{
 				let old_path = path.set_path(key)?;
-				window_properties().set_window_title(format!("{name} - NBT Workbench", name = path.name()).as_str());
 				Ok(RenameElementResult {
 					indices,
 					key: Some(old_path.to_string_lossy().into_owned().into()),
@@ -96,8 +122,8 @@ pub enum RenameElementError {
 	Navigation(#[from] ParentNavigationError),
 	#[error(transparent)]
 	FilePathError(#[from] FilePathError),
-	#[error("Invalid value '{value}' for {child}.")]
-	InvalidValue { value: String, child: &'static str },
+	#[error("Invalid value '{value}' for {child}: {diagnostic}", diagnostic = error.diagnostic())]
+	InvalidValue { value: String, child: &'static str, error: SnbtError },
 	#[error("Duplicate key ({key}) @ {nth} child for {indices}", nth = crate::util::nth(idx + 1))]
 	DuplicateKey { idx: usize, indices: OwnedIndices, key: CompactString },
 	#[error("Tried to rename root with {key:?} and {value:?}; needs key only.")]
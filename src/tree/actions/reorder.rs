@@ -9,6 +9,7 @@ use crate::{
 	history::WorkbenchAction,
 	tree::{
 		MutableIndices,
+		actions::decode::{DecodeChunkError, decode_chunk_at},
 		indices::OwnedIndices,
 		navigate::{NavigationError, NavigationInformationMut},
 	},
@@ -24,6 +25,8 @@ pub fn reorder_element<'m1, 'm2: 'm1>(
 	mapping: impl Into<Box<[usize]>>,
 	mi: &'m1 mut MutableIndices<'m2>
 ) -> Result<ReorderElementResult, ReorderElementError> {
+	// a chunk's entries are only there to be reordered once it's decoded
+	decode_chunk_at(root, &indices, mi.bookmarks)?;
 	let NavigationInformationMut { element, line_number, true_line_number, .. } = root.navigate_mut(&indices)?;
 	let len = element
 		.len()
@@ -46,7 +49,7 @@ pub fn reorder_element<'m1, 'm2: 'm1>(
 		let mut new_idx_true_line_number = true_line_number + 1;
 
 		(0..len)
-			.map(|idx| entries[mapping[idx]].value.heights())
+			.map(|new_idx| entries[inverted_mapping[new_idx]].value.heights())
 			.map(|(height, true_height)| {
 				let line_number = new_idx_line_number;
 				let true_line_number = new_idx_true_line_number;
@@ -57,18 +60,15 @@ pub fn reorder_element<'m1, 'm2: 'm1>(
 			.collect::<Vec<_>>()
 	};
 
-	let mut new_bookmarks = Vec::with_capacity(mi.bookmarks[true_line_number..true_line_number + parent_true_height].len());
+	// the parent's own line doesn't move
+	let mut new_bookmarks = Vec::with_capacity(mi.bookmarks[true_line_number + 1..true_line_number + parent_true_height].len());
 
 	// line numbers for the current child under the old ordering
 	let mut old_idx__line_number = line_number + 1;
 	let mut old_idx__true_line_number = true_line_number + 1;
 
-	for (((idx, &new_idx), entry), (new_idx__line_number, new_idx__true_line_number)) in mapping
-		.iter()
-		.enumerate()
-		.zip(entries.iter())
-		.zip(new_idx__line_numbers)
-	{
+	for ((idx, &new_idx), entry) in mapping.iter().enumerate().zip(entries.iter()) {
+		let (new_idx__line_number, new_idx__true_line_number) = new_idx__line_numbers[new_idx];
 		let child_height = entry.value.height();
 		let child_true_height = entry.value.true_height();
 
@@ -93,12 +93,14 @@ pub fn reorder_element<'m1, 'm2: 'm1>(
 		}
 	});
 
-	let bookmark_slice = &mut mi.bookmarks[true_line_number..true_line_number + parent_true_height];
+	let bookmark_slice = &mut mi.bookmarks[true_line_number + 1..true_line_number + parent_true_height];
 	let new_bookmarks = MarkedLines::from(new_bookmarks);
 	bookmark_slice.copy_from_slice(&new_bookmarks);
 
 	util::reorder(entries, &*mapping)?;
 
+	super::mark_chunk_modified(root, &indices);
+
 	Ok(ReorderElementResult { indices, mapping: inverted_mapping })
 }
 
@@ -116,6 +118,8 @@ pub enum ReorderElementError {
 	#[error(transparent)]
 	Navigation(#[from] NavigationError),
 	#[error(transparent)]
+	DecodeChunk(#[from] DecodeChunkError),
+	#[error(transparent)]
 	InvertMapping(#[from] InvertMappingError),
 	#[error(transparent)]
 	ReorderMapping(#[from] ReorderMappingError),
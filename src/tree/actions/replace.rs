@@ -40,13 +40,18 @@ pub fn replace_element<'m1, 'm2: 'm1>(
 				}
 			});
 
+			if let Some((_, parent_indices)) = indices.split_last() {
+				super::mark_chunk_modified(root, parent_indices);
+			}
 			root.recache_along_indices(&indices);
 
 			Ok(ReplaceElementResult { indices, kv: (old_key, old_value) })
 		}
 		Err(ParentNavigationError::EmptyIndices) =>
-			if root.id() == value.1.id() {
+			// a list at the root can be wrapped in a compound, and put back
+			if root.id() == value.1.id() || (root.is_compound() || root.is_list()) && (value.1.is_compound() || value.1.is_list()) {
 				mi.bookmarks.remove(..);
+				mi.apply(|_, ci| ci.remove());
 
 				Ok(ReplaceElementResult {
 					indices,
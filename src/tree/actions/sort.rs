@@ -0,0 +1,96 @@
+use std::cmp::Ordering;
+
+use crate::{
+	elements::{
+		chunk::NbtChunk,
+		compound::CompoundEntry,
+		element::{NbtElement, NbtPattern},
+	},
+	history::WorkbenchAction,
+	tree::{
+		MutableIndices,
+		actions::reorder::{ReorderElementError, reorder_element},
+		indices::{Indices, OwnedIndices},
+	},
+};
+
+/// Sorts the entries of every compound within the element at `indices`, and its own if it's one, as one action
+///
+/// Compounds within lists are sorted too, but lists themselves keep their order. Chunks which haven't been decoded are left alone
+#[rustfmt::skip]
+pub fn sort_element_recursively<'m1, 'm2: 'm1>(
+	root: &mut NbtElement,
+	indices: &Indices,
+	f: fn(&CompoundEntry, &CompoundEntry) -> Ordering,
+	mi: &'m1 mut MutableIndices<'m2>
+) -> Result<Option<WorkbenchAction>, ReorderElementError> {
+	let element = root.navigate(indices)?.element;
+	// sorting one compound doesn't move the entries of any other, so these can all be found first
+	let mut mappings = Vec::new();
+	collect_mappings(element, &mut indices.to_owned(), f, &mut mappings);
+	let mut actions = Vec::with_capacity(mappings.len());
+	for (indices, mapping) in mappings {
+		actions.push(reorder_element(root, indices, mapping, mi)?.into_action());
+	}
+	Ok(WorkbenchAction::bulk(actions))
+}
+
+/// Deepest first, so each compound is still where it was found when it's sorted
+fn collect_mappings(element: &NbtElement, indices: &mut OwnedIndices, f: fn(&CompoundEntry, &CompoundEntry) -> Ordering, mappings: &mut Vec<(OwnedIndices, Box<[usize]>)>) {
+	if element.as_chunk().is_some_and(NbtChunk::is_undecoded) {
+		return
+	}
+	if let Some(children) = element.values() {
+		for (idx, child) in children.enumerate() {
+			indices.push(idx);
+			collect_mappings(child, indices, f, mappings);
+			indices.pop();
+		}
+	}
+	let map = match element.as_pattern() {
+		NbtPattern::Compound(compound) => &compound.map,
+		NbtPattern::Chunk(chunk) => &chunk.map,
+		_ => return,
+	};
+	let mapping = map.create_sort_mapping(f);
+	// already in order
+	if mapping.iter().enumerate().all(|(idx, &new_idx)| idx == new_idx) {
+		return
+	}
+	mappings.push((indices.clone(), mapping));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		mutable_indices,
+		tree::OwnedMutableIndices,
+		workbench::{
+			element_action::ElementAction,
+			marked_line::{MarkedLine, MarkedLines},
+			tab::{FilePath, NbtFileFormat},
+		},
+	};
+
+	fn fixture(snbt: &str) -> (NbtElement, OwnedMutableIndices) { (NbtElement::from_str(snbt).expect("Valid SNBT").1, OwnedMutableIndices::default()) }
+
+	#[test]
+	fn test_sort_recursively() {
+		let (mut root, mut state) = fixture("{a:{Y:3b,x:4b},b:{l:[{Z:1b,a:2b}]}}");
+		let original = root.to_string();
+		// on `x`
+		state.bookmarks = MarkedLines::from(vec![MarkedLine::new(4, 4)]);
+		let mi = mutable_indices!(state);
+
+		let action = sort_element_recursively(&mut root, Indices::EMPTY, ElementAction::by_name, mi).expect("Valid indices").expect("Something to sort");
+		// case doesn't matter, and the list is left as it was
+		assert_eq!(root.to_string(), "{a:{x:4b,Y:3b},b:{l:[{a:2b,Z:1b}]}}");
+		assert_eq!(mi.bookmarks.iter().map(|bookmark| bookmark.true_line_number()).collect::<Vec<_>>(), [3]);
+
+		let mut path = FilePath::new("test.nbt").expect("Valid path");
+		let _ = action.undo(&mut root, mi, &mut path, &mut NbtFileFormat::Nbt, &mut None).expect("Can be undone");
+		assert_eq!(root.to_string(), original);
+		assert_eq!(mi.bookmarks.iter().map(|bookmark| bookmark.true_line_number()).collect::<Vec<_>>(), [4]);
+	}
+}
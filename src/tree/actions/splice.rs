@@ -0,0 +1,110 @@
+use std::ops::Range;
+
+use thiserror::Error;
+
+use crate::{
+	elements::{ComplexNbtElementVariant, byte::NbtByte, element::NbtElement},
+	history::WorkbenchAction,
+	tree::{
+		MutableIndices,
+		actions::{
+			add::{AddElementError, add_element},
+			remove::{RemoveElementError, remove_element},
+			replace::{ReplaceElementError, replace_element},
+		},
+		indices::Indices,
+		navigate::NavigationError,
+	},
+};
+
+/// Replaces the bytes within `range` of the byte array at `indices` with `bytes` as one action
+///
+/// Each byte changed is its own replace, remove or add, so bookmarks and the selection within the array follow along as they would editing it in the tree
+#[rustfmt::skip]
+pub fn splice_byte_array<'m1, 'm2: 'm1>(
+	root: &mut NbtElement,
+	indices: &Indices,
+	range: Range<usize>,
+	bytes: &[i8],
+	mi: &'m1 mut MutableIndices<'m2>
+) -> Result<Option<WorkbenchAction>, SpliceByteArrayError> {
+	let element = root.navigate(indices)?.element;
+	let array = element.as_byte_array().ok_or(SpliceByteArrayError::NotByteArray { found: element.display_name() })?;
+	let len = array.len();
+	if range.start > range.end || range.end > len {
+		return Err(SpliceByteArrayError::OutOfBounds { range, len })
+	}
+	let old = array.children().skip(range.start).take(range.len()).map(|byte| byte.as_byte().map_or(0, |byte| byte.value)).collect::<Vec<_>>();
+	let child_indices = |idx: usize| {
+		let mut indices = indices.to_owned();
+		indices.push(idx);
+		indices
+	};
+	let overwritten = range.len().min(bytes.len());
+	let mut actions = Vec::new();
+	for (idx, (&old, &value)) in (range.start..).zip(old.iter().zip(bytes)) {
+		if old != value {
+			actions.push(replace_element(root, (None, NbtElement::Byte(NbtByte { value })), child_indices(idx), mi)?.into_action());
+		}
+	}
+	// from the end, so the ones before are still where they were
+	for idx in (range.start + overwritten..range.end).rev() {
+		actions.push(remove_element(root, child_indices(idx), mi)?.into_action());
+	}
+	for (idx, &value) in (range.start + overwritten..).zip(&bytes[overwritten..]) {
+		actions.push(add_element(root, (None, NbtElement::Byte(NbtByte { value })), child_indices(idx), mi)?.into_action());
+	}
+	Ok(WorkbenchAction::bulk(actions))
+}
+
+#[derive(Error, Debug)]
+pub enum SpliceByteArrayError {
+	#[error("Expected a byte array, found {found}")]
+	NotByteArray { found: &'static str },
+	#[error("Bytes {}..{} are out of bounds for a byte array of {len}", .range.start, .range.end)]
+	OutOfBounds { range: Range<usize>, len: usize },
+	#[error(transparent)]
+	Navigation(#[from] NavigationError),
+	#[error(transparent)]
+	Add(#[from] AddElementError),
+	#[error(transparent)]
+	Remove(#[from] RemoveElementError),
+	#[error(transparent)]
+	Replace(#[from] ReplaceElementError),
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		mutable_indices,
+		tree::{OwnedMutableIndices, indices::OwnedIndices},
+		workbench::tab::{FilePath, NbtFileFormat},
+	};
+
+	fn fixture(snbt: &str) -> (NbtElement, OwnedMutableIndices) { (NbtElement::from_str(snbt).expect("Valid SNBT").1, OwnedMutableIndices::default()) }
+
+	#[test]
+	fn test_splice_byte_array() {
+		let (mut root, mut state) = fixture("{a:[B;1b,2b,3b,4b]}");
+		let mi = mutable_indices!(state);
+		let indices = OwnedIndices::from([0]);
+		let mut path = FilePath::new("test.nbt").expect("Valid path");
+
+		// overwrites one, removes the other
+		let action = splice_byte_array(&mut root, &indices, 1..3, &[9], mi).expect("In bounds").expect("Something changed");
+		assert_eq!(root.to_string(), "{a:[B;1b,9b,4b]}");
+		let _ = action.undo(&mut root, mi, &mut path, &mut NbtFileFormat::Nbt, &mut None).expect("Can be undone");
+		assert_eq!(root.to_string(), "{a:[B;1b,2b,3b,4b]}");
+
+		// inserts past the end of the range
+		let action = splice_byte_array(&mut root, &indices, 4..4, &[5, 6], mi).expect("In bounds").expect("Something changed");
+		assert_eq!(root.to_string(), "{a:[B;1b,2b,3b,4b,5b,6b]}");
+		let _ = action.undo(&mut root, mi, &mut path, &mut NbtFileFormat::Nbt, &mut None).expect("Can be undone");
+		assert_eq!(root.to_string(), "{a:[B;1b,2b,3b,4b]}");
+
+		assert!(splice_byte_array(&mut root, &indices, 0..1, &[1], mi).expect("In bounds").is_none());
+		assert!(matches!(splice_byte_array(&mut root, &indices, 3..5, &[], mi), Err(SpliceByteArrayError::OutOfBounds { .. })));
+		assert!(matches!(splice_byte_array(&mut root, Indices::EMPTY, 0..0, &[], mi), Err(SpliceByteArrayError::NotByteArray { .. })));
+	}
+}
@@ -74,6 +74,7 @@ pub fn swap_element_same_depth<'m1, 'm2: 'm1>(
 		parent.swap(a, b);
 	}
 
+	super::mark_chunk_modified(root, &parent_indices);
 	root.recache_along_indices(&parent_indices);
 
 	Ok(SwapElementResultSameDepth { parent: parent_indices, a, b })
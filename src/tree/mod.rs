@@ -1,6 +1,7 @@
 pub mod actions;
 pub mod indices;
 pub mod navigate;
+pub mod path;
 pub mod traverse;
 
 #[must_use]
@@ -53,17 +54,21 @@ pub struct MutableIndices<'m2> {
 	pub subscription: &'m2 mut Option<FileUpdateSubscription>,
 	pub selected_text: &'m2 mut Option<SelectedText>,
 	pub bookmarks: &'m2 mut MarkedLines,
+	pub selection: &'m2 mut Selection,
+	pub boolean_overrides: &'m2 mut BooleanOverrides,
 	pub temp: Vec<&'m2 mut Option<OwnedIndices>>,
 }
 
 impl<'m1, 'm2: 'm1> MutableIndices<'m2> {
 	#[must_use]
-	pub fn new(subscription: &'m2 mut Option<FileUpdateSubscription>, selected_text: &'m2 mut Option<SelectedText>, bookmarks: &'m2 mut MarkedLines) -> Self {
+	pub fn new(subscription: &'m2 mut Option<FileUpdateSubscription>, selected_text: &'m2 mut Option<SelectedText>, bookmarks: &'m2 mut MarkedLines, selection: &'m2 mut Selection, boolean_overrides: &'m2 mut BooleanOverrides) -> Self {
 		Self {
 			is_empty: false,
 			subscription,
 			selected_text,
 			bookmarks,
+			selection,
+			boolean_overrides,
 			temp: Vec::new(),
 		}
 	}
@@ -89,6 +94,9 @@ impl<'m1, 'm2: 'm1> MutableIndices<'m2> {
 			}
 		}
 
+		self.selection.remap(&mut f);
+		self.boolean_overrides.remap(&mut f);
+
 		for temp in &mut *self.temp {
 			if let Some(temp_inner) = temp {
 				let mut ci = CallbackInfo::new();
@@ -104,6 +112,17 @@ impl<'m1, 'm2: 'm1> MutableIndices<'m2> {
 	pub fn as_inner_mut(&'m1 mut self) -> (&'m1 mut &'m2 mut Option<FileUpdateSubscription>, &'m1 mut &'m2 mut Option<SelectedText>, &'m1 mut Vec<&'m2 mut Option<OwnedIndices>>) { (&mut self.subscription, &mut self.selected_text, &mut self.temp) }
 }
 
+/// What [`MutableIndices`] borrows, for tests to make one with [`mutable_indices!`](crate::mutable_indices)
+#[cfg(test)]
+#[derive(Default)]
+pub struct OwnedMutableIndices {
+	pub subscription: Option<FileUpdateSubscription>,
+	pub selected_text: Option<SelectedText>,
+	pub bookmarks: MarkedLines,
+	pub selection: Selection,
+	pub boolean_overrides: BooleanOverrides,
+}
+
 mod callback_info {
 	pub struct CallbackInfo {
 		removed: bool,
@@ -130,5 +149,5 @@ use crate::{
 	elements::element::NbtElement,
 	render::widget::selected_text::SelectedText,
 	tree::indices::{Indices, OwnedIndices},
-	workbench::{marked_line::MarkedLines, FileUpdateSubscription},
+	workbench::{boolean_overrides::BooleanOverrides, marked_line::MarkedLines, selection::Selection, FileUpdateSubscription},
 };
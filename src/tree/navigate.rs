@@ -123,8 +123,8 @@ impl<'nbt, 'indices> ParentNavigationInformation<'nbt, 'indices> {
 		for idx in parent_indices {
 			let len = parent.len().ok_or_else(|| NavigationError::ParentWasPrimitive { indices: parent_indices.to_owned() })?;
 
-			if last >= len {
-				return Err(NavigationError::IndexOutOfBounds { idx: last, indices: parent_indices.to_owned() }.into());
+			if idx >= len {
+				return Err(NavigationError::IndexOutOfBounds { idx, indices: parent_indices.to_owned() }.into());
 			}
 
 			line_number += 1;
@@ -184,8 +184,8 @@ impl<'nbt, 'indices> ParentNavigationInformationMut<'nbt, 'indices> {
 		for idx in parent_indices {
 			let len = parent.len().ok_or_else(|| NavigationError::ParentWasPrimitive { indices: parent_indices.to_owned() })?;
 
-			if last >= len {
-				return Err(NavigationError::IndexOutOfBounds { idx: last, indices: parent_indices.to_owned() }.into());
+			if idx >= len {
+				return Err(NavigationError::IndexOutOfBounds { idx, indices: parent_indices.to_owned() }.into());
 			}
 
 			line_number += 1;
@@ -292,9 +292,10 @@ impl<'nbt, 'indices> Iterator for IterativeNavigationInformationMut<'nbt, 'indic
 			self.true_line_number += sibling.true_height();
 		}
 
+		// the line numbers are now the child's, so the child is what's yielded
 		let (element, child_element) = unsafe {
-			let duplicate_reference = std::ptr::read(&raw const self.element);
 			let child_reference = core::mem::transmute::<_, &'nbt mut NbtElement>(self.element[idx].as_nonnull_mut()?);
+			let duplicate_reference = std::ptr::read(&raw const child_reference);
 			(duplicate_reference, child_reference)
 		};
 
@@ -365,9 +366,10 @@ impl<'nbt, 'indices> Iterator for ParentIterativeNavigationInformationMut<'nbt,
 			self.true_line_number += sibling.true_height();
 		}
 
+		// the line numbers are now the child's, so the child is what's yielded
 		let (element, child_element) = unsafe {
-			let duplicate_reference = std::ptr::read(&raw const self.element);
 			let child_reference = core::mem::transmute::<_, &'nbt mut NbtElement>(self.element[idx].as_nonnull_mut()?);
+			let duplicate_reference = std::ptr::read(&raw const child_reference);
 			(duplicate_reference, child_reference)
 		};
 
@@ -0,0 +1,345 @@
+use std::fmt::{Display, Formatter};
+
+use compact_str::CompactString;
+use thiserror::Error;
+
+use crate::{
+	elements::{Matches, NbtElementVariant, compound::NbtCompound, element::NbtElement},
+	tree::indices::{Indices, OwnedIndices},
+	util::StrExt,
+};
+
+/// A path to a single element in Minecraft's `/data` syntax, like `Data.Player.Inventory[3].tag.display.Name`
+#[derive(Clone)]
+pub struct NbtPath {
+	nodes: Vec<NbtPathNode>,
+}
+
+#[derive(Clone)]
+pub enum NbtPathNode {
+	/// `{...}`, can only be the first node
+	MatchRoot(NbtCompound),
+	/// `key` or `"quoted key"`
+	Key(CompactString),
+	/// `key{...}`
+	MatchKey(CompactString, NbtCompound),
+	/// `[n]`, where a negative `n` counts from the end
+	Index(isize),
+	/// `[]`
+	AllElements,
+	/// `[{...}]`
+	MatchElement(NbtCompound),
+}
+
+impl NbtPathNode {
+	#[must_use]
+	pub fn is_key(&self) -> bool { matches!(self, Self::Key(_) | Self::MatchKey(_, _)) }
+}
+
+impl Display for NbtPathNode {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		fn write_key(f: &mut Formatter<'_>, key: &str) -> std::fmt::Result {
+			if key.is_empty() || key.contains(|c: char| !is_unquoted_key_char(c)) || key.starts_with('\'') {
+				write!(f, "{key:?}")
+			} else {
+				write!(f, "{key}")
+			}
+		}
+
+		match self {
+			Self::MatchRoot(matcher) => write!(f, "{matcher}"),
+			Self::Key(key) => write_key(f, key),
+			Self::MatchKey(key, matcher) => {
+				write_key(f, key)?;
+				write!(f, "{matcher}")
+			}
+			Self::Index(idx) => write!(f, "[{idx}]"),
+			Self::AllElements => write!(f, "[]"),
+			Self::MatchElement(matcher) => write!(f, "[{matcher}]"),
+		}
+	}
+}
+
+impl Display for NbtPath {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { self.fmt_prefix(f, self.nodes.len()) }
+}
+
+#[must_use]
+const fn is_unquoted_key_char(c: char) -> bool { !matches!(c, ' ' | '"' | '\'' | '[' | ']' | '.' | '{' | '}') }
+
+impl NbtPath {
+	/// # Errors
+	/// * If `s` isn't a valid path, along with where it stops being one
+	pub fn parse(s: &str) -> Result<Self, NbtPathParseError> {
+		fn key(s: &str) -> Result<(CompactString, &str), usize> {
+			if s.starts_with('"') || s.starts_with('\'') {
+				return s.snbt_string_read()
+			}
+			let end = s.find(|c: char| !is_unquoted_key_char(c)).unwrap_or(s.len());
+			if end == 0 {
+				return Err(s.len())
+			}
+			Ok((CompactString::from(&s[..end]), &s[end..]))
+		}
+
+		fn key_node(s: &str) -> Result<(NbtPathNode, &str), (usize, &'static str)> {
+			let (key, s) = key(s).map_err(|remaining| (remaining, "Expected a key"))?;
+			if s.starts_with('{') {
+				let (s, matcher) = NbtCompound::from_str0(s).map_err(|remaining| (remaining, "Invalid compound"))?;
+				Ok((NbtPathNode::MatchKey(key, matcher), s))
+			} else {
+				Ok((NbtPathNode::Key(key), s))
+			}
+		}
+
+		fn index_node(s: &str) -> Result<(NbtPathNode, &str), (usize, &'static str)> {
+			let s = s.strip_prefix('[').ok_or((s.len(), "Expected '['"))?;
+			if let Some(s) = s.strip_prefix(']') {
+				return Ok((NbtPathNode::AllElements, s))
+			}
+			let (node, s) = if s.starts_with('{') {
+				let (s, matcher) = NbtCompound::from_str0(s).map_err(|remaining| (remaining, "Invalid compound"))?;
+				(NbtPathNode::MatchElement(matcher), s)
+			} else {
+				let end = s.find(']').unwrap_or(s.len());
+				let idx = s[..end].parse::<isize>().map_err(|_| (s.len(), "Expected an index"))?;
+				(NbtPathNode::Index(idx), &s[end..])
+			};
+			let s = s.strip_prefix(']').ok_or((s.len(), "Expected ']'"))?;
+			Ok((node, s))
+		}
+
+		let s = s.trim();
+		let total_len = s.len();
+		let err = |(remaining, reason): (usize, &'static str)| NbtPathParseError { idx: total_len - remaining, reason };
+
+		let mut nodes = Vec::new();
+		let (node, mut rest) = if s.starts_with('{') {
+			let (rest, matcher) = NbtCompound::from_str0(s).map_err(|remaining| err((remaining, "Invalid compound")))?;
+			(NbtPathNode::MatchRoot(matcher), rest)
+		} else if s.starts_with('[') {
+			index_node(s).map_err(err)?
+		} else {
+			key_node(s).map_err(err)?
+		};
+		nodes.push(node);
+
+		while !rest.is_empty() {
+			let (node, rest2) = if let Some(rest2) = rest.strip_prefix('.') {
+				key_node(rest2).map_err(err)?
+			} else if rest.starts_with('[') {
+				index_node(rest).map_err(err)?
+			} else {
+				return Err(err((rest.len(), "Expected '.' or '['")))
+			};
+			nodes.push(node);
+			rest = rest2;
+		}
+
+		Ok(Self { nodes })
+	}
+
+	/// The path to the element at `indices`, or `None` if they don't point to one
+	#[must_use]
+	pub fn from_indices(root: &NbtElement, indices: &Indices) -> Option<Self> {
+		let mut nodes = Vec::with_capacity(indices.len());
+		let mut element = root;
+		for idx in indices {
+			let (key, child) = element.get(idx)?;
+			nodes.push(match key {
+				Some(key) => NbtPathNode::Key(CompactString::from(key)),
+				None => NbtPathNode::Index(idx.cast_signed()),
+			});
+			element = child;
+		}
+		if nodes.is_empty() {
+			// `{}` matches any compound, which is as close as paths get to pointing at the root
+			nodes.push(NbtPathNode::MatchRoot(NbtCompound::default()));
+		}
+		Some(Self { nodes })
+	}
+
+	/// Like [`NbtPath::from_indices`], but an element within a region is prefixed by its chunk's position in the region named `file_name`, like `r.0.0.mca[12,30] → Level.Sections[2]`
+	#[must_use]
+	pub fn display_from_indices(root: &NbtElement, indices: &Indices, file_name: &str) -> Option<String> {
+		if root.is_region() {
+			let Some((chunk_idx, rest)) = indices.split_first() else { return Some(file_name.to_owned()) };
+			let chunk = root.get(chunk_idx)?.1;
+			let prefix = chunk.as_chunk().map_or_else(|| format!("{file_name}[{chunk_idx}]"), |chunk| format!("{file_name}[{x},{z}]", x = chunk.x, z = chunk.z));
+			return if rest.is_root() { Some(prefix) } else { Self::from_indices(chunk, rest).map(|path| format!("{prefix} → {path}")) }
+		}
+		Self::from_indices(root, indices).map(|path| path.to_string())
+	}
+
+	#[must_use]
+	pub fn nodes(&self) -> &[NbtPathNode] { &self.nodes }
+
+	/// Just the first `len` nodes, or all of them if there aren't that many
+	#[must_use]
+	pub fn prefix(&self, len: usize) -> Self { Self { nodes: self.nodes[..len.min(self.nodes.len())].to_vec() } }
+
+	fn fmt_prefix(&self, f: &mut Formatter<'_>, len: usize) -> std::fmt::Result {
+		for (idx, node) in self.nodes[..len].iter().enumerate() {
+			if idx > 0 && node.is_key() {
+				write!(f, ".")?;
+			}
+			write!(f, "{node}")?;
+		}
+		Ok(())
+	}
+
+	/// The indices of the one element this path points to within `root`
+	///
+	/// # Errors
+	/// * If some segment couldn't be resolved, along with which one it was
+	pub fn resolve(&self, root: &NbtElement) -> Result<OwnedIndices, NbtPathResolveError> {
+		fn matches_compound(matcher: &NbtCompound, element: &NbtElement) -> bool {
+			if let Some(compound) = element.as_compound() {
+				matcher.matches(compound)
+			} else if let Some(chunk) = element.as_chunk() {
+				matcher.matches(chunk)
+			} else {
+				false
+			}
+		}
+
+		fn key_idx(element: &NbtElement, key: &str) -> Result<usize, NbtPathResolveErrorKind> {
+			let map = if let Some(compound) = element.as_compound() {
+				&compound.map
+			} else if let Some(chunk) = element.as_chunk() {
+				&chunk.map
+			} else {
+				return Err(NbtPathResolveErrorKind::NotKeyed(element.display_name()))
+			};
+			map.idx_of(key).ok_or(NbtPathResolveErrorKind::NoSuchKey)
+		}
+
+		fn index(element: &NbtElement, idx: isize) -> Result<usize, NbtPathResolveErrorKind> {
+			let len = element.len().filter(|_| !element.has_keys()).ok_or(NbtPathResolveErrorKind::NotIndexable(element.display_name()))?;
+			let resolved = if idx < 0 { len.checked_sub(idx.unsigned_abs()) } else { Some(idx.unsigned_abs()) };
+			resolved.filter(|&idx| idx < len).ok_or(NbtPathResolveErrorKind::IndexOutOfBounds { idx, len })
+		}
+
+		let mut indices = OwnedIndices::new();
+		let mut element = root;
+		for (n, node) in self.nodes.iter().enumerate() {
+			let idx = match node {
+				NbtPathNode::MatchRoot(matcher) => {
+					if matches_compound(matcher, element) {
+						continue
+					}
+					Err(NbtPathResolveErrorKind::NoMatch)
+				}
+				NbtPathNode::Key(key) => key_idx(element, key),
+				NbtPathNode::MatchKey(key, matcher) => key_idx(element, key).and_then(|idx| if element.get(idx).is_some_and(|(_, child)| matches_compound(matcher, child)) { Ok(idx) } else { Err(NbtPathResolveErrorKind::NoMatch) }),
+				&NbtPathNode::Index(idx) => index(element, idx),
+				NbtPathNode::AllElements => Err(NbtPathResolveErrorKind::AllElements),
+				NbtPathNode::MatchElement(matcher) => match element.children() {
+					Some(Ok(mut children)) => children.position(|child| matches_compound(matcher, child)).ok_or(NbtPathResolveErrorKind::NoMatch),
+					_ => Err(NbtPathResolveErrorKind::NotIndexable(element.display_name())),
+				},
+			};
+			let (idx, child) = match idx.map(|idx| (idx, element.get(idx))) {
+				Ok((idx, Some((_, child)))) => (idx, child),
+				Ok((idx, None)) => return Err(self.resolve_error(n, NbtPathResolveErrorKind::IndexOutOfBounds { idx: idx.cast_signed(), len: element.len().unwrap_or(0) })),
+				Err(kind) => return Err(self.resolve_error(n, kind)),
+			};
+			indices.push(idx);
+			element = child;
+		}
+
+		Ok(indices)
+	}
+
+	fn resolve_error(&self, n: usize, kind: NbtPathResolveErrorKind) -> NbtPathResolveError {
+		struct Prefix<'a>(&'a NbtPath, usize);
+
+		impl Display for Prefix<'_> {
+			fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { self.0.fmt_prefix(f, self.1) }
+		}
+
+		NbtPathResolveError {
+			segment: self.nodes[n].to_string(),
+			resolved: if n == 0 { "the root".to_owned() } else { format!("`{}`", Prefix(self, n)) },
+			kind,
+		}
+	}
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{reason} at column {column}", column = self.idx + 1)]
+pub struct NbtPathParseError {
+	pub idx: usize,
+	pub reason: &'static str,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Couldn't resolve `{segment}` under {resolved}: {kind}")]
+pub struct NbtPathResolveError {
+	pub segment: String,
+	pub resolved: String,
+	pub kind: NbtPathResolveErrorKind,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum NbtPathResolveErrorKind {
+	#[error("there is no such key")]
+	NoSuchKey,
+	#[error("a {0} has no keys")]
+	NotKeyed(&'static str),
+	#[error("a {0} can't be indexed")]
+	NotIndexable(&'static str),
+	#[error("index {idx} is out of bounds for a length of {len}")]
+	IndexOutOfBounds { idx: isize, len: usize },
+	#[error("nothing matched")]
+	NoMatch,
+	#[error("`[]` is every element rather than one")]
+	AllElements,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_and_resolve() {
+		let root = NbtElement::from_str(r#"{Data:{Player:{Inventory:[{Slot:0b,id:"minecraft:stone"},{Slot:1b,id:"minecraft:dirt",tag:{display:{Name:"x"}}}],"odd key":[I;1,2,3]}}}"#).unwrap().1;
+
+		// the key and value of the element resolved to, since the order of keys depends on the sort
+		let resolve = |s: &str| {
+			let indices = NbtPath::parse(s).unwrap_or_else(|e| panic!("{e}")).resolve(&root)?;
+			let (key, value) = root.get_kv_under_indices(&indices).expect("indices are valid");
+			Ok::<_, NbtPathResolveError>((key.map(str::to_owned), value.to_string()))
+		};
+		assert_eq!(resolve("Data.Player.Inventory[1].tag.display.Name").unwrap(), (Some("Name".to_owned()), "x".to_owned()));
+		assert_eq!(resolve("Data.Player.Inventory[-2].id").unwrap(), (Some("id".to_owned()), r#""minecraft:stone""#.to_owned()));
+		assert_eq!(resolve(r#"Data.Player.Inventory[{id:"minecraft:dirt"}].Slot"#).unwrap(), (Some("Slot".to_owned()), "1b".to_owned()));
+		assert_eq!(resolve(r#"{Data:{}}.Data.Player."odd key"[2]"#).unwrap(), (None, "3".to_owned()));
+		assert_eq!(resolve("Data.Player{Inventory:[{Slot:1b}]}").unwrap().0.as_deref(), Some("Player"));
+
+		let e = resolve("Data.Player.Armor[0]").unwrap_err();
+		assert_eq!(e.segment, "Armor");
+		assert_eq!(e.resolved, "`Data.Player`");
+		assert_eq!(e.kind, NbtPathResolveErrorKind::NoSuchKey);
+		assert_eq!(resolve("Data.Player.Inventory[2]").unwrap_err().kind, NbtPathResolveErrorKind::IndexOutOfBounds { idx: 2, len: 2 });
+		assert_eq!(resolve("Data.Player.Inventory[]").unwrap_err().kind, NbtPathResolveErrorKind::AllElements);
+		assert_eq!(resolve("Data.Player.Inventory.Slot").unwrap_err().kind, NbtPathResolveErrorKind::NotKeyed("List"));
+
+		assert_eq!(NbtPath::parse(r#"a."b.c"[0][{x:1b}]"#).map(|path| path.to_string()).ok().as_deref(), Some(r#"a."b.c"[0][{x:1b}]"#));
+		assert_eq!(NbtPath::parse("a..b").err().map(|e| e.idx), Some(2));
+		assert_eq!(NbtPath::parse("a[x]").err().map(|e| e.idx), Some(2));
+		assert!(NbtPath::parse("").is_err());
+	}
+
+	#[test]
+	fn test_from_indices() {
+		let root = NbtElement::from_str(r#"{"a.b":{"c d":[{e:1b}]},f:2b}"#).unwrap().1;
+		let idx = |key: &str| root.children().and_then(Result::err).and_then(|mut children| children.position(|entry| entry.key == key)).unwrap();
+
+		let path = NbtPath::display_from_indices(&root, &OwnedIndices::from(vec![idx("a.b"), 0, 0, 0]), "level.dat").unwrap();
+		assert_eq!(path, r#""a.b"."c d"[0].e"#);
+		assert_eq!(NbtPath::parse(&path).unwrap().resolve(&root).unwrap().iter().collect::<Vec<_>>(), vec![idx("a.b"), 0, 0, 0]);
+		assert_eq!(NbtPath::display_from_indices(&root, &OwnedIndices::new(), "level.dat").as_deref(), Some("{}"));
+		assert_eq!(NbtPath::display_from_indices(&root, &OwnedIndices::from(vec![9]), "level.dat"), None);
+	}
+}
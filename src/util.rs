@@ -7,13 +7,17 @@ use std::{
 	iter,
 	mem::MaybeUninit,
 	ops::{Add, Sub},
+	path::Path,
 };
 use std::ops::{AddAssign, Div, DivAssign, Mul, MulAssign, SubAssign};
 use compact_str::{CompactString, ToCompactString};
 use regex::{Regex, RegexBuilder};
 use thiserror::Error;
 use winit::dpi::{PhysicalPosition, PhysicalSize};
-use crate::render::vertex_buffer_builder::VertexBufferBuilder;
+use crate::{
+	elements::{float_display::strip_non_finite, snbt_error},
+	render::vertex_buffer_builder::VertexBufferBuilder,
+};
 #[cfg(target_arch = "wasm32")]
 pub use crate::wasm::{get_clipboard, set_clipboard};
 
@@ -49,7 +53,10 @@ impl Timestamp {
 	}
 
 	#[must_use]
-	pub fn elapsed(self) -> Duration { self - Self::UNIX_EPOCH }
+	pub fn elapsed(self) -> Duration { Self::now().saturating_sub(self) }
+
+	#[must_use]
+	pub const fn since_epoch(self) -> Duration { self.since_epoch }
 
 	#[must_use]
 	pub const fn saturating_sub(self, rhs: Self) -> Duration { self.since_epoch.saturating_sub(rhs.since_epoch) }
@@ -73,8 +80,61 @@ impl Add<Duration> for Timestamp {
 	fn add(self, rhs: Duration) -> Self::Output { Self { since_epoch: self.since_epoch + rhs } }
 }
 
+/// `secs` since the unix epoch as a UTC date and time, such as `2024-05-01 12:34:56 UTC`
 #[must_use]
-pub fn create_regex(mut str: String, case_sensitive: bool) -> Option<Regex> {
+pub fn format_unix_time(secs: u64) -> String {
+	let (days, secs) = (secs / 86400, secs % 86400);
+	// shifted so years start in March, which puts the leap day at the end of them
+	let days = days + 719_468;
+	let era = days / 146_097;
+	let day_of_era = days % 146_097;
+	let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let month = (5 * day_of_year + 2) / 153;
+	let day = day_of_year - (153 * month + 2) / 5 + 1;
+	let month = if month < 10 { month + 3 } else { month - 9 };
+	let year = year_of_era + era * 400 + u64::from(month <= 2);
+	format!("{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// The seconds since the unix epoch of a UTC date as [`format_unix_time`] writes it, where the time and `UTC` can be left off, or of a plain number of seconds
+#[must_use]
+pub fn parse_unix_time(text: &str) -> Option<u64> {
+	let text = text.trim();
+	if let Ok(secs) = text.parse::<u64>() {
+		return Some(secs)
+	}
+	let text = text.strip_suffix("UTC").unwrap_or(text).trim_end();
+	let (date, time) = text.split_once([' ', 'T']).unwrap_or((text, "00:00:00"));
+	let mut date = date.splitn(3, '-').map(str::parse::<u64>);
+	let (Some(Ok(year)), Some(Ok(month)), Some(Ok(day))) = (date.next(), date.next(), date.next()) else { return None };
+	let mut time = time.trim().splitn(3, ':').map(str::parse::<u64>);
+	let (hour, minute, second) = (time.next().unwrap_or(Ok(0)).ok()?, time.next().unwrap_or(Ok(0)).ok()?, time.next().unwrap_or(Ok(0)).ok()?);
+	let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+	let days_in_month = match month {
+		2 if leap => 29,
+		2 => 28,
+		4 | 6 | 9 | 11 => 30,
+		1..=12 => 31,
+		_ => return None,
+	};
+	if year < 1970 || !(1..=days_in_month).contains(&day) || hour >= 24 || minute >= 60 || second >= 60 {
+		return None
+	}
+	// the inverse of `format_unix_time`, from years starting in March
+	let year = if month <= 2 { year - 1 } else { year };
+	let (era, year_of_era) = (year / 400, year % 400);
+	let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+	let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+	let days = (era * 146_097 + day_of_era).checked_sub(719_468)?;
+	Some(days * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+/// Builds a regex from either a bare pattern or one in `/pattern/flags` form.
+///
+/// # Errors
+/// * If one of the flags is unknown or the pattern doesn't compile
+pub fn create_regex(mut str: String, case_sensitive: bool) -> Result<Regex, CreateRegexError> {
 	let flags = 'a: {
 		if !str.starts_with("/") {
 			break 'a 0;
@@ -91,8 +151,9 @@ pub fn create_regex(mut str: String, case_sensitive: bool) -> Option<Regex> {
 				's' => flags |= 0b001000,
 				'u' => flags |= 0b010000,
 				'y' => flags |= 0b100000,
+				'U' => flags |= 0b100_0000,
 				'/' => break,
-				_ => return None,
+				_ => return Err(CreateRegexError::UnknownFlag(char)),
 			}
 		}
 		flags
@@ -102,15 +163,40 @@ pub fn create_regex(mut str: String, case_sensitive: bool) -> Option<Regex> {
 		.case_insensitive((flags & 0b1 > 0) || !case_sensitive)
 		.multi_line(flags & 0b100 > 0)
 		.dot_matches_new_line(flags & 0b1000 > 0)
-		.unicode(flags & 0b10000 > 0)
-		.swap_greed(flags & 0b10000 > 0)
+		// without unicode, `.` can match invalid UTF-8 which string regexes refuse to build, so `u` is always on
+		.unicode(true)
+		.swap_greed(flags & 0b100_0000 > 0)
 		.build()
-		.ok()
+		.map_err(CreateRegexError::from)
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum CreateRegexError {
+	#[error("Unknown regex flag '{0}'")]
+	UnknownFlag(char),
+	#[error("{0}")]
+	Syntax(String),
+}
+
+impl From<regex::Error> for CreateRegexError {
+	fn from(value: regex::Error) -> Self {
+		// syntax errors draw the pattern with a caret under it before the actual message, which is all that fits in the search box
+		let message = value.to_string();
+		Self::Syntax(message.lines().last().map_or(message.as_str(), |line| line.trim_start_matches("error: ")).to_owned())
+	}
 }
 
 #[must_use]
 pub fn split_lines<const MAX_WIDTH: usize>(s: String) -> Vec<String> {
 	let mut lines = Vec::new();
+	// a new line in the text always starts one, so what's under it stays lined up
+	for line in s.split('\n') {
+		split_line::<MAX_WIDTH>(line, &mut lines);
+	}
+	lines
+}
+
+fn split_line<const MAX_WIDTH: usize>(s: &str, lines: &mut Vec<String>) {
 	let mut current_line = String::new();
 	let mut is_previous_byte_ascii_whitespace = true;
 	for word in s
@@ -133,8 +219,24 @@ pub fn split_lines<const MAX_WIDTH: usize>(s: String) -> Vec<String> {
 	if !trimmed.is_empty() {
 		lines.push(trimmed.to_string());
 	}
+}
 
-	lines
+/// The first line of `text`, cut short with `ellipsis` if it's wider than `width`
+#[must_use]
+pub fn truncate_to_width(text: &str, width: usize, ellipsis: &str) -> String {
+	let line = text.split('\n').next().unwrap_or_default();
+	if line.width() <= width && line.len() == text.len() {
+		return line.to_owned()
+	}
+	let mut total = ellipsis.width();
+	let end = line
+		.char_indices()
+		.find(|&(_, char)| {
+			total += char.width();
+			total > width
+		})
+		.map_or(line.len(), |(idx, _)| idx);
+	format!("{}{ellipsis}", &line[..end])
 }
 
 #[must_use]
@@ -156,18 +258,35 @@ pub fn nth(n: usize) -> String {
 	buf
 }
 
+/// The hyphenated form of the UUID Minecraft stores as four ints, most significant first
 #[must_use]
-pub fn encompasses_or_equal<T: Ord>(outer: &[T], inner: &[T]) -> bool { outer.len() <= inner.len() && outer == &inner[..outer.len()] }
+pub fn uuid_to_string(ints: [i32; 4]) -> String {
+	let uuid = ints.into_iter().fold(0_u128, |uuid, int| (uuid << 32) | u128::from(int.cast_unsigned()));
+	format!("{:08x}-{:04x}-{:04x}-{:04x}-{:012x}", uuid >> 96, (uuid >> 80) & 0xFFFF, (uuid >> 64) & 0xFFFF, (uuid >> 48) & 0xFFFF, uuid & 0xFFFF_FFFF_FFFF)
+}
 
+/// The four ints of a hyphenated UUID, see [`uuid_to_string`]
 #[must_use]
-pub fn encompasses<T: Ord>(outer: &[T], inner: &[T]) -> bool { outer.len() < inner.len() && outer == &inner[..outer.len()] }
+#[allow(clippy::cast_possible_truncation)]
+pub fn parse_uuid(s: &str) -> Option<[i32; 4]> {
+	let s = s.trim();
+	let groups = s.split('-').collect::<Vec<_>>();
+	if groups.iter().map(|group| group.len()).ne([8, 4, 4, 4, 12]) || !groups.iter().all(|group| group.bytes().all(|b| b.is_ascii_hexdigit())) {
+		return None
+	}
+	let uuid = u128::from_str_radix(&groups.concat(), 16).ok()?;
+	Some([(uuid >> 96) as u32, (uuid >> 64) as u32, (uuid >> 32) as u32, uuid as u32].map(u32::cast_signed))
+}
 
 #[must_use]
-pub const fn is_utf8_char_boundary(x: u8) -> bool { (x as i8) >= -0x40 }
+pub fn encompasses_or_equal<T: Ord>(outer: &[T], inner: &[T]) -> bool { outer.len() <= inner.len() && outer == &inner[..outer.len()] }
+
+#[must_use]
+pub fn encompasses<T: Ord>(outer: &[T], inner: &[T]) -> bool { outer.len() < inner.len() && outer == &inner[..outer.len()] }
 
 // importantly, no underscores
 #[must_use]
-pub fn is_jump_char_boundary(x: u8) -> bool { b" \t\r\n/\\()\"'-.,:;<>~!@#$%^&*|+=[]{}~?|".contains(&x) }
+pub fn is_jump_char_boundary(x: char) -> bool { " \t\r\n/\\()\"'-.,:;<>~!@#$%^&*|+=[]{}~?|".contains(x) }
 
 pub struct SinglyLinkedNode<T> {
 	value: T,
@@ -394,6 +513,18 @@ impl<T> LinkedQueue<T> {
 		}
 	}
 
+	/// Takes the value pushed the longest ago, walking the whole queue to get to it
+	#[must_use]
+	pub fn pop_oldest(&mut self) -> Option<T> {
+		let mut node = &mut self.tail;
+		while node.as_ref()?.prev.is_some() {
+			node = &mut node.as_mut()?.prev;
+		}
+		let box SinglyLinkedNode { value, .. } = node.take()?;
+		self.len -= 1;
+		Some(value)
+	}
+
 	#[must_use]
 	pub fn get(&self) -> Option<&T> { self.tail.as_ref().map(|x| &x.value) }
 
@@ -506,7 +637,7 @@ impl StrExt for str {
 									sub += 2;
 								}
 							} else {
-								return Err(self.len() - idx);
+								return Err(snbt_error::expected(self.len() - idx, "a valid escape"));
 							}
 						} else if byte == b'u' {
 							if let Ok([(_, _), (_, b), (_, c), _]) = iter.next_chunk::<4>()
@@ -523,7 +654,7 @@ impl StrExt for str {
 									sub += 3;
 								}
 							} else {
-								return Err(self.len() - idx);
+								return Err(snbt_error::expected(self.len() - idx, "a valid escape"));
 							}
 						} else {
 							sub += 1;
@@ -541,6 +672,8 @@ impl StrExt for str {
 						backslash = false;
 					}
 				}
+				// it runs to the end of the text, which is where the quote's missing
+				let _ = snbt_error::expected(0, "a closing quote");
 				return Err(self.len());
 			};
 			let mut out = CompactString::with_capacity(len);
@@ -673,7 +806,9 @@ impl StrExt for str {
 		}
 	}
 
-	fn needs_escape(&self) -> bool { self.as_bytes().first().copied().is_some_and(valid_starting_char) || !self.bytes().all(valid_unescaped_char) }
+	fn needs_escape(&self) -> bool {
+		!self.as_bytes().first().copied().is_some_and(valid_starting_char) || !self.bytes().all(valid_unescaped_char) || matches!(self, "true" | "false") || strip_non_finite(self).is_some_and(|(rest, ..)| rest.is_empty())
+	}
 
 	fn width(&self) -> usize { self.chars().map(CharExt::width).sum() }
 
@@ -767,14 +902,18 @@ pub fn reorder<T>(data: &mut [T], mapping: impl Into<Box<[usize]>>) -> Result<()
 	}
 
 	for current_idx in 0..len {
-		let old_idx = current_idx;
-		let new_idx = mapping[current_idx];
+		// each swap puts one element where it belongs, so it takes a swap per element of the cycle `current_idx` is in
+		// (an element which another is already in the place of means the mapping isn't a permutation, so it stops there)
+		while mapping[current_idx] != current_idx && mapping[mapping[current_idx]] != mapping[current_idx] {
+			let old_idx = current_idx;
+			let new_idx = mapping[current_idx];
 
-		unsafe { core::hint::assert_unchecked(new_idx < len) }
-		unsafe { core::hint::assert_unchecked(old_idx < len) }
+			unsafe { core::hint::assert_unchecked(new_idx < len) }
+			unsafe { core::hint::assert_unchecked(old_idx < len) }
 
-		data.swap(old_idx, new_idx);
-		mapping.swap(old_idx, new_idx);
+			data.swap(old_idx, new_idx);
+			mapping.swap(old_idx, new_idx);
+		}
 	}
 
 	Ok(())
@@ -890,6 +1029,61 @@ pub fn open_file(str: &str) -> anyhow::Result<std::process::ExitStatus> {
 	return Ok(std::process::Command::new("xdg-open").arg(str).status()?);
 }
 
+/// Writes to a temporary file next to `path` and renames it over the original once it is fully on disk, so an interrupted save never leaves a truncated file behind.
+///
+/// # Errors
+/// * If the temporary file can't be written or synced
+/// * If the temporary file can't replace `path`, the original is left untouched
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> anyhow::Result<()> {
+	use std::io::Write as _;
+
+	write_atomic_with(path, |file| Ok(file.write_all(bytes)?))
+}
+
+/// [`write_atomic`], but `write` streams the contents into the temporary file itself
+///
+/// # Errors
+/// * If `write` fails, or the temporary file can't be created or synced
+/// * If the temporary file can't replace `path`, the original is left untouched
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write_atomic_with(path: &Path, write: impl FnOnce(&mut std::fs::File) -> anyhow::Result<()>) -> anyhow::Result<()> {
+	use anyhow::Context;
+
+	let name = path.file_name().context("Path has no file name")?.to_string_lossy();
+	let temp_path = path.with_file_name(format!(".{name}.{pid}.tmp", pid = std::process::id()));
+	let result = (|| {
+		let mut file = std::fs::File::create(&temp_path).with_context(|| format!("Failed to create {}", temp_path.display()))?;
+		write(&mut file).with_context(|| format!("Failed to write {}", temp_path.display()))?;
+		file.sync_all().with_context(|| format!("Failed to sync {}", temp_path.display()))?;
+		drop(file);
+		replace_file(&temp_path, path).with_context(|| format!("Failed to replace {}", path.display()))
+	})();
+	if result.is_err() {
+		let _ = std::fs::remove_file(&temp_path);
+	}
+	result
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn replace_file(from: &Path, to: &Path) -> std::io::Result<()> {
+	#[cfg(target_os = "windows")]
+	if to.exists() {
+		use std::os::windows::ffi::OsStrExt as _;
+
+		use winapi::um::winbase::{REPLACEFILE_IGNORE_MERGE_ERRORS, ReplaceFileW};
+
+		let to_wide = to.as_os_str().encode_wide().chain(core::iter::once(0)).collect::<Vec<u16>>();
+		let from_wide = from.as_os_str().encode_wide().chain(core::iter::once(0)).collect::<Vec<u16>>();
+		// keeps the attributes and ACLs of the original, unlike a plain rename
+		if unsafe { ReplaceFileW(to_wide.as_ptr(), from_wide.as_ptr(), core::ptr::null(), REPLACEFILE_IGNORE_MERGE_ERRORS, core::ptr::null_mut(), core::ptr::null_mut()) } != 0 {
+			return Ok(())
+		}
+	}
+	// on windows this is `MoveFileExW` with `MOVEFILE_REPLACE_EXISTING`, on unix `rename` already replaces atomically
+	std::fs::rename(from, to)
+}
+
 #[derive(Copy, Clone, Eq)]
 pub struct Vec2u {
 	pub x: usize,
@@ -1256,5 +1450,65 @@ mod tests {
 		assert_eq!(reorder(vec![1, 2, 3], &[2, 0, 1]), vec![2, 3, 1]);
 		assert_eq!(reorder(vec![1, 2, 3, 4, 5], &[0, 1, 2, 3, 4]), vec![1, 2, 3, 4, 5]);
 		assert_eq!(reorder(vec![1, 2, 3, 4], &[3, 2, 1, 0]), vec![4, 3, 2, 1]);
+		// a cycle longer than the first swap fixes
+		assert_eq!(reorder(vec!['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i'], &[1, 6, 7, 8, 5, 3, 2, 4, 0]), vec!['i', 'a', 'g', 'f', 'h', 'e', 'b', 'c', 'd']);
+	}
+
+	#[test]
+	fn test_write_atomic() {
+		let dir = std::env::temp_dir().join(format!("nbtworkbench-test-write-atomic-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).expect("Created temp dir");
+		let path = dir.join("level.dat");
+
+		super::write_atomic(&path, b"old").expect("Wrote new file");
+		super::write_atomic(&path, b"new").expect("Replaced existing file");
+		assert_eq!(std::fs::read(&path).expect("Read file"), b"new");
+		// only the target remains, no leftover temporary files
+		assert_eq!(std::fs::read_dir(&dir).expect("Read temp dir").count(), 1);
+
+		let _ = std::fs::remove_dir_all(&dir);
+	}
+
+	#[test]
+	fn test_uuid() {
+		use super::{parse_uuid, uuid_to_string};
+
+		let ints = [-1_170_400_740, 1_183_140_687, -1_684_127_252, 1_014_918_861];
+		assert_eq!(uuid_to_string(ints), "ba3d1a1c-4685-4b4f-9b9e-41ec3c7e6ecd");
+		assert_eq!(parse_uuid("BA3D1A1C-4685-4B4F-9B9E-41EC3C7E6ECD"), Some(ints));
+		assert_eq!(parse_uuid("ba3d1a1c46854b4f9b9e41ec3c7e6ecd"), None);
+		assert_eq!(parse_uuid("ba3d1a1c-4685-4b4f-9b9e-41ec3c7e6ecg"), None);
+	}
+
+	#[test]
+	fn test_create_regex() {
+		use super::{CreateRegexError, create_regex};
+
+		let regex = create_regex("/minecraft:oak_(.*)/g".to_owned(), true).expect("Valid regex");
+		assert_eq!(regex.replace_all("minecraft:oak_log", "minecraft:birch_$1"), "minecraft:birch_log");
+		assert!(!regex.is_match("minecraft:OAK_log"));
+		assert!(create_regex("minecraft:oak_".to_owned(), false).expect("Valid regex").is_match("minecraft:OAK_log"));
+		assert_eq!(create_regex("/oak_(.*)_/U".to_owned(), true).expect("Valid regex").find("oak_log_with_bark_").map(|m| m.as_str()), Some("oak_log_"));
+
+		assert_matches!(create_regex("/oak/q".to_owned(), true), Err(CreateRegexError::UnknownFlag('q')));
+		let e = create_regex("minecraft:(oak".to_owned(), true).expect_err("Unclosed group");
+		assert_matches!(&e, CreateRegexError::Syntax(_));
+		assert_eq!(e.to_string(), "unclosed group");
+	}
+
+	#[test]
+	fn test_unix_time() {
+		use super::{format_unix_time, parse_unix_time};
+
+		assert_eq!(format_unix_time(0), "1970-01-01 00:00:00 UTC");
+		assert_eq!(format_unix_time(1_709_210_096), "2024-02-29 12:34:56 UTC");
+		assert_eq!(format_unix_time(u64::from(u32::MAX)), "2106-02-07 06:28:15 UTC");
+		assert_eq!(parse_unix_time("2024-02-29 12:34:56 UTC"), Some(1_709_210_096));
+		assert_eq!(parse_unix_time("2024-02-29T12:34:56"), Some(1_709_210_096));
+		assert_eq!(parse_unix_time("2024-02-29"), Some(1_709_164_800));
+		assert_eq!(parse_unix_time(" 1709210096 "), Some(1_709_210_096));
+		assert_eq!(parse_unix_time("2023-02-29"), None);
+		assert_eq!(parse_unix_time("1969-12-31"), None);
+		assert_eq!(parse_unix_time("2024-02-29 24:00"), None);
 	}
 }
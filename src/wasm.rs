@@ -60,6 +60,53 @@ pub fn open_file(name: String, bytes: Vec<u8>) {
 	workbench.on_open_file(name.as_str().as_ref(), bytes).alert_err(&mut workbench.alerts);
 }
 
+/// Fetches `url` and opens it once it's done, any redirects are followed by the browser
+pub fn open_url(url: String) {
+	wasm_bindgen_futures::spawn_local(async move {
+		let result = fetch(&url).await;
+		let workbench = unsafe { &mut WORKBENCH };
+		match result {
+			Ok(bytes) => {
+				workbench.on_open_url(url, bytes).alert_err(&mut workbench.alerts);
+			}
+			Err(e) => workbench.alerts.alert(Alert::error(e)),
+		}
+	});
+}
+
+async fn fetch(url: &str) -> anyhow::Result<Vec<u8>> {
+	use anyhow::{Context, anyhow, bail};
+	use wasm_bindgen::JsCast;
+	use wasm_bindgen_futures::JsFuture;
+
+	use crate::workbench::tab::Tab;
+
+	let window = web_sys::window().context("Could not find the window")?;
+	let response = JsFuture::from(window.fetch_with_str(url))
+		.await
+		.and_then(|response| response.dyn_into::<web_sys::Response>())
+		.map_err(|e| anyhow!("Could not download {url}: {e:?}"))?;
+	if !response.ok() {
+		bail!("{url} responded with {} {}", response.status(), response.status_text());
+	}
+	let too_large = || anyhow!("{url} is larger than the {}MiB limit for downloads", Tab::MAX_DOWNLOAD_SIZE / 1_048_576);
+	if let Ok(Some(len)) = response.headers().get("Content-Length")
+		&& len.parse::<u64>().is_ok_and(|len| len > Tab::MAX_DOWNLOAD_SIZE)
+	{
+		return Err(too_large())
+	}
+	let buffer = match response.array_buffer() {
+		Ok(promise) => JsFuture::from(promise).await,
+		Err(e) => Err(e),
+	}
+	.map_err(|e| anyhow!("Could not download {url}: {e:?}"))?;
+	let bytes = js_sys::Uint8Array::new(&buffer);
+	if u64::from(bytes.length()) > Tab::MAX_DOWNLOAD_SIZE {
+		return Err(too_large())
+	}
+	Ok(bytes.to_vec())
+}
+
 #[wasm_bindgen]
 pub fn close() -> usize { unsafe { WORKBENCH.close() } }
 
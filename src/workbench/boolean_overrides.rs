@@ -0,0 +1,79 @@
+use crate::{
+	config,
+	elements::element::NbtElement,
+	tree::{
+		CallbackInfo,
+		indices::{Indices, OwnedIndices},
+	},
+};
+
+/// Bytes shown as booleans when the config says to show them as numbers, or the other way around
+///
+/// Only ever differing from the config, since an override matching it would change nothing
+#[derive(Default, Clone)]
+pub struct BooleanOverrides {
+	overrides: Vec<(OwnedIndices, bool)>,
+}
+
+impl BooleanOverrides {
+	#[must_use]
+	pub const fn new() -> Self { Self { overrides: Vec::new() } }
+
+	#[must_use]
+	pub fn get(&self, indices: &Indices) -> Option<bool> { self.overrides.iter().find(|(overridden, _)| overridden.iter().eq(indices.iter())).map(|&(_, as_boolean)| as_boolean) }
+
+	/// Whether the byte at `indices` is shown as a boolean, if it's 0 or 1
+	#[must_use]
+	pub fn shows_boolean(&self, indices: &Indices) -> bool { self.get(indices).unwrap_or_else(config::get_bytes_as_booleans) }
+
+	/// Shows the byte at `indices` the other way to how it's shown now, returning whether it's now shown as a boolean
+	pub fn toggle(&mut self, indices: OwnedIndices) -> bool {
+		let as_boolean = !self.shows_boolean(&indices);
+		self.overrides.retain(|(overridden, _)| !overridden.iter().eq(indices.iter()));
+		if as_boolean != config::get_bytes_as_booleans() {
+			self.overrides.push((indices, as_boolean));
+		}
+		as_boolean
+	}
+
+	/// Keeps up with the tree changing, see [`MutableIndices::apply`](crate::tree::MutableIndices::apply)
+	pub fn remap<F: FnMut(&mut OwnedIndices, &mut CallbackInfo)>(&mut self, mut f: F) {
+		self.overrides.retain_mut(|(indices, _)| {
+			let mut ci = CallbackInfo::new();
+			f(indices, &mut ci);
+			!ci.removed()
+		});
+	}
+
+	/// The true line number of each overridden element with how it's shown, in order
+	#[must_use]
+	pub fn true_lines(&self, root: &NbtElement) -> Vec<(usize, bool)> {
+		let mut lines = self.overrides.iter().filter_map(|(indices, as_boolean)| root.navigate(indices).ok().map(|info| (info.true_line_number, *as_boolean))).collect::<Vec<_>>();
+		lines.sort_unstable_by_key(|&(true_line_number, _)| true_line_number);
+		lines
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_boolean_overrides() {
+		let (_, root) = NbtElement::from_str("{a:1b,b:0b}").expect("Valid SNBT");
+		let idx = |key: &str| root.as_compound().and_then(|compound| compound.map.idx_of(key)).expect("Key exists");
+		let mut overrides = BooleanOverrides::new();
+		assert!(overrides.toggle(OwnedIndices::from([idx("b")])));
+		assert!(overrides.shows_boolean(&OwnedIndices::from([idx("b")])));
+		assert!(!overrides.shows_boolean(&OwnedIndices::from([idx("a")])));
+		assert_eq!(overrides.true_lines(&root), [(idx("b") + 2, true)]);
+
+		// back to how the config shows it, so there's nothing to remember
+		assert!(overrides.toggle(OwnedIndices::from([idx("a")])));
+		assert!(!overrides.toggle(OwnedIndices::from([idx("a")])));
+		assert_eq!(overrides.overrides.len(), 1);
+
+		overrides.remap(|_, ci| ci.remove());
+		assert!(overrides.overrides.is_empty());
+	}
+}
@@ -8,6 +8,7 @@ use notify::{EventKind, PollWatcher, RecursiveMode, Watcher};
 #[cfg(not(target_arch = "wasm32"))]
 use crate::render::assets::{OPEN_ARRAY_IN_HEX_UV, OPEN_IN_TXT_UV};
 use crate::{
+	config,
 	elements::{
 		NbtElementVariant,
 		array::{NbtByteArray, NbtIntArray, NbtLongArray},
@@ -21,19 +22,21 @@ use crate::{
 		list::NbtList,
 		long::NbtLong,
 		short::NbtShort,
+		snbt_error::SnbtError,
 		string::NbtString,
 	},
 	history::WorkbenchAction,
 	render::{
-		assets::{ACTION_WHEEL_Z, COPY_FORMATTED_UV, COPY_RAW_UV, INSERT_FROM_CLIPBOARD_UV, INVERT_BOOKMARKS_UV, SORT_COMPOUND_BY_NAME_UV, SORT_COMPOUND_BY_TYPE_UV},
+		assets::{ACTION_WHEEL_Z, COPY_FORMATTED_UV, COPY_JSON_UV, COPY_PATH_UV, COPY_RAW_UV, DELETE_CORRUPTED_CHUNK_UV, INSERT_FROM_CLIPBOARD_UV, INVERT_BOOKMARKS_UV, SORT_COMPOUND_BY_NAME_UV, SORT_COMPOUND_BY_TYPE_UV},
 		vertex_buffer_builder::VertexBufferBuilder,
 	},
 	serialization::encoder::UncheckedBufWriter,
 	tree::{
 		MutableIndices,
-		actions::{add::add_element, reorder::reorder_element},
+		actions::{add::add_element, decode::decode_chunk_at, remove::remove_element, reorder::reorder_element},
 		indices::OwnedIndices,
 		navigate::NavigationInformation,
+		path::NbtPath,
 	},
 	util::{StrExt, Timestamp, get_clipboard, set_clipboard},
 	workbench::{
@@ -47,6 +50,8 @@ use crate::{
 pub enum ElementAction {
 	CopyRaw,
 	CopyFormatted,
+	CopyJson,
+	CopyPath,
 	#[cfg(not(target_arch = "wasm32"))]
 	OpenArrayInHex,
 	#[cfg(not(target_arch = "wasm32"))]
@@ -55,6 +60,8 @@ pub enum ElementAction {
 	SortCompoundByType,
 	InsertFromClipboard,
 	InvertBookmarks,
+	/// Only for chunks which couldn't be read, which are otherwise written back as they were
+	DeleteCorruptedChunk,
 }
 
 impl ElementAction {
@@ -73,6 +80,18 @@ impl ElementAction {
 					builder.draw_tooltip(&["Copy formatted snbt to clipboard"], pos, false);
 				}
 			}
+			Self::CopyJson => {
+				builder.draw_texture_z(pos, ACTION_WHEEL_Z, COPY_JSON_UV, (10, 10));
+				if hovered {
+					builder.draw_tooltip(&["Copy json to clipboard"], pos, false);
+				}
+			}
+			Self::CopyPath => {
+				builder.draw_texture_z(pos, ACTION_WHEEL_Z, COPY_PATH_UV, (10, 10));
+				if hovered {
+					builder.draw_tooltip(&["Copy NBT path to clipboard"], pos, false);
+				}
+			}
 			#[cfg(not(target_arch = "wasm32"))]
 			Self::OpenArrayInHex => {
 				builder.draw_texture_z(pos, ACTION_WHEEL_Z, OPEN_ARRAY_IN_HEX_UV, (10, 10));
@@ -111,11 +130,30 @@ impl ElementAction {
 					builder.draw_tooltip(&["Invert bookmarks"], pos, false);
 				}
 			}
+			Self::DeleteCorruptedChunk => {
+				builder.draw_texture_z(pos, ACTION_WHEEL_Z, DELETE_CORRUPTED_CHUNK_UV, (10, 10));
+				if hovered {
+					builder.draw_tooltip(&["Delete corrupted chunk"], pos, false);
+				}
+			}
 		}
 	}
 
+	/// Whether it changes the tree, which read-only tabs don't allow
 	#[must_use]
-	pub fn by_name(a: &CompoundEntry, b: &CompoundEntry) -> Ordering { a.key.cmp(&b.key) }
+	pub const fn is_edit(self) -> bool {
+		match self {
+			Self::CopyRaw | Self::CopyFormatted | Self::CopyJson | Self::CopyPath | Self::InvertBookmarks => false,
+			// changes made in the other program are written back to the tab
+			#[cfg(not(target_arch = "wasm32"))]
+			Self::OpenArrayInHex | Self::OpenInTxt => true,
+			Self::SortCompoundByName | Self::SortCompoundByType | Self::InsertFromClipboard | Self::DeleteCorruptedChunk => true,
+		}
+	}
+
+	/// Ignoring case, then by the exact keys for the ones which only differ in case
+	#[must_use]
+	pub fn by_name(a: &CompoundEntry, b: &CompoundEntry) -> Ordering { a.key.chars().flat_map(char::to_lowercase).cmp(b.key.chars().flat_map(char::to_lowercase)).then_with(|| a.key.cmp(&b.key)) }
 
 	#[must_use]
 	pub fn by_type(a: &CompoundEntry, b: &CompoundEntry) -> Ordering {
@@ -148,7 +186,7 @@ impl ElementAction {
 		ORDERING[a.value.id() as usize].cmp(&ORDERING[b.value.id() as usize]).then_with(|| a.key.cmp(&b.key))
 	}
 
-	pub fn apply<'m1, 'm2: 'm1>(self, root: &mut NbtElement, mut indices: OwnedIndices, mi: &'m1 mut MutableIndices<'m2>) -> anyhow::Result<Option<WorkbenchAction>> {
+	pub fn apply<'m1, 'm2: 'm1>(self, root: &mut NbtElement, mut indices: OwnedIndices, file_name: &str, mi: &'m1 mut MutableIndices<'m2>) -> anyhow::Result<Option<WorkbenchAction>> {
 		match self {
 			action @ (Self::CopyRaw | Self::CopyFormatted) => {
 				use core::fmt::Write;
@@ -175,6 +213,20 @@ impl ElementAction {
 
 				Ok(None)
 			}
+			Self::CopyJson => {
+				let NavigationInformation { element, .. } = root.navigate(&indices).context("Could not navigate indices")?;
+
+				// keys aren't included since `"key":value` on its own isn't valid json
+				set_clipboard(element.to_json(config::get_json_number_policy()));
+
+				Ok(None)
+			}
+			Self::CopyPath => {
+				let path = NbtPath::display_from_indices(root, &indices, file_name).context("Could not navigate indices")?;
+				set_clipboard(path);
+
+				Ok(None)
+			}
 			#[cfg(not(target_arch = "wasm32"))]
 			action @ (Self::OpenArrayInHex | Self::OpenInTxt) => {
 				use std::io::Write;
@@ -182,7 +234,7 @@ impl ElementAction {
 
 				let NavigationInformation { key, element, .. } = root.navigate(&indices).context("Could not navigate indices")?;
 
-				let hash = (Timestamp::now().elapsed().as_millis() as usize).wrapping_mul(element as *const NbtElement as usize);
+				let hash = (Timestamp::now().since_epoch().as_millis() as usize).wrapping_mul(element as *const NbtElement as usize);
 				let path = std::env::temp_dir().join(format!("nbtworkbench-{hash:0width$x}.{ext}", width = usize::BITS as usize / 8, ext = if action == Self::OpenArrayInHex { "bin" } else { "txt" }));
 				let (tx, rx) = std::sync::mpsc::channel();
 				let mut watcher = PollWatcher::new(
@@ -243,6 +295,8 @@ impl ElementAction {
 				Ok(None)
 			}
 			action @ (Self::SortCompoundByName | Self::SortCompoundByType) => {
+				// the mapping has to cover the entries of a chunk which hasn't been opened yet
+				decode_chunk_at(root, &indices, mi.bookmarks).context("Could not decode chunk")?;
 				let NavigationInformation { element, .. } = root.navigate(&indices).context("Could not navigate indices")?;
 
 				let mapping = match element.as_pattern() {
@@ -255,7 +309,7 @@ impl ElementAction {
 			}
 			Self::InsertFromClipboard => {
 				let clipboard = get_clipboard().context("Could not get clipboard")?;
-				let kv = NbtElement::from_str(&clipboard).map_err(|idx| anyhow!("Could not parse clipboard as SNBT (failed at index {idx})"))?;
+				let kv = NbtElement::from_str_or_json(&clipboard).map_err(|idx| anyhow!("Could not parse clipboard as SNBT: {}", SnbtError::of(&clipboard, idx).diagnostic()))?;
 				indices.push(0);
 				Ok(Some(add_element(root, kv, indices, mi).context("Failed to insert element")?.into_action()))
 			}
@@ -285,6 +339,15 @@ impl ElementAction {
 				}
 				Ok(None)
 			}
+			Self::DeleteCorruptedChunk => {
+				let NavigationInformation { element, .. } = root.navigate(&indices).context("Could not navigate indices")?;
+				if !element.as_chunk().is_some_and(NbtChunk::is_corrupted) {
+					bail!("Only corrupted chunks can be deleted this way");
+				}
+
+				// the region puts an unloaded chunk in its place, which isn't written at all
+				Ok(Some(remove_element(root, indices, mi)?.into_action()))
+			}
 		}
 	}
 }
@@ -93,6 +93,7 @@ impl Ord for MarkedLine {
 	fn cmp(&self, other: &Self) -> Ordering { self.true_line_number.cmp(&other.true_line_number) }
 }
 
+#[derive(Clone)]
 pub struct MarkedLines {
 	inner: Vec<MarkedLine>,
 }
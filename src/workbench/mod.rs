@@ -1,5 +1,7 @@
+pub mod boolean_overrides;
 pub mod element_action;
 pub mod marked_line;
+pub mod selection;
 pub mod tab;
 mod held_key_manager;
 
@@ -7,8 +9,8 @@ mod held_key_manager;
 use std::{
     assert_matches::debug_assert_matches,
     fmt::{Display, Formatter, Write},
+    ops::Range,
     path::{Path, PathBuf},
-    str::FromStr,
     sync::mpsc::TryRecvError,
     time::Duration,
 };
@@ -19,20 +21,27 @@ use fxhash::{FxBuildHasher, FxHashSet};
 use serde::{Deserialize, Serialize};
 use winit::{
     dpi::{PhysicalPosition, PhysicalSize},
-    event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta},
+    event::{ElementState, Ime, KeyEvent, MouseButton, MouseScrollDelta},
     keyboard::{KeyCode, PhysicalKey},
 };
 
 #[cfg(target_arch = "wasm32")] use crate::wasm::fake_scope as scope;
+#[cfg(not(target_arch = "wasm32"))] use crate::workbench::tab::loading::LoadingTab;
+#[cfg(not(target_arch = "wasm32"))] use crate::workbench::tab::session::{Session, SessionTab};
+#[cfg(not(target_arch = "wasm32"))] use crate::single_instance::InstanceListener;
+#[cfg(target_arch = "wasm32")] use crate::render::widget::close_prompt::{ClosePrompt, ClosePromptButton};
 use crate::{
     action_result::{ActionResult, AnyhowActionResult, IntoFailingActionResult},
-    config,
+    config, error,
     elements::{
-        NbtElementAndKey, NbtElementVariant,
+        ComplexNbtElementVariant, NbtElementAndKey, NbtElementVariant,
         array::{NbtByteArray, NbtIntArray, NbtLongArray},
+        boolean::{AsBoolean, checkbox},
         byte::NbtByte,
         chunk::NbtChunk,
-        compound::{CompoundMap, NbtCompound},
+        compound::{CompoundEntry, CompoundMap, NbtCompound},
+        convert::{ConversionError, ConversionTarget, convert},
+        data_version::{root_data_version, version_name},
         double::NbtDouble,
         element::NbtElement,
         float::NbtFloat,
@@ -41,7 +50,9 @@ use crate::{
         long::NbtLong,
         region::NbtRegion,
         short::NbtShort,
+        snbt_error::SnbtError,
         string::NbtString,
+        values_text::{is_fixed_length, timestamp_as_text, values_as_text},
     },
     flags, get_interaction_information, hash,
     history::WorkbenchAction,
@@ -49,11 +60,11 @@ use crate::{
     render::{
         RenderContext,
         assets::{
-            ACTION_WHEEL_Z, BASE_TEXT_Z, BASE_Z, CLOSED_WIDGET_UV, DARK_STRIPE_UV, HEADER_SIZE, HELD_ENTRY_Z, HORIZONTAL_SEPARATOR_UV, HOVERED_STRIPE_UV, HOVERED_WIDGET_UV, JUST_OVERLAPPING_BASE_TEXT_Z, LIGHT_STRIPE_UV, LINE_NUMBER_SEPARATOR_UV,
-            REPLACE_BOX_Z, SAVE_GRAYSCALE_UV, SAVE_UV, SELECTED_ACTION_WHEEL, SELECTED_WIDGET_UV, TRAY_UV, UNSELECTED_ACTION_WHEEL, UNSELECTED_WIDGET_UV, ZOffset,
+            ACTION_WHEEL_Z, BASE_TEXT_Z, BASE_Z, DARK_STRIPE_UV, DROP_OVERLAY_TEXT_Z, DROP_OVERLAY_Z, HEADER_SIZE, HELD_ENTRY_TEXT_Z, HELD_ENTRY_Z, HELD_TAB_TEXT_Z, HELD_TAB_Z, HELD_UNSAVED_TAB_DOT_Z, HORIZONTAL_SEPARATOR_UV, HOVERED_STRIPE_UV, HOVERED_WIDGET_UV, JUST_OVERLAPPING_BASE_TEXT_Z, JUST_OVERLAPPING_BASE_Z, LIGHT_STRIPE_UV, LINE_NUMBER_SEPARATOR_UV,
+            NOTIFICATION_BAR_BACKDROP_UV, NOTIFICATION_BAR_UV, READ_ONLY_UV, REPLACE_BOX_Z, SAVE_GRAYSCALE_UV, SAVE_UV, SELECTED_ACTION_WHEEL, SELECTED_WIDGET_UV, TAB_CYCLE_SELECTION_Z, TAB_CYCLE_TEXT_Z, TAB_CYCLE_Z, TRAY_UV, UNSELECTED_ACTION_WHEEL, UNSELECTED_WIDGET_UV, UNSAVED_TAB_DOT_Z, ZOffset,
         },
         color::TextColor,
-        vertex_buffer_builder::VertexBufferBuilder,
+        vertex_buffer_builder::{VertexBufferBuilder, Viewport},
         widget::{
             alert::{
                 Alert,
@@ -62,40 +73,65 @@ use crate::{
             Widget, WidgetContext, WidgetContextMut,
             button::{
                 exact_match::ExactMatchButton, freehand_mode::FreehandModeButton, new_tab::NewTabButton, open_file::OpenFileButton, refresh::RefreshButton, replace_by::ReplaceByButton,
-                search_flags::SearchFlagsButton, search_mode::SearchModeButton, search_operation::SearchOperationButton, sort_algorithm::SortAlgorithmButton, theme::ThemeButton,
+                search_flags::SearchFlagsButton, search_mode::SearchModeButton, search_operation::SearchOperationButton, search_tag_type::SearchTagTypeButton, sort_algorithm::SortAlgorithmButton, theme::ThemeButton,
             },
+            breadcrumbs::Breadcrumbs,
+            clipboard_ring::ClipboardRing,
+            go_to_box::GoToBox,
+            hex_view::HexView,
+            history_panel::HistoryPanel,
+            key_conflict_prompt::{KeyConflictPrompt, KeyConflictPromptButton, KeyConflictResolution, compound_map, conflicting_keys, unique_key},
+            scratch_pad::ScratchPad,
+            string_editor::StringEditor,
             notification::{Notification, NotificationKind, manager::NotificationManager},
+            recent_files::{RecentFilesMenu, RecentFilesMenuAction},
+            region_issues_banner::{RegionIssuesBanner, RegionIssuesBannerButton},
+            reload_banner::{ReloadBanner, ReloadBannerButton},
             replace_box::ReplaceBox,
             search_box::{SEARCH_BOX_END_X, SEARCH_BOX_START_X, SearchBox},
             selected_text::SelectedText,
-            text::{TEXT_DOUBLE_CLICK_INTERVAL, get_cursor_idx, get_cursor_left_jump_idx, get_cursor_right_jump_idx},
+            element_menu::{ElementMenu, ElementMenuAction},
+            tab_menu::{TabMenu, TabMenuAction},
+            text::{StringEditorKeyResult, TEXT_DOUBLE_CLICK_INTERVAL, get_cursor_idx, get_cursor_left_jump_idx, get_cursor_right_jump_idx},
+            url_box::UrlBox,
+            values_box::{ValuesBox, ValuesBoxEdit, ValuesBoxKind, WrapRootThen},
         },
         window::{MIN_WINDOW_HEIGHT, MIN_WINDOW_WIDTH, Theme, WINDOW_HEIGHT, WINDOW_WIDTH},
     },
     serialization::{
-        decoder::{BigEndianDecoder, Decoder},
+        decoder::{BedrockHeader, BigEndianDecoder, Decoder},
         encoder::UncheckedBufWriter,
+        json,
     },
     tree::{
         actions::{
             add::{AddElementResult, add_element},
             close::close_element,
+            duplicate::{DuplicateElementError, duplicate_element},
             expand::expand_element,
+            expand_to_depth::expand_element_to_depth,
             expand_to_indices::expand_element_to_indices,
+            move_chunk::move_chunk,
+            nudge::{NudgeElementError, NudgeStep, nudge_element},
             open::open_element,
             remove::{RemoveElementResult, remove_element},
+            rename::rename_element,
             replace::replace_element,
+            sort::sort_element_recursively,
+            splice::splice_byte_array,
         },
         indices::{Indices, OwnedIndices},
         navigate::NavigationInformation,
+        path::NbtPath,
         traverse::{TraversalError, TraversalInformation, TraversalInformationMut},
     },
-    util::{self, LinkedQueue, StrExt, Timestamp, Vec2u, drop_on_separate_thread, get_clipboard, nth, set_clipboard},
+    util::{self, CharExt, LinkedQueue, StrExt, Timestamp, Vec2u, drop_on_separate_thread, get_clipboard, nth, set_clipboard},
     workbench::{
         element_action::ElementAction,
         marked_line::MarkedLine,
-        tab::{FilePath, NbtFileFormat, Tab, TabConstants, manager::TabManager},
+        tab::{BedrockHeaderMismatch, FilePath, FormatWarning, NbtFileFormat, NewTabRoot, Tab, TabConstants, manager::TabManager},
     },
+    window_properties,
 };
 use crate::util::{AxisAlignedBoundingBox, Vec2d};
 use crate::workbench::held_key_manager::{HeldKeyManager, Modifiers};
@@ -123,6 +159,19 @@ pub enum InteractionInformation<'a> {
 
 pub struct Workbench {
     pub tabs: TabManager,
+    #[cfg(not(target_arch = "wasm32"))]
+    loading_tabs: Vec<LoadingTab>,
+    /// The [`LoadingTab::order`] of the last one switched to once it finished
+    #[cfg(not(target_arch = "wasm32"))]
+    last_focused_load: usize,
+    /// Reported together by [`Workbench::report_failed_loads`]
+    #[cfg(not(target_arch = "wasm32"))]
+    failed_loads: Vec<anyhow::Error>,
+    #[cfg(not(target_arch = "wasm32"))]
+    instance_listener: Option<InstanceListener>,
+    /// Most recently closed last, for [`Workbench::reopen_closed_tab`]
+    #[cfg(not(target_arch = "wasm32"))]
+    closed_tabs: Vec<PathBuf>,
     last_mouse_state: ElementState,
     raw_mouse: Vec2d,
     mouse: Vec2u,
@@ -134,19 +183,36 @@ pub struct Workbench {
     tab_scroll: usize,
     // todo: make widget
     scrollbar_offset: Option<usize>,
+    /// Whether the divider between the panes of a split is being dragged
+    held_divider: bool,
     // todo: need to rework this
     action_wheel: Option<Vec2u>,
     pub cursor_visible: bool,
+    /// Being dragged over the window, to be opened if dropped
+    pub hovered_files: Vec<PathBuf>,
     pub alerts: AlertManager,
     pub notifications: NotificationManager,
     pub scale: f32,
     search_box: SearchBox,
     replace_box: ReplaceBox,
+    go_to_box: GoToBox,
+    url_box: UrlBox,
+    values_box: ValuesBox,
+    breadcrumbs: Breadcrumbs,
+    recent_files: RecentFilesMenu,
+    tab_menu: TabMenu,
+    element_menu: ElementMenu,
+    clipboard_ring: ClipboardRing,
+    history_panel: HistoryPanel,
+    #[cfg(target_arch = "wasm32")]
+    close_prompt: Option<ClosePrompt>,
+    key_conflict_prompt: Option<KeyConflictPrompt>,
     ignore_event_end: Timestamp,
     debug_menu: bool,
 
     search_flags_button: SearchFlagsButton,
     search_operation_button: SearchOperationButton,
+    search_tag_type_button: SearchTagTypeButton,
     search_mode_button: SearchModeButton,
     exact_match_button: ExactMatchButton,
     sort_algorithm_button: SortAlgorithmButton,
@@ -159,10 +225,24 @@ pub struct Workbench {
 }
 
 impl Workbench {
+    #[cfg(not(target_arch = "wasm32"))]
+    const CLOSED_TABS_CAPACITY: usize = 16;
+    const TAB_SCROLL_BUTTON_WIDTH: usize = 16;
+
     #[must_use]
     pub const unsafe fn uninit() -> Self {
         Self {
             tabs: TabManager::without_tab(),
+            #[cfg(not(target_arch = "wasm32"))]
+            loading_tabs: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            last_focused_load: 0,
+            #[cfg(not(target_arch = "wasm32"))]
+            failed_loads: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            instance_listener: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            closed_tabs: Vec::new(),
             last_mouse_state: ElementState::Released,
             raw_mouse: Vec2d::new(0.0, 0.0),
             mouse: Vec2u::new(0, 0),
@@ -172,19 +252,34 @@ impl Workbench {
             held_keys: HeldKeyManager::new(),
             tab_scroll: 0,
             scrollbar_offset: None,
+            held_divider: false,
             action_wheel: None,
             cursor_visible: false,
+            hovered_files: Vec::new(),
             alerts: AlertManager::new(),
             notifications: unsafe { NotificationManager::uninit() },
             scale: 0.0,
             search_box: SearchBox::uninit(),
             replace_box: ReplaceBox::uninit(),
+            go_to_box: GoToBox::uninit(),
+            url_box: UrlBox::uninit(),
+            values_box: ValuesBox::uninit(),
+            breadcrumbs: Breadcrumbs::new(),
+            recent_files: RecentFilesMenu::new(),
+            tab_menu: TabMenu::new(),
+            element_menu: ElementMenu::new(),
+            clipboard_ring: ClipboardRing::new(),
+            history_panel: HistoryPanel::new(),
+            #[cfg(target_arch = "wasm32")]
+            close_prompt: None,
+            key_conflict_prompt: None,
             ignore_event_end: Timestamp::UNIX_EPOCH,
             debug_menu: false,
 
             search_flags_button: unsafe { core::mem::zeroed() },
             search_mode_button: unsafe { core::mem::zeroed() },
             search_operation_button: unsafe { core::mem::zeroed() },
+            search_tag_type_button: unsafe { core::mem::zeroed() },
             exact_match_button: unsafe { core::mem::zeroed() },
             sort_algorithm_button: unsafe { core::mem::zeroed() },
             theme_button: unsafe { core::mem::zeroed() },
@@ -196,9 +291,20 @@ impl Workbench {
         }
     }
 
+    #[allow(clippy::too_many_lines)]
     pub fn new(window_dims: Option<PhysicalSize<u32>>) -> Result<Self> {
         let mut workbench = Self {
             tabs: TabManager::without_tab(),
+            #[cfg(not(target_arch = "wasm32"))]
+            loading_tabs: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            last_focused_load: 0,
+            #[cfg(not(target_arch = "wasm32"))]
+            failed_loads: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            instance_listener: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            closed_tabs: Vec::new(),
             last_mouse_state: ElementState::Released,
             raw_mouse: Vec2d::new(0.0, 0.0),
             mouse: Vec2u::new(0, 0),
@@ -208,13 +314,27 @@ impl Workbench {
             held_keys: HeldKeyManager::new(),
             tab_scroll: 0,
             scrollbar_offset: None,
+            held_divider: false,
             action_wheel: None,
             cursor_visible: true,
+            hovered_files: Vec::new(),
             alerts: AlertManager::new(),
             notifications: NotificationManager::new(),
             scale: 1.0,
             search_box: SearchBox::new(),
             replace_box: ReplaceBox::new(),
+            go_to_box: GoToBox::new(),
+            url_box: UrlBox::new(),
+            values_box: ValuesBox::new(),
+            breadcrumbs: Breadcrumbs::new(),
+            recent_files: RecentFilesMenu::new(),
+            tab_menu: TabMenu::new(),
+            element_menu: ElementMenu::new(),
+            clipboard_ring: ClipboardRing::new(),
+            history_panel: HistoryPanel::new(),
+            #[cfg(target_arch = "wasm32")]
+            close_prompt: None,
+            key_conflict_prompt: None,
             ignore_event_end: Timestamp::UNIX_EPOCH,
             debug_menu: false,
 
@@ -222,6 +342,7 @@ impl Workbench {
             freehand_mode_button: Widget::new(),
             search_flags_button: Widget::new(),
             search_operation_button: Widget::new(),
+            search_tag_type_button: Widget::new(),
             search_mode_button: Widget::new(),
             sort_algorithm_button: Widget::new(),
             theme_button: Widget::new(),
@@ -242,13 +363,25 @@ impl Workbench {
             }
         }
         'create_tab: {
-            if let Some(path) = &std::env::args().nth(1).and_then(|x| PathBuf::from_str(&x).ok())
-                && let Ok(buf) = std::fs::read(path)
-            {
-                if workbench.on_open_file(path, buf).alert_err(&mut workbench.alerts).is_some() {
-                    break 'create_tab;
+            #[cfg(not(target_arch = "wasm32"))]
+            if window_dims.is_some() && config::get_restore_session() {
+                workbench.restore_session();
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            if window_dims.is_some() && !Self::is_new_window() {
+                workbench.instance_listener = InstanceListener::spawn().inspect_err(|e| error!("Could not listen for other launches: {e}")).ok();
+            }
+            for path in Self::file_arguments() {
+                if let Ok(buf) = std::fs::read(&path)
+                    && workbench.on_open_file(&path, buf).alert_err(&mut workbench.alerts).is_some()
+                    && let Ok(path) = std::fs::canonicalize(path)
+                {
+                    config::push_recent_file(path);
                 }
             }
+            if !workbench.tabs.is_empty() {
+                break 'create_tab;
+            }
             workbench.tabs.add(Tab::new(
                 if cfg!(debug_assertions) {
                     let sort = config::set_sort_algorithm(SortAlgorithm::None);
@@ -277,19 +410,29 @@ impl Workbench {
         } else {
             if AxisAlignedBoundingBox::new(0, usize::MAX, 0, 21).contains(self.mouse) {
                 let scroll = if shift { -v } else { -h };
-                self.tab_scroll = ((self.tab_scroll as isize + (scroll * 48.0) as isize).max(0) as usize).min(
-                    {
-                        let mut tabs_width = 3_usize;
-                        for tab in &self.tabs {
-                            tabs_width += tab.path.name().width() + 32 + 6 + 6;
-                        }
-                        tabs_width
-                    }
-                        .saturating_sub(self.window_dims.width as usize),
-                );
+                self.scroll_tabs((scroll * 48.0) as isize);
+            } else if self.history_panel.is_within_bounds(self.mouse, self.window_dims) {
+                let len = self.tabs.active_tab().history.steps().len();
+                self.history_panel.on_scroll(if shift { -h } else { -v }, len, self.window_dims);
             } else {
-                let tab = self.tabs.active_tab_mut();
-                if shift {
+                // the pane under the cursor is scrolled, focused or not
+                let idx = self.tabs.pane_at(self.mouse.x, self.window_dims.width as usize).unwrap_or(self.tabs.active_tab_idx());
+                let dims = self.pane_dims(idx);
+                let pane_x = self.tabs.pane(idx, self.window_dims.width as usize).map_or(0, |(x, _)| x);
+                let mouse = Vec2u::new(self.mouse.x.saturating_sub(pane_x), self.mouse.y);
+                let Some(tab) = self.tabs.get_mut(idx) else { return ActionResult::Pass };
+                let window_height = tab.window_dims.height as usize;
+                if let Some(scratch_pad) = &mut tab.scratch_pad
+                    && ScratchPad::contains(mouse, dims)
+                {
+                    scratch_pad.on_scroll(if shift { -h } else { -v }, dims);
+                } else if let Some(len) = tab.hex_view_len()
+                    && let Some(hex_view) = &mut tab.hex_view
+                {
+                    hex_view.on_scroll(if shift { -h } else { -v }, len, window_height);
+                } else if let Some(string_editor) = &mut tab.string_editor {
+                    string_editor.on_scroll(if shift { -h } else { -v }, dims);
+                } else if shift {
                     tab.on_horizontal_scroll(-v);
                     tab.on_scroll(-h);
                 } else {
@@ -303,13 +446,34 @@ impl Workbench {
 
     pub fn on_mouse_input(&mut self, state: ElementState, button: MouseButton) -> ActionResult {
         self.tabs.active_tab_mut().last_interaction = Timestamp::now();
-        let TabConstants { left_margin, horizontal_scroll, .. } = self.tabs.active_tab().consts();
         let Modifiers { shift, .. } = self.held_keys.modifiers();
         self.last_mouse_state = state;
 
         match state {
             ElementState::Pressed => {
                 self.held_mouse_keys.insert(button);
+                #[cfg(target_arch = "wasm32")]
+                if self.close_prompt.is_some() {
+                    return self.click_close_prompt(button);
+                }
+                if self.key_conflict_prompt.is_some() {
+                    return self.click_key_conflict_prompt(button);
+                }
+
+                // clicks into the text being edited move its cursor rather than saving it
+                if button == MouseButton::Left
+                    && self.mouse.y >= HEADER_SIZE
+                    && self.action_wheel.is_none()
+                    && !self.recent_files.is_within_bounds(self.mouse)
+                    && !self.tab_menu.is_within_bounds(self.mouse)
+                    && !self.element_menu.is_within_bounds(self.mouse)
+                    && !self.clipboard_ring.is_within_bounds(self.mouse)
+                    && !self.history_panel.is_within_bounds(self.mouse, self.window_dims)
+                    && let mouse = self.pane_mouse()
+                    && self.tabs.active_tab_mut().click_selected_text(mouse, shift)
+                {
+                    return ActionResult::Success(());
+                }
 
                 if let MouseButton::Left | MouseButton::Right = button
                     && let tab = self.tabs.active_tab_mut()
@@ -317,9 +481,34 @@ impl Workbench {
                 {
                     let action = text.save(&mut tab.root, &mut tab.path).alert_err(&mut self.alerts).failure_on_err()?;
                     tab.history.append(action);
+                    // saved, so selecting some other text won't save it again
+                    tab.selected_text = None;
                     // do not exit early
                 }
 
+                if self.recent_files.is_within_bounds(self.mouse) {
+                    return self.click_recent_files(button);
+                }
+                // clicking anywhere else closes it, unless it's the button toggling it
+                if !(button == MouseButton::Right && self.new_tab_button.bounds(self.window_dims).contains(self.mouse)) {
+                    self.recent_files.close();
+                }
+                if self.tab_menu.is_within_bounds(self.mouse) {
+                    return self.click_tab_menu(button);
+                }
+                self.tab_menu.close();
+                if self.element_menu.is_within_bounds(self.mouse) {
+                    return self.click_element_menu(button);
+                }
+                self.element_menu.close();
+                if self.clipboard_ring.is_within_bounds(self.mouse) {
+                    return self.click_clipboard_ring(button);
+                }
+                self.clipboard_ring.close();
+                if self.history_panel.is_within_bounds(self.mouse, self.window_dims) {
+                    return self.click_history_panel(button);
+                }
+
                 {
                     let mut ctx = WidgetContextMut::new(&mut self.tabs, &mut self.search_box, &mut self.replace_box, &mut self.alerts, &mut self.notifications, shift);
 
@@ -329,11 +518,17 @@ impl Workbench {
 								// hardcoded to mouse_down only for now
 								let result = self.$field.on_mouse_input(state, button, &mut ctx);
 								if result == ActionResult::Success(()) {
-									let $crate::render::widget::WidgetAccumulatedResult { open_file_requests } = ctx.take_accumulated();
+									let $crate::render::widget::WidgetAccumulatedResult { open_file_requests, toggle_recent_files, toggle_url_box } = ctx.take_accumulated();
 
 									for _ in 0..open_file_requests {
 										self.open_file()?;
 									}
+									if toggle_recent_files {
+										self.recent_files.toggle();
+									}
+									if toggle_url_box {
+										self.toggle_url_box();
+									}
                                     
                                     return ActionResult::Success(());
 								}
@@ -344,6 +539,7 @@ impl Workbench {
 
                     try_click_widget!(search_mode_button);
                     try_click_widget!(search_operation_button);
+                    try_click_widget!(search_tag_type_button);
                     try_click_widget!(search_flags_button);
                     try_click_widget!(exact_match_button);
                     try_click_widget!(sort_algorithm_button);
@@ -355,6 +551,34 @@ impl Workbench {
                     try_click_widget!(replace_by_button);
                 }
 
+                if let MouseButton::Left | MouseButton::Right = button
+                    && self.go_to_box.is_selected()
+                    && GoToBox::is_within_bounds(self.mouse, self.window_dims)
+                {
+                    self.go_to_box.select(self.mouse.x - SEARCH_BOX_START_X, button);
+                    return ActionResult::Success(());
+                }
+                self.go_to_box.deselect();
+
+                if let MouseButton::Left | MouseButton::Right = button
+                    && self.url_box.is_selected()
+                    && UrlBox::is_within_bounds(self.mouse, self.window_dims)
+                {
+                    self.url_box.select(self.mouse.x - SEARCH_BOX_START_X, button);
+                    return ActionResult::Success(());
+                }
+                self.url_box.deselect();
+
+                if self.values_box.is_open() {
+                    if let MouseButton::Left | MouseButton::Right = button
+                        && ValuesBox::is_within_bounds(self.mouse, self.window_dims)
+                    {
+                        self.values_box.select(self.mouse.x - SEARCH_BOX_START_X, button);
+                        return ActionResult::Success(());
+                    }
+                    self.values_box.close();
+                }
+
                 if let MouseButton::Left | MouseButton::Right = button
                     && ReplaceBox::is_visible(&self.search_box, &self.replace_box)
                 {
@@ -369,13 +593,84 @@ impl Workbench {
                     self.search_box.deselect();
                 }
 
-                if AxisAlignedBoundingBox::new(4, usize::MAX, 2, 19).contains(self.mouse) {
+                if self.tab_strip_overflows() && AxisAlignedBoundingBox::new(self.visible_tab_strip_width(), usize::MAX, 2, 19).contains(self.mouse) {
+                    self.click_tab_scroll_buttons(button)?;
+                } else if AxisAlignedBoundingBox::new(4, usize::MAX, 2, 19).contains(self.mouse) {
                     self.click_tab(button)?;
                 }
                 if AxisAlignedBoundingBox::new(0, 16, 24, 46).contains(self.mouse) {
                     self.open_file()?;
                 }
 
+                if ReloadBanner::is_visible(self.tabs.active_tab()) && ReloadBanner::is_within_bounds(self.mouse, self.window_dims) {
+                    if button == MouseButton::Left {
+                        let tab = self.tabs.active_tab_mut();
+                        match ReloadBanner::button_at(self.mouse, self.window_dims) {
+                            Some(ReloadBannerButton::Reload) => tab.reload().alert_err(&mut self.alerts).failure_on_err()?,
+                            Some(ReloadBannerButton::Ignore) => tab.changed_on_disk = false,
+                            None => {}
+                        }
+                    }
+                    // the row underneath is covered, so it shouldn't be interacted with
+                    return ActionResult::Success(());
+                }
+
+                if RegionIssuesBanner::is_visible(self.tabs.active_tab()) && RegionIssuesBanner::is_within_bounds(self.tabs.active_tab(), self.mouse, self.window_dims) {
+                    if button == MouseButton::Left {
+                        let tab = self.tabs.active_tab_mut();
+                        match RegionIssuesBanner::button_at(tab, self.mouse, self.window_dims) {
+                            Some(RegionIssuesBannerButton::Details) => tab.region_issues_open = !tab.region_issues_open,
+                            Some(RegionIssuesBannerButton::Repair) => {
+                                tab.ensure_writable(&mut self.notifications)?;
+                                tab.repair_region(&mut self.alerts).alert_err(&mut self.alerts).failure_on_err()?;
+                            }
+                            Some(RegionIssuesBannerButton::Ignore) => {
+                                tab.region_issues.clear();
+                                tab.region_issues_open = false;
+                            }
+                            None => {}
+                        }
+                    }
+                    // the rows underneath are covered, so they shouldn't be interacted with
+                    return ActionResult::Success(());
+                }
+
+                if self.are_breadcrumbs_visible() && Breadcrumbs::is_within_bounds(self.mouse) {
+                    if button == MouseButton::Left {
+                        self.try_collapse_to_breadcrumb()?;
+                    }
+                    // the row underneath is covered, so it shouldn't be interacted with
+                    return ActionResult::Success(());
+                }
+
+                if self.mouse.y >= HEADER_SIZE && self.action_wheel.is_none() {
+                    if button == MouseButton::Left && self.tabs.is_on_divider(self.mouse.x, self.window_dims.width as usize) {
+                        self.held_divider = true;
+                        return ActionResult::Success(());
+                    }
+                    // clicking into the other pane focuses it and then acts as any click on it would
+                    if let Some(idx) = self.tabs.pane_at(self.mouse.x, self.window_dims.width as usize)
+                        && idx != self.tabs.active_tab_idx()
+                    {
+                        self.tabs.set_active_idx(idx);
+                    }
+                    if self.tabs.active_tab_mut().hex_view_len().is_some() {
+                        return self.click_hex_view(button);
+                    }
+                    if self.tabs.active_tab().string_editor.is_some() {
+                        return self.click_string_editor(button);
+                    }
+                    if self.tabs.active_tab().scratch_pad.is_some() && ScratchPad::contains(self.pane_mouse(), self.pane_dims(self.tabs.active_tab_idx())) {
+                        return self.click_scratch_pad(button);
+                    }
+                }
+                // the tree takes the keys again once it's clicked
+                if let Some(scratch_pad) = &mut self.tabs.active_tab_mut().scratch_pad {
+                    scratch_pad.unfocus();
+                }
+                let TabConstants { left_margin, horizontal_scroll, .. } = self.tabs.active_tab().consts();
+                let mouse = self.pane_mouse();
+
                 if button == MouseButton::Left && AxisAlignedBoundingBox::new(0, usize::MAX, HEADER_SIZE, usize::MAX).contains(self.mouse) && self.tabs.active_tab().held_entry.is_some() {
                     self.drop_held_entry()?;
                 }
@@ -384,13 +679,13 @@ impl Workbench {
                     self.bookmark_line(true)?;
                 }
 
-                if AxisAlignedBoundingBox::new(left_margin, usize::MAX, HEADER_SIZE, usize::MAX).contains(self.mouse + (horizontal_scroll, 0)) {
+                if AxisAlignedBoundingBox::new(left_margin, usize::MAX, HEADER_SIZE, usize::MAX).contains(mouse + (horizontal_scroll, 0)) {
                     match self.action_wheel.take() {
                         Some(_) => {}
                         None => {
                             if button == MouseButton::Right
                                 && let InteractionInformation::Content { is_in_left_margin: false, depth, y, .. } = get_interaction_information!(self)
-                                && depth + 1 == (self.mouse.x + horizontal_scroll - left_margin) / 16
+                                && depth + 1 == (mouse.x + horizontal_scroll - left_margin) / 16
                             {
                                 self.action_wheel = Some(Vec2u::new(left_margin + depth * 16 + 16 + 6, y * 16 + HEADER_SIZE + 7));
                                 return ActionResult::Success(());
@@ -402,6 +697,14 @@ impl Workbench {
                         self.try_root_style_change()?;
                     }
 
+                    if MouseButton::Left == button {
+                        self.try_flip_boolean()?;
+                    }
+
+                    if MouseButton::Left == button {
+                        self.try_select_row()?;
+                    }
+
                     if MouseButton::Left == button {
                         self.toggle(shift, self.tabs.active_tab().freehand_mode)?;
                     }
@@ -411,6 +714,7 @@ impl Workbench {
                     }
 
                     if button == MouseButton::Right {
+                        self.try_open_element_menu()?;
                         self.try_select_text(false)?
                     }
 
@@ -428,11 +732,11 @@ impl Workbench {
                         let tab = self.tabs.active_tab_mut();
                         let TabConstants { scroll, .. } = tab.consts();
                         let height = tab.root.height() * 16 + 48;
-                        let total = self.window_dims.height as usize - HEADER_SIZE;
+                        let total = tab.window_dims.height as usize - HEADER_SIZE;
                         if height - 48 > total {
                             let start = total * scroll / height + HEADER_SIZE;
                             let end = start + total * total / height;
-                            if AxisAlignedBoundingBox::new(self.window_dims.width as usize - 7, self.window_dims.width as usize, start, end + 1).contains(self.mouse) {
+                            if AxisAlignedBoundingBox::new(tab.window_dims.width as usize - 7, tab.window_dims.width as usize, start, end + 1).contains(mouse) {
                                 self.scrollbar_offset = Some(self.mouse.y - start);
                                 return ActionResult::Success(());
                             }
@@ -451,7 +755,7 @@ impl Workbench {
             ElementState::Released => {
                 self.held_mouse_keys.remove(&button);
                 
-                if let MouseButton::Right = button
+                if let MouseButton::Left | MouseButton::Right = button
                     && let Some(selected_text) = self.tabs.active_tab_mut().selected_text.as_mut()
                 {
                     selected_text.set_drag_selectable(false);
@@ -459,6 +763,12 @@ impl Workbench {
 
                 self.process_action_wheel()?;
                 self.scrollbar_offset = None;
+                self.held_divider = false;
+                if button == MouseButton::Left && self.tabs.held().is_some() {
+                    // dropping it anywhere but the tab strip leaves it where it was
+                    let x = AxisAlignedBoundingBox::new(0, usize::MAX, 0, 21).contains(self.mouse).then_some(self.mouse.x + self.tab_scroll);
+                    self.tabs.drop_held(x);
+                }
                 if button == MouseButton::Left {
                     self.tabs.active_tab_mut().steal_animation_data = None;
                 }
@@ -469,12 +779,248 @@ impl Workbench {
 
     #[deprecated = "refactor to UFCS only"]
     pub fn on_open_file(&mut self, path: &Path, buf: Vec<u8>) -> Result<()> {
+        let region_issues = Tab::check_region(path, &buf);
         let (nbt, format) = Tab::parse_raw(path, buf)?;
-        let tab = Tab::new(nbt, FilePath::new(path).map_err(|path| anyhow!("Invalid file path: {path:?}"))?, format, self.window_dims)?;
+        let mut tab = Tab::new(nbt, FilePath::new(path).map_err(|path| anyhow!("Invalid file path: {path:?}"))?, format, self.window_dims)?;
+        tab.region_issues = region_issues;
+        self.tabs.add(tab);
+        Ok(())
+    }
+
+    /// The files to open which the app was launched with
+    #[must_use]
+    pub fn file_arguments() -> Vec<PathBuf> { std::env::args().skip(1).filter(|arg| !arg.starts_with("--")).map(PathBuf::from).collect() }
+
+    /// Whether the app was launched with `--new-window`, so it shouldn't hand its files to one already open or take the files of later launches
+    #[must_use]
+    pub fn is_new_window() -> bool { std::env::args().skip(1).any(|arg| arg == "--new-window") }
+
+    /// Opens the files forwarded by later launches, see [`InstanceListener`]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn poll_instance_listener(&mut self) {
+        let Some(listener) = &self.instance_listener else { return };
+        let mut forwarded = None;
+        while let Some(paths) = listener.poll() {
+            forwarded.get_or_insert_with(Vec::new).extend(paths);
+        }
+        let Some(paths) = forwarded else { return };
+        for path in paths {
+            self.open_path_in_background(path);
+        }
+        window_properties().focus();
+    }
+
+    /// Reopens the tabs of the last [`Session`], with a notice for any files which are gone
+    #[cfg(not(target_arch = "wasm32"))]
+    fn restore_session(&mut self) {
+        let Some(session) = Session::read().alert_err(&mut self.alerts) else { return };
+        let mut active_tab = None;
+        let mut missing = 0_usize;
+        for (idx, SessionTab { path, format, scroll, horizontal_scroll }) in session.tabs.into_iter().enumerate() {
+            let Ok(buf) = std::fs::read(&path) else {
+                missing += 1;
+                continue
+            };
+            if self.on_open_file(&path, buf).alert_err(&mut self.alerts).is_none() {
+                continue
+            }
+            let tab = self.tabs.active_tab_mut();
+            tab.format = format;
+            tab.scroll = scroll;
+            tab.horizontal_scroll = horizontal_scroll;
+            tab.refresh_scrolls();
+            if idx <= session.active_tab {
+                active_tab = Some(self.tabs.len() - 1);
+            }
+        }
+        if let Some(active_tab) = active_tab {
+            self.tabs.set_active_idx(active_tab);
+        }
+        if missing > 0 {
+            self.notifications.notify(Notification::new(
+                format!("Skipped {missing} file{suffix} from the last session which no longer exist{verb_suffix}", suffix = if missing == 1 { "" } else { "s" }, verb_suffix = if missing == 1 { "s" } else { "" }),
+                TextColor::Yellow,
+                NotificationKind::Session,
+            ));
+        }
+    }
+
+    /// Reopens the most recently closed tab which was saved to a file
+    #[cfg(not(target_arch = "wasm32"))]
+    fn reopen_closed_tab(&mut self) -> ActionResult {
+        let Some(path) = self.closed_tabs.pop() else { return ActionResult::Pass };
+        let bytes = std::fs::read(&path).with_context(|| format!("Could not reopen {}", path.display())).alert_err(&mut self.alerts).failure_on_err()?;
+        self.on_open_file_in_background(path, bytes).alert_err(&mut self.alerts).failure_on_err()?;
+        ActionResult::Success(())
+    }
+
+    fn click_tab_menu(&mut self, button: MouseButton) -> ActionResult {
+        if button != MouseButton::Left {
+            return ActionResult::Success(())
+        }
+        let Some(action) = self.tab_menu.click(self.mouse, self.tabs.len(), self.tabs.active_tab_idx(), self.tabs.split_view().map(|split| split.other_idx)) else { return ActionResult::Success(()) };
+        self.tab_menu.close();
+        match action {
+            TabMenuAction::Duplicate(idx) => {
+                self.duplicate_tab(idx);
+                ActionResult::Success(())
+            }
+            TabMenuAction::CompareWithActive(idx) => {
+                self.compare_tabs(idx, self.tabs.active_tab_idx());
+                ActionResult::Success(())
+            }
+            TabMenuAction::SplitWithActive(idx) => {
+                self.tabs.split(idx, self.window_dims.width as usize);
+                self.tabs.refresh_window_dims(self.window_dims);
+                ActionResult::Success(())
+            }
+            TabMenuAction::CloseSplit => {
+                self.tabs.unsplit();
+                self.tabs.refresh_window_dims(self.window_dims);
+                ActionResult::Success(())
+            }
+            TabMenuAction::CloseOthers(idx) => {
+                self.tabs.set_active_idx(idx);
+                self.close_tabs(&(0..self.tabs.len()).filter(|&other| other != idx).collect::<Vec<_>>())
+            }
+            TabMenuAction::CloseToTheRight(idx) => self.close_tabs(&(idx + 1..self.tabs.len()).collect::<Vec<_>>()),
+            TabMenuAction::OpenAs(idx, format) => {
+                let Some(tab) = self.tabs.get_mut(idx) else { return ActionResult::Success(()) };
+                tab.reopen_as(format).alert_err(&mut self.alerts).failure_on_err()?;
+                ActionResult::Success(())
+            }
+        }
+    }
+
+    /// Opens a copy of the tab right after it, see [`Tab::duplicate`]
+    fn duplicate_tab(&mut self, idx: usize) {
+        let Some(tab) = self.tabs.get(idx) else { return };
+        let copy = tab.duplicate();
+        self.tabs.insert(idx + 1, copy);
+    }
+
+    /// Opens what differs between the tabs right after `after`, see [`Tab::compare`]
+    fn compare_tabs(&mut self, before: usize, after: usize) {
+        let (Some(before_tab), Some(after_tab)) = (self.tabs.get(before), self.tabs.get(after)) else { return };
+        let comparison = Tab::compare(before_tab, after_tab);
+        let message = match comparison.differences.len() {
+            0 => format!("{} and {} are the same", before_tab.path.name(), after_tab.path.name()),
+            1 => "1 difference, F2 to jump to it".to_owned(),
+            n => format!("{n} differences, F2 / Shift + F2 to jump between them"),
+        };
+        self.notifications.notify(Notification::new(message, TextColor::White, NotificationKind::Compare));
+        self.tabs.insert(after + 1, comparison);
+        self.scroll_to_active_tab();
+    }
+
+    fn click_recent_files(&mut self, button: MouseButton) -> ActionResult {
+        if button != MouseButton::Left {
+            return ActionResult::Success(())
+        }
+        match self.recent_files.click(self.mouse) {
+            Some(RecentFilesMenuAction::Open(path)) => {
+                self.recent_files.close();
+                let bytes = std::fs::read(&path).with_context(|| format!("Could not open {}", path.display())).alert_err(&mut self.alerts).failure_on_err()?;
+                #[cfg(not(target_arch = "wasm32"))]
+                self.on_open_file_in_background(path, bytes).alert_err(&mut self.alerts).failure_on_err()?;
+                #[cfg(target_arch = "wasm32")]
+                self.on_open_file(&path, bytes).alert_err(&mut self.alerts).failure_on_err()?;
+            }
+            Some(RecentFilesMenuAction::Remove(path)) => config::remove_recent_file(&path),
+            None => {}
+        }
+        ActionResult::Success(())
+    }
+
+    /// Reads the file and parses it on a worker thread, see [`Workbench::report_failed_loads`] for what happens if that fails
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open_path_in_background(&mut self, path: PathBuf) {
+        let result = std::fs::read(&path).with_context(|| format!("Could not read {}", path.display())).and_then(|bytes| self.on_open_file_in_background(path, bytes));
+        if let Err(e) = result {
+            self.failed_loads.push(e);
+        }
+    }
+
+    /// Asks whether to open the file which couldn't be loaded because of `e`, its [`BedrockHeaderMismatch`], anyway, taking the header as it is
+    ///
+    /// # Errors
+    /// * If the dialog couldn't be shown, or the file couldn't be read or parsed after its header
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_anyway(&mut self, path: &Path, e: &anyhow::Error) -> Result<()> {
+        if !Tab::confirm(format!("{e:#}\n\nLoad it anyway?"))? {
+            return Ok(())
+        }
+        let buf = std::fs::read(path).with_context(|| format!("Could not read {}", path.display()))?;
+        let (nbt, format) = Tab::parse_as(path, buf, NbtFileFormat::LittleEndianHeaderNbt(BedrockHeader::DEFAULT_VERSION))?;
+        self.tabs.add(Tab::new(nbt, FilePath::new(path)?, format, self.window_dims)?);
+        Ok(())
+    }
+
+    /// Downloads the file and parses it on a worker thread, the tab can only be saved with Save As since it has no file of its own
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open_url(&mut self, url: String) {
+        match LoadingTab::spawn_url(url) {
+            Ok(loading) => self.loading_tabs.push(loading),
+            Err(e) => self.failed_loads.push(e),
+        }
+    }
+
+    /// Fetches the file with the browser, see [`crate::wasm::open_url`]
+    #[cfg(target_arch = "wasm32")]
+    pub fn open_url(&mut self, url: String) { crate::wasm::open_url(url); }
+
+    /// Opens a file fetched by [`Workbench::open_url`]
+    ///
+    /// # Errors
+    /// * If the file couldn't be parsed
+    #[cfg(target_arch = "wasm32")]
+    pub fn on_open_url(&mut self, url: String, buf: Vec<u8>) -> Result<()> {
+        let path = FilePath::for_url(&url);
+        let region_issues = Tab::check_region(path.path(), &buf);
+        let (nbt, format) = Tab::parse_raw(path.path(), buf)?;
+        let mut tab = Tab::from_url(nbt, url, format, self.window_dims)?;
+        tab.region_issues = region_issues;
         self.tabs.add(tab);
         Ok(())
     }
 
+    fn toggle_url_box(&mut self) {
+        if self.url_box.is_selected() {
+            self.url_box.deselect();
+        } else {
+            self.url_box.select(self.url_box.value.width(), MouseButton::Left);
+        }
+        self.search_box.deselect();
+        self.replace_box.deselect();
+        self.go_to_box.deselect();
+        self.values_box.close();
+    }
+
+    /// Alerts about the files which couldn't be opened once none are left loading, so opening many at once doesn't bury the window in alerts
+    #[cfg(not(target_arch = "wasm32"))]
+    fn report_failed_loads(&mut self) {
+        match self.failed_loads.as_slice() {
+            [] => return,
+            [error] => self.alerts.alert(Alert::error(error)),
+            errors => self.alerts.alert(Alert::new(
+                "Error!",
+                TextColor::Red,
+                format!("Could not open {len} files:\n{errors}", len = errors.len(), errors = errors.iter().map(|error| format!("{error:#}")).collect::<Vec<_>>().join("\n")),
+            )),
+        }
+        self.failed_loads.clear();
+    }
+
+    /// Parses the file on a worker thread, showing a placeholder tab until it's done
+    ///
+    /// # Errors
+    /// * If the path has no file name or the thread couldn't be spawned
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn on_open_file_in_background(&mut self, path: PathBuf, buf: Vec<u8>) -> Result<()> {
+        self.loading_tabs.push(LoadingTab::spawn(path, buf)?);
+        Ok(())
+    }
+
     #[deprecated = "refactor to UFCS only"]
     fn process_action_wheel(&mut self) -> ActionResult {
         use core::f64::consts::TAU;
@@ -483,17 +1029,38 @@ impl Workbench {
         if center.y < HEADER_SIZE {
             return ActionResult::Failure(());
         }
+        let mouse = self.pane_mouse();
         let tab = self.tabs.active_tab_mut();
         let TabConstants { left_margin, scroll, .. } = tab.consts();
-        if (Vec2d::from(center) - Vec2d::from(self.mouse)).distance_squared() <= 8_f64.powi(2) {
+        if (Vec2d::from(center) - Vec2d::from(mouse)).distance_squared() <= 8_f64.powi(2) {
             return ActionResult::Failure(());
         }
-        let highlight_idx = ((center - self.mouse).angle() / TAU * 8.0 + 3.5).rem_euclid(8.0) as usize;
+        let highlight_idx = ((center - mouse).angle() / TAU * 8.0 + 3.5).rem_euclid(8.0) as usize;
         let TraversalInformation { indices, element, .. } = tab.root.traverse((center.y - (HEADER_SIZE + 7) + scroll) / 16, Some((center.x - left_margin) / 16)).alert_err(&mut self.alerts).failure_on_err()?;
         if let Some(action) = element.actions().get(highlight_idx).copied() {
-            if let Some(Some(action)) = action.apply(&mut tab.root, indices, mutable_indices!(tab)).alert_err(&mut self.alerts) {
-                tab.history.append(action);
-            }
+            self.apply_element_action(action, indices)?;
+        }
+        ActionResult::Success(())
+    }
+
+    fn apply_element_action(&mut self, action: ElementAction, indices: OwnedIndices) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        if action.is_edit() {
+            tab.ensure_writable(&mut self.notifications)?;
+        }
+        if let ElementAction::CopyRaw | ElementAction::CopyFormatted = action
+            && let Ok(NavigationInformation { key, element, .. }) = tab.root.navigate(&indices)
+        {
+            self.clipboard_ring.push(vec![(key.map(CompactString::from), element.clone())]);
+        }
+        if let ElementAction::CopyJson = action
+            && let Ok(NavigationInformation { element, .. }) = tab.root.navigate(&indices)
+            && let Some(warning) = json::non_finite_warning(element)
+        {
+            self.alerts.alert(Alert::warning(warning));
+        }
+        if let Some(Some(action)) = action.apply(&mut tab.root, indices, tab.path.name(), mutable_indices!(tab)).alert_err(&mut self.alerts) {
+            tab.history.append(action);
         }
         ActionResult::Success(())
     }
@@ -516,7 +1083,7 @@ impl Workbench {
                             config::set_sort_algorithm(sort);
                             match result {
                                 Ok(kv) => kv,
-                                Err(idx) => bail!("Failed to parse SNBT at index {idx}"),
+                                Err(idx) => bail!("Failed to parse SNBT: {}", SnbtError::of(s, idx).diagnostic()),
                             }
                         }
                         kind => {
@@ -612,6 +1179,7 @@ impl Workbench {
             return ActionResult::Pass
         }
         let is_grid_layout = self.tabs.active_tab().root.as_region().is_some_and(NbtRegion::is_grid_layout);
+        let copy = self.held_keys.ctrl() || self.held_keys.alt();
 
         if let InteractionInformation::Content {
             is_in_left_margin: false,
@@ -625,7 +1193,27 @@ impl Workbench {
             && y > 0
         {
             let tab = self.tabs.active_tab_mut();
+            if !tab.ensure_writable(&mut self.notifications).passed() {
+                tab.steal_animation_data = None;
+                return ActionResult::Failure(());
+            }
+
+            if copy {
+                let NavigationInformation { key, element, .. } = tab.root.navigate(&indices).alert_err(&mut self.alerts).failure_on_err()?;
+                let mut value = element.clone();
+                // SAFETY: value is a copy, detached from all caches
+                scope(|scope| unsafe { value.shut(scope) });
+                tab.selection.clear();
+                tab.held_entry = Some(HeldEntry::copy_of((key.map(CompactString::from), value)));
+                return ActionResult::Success(())
+            }
 
+            // the rest of the selection follows it if it's part of it
+            if tab.selection.contains(&indices) {
+                tab.selection.dragging = true;
+            } else {
+                tab.selection.clear();
+            }
             let RemoveElementResult { indices, kv: (key, mut value), replaces: _ } = remove_element(&mut tab.root, indices, mutable_indices!(tab)).alert_err(&mut self.alerts).failure_on_err()?;
 
             // SAFETY: value is detached from all caches
@@ -660,34 +1248,56 @@ impl Workbench {
     }
 
     #[deprecated = "refactor to UFCS only"]
+    /// Duplicates the selected row, or the hovered one if there isn't one
     fn try_duplicate(&mut self) -> ActionResult {
-        if let InteractionInformation::Content {
-            is_in_left_margin: false,
-            y,
-            key,
-            value,
-            mut indices,
-            ..
-        } = get_interaction_information!(self)
-            && y > 0
-        {
-            *indices.last_mut().expect("y > 0") += 1;
-            let duplicate = value.clone();
-            let tab = self.tabs.active_tab_mut();
-            let result = add_element(&mut tab.root, (key, duplicate), indices, mutable_indices!(tab)).alert_err(&mut self.alerts).failure_on_err()?;
-            tab.history.append(result.into_action());
-            tab.refresh_scrolls();
-            ActionResult::Success(())
+        if let Some(selected_text) = &self.tabs.active_tab().selected_text {
+            let indices = selected_text.indices.clone();
+            self.duplicate_element(indices)
+        } else if let InteractionInformation::Content { is_in_left_margin: false, indices, .. } = get_interaction_information!(self) {
+            self.duplicate_element(indices)
         } else {
             ActionResult::Pass
         }
     }
 
+    /// Adds a copy of the element at `indices` right after it and scrolls to it, see [`duplicate_element`]
+    fn duplicate_element(&mut self, indices: OwnedIndices) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        let result = match duplicate_element(&mut tab.root, &indices, mutable_indices!(tab)) {
+            Ok(result) => result,
+            Err(e @ (DuplicateElementError::Root | DuplicateElementError::Chunk)) => {
+                self.notifications.notify(Notification::new(e.to_string(), TextColor::Yellow, NotificationKind::Duplicate));
+                return ActionResult::Failure(())
+            }
+            Err(e) => {
+                self.alerts.alert(e);
+                return ActionResult::Failure(())
+            }
+        };
+        let line_number = tab.root.navigate(&result.indices).map_or(0, |info| info.line_number);
+        tab.history.append(result.into_action());
+        tab.refresh_scrolls();
+        tab.scroll_line_into_view(line_number);
+        ActionResult::Success(())
+    }
+
     #[deprecated = "refactor to UFCS only"]
     fn try_copy(&mut self, debug: bool) -> ActionResult {
+        let tab = self.tabs.active_tab();
+        if !tab.selection.is_empty() {
+            self.clipboard_ring.push(tab.selection.entries(&tab.root));
+            return if set_clipboard(tab.selection.to_snbt(&tab.root, debug)) {
+                ActionResult::Success(())
+            } else {
+                self.alerts.alert(Alert::error("Could not set clipboard"));
+                ActionResult::Failure(())
+            }
+        }
         let InteractionInformation::Content { is_in_left_margin: false, key, value, .. } = get_interaction_information!(self) else {
             return ActionResult::Pass
         };
+        self.clipboard_ring.push(vec![(key.clone(), value.clone())]);
         let mut buf = String::new();
         let key = key.map(|key| if key.needs_escape() { format_compact!("{key:?}") } else { key });
         let key_exists = key.is_some();
@@ -704,73 +1314,402 @@ impl Workbench {
         }
     }
 
-    #[deprecated = "refactor to UFCS only"]
-    fn delete(&mut self, clipboard: bool) -> ActionResult {
-        if let InteractionInformation::Content {
-            is_in_left_margin: false, indices, key, value, ..
-        } = get_interaction_information!(self)
-        {
-            if clipboard {
-                let key = key.map(|key| if key.needs_escape() { format_compact!("{key:?}") } else { key });
-                let mut buf = String::new();
-                if write!(&mut buf, "{}{}{value}", key.as_ref().map_or("", CompactString::as_str), if key.is_some() { ":" } else { "" }).is_ok() {
-                    set_clipboard(buf);
-                }
+    /// Nudges the selected numbers, or the hovered one if nothing's selected, where nudging the same ones again straight away is undone along with the last nudge
+    fn try_nudge(&mut self, up: bool, step: NudgeStep) -> ActionResult {
+        let targets = if !self.tabs.active_tab().selection.is_empty() {
+            self.tabs.active_tab().selection.indices().to_vec()
+        } else if let InteractionInformation::Content { is_in_left_margin: false, indices, .. } = get_interaction_information!(self) {
+            vec![indices]
+        } else {
+            return ActionResult::Pass
+        };
+        let tab = self.tabs.active_tab_mut();
+        // the arrows mean nothing to anything else
+        if !targets.iter().any(|indices| tab.root.navigate(indices).is_ok_and(|info| info.element.is_primitive() && !info.element.is_string())) {
+            return ActionResult::Pass
+        }
+        tab.ensure_writable(&mut self.notifications)?;
+        let mut actions = Vec::new();
+        for indices in &targets {
+            match nudge_element(&mut tab.root, indices.clone(), step, up, &mut tab.path) {
+                Ok(result) => actions.push(result.into_action()),
+                // mixed in with the numbers being nudged
+                Err(NudgeElementError::NotANumber { .. } | NudgeElementError::Fractional { .. }) => {}
+                Err(e) => self.alerts.alert(Alert::error(e)),
             }
-            let tab = self.tabs.active_tab_mut();
-            let result = remove_element(&mut tab.root, indices, mutable_indices!(tab)).alert_err(&mut self.alerts).failure_on_err()?;
-            tab.history.append(result.into_action());
+        }
+        if actions.is_empty() {
+            return ActionResult::Failure(())
+        }
+        let same_targets = |last: &[OwnedIndices]| last.len() == targets.len() && last.iter().zip(&targets).all(|(a, b)| a.iter().eq(b.iter()));
+        // a save in between keeps them apart, so undoing can get back to what was saved
+        if tab.last_nudge.as_ref().is_some_and(|(last, generation)| same_targets(last) && *generation == tab.history.generation()) && tab.history.has_unsaved_changes() {
+            tab.history.extend_last();
+        } else if let Some(action) = WorkbenchAction::bulk(actions) {
+            tab.history.append(action);
+        }
+        tab.last_nudge = Some((targets, tab.history.generation()));
+        ActionResult::Success(())
+    }
+
+    /// Copies the keys of the selected elements, or the hovered element's key if nothing's selected
+    fn try_copy_key(&mut self) -> ActionResult {
+        let tab = self.tabs.active_tab();
+        let text = if !tab.selection.is_empty() {
+            tab.selection.keys(&tab.root)
+        } else if let InteractionInformation::Content { is_in_left_margin: false, key: Some(key), .. } = get_interaction_information!(self) {
+            key.into_string()
+        } else {
+            return ActionResult::Pass
+        };
+        // nothing selected has a key, like the elements of a list
+        if text.is_empty() {
+            return ActionResult::Pass
+        }
+        if set_clipboard(text) {
             ActionResult::Success(())
         } else {
-            ActionResult::Pass
+            self.alerts.alert(Alert::error("Could not set clipboard"));
+            ActionResult::Failure(())
         }
     }
 
-    #[deprecated = "refactor to UFCS only"]
-    fn drop_held_entry(&mut self) -> ActionResult {
-        let tab = self.tabs.active_tab_mut();
-        let TabConstants { left_margin, scroll, horizontal_scroll } = tab.consts();
-
-        if self.mouse.y <= HEADER_SIZE {
+    /// Copies the selected elements as SNBT without their keys, or the hovered one if nothing's selected
+    fn try_copy_value(&mut self, pretty: bool) -> ActionResult {
+        let tab = self.tabs.active_tab();
+        let text = if !tab.selection.is_empty() {
+            tab.selection.values_snbt(&tab.root, pretty)
+        } else if let InteractionInformation::Content { is_in_left_margin: false, value, .. } = get_interaction_information!(self) {
+            if pretty { format!("{value:#?}") } else { value.to_string() }
+        } else {
             return ActionResult::Pass
+        };
+        if set_clipboard(text) {
+            ActionResult::Success(())
+        } else {
+            self.alerts.alert(Alert::error("Could not set clipboard"));
+            ActionResult::Failure(())
         }
-        if self.mouse.x + horizontal_scroll + 16 < left_margin {
+    }
+
+    /// Copies the path to the selected text's element, or the hovered one if nothing's selected
+    fn try_copy_path(&mut self) -> ActionResult {
+        let indices = if let Some(selected_text) = &self.tabs.active_tab().selected_text {
+            selected_text.indices.clone()
+        } else if let InteractionInformation::Content { is_in_left_margin: false, indices, .. } = get_interaction_information!(self) {
+            indices
+        } else {
             return ActionResult::Pass
-        }
-        let y = self.mouse.y - HEADER_SIZE + scroll;
-        let x = (self.mouse.x + horizontal_scroll - left_margin) / 16 - 1;
-        
-        let Some(HeldEntry { kv, indices_history }) = tab.held_entry.take() else { return ActionResult::Pass };
-        if let Some(indices) = tab.root.create_drop_indices((kv.0.as_deref(), &kv.1), y, x) {
-            let AddElementResult { indices, old_kv } = add_element(&mut tab.root, kv, indices, mutable_indices!(tab)).alert_err(&mut self.alerts).failure_on_err()?;
-            expand_element_to_indices(&mut tab.root, &indices, &mut tab.bookmarks).alert_err(&mut self.alerts);
-            tab.history.append(WorkbenchAction::AddFromHeldEntry { indices, old_kv, indices_history });
+        };
+        let tab = self.tabs.active_tab();
+        let Some(path) = NbtPath::display_from_indices(&tab.root, &indices, tab.path.name()) else { return ActionResult::Pass };
+        if set_clipboard(path) {
             ActionResult::Success(())
         } else {
-            tab.history.append(WorkbenchAction::DiscardHeldEntry { held_entry: HeldEntry { kv, indices_history } });
-            ActionResult::Success(())
+            self.alerts.alert(Alert::error("Could not set clipboard"));
+            ActionResult::Failure(())
         }
     }
 
-    #[deprecated = "refactor to UFCS only"]
-    fn hold_entry(&mut self, button: MouseButton) -> AnyhowActionResult {
-        if button == MouseButton::Left && self.mouse.x >= 16 + 16 + 4 {
-            let tab = self.tabs.active_tab_mut();
-            let x = self.mouse.x - (16 + 16 + 4);
-            if x / 16 == 13 {
-                match NbtElement::from_str(&get_clipboard().ok_or_else(|| anyhow!("Failed to get clipboard")).failure_on_err()?) {
-                    Ok((key, element)) =>
-                        if element.is_chunk() && !tab.root.is_region() {
-                            return ActionResult::Failure(anyhow!("Chunks are not supported for non-region tabs"));
-                        } else {
-                            let old_held_entry = tab.held_entry.replace(HeldEntry::from_aether((key, element)));
-                            if let Some(held_entry) = old_held_entry {
-                                tab.history.append(WorkbenchAction::DiscardHeldEntry { held_entry });
-                            }
-                            tab.history.append(WorkbenchAction::CreateHeldEntry);
-                        },
-                    Err(idx) => return ActionResult::Failure(anyhow!("Could not parse clipboard as SNBT (failed at index {idx})")),
+    /// The cursor relative to the pane of the active tab, which is the whole window unless it's split
+    #[must_use]
+    pub fn pane_mouse(&self) -> Vec2u {
+        let x = self.tabs.pane(self.tabs.active_tab_idx(), self.window_dims.width as usize).map_or(0, |(x, _)| x);
+        Vec2u::new(self.mouse.x.saturating_sub(x), self.mouse.y)
+    }
+
+    /// How wide the pane showing the tab at `idx` is, or the window if it isn't split, and how tall
+    fn pane_dims(&self, idx: usize) -> Vec2u {
+        let window_width = self.window_dims.width as usize;
+        Vec2u::new(self.tabs.pane(idx, window_width).map_or(window_width, |(_, width)| width), self.window_dims.height as usize)
+    }
+
+    /// Where the active tab is drawn, [`None`] for the whole window
+    fn active_viewport(&self, builder: &VertexBufferBuilder) -> Option<Viewport> {
+        let tab = self.tabs.active_tab();
+        self.tabs.pane(self.tabs.active_tab_idx(), builder.window_width()).map(|(x, width)| Viewport { x, width, scroll: tab.consts().scroll })
+    }
+
+    #[must_use]
+    fn are_breadcrumbs_visible(&self) -> bool { Breadcrumbs::is_visible(self.tabs.active_tab()) && self.tabs.active_tab().hex_view.is_none() && !ReplaceBox::is_visible(&self.search_box, &self.replace_box) && !self.go_to_box.is_selected() && !self.url_box.is_selected() && !self.values_box.is_open() }
+
+    fn try_collapse_to_breadcrumb(&mut self) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        let Some(indices) = self.breadcrumbs.segment_at(&tab.root, self.mouse, self.window_dims.width as usize) else { return ActionResult::Pass };
+        let indices = indices.to_owned();
+        tab.collapse_to(&indices).alert_err(&mut self.alerts).failure_on_err()?;
+        ActionResult::Success(())
+    }
+
+    #[deprecated = "refactor to UFCS only"]
+    fn delete(&mut self, clipboard: bool) -> ActionResult {
+        self.tabs.active_tab().ensure_writable(&mut self.notifications)?;
+        if !self.tabs.active_tab().selection.is_empty() {
+            return self.delete_selection(clipboard)
+        }
+        if let InteractionInformation::Content { is_in_left_margin: false, indices, .. } = get_interaction_information!(self) {
+            self.delete_element(indices, clipboard)
+        } else {
+            ActionResult::Pass
+        }
+    }
+
+    /// Removes the element at `indices`, copying it as SNBT first if `clipboard`
+    fn delete_element(&mut self, indices: OwnedIndices, clipboard: bool) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        if clipboard {
+            let NavigationInformation { key, element: value, .. } = tab.root.navigate(&indices).alert_err(&mut self.alerts).failure_on_err()?;
+            self.clipboard_ring.push(vec![(key.map(CompactString::from), value.clone())]);
+            let key = key.map(|key| if key.needs_escape() { format_compact!("{key:?}") } else { key.to_compact_string() });
+            let mut buf = String::new();
+            if write!(&mut buf, "{}{}{value}", key.as_ref().map_or("", CompactString::as_str), if key.is_some() { ":" } else { "" }).is_ok() {
+                set_clipboard(buf);
+            }
+        }
+        let result = remove_element(&mut tab.root, indices, mutable_indices!(tab)).alert_err(&mut self.alerts).failure_on_err()?;
+        tab.history.append(result.into_action());
+        ActionResult::Success(())
+    }
+
+    /// Removes every selected element as one action, copying them as SNBT first if `clipboard`
+    fn delete_selection(&mut self, clipboard: bool) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        if clipboard {
+            self.clipboard_ring.push(tab.selection.entries(&tab.root));
+            set_clipboard(tab.selection.to_snbt(&tab.root, false));
+        }
+        let mut actions = Vec::new();
+        // from the last, so the rest keep their indices
+        for indices in tab.selection.take().into_iter().rev() {
+            if let Some(result) = remove_element(&mut tab.root, indices, mutable_indices!(tab)).alert_err(&mut self.alerts) {
+                actions.push(result.into_action());
+            }
+        }
+        if let Some(action) = WorkbenchAction::bulk(actions) {
+            tab.history.append(action);
+        }
+        tab.refresh_scrolls();
+        ActionResult::Success(())
+    }
+
+    #[deprecated = "refactor to UFCS only"]
+    fn drop_held_entry(&mut self) -> ActionResult {
+        let mouse = self.pane_mouse();
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        let TabConstants { left_margin, scroll, horizontal_scroll, .. } = tab.consts();
+
+        if mouse.y <= HEADER_SIZE {
+            return ActionResult::Pass
+        }
+        if mouse.x + horizontal_scroll + 16 < left_margin {
+            return ActionResult::Pass
+        }
+        let y = mouse.y - HEADER_SIZE + scroll;
+        let x = (mouse.x + horizontal_scroll - left_margin) / 16 - 1;
+        
+        let Some(held_entry) = &tab.held_entry else { return ActionResult::Pass };
+        if let Some(indices) = tab.root.create_drop_indices((held_entry.kv.0.as_deref(), &held_entry.kv.1), y, x) {
+            let indices = if self.held_keys.ctrl() || self.held_keys.alt() {
+                let Some(indices) = self.leave_held_entry_original(indices) else { return ActionResult::Failure(()) };
+                indices
+            } else {
+                indices
+            };
+            self.drop_held_entry_at(indices, Vec::new())
+        } else {
+            self.return_held_entry()
+        }
+    }
+
+    /// Puts the original of an entry picked up from the tree back and holds a copy of it instead, so dropping it at `indices` copies rather than moves it, giving back where that is once the original is back
+    ///
+    /// Only once nothing else has happened since it was picked up, otherwise it's still moved
+    fn leave_held_entry_original(&mut self, indices: OwnedIndices) -> Option<OwnedIndices> {
+        let tab = self.tabs.active_tab_mut();
+        let Some(held_entry) = &tab.held_entry else { return Some(indices) };
+        if held_entry.copy || held_entry.indices_history.is_empty() || !matches!(tab.history.last(), Some(WorkbenchAction::RemoveToHeldEntry)) {
+            return Some(indices)
+        }
+        let kv = held_entry.kv.clone();
+        // moved along as the original goes back in before it
+        let mut target = Some(indices);
+        {
+            let mi = mutable_indices!(tab);
+            mi.temp.push(&mut target);
+            tab.history.undo_forgotten(&mut tab.root, mi, &mut tab.path, &mut tab.format, &mut tab.held_entry).alert_err(&mut self.alerts)?;
+        }
+        tab.selection.dragging = false;
+        tab.held_entry = Some(HeldEntry::copy_of(kv));
+        target
+    }
+
+    /// Puts the held entry back where it was picked up from, or at the end of the root if that's gone, and discards it if it wasn't picked up from the tree or is a copy
+    ///
+    /// If nothing happened since it was picked up, that's forgotten too, so the round trip leaves nothing to undo
+    fn return_held_entry(&mut self) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.selection.dragging = false;
+        let Some(held_entry) = tab.held_entry.take() else { return ActionResult::Pass };
+        // nothing was recorded when it was picked up, and the original never left
+        if held_entry.copy {
+            return ActionResult::Success(())
+        }
+        let Some(origin) = held_entry.indices_history.get().cloned() else {
+            tab.history.append(WorkbenchAction::DiscardHeldEntry { held_entry });
+            return ActionResult::Success(())
+        };
+        let fits = |indices: &Indices| {
+            indices.split_last().is_some_and(|(idx, parent)| {
+                tab.root.navigate(parent).is_ok_and(|NavigationInformation { element: parent, .. }| parent.len().is_some_and(|len| idx <= len) && parent.can_insert(&held_entry.kv.1))
+            })
+        };
+        let origin_fits = fits(&origin);
+        let root = OwnedIndices::from([tab.root.len().unwrap_or(0)]);
+        let root_fits = fits(&root);
+        tab.held_entry = Some(held_entry);
+        if origin_fits {
+            if let Some(WorkbenchAction::RemoveToHeldEntry) = tab.history.last() {
+                tab.history.undo_forgotten(&mut tab.root, mutable_indices!(tab), &mut tab.path, &mut tab.format, &mut tab.held_entry).alert_err(&mut self.alerts).failure_on_err()?;
+                return ActionResult::Success(())
+            }
+            self.drop_held_entry_at(origin, Vec::new())
+        } else if root_fits {
+            self.notifications.notify(Notification::new("Where it was picked up from is gone, so it was put at the end of the root instead", TextColor::Yellow, NotificationKind::HeldEntry));
+            self.drop_held_entry_at(root, Vec::new())
+        } else {
+            let tab = self.tabs.active_tab_mut();
+            let Some(held_entry) = tab.held_entry.take() else { return ActionResult::Pass };
+            self.notifications.notify(Notification::new("Where it was picked up from is gone, so it was discarded", TextColor::Yellow, NotificationKind::HeldEntry));
+            tab.history.append(WorkbenchAction::DiscardHeldEntry { held_entry });
+            ActionResult::Success(())
+        }
+    }
+
+    /// Drops the held entry at `indices`, first asking through a [`KeyConflictPrompt`] what to do with each key the compound already has, which `resolutions` answers in order
+    fn drop_held_entry_at(&mut self, indices: OwnedIndices, resolutions: Vec<KeyConflictResolution>) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        let Some(HeldEntry { kv, indices_history, pasted, copy }) = tab.held_entry.take() else { return ActionResult::Pass };
+        let Some((_, parent_indices)) = indices.split_last() else {
+            tab.held_entry = Some(HeldEntry { kv, indices_history, pasted, copy });
+            return ActionResult::Pass
+        };
+        let parent_indices = parent_indices.to_owned();
+        if let Ok(NavigationInformation { element: parent, .. }) = tab.root.navigate(&parent_indices) {
+            // lists and arrays have nowhere to keep the keys
+            if let Some(rest) = &pasted
+                && core::iter::once(&kv).chain(rest).any(|(key, _)| key.is_some())
+                && !parent.is_compound()
+                && !parent.is_chunk()
+            {
+                self.alerts.alert(Alert::error(anyhow!("Named entries can't be pasted into a {} without losing their keys", parent.display_name().to_lowercase())));
+                tab.held_entry = Some(HeldEntry { kv, indices_history, pasted, copy });
+                return ActionResult::Failure(())
+            }
+            if let Some(map) = compound_map(parent)
+                && let Some(prompt) = KeyConflictPrompt::new(indices.clone(), conflicting_keys(map, core::iter::once(&kv).chain(pasted.iter().flatten()), &resolutions), resolutions.clone())
+            {
+                tab.held_entry = Some(HeldEntry { kv, indices_history, pasted, copy });
+                self.key_conflict_prompt = Some(prompt);
+                return ActionResult::Success(())
+            }
+        }
+
+        let mut resolutions = resolutions.into_iter();
+        let mut indices_history = Some(indices_history);
+        let mut actions = Vec::new();
+        let mut first = None;
+        let mut next = indices;
+        for (n, mut kv) in core::iter::once(kv).chain(pasted.into_iter().flatten()).enumerate() {
+            let conflict = kv.0.as_deref().and_then(|key| {
+                let map = compound_map(tab.root.navigate(&parent_indices).ok()?.element)?;
+                Some((map.idx_of(key)?, unique_key(key, |key| map.has(key))))
+            });
+            if let Some((existing, renamed)) = conflict {
+                if resolutions.next() == Some(KeyConflictResolution::Replace) {
+                    let mut existing_indices = parent_indices.clone();
+                    existing_indices.push(existing);
+                    let Some(removed) = remove_element(&mut tab.root, existing_indices, mutable_indices!(tab)).alert_err(&mut self.alerts) else { break };
+                    actions.push(removed.into_action());
+                    if let Some(idx) = next.last_mut()
+                        && existing < *idx
+                    {
+                        *idx -= 1;
+                    }
+                } else {
+                    kv.0 = Some(renamed);
                 }
+            }
+            let Some(AddElementResult { indices, old_kv }) = add_element(&mut tab.root, kv, next.clone(), mutable_indices!(tab)).alert_err(&mut self.alerts) else { break };
+            if n == 0 {
+                expand_element_to_indices(&mut tab.root, &indices, &mut tab.bookmarks).alert_err(&mut self.alerts);
+                first = Some(indices.clone());
+            }
+            // a copy was never taken from anywhere, so undoing it just removes it
+            if n == 0 && !copy {
+                actions.push(WorkbenchAction::AddFromHeldEntry { indices, old_kv, indices_history: indices_history.take().unwrap_or_else(LinkedQueue::new) });
+            } else {
+                actions.push(AddElementResult { indices, old_kv }.into_action());
+            }
+            // each one goes after the last
+            if let Some(idx) = next.last_mut() {
+                *idx += 1;
+            }
+        }
+        if let Some(action) = WorkbenchAction::bulk(actions) {
+            tab.history.append(action);
+        }
+        let Some(first) = first else { return ActionResult::Failure(()) };
+        if tab.selection.dragging
+            && let Some(action) = tab.move_selection_after(first)
+        {
+            tab.history.append(action);
+        }
+        ActionResult::Success(())
+    }
+
+    /// While the [`KeyConflictPrompt`] is open, nothing else can be clicked
+    fn click_key_conflict_prompt(&mut self, button: MouseButton) -> ActionResult {
+        let Some(prompt) = &mut self.key_conflict_prompt else { return ActionResult::Pass };
+        if button != MouseButton::Left {
+            return ActionResult::Success(())
+        }
+        let resolution = match prompt.button_at(self.mouse, self.window_dims) {
+            Some(KeyConflictPromptButton::Replace) => KeyConflictResolution::Replace,
+            Some(KeyConflictPromptButton::KeepBoth) => KeyConflictResolution::KeepBoth,
+            Some(KeyConflictPromptButton::Cancel) => {
+                self.key_conflict_prompt = None;
+                return ActionResult::Success(())
+            }
+            Some(KeyConflictPromptButton::ApplyToAll) => {
+                prompt.toggle_apply_to_all();
+                return ActionResult::Success(())
+            }
+            None => return ActionResult::Success(()),
+        };
+        let Some(prompt) = self.key_conflict_prompt.take() else { return ActionResult::Pass };
+        let (indices, resolutions) = prompt.resolve(resolution);
+        self.drop_held_entry_at(indices, resolutions)
+    }
+
+    #[deprecated = "refactor to UFCS only"]
+    fn hold_entry(&mut self, button: MouseButton) -> AnyhowActionResult {
+        if button == MouseButton::Left && self.mouse.x >= 16 + 16 + 4 {
+            let tab = self.tabs.active_tab_mut();
+            let x = self.mouse.x - (16 + 16 + 4);
+            // the notification already says why
+            if x / 16 <= 13 && !tab.ensure_writable(&mut self.notifications).passed() {
+                return ActionResult::Success(());
+            }
+            if x / 16 == 13 {
+                let held_entry = HeldEntry::parse_clipboard(&mut self.alerts, tab.root.is_region()).failure_on_err()?;
+                let old_held_entry = tab.held_entry.replace(held_entry);
+                if let Some(held_entry) = old_held_entry {
+                    tab.history.append(WorkbenchAction::DiscardHeldEntry { held_entry });
+                }
+                tab.history.append(WorkbenchAction::CreateHeldEntry);
             } else {
                 let old_held_entry = tab.held_entry.replace(HeldEntry::from_aether((None, match x / 16 {
                     0 => NbtElement::Byte(NbtByte::default()),
@@ -797,6 +1736,53 @@ impl Workbench {
         ActionResult::Success(())
     }
 
+    /// How wide all of the tabs are together, including the ones still loading
+    fn tab_strip_width(&self) -> usize {
+        let mut width = TabManager::STRIP_START;
+        for tab in &self.tabs {
+            width += tab.strip_width() + TabManager::STRIP_GAP;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        for loading in &self.loading_tabs {
+            width += loading.path.name().width() + 48 + 9 + TabManager::STRIP_GAP;
+        }
+        width
+    }
+
+    fn tab_strip_overflows(&self) -> bool { self.tab_strip_width() > self.window_dims.width as usize }
+
+    /// How much of the window the tabs are shown in, which leaves room for the scroll buttons once they don't all fit
+    fn visible_tab_strip_width(&self) -> usize {
+        let window_width = self.window_dims.width as usize;
+        if self.tab_strip_overflows() { window_width.saturating_sub(Self::TAB_SCROLL_BUTTON_WIDTH * 2 + 2) } else { window_width }
+    }
+
+    fn scroll_tabs(&mut self, delta: isize) {
+        let max = self.tab_strip_width().saturating_sub(self.visible_tab_strip_width());
+        self.tab_scroll = self.tab_scroll.saturating_add_signed(delta).min(max);
+    }
+
+    /// Scrolls the tab strip just far enough for all of the active tab to be seen
+    fn scroll_to_active_tab(&mut self) {
+        let start = self.tabs.tab_start(self.tabs.active_tab_idx());
+        let end = start + self.tabs.active_tab().strip_width() + TabManager::STRIP_GAP;
+        let visible = self.visible_tab_strip_width();
+        if start < self.tab_scroll + TabManager::STRIP_START {
+            self.tab_scroll = start - TabManager::STRIP_START;
+        } else if end > self.tab_scroll + visible {
+            self.tab_scroll = end - visible;
+        }
+    }
+
+    fn click_tab_scroll_buttons(&mut self, button: MouseButton) -> ActionResult {
+        if button != MouseButton::Left {
+            return ActionResult::Pass
+        }
+        let left = self.mouse.x < self.visible_tab_strip_width() + 2 + Self::TAB_SCROLL_BUTTON_WIDTH;
+        self.scroll_tabs(if left { -48 } else { 48 });
+        ActionResult::Success(())
+    }
+
     fn click_tab(&mut self, button: MouseButton) -> ActionResult {
         let mouse_x = self.mouse.x + self.tab_scroll;
         if mouse_x < 2 {
@@ -808,25 +1794,25 @@ impl Workbench {
 
         let mut x = mouse_x - 2;
         for (idx, tab) in self.tabs.iter_mut().enumerate() {
-            let width = tab.path.name().width() + 48 + 5;
+            let width = tab.display_name().width() + tab.read_only_icon_width() + 48 + 5;
 
             if x <= width {
                 if button == MouseButton::Middle {
-                    drop_on_separate_thread(self.tabs.remove(idx));
-                    return ActionResult::Success(());
+                    return self.close_tab(idx);
                 } else if idx == active_tab_idx && x > width - 16 && x < width {
-                    if button == MouseButton::Left {
-                        tab.format = tab.format.cycle();
-                        return ActionResult::Success(());
-                    } else if button == MouseButton::Right {
-                        tab.format = tab.format.rev_cycle();
-                        return ActionResult::Success(());
+                    tab.ensure_writable(&mut self.notifications)?;
+                    if button == MouseButton::Left || button == MouseButton::Right {
+                        let format = tab.cycled_format(button == MouseButton::Right);
+                        return self.set_format(format);
                     }
                 } else if idx == active_tab_idx && x + 1 >= width - 32 && x < width - 16 {
-                    tab.save(shift).alert_err(&mut self.alerts);
-                    return ActionResult::Success(());
+                    return self.save_active_tab(shift);
                 } else if button == MouseButton::Left {
                     self.tabs.set_active_idx(idx);
+                    self.tabs.hold(idx, mouse_x);
+                    return ActionResult::Success(());
+                } else if button == MouseButton::Right {
+                    self.tab_menu.open(idx, self.mouse.x);
                     return ActionResult::Success(());
                 }
             }
@@ -840,25 +1826,156 @@ impl Workbench {
             }
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
+        for idx in 0..self.loading_tabs.len() {
+            let width = self.loading_tabs[idx].path.name().width() + 48 + 5;
+
+            if x <= width {
+                if button == MouseButton::Middle {
+                    self.loading_tabs.remove(idx).cancel();
+                    return ActionResult::Success(());
+                }
+                return ActionResult::Pass;
+            }
+
+            x -= width;
+
+            if x < 6 {
+                return ActionResult::Pass;
+            }
+            x -= 6;
+        }
+
         if button == MouseButton::Middle {
-            self.tabs.add(Tab::new_empty_tab(shift, self.window_dims));
+            self.tabs.add_empty(if shift { NewTabRoot::Region } else { NewTabRoot::Compound }, self.window_dims);
         }
 
         ActionResult::Pass
     }
 
+    /// Closes the tab, first asking what to do with its unsaved changes if it has any
+    #[cfg(not(target_arch = "wasm32"))]
+    fn close_tab(&mut self, idx: usize) -> ActionResult {
+        let Some(tab) = self.tabs.get_mut(idx) else { return ActionResult::Pass };
+        let can_close = tab.confirm_close(&mut self.alerts);
+        self.ignore_event_end = Timestamp::now() + Duration::from_millis(50);
+        if !can_close.alert_err(&mut self.alerts).failure_on_err()? {
+            return ActionResult::Success(())
+        }
+        self.remove_tab(idx);
+        ActionResult::Success(())
+    }
+
+    /// Closes the tabs at `indices`, asking about all of their unsaved changes at once, see [`TabManager::confirm_close`]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn close_tabs(&mut self, indices: &[usize]) -> ActionResult {
+        let closable = self.tabs.confirm_close(indices, &mut self.alerts);
+        self.ignore_event_end = Timestamp::now() + Duration::from_millis(50);
+        let mut closable = closable.alert_err(&mut self.alerts).failure_on_err()?;
+        // from the right, so the ones left to close keep their indices
+        closable.sort_unstable();
+        for idx in closable.into_iter().rev() {
+            self.remove_tab(idx);
+        }
+        ActionResult::Success(())
+    }
+
+    /// Closes the tab without asking, so it can be reopened later if it has a file
+    #[cfg(not(target_arch = "wasm32"))]
+    fn remove_tab(&mut self, idx: usize) {
+        // closing the last tab quits, which leaves nothing to reopen
+        if self.tabs.len() == 1
+            && config::get_restore_session()
+            && let Err(e) = Session::default().write()
+        {
+            error!("Could not save session: {e}");
+        }
+        let tab = self.tabs.remove(idx);
+        if let Ok(path) = std::fs::canonicalize(tab.path.path())
+            && path.is_file()
+        {
+            if self.closed_tabs.len() >= Self::CLOSED_TABS_CAPACITY {
+                self.closed_tabs.remove(0);
+            }
+            self.closed_tabs.push(path);
+        }
+        drop_on_separate_thread(tab);
+    }
+
+    /// Closes the tab, first asking what to do with its unsaved changes through a [`ClosePrompt`] if it has any
+    #[cfg(target_arch = "wasm32")]
+    fn close_tab(&mut self, idx: usize) -> ActionResult {
+        let Some(tab) = self.tabs.get_mut(idx) else { return ActionResult::Pass };
+        if tab.history.has_unsaved_changes() {
+            self.close_prompt = Some(ClosePrompt::new(idx));
+        } else {
+            drop_on_separate_thread(self.tabs.remove(idx));
+        }
+        ActionResult::Success(())
+    }
+
+    /// There's no way to ask about more than one tab at a time, so the ones with unsaved changes are left open for [`Workbench::close_tab`]
+    #[cfg(target_arch = "wasm32")]
+    fn close_tabs(&mut self, indices: &[usize]) -> ActionResult {
+        let mut unsaved = 0_usize;
+        for &idx in indices.iter().rev() {
+            if self.tabs.get_mut(idx).is_some_and(|tab| tab.history.has_unsaved_changes()) {
+                unsaved += 1;
+            } else {
+                drop_on_separate_thread(self.tabs.remove(idx));
+            }
+        }
+        if unsaved > 0 {
+            self.notifications.notify(Notification::new(format!("{unsaved} tabs with unsaved changes were left open"), TextColor::Yellow, NotificationKind::Save));
+        }
+        ActionResult::Success(())
+    }
+
+    /// While the [`ClosePrompt`] is open, nothing else can be clicked
+    #[cfg(target_arch = "wasm32")]
+    fn click_close_prompt(&mut self, button: MouseButton) -> ActionResult {
+        let Some(prompt) = &self.close_prompt else { return ActionResult::Pass };
+        let idx = prompt.idx();
+        let Some(tab) = self.tabs.get_mut(idx) else {
+            self.close_prompt = None;
+            return ActionResult::Pass
+        };
+        if button != MouseButton::Left {
+            return ActionResult::Success(())
+        }
+        match ClosePrompt::button_at(tab, self.mouse, self.window_dims) {
+            Some(ClosePromptButton::Save) => {
+                self.close_prompt = None;
+                tab.save(false, &mut self.alerts).alert_err(&mut self.alerts).failure_on_err()?;
+                drop_on_separate_thread(self.tabs.remove(idx));
+            }
+            Some(ClosePromptButton::Discard) => {
+                self.close_prompt = None;
+                drop_on_separate_thread(self.tabs.remove(idx));
+            }
+            Some(ClosePromptButton::Cancel) => self.close_prompt = None,
+            None => {}
+        }
+        ActionResult::Success(())
+    }
+
     #[deprecated = "refactor to UFCS only"]
     #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
     fn open_file(&mut self) -> ActionResult {
         let dialog = native_dialog::FileDialogBuilder::default()
             .set_location("~/Downloads")
             .add_filters(Tab::FILE_TYPE_FILTERS.iter().copied().map(|(a, b)| (a.to_owned(), b.iter().map(|x| x.to_string()).collect::<Vec<_>>())))
-            .open_single_file();
+            .add_filter(Tab::JSON_FILE_TYPE_FILTER.0, Tab::JSON_FILE_TYPE_FILTER.1)
+            .open_multiple_file();
         let dialog_result = dialog.show();
         self.ignore_event_end = Timestamp::now() + Duration::from_millis(50);
-        let path = dialog_result.alert_err(&mut self.alerts).failure_on_err()?.failure_on_err()?;
-        let bytes = std::fs::read(&path).alert_err(&mut self.alerts).failure_on_err()?;
-        self.on_open_file(&path, bytes).alert_err(&mut self.alerts);
+        let paths = dialog_result.alert_err(&mut self.alerts).failure_on_err()?;
+        if paths.is_empty() {
+            return ActionResult::Failure(())
+        }
+        for path in paths {
+            self.open_path_in_background(path);
+        }
         ActionResult::Success(())
     }
 
@@ -868,7 +1985,7 @@ impl Workbench {
 
     #[must_use]
     pub fn get_interaction_information_raw(consts: TabConstants, mouse: Vec2u, root: &mut NbtElement) -> InteractionInformation {
-        let TabConstants { left_margin, scroll, horizontal_scroll } = consts;
+        let TabConstants { left_margin, scroll, horizontal_scroll, .. } = consts;
 
         if mouse.y < HEADER_SIZE {
             return InteractionInformation::Header
@@ -901,16 +2018,17 @@ impl Workbench {
 
     #[deprecated = "refactor to UFCS only"]
     fn try_root_style_change(&mut self) -> ActionResult {
+        let mouse = self.pane_mouse();
         let tab = self.tabs.active_tab_mut();
         let TabConstants { left_margin, horizontal_scroll, scroll, .. } = tab.consts();
-        if self.mouse.x + horizontal_scroll < left_margin {
+        if mouse.x + horizontal_scroll < left_margin {
             return ActionResult::Pass
         }
-        if self.mouse.y < HEADER_SIZE {
+        if mouse.y < HEADER_SIZE {
             return ActionResult::Pass
         }
-        let x = (self.mouse.x + horizontal_scroll - left_margin) / 16;
-        let y = (self.mouse.y - HEADER_SIZE) / 16 + scroll / 16;
+        let x = (mouse.x + horizontal_scroll - left_margin) / 16;
+        let y = (mouse.y - HEADER_SIZE) / 16 + scroll / 16;
         if !(x == 1 && y == 0) {
             return ActionResult::Pass
         }
@@ -920,6 +2038,56 @@ impl Workbench {
         ActionResult::Success(())
     }
 
+    /// Flips the byte under the mouse between `true` and `false` when its checkbox is clicked, see [`checkbox`]
+    fn try_flip_boolean(&mut self) -> ActionResult {
+        let mouse = self.pane_mouse();
+        let tab = self.tabs.active_tab_mut();
+        if tab.held_entry.is_some() || tab.freehand_mode {
+            return ActionResult::Pass
+        }
+        let consts @ TabConstants { left_margin, horizontal_scroll, number_base, .. } = tab.consts();
+        let InteractionInformation::Content { is_in_left_margin: false, depth, key, value, indices, .. } = Self::get_interaction_information_raw(consts, mouse, &mut tab.root) else { return ActionResult::Pass };
+        let booleans = tab.boolean_overrides.shows_boolean(&indices);
+        let Some(boolean) = value.as_byte().and_then(|byte| byte.value.as_boolean()).filter(|_| booleans) else { return ActionResult::Pass };
+        let key_width = key.as_ref().map_or(0, |key| key.width() + ": ".width());
+        let text_end = Indices::end_x_from_depth(depth, left_margin) + SelectedText::PREFIXING_SPACE_WIDTH + key_width + value.keyed_value_width(key.as_deref(), number_base, booleans);
+        let checkbox_x = text_end + " ".width();
+        if !(checkbox_x..checkbox_x + checkbox(boolean).width()).contains(&(mouse.x + horizontal_scroll)) {
+            return ActionResult::Pass
+        }
+        tab.ensure_writable(&mut self.notifications)?;
+        let action = rename_element(&mut tab.root, indices, None, Some((!boolean).to_string()), &mut tab.path).alert_err(&mut self.alerts).failure_on_err()?.into_action();
+        tab.history.append(action);
+        ActionResult::Success(())
+    }
+
+    /// Ctrl clicking a row toggles whether it's selected and Shift clicking one selects every row from the last one Ctrl clicked, while clicking outside the selection clears it
+    fn try_select_row(&mut self) -> ActionResult {
+        let Modifiers { ctrl, shift, .. } = self.held_keys.modifiers();
+        let tab = self.tabs.active_tab();
+        if tab.held_entry.is_some() || tab.freehand_mode || tab.root.as_region().is_some_and(NbtRegion::is_grid_layout) {
+            return ActionResult::Pass
+        }
+        // the toggle keeps its own clicks
+        let InteractionInformation::Content { is_in_left_margin: false, x, depth, indices, .. } = get_interaction_information!(self) else { return ActionResult::Pass };
+        if x <= depth || indices.is_root() {
+            return ActionResult::Pass
+        }
+        let tab = self.tabs.active_tab_mut();
+        if ctrl {
+            tab.selection.toggle(indices);
+        } else if shift {
+            tab.selection.select_range(&tab.root, indices);
+        } else {
+            // clicking within it might be to drag it
+            if !tab.selection.covers(&indices) {
+                tab.selection.clear();
+            }
+            return ActionResult::Pass
+        }
+        ActionResult::Success(())
+    }
+
     #[deprecated = "refactor to UFCS only"]
     fn toggle(&mut self, expand: bool, ignore_depth: bool) -> ActionResult {
         if let InteractionInformation::Content {
@@ -932,25 +2100,48 @@ impl Workbench {
         } = get_interaction_information!(self)
             && (x <= depth || ignore_depth)
             && value.is_complex()
-            && value.true_height() > 1
+            && (value.true_height() > 1 || value.as_chunk().is_some_and(NbtChunk::is_undecoded))
         {
             let is_open = value.is_open();
-            let tab = self.tabs.active_tab_mut();
             if expand {
-                expand_element(&mut tab.root, &indices, &mut tab.bookmarks).alert_err(&mut self.alerts);
+                return self.expand_to_depth(&indices, usize::MAX)
+            }
+            if !is_open && value.as_byte_array().is_some_and(|array| array.len() >= HexView::DEFAULT_THRESHOLD) {
+                return self.open_hex_view(indices)
+            }
+            let tab = self.tabs.active_tab_mut();
+            if is_open {
+                close_element(&mut tab.root, &indices, &mut tab.bookmarks).alert_err(&mut self.alerts);
             } else {
-                if is_open {
-                    close_element(&mut tab.root, &indices, &mut tab.bookmarks).alert_err(&mut self.alerts);
-                } else {
-                    open_element(&mut tab.root, &indices, &mut tab.bookmarks).alert_err(&mut self.alerts);
-                }
-            };
+                open_element(&mut tab.root, &indices, &mut tab.bookmarks).alert_err(&mut self.alerts);
+            }
             ActionResult::Success(())
         } else {
             ActionResult::Pass
         }
     }
 
+    /// Opens everything within the element at `indices` down to `depth` levels and closes everything deeper, where [`usize::MAX`] expands all of it
+    fn expand_to_depth(&mut self, indices: &Indices, depth: usize) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        if depth == usize::MAX {
+            let true_height = tab.root.navigate(indices).map_or(0, |info| info.element.true_height());
+            if !tab.confirm_expand(indices, true_height, &mut self.notifications) {
+                return ActionResult::Success(())
+            }
+            expand_element(&mut tab.root, indices, &mut tab.bookmarks).alert_err(&mut self.alerts).failure_on_err()?;
+        } else {
+            expand_element_to_depth(&mut tab.root, indices, depth, &mut tab.bookmarks).alert_err(&mut self.alerts).failure_on_err()?;
+        }
+        tab.refresh_scrolls();
+        ActionResult::Success(())
+    }
+
+    fn try_expand_selected_to_depth(&mut self, depth: usize) -> ActionResult {
+        let Some(indices) = self.tabs.active_tab().selected_text.as_ref().map(|selected_text| selected_text.indices.clone()) else { return ActionResult::Pass };
+        self.expand_to_depth(&indices, depth)
+    }
+
     fn try_select_search_box(&mut self, button: MouseButton) -> ActionResult {
         if !SearchBox::is_within_bounds(self.mouse, self.window_dims) {
             return ActionResult::Pass
@@ -966,8 +2157,8 @@ impl Workbench {
                 (0, self.search_box.value.len())
             } else {
                 (
-                    get_cursor_left_jump_idx(self.search_box.cursor, self.search_box.value.as_bytes()),
-                    get_cursor_right_jump_idx(self.search_box.cursor, self.search_box.value.as_bytes()),
+                    get_cursor_left_jump_idx(self.search_box.cursor, &self.search_box.value),
+                    get_cursor_right_jump_idx(self.search_box.cursor, &self.search_box.value),
                 )
             };
             // if they're == it's also false, just being careful here
@@ -996,8 +2187,8 @@ impl Workbench {
                 (0, self.replace_box.value.len())
             } else {
                 (
-                    get_cursor_left_jump_idx(self.replace_box.cursor, self.replace_box.value.as_bytes()),
-                    get_cursor_right_jump_idx(self.replace_box.cursor, self.replace_box.value.as_bytes()),
+                    get_cursor_left_jump_idx(self.replace_box.cursor, &self.replace_box.value),
+                    get_cursor_right_jump_idx(self.replace_box.cursor, &self.replace_box.value),
                 )
             };
             // if they're == it's also false, just being careful here
@@ -1012,23 +2203,677 @@ impl Workbench {
     }
 
     fn try_select_text(&mut self, snap_to_ends: bool) -> ActionResult {
+        let mouse = self.pane_mouse();
         let tab = self.tabs.active_tab_mut();
         let consts @ TabConstants { left_margin, scroll, horizontal_scroll, .. } = tab.consts();
 
-        if self.mouse.x + horizontal_scroll < left_margin {
+        if mouse.x + horizontal_scroll < left_margin {
             return ActionResult::Pass
         }
-        if self.mouse.y < HEADER_SIZE {
+        if mouse.y < HEADER_SIZE {
             return ActionResult::Pass
         }
+        tab.ensure_writable(&mut self.notifications)?;
 
-        let y = (self.mouse.y - HEADER_SIZE) / 16 + scroll / 16;
-        tab.set_selected_text_with_doubleclick(SelectedText::for_y(consts, &tab.root, &tab.path, y, self.mouse.x, snap_to_ends, None))
+        let y = (mouse.y - HEADER_SIZE) / 16 + scroll / 16;
+        if y > 0
+            && let Ok(TraversalInformation { indices, depth, key, element, .. }) = tab.root.traverse(y, None)
+            && element.as_string().is_some_and(NbtString::is_multiline)
+            && mouse.x + horizontal_scroll >= Indices::end_x_from_depth(depth, left_margin) + SelectedText::PREFIXING_SPACE_WIDTH + key.map_or(0, |key| key.width() + ": ".width())
+        {
+            return self.open_string_editor(indices)
+        }
+        tab.set_selected_text_with_doubleclick(SelectedText::for_y(consts, &tab.root, &tab.path, &tab.boolean_overrides, y, mouse.x, snap_to_ends, None))
             .alert_err(&mut self.alerts)
             .failure_on_err()?;
         ActionResult::Success(())
     }
 
+    /// Opens the [`ElementMenu`] for the hovered row when it's clicked past the end of its text, since the text itself is clicked into to edit it
+    fn try_open_element_menu(&mut self) -> ActionResult {
+        let mouse = self.pane_mouse();
+        let tab = self.tabs.active_tab_mut();
+        let consts @ TabConstants { left_margin, horizontal_scroll, number_base, .. } = tab.consts();
+        let name_width = tab.root_name().width();
+        let InteractionInformation::Content { is_in_left_margin: false, depth, key, value, indices, .. } = Self::get_interaction_information_raw(consts, mouse, &mut tab.root) else { return ActionResult::Pass };
+        // the root has the file name in place of a key
+        let key_width = if indices.is_root() { name_width } else { key.as_ref().map_or(0, |key| key.width() + ": ".width()) };
+        let text_end = Indices::end_x_from_depth(depth, left_margin) + SelectedText::PREFIXING_SPACE_WIDTH + key_width + value.keyed_value_width(key.as_deref(), number_base, tab.boolean_overrides.shows_boolean(&indices));
+        if mouse.x + horizontal_scroll < text_end + 8 {
+            return ActionResult::Pass
+        }
+        self.element_menu.open(&self.tabs.active_tab().root, indices, self.mouse, self.window_dims);
+        ActionResult::Success(())
+    }
+
+    fn click_clipboard_ring(&mut self, button: MouseButton) -> ActionResult {
+        if button != MouseButton::Left {
+            return ActionResult::Success(())
+        }
+        let Some(idx) = self.clipboard_ring.row_at(self.mouse) else { return ActionResult::Success(()) };
+        self.hold_from_clipboard_ring(idx)
+    }
+
+    /// Undoes or redoes the active tab back to the step clicked
+    fn click_history_panel(&mut self, button: MouseButton) -> ActionResult {
+        if button != MouseButton::Left {
+            return ActionResult::Success(())
+        }
+        let tab = self.tabs.active_tab_mut();
+        let steps = tab.history.steps();
+        let Some(position) = self.history_panel.row_at(self.mouse, steps.len(), self.window_dims).map(|idx| steps[idx].position) else { return ActionResult::Success(()) };
+        tab.ensure_writable(&mut self.notifications)?;
+        tab.selection.dragging = false;
+        tab.seek_history(position).alert_err(&mut self.alerts).failure_on_err()?;
+        ActionResult::Success(())
+    }
+
+    /// Up and Down move through the picker, Enter or a digit picks from it, and anything else is ignored while it's open
+    fn on_clipboard_ring_key(&mut self, key: KeyCode) -> ActionResult {
+        let idx = match key {
+            KeyCode::Escape => {
+                self.clipboard_ring.close();
+                return ActionResult::Success(())
+            }
+            KeyCode::ArrowUp => {
+                self.clipboard_ring.move_highlight(false);
+                return ActionResult::Success(())
+            }
+            KeyCode::ArrowDown => {
+                self.clipboard_ring.move_highlight(true);
+                return ActionResult::Success(())
+            }
+            KeyCode::Enter | KeyCode::NumpadEnter => self.clipboard_ring.highlighted().unwrap_or(0),
+            KeyCode::Digit1 => 0,
+            KeyCode::Digit2 => 1,
+            KeyCode::Digit3 => 2,
+            KeyCode::Digit4 => 3,
+            KeyCode::Digit5 => 4,
+            KeyCode::Digit6 => 5,
+            KeyCode::Digit7 => 6,
+            KeyCode::Digit8 => 7,
+            KeyCode::Digit9 => 8,
+            _ => return ActionResult::Success(()),
+        };
+        self.hold_from_clipboard_ring(idx)
+    }
+
+    /// Holds a copy of the entries at `idx` of the clipboard ring, to be dropped like those pasted from the clipboard
+    fn hold_from_clipboard_ring(&mut self, idx: usize) -> ActionResult {
+        let Some(entries) = self.clipboard_ring.pick(idx) else { return ActionResult::Pass };
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        if !tab.root.is_region() && entries.iter().any(|(_, element)| element.is_chunk()) {
+            self.alerts.alert(Alert::error(anyhow!("Chunks are not supported for non-region tabs")));
+            return ActionResult::Failure(())
+        }
+        let mut entries = entries.into_iter();
+        let Some(first) = entries.next() else { return ActionResult::Pass };
+        let old_held_entry = tab.held_entry.replace(HeldEntry::from_clipboard(first, entries.collect()));
+        if let Some(held_entry) = old_held_entry {
+            tab.history.append(WorkbenchAction::DiscardHeldEntry { held_entry });
+        }
+        tab.history.append(WorkbenchAction::CreateHeldEntry);
+        ActionResult::Success(())
+    }
+
+    fn click_element_menu(&mut self, button: MouseButton) -> ActionResult {
+        if button != MouseButton::Left {
+            return ActionResult::Success(())
+        }
+        let Some((indices, action)) = self.element_menu.click(self.mouse) else { return ActionResult::Success(()) };
+        self.element_menu.close();
+        match action {
+            ElementMenuAction::CopySnbt => self.apply_element_action(ElementAction::CopyRaw, indices),
+            action @ (ElementMenuAction::CopyKey | ElementMenuAction::CopyValue | ElementMenuAction::CopyValueSnbt) => {
+                let tab = self.tabs.active_tab();
+                let text = match action {
+                    // the rest of the selection along with it, when it's part of one
+                    ElementMenuAction::CopyKey if tab.selection.contains(&indices) => tab.selection.keys(&tab.root),
+                    ElementMenuAction::CopyValueSnbt if tab.selection.contains(&indices) => tab.selection.values_snbt(&tab.root, false),
+                    _ => {
+                        let NavigationInformation { key, element, .. } = tab.root.navigate(&indices).alert_err(&mut self.alerts).failure_on_err()?;
+                        if let ElementMenuAction::CopyKey = action {
+                            key.unwrap_or_default().to_owned()
+                        } else if let ElementMenuAction::CopyValueSnbt = action {
+                            element.to_string()
+                        } else if let Some(string) = element.as_string() {
+                            // all of it, where only a preview is shown of long strings
+                            string.str.as_str().to_owned()
+                        } else {
+                            element.keyed_value(key, tab.number_base, tab.boolean_overrides.shows_boolean(&indices)).0.into_owned()
+                        }
+                    }
+                };
+                if set_clipboard(text) {
+                    ActionResult::Success(())
+                } else {
+                    self.alerts.alert(Alert::error("Could not set clipboard"));
+                    ActionResult::Failure(())
+                }
+            }
+            ElementMenuAction::CopyPath => self.apply_element_action(ElementAction::CopyPath, indices),
+            ElementMenuAction::PasteInto => self.apply_element_action(ElementAction::InsertFromClipboard, indices),
+            ElementMenuAction::Duplicate => self.duplicate_element(indices),
+            ElementMenuAction::Delete => self.delete_element(indices, false),
+            ElementMenuAction::Rename => self.rename_element(&indices),
+            ElementMenuAction::ConvertTo(target) => self.convert_element(indices, target),
+            ElementMenuAction::SortByName => self.apply_element_action(ElementAction::SortCompoundByName, indices),
+            ElementMenuAction::SortByType => self.apply_element_action(ElementAction::SortCompoundByType, indices),
+            ElementMenuAction::SortRecursively => self.sort_element_recursively(&indices),
+            ElementMenuAction::Bookmark => {
+                let tab = self.tabs.active_tab_mut();
+                let NavigationInformation { line_number, true_line_number, .. } = tab.root.navigate(&indices).alert_err(&mut self.alerts).failure_on_err()?;
+                let _ = tab.bookmarks.toggle(MarkedLine::new(true_line_number, line_number));
+                ActionResult::Success(())
+            }
+            ElementMenuAction::ExpandAll => self.expand_to_depth(&indices, usize::MAX),
+            ElementMenuAction::CollapseAll => self.expand_to_depth(&indices, 0),
+            ElementMenuAction::HexView => self.open_hex_view(indices),
+            ElementMenuAction::EditAsText => self.edit_as_text(indices),
+            ElementMenuAction::EditTimestamp => self.edit_chunk_timestamp(indices),
+            ElementMenuAction::MoveChunk => self.open_chunk_pos_box(indices, ValuesBoxKind::MoveChunk),
+            ElementMenuAction::NewChunk => self.open_chunk_pos_box(indices, ValuesBoxKind::NewChunk),
+            ElementMenuAction::ExportChunk => self.export_chunk(&indices),
+            ElementMenuAction::ImportChunk => self.import_chunk(),
+            ElementMenuAction::BooleanDisplay => {
+                let as_boolean = self.tabs.active_tab_mut().boolean_overrides.toggle(indices);
+                let message = if as_boolean { "Showing this byte as a boolean" } else { "Showing this byte as a number" };
+                self.notifications.notify(Notification::new(message, TextColor::White, NotificationKind::Booleans));
+                ActionResult::Success(())
+            }
+        }
+    }
+
+    /// Selects the key of the element at `indices` to be typed over
+    fn rename_element(&mut self, indices: &Indices) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        let consts @ TabConstants { left_margin, horizontal_scroll, .. } = tab.consts();
+        let NavigationInformation { key: Some(key), line_number, .. } = tab.root.navigate(indices).alert_err(&mut self.alerts).failure_on_err()? else { return ActionResult::Pass };
+        // with the cursor at the end of the key
+        let mouse_x = (Indices::end_x_from_depth(indices.len(), left_margin) + key.width()).saturating_sub(horizontal_scroll);
+        let mut selected_text = SelectedText::for_y(consts, &tab.root, &tab.path, &tab.boolean_overrides, line_number, mouse_x, false, None).alert_err(&mut self.alerts).failure_on_err()?;
+        selected_text.selection = Some(0);
+        tab.selected_text = Some(selected_text);
+        tab.refresh_selected_text_horizontal_scroll();
+        ActionResult::Success(())
+    }
+
+    /// Replaces the element at `indices` with itself as `target`, see [`convert`]
+    fn convert_element(&mut self, indices: OwnedIndices, target: ConversionTarget) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        let NavigationInformation { key, element, .. } = tab.root.navigate(&indices).alert_err(&mut self.alerts).failure_on_err()?;
+        let converted = match convert(element, target) {
+            Ok(converted) => converted,
+            Err(e @ (ConversionError::Heterogeneous { .. } | ConversionError::WrongElementType { .. })) => {
+                self.notifications.notify(Notification::new(e.to_string(), TextColor::Yellow, NotificationKind::Convert));
+                return ActionResult::Failure(())
+            }
+            Err(e) => {
+                self.alerts.alert(Alert::new("Error!", TextColor::Red, e));
+                return ActionResult::Failure(())
+            }
+        };
+        let kv = (key.map(CompactString::from), converted);
+        let result = replace_element(&mut tab.root, kv, indices, mutable_indices!(tab)).alert_err(&mut self.alerts).failure_on_err()?;
+        tab.history.append(result.into_action());
+        tab.refresh_scrolls();
+        ActionResult::Success(())
+    }
+
+    /// Sorts every compound within the element at `indices` by name, see [`sort_element_recursively`]
+    fn sort_element_recursively(&mut self, indices: &Indices) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        if let Some(action) = sort_element_recursively(&mut tab.root, indices, ElementAction::by_name, mutable_indices!(tab)).alert_err(&mut self.alerts).failure_on_err()? {
+            tab.history.append(action);
+        }
+        ActionResult::Success(())
+    }
+
+    /// Opens the [`ValuesBox`] for the array or list of numbers at `indices`, holding its values, or the [`StringEditor`] for a string
+    fn edit_as_text(&mut self, indices: OwnedIndices) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        let element = tab.root.navigate(&indices).alert_err(&mut self.alerts).failure_on_err()?.element;
+        if element.is_string() {
+            return self.open_string_editor(indices)
+        }
+        let Some(values) = values_as_text(element, tab.number_base) else { return ActionResult::Pass };
+        self.values_box.open(indices, ValuesBoxKind::Values, values, self.window_dims);
+        self.search_box.deselect();
+        self.replace_box.deselect();
+        self.go_to_box.deselect();
+        self.url_box.deselect();
+        ActionResult::Success(())
+    }
+
+    /// Opens the [`ValuesBox`] on when the chunk at `indices` was last modified, as it's kept in the region's timestamp table
+    fn edit_chunk_timestamp(&mut self, indices: OwnedIndices) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        let Some(chunk) = tab.root.navigate(&indices).alert_err(&mut self.alerts).failure_on_err()?.element.as_chunk() else { return ActionResult::Pass };
+        let timestamp = timestamp_as_text(chunk);
+        self.values_box.open(indices, ValuesBoxKind::Timestamp, timestamp, self.window_dims);
+        self.search_box.deselect();
+        self.replace_box.deselect();
+        self.go_to_box.deselect();
+        self.url_box.deselect();
+        ActionResult::Success(())
+    }
+
+    /// Opens the [`ValuesBox`] on a chunk position, to move the chunk at `indices` to or to create a new chunk at in the region
+    fn open_chunk_pos_box(&mut self, indices: OwnedIndices, kind: ValuesBoxKind) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        self.values_box.open(indices, kind, String::new(), self.window_dims);
+        self.search_box.deselect();
+        self.replace_box.deselect();
+        self.go_to_box.deselect();
+        self.url_box.deselect();
+        ActionResult::Success(())
+    }
+
+    /// Moves the chunk at `indices` to `pos`, within the region or in the world as with [`Tab::chunk_idx`], as one action
+    fn move_chunk_to(&mut self, indices: &OwnedIndices, pos: (i32, i32)) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        let to = tab.chunk_idx(pos).alert_err(&mut self.alerts).failure_on_err()?;
+        let Some(from) = indices.first() else { return ActionResult::Pass };
+        let result = move_chunk(&mut tab.root, from, to, tab.path.path(), mutable_indices!(tab)).alert_err(&mut self.alerts).failure_on_err()?;
+        tab.history.append(result.into_action());
+        tab.refresh_scrolls();
+        ActionResult::Success(())
+    }
+
+    /// Adds a chunk at `pos` in the region, as with [`Tab::chunk_idx`], as one action, which is `imported` with its `xPos` and `zPos` moved there or else empty
+    fn new_chunk_at(&mut self, pos: (i32, i32), imported: Option<NbtCompound>) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        let idx = tab.chunk_idx(pos).alert_err(&mut self.alerts).failure_on_err()?;
+        let Some(region) = tab.root.as_region() else { return ActionResult::Pass };
+        if region.chunks[idx].as_chunk().is_some_and(NbtChunk::is_loaded) {
+            self.alerts.alert(Alert::new("Error!", TextColor::Red, format!("Chunk {}, {} is already in the region", idx % 32, idx / 32)));
+            return ActionResult::Failure(())
+        }
+        #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)] // within 0..32
+        let world = region.world_pos_of(tab.path.path(), idx).unwrap_or(((idx % 32) as i32, (idx / 32) as i32));
+        let chunk = match imported {
+            Some(compound) => NbtRegion::imported_chunk(compound, world),
+            None => region.empty_chunk(world),
+        };
+        let kv = (None, NbtElement::Chunk(chunk));
+        let result = add_element(&mut tab.root, kv, OwnedIndices::from([idx]), mutable_indices!(tab)).alert_err(&mut self.alerts).failure_on_err()?;
+        tab.history.append(result.into_action());
+        tab.refresh_scrolls();
+        ActionResult::Success(())
+    }
+
+    /// Switches the active tab to `format`, once it's [confirmed](Self::confirm_format)
+    fn set_format(&mut self, format: NbtFileFormat) -> ActionResult {
+        if self.confirm_format(format, WrapRootThen::Format(format)).alert_err(&mut self.alerts).failure_on_err()? {
+            self.tabs.active_tab_mut().set_format(format);
+        }
+        ActionResult::Success(())
+    }
+
+    /// Saves the active tab, through a dialog for where to if `force_dialog`, once its format is [confirmed](Self::confirm_format)
+    fn save_active_tab(&mut self, force_dialog: bool) -> ActionResult {
+        let format = self.tabs.active_tab().format;
+        if self.confirm_format(format, WrapRootThen::Save { force_dialog }).alert_err(&mut self.alerts).failure_on_err()? {
+            self.tabs.active_tab_mut().save(force_dialog, &mut self.alerts).alert_err(&mut self.alerts).failure_on_err()?;
+        }
+        ActionResult::Success(())
+    }
+
+    /// Whether to go on with writing the active tab as `format`, asking first if there's a [warning](Tab::format_warning) for it
+    ///
+    /// A list at the root can be wrapped in a compound instead, which opens the values box for its key and goes on to `then` once that's entered.
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+    fn confirm_format(&mut self, format: NbtFileFormat, then: WrapRootThen) -> Result<bool> {
+        let tab = self.tabs.active_tab_mut();
+        let Some(warning) = tab.format_warning(format) else { return Ok(true) };
+        let message = warning.message(tab.path.name(), format);
+        let anyway = match then {
+            WrapRootThen::Format(format) => format!("Switch to {format} anyway?"),
+            WrapRootThen::Save { .. } => "Save it anyway?".to_owned(),
+        };
+        match warning {
+            FormatWarning::RootList => {
+                if Tab::confirm(format!("{message}\n\nWrap the list in a compound under a key of your choice?"))? {
+                    self.values_box.open(OwnedIndices::new(), ValuesBoxKind::WrapRoot(then), "data".to_owned(), self.window_dims);
+                    return Ok(false)
+                }
+                let confirmed = Tab::confirm(anyway)?;
+                tab.root_list_confirmed = confirmed;
+                Ok(confirmed)
+            }
+            FormatWarning::RegionData => Tab::confirm(format!("{message}\n\n{anyway}")),
+        }
+    }
+
+    /// There's no dialog to ask with, so any [warning](Tab::format_warning) is only shown
+    #[cfg(target_arch = "wasm32")]
+    #[allow(clippy::unnecessary_wraps)]
+    fn confirm_format(&mut self, format: NbtFileFormat, _: WrapRootThen) -> Result<bool> {
+        let tab = self.tabs.active_tab();
+        if let Some(warning) = tab.format_warning(format) {
+            self.notifications.notify(Notification::new(warning.message(tab.path.name(), format), TextColor::Yellow, NotificationKind::Save));
+        }
+        Ok(true)
+    }
+
+    /// Wraps the list at the root of the active tab in a compound under `key`, as one action, then goes on with `then`
+    fn wrap_root(&mut self, key: CompactString, then: WrapRootThen) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        if !tab.root.is_list() {
+            return ActionResult::Pass
+        }
+        // the history keeps the list as it was to put back
+        let mut compound = NbtCompound::new(vec![CompoundEntry::new(key, tab.root.clone())]);
+        // SAFETY: it's recached right after
+        unsafe { compound.toggle() };
+        compound.recache();
+        let result = replace_element(&mut tab.root, (None, NbtElement::Compound(compound)), OwnedIndices::new(), mutable_indices!(tab)).alert_err(&mut self.alerts).failure_on_err()?;
+        tab.history.append(result.into_action());
+        tab.refresh_scrolls();
+        match then {
+            WrapRootThen::Format(format) => tab.set_format(format),
+            WrapRootThen::Save { force_dialog } => tab.save(force_dialog, &mut self.alerts).alert_err(&mut self.alerts).failure_on_err()?,
+        }
+        ActionResult::Success(())
+    }
+
+    /// The file types a chunk can be exported as or imported from, led by the gzip compressed NBT the game writes
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+    fn chunk_file_type_filters() -> impl Iterator<Item = (String, Vec<String>)> {
+        let (compressed, others) = Tab::FILE_TYPE_FILTERS.iter().copied().filter(|(_, extensions)| !extensions.contains(&"mca")).partition::<Vec<_>, _>(|(_, extensions)| extensions.contains(&"dat_old"));
+        compressed.into_iter().chain(others).map(|(a, b)| (a.to_owned(), b.iter().map(|&extension| extension.to_owned()).collect::<Vec<_>>()))
+    }
+
+    /// Writes the chunk at `indices` to a file of its own, in the format of the extension it's given
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+    fn export_chunk(&mut self, indices: &OwnedIndices) -> ActionResult {
+        let tab = self.tabs.active_tab();
+        let Some(region) = tab.root.as_region() else { return ActionResult::Pass };
+        let Some(idx) = indices.first() else { return ActionResult::Pass };
+        let Some(chunk) = region.chunks.get(idx).and_then(NbtElement::as_chunk) else { return ActionResult::Pass };
+        #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)] // within 0..32
+        let (x, z) = region.world_pos_of(tab.path.path(), idx).unwrap_or(((idx % 32) as i32, (idx / 32) as i32));
+        let compound = chunk.decoded().map_err(|e| anyhow!("Chunk {x}, {z} couldn't be exported since {e}")).alert_err(&mut self.alerts).failure_on_err()?.into_owned();
+        let dialog = native_dialog::FileDialogBuilder::default().set_filename(format!("c.{x}.{z}.dat")).add_filters(Self::chunk_file_type_filters()).save_single_file();
+        let dialog_result = dialog.show();
+        self.ignore_event_end = Timestamp::now() + Duration::from_millis(50);
+        let Some(path) = dialog_result.alert_err(&mut self.alerts).failure_on_err()? else { return ActionResult::Failure(()) };
+        let format = NbtFileFormat::from_path(&path).filter(|&format| format != NbtFileFormat::Mca).unwrap_or(NbtFileFormat::Gzip);
+        crate::util::write_atomic(&path, &format.encode(&NbtElement::Compound(compound))).alert_err(&mut self.alerts).failure_on_err()?;
+        ActionResult::Success(())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn export_chunk(&mut self, _: &OwnedIndices) -> ActionResult { ActionResult::Pass }
+
+    /// Reads a chunk exported to a file of its own, then asks where in the region to add it in the [`ValuesBox`]
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+    fn import_chunk(&mut self) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        let dialog = native_dialog::FileDialogBuilder::default()
+            .add_filters(Self::chunk_file_type_filters())
+            .add_filter(Tab::JSON_FILE_TYPE_FILTER.0, Tab::JSON_FILE_TYPE_FILTER.1)
+            .open_single_file();
+        let dialog_result = dialog.show();
+        self.ignore_event_end = Timestamp::now() + Duration::from_millis(50);
+        let Some(path) = dialog_result.alert_err(&mut self.alerts).failure_on_err()? else { return ActionResult::Failure(()) };
+        let buf = std::fs::read(&path).with_context(|| format!("Could not read {}", path.display())).alert_err(&mut self.alerts).failure_on_err()?;
+        let chunk = Tab::parse_chunk(&path, buf).alert_err(&mut self.alerts).failure_on_err()?;
+        self.values_box.open_import(chunk, self.window_dims);
+        self.search_box.deselect();
+        self.replace_box.deselect();
+        self.go_to_box.deselect();
+        self.url_box.deselect();
+        ActionResult::Success(())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn import_chunk(&mut self) -> ActionResult { ActionResult::Pass }
+
+    /// Replaces the element at `indices` with the values entered in the [`ValuesBox`], as one action
+    ///
+    /// Changing how many values an array holds where the game expects a certain amount is allowed, but warned about
+    fn apply_values_text(&mut self, indices: OwnedIndices, element: NbtElement) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        let NavigationInformation { key, element: old, .. } = tab.root.navigate(&indices).alert_err(&mut self.alerts).failure_on_err()?;
+        if let Some(key) = key
+            && is_fixed_length(key)
+            && let (Some(old_len), Some(len)) = (old.len(), element.len())
+            && old_len != len
+        {
+            self.notifications.notify(Notification::new(format!("{key} had {old_len} values and now has {len}, the game may not load it"), TextColor::Yellow, NotificationKind::ValuesText));
+        }
+        let kv = (key.map(CompactString::from), element);
+        let result = replace_element(&mut tab.root, kv, indices, mutable_indices!(tab)).alert_err(&mut self.alerts).failure_on_err()?;
+        tab.history.append(result.into_action());
+        tab.refresh_scrolls();
+        ActionResult::Success(())
+    }
+
+    /// Opens a [`StringEditor`] over the tree for the string at `indices`
+    fn open_string_editor(&mut self, indices: OwnedIndices) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        let NavigationInformation { key, element, .. } = tab.root.navigate(&indices).alert_err(&mut self.alerts).failure_on_err()?;
+        let Some(string) = element.as_string() else { return ActionResult::Pass };
+        let string_editor = StringEditor::new(indices, key, string.str.as_str().to_owned());
+        // keys go to the editor from now on, so the text being edited under it is saved
+        tab.save_selected_text().alert_err(&mut self.alerts).failure_on_err()?;
+        tab.selected_text = None;
+        tab.string_editor = Some(string_editor);
+        self.search_box.deselect();
+        self.replace_box.deselect();
+        self.go_to_box.deselect();
+        self.url_box.deselect();
+        self.values_box.close();
+        ActionResult::Success(())
+    }
+
+    fn click_string_editor(&mut self, button: MouseButton) -> ActionResult {
+        let mouse = self.pane_mouse();
+        let shift = self.held_keys.shift();
+        let dims = self.pane_dims(self.tabs.active_tab_idx());
+        if button == MouseButton::Left
+            && let Some(string_editor) = &mut self.tabs.active_tab_mut().string_editor
+        {
+            string_editor.click(mouse, shift, dims);
+        }
+        ActionResult::Success(())
+    }
+
+    /// Keys for the [`StringEditor`] of the active tab, which takes all of them while it's open so nothing happens to the tree hidden under it
+    fn on_string_editor_key(&mut self, key: KeyCode, char: Option<char>, flags: u8) -> ActionResult {
+        let dims = self.pane_dims(self.tabs.active_tab_idx());
+        let tab = self.tabs.active_tab_mut();
+        let Some(string_editor) = &mut tab.string_editor else { return ActionResult::Pass };
+        match string_editor.on_key_press(key, char, flags, dims) {
+            StringEditorKeyResult::NoAction | StringEditorKeyResult::GenericAction => {}
+            StringEditorKeyResult::Cancel => tab.string_editor = None,
+            StringEditorKeyResult::Save => {
+                let Some(string_editor) = tab.string_editor.take() else { return ActionResult::Pass };
+                let action = string_editor.save(&mut tab.root, &mut tab.path).alert_err(&mut self.alerts).failure_on_err()?;
+                tab.history.append(action);
+                tab.refresh_scrolls();
+            }
+        }
+        ActionResult::Success(())
+    }
+
+    fn click_scratch_pad(&mut self, button: MouseButton) -> ActionResult {
+        let mouse = self.pane_mouse();
+        let shift = self.held_keys.shift();
+        let dims = self.pane_dims(self.tabs.active_tab_idx());
+        let tab = self.tabs.active_tab_mut();
+        if button != MouseButton::Left {
+            return ActionResult::Success(())
+        }
+        // keys go to the text from now on
+        tab.save_selected_text().alert_err(&mut self.alerts).failure_on_err()?;
+        tab.selected_text = None;
+        if let Some(scratch_pad) = &mut tab.scratch_pad
+            && scratch_pad.click(mouse, shift, dims)
+        {
+            tab.rewrite_scratch_pad();
+        }
+        self.search_box.deselect();
+        self.replace_box.deselect();
+        self.go_to_box.deselect();
+        self.url_box.deselect();
+        self.values_box.close();
+        ActionResult::Success(())
+    }
+
+    /// Keys for the [`ScratchPad`] of the active tab while it's focused, any it doesn't use go on to the tree and the shortcuts
+    fn on_scratch_pad_key(&mut self, key: KeyCode, char: Option<char>, flags: u8) -> ActionResult {
+        let dims = self.pane_dims(self.tabs.active_tab_idx());
+        let tab = self.tabs.active_tab_mut();
+        // either of those covers it
+        if tab.string_editor.is_some() || tab.hex_view.is_some() {
+            return ActionResult::Pass
+        }
+        let Some(scratch_pad) = tab.scratch_pad.as_mut().filter(|scratch_pad| scratch_pad.is_focused()) else { return ActionResult::Pass };
+        match scratch_pad.on_key_press(key, char, flags, dims) {
+            StringEditorKeyResult::NoAction => ActionResult::Pass,
+            StringEditorKeyResult::GenericAction | StringEditorKeyResult::Cancel | StringEditorKeyResult::Save => ActionResult::Success(()),
+        }
+    }
+
+    /// Shows the byte array at `indices` in a [`HexView`] in place of the tree
+    fn open_hex_view(&mut self, indices: OwnedIndices) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        if tab.root.navigate(&indices).alert_err(&mut self.alerts).failure_on_err()?.element.as_byte_array().is_none() {
+            return ActionResult::Pass
+        }
+        // keys go to the hex view from now on, so the text being edited under it is saved
+        tab.save_selected_text().alert_err(&mut self.alerts).failure_on_err()?;
+        tab.selected_text = None;
+        tab.hex_view = Some(HexView::new(indices));
+        ActionResult::Success(())
+    }
+
+    /// Goes back to the tree with the byte array of the [`HexView`] opened, showing any edits made to it
+    fn close_hex_view(&mut self) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        let Some(hex_view) = tab.hex_view.take() else { return ActionResult::Pass };
+        // already gone, such as after undoing adding it
+        let Ok(NavigationInformation { element, line_number, .. }) = tab.root.navigate(hex_view.indices()) else { return ActionResult::Success(()) };
+        if !element.is_open() && !element.is_empty() {
+            open_element(&mut tab.root, hex_view.indices(), &mut tab.bookmarks).alert_err(&mut self.alerts).failure_on_err()?;
+        }
+        tab.scroll_line_into_view(line_number);
+        ActionResult::Success(())
+    }
+
+    fn click_hex_view(&mut self, button: MouseButton) -> ActionResult {
+        let mouse = self.pane_mouse();
+        let shift = self.held_keys.shift();
+        if button != MouseButton::Left {
+            return ActionResult::Success(())
+        }
+        if HexView::is_on_tree_view_button(mouse) {
+            return self.close_hex_view()
+        }
+        let tab = self.tabs.active_tab_mut();
+        let window_height = tab.window_dims.height as usize;
+        let Some(len) = tab.hex_view_len() else { return ActionResult::Pass };
+        let Some(hex_view) = &mut tab.hex_view else { return ActionResult::Pass };
+        if let Some(idx) = hex_view.byte_at(mouse, len, window_height) {
+            hex_view.move_cursor(idx, len, shift, window_height);
+        }
+        ActionResult::Success(())
+    }
+
+    /// Keys for the [`HexView`] of the active tab, passing on the ones it has no use for, like undoing
+    fn on_hex_view_key(&mut self, key: KeyCode, char: Option<char>, flags: u8) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        let window_height = tab.window_dims.height as usize;
+        let Some(len) = tab.hex_view_len() else { return ActionResult::Pass };
+        let Some(hex_view) = &mut tab.hex_view else { return ActionResult::Pass };
+        if hex_view.on_navigation_key(key, flags, len, window_height) {
+            return ActionResult::Success(())
+        }
+        if key == KeyCode::Escape && flags == flags!() {
+            return self.close_hex_view()
+        }
+        if key == KeyCode::KeyA && flags == flags!(Ctrl) {
+            hex_view.select_all(len);
+            return ActionResult::Success(())
+        }
+        if key == KeyCode::KeyC && flags == flags!(Ctrl) {
+            let range = hex_view.selection(len).unwrap_or(0..len);
+            let Some(array) = hex_view.array(&tab.root) else { return ActionResult::Pass };
+            let hex = HexView::to_hex_string(array.children().skip(range.start).take(range.len()).filter_map(NbtElement::as_byte).map(|byte| byte.value));
+            return if set_clipboard(hex) {
+                ActionResult::Success(())
+            } else {
+                self.alerts.alert(Alert::error("Could not set clipboard"));
+                ActionResult::Failure(())
+            }
+        }
+        let range = hex_view.selection_or_cursor(len);
+        if key == KeyCode::KeyV && flags == flags!(Ctrl) {
+            let Some(bytes) = get_clipboard().as_deref().and_then(HexView::parse_hex_string) else {
+                self.notifications.notify(Notification::new("The clipboard doesn't hold a string of hex digits", TextColor::Yellow, NotificationKind::HexView));
+                return ActionResult::Failure(())
+            };
+            // pasted before the cursor rather than over it, unless there's a selection
+            let range = hex_view.selection(len).unwrap_or(range.start..range.start);
+            return self.splice_hex_view(range.clone(), &bytes, range.start + bytes.len())
+        }
+        if let KeyCode::Delete | KeyCode::Backspace = key
+            && flags == flags!()
+        {
+            let range = if key == KeyCode::Backspace && hex_view.selection(len).is_none() { range.start.saturating_sub(1)..range.start } else { range };
+            return self.splice_hex_view(range.clone(), &[], range.start)
+        }
+        if key == KeyCode::Insert && flags == flags!() {
+            return self.splice_hex_view(range.start..range.start, &[0], range.start)
+        }
+        if flags & !flags!(Shift) == flags!()
+            && let Some(nibble) = char.and_then(|char| char.to_digit(16))
+        {
+            self.tabs.active_tab().ensure_writable(&mut self.notifications)?;
+            let Some(hex_view) = &mut self.tabs.active_tab_mut().hex_view else { return ActionResult::Pass };
+            if let Some(byte) = hex_view.type_nibble(nibble as u8) {
+                // past the last byte adds one
+                let range = range.start..(range.start + 1).min(len);
+                return self.splice_hex_view(range.clone(), &[byte], range.start + 1)
+            }
+            return ActionResult::Success(())
+        }
+        ActionResult::Pass
+    }
+
+    /// Replaces `range` of the byte array in the [`HexView`] of the active tab with `bytes` as one action, leaving the cursor at `cursor`
+    fn splice_hex_view(&mut self, range: Range<usize>, bytes: &[i8], cursor: usize) -> ActionResult {
+        let tab = self.tabs.active_tab_mut();
+        tab.ensure_writable(&mut self.notifications)?;
+        let window_height = tab.window_dims.height as usize;
+        let Some(indices) = tab.hex_view.as_ref().map(|hex_view| hex_view.indices().to_owned()) else { return ActionResult::Pass };
+        if let Some(action) = splice_byte_array(&mut tab.root, &indices, range, bytes, mutable_indices!(tab)).alert_err(&mut self.alerts).failure_on_err()? {
+            tab.history.append(action);
+        }
+        if let Some(len) = tab.hex_view_len()
+            && let Some(hex_view) = &mut tab.hex_view
+        {
+            hex_view.move_cursor(cursor, len, false, window_height);
+        }
+        tab.refresh_scrolls();
+        ActionResult::Success(())
+    }
+
     fn bookmark_line(&mut self, require_left_margin_cursor: bool) -> ActionResult {
         if let InteractionInformation::Content { is_in_left_margin, true_line_number, y, .. } = get_interaction_information!(self)
             && (is_in_left_margin || !require_left_margin_cursor)
@@ -1037,8 +2882,78 @@ impl Workbench {
             let _ = self.tabs.active_tab_mut().bookmarks.toggle(bookmark);
             ActionResult::Success(())
         } else {
-            ActionResult::Pass
+            ActionResult::Pass
+        }
+    }
+
+    /// Shows what an input method is composing in the text being edited, and types it once it's committed
+    pub fn on_ime(&mut self, ime: Ime) -> ActionResult {
+        let (preedit, commit) = match ime {
+            Ime::Preedit(preedit, _) => (Some(preedit).filter(|preedit| !preedit.is_empty()), None),
+            Ime::Commit(commit) => (None, Some(commit)),
+            Ime::Enabled | Ime::Disabled => (None, None),
+        };
+        let window_dims = self.window_dims;
+        let dims = self.pane_dims(self.tabs.active_tab_idx());
+        // the same order keys go to them in
+        if self.search_box.is_selected() {
+            self.search_box.preedit = preedit;
+            if let Some(commit) = commit {
+                self.search_box.insert_str(&commit);
+                self.tabs.active_tab_mut().last_search_hit = None;
+                self.search_box.post_input(window_dims);
+            }
+        } else if self.replace_box.is_selected() {
+            self.replace_box.preedit = preedit;
+            if let Some(commit) = commit {
+                self.replace_box.insert_str(&commit);
+                self.replace_box.post_input(window_dims);
+            }
+        } else if self.go_to_box.is_selected() {
+            self.go_to_box.preedit = preedit;
+            if let Some(commit) = commit {
+                self.go_to_box.insert_str(&commit);
+                self.go_to_box.post_input(window_dims);
+            }
+        } else if self.url_box.is_selected() {
+            self.url_box.preedit = preedit;
+            if let Some(commit) = commit {
+                self.url_box.insert_str(&commit);
+                self.url_box.post_input(window_dims);
+            }
+        } else if self.values_box.is_open() {
+            self.values_box.preedit = preedit;
+            if let Some(commit) = commit {
+                self.values_box.insert_str(&commit);
+                self.values_box.post_input(window_dims);
+            }
+        } else if let tab = self.tabs.active_tab_mut()
+            && let Some(scratch_pad) = &mut tab.scratch_pad
+            && scratch_pad.is_focused()
+        {
+            if let Some(commit) = commit {
+                scratch_pad.insert_str(&commit, dims);
+            }
+        } else if let tab = self.tabs.active_tab_mut()
+            && let Some(string_editor) = &mut tab.string_editor
+        {
+            string_editor.preedit = preedit;
+            if let Some(commit) = commit {
+                string_editor.insert_str(&commit, dims);
+            }
+        } else if let tab = self.tabs.active_tab_mut()
+            && let Some(selected_text) = &mut tab.selected_text
+        {
+            selected_text.preedit = preedit;
+            if let Some(commit) = commit {
+                selected_text.insert_str(&commit);
+                selected_text.post_input();
+                tab.refresh_selected_text_horizontal_scroll();
+            }
+        } else {
+            return ActionResult::Pass
         }
+        ActionResult::Success(())
     }
 
     #[allow(clippy::collapsible_if, clippy::too_many_lines, clippy::cognitive_complexity)]
@@ -1047,11 +2962,75 @@ impl Workbench {
 
         self.tabs.active_tab_mut().last_interaction = Timestamp::now();
         let consts = self.tabs.active_tab().consts();
+        // what the keyboard layout types, so accents and letters not on a US keyboard come through as themselves
+        let typed = key.text.as_deref().and_then(|text| {
+            let mut chars = text.chars();
+            chars.next().filter(|char| !char.is_control() && chars.next().is_none())
+        });
         if key.state == ElementState::Pressed {
             if let PhysicalKey::Code(key) = key.physical_key {
                 self.held_keys.on_press(key);
-                let char = self.char_from_key(key);
+                #[cfg(target_arch = "wasm32")]
+                if self.close_prompt.is_some() {
+                    if key == KeyCode::Escape {
+                        self.close_prompt = None;
+                    }
+                    return Success(());
+                }
+                if self.key_conflict_prompt.is_some() {
+                    if key == KeyCode::Escape {
+                        self.key_conflict_prompt = None;
+                    }
+                    return Success(());
+                }
+                if key == KeyCode::Escape && self.recent_files.is_open() {
+                    self.recent_files.close();
+                    return Success(());
+                }
+                if key == KeyCode::Escape && self.tab_menu.is_open() {
+                    self.tab_menu.close();
+                    return Success(());
+                }
+                // keys can change the tree under it, so it doesn't stay open past them
+                if self.element_menu.is_open() {
+                    self.element_menu.close();
+                    if key == KeyCode::Escape {
+                        return Success(());
+                    }
+                }
+                if self.clipboard_ring.is_open() {
+                    return self.on_clipboard_ring_key(key);
+                }
+                let char = typed.filter(|_| !self.held_keys.ctrl()).or_else(|| self.char_from_key(key));
                 let flags = self.held_keys.modifiers().into_bitflags();
+                // before anything with focus gets a chance to take them
+                if key == KeyCode::Tab && flags & !flags!(Shift) == flags!(Ctrl) {
+                    self.tabs.cycle(flags & flags!(Shift) > 0);
+                    self.scroll_to_active_tab();
+                    return Success(());
+                }
+                if flags == flags!(Ctrl) {
+                    let idx = match key {
+                        KeyCode::Digit1 => Some(0),
+                        KeyCode::Digit2 => Some(1),
+                        KeyCode::Digit3 => Some(2),
+                        KeyCode::Digit4 => Some(3),
+                        KeyCode::Digit5 => Some(4),
+                        KeyCode::Digit6 => Some(5),
+                        KeyCode::Digit7 => Some(6),
+                        KeyCode::Digit8 => Some(7),
+                        KeyCode::Digit9 => Some(usize::MAX),
+                        _ => None,
+                    };
+                    if let Some(idx) = idx {
+                        self.tabs.set_active_idx(idx);
+                        self.scroll_to_active_tab();
+                        return Success(());
+                    }
+                }
+                if key == KeyCode::KeyW && flags == flags!(Ctrl) {
+                    return self.close_tab(self.tabs.active_tab_idx());
+                }
                 self.search_box.on_key_press(
                     key,
                     char,
@@ -1063,6 +3042,59 @@ impl Workbench {
                     self.window_dims,
                 )?;
                 self.replace_box.on_key_press(key, char, flags, &mut self.search_box, self.tabs.active_tab_mut(), &mut self.alerts, &mut self.notifications, self.window_dims)?;
+                self.go_to_box.on_key_press(key, char, flags, self.tabs.active_tab_mut(), &mut self.alerts, self.window_dims)?;
+                match self.url_box.on_key_press(key, char, flags, &mut self.alerts, self.window_dims) {
+                    Success(url) => {
+                        if let Some(url) = url {
+                            self.open_url(url);
+                        }
+                        return Success(());
+                    }
+                    Pass => {}
+                    Failure(()) => return Failure(()),
+                }
+                match self.values_box.on_key_press(key, char, flags, self.tabs.active_tab(), self.window_dims) {
+                    Success(edit) => {
+                        match edit {
+                            Some(ValuesBoxEdit::Replace(indices, element)) => self.apply_values_text(indices, element)?,
+                            Some(ValuesBoxEdit::MoveChunk(indices, pos)) => self.move_chunk_to(&indices, pos)?,
+                            Some(ValuesBoxEdit::NewChunk(pos, imported)) => self.new_chunk_at(pos, imported)?,
+                            Some(ValuesBoxEdit::WrapRoot(key, then)) => self.wrap_root(key, then)?,
+                            None => {}
+                        }
+                        return Success(());
+                    }
+                    Pass => {}
+                    Failure(()) => return Failure(()),
+                }
+                self.on_scratch_pad_key(key, char, flags)?;
+                self.on_string_editor_key(key, char, flags)?;
+                self.on_hex_view_key(key, char, flags)?;
+                if flags == flags!(Shift + Alt) {
+                    match key {
+                        KeyCode::ArrowRight => self.try_expand_selected_to_depth(usize::MAX)?,
+                        // leaves only its children showing
+                        KeyCode::ArrowLeft => self.try_expand_selected_to_depth(1)?,
+                        _ => {}
+                    }
+                }
+                if flags == flags!(Alt) {
+                    let depth = match key {
+                        KeyCode::Digit1 => Some(1),
+                        KeyCode::Digit2 => Some(2),
+                        KeyCode::Digit3 => Some(3),
+                        KeyCode::Digit4 => Some(4),
+                        KeyCode::Digit5 => Some(5),
+                        KeyCode::Digit6 => Some(6),
+                        KeyCode::Digit7 => Some(7),
+                        KeyCode::Digit8 => Some(8),
+                        KeyCode::Digit9 => Some(9),
+                        _ => None,
+                    };
+                    if let Some(depth) = depth {
+                        self.try_expand_selected_to_depth(depth)?;
+                    }
+                }
                 if let tab = self.tabs.active_tab_mut()
                     && let Some(mut selected_text) = tab.selected_text.take()
                 {
@@ -1075,19 +3107,47 @@ impl Workbench {
                             }
                             tab.refresh_scrolls();
                             tab.refresh_selected_text_horizontal_scroll();
+                            // taken by the text, so copying, cutting or typing doesn't also act on the tree under it
+                            return Success(());
                         }
                         Pass => {}
                         Failure(()) => return Failure(()),
                     }
                 }
                 if key == KeyCode::KeyF && flags == flags!(Ctrl) {
-                    self.search_box.select(0, MouseButton::Left);
+                    if self.search_box.is_selected() {
+                        self.search_box.deselect();
+                    } else {
+                        self.search_box.select(0, MouseButton::Left);
+                    }
                     self.replace_box.deselect();
+                    self.go_to_box.deselect();
+                    self.url_box.deselect();
+                    self.values_box.close();
                     return Success(());
                 }
-                if key == KeyCode::KeyR && flags == flags!(Ctrl) {
-                    self.replace_box.select(0, MouseButton::Left);
+                if key == KeyCode::KeyH && flags == flags!(Ctrl) {
+                    if self.replace_box.is_selected() {
+                        self.replace_box.deselect();
+                    } else {
+                        self.replace_box.select(0, MouseButton::Left);
+                    }
+                    self.search_box.deselect();
+                    self.go_to_box.deselect();
+                    self.url_box.deselect();
+                    self.values_box.close();
+                    return Success(());
+                }
+                if key == KeyCode::KeyG && flags == flags!(Ctrl) {
+                    if self.go_to_box.is_selected() {
+                        self.go_to_box.deselect();
+                    } else {
+                        self.go_to_box.select(self.go_to_box.value.width(), MouseButton::Left);
+                    }
                     self.search_box.deselect();
+                    self.replace_box.deselect();
+                    self.url_box.deselect();
+                    self.values_box.close();
                     return Success(());
                 }
                 if key == KeyCode::Equal && flags & !flags!(Shift) == flags!(Ctrl) {
@@ -1104,9 +3164,16 @@ impl Workbench {
                 }
                 if key == KeyCode::Escape
                     && flags == flags!()
-                    && let Some(held_entry) = self.tabs.active_tab_mut().held_entry.take()
+                    && self.tabs.active_tab().held_entry.is_some()
+                {
+                    self.return_held_entry()?;
+                    return Success(());
+                }
+                if key == KeyCode::Escape
+                    && flags == flags!()
+                    && !self.tabs.active_tab().selection.is_empty()
                 {
-                    self.tabs.active_tab_mut().history.append(WorkbenchAction::DiscardHeldEntry { held_entry });
+                    self.tabs.active_tab_mut().selection.clear();
                     return Success(());
                 }
                 if (key == KeyCode::Enter || key == KeyCode::NumpadEnter)
@@ -1114,6 +3181,7 @@ impl Workbench {
                     && tab.selected_text.is_none()
                     && flags == flags!()
                 {
+                    tab.ensure_writable(&mut self.notifications)?;
                     if tab.held_entry.is_some() {
                         self.drop_held_entry()?;
                     } else {
@@ -1121,37 +3189,80 @@ impl Workbench {
                     }
                     return Failure(());
                 }
+                if key == KeyCode::F2 && flags & !flags!(Shift) == flags!() {
+                    let tab = self.tabs.active_tab_mut();
+                    // the bookmarks of a comparison start out as its differences
+                    let noun = if tab.differences.is_empty() { "Bookmark" } else { "Difference" };
+                    if let Some((idx, bookmarks)) = tab.jump_to_bookmark(flags == flags!()).alert_err(&mut self.alerts).failure_on_err()? {
+                        self.notifications.notify(Notification::new(format!("{noun} {n} of {bookmarks}", n = idx + 1), TextColor::White, NotificationKind::Bookmark));
+                    }
+                    return Success(());
+                }
                 if key == KeyCode::F3 && flags == flags!() {
                     self.debug_menu = !self.debug_menu;
                 }
-                if flags == flags!(Ctrl) {
-                    let idx = match key {
-                        KeyCode::Digit1 => Some(0),
-                        KeyCode::Digit2 => Some(1),
-                        KeyCode::Digit3 => Some(2),
-                        KeyCode::Digit4 => Some(3),
-                        KeyCode::Digit5 => Some(4),
-                        KeyCode::Digit6 => Some(5),
-                        KeyCode::Digit7 => Some(6),
-                        KeyCode::Digit8 => Some(7),
-                        KeyCode::Digit9 => Some(usize::MAX),
-                        _ => None,
-                    };
-                    if let Some(idx) = idx {
-                        self.tabs.set_active_idx(idx);
-                        return Success(());
-                    }
-                }
                 if key == KeyCode::KeyR && flags == flags!(Ctrl) {
                     let tab = self.tabs.active_tab_mut();
                     tab.refresh().alert_err(&mut self.alerts);
                     return Success(());
                 }
+                #[cfg(not(target_arch = "wasm32"))]
+                if key == KeyCode::KeyR && flags == flags!(Ctrl + Alt) {
+                    let tab = self.tabs.active_tab_mut();
+                    let watching = tab.toggle_watching().alert_err(&mut self.alerts).failure_on_err()?;
+                    let message = format!("{verb} watching {name} for changes", verb = if watching { "Started" } else { "Stopped" }, name = tab.path.name());
+                    self.notifications.notify(Notification::new(message, TextColor::White, NotificationKind::Watch));
+                    return Success(());
+                }
                 if key == KeyCode::KeyF && flags == flags!(Ctrl + Shift) {
                     let tab = self.tabs.active_tab_mut();
                     tab.freehand_mode = !tab.freehand_mode;
                     return Success(());
                 }
+                if key == KeyCode::KeyL && flags == flags!(Ctrl + Alt) {
+                    let tab = self.tabs.active_tab_mut();
+                    tab.set_read_only(!tab.read_only).alert_err(&mut self.alerts).failure_on_err()?;
+                    let message = format!("{name} is {state}", name = tab.path.name(), state = if tab.read_only { "now read-only" } else { "no longer read-only" });
+                    self.notifications.notify(Notification::new(message, TextColor::White, NotificationKind::ReadOnly));
+                    return Success(());
+                }
+                if key == KeyCode::KeyB && flags == flags!(Ctrl + Alt) {
+                    let tab = self.tabs.active_tab_mut();
+                    tab.number_base = tab.number_base.cycle();
+                    let message = format!("Showing integers in {base}", base = tab.number_base.name());
+                    self.notifications.notify(Notification::new(message, TextColor::White, NotificationKind::NumberBase));
+                    return Success(());
+                }
+                if key == KeyCode::KeyH && flags == flags!(Ctrl + Shift) {
+                    self.history_panel.toggle();
+                    return Success(());
+                }
+                if key == KeyCode::KeyH && flags == flags!(Ctrl + Alt) {
+                    let tab = self.tabs.active_tab_mut();
+                    tab.set_region_heatmap(tab.region_heatmap.cycle());
+                    let message = format!("Region heatmap: {name}", name = tab.region_heatmap.name());
+                    self.notifications.notify(Notification::new(message, TextColor::White, NotificationKind::RegionHeatmap));
+                    return Success(());
+                }
+                if key == KeyCode::KeyB && flags == flags!(Ctrl + Shift + Alt) {
+                    let bytes_as_booleans = !config::get_bytes_as_booleans();
+                    config::set_bytes_as_booleans(bytes_as_booleans);
+                    let message = if bytes_as_booleans { "Showing bytes of 0 and 1 as false and true" } else { "Showing bytes as numbers" };
+                    self.notifications.notify(Notification::new(message, TextColor::White, NotificationKind::Booleans));
+                    return Success(());
+                }
+                if key == KeyCode::KeyF && flags == flags!(Ctrl + Alt) {
+                    let float_display = config::get_float_display().cycle();
+                    config::set_float_display(float_display);
+                    let message = format!("Floats and doubles: {name}", name = float_display.name());
+                    self.notifications.notify(Notification::new(message, TextColor::White, NotificationKind::FloatDisplay));
+                    return Success(());
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if key == KeyCode::KeyT && flags == flags!(Ctrl + Shift) {
+                    self.reopen_closed_tab()?;
+                    return Success(());
+                }
                 if key == KeyCode::KeyT && flags == flags!(Ctrl + Alt) {
                     config::set_theme(match config::get_theme() {
                         Theme::Light => Theme::Dark,
@@ -1159,30 +3270,68 @@ impl Workbench {
                     });
                     return Success(());
                 }
-                if key == KeyCode::KeyN && flags & (!flags!(Shift)) == flags!(Ctrl) {
-                    self.tabs.add(Tab::new_empty_tab((flags & flags!(Shift)) > 0, self.window_dims));
+                if key == KeyCode::KeyN && matches!(flags, flags!(Ctrl) | flags!(Ctrl + Shift) | flags!(Ctrl + Alt) | flags!(Ctrl + Shift + Alt)) {
+                    let root = if flags == flags!(Ctrl + Shift) {
+                        NewTabRoot::Region
+                    } else if flags == flags!(Ctrl + Alt) {
+                        NewTabRoot::List
+                    } else if flags == flags!(Ctrl + Shift + Alt) {
+                        NewTabRoot::Scratch
+                    } else {
+                        NewTabRoot::Compound
+                    };
+                    self.tabs.add_empty(root, self.window_dims);
+                    self.scroll_to_active_tab();
                     return Success(());
                 }
                 if key == KeyCode::KeyO && flags == flags!(Ctrl) {
                     self.open_file()?;
                     return Success(());
                 }
+                if key == KeyCode::KeyO && flags == flags!(Ctrl + Shift) {
+                    self.toggle_url_box();
+                    return Success(());
+                }
                 if key == KeyCode::KeyS && flags & (!flags!(Shift)) == flags!(Ctrl) {
+                    return self.save_active_tab((flags & flags!(Shift)) > 0);
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if key == KeyCode::KeyS && flags == flags!(Ctrl + Alt) {
+                    let summary = self.tabs.save_all(&mut self.alerts);
+                    self.ignore_event_end = Timestamp::now() + Duration::from_millis(50);
+                    let color = if summary.failed.is_empty() { TextColor::White } else { TextColor::Red };
+                    self.notifications.notify(Notification::new(summary.to_string(), color, NotificationKind::Save));
+                    return Success(());
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if key == KeyCode::KeyK && flags == flags!(Ctrl + Alt) {
                     let tab = self.tabs.active_tab_mut();
-                    tab.save((flags & flags!(Shift)) > 0).alert_err(&mut self.alerts).failure_on_err()?;
+                    let reclaimed = tab.compact_region(&mut self.alerts).alert_err(&mut self.alerts).failure_on_err()?;
+                    self.ignore_event_end = Timestamp::now() + Duration::from_millis(50);
+                    let message = match reclaimed {
+                        Some(reclaimed) => format!("Compacted {name}, reclaiming {kib} KiB", name = tab.path.name(), kib = reclaimed / 1024),
+                        None => format!("Compacting {name} was cancelled", name = tab.path.name()),
+                    };
+                    self.notifications.notify(Notification::new(message, TextColor::White, NotificationKind::Save));
+                    return Success(());
                 }
-                if key == KeyCode::KeyW && flags == flags!(Ctrl) {
-                    let active_tab_idx = self.tabs.active_tab_idx();
-                    drop_on_separate_thread(self.tabs.remove(active_tab_idx));
+                if key == KeyCode::KeyD && flags == flags!(Ctrl + Alt) {
+                    self.duplicate_tab(self.tabs.active_tab_idx());
+                    self.scroll_to_active_tab();
                     return Success(());
                 }
                 if key == KeyCode::KeyZ && flags == flags!(Ctrl) {
                     let tab = self.tabs.active_tab_mut();
-                    tab.history.undo(&mut tab.root, mutable_indices!(tab), &mut tab.path, &mut tab.held_entry).alert_err(&mut self.alerts).failure_on_err()?;
+                    tab.ensure_writable(&mut self.notifications)?;
+                    // it only follows the held entry it was grabbed with
+                    tab.selection.dragging = false;
+                    tab.undo().alert_err(&mut self.alerts).failure_on_err()?;
                 }
                 if key == KeyCode::KeyY && flags == flags!(Ctrl) || key == KeyCode::KeyZ && flags == flags!(Ctrl + Shift) {
                     let tab = self.tabs.active_tab_mut();
-                    tab.history.redo(&mut tab.root, mutable_indices!(tab), &mut tab.path, &mut tab.held_entry).alert_err(&mut self.alerts).failure_on_err()?;
+                    tab.ensure_writable(&mut self.notifications)?;
+                    tab.selection.dragging = false;
+                    tab.redo().alert_err(&mut self.alerts).failure_on_err()?;
                 }
                 if ((key == KeyCode::Backspace || key == KeyCode::Delete) && flags == flags!()) || (key == KeyCode::KeyX && flags == flags!(Ctrl)) {
                     self.delete(flags & flags!(Ctrl) > 0)?
@@ -1193,6 +3342,30 @@ impl Workbench {
                 if key == KeyCode::KeyC && (flags & !flags!(Shift)) == flags!(Ctrl) {
                     self.try_copy((flags & !flags!(Ctrl)) == flags!(Shift))?;
                 }
+                if key == KeyCode::KeyC && flags == flags!(Ctrl + Alt) {
+                    self.try_copy_path()?;
+                }
+                if key == KeyCode::KeyK && flags == flags!(Ctrl) {
+                    self.try_copy_key()?;
+                }
+                if let KeyCode::ArrowUp | KeyCode::ArrowDown = key {
+                    let step = match flags {
+                        flags!() => Some(NudgeStep::One),
+                        flags!(Shift) => Some(NudgeStep::Ten),
+                        flags!(Alt) => Some(NudgeStep::Tenth),
+                        _ => None,
+                    };
+                    if let Some(step) = step {
+                        self.try_nudge(key == KeyCode::ArrowUp, step)?;
+                    }
+                }
+                if key == KeyCode::KeyV && (flags & !flags!(Shift)) == flags!(Ctrl + Alt) {
+                    self.try_copy_value((flags & !flags!(Ctrl + Alt)) == flags!(Shift))?;
+                }
+                if key == KeyCode::KeyV && flags == flags!(Ctrl + Shift) {
+                    self.clipboard_ring.open(self.mouse, self.window_dims);
+                    return Success(());
+                }
                 if flags == flags!() {
                     let tab = self.tabs.active_tab_mut();
                     let kv = match key {
@@ -1215,16 +3388,18 @@ impl Workbench {
                                 return Failure(())
                             },
                         KeyCode::KeyV => {
-                            fn element_from_clipboard() -> Result<NbtElementAndKey> {
-                                let clipboard = get_clipboard().ok_or_else(|| anyhow!("Failed to get clipboard"))?;
-                                let kv = NbtElement::from_str(&clipboard).map_err(|idx| anyhow!("Could not parse clipboard as SNBT (failed at index {idx})"))?;
-                                Ok(kv)
+                            tab.ensure_writable(&mut self.notifications)?;
+                            let held_entry = HeldEntry::parse_clipboard(&mut self.alerts, tab.root.is_region()).alert_err(&mut self.alerts).failure_on_err()?;
+                            let old_held_entry = tab.held_entry.replace(held_entry);
+                            if let Some(held_entry) = old_held_entry {
+                                tab.history.append(WorkbenchAction::DiscardHeldEntry { held_entry });
                             }
-
-                            element_from_clipboard().alert_err(&mut self.alerts).failure_on_err()?
+                            tab.history.append(WorkbenchAction::CreateHeldEntry);
+                            return Success(());
                         }
                         _ => return Failure(()),
                     };
+                    tab.ensure_writable(&mut self.notifications)?;
                     let old_held_entry = tab.held_entry.replace(HeldEntry::from_aether(kv));
                     if let Some(held_entry) = old_held_entry {
                         tab.history.append(WorkbenchAction::DiscardHeldEntry { held_entry });
@@ -1236,6 +3411,9 @@ impl Workbench {
         } else if key.state == ElementState::Released {
             if let PhysicalKey::Code(x) = key.physical_key {
                 self.held_keys.on_release(x);
+                if !self.held_keys.ctrl() {
+                    self.tabs.finish_cycle();
+                }
             }
         }
 
@@ -1245,6 +3423,11 @@ impl Workbench {
     pub fn on_mouse_move(&mut self, pos: PhysicalPosition<f64>) -> ActionResult {
         self.raw_mouse = pos.into();
         self.mouse = (self.raw_mouse / self.scale as f64).into();
+        if self.held_divider {
+            self.tabs.set_divider(self.mouse.x);
+            self.tabs.refresh_window_dims(self.window_dims);
+            return ActionResult::Success(());
+        }
         let tab = self.tabs.active_tab_mut();
         let TabConstants { scroll, .. } = tab.consts();
         if let Some(scrollbar_offset) = self.scrollbar_offset
@@ -1259,11 +3442,13 @@ impl Workbench {
             let pixel_delta = height as isize * dy / total as isize;
             tab.modify_scroll(|scroll| (scroll as isize + pixel_delta).max(0) as usize);
         }
+        self.tabs.drag(self.mouse.x + self.tab_scroll);
         self.try_extend_drag_selection();
         ActionResult::Success(())
     }
 
     pub fn try_extend_drag_selection(&mut self) {
+        let mouse = self.pane_mouse();
         let tab = self.tabs.active_tab_mut();
         let horizontal_scroll = tab.horizontal_scroll();
         let left_margin = tab.left_margin();
@@ -1273,10 +3458,7 @@ impl Workbench {
                 && selected_text.is_drag_selectable()
             {
                 let cursor = selected_text.selection.unwrap_or(selected_text.cursor);
-                let selection = get_cursor_idx(
-                    &selected_text.value,
-                    (self.mouse.x + horizontal_scroll) as isize - (selected_text.indices.len() * 16 + 32 + SelectedText::PREFIXING_SPACE_WIDTH + left_margin) as isize - selected_text.prefix.0.width() as isize - selected_text.keyfix.as_ref().map_or(0, |(a, _)| a.width()) as isize,
-                );
+                let selection = selected_text.cursor_idx_at(mouse.x + horizontal_scroll, left_margin);
                 selected_text.cursor = selection;
                 selected_text.selection = Some(cursor).filter(|cursor| *cursor != selected_text.cursor);
                 selected_text.interact();
@@ -1313,9 +3495,7 @@ impl Workbench {
         self.mouse = (self.raw_mouse / self.scale as f64).into();
         let dims = Vec2u::from((Vec2d::from(self.raw_window_dims.cast::<f64>()) / self.scale as f64).round());
         self.window_dims = PhysicalSize::new(dims.x as u32, dims.y as u32);
-        for tab in &mut self.tabs {
-            tab.set_window_dims(self.window_dims);
-        }
+        self.tabs.refresh_window_dims(self.window_dims);
 
         if old_scale != scale {
             self.notifications.notify(Notification::new(format!("Scale: {scale:.1}x (Max {max_scale}.0)"), TextColor::White, NotificationKind::Scale))
@@ -1333,27 +3513,31 @@ impl Workbench {
         builder.draw_texture_region_z((SEARCH_BOX_START_X - 3, 22), BASE_Z, LINE_NUMBER_SEPARATOR_UV, (2, 23), (2, 16));
         builder.draw_texture_region_z((builder.window_width() - SEARCH_BOX_END_X, 22), BASE_Z, LINE_NUMBER_SEPARATOR_UV, (2, 23), (2, 16));
 
-        if ReplaceBox::is_visible(&self.search_box, &self.replace_box) {
+        if ReplaceBox::is_visible(&self.search_box, &self.replace_box) || self.go_to_box.is_selected() || self.url_box.is_selected() || self.values_box.is_open() {
             builder.draw_texture_region_z((SEARCH_BOX_START_X - 3, 45), REPLACE_BOX_Z, LINE_NUMBER_SEPARATOR_UV, (2, 25), (2, 16));
             builder.draw_texture_region_z((builder.window_width() - SEARCH_BOX_END_X, 45), REPLACE_BOX_Z, LINE_NUMBER_SEPARATOR_UV, (2, 25), (2, 16));
             builder.draw_texture_region_z((SEARCH_BOX_START_X - 1, 68), REPLACE_BOX_Z, HORIZONTAL_SEPARATOR_UV, (builder.window_width() - SEARCH_BOX_END_X - SEARCH_BOX_START_X + 1, 2), (14, 2));
         }
 
-        for n in 0..(builder.window_height() - HEADER_SIZE + 15) / 16 {
-            let uv = if (n % 2 == 0) ^ ((builder.scroll() / 16) % 2 == 0) { DARK_STRIPE_UV + (1, 1) } else { LIGHT_STRIPE_UV + (1, 1) };
-            builder.draw_texture_region_z((0, n * 16 + HEADER_SIZE - (n == 0) as usize), BASE_Z, uv, (builder.window_width(), 16 + (n == 0) as usize), (14, 14));
-        }
         // let start = std::time::Instant::now();
         self.render_tabs(builder);
         // println!("Tabs Bar: {}ms", start.elapsed().as_millis_f64());
+        self.render_other_pane(builder);
+        let hovering_replace_box = ReplaceBox::is_within_bounds(self.mouse, builder.window_dims());
+        let window_width = builder.window_width();
+        let viewport = builder.set_viewport(self.active_viewport(builder));
+        Self::render_stripes(builder);
         let tab = self.tabs.active_tab();
+        self.breadcrumbs.update(tab, self.mouse);
+        // drawn as if the cursor were away while it's over the other pane
+        let mouse = if self.tabs.pane(self.tabs.active_tab_idx(), window_width).is_none_or(|(x, width)| (x..x + width).contains(&self.mouse.x)) { self.pane_mouse() } else { Vec2u::new(0, 0) };
         let left_margin = tab.left_margin();
         let horizontal_scroll = tab.horizontal_scroll;
-        let ghost = if self.mouse.x + horizontal_scroll >= left_margin && self.mouse.y >= HEADER_SIZE {
+        let ghost = if mouse.x + horizontal_scroll >= left_margin && mouse.y >= HEADER_SIZE {
             tab.held_entry.as_ref().map(|entry| {
                 (
                     &entry.kv.1,
-                    Vec2u::new(((self.mouse.x + horizontal_scroll - left_margin) & !15) + left_margin, ((self.mouse.y - HEADER_SIZE) & !0b0111) + HEADER_SIZE),
+                    Vec2u::new(((mouse.x + horizontal_scroll - left_margin) & !15) + left_margin, ((mouse.y - HEADER_SIZE) & !0b0111) + HEADER_SIZE),
                 )
             })
         } else {
@@ -1379,12 +3563,9 @@ impl Workbench {
         } else {
             (None, None, false)
         };
-        let mut ctx = RenderContext::new(selected_text_y, selected_key, selected_value, selecting_key, ghost, left_margin, self.mouse, tab.freehand_mode);
-        if self.mouse.y >= HEADER_SIZE && self.action_wheel.is_none() && !ReplaceBox::is_within_bounds(self.mouse, builder.window_dims()) {
-            builder.draw_texture_region_z((0, self.mouse.y & !15), BASE_Z, HOVERED_STRIPE_UV, (builder.window_width(), 16), (14, 14));
-        }
-        {
-            builder.draw_texture_region_z((33, 22), BASE_Z, LINE_NUMBER_SEPARATOR_UV, (2, 23), (2, 16));
+        let mut ctx = RenderContext::new(selected_text_y, selected_key, selected_value, selecting_key, ghost, left_margin, mouse, tab.freehand_mode, tab.number_base, tab.region_heatmap, tab.boolean_overrides.true_lines(&tab.root));
+        if mouse.y >= HEADER_SIZE && self.action_wheel.is_none() && !hovering_replace_box {
+            builder.draw_texture_region_z((0, mouse.y & !15), BASE_Z, HOVERED_STRIPE_UV, (builder.window_width(), 16), (14, 14));
         }
         {
             // let start = std::time::Instant::now();
@@ -1409,6 +3590,29 @@ impl Workbench {
             }
             // println!("Selected Text: {}ms", start.elapsed().as_millis_f64());
         }
+        builder.set_viewport(viewport);
+        {
+            builder.draw_texture_region_z((33, 22), BASE_Z, LINE_NUMBER_SEPARATOR_UV, (2, 23), (2, 16));
+        }
+        tab.render_toolbar(builder, self.mouse, self.action_wheel.is_some());
+        if self.are_breadcrumbs_visible() {
+            self.breadcrumbs.render(builder, &tab.root, self.mouse);
+        }
+        if ReloadBanner::is_visible(tab) {
+            ReloadBanner::render(builder, self.mouse);
+        }
+        if RegionIssuesBanner::is_visible(tab) {
+            RegionIssuesBanner::render(builder, tab, self.mouse);
+        }
+        #[cfg(target_arch = "wasm32")]
+        if let Some(prompt) = &self.close_prompt
+            && let Some(tab) = self.tabs.iter().nth(prompt.idx())
+        {
+            ClosePrompt::render(builder, tab, self.mouse);
+        }
+        if let Some(prompt) = &self.key_conflict_prompt {
+            prompt.render(builder, self.mouse);
+        }
         {
             // let start = std::time::Instant::now();
             let ctx = WidgetContext::new(&self.tabs, &self.search_box, &self.replace_box, shift);
@@ -1424,6 +3628,7 @@ impl Workbench {
 
             render_button!(search_flags_button);
             render_button!(search_operation_button);
+            render_button!(search_tag_type_button);
             render_button!(search_mode_button);
             render_button!(exact_match_button);
             render_button!(sort_algorithm_button);
@@ -1435,39 +3640,136 @@ impl Workbench {
             render_button!(replace_by_button);
             // println!("Buttons: {}ms", start.elapsed().as_millis_f64());
         }
+        self.history_panel.render(builder, &self.tabs.active_tab().history, self.mouse, self.window_dims);
+        self.recent_files.render(builder, self.mouse);
+        self.tab_menu.render(builder, self.mouse, self.tabs.len(), self.tabs.active_tab_idx(), self.tabs.split_view().map(|split| split.other_idx));
+        self.element_menu.render(builder, self.mouse);
+        self.clipboard_ring.render(builder, self.mouse);
+        self.render_tab_cycle(builder);
 
         {
             // let start = std::time::Instant::now();
+            let viewport = builder.set_viewport(self.active_viewport(builder));
             self.render_action_wheel(builder);
+            builder.set_viewport(viewport);
             self.render_held_entry(builder);
             {
                 let mut y = HEADER_SIZE;
                 self.notifications.render(&mut y, builder);
                 self.alerts.render(&mut y, builder);
             }
+            self.render_data_version(builder);
             self.render_debug_menu(builder);
+            self.render_drop_overlay(builder);
             // println!("Misc: {}ms", start.elapsed().as_millis_f64());
         }
         builder.draw_tooltips();
     }
 
+    /// The alternating stripes behind every line, across the window or the pane being drawn
+    fn render_stripes(builder: &mut VertexBufferBuilder) {
+        for n in 0..(builder.window_height() - HEADER_SIZE + 15) / 16 {
+            let uv = if (n % 2 == 0) ^ ((builder.scroll() / 16) % 2 == 0) { DARK_STRIPE_UV + (1, 1) } else { LIGHT_STRIPE_UV + (1, 1) };
+            builder.draw_texture_region_z((0, n * 16 + HEADER_SIZE - (n == 0) as usize), BASE_Z, uv, (builder.window_width(), 16 + (n == 0) as usize), (14, 14));
+        }
+    }
+
+    /// The tab split beside the active one and the divider between them, see [`TabManager::split`]
+    fn render_other_pane(&self, builder: &mut VertexBufferBuilder) {
+        let Some(split) = self.tabs.split_view() else { return };
+        let (Some(tab), Some((x, width)), Some(divider)) = (self.tabs.get(split.other_idx), self.tabs.pane(split.other_idx, builder.window_width()), self.tabs.divider(builder.window_width())) else { return };
+        let TabConstants { left_margin, scroll, .. } = tab.consts();
+        let viewport = builder.set_viewport(Some(Viewport { x, width, scroll }));
+        Self::render_stripes(builder);
+        // nothing in it can be interacted with until it's focused, so it's drawn as if the cursor were away
+        let mut ctx = RenderContext::new(None, None, None, false, None, left_margin, Vec2u::new(0, 0), tab.freehand_mode, tab.number_base, tab.region_heatmap, tab.boolean_overrides.true_lines(&tab.root));
+        tab.render(builder, &mut ctx, false, true, 0.0);
+        builder.set_viewport(viewport);
+        builder.draw_texture_region_z((divider, HEADER_SIZE), BASE_Z, LINE_NUMBER_SEPARATOR_UV, (TabManager::DIVIDER_WIDTH, builder.window_height() - HEADER_SIZE), (2, 16));
+    }
+
     pub fn render_search_boxes(&self, builder: &mut VertexBufferBuilder) {
         self.search_box.render(builder);
         if ReplaceBox::is_visible(&self.search_box, &self.replace_box) {
             self.replace_box.render(builder);
+        } else if self.go_to_box.is_selected() {
+            self.go_to_box.render(builder, self.tabs.active_tab().root.is_region());
+        } else if self.url_box.is_selected() {
+            self.url_box.render(builder);
+        } else if self.values_box.is_open() {
+            self.values_box.render(builder);
         }
         builder.draw_tooltips();
     }
 
     pub fn tick(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        self.poll_instance_listener();
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut idx = 0;
+            while let Some(loading) = self.loading_tabs.get(idx) {
+                if loading.is_finished() {
+                    let loading = self.loading_tabs.remove(idx);
+                    let order = loading.order;
+                    let name = loading.path.name().to_owned();
+                    let path = loading.path.path().to_path_buf();
+                    let is_download = loading.is_download();
+                    match loading.finish(self.window_dims).with_context(|| format!("Could not open {name}")) {
+                        Ok(tab) => {
+                            if tab.url.is_none()
+                                && let Ok(path) = std::fs::canonicalize(tab.path.path())
+                            {
+                                config::push_recent_file(path);
+                            }
+                            // one opened after it which already finished keeps the focus
+                            if order > self.last_focused_load {
+                                self.last_focused_load = order;
+                                self.tabs.add(tab);
+                            } else {
+                                self.tabs.add_in_background(tab);
+                            }
+                        }
+                        Err(e) if !is_download && e.downcast_ref::<BedrockHeaderMismatch>().is_some() => {
+                            if let Err(e) = self.load_anyway(&path, &e) {
+                                self.failed_loads.push(e);
+                            }
+                        }
+                        Err(e) => self.failed_loads.push(e),
+                    }
+                } else {
+                    idx += 1;
+                }
+            }
+            if self.loading_tabs.is_empty() {
+                self.report_failed_loads();
+            }
+        }
         #[cfg(not(target_arch = "wasm32"))]
         for (idx, tab) in self.tabs.iter_mut().enumerate() {
-            if (tab.last_interaction.elapsed() >= Tab::AUTOSAVE_INTERVAL) && tab.history.has_unsaved_changes() && tab.root.true_height() <= Tab::AUTOSAVE_MAXIMUM_LINES {
-                if let Err(e) = tab.save(false) {
+            if let Err(e) = tab.finish_save(false, &mut self.alerts) {
+                self.alerts.alert(e);
+            }
+            if let Err(e) = tab.poll_watcher() {
+                self.alerts.alert(e.context(format!("Failed to reload {}", tab.path.name())));
+            }
+            if (tab.last_interaction.elapsed() >= Tab::AUTOSAVE_INTERVAL) && tab.history.has_unsaved_changes() && tab.saving_since().is_none() && tab.root.true_height() <= Tab::AUTOSAVE_MAXIMUM_LINES {
+                if let Err(e) = tab.autosave(&mut self.alerts) {
                     self.alerts.alert(e.context(format!("Failed to autosave {nth} tab", nth = nth(idx + 1))));
                 }
             }
         }
+        for tab in &mut self.tabs {
+            tab.reparse_scratch_pad();
+            if tab.history.take_discarded() {
+                self.notifications.notify(Notification::new(format!("Oldest undo steps of {} discarded", tab.path.name()), TextColor::Gray, NotificationKind::History));
+            }
+        }
+        // saving as or renaming the root may have given a tab another name, and any edit may have flipped whether the active one has unsaved changes
+        self.tabs.refresh_display_names();
+        self.tabs.refresh_window_title();
+        // switching tabs may have moved one into or out of a split
+        self.tabs.refresh_window_dims(self.window_dims);
         if (self.tabs.active_tab().held_entry.is_some() || self.tabs.active_tab().freehand_mode || ((self.tabs.active_tab().selected_text.is_some() || self.search_box.is_selected()) && self.last_mouse_state == ElementState::Pressed))
             && self.action_wheel.is_none()
             && self.scrollbar_offset.is_none()
@@ -1487,41 +3789,66 @@ impl Workbench {
         }
     }
 
+    /// Asks what to do with the unsaved changes of each tab before quitting, returning how many tabs are still unsaved if any of them was kept open
+    ///
+    /// Otherwise, the tabs are kept as the [`Session`] to reopen next time
+    #[cfg(not(target_arch = "wasm32"))]
     #[must_use]
     pub fn close(&mut self) -> usize {
-        let mut failed_tabs = 0_usize;
-
-        for tab in &mut self.tabs {
-            if tab.history.has_unsaved_changes() && core::mem::replace(&mut tab.last_close_attempt, Timestamp::now()).elapsed() > Tab::TAB_CLOSE_DOUBLE_CLICK_INTERVAL {
-                failed_tabs += 1;
+        for (idx, tab) in self.tabs.iter_mut().enumerate() {
+            if tab.confirm_close(&mut self.alerts).alert_err(&mut self.alerts) != Some(true) {
+                // the rest aren't asked about once one is kept open
+                return self.tabs.iter().skip(idx).filter(|tab| tab.history.has_unsaved_changes()).count().max(1)
             }
         }
-
-        if failed_tabs > 0 {
-            self.alerts.alert(Alert::new(
-                "Are you sure you want to exit?",
-                TextColor::Yellow,
-                format!("You have {failed_tabs} unsaved tab{tab_suffix}.", tab_suffix = if failed_tabs == 1 { "" } else { "s" }),
-            ));
+        if config::get_restore_session()
+            && let Err(e) = Session::capture(&self.tabs).write()
+        {
+            error!("Could not save session: {e}");
         }
-        failed_tabs
+        0
     }
 
+    /// How many tabs have unsaved changes, for the page to ask about before it's left
+    #[cfg(target_arch = "wasm32")]
+    #[must_use]
+    pub fn close(&mut self) -> usize { self.tabs.iter().filter(|tab| tab.history.has_unsaved_changes()).count() }
+
     fn render_held_entry(&self, builder: &mut VertexBufferBuilder) {
         let shift = self.held_keys.shift();
 
         if let Some(held_entry) = &self.tabs.active_tab().held_entry {
             let element = &held_entry.kv.1;
             builder.draw_texture_z(self.mouse.saturating_sub((8, 8).into()), HELD_ENTRY_Z, element.uv(), (16, 16));
+            if held_entry.copy || self.held_keys.ctrl() || self.held_keys.alt() {
+                builder.settings(self.mouse + (4, 0), false, HELD_ENTRY_TEXT_Z);
+                builder.color = TextColor::Green.to_raw();
+                let _ = write!(builder, "+");
+            }
 
-            if (!element.is_primitive() || !element.is_default_state()) && element.should_render_description() || shift {
+            let more = held_entry.pasted.as_ref().filter(|rest| !rest.is_empty()).map(|rest| format!("and {} more", rest.len()));
+            if (!element.is_primitive() || !element.is_default_state()) && element.should_render_description() || shift || more.is_some() {
                 let (text, color) = element.value();
                 builder.color = color.to_raw();
-                builder.draw_tooltip(&[&text], self.mouse, false);
+                builder.draw_tooltip(&core::iter::once(text.as_ref()).chain(more.as_deref()).collect::<Vec<_>>(), self.mouse, false);
             }
         }
     }
 
+    fn render_drop_overlay(&self, builder: &mut VertexBufferBuilder) {
+        let [first, rest @ ..] = self.hovered_files.as_slice() else { return };
+        let text = if rest.is_empty() {
+            format!("Drop to open {}", first.file_name().unwrap_or(first.as_os_str()).to_string_lossy())
+        } else {
+            format!("Drop to open {} files", rest.len() + 1)
+        };
+        let y = builder.window_height().saturating_sub(32) / 2;
+        builder.draw_texture_region_z((0, y), DROP_OVERLAY_Z, DARK_STRIPE_UV + (1, 1), (builder.window_width(), 32), (14, 14));
+        builder.settings((builder.window_width().saturating_sub(text.width()) / 2, y + 8), false, DROP_OVERLAY_TEXT_Z);
+        builder.color = TextColor::Yellow.to_raw();
+        let _ = write!(builder, "{text}");
+    }
+
     fn render_debug_menu(&mut self, builder: &mut VertexBufferBuilder) {
         if !self.debug_menu {
             return
@@ -1593,74 +3920,202 @@ impl Workbench {
         }
     }
 
+    /// The version of Minecraft the active tab is from, in the bottom right corner
+    fn render_data_version(&self, builder: &mut VertexBufferBuilder) {
+        if self.debug_menu {
+            return
+        }
+        let Some(data_version) = root_data_version(&self.tabs.active_tab().root) else { return };
+        let line = format!("Minecraft {} (DataVersion {data_version})", version_name(data_version));
+        if builder.window_height() < HEADER_SIZE + VertexBufferBuilder::CHAR_HEIGHT {
+            return
+        }
+        builder.settings((builder.window_width().saturating_sub(line.width() + 4), builder.window_height() - VertexBufferBuilder::CHAR_HEIGHT), false, ZOffset::DEBUG_TEXT_Z);
+        builder.color = TextColor::Gray.to_raw();
+        let _ = write!(builder, "{line}");
+    }
+
     fn render_tabs(&self, builder: &mut VertexBufferBuilder) {
-        let mut offset = 3;
+        let mut offset = TabManager::STRIP_START;
         builder.horizontal_scroll = self.tab_scroll;
-        for (idx, tab) in self.tabs.iter().enumerate() {
-            let remaining_width = tab.path.name().width() + 48 + 3;
-            let uv = if tab.last_close_attempt.elapsed() <= Tab::TAB_CLOSE_DOUBLE_CLICK_INTERVAL {
-                CLOSED_WIDGET_UV
-            } else if idx == self.tabs.active_tab_idx() {
-                SELECTED_WIDGET_UV
-            } else if (offset..offset + 3 + remaining_width).contains(&self.mouse.x) && (3..=19).contains(&self.mouse.y) {
-                HOVERED_WIDGET_UV
-            } else {
-                UNSELECTED_WIDGET_UV
-            };
-            builder.draw_texture((offset, 3), uv, (3, 16));
-            if (offset..offset + 16).contains(&self.mouse.x) && (3..19).contains(&self.mouse.y) {
-                builder.draw_tooltip(&[tab.root.display_name()], self.mouse, false);
+        let overflows = self.tab_strip_overflows();
+        if overflows {
+            builder.clip_right = self.visible_tab_strip_width();
+        }
+        let dragged = self.tabs.dragged();
+        let drop_idx = self.tabs.drop_idx(self.mouse.x + self.tab_scroll);
+        for (idx, tab) in self.tabs.iter().enumerate().filter(|&(idx, _)| dragged.is_none_or(|held| held.idx != idx)) {
+            // leaves a gap where the dragged tab would be dropped
+            if let Some(held) = dragged
+                && drop_idx == Some(idx - usize::from(idx > held.idx))
+            {
+                offset += self.tabs.iter().nth(held.idx).map_or(0, |tab| tab.strip_width() + TabManager::STRIP_GAP);
+            }
+            self.render_tab(builder, idx, tab, offset, dragged.is_none());
+            offset += tab.strip_width() + TabManager::STRIP_GAP;
+        }
+        if let Some(held) = dragged
+            && let Some(tab) = self.tabs.iter().nth(held.idx)
+        {
+            if drop_idx == Some(self.tabs.len() - 1) {
+                offset += tab.strip_width() + TabManager::STRIP_GAP;
             }
-            offset += 2;
-            tab.draw_icon(builder, (offset, 2), JUST_OVERLAPPING_BASE_TEXT_Z);
-            offset += 1;
+            self.render_tab(builder, held.idx, tab, (self.mouse.x + self.tab_scroll).saturating_sub(held.grab_offset), false);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        for loading in &self.loading_tabs {
+            let remaining_width = loading.path.name().width() + 48 + 3;
+            let hovered = (offset..offset + 3 + remaining_width).contains(&self.mouse.x) && (3..=19).contains(&self.mouse.y);
+            let uv = if hovered { HOVERED_WIDGET_UV } else { UNSELECTED_WIDGET_UV };
+            builder.draw_texture((offset, 3), uv, (3, 16));
+            offset += 3;
             builder.draw_texture_region_z((offset, 3), BASE_Z, uv + (3, 0), (remaining_width, 16), (10, 16));
             builder.settings((offset + 16, 3), false, BASE_TEXT_Z);
-            builder.color = match config::get_theme() {
-                Theme::Light => TextColor::DarkGray,
-                Theme::Dark => TextColor::White,
-            }
-                .to_raw();
-            let _ = write!(builder, "{}", tab.path.name());
+            builder.color = TextColor::Gray.to_raw();
+            let _ = write!(builder, "{}", loading.path.name());
             offset += remaining_width;
             builder.draw_texture((offset, 3), uv + (13, 0), (3, 16));
-            builder.draw_texture((offset - 32, 3), if tab.history.has_unsaved_changes() { SAVE_UV } else { SAVE_GRAYSCALE_UV }, (16, 16));
-            builder.draw_texture((offset - 16, 3), tab.format.uv(), (16, 16));
-            if AxisAlignedBoundingBox::new(offset - 32, offset - 16, 3, 19).contains(self.mouse) {
-                builder.draw_tooltip(&["Save"], self.mouse, false);
-            }
-            if (offset - 16..offset).contains(&self.mouse.x) && (3..19).contains(&self.mouse.y) {
-                builder.draw_tooltip(&[tab.format.into_str()], self.mouse, false);
+            // in place of the save and format buttons
+            let (bar_start, bar_width) = match loading.progress() {
+                Some((done, total)) => (0, done * 28 / total),
+                // bounces back and forth when there's no way to tell
+                None => ((loading.started.elapsed().as_millis() as usize / 40 % 40).abs_diff(20), 8),
+            };
+            builder.draw_texture_region_z((offset - 30, 10), JUST_OVERLAPPING_BASE_Z, NOTIFICATION_BAR_BACKDROP_UV, (28, 2), (20, 1));
+            builder.draw_texture_region_z((offset - 30 + bar_start, 10), JUST_OVERLAPPING_BASE_Z, NOTIFICATION_BAR_UV, (bar_width, 2), (20, 1));
+            if hovered {
+                builder.draw_tooltip(&["Loading...", "Middle-click to cancel"], self.mouse, false);
             }
             offset += 6;
         }
         builder.horizontal_scroll = 0;
+        builder.clip_right = usize::MAX;
+        if overflows {
+            self.render_tab_scroll_buttons(builder);
+        }
         builder.draw_texture_region_z((0, 21), BASE_Z, HORIZONTAL_SEPARATOR_UV, (builder.window_width(), 2), (14, 2));
         builder.draw_texture_region_z((0, 45), BASE_Z, HORIZONTAL_SEPARATOR_UV, (builder.window_width(), 2), (14, 2));
     }
 
+    /// The tabs in the order Ctrl + Tab goes through them, shown while Ctrl is held
+    fn render_tab_cycle(&self, builder: &mut VertexBufferBuilder) {
+        const PADDING: usize = 4;
+
+        let Some((order, position)) = self.tabs.cycle_order() else { return };
+        let names = order.iter().filter_map(|&idx| self.tabs.get(idx)).map(Tab::display_name).collect::<Vec<_>>();
+        let width = names.iter().map(|name| name.width()).max().unwrap_or(0) + PADDING * 2;
+        let height = names.len() * 16;
+        let x = (builder.window_width().saturating_sub(width)) / 2;
+        let y = HEADER_SIZE.max(builder.window_height().saturating_sub(height) / 2);
+        builder.draw_texture_region_z((x, y), TAB_CYCLE_Z, DARK_STRIPE_UV + (1, 1), (width, height), (14, 14));
+        builder.draw_texture_region_z((x, y + position * 16), TAB_CYCLE_SELECTION_Z, HOVERED_STRIPE_UV, (width, 16), (16, 16));
+        for (idx, name) in names.into_iter().enumerate() {
+            builder.settings((x + PADDING, y + idx * 16), false, TAB_CYCLE_TEXT_Z);
+            builder.color = if idx == position { TextColor::White } else { TextColor::Gray }.to_raw();
+            let _ = write!(builder, "{name}");
+        }
+    }
+
+    fn render_tab_scroll_buttons(&self, builder: &mut VertexBufferBuilder) {
+        let max_scroll = self.tab_strip_width().saturating_sub(self.visible_tab_strip_width());
+        let mut x = self.visible_tab_strip_width() + 2;
+        for (text, enabled) in [("<", self.tab_scroll > 0), (">", self.tab_scroll < max_scroll)] {
+            let hovered = enabled && AxisAlignedBoundingBox::new(x, x + Self::TAB_SCROLL_BUTTON_WIDTH, 3, 19).contains(self.mouse);
+            let uv = if hovered { HOVERED_WIDGET_UV } else { UNSELECTED_WIDGET_UV };
+            builder.draw_texture_region_z((x, 3), BASE_Z, uv, (Self::TAB_SCROLL_BUTTON_WIDTH, 16), (16, 16));
+            builder.settings((x + (Self::TAB_SCROLL_BUTTON_WIDTH - text.width()) / 2, 3), false, BASE_TEXT_Z);
+            builder.color = if enabled { TextColor::White } else { TextColor::DarkGray }.to_raw();
+            let _ = write!(builder, "{text}");
+            x += Self::TAB_SCROLL_BUTTON_WIDTH;
+        }
+    }
+
+    /// Drawn above everything else in the tab strip while it's being dragged, without any hover effects while anything is
+    fn render_tab(&self, builder: &mut VertexBufferBuilder, idx: usize, tab: &Tab, mut offset: usize, hoverable: bool) {
+        let held = self.tabs.dragged().is_some_and(|held| held.idx == idx);
+        let (z, icon_z, text_z, dot_z) = if held { (HELD_TAB_Z, HELD_TAB_TEXT_Z, HELD_TAB_TEXT_Z, HELD_UNSAVED_TAB_DOT_Z) } else { (BASE_Z, JUST_OVERLAPPING_BASE_TEXT_Z, BASE_TEXT_Z, UNSAVED_TAB_DOT_Z) };
+        let remaining_width = tab.display_name().width() + tab.read_only_icon_width() + 48 + 3;
+        let uv = if idx == self.tabs.active_tab_idx() {
+            SELECTED_WIDGET_UV
+        } else if hoverable && (offset..offset + 3 + remaining_width).contains(&self.mouse.x) && (3..=19).contains(&self.mouse.y) {
+            HOVERED_WIDGET_UV
+        } else {
+            UNSELECTED_WIDGET_UV
+        };
+        builder.draw_texture_z((offset, 3), z, uv, (3, 16));
+        if hoverable && (offset..offset + 16).contains(&self.mouse.x) && (3..19).contains(&self.mouse.y) {
+            builder.draw_tooltip(&[tab.root.display_name()], self.mouse, false);
+        }
+        offset += 2;
+        tab.draw_icon(builder, (offset, 2), icon_z);
+        offset += 1;
+        builder.draw_texture_region_z((offset, 3), z, uv + (3, 0), (remaining_width, 16), (10, 16));
+        builder.settings((offset + 16, 3), false, text_z);
+        let text_color = match config::get_theme() {
+            Theme::Light => TextColor::DarkGray,
+            Theme::Dark => TextColor::White,
+        }
+            .to_raw();
+        builder.color = text_color;
+        let _ = write!(builder, "{}", tab.display_name());
+        offset += remaining_width;
+        builder.draw_texture_z((offset, 3), z, uv + (13, 0), (3, 16));
+        let save_uv = if let Some(saving_since) = tab.saving_since() {
+            // blinks while the save is running in the background
+            if saving_since.elapsed().as_millis() / 250 % 2 == 0 { SAVE_UV } else { SAVE_GRAYSCALE_UV }
+        } else if tab.history.has_unsaved_changes() {
+            SAVE_UV
+        } else {
+            SAVE_GRAYSCALE_UV
+        };
+        builder.draw_texture_z((offset - 32, 3), icon_z, save_uv, (16, 16));
+        builder.draw_texture_z((offset - 16, 3), icon_z, tab.format.uv(), (16, 16));
+        if tab.history.has_unsaved_changes() {
+            // in the top right corner of the file type icon
+            builder.settings((offset - 5, 0), false, dot_z);
+            builder.color = text_color;
+            let _ = write!(builder, "●");
+        }
+        if tab.read_only {
+            builder.draw_texture_z((offset - 48, 3), icon_z, READ_ONLY_UV, (16, 16));
+            if hoverable && AxisAlignedBoundingBox::new(offset - 48, offset - 32, 3, 19).contains(self.mouse) {
+                builder.draw_tooltip(&["Read-only (Ctrl + Alt + L)"], self.mouse, false);
+            }
+        }
+        if !hoverable {
+            return
+        }
+        if AxisAlignedBoundingBox::new(offset - 32, offset - 16, 3, 19).contains(self.mouse) {
+            builder.draw_tooltip(&[if tab.saving_since().is_some() { "Saving..." } else { "Save" }], self.mouse, false);
+        }
+        if (offset - 16..offset).contains(&self.mouse.x) && (3..19).contains(&self.mouse.y) {
+            builder.draw_tooltip(&[tab.format.into_str()], self.mouse, false);
+        }
+    }
+
     fn render_action_wheel(&mut self, builder: &mut VertexBufferBuilder) {
         use std::f64::consts::TAU;
 
         let Some(mut center) = self.action_wheel else { return };
+        let mouse = self.pane_mouse();
         center.x = center.x.saturating_sub(31) + 31;
         center.y = center.y.saturating_sub(31) + 31;
         let tab = self.tabs.active_tab_mut();
         let consts @ TabConstants { left_margin, .. } = tab.consts();
-        let highlight_idx = ((center - self.mouse).angle() / TAU * 8.0 + 3.5).rem_euclid(8.0) as usize;
-        let squared_distance_from_origin = (center.y as isize - self.mouse.y as isize).pow(2) + (center.x as isize - self.mouse.x as isize).pow(2);
+        let highlight_idx = ((center - mouse).angle() / TAU * 8.0 + 3.5).rem_euclid(8.0) as usize;
+        let squared_distance_from_origin = (center.y as isize - mouse.y as isize).pow(2) + (center.x as isize - mouse.x as isize).pow(2);
         if center.y >= HEADER_SIZE {
             if center.y > tab.root.height() * 16 + HEADER_SIZE {
                 return
             };
             let InteractionInformation::Content {
-                is_in_left_margin: false, depth, key, value, ..
+                is_in_left_margin: false, depth, key, value, indices, ..
             } = Self::get_interaction_information_raw(consts, center, &mut tab.root)
             else {
                 return
             };
             let min_x = depth * 16 + left_margin;
-            let max_x = min_x + 32 + value.value_width() + key.map(|key| key.width() + ": ".width()).unwrap_or(0);
+            let max_x = min_x + 32 + value.keyed_value_width(key.as_deref(), tab.number_base, tab.boolean_overrides.shows_boolean(&indices)) + key.map(|key| key.width() + ": ".width()).unwrap_or(0);
             if !(min_x..max_x).contains(&center.x) {
                 return
             };
@@ -1693,22 +4148,23 @@ impl Workbench {
     }
 
     pub fn try_mouse_scroll(&mut self) {
+        let mouse = self.pane_mouse();
         let tab = self.tabs.active_tab_mut();
-        if self.mouse.x >= self.window_dims.width as usize - 16 && self.mouse.y >= HEADER_SIZE {
+        if mouse.x >= tab.window_dims.width as usize - 16 && mouse.y >= HEADER_SIZE {
             tab.modify_horizontal_scroll(|scroll| scroll + 16);
-        } else if self.mouse.x < 16 {
+        } else if mouse.x < 16 {
             tab.modify_horizontal_scroll(|scroll| scroll.saturating_sub(16));
         }
 
-        if self.mouse.y < HEADER_SIZE + 16 {
+        if mouse.y < HEADER_SIZE + 16 {
             tab.modify_scroll(|scroll| scroll.saturating_sub(16));
-        } else if self.mouse.y >= usize::min(self.window_dims.width as usize - 16, tab.root.height() * 16 + HEADER_SIZE) {
+        } else if mouse.y >= usize::min(tab.window_dims.width as usize - 16, tab.root.height() * 16 + HEADER_SIZE) {
             tab.modify_scroll(|scroll| scroll + 16);
         }
     }
 
     pub fn try_search_box_scroll(&mut self) {
-        let search_box_x = (SEARCH_BOX_START_X + 16)..(self.window_dims.width as usize - (SEARCH_BOX_END_X + 64));
+        let search_box_x = (SEARCH_BOX_START_X + 16)..(self.window_dims.width as usize - (SEARCH_BOX_END_X + 17 + 16 + 16 + 16 + 16));
         if self.last_mouse_state == ElementState::Pressed && self.search_box.is_selected() && !search_box_x.contains(&self.mouse.x) {
             if self.mouse.x < search_box_x.start {
                 self.search_box.horizontal_scroll = self.search_box.horizontal_scroll.saturating_sub(4);
@@ -2051,11 +4507,45 @@ pub const LINE_DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(250);
 pub struct HeldEntry {
     pub(super) kv: NbtElementAndKey,
     pub(super) indices_history: LinkedQueue<OwnedIndices>,
+    /// Only set when it was pasted, holding the entries after the first which are dropped along with it
+    pub(super) pasted: Option<Vec<NbtElementAndKey>>,
+    /// Picked up with Ctrl or Alt held, leaving the original where it was, so nothing is recorded until it's dropped
+    pub(super) copy: bool,
 }
 
 impl HeldEntry {
     #[must_use]
-    pub fn from_aether(kv: NbtElementAndKey) -> Self { Self { kv, indices_history: LinkedQueue::new() } }
+    pub fn from_aether(kv: NbtElementAndKey) -> Self { Self { kv, indices_history: LinkedQueue::new(), pasted: None, copy: false } }
+
+    #[must_use]
+    pub fn copy_of(kv: NbtElementAndKey) -> Self { Self { kv, indices_history: LinkedQueue::new(), pasted: None, copy: true } }
+
+    #[must_use]
+    pub fn from_clipboard(kv: NbtElementAndKey, rest: Vec<NbtElementAndKey>) -> Self { Self { kv, indices_history: LinkedQueue::new(), pasted: Some(rest), copy: false } }
+
+    /// The entries on the clipboard, alerting about the first one which couldn't be parsed when there are any before it to hold
+    ///
+    /// # Errors
+    /// If the clipboard couldn't be read, none of it parsed, or it holds a chunk outside of a region
+    pub fn parse_clipboard(alerts: &mut AlertManager, is_region: bool) -> Result<Self> {
+        let clipboard = get_clipboard().ok_or_else(|| anyhow!("Failed to get clipboard"))?;
+        let (entries, err) = NbtElement::entries_from_str(&clipboard);
+        let mut entries = entries.into_iter();
+        let Some(first) = entries.next() else {
+            match err {
+                Some((_, idx)) => bail!("Could not parse clipboard as SNBT: {}", SnbtError::of(&clipboard, idx).diagnostic()),
+                None => bail!("The clipboard doesn't hold any SNBT"),
+            }
+        };
+        let rest = entries.collect::<Vec<_>>();
+        if !is_region && core::iter::once(&first).chain(&rest).any(|(_, element)| element.is_chunk()) {
+            bail!("Chunks are not supported for non-region tabs");
+        }
+        if let Some((entry, idx)) = err {
+            alerts.alert(Alert::error(anyhow!("Could not parse the {} entry on the clipboard as SNBT, so only the {entry} before it are held: {}", nth(entry + 1), SnbtError::of(&clipboard, idx).diagnostic())));
+        }
+        Ok(Self::from_clipboard(first, rest))
+    }
 
     #[must_use]
     pub fn from_indices(kv: NbtElementAndKey, indices: OwnedIndices) -> Self {
@@ -2066,6 +4556,8 @@ impl HeldEntry {
                 queue.push(indices);
                 queue
             },
+            pasted: None,
+            copy: false,
         }
     }
 }
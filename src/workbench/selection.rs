@@ -0,0 +1,213 @@
+use std::{fmt::Write, ops::Range};
+
+use compact_str::{CompactString, format_compact};
+
+use crate::{
+	elements::{NbtElementAndKey, element::NbtElement},
+	tree::{
+		CallbackInfo,
+		indices::{Indices, OwnedIndices},
+		navigate::NavigationInformation,
+		traverse::TraversalInformation,
+	},
+	util::StrExt,
+};
+
+/// Rows picked out with Ctrl and Shift clicks to be deleted, copied or moved all at once
+///
+/// Kept in the order of the tree, and never holding both an element and one within it since the outer one already covers it
+#[derive(Default, Clone)]
+pub struct Selection {
+	indices: Vec<OwnedIndices>,
+	/// The row last Ctrl clicked, which Shift clicks select from
+	anchor: Option<OwnedIndices>,
+	/// Set while the rest of the selection follows the held entry it was grabbed by
+	pub dragging: bool,
+}
+
+impl Selection {
+	#[must_use]
+	pub const fn new() -> Self { Self { indices: Vec::new(), anchor: None, dragging: false } }
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool { self.indices.is_empty() }
+
+	#[must_use]
+	pub fn len(&self) -> usize { self.indices.len() }
+
+	#[must_use]
+	pub fn indices(&self) -> &[OwnedIndices] { &self.indices }
+
+	/// Whether the element at `indices` is itself selected
+	#[must_use]
+	pub fn contains(&self, indices: &Indices) -> bool { self.indices.iter().any(|selected| selected.iter().eq(indices.iter())) }
+
+	pub fn clear(&mut self) { let _ = self.take(); }
+
+	/// Empties it, giving back what was selected in the order of the tree
+	pub fn take(&mut self) -> Vec<OwnedIndices> {
+		self.anchor = None;
+		self.dragging = false;
+		core::mem::take(&mut self.indices)
+	}
+
+	/// Whether the element at `indices` is selected, or one it's within is
+	#[must_use]
+	pub fn covers(&self, indices: &Indices) -> bool { self.indices.iter().any(|selected| selected.encompasses_or_equal(indices)) }
+
+	/// Selects the element at `indices`, or deselects it if it already was
+	pub fn toggle(&mut self, indices: OwnedIndices) {
+		if let Some(idx) = self.indices.iter().position(|selected| selected.iter().eq(indices.iter())) {
+			self.indices.remove(idx);
+		} else {
+			self.insert(indices.clone());
+		}
+		self.anchor = Some(indices);
+	}
+
+	/// Selects every row shown from the anchor to the element at `indices`, or toggles only that one if there's no anchor
+	pub fn select_range(&mut self, root: &NbtElement, indices: OwnedIndices) {
+		let Some(Ok(NavigationInformation { line_number: from, .. })) = self.anchor.as_ref().map(|anchor| root.navigate(anchor)) else {
+			self.toggle(indices);
+			return
+		};
+		let Ok(NavigationInformation { line_number: to, .. }) = root.navigate(&indices) else { return };
+		// the root can't be moved or deleted, so it isn't selected
+		for y in from.min(to).max(1)..=from.max(to) {
+			if let Ok(TraversalInformation { indices, .. }) = root.traverse(y, None) {
+				self.insert(indices);
+			}
+		}
+	}
+
+	fn insert(&mut self, indices: OwnedIndices) {
+		if self.covers(&indices) {
+			return
+		}
+		self.indices.retain(|selected| !indices.encompasses(selected));
+		let idx = self.indices.partition_point(|selected| selected.iter().lt(indices.iter()));
+		self.indices.insert(idx, indices);
+	}
+
+	/// Keeps up with the tree changing, see [`MutableIndices::apply`](crate::tree::MutableIndices::apply)
+	pub fn remap<F: FnMut(&mut OwnedIndices, &mut CallbackInfo)>(&mut self, mut f: F) {
+		self.indices.retain_mut(|indices| {
+			let mut ci = CallbackInfo::new();
+			f(indices, &mut ci);
+			!ci.removed()
+		});
+		if let Some(anchor) = &mut self.anchor {
+			let mut ci = CallbackInfo::new();
+			f(anchor, &mut ci);
+			if ci.removed() {
+				self.anchor = None;
+			}
+		}
+		// swapping and reordering can change which comes first
+		self.indices.sort_by(|a, b| a.iter().cmp(b.iter()));
+	}
+
+	/// The true line numbers of the selected elements and everything within them, in order
+	#[must_use]
+	pub fn true_line_ranges(&self, root: &NbtElement) -> Vec<Range<usize>> {
+		self.indices
+			.iter()
+			.filter_map(|indices| root.navigate(indices).ok())
+			.map(|NavigationInformation { element, true_line_number, .. }| true_line_number..true_line_number + element.true_height())
+			.collect()
+	}
+
+	/// Each selected element as SNBT with its key, a line each
+	#[must_use]
+	pub fn to_snbt(&self, root: &NbtElement, pretty: bool) -> String {
+		let mut buf = String::new();
+		for NavigationInformation { key, element, .. } in self.indices.iter().filter_map(|indices| root.navigate(indices).ok()) {
+			if !buf.is_empty() {
+				buf.push('\n');
+			}
+			let key = key.map(|key| if key.needs_escape() { format_compact!("{key:?}") } else { key.into() });
+			let separator = match (&key, pretty) {
+				(None, _) => "",
+				(Some(_), true) => ": ",
+				(Some(_), false) => ":",
+			};
+			let _ = if pretty {
+				write!(&mut buf, "{}{separator}{element:#?}", key.as_deref().unwrap_or(""))
+			} else {
+				write!(&mut buf, "{}{separator}{element}", key.as_deref().unwrap_or(""))
+			};
+		}
+		buf
+	}
+
+	/// A copy of each selected element along with its key
+	#[must_use]
+	pub fn entries(&self, root: &NbtElement) -> Vec<NbtElementAndKey> {
+		self.indices
+			.iter()
+			.filter_map(|indices| root.navigate(indices).ok())
+			.map(|NavigationInformation { key, element, .. }| (key.map(CompactString::from), element.clone()))
+			.collect()
+	}
+
+	/// The keys of the selected elements which have one, a line each
+	#[must_use]
+	pub fn keys(&self, root: &NbtElement) -> String {
+		let keys = self.indices.iter().filter_map(|indices| root.navigate(indices).ok()?.key).collect::<Vec<_>>();
+		keys.join("\n")
+	}
+
+	/// Each selected element as SNBT without its key, a line each
+	#[must_use]
+	pub fn values_snbt(&self, root: &NbtElement, pretty: bool) -> String {
+		let mut buf = String::new();
+		for NavigationInformation { element, .. } in self.indices.iter().filter_map(|indices| root.navigate(indices).ok()) {
+			if !buf.is_empty() {
+				buf.push('\n');
+			}
+			let _ = if pretty { write!(&mut buf, "{element:#?}") } else { write!(&mut buf, "{element}") };
+		}
+		buf
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{tree::actions::open::open_element, workbench::marked_line::MarkedLines};
+
+	fn selected(selection: &Selection) -> Vec<Vec<usize>> { selection.indices.iter().map(|indices| indices.iter().collect()).collect() }
+
+	#[test]
+	fn test_selection() {
+		let (_, mut root) = NbtElement::from_str("{a:{b:1b,c:2b},d:{e:3b}}").expect("Valid SNBT");
+		let mut bookmarks = MarkedLines::new();
+		open_element(&mut root, Indices::EMPTY, &mut bookmarks).expect("Root is complex");
+		open_element(&mut root, &OwnedIndices::from([0]), &mut bookmarks).expect("`a` is complex");
+		let mut selection = Selection::new();
+
+		selection.toggle(OwnedIndices::from([0, 1]));
+		selection.select_range(&root, OwnedIndices::from([1]));
+		// `c` and `d`, with `d` still closed
+		assert_eq!(selected(&selection), [vec![0, 1], vec![1]]);
+		assert_eq!(selection.to_snbt(&root, false), "c:2b\nd:{e:3b}");
+		assert_eq!(selection.keys(&root), "c\nd");
+		assert_eq!(selection.values_snbt(&root, false), "2b\n{e:3b}");
+
+		// `a` covers `c`
+		selection.toggle(OwnedIndices::from([0]));
+		assert_eq!(selection.len(), 2);
+		assert!(selection.covers(&OwnedIndices::from([0, 0])));
+		assert_eq!(selection.true_line_ranges(&root), [2..5, 5..7]);
+
+		// as if `a` was removed
+		selection.remap(|indices, ci| {
+			if indices[0] == 0 {
+				ci.remove();
+			} else {
+				indices[0] -= 1;
+			}
+		});
+		assert_eq!(selected(&selection), [vec![0]]);
+	}
+}
@@ -0,0 +1,170 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{path::PathBuf, sync::Arc, thread::JoinHandle, time::Duration};
+
+#[cfg(not(target_arch = "wasm32"))]
+use anyhow::{Context, Result, anyhow, bail};
+#[cfg(not(target_arch = "wasm32"))]
+use winit::dpi::PhysicalSize;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+	elements::{element::NbtElement, region_integrity::RegionIssue},
+	util::Timestamp,
+	workbench::tab::{FilePath, NbtFileFormat, Tab},
+};
+
+/// Shared between a [`LoadingTab`] and the thread parsing its file
+#[derive(Default)]
+pub struct LoadProgress {
+	done: AtomicUsize,
+	total: AtomicUsize,
+	cancelled: AtomicBool,
+}
+
+impl LoadProgress {
+	pub fn set_total(&self, total: usize) { self.total.store(total, Ordering::Relaxed); }
+
+	pub fn increment(&self) { self.done.fetch_add(1, Ordering::Relaxed); }
+
+	/// `(done, total)`, or `None` if the parser can't tell how far along it is
+	#[must_use]
+	pub fn get(&self) -> Option<(usize, usize)> {
+		let total = self.total.load(Ordering::Relaxed);
+		(total > 0).then(|| (self.done.load(Ordering::Relaxed).min(total), total))
+	}
+
+	pub fn cancel(&self) { self.cancelled.store(true, Ordering::Relaxed); }
+
+	#[must_use]
+	pub fn is_cancelled(&self) -> bool { self.cancelled.load(Ordering::Relaxed) }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+static SPAWNED: AtomicUsize = AtomicUsize::new(0);
+
+/// A placeholder for a file being parsed on a worker thread, swapped for a real [`Tab`] once it's done
+#[cfg(not(target_arch = "wasm32"))]
+pub struct LoadingTab {
+	pub path: FilePath,
+	pub started: Timestamp,
+	/// Counts up with each file opened, so whichever was opened last can be switched to no matter which finishes first
+	pub order: usize,
+	/// Set when the file is being downloaded, see [`Tab::url`]
+	url: Option<String>,
+	progress: Arc<LoadProgress>,
+	handle: JoinHandle<Result<(NbtElement, NbtFileFormat, Vec<RegionIssue>)>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl LoadingTab {
+	/// # Errors
+	/// * If the path has no file name or the thread couldn't be spawned
+	pub fn spawn(path: PathBuf, buf: Vec<u8>) -> Result<Self> {
+		let file_path = FilePath::new(&path)?;
+		let progress = Arc::new(LoadProgress::default());
+		let handle = std::thread::Builder::new()
+			.name(format!("Parsing {}", file_path.name()))
+			// parsing is recursive
+			.stack_size(1_048_576 * 64 /* 64MiB */)
+			.spawn({
+				let progress = Arc::clone(&progress);
+				move || {
+					let region_issues = Tab::check_region(&path, &buf);
+					let (nbt, format) = Tab::parse_raw_with_progress(&path, buf, Some(progress))?;
+					Ok((nbt, format, region_issues))
+				}
+			})?;
+		Ok(Self {
+			path: file_path,
+			started: Timestamp::now(),
+			order: SPAWNED.fetch_add(1, Ordering::Relaxed) + 1,
+			url: None,
+			progress,
+			handle,
+		})
+	}
+
+	/// Downloads the file before parsing it, following any redirects
+	///
+	/// # Errors
+	/// * If the thread couldn't be spawned
+	pub fn spawn_url(url: String) -> Result<Self> {
+		let file_path = FilePath::for_url(&url);
+		let progress = Arc::new(LoadProgress::default());
+		let handle = std::thread::Builder::new()
+			.name(format!("Downloading {}", file_path.name()))
+			// parsing is recursive
+			.stack_size(1_048_576 * 64 /* 64MiB */)
+			.spawn({
+				let progress = Arc::clone(&progress);
+				let url = url.clone();
+				let path = file_path.path().to_path_buf();
+				move || {
+					let buf = download(&url)?;
+					if progress.is_cancelled() {
+						bail!("Cancelled");
+					}
+					let region_issues = Tab::check_region(&path, &buf);
+					let (nbt, format) = Tab::parse_raw_with_progress(&path, buf, Some(progress))?;
+					Ok((nbt, format, region_issues))
+				}
+			})?;
+		Ok(Self {
+			path: file_path,
+			started: Timestamp::now(),
+			order: SPAWNED.fetch_add(1, Ordering::Relaxed) + 1,
+			url: Some(url),
+			progress,
+			handle,
+		})
+	}
+
+	#[must_use]
+	pub fn progress(&self) -> Option<(usize, usize)> { self.progress.get() }
+
+	#[must_use]
+	pub fn is_finished(&self) -> bool { self.handle.is_finished() }
+
+	/// Whether it's being downloaded rather than read from a file, see [`Tab::url`]
+	#[must_use]
+	pub fn is_download(&self) -> bool { self.url.is_some() }
+
+	/// # Errors
+	/// * If the file couldn't be parsed, with the same error as [`Tab::parse_raw`]
+	pub fn finish(self, window_dims: PhysicalSize<u32>) -> Result<Tab> {
+		let (nbt, format, region_issues) = self.handle.join().map_err(|_| anyhow!("Thread panicked while parsing {}", self.path.name()))??;
+		let mut tab = match self.url {
+			Some(url) => Tab::from_url(nbt, url, format, window_dims),
+			None => Tab::new(nbt, self.path, format, window_dims),
+		}?;
+		tab.region_issues = region_issues;
+		Ok(tab)
+	}
+
+	/// Stops the parse as soon as possible, the worker drops whatever it already parsed on its own thread
+	pub fn cancel(self) { self.progress.cancel(); }
+}
+
+/// The body of a `GET` to `url`, as long as it responds with `200 OK` and isn't larger than [`Tab::MAX_DOWNLOAD_SIZE`]
+#[cfg(not(target_arch = "wasm32"))]
+fn download(url: &str) -> Result<Vec<u8>> {
+	let agent = ureq::Agent::config_builder().timeout_global(Some(Duration::from_secs(60))).build().new_agent();
+	let mut response = match agent.get(url).call() {
+		Ok(response) => response,
+		Err(ureq::Error::StatusCode(code)) => {
+			let reason = ureq::http::StatusCode::from_u16(code).ok().and_then(|status| status.canonical_reason()).unwrap_or_default();
+			bail!("{url} responded with {code} {reason}")
+		}
+		Err(e) => return Err(e).with_context(|| format!("Could not download {url}")),
+	};
+	let too_large = || anyhow!("{url} is larger than the {}MiB limit for downloads", Tab::MAX_DOWNLOAD_SIZE / 1_048_576);
+	if response.body().content_length().is_some_and(|len| len > Tab::MAX_DOWNLOAD_SIZE) {
+		return Err(too_large())
+	}
+	match response.body_mut().with_config().limit(Tab::MAX_DOWNLOAD_SIZE).read_to_vec() {
+		Ok(buf) => Ok(buf),
+		Err(ureq::Error::BodyExceedsLimit(_)) => Err(too_large()),
+		Err(e) => Err(e).with_context(|| format!("Could not download {url}")),
+	}
+}
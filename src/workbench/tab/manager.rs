@@ -1,16 +1,100 @@
-use crate::{util::Timestamp, window_properties, workbench::tab::Tab};
+#[cfg(not(target_arch = "wasm32"))]
+use std::fmt::{Display, Formatter};
+use std::{
+	ffi::OsStr,
+	path::{Path, PathBuf},
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use anyhow::Result;
+use winit::dpi::PhysicalSize;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::render::widget::alert::manager::{AlertManager, Alertable};
+use crate::{
+	window_properties,
+	workbench::tab::{NewTabRoot, Tab},
+};
 
 pub struct TabManager {
 	tabs: Vec<Tab>,
 	active_tab_idx: usize,
+	held: Option<HeldTab>,
+	/// Counts up each time a tab is switched to, which is when each tab was last used
+	activations: usize,
+	cycle: Option<TabCycle>,
+	/// Of every tab when [`TabManager::refresh_display_names`] last gave them their names
+	named_paths: Vec<PathBuf>,
+	/// Last given to the window, see [`TabManager::refresh_window_title`]
+	window_title: String,
+	split: Option<Split>,
+}
+
+/// Two tabs side by side, the active one always being in the focused pane, see [`TabManager::split`]
+#[derive(Copy, Clone, Debug)]
+pub struct Split {
+	/// The tab in the pane which isn't focused
+	pub other_idx: usize,
+	/// Whether the active tab is in the right pane
+	pub focus_right: bool,
+	/// How far along the window the divider was put, see [`TabManager::divider`]
+	divider: usize,
+}
+
+/// The tabs in the order they were last used, being switched through with Ctrl + Tab until Ctrl is let go
+struct TabCycle {
+	order: Vec<usize>,
+	position: usize,
+}
+
+/// A tab being dragged along the tab strip to reorder it, see [`TabManager::hold`]
+#[derive(Copy, Clone, Debug)]
+pub struct HeldTab {
+	pub idx: usize,
+	/// How far into the tab it was grabbed, so it doesn't jump to be under the cursor
+	pub grab_offset: usize,
+	origin_x: usize,
+	/// Whether it's been moved far enough from where it was grabbed to not just be a click
+	pub dragging: bool,
 }
 
 impl TabManager {
+	/// Where the first tab starts along the tab strip
+	pub const STRIP_START: usize = 3;
+	/// Between the end of one tab and the start of the next
+	pub const STRIP_GAP: usize = 3;
+	const DRAG_THRESHOLD: usize = 4;
+	/// Narrowest a pane can be dragged to
+	pub const MIN_PANE_WIDTH: usize = 160;
+	pub const DIVIDER_WIDTH: usize = 2;
+
 	#[must_use]
-	pub const fn without_tab() -> Self { Self { tabs: Vec::new(), active_tab_idx: 0 } }
+	pub const fn without_tab() -> Self {
+		Self {
+			tabs: Vec::new(),
+			active_tab_idx: 0,
+			held: None,
+			activations: 0,
+			cycle: None,
+			named_paths: Vec::new(),
+			window_title: String::new(),
+			split: None,
+		}
+	}
 
 	#[must_use]
-	pub fn from_tab(tab: Tab) -> Self { Self { tabs: vec![tab], active_tab_idx: 0 } }
+	pub fn from_tab(tab: Tab) -> Self {
+		Self {
+			tabs: vec![tab],
+			active_tab_idx: 0,
+			held: None,
+			activations: 0,
+			cycle: None,
+			named_paths: Vec::new(),
+			window_title: String::new(),
+			split: None,
+		}
+	}
 
 	#[must_use]
 	pub fn active_tab(&self) -> &Tab { unsafe { self.tabs.get(self.active_tab_idx).unwrap_unchecked() } }
@@ -19,24 +103,126 @@ impl TabManager {
 	pub fn active_tab_mut(&mut self) -> &mut Tab { unsafe { self.tabs.get_mut(self.active_tab_idx).unwrap_unchecked() } }
 
 	pub fn set_active_idx(&mut self, idx: usize) {
-		self.active_tab_idx = idx.min(self.tabs.len() - 1);
-		window_properties().set_window_title(format!("{} - NBT Workbench", self.active_tab().path.name()).as_str());
+		self.show(idx);
+		self.activations += 1;
+		self.tabs[self.active_tab_idx].last_active = self.activations;
+	}
+
+	/// Like [`TabManager::set_active_idx`] without counting as using it, for going through tabs with [`TabManager::cycle`]
+	///
+	/// Showing the tab in the other pane of a split focuses that pane
+	fn show(&mut self, idx: usize) {
+		let idx = idx.min(self.tabs.len() - 1);
+		if let Some(split) = &mut self.split
+			&& split.other_idx == idx
+		{
+			split.other_idx = self.active_tab_idx;
+			split.focus_right = !split.focus_right;
+		}
+		self.active_tab_idx = idx;
+		self.refresh_window_title();
+	}
+
+	/// Names the window after the active tab, starred while it has unsaved changes
+	///
+	/// Only tells the window when the title changed, so it's cheap to call every frame to catch edits, undos back to the last save and renames
+	pub fn refresh_window_title(&mut self) {
+		let Some(tab) = self.tabs.get(self.active_tab_idx) else { return };
+		let title = format!("{unsaved}{name} — NBT Workbench", unsaved = if tab.history.has_unsaved_changes() { "*" } else { "" }, name = tab.path.name());
+		if title != self.window_title {
+			window_properties().set_window_title(&title);
+			self.window_title = title;
+		}
 	}
 
+	/// Switches to the next tab in the order they were last used, or the previous one going `backwards`
+	pub fn cycle(&mut self, backwards: bool) {
+		let cycle = self.cycle.get_or_insert_with(|| {
+			let mut order = (0..self.tabs.len()).collect::<Vec<_>>();
+			order.sort_by_key(|&idx| core::cmp::Reverse(self.tabs[idx].last_active));
+			TabCycle { order, position: 0 }
+		});
+		let len = cycle.order.len();
+		cycle.position = if backwards { (cycle.position + len - 1) % len } else { (cycle.position + 1) % len };
+		let idx = cycle.order[cycle.position];
+		self.show(idx);
+	}
+
+	/// Counts the tab [`TabManager::cycle`] stopped at as used, once Ctrl is let go
+	pub fn finish_cycle(&mut self) {
+		if self.cycle.take().is_some() {
+			self.set_active_idx(self.active_tab_idx);
+		}
+	}
+
+	/// The tabs being gone through with [`TabManager::cycle`] in order, along with which one is shown
+	#[must_use]
+	pub fn cycle_order(&self) -> Option<(&[usize], usize)> { self.cycle.as_ref().map(|cycle| (cycle.order.as_slice(), cycle.position)) }
+
 	pub fn add(&mut self, tab: Tab) {
+		self.cycle = None;
 		self.tabs.push(tab);
+		self.refresh_display_names();
 		self.set_active_idx(self.tabs.len() - 1);
 	}
 
-	/// You might want to consider dropping this on a seperate thread ([`crate::util::drop_on_separate_thread`])
+	/// Adds a new tab named by [`TabManager::new_tab_name`]
+	pub fn add_empty(&mut self, root: NewTabRoot, window_dims: PhysicalSize<u32>) {
+		let name = if root == NewTabRoot::Scratch { self.free_name("scratch", "snbt") } else { self.new_tab_name() };
+		self.add(Tab::new_empty_tab(root, &name, window_dims));
+	}
+
+	/// `new.nbt`, or `new2.nbt`, `new3.nbt` and so on if another tab already has that name
 	#[must_use]
-	pub fn remove(&mut self, idx: usize) -> Option<Tab> {
-		let tab = unsafe { self.tabs.get_unchecked_mut(idx) };
-		if tab.history.has_unsaved_changes() && core::mem::replace(&mut tab.last_close_attempt, Timestamp::now()).elapsed() >= Tab::TAB_CLOSE_DOUBLE_CLICK_INTERVAL {
-			return None;
+	pub fn new_tab_name(&self) -> String { self.free_name("new", "nbt") }
+
+	/// `<stem>.<extension>`, or with 2, 3 and so on after `stem` if another tab already has that name
+	fn free_name(&self, stem: &str, extension: &str) -> String {
+		// one more than there are tabs, so at least one of them is free
+		(1..=self.tabs.len() + 1)
+			.map(|n| if n == 1 { format!("{stem}.{extension}") } else { format!("{stem}{n}.{extension}") })
+			.find(|name| self.tabs.iter().all(|tab| tab.path.name() != name))
+			.unwrap_or_default()
+	}
+
+	/// Like [`TabManager::add`], but at `idx` instead of after every other tab
+	pub fn insert(&mut self, idx: usize, tab: Tab) {
+		let idx = idx.min(self.tabs.len());
+		self.cycle = None;
+		self.tabs.insert(idx, tab);
+		if let Some(split) = &mut self.split
+			&& split.other_idx >= idx
+		{
+			split.other_idx += 1;
 		}
+		self.refresh_display_names();
+		self.set_active_idx(idx);
+	}
 
+	/// Like [`TabManager::add`] without switching to it
+	pub fn add_in_background(&mut self, tab: Tab) {
+		self.cycle = None;
+		self.tabs.push(tab);
+		self.refresh_display_names();
+	}
+
+	/// Closes the tab without asking about unsaved changes, see [`crate::workbench::Workbench::close_tab`] for that
+	///
+	/// You might want to consider dropping this on a seperate thread ([`crate::util::drop_on_separate_thread`])
+	#[must_use]
+	pub fn remove(&mut self, idx: usize) -> Tab {
 		let tab = self.tabs.remove(idx);
+		self.held = None;
+		self.cycle = None;
+		// either pane's tab closing leaves only one to show
+		if let Some(split) = &mut self.split {
+			if idx == split.other_idx || idx == self.active_tab_idx {
+				self.split = None;
+			} else if idx < split.other_idx {
+				split.other_idx -= 1;
+			}
+		}
+		self.refresh_display_names();
 		if self.tabs.is_empty() {
 			#[cfg(target_arch = "wasm32")]
 			if let Some(window) = web_sys::window() {
@@ -47,15 +233,308 @@ impl TabManager {
 		if idx <= self.active_tab_idx {
 			self.set_active_idx(self.active_tab_idx.saturating_sub(1));
 		}
-		Some(tab)
+		tab
+	}
+
+	/// Gives each tab whose file has the same name as another's the closest directory telling them apart, like `level.dat — New World`
+	///
+	/// Only does anything once a tab was added, removed or saved somewhere else, so it's cheap to call every frame
+	pub fn refresh_display_names(&mut self) {
+		if self.tabs.len() == self.named_paths.len() && self.tabs.iter().zip(&self.named_paths).all(|(tab, path)| tab.path.path() == path) {
+			return
+		}
+		self.named_paths = self.tabs.iter().map(|tab| tab.path.path().to_path_buf()).collect();
+		let display_names = self
+			.tabs
+			.iter()
+			.enumerate()
+			.map(|(idx, tab)| {
+				let others = self.tabs.iter().enumerate().filter(|&(other_idx, other)| other_idx != idx && other.path.name() == tab.path.name()).map(|(_, other)| other.path.path()).collect::<Vec<_>>();
+				if others.is_empty() {
+					return None
+				}
+				distinguishing_directory(tab.path.path(), &others).map(|directory| format!("{} — {directory}", tab.path.name()))
+			})
+			.collect::<Vec<_>>();
+		for (tab, display_name) in self.tabs.iter_mut().zip(display_names) {
+			tab.display_name = display_name;
+		}
+	}
+
+	/// Moves the tab at `from` to be at `to` once it's been taken out, keeping the same tab active
+	pub fn move_tab(&mut self, from: usize, to: usize) {
+		self.cycle = None;
+		let tab = self.tabs.remove(from);
+		let to = to.min(self.tabs.len());
+		self.tabs.insert(to, tab);
+		let moved = |idx: usize| {
+			if idx == from {
+				to
+			} else {
+				let idx = idx - usize::from(idx > from);
+				idx + usize::from(idx >= to)
+			}
+		};
+		self.active_tab_idx = moved(self.active_tab_idx);
+		if let Some(split) = &mut self.split {
+			split.other_idx = moved(split.other_idx);
+		}
+	}
+
+	/// Shows the tab at `idx` in a pane beside the active one, with the divider in the middle of the window
+	pub fn split(&mut self, idx: usize, window_width: usize) {
+		if idx == self.active_tab_idx || idx >= self.tabs.len() {
+			return
+		}
+		self.split = Some(Split {
+			other_idx: idx,
+			focus_right: false,
+			divider: window_width / 2,
+		});
+	}
+
+	pub const fn unsplit(&mut self) { self.split = None; }
+
+	#[must_use]
+	pub const fn split_view(&self) -> Option<Split> { self.split }
+
+	/// Where the divider is in a window `window_width` wide, kept far enough from both sides for either pane to be usable
+	#[must_use]
+	pub fn divider(&self, window_width: usize) -> Option<usize> {
+		let split = self.split?;
+		Some(split.divider.min(window_width.saturating_sub(Self::MIN_PANE_WIDTH + Self::DIVIDER_WIDTH)).max(Self::MIN_PANE_WIDTH))
+	}
+
+	pub const fn set_divider(&mut self, x: usize) {
+		if let Some(split) = &mut self.split {
+			split.divider = x;
+		}
+	}
+
+	/// Whether `x` is close enough to the divider to grab it
+	#[must_use]
+	pub fn is_on_divider(&self, x: usize, window_width: usize) -> bool { self.divider(window_width).is_some_and(|divider| (divider.saturating_sub(2)..divider + Self::DIVIDER_WIDTH + 2).contains(&x)) }
+
+	/// Where the pane showing the tab at `idx` starts and how wide it is, [`None`] unless the window is split and it's one of the two tabs shown
+	#[must_use]
+	pub fn pane(&self, idx: usize, window_width: usize) -> Option<(usize, usize)> {
+		let split = self.split?;
+		let right = if idx == self.active_tab_idx {
+			split.focus_right
+		} else if idx == split.other_idx {
+			!split.focus_right
+		} else {
+			return None
+		};
+		let divider = self.divider(window_width)?;
+		Some(if right { (divider + Self::DIVIDER_WIDTH, window_width.saturating_sub(divider + Self::DIVIDER_WIDTH)) } else { (0, divider) })
+	}
+
+	/// The tab in the pane at `x`, [`None`] unless the window is split
+	#[must_use]
+	pub fn pane_at(&self, x: usize, window_width: usize) -> Option<usize> {
+		let split = self.split?;
+		[self.active_tab_idx, split.other_idx].into_iter().find(|&idx| self.pane(idx, window_width).is_some_and(|(start, width)| (start..start + width).contains(&x)))
+	}
+
+	/// Gives the tabs in a split the size of their pane, and every other tab the whole window
+	///
+	/// Only tells the tabs whose size changed, so it's cheap to call every frame to catch the split changing
+	pub fn refresh_window_dims(&mut self, window_dims: PhysicalSize<u32>) {
+		for idx in 0..self.tabs.len() {
+			let width = self.pane(idx, window_dims.width as usize).map_or(window_dims.width, |(_, width)| width as u32);
+			let window_dims = PhysicalSize::new(width, window_dims.height);
+			let tab = &mut self.tabs[idx];
+			if tab.window_dims != window_dims {
+				tab.set_window_dims(window_dims);
+			}
+		}
 	}
 
+	/// Where the tab at `idx` starts along the tab strip, not counting its scroll
+	#[must_use]
+	pub fn tab_start(&self, idx: usize) -> usize { Self::STRIP_START + self.tabs[..idx].iter().map(|tab| tab.strip_width() + Self::STRIP_GAP).sum::<usize>() }
+
+	#[must_use]
+	pub const fn held(&self) -> Option<HeldTab> { self.held }
+
+	/// The held tab, once it's actually being dragged
+	#[must_use]
+	pub fn dragged(&self) -> Option<HeldTab> { self.held.filter(|held| held.dragging) }
+
+	/// Starts dragging the tab at `idx` from `x` along the tab strip, which only becomes a drag once it's moved a little
+	pub fn hold(&mut self, idx: usize, x: usize) {
+		self.held = Some(HeldTab {
+			idx,
+			grab_offset: x.saturating_sub(self.tab_start(idx)),
+			origin_x: x,
+			dragging: false,
+		});
+	}
+
+	pub fn drag(&mut self, x: usize) {
+		if let Some(held) = &mut self.held
+			&& x.abs_diff(held.origin_x) >= Self::DRAG_THRESHOLD
+		{
+			held.dragging = true;
+		}
+	}
+
+	/// Where the held tab would go if it were dropped with the cursor at `x` along the tab strip, as an index into the tabs other than it
+	#[must_use]
+	pub fn drop_idx(&self, x: usize) -> Option<usize> {
+		let held = self.dragged()?;
+		let center = x.saturating_sub(held.grab_offset) + self.tabs[held.idx].strip_width() / 2;
+		let mut start = Self::STRIP_START;
+		let mut drop_idx = 0;
+		for tab in self.tabs.iter().enumerate().filter(|&(idx, _)| idx != held.idx).map(|(_, tab)| tab) {
+			let width = tab.strip_width();
+			if center < start + width / 2 {
+				break
+			}
+			start += width + Self::STRIP_GAP;
+			drop_idx += 1;
+		}
+		Some(drop_idx)
+	}
+
+	/// Moves the held tab to where it was dragged, or leaves it where it was if it was dropped off the tab strip (`x` is [`None`])
+	///
+	/// Returns whether it was moved
+	pub fn drop_held(&mut self, x: Option<usize>) -> bool {
+		let drop_idx = x.and_then(|x| self.drop_idx(x));
+		let Some(held) = self.held.take() else { return false };
+		let Some(drop_idx) = drop_idx else { return false };
+		if drop_idx == held.idx {
+			return false
+		}
+		self.move_tab(held.idx, drop_idx);
+		true
+	}
+
+	#[must_use]
+	pub fn get(&self, idx: usize) -> Option<&Tab> { self.tabs.get(idx) }
+
+	#[must_use]
+	pub fn get_mut(&mut self, idx: usize) -> Option<&mut Tab> { self.tabs.get_mut(idx) }
+
 	pub fn iter(&self) -> std::slice::Iter<'_, Tab> { self.tabs.iter() }
 
 	pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Tab> { self.tabs.iter_mut() }
 
 	#[must_use]
 	pub fn active_tab_idx(&self) -> usize { self.active_tab_idx }
+
+	#[must_use]
+	pub fn len(&self) -> usize { self.tabs.len() }
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool { self.tabs.is_empty() }
+
+	/// Like [`Tab::confirm_close`] for each of the tabs at `indices`, but asking only once for all of them when more than one has unsaved changes
+	///
+	/// Returns the ones which can be closed
+	///
+	/// # Errors
+	/// * If a dialog couldn't be shown, none of them should be closed
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn confirm_close(&mut self, indices: &[usize], alerts: &mut AlertManager) -> Result<Vec<usize>> {
+		let mut unsaved = Vec::new();
+		for &idx in indices {
+			let tab = &mut self.tabs[idx];
+			// a save still running may be all that's left unsaved
+			tab.finish_save(true, alerts)?;
+			if tab.history.has_unsaved_changes() {
+				unsaved.push(idx);
+			}
+		}
+		if unsaved.len() <= 1 {
+			let mut closable = Vec::with_capacity(indices.len());
+			for &idx in indices {
+				if self.tabs[idx].confirm_close(alerts)? {
+					closable.push(idx);
+				}
+			}
+			return Ok(closable)
+		}
+
+		let names = unsaved.iter().map(|&idx| self.tabs[idx].path.name()).collect::<Vec<_>>().join("\n");
+		// save, discard or cancel
+		if Tab::confirm(format!("{} tabs have unsaved changes:\n\n{names}\n\nSave them before closing?", unsaved.len()))? {
+			for &idx in &unsaved {
+				let tab = &mut self.tabs[idx];
+				let result = tab.save(false, alerts).and_then(|()| tab.finish_save(true, alerts));
+				result.alert_err(alerts);
+			}
+			// unless the save failed or was cancelled
+			return Ok(indices.iter().copied().filter(|&idx| !self.tabs[idx].history.has_unsaved_changes()).collect())
+		}
+		if Tab::confirm(format!("Close {} tabs and discard their unsaved changes?", unsaved.len()))? { Ok(indices.to_vec()) } else { Ok(Vec::new()) }
+	}
+
+	/// Saves every tab with unsaved changes, only asking where to for the ones without a file of their own
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn save_all(&mut self, alerts: &mut AlertManager) -> SaveAllSummary {
+		let mut summary = SaveAllSummary::default();
+		for tab in &mut self.tabs {
+			// a save still running may be all that's left unsaved
+			let result = tab.finish_save(true, alerts).and_then(|()| {
+				if !tab.history.has_unsaved_changes() {
+					return Ok(false)
+				}
+				tab.save(!tab.has_file(), alerts)?;
+				tab.finish_save(true, alerts)?;
+				Ok(true)
+			});
+			match result {
+				Ok(false) => {}
+				// unless the save was cancelled
+				Ok(true) if tab.history.has_unsaved_changes() => summary.skipped += 1,
+				Ok(true) => summary.saved += 1,
+				Err(e) => summary.failed.push(format!("{name} ({cause})", name = tab.path.name(), cause = e.root_cause())),
+			}
+		}
+		summary
+	}
+}
+
+/// The name of the closest directory `path` is in which none of `others` are in at the same depth, like VS Code does
+fn distinguishing_directory(path: &Path, others: &[&Path]) -> Option<String> {
+	fn directory_at(path: &Path, depth: usize) -> Option<&OsStr> { path.ancestors().nth(depth).and_then(Path::file_name) }
+
+	path.ancestors()
+		.enumerate()
+		.skip(1)
+		.filter_map(|(depth, directory)| Some((depth, directory.file_name()?)))
+		.find(|&(depth, name)| others.iter().all(|other| directory_at(other, depth) != Some(name)))
+		.map(|(_, name)| name.to_string_lossy().into_owned())
+}
+
+/// What [`TabManager::save_all`] did, shown as a single notification instead of one per tab
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+pub struct SaveAllSummary {
+	pub saved: usize,
+	/// Of tabs whose Save As dialog was closed without choosing a file
+	pub skipped: usize,
+	pub failed: Vec<String>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Display for SaveAllSummary {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		if self.saved + self.skipped + self.failed.len() == 0 {
+			return write!(f, "Nothing to save")
+		}
+		write!(f, "{} saved", self.saved)?;
+		if self.skipped > 0 {
+			write!(f, ", {} skipped", self.skipped)?;
+		}
+		if !self.failed.is_empty() {
+			write!(f, ", {} failed: {}", self.failed.len(), self.failed.join(", "))?;
+		}
+		Ok(())
+	}
 }
 
 impl<'a> IntoIterator for &'a TabManager {
@@ -71,3 +550,122 @@ impl<'a> IntoIterator for &'a mut TabManager {
 
 	fn into_iter(self) -> Self::IntoIter { self.iter_mut() }
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		elements::element::NbtElement,
+		workbench::tab::{FilePath, NbtFileFormat},
+	};
+
+	fn tab(name: &str) -> Tab { Tab::new(NbtElement::from_str("{}").expect("Valid SNBT").1, FilePath::new(name).expect("Valid path"), NbtFileFormat::Nbt, PhysicalSize::new(1920, 1080)).expect("Valid tab") }
+
+	#[test]
+	fn test_move_tab() {
+		let names = |tabs: &TabManager| tabs.iter().map(|tab| tab.path.name().to_owned()).collect::<Vec<_>>();
+		let mut tabs = TabManager::from_tab(tab("a.nbt"));
+		tabs.add_in_background(tab("b.nbt"));
+		tabs.add_in_background(tab("c.nbt"));
+		tabs.active_tab_idx = 1;
+
+		tabs.move_tab(0, 2);
+		assert_eq!(names(&tabs), ["b.nbt", "c.nbt", "a.nbt"]);
+		assert_eq!(tabs.active_tab_idx(), 0);
+		tabs.move_tab(0, 1);
+		assert_eq!(names(&tabs), ["c.nbt", "b.nbt", "a.nbt"]);
+		assert_eq!(tabs.active_tab_idx(), 1);
+		tabs.move_tab(2, 0);
+		assert_eq!(names(&tabs), ["a.nbt", "c.nbt", "b.nbt"]);
+		assert_eq!(tabs.active_tab_idx(), 2);
+	}
+
+	#[test]
+	fn test_split() {
+		let mut tabs = TabManager::from_tab(tab("a.nbt"));
+		tabs.add_in_background(tab("b.nbt"));
+		tabs.add_in_background(tab("c.nbt"));
+		tabs.split(2, 800);
+		assert_eq!(tabs.pane(0, 800), Some((0, 400)));
+		assert_eq!(tabs.pane(2, 800), Some((402, 398)));
+		assert_eq!(tabs.pane(1, 800), None);
+
+		// switching to the other pane's tab focuses it
+		tabs.set_active_idx(2);
+		assert_eq!(tabs.split_view().map(|split| (split.other_idx, split.focus_right)), Some((0, true)));
+		assert_eq!(tabs.pane_at(500, 800), Some(2));
+
+		tabs.move_tab(0, 2);
+		assert_eq!(tabs.split_view().map(|split| split.other_idx), Some(2));
+		assert_eq!(tabs.active_tab_idx(), 1);
+
+		// closing the other pane's tab collapses the split
+		let _ = tabs.remove(2);
+		assert!(tabs.split_view().is_none());
+	}
+
+	#[test]
+	fn test_new_tab_name() {
+		let mut tabs = TabManager::without_tab();
+		assert_eq!(tabs.new_tab_name(), "new.nbt");
+		tabs.add_empty(NewTabRoot::Compound, PhysicalSize::new(1920, 1080));
+		tabs.add_empty(NewTabRoot::Region, PhysicalSize::new(1920, 1080));
+		tabs.add_empty(NewTabRoot::List, PhysicalSize::new(1920, 1080));
+		assert_eq!(tabs.iter().map(|tab| tab.path.name()).collect::<Vec<_>>(), ["new.nbt", "new2.nbt", "new3.nbt"]);
+		assert_eq!(tabs.iter().map(|tab| tab.format).collect::<Vec<_>>(), [NbtFileFormat::Nbt, NbtFileFormat::Mca, NbtFileFormat::Nbt]);
+
+		// a name freed up by closing its tab is used again
+		let _ = tabs.remove(0);
+		assert_eq!(tabs.new_tab_name(), "new.nbt");
+	}
+
+	#[test]
+	fn test_display_names() {
+		let mut tabs = TabManager::from_tab(tab("saves/First/level.dat"));
+		tabs.add_in_background(tab("saves/Second/level.dat"));
+		tabs.add_in_background(tab("saves/First/region/r.0.0.mca"));
+		tabs.add_in_background(tab("saves/Second/region/r.0.0.mca"));
+		tabs.add_in_background(tab("saves/First/data/raids.dat"));
+		assert_eq!(
+			tabs.iter().map(Tab::display_name).collect::<Vec<_>>(),
+			["level.dat — First", "level.dat — Second", "r.0.0.mca — First", "r.0.0.mca — Second", "raids.dat"]
+		);
+
+		let _ = tabs.remove(1);
+		assert_eq!(tabs.iter().next().map(Tab::display_name), Some("level.dat"));
+	}
+
+	#[test]
+	fn test_cycle() {
+		let mut tabs = TabManager::from_tab(tab("a.nbt"));
+		tabs.add(tab("b.nbt"));
+		tabs.add(tab("c.nbt"));
+		tabs.set_active_idx(0);
+
+		// most recently used first, so back and forth between the last two
+		tabs.cycle(false);
+		assert_eq!(tabs.active_tab_idx(), 2);
+		tabs.finish_cycle();
+		tabs.cycle(false);
+		assert_eq!(tabs.active_tab_idx(), 0);
+		tabs.cycle(false);
+		assert_eq!(tabs.active_tab_idx(), 1);
+		tabs.cycle(true);
+		assert_eq!(tabs.active_tab_idx(), 0);
+		tabs.finish_cycle();
+		assert_eq!(tabs.cycle_order(), None);
+		tabs.cycle(true);
+		assert_eq!(tabs.active_tab_idx(), 1);
+	}
+
+	#[test]
+	fn test_save_all_summary() {
+		assert_eq!(SaveAllSummary::default().to_string(), "Nothing to save");
+		let summary = SaveAllSummary {
+			saved: 4,
+			skipped: 0,
+			failed: vec!["level.dat (Permission denied)".to_owned()],
+		};
+		assert_eq!(summary.to_string(), "4 saved, 1 failed: level.dat (Permission denied)");
+	}
+}
@@ -1,79 +1,158 @@
 use std::{
+	borrow::Cow,
 	ffi::OsStr,
 	fmt::Display,
-	io::Read,
+	io::{Read, Write},
 	path::{Path, PathBuf},
+	sync::Arc,
 	time::Duration,
 };
 
-use anyhow::{Context, Result, anyhow, ensure};
+use anyhow::{Context, Result, anyhow, bail, ensure};
 use compact_str::CompactString;
 use flate2::Compression;
+use ruzstd::decoding::StreamingDecoder;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use winit::dpi::PhysicalSize;
 use zune_inflate::DeflateDecoder;
 
 use crate::{
+	config,
 	elements::{
 		ComplexNbtElementVariant, NbtElementVariant,
 		array::{NbtByteArray, NbtIntArray, NbtLongArray},
 		byte::NbtByte,
 		chunk::NbtChunk,
 		compound::NbtCompound,
+		diff::{self, Difference},
 		double::NbtDouble,
 		element::NbtElement,
 		float::NbtFloat,
 		int::NbtInt,
 		list::NbtList,
 		long::NbtLong,
+		number_base::NumberBase,
 		region::NbtRegion,
+		region_heatmap::RegionHeatmap,
+		region_integrity::{self, RegionIssue},
 		short::NbtShort,
+		snbt_error::SnbtError,
 		string::NbtString,
 	},
+	action_result::ActionResult,
 	history::{WorkbenchAction, manager::HistoryMananger},
+	mutable_indices,
 	render::{
 		RenderContext,
 		assets::{
-			BASE_Z, CONNECTION_UV, FROM_CLIPBOARD_GHOST_UV, FROM_CLIPBOARD_UV, GZIP_FILE_TYPE_UV, HEADER_SIZE, HELD_SCROLLBAR_UV, JUST_OVERLAPPING_BASE_Z, LINE_NUMBER_SEPARATOR_UV, LITTLE_ENDIAN_HEADER_NBT_FILE_TYPE_UV,
-			LITTLE_ENDIAN_NBT_FILE_TYPE_UV, MCA_FILE_TYPE_UV, NBT_FILE_TYPE_UV, SCROLLBAR_Z, SNBT_FILE_TYPE_UV, STEAL_ANIMATION_OVERLAY_UV, UNHELD_SCROLLBAR_UV, ZLIB_FILE_TYPE_UV, ZOffset,
+			BASE_Z, CONNECTION_UV, FROM_CLIPBOARD_GHOST_UV, FROM_CLIPBOARD_UV, GZIP_FILE_TYPE_UV, HEADER_SIZE, HELD_SCROLLBAR_UV, HOVERED_GRID_CHUNK_Z, JUST_OVERLAPPING_BASE_Z, LINE_NUMBER_SEPARATOR_UV, LITTLE_ENDIAN_HEADER_NBT_FILE_TYPE_UV,
+			LITTLE_ENDIAN_NBT_FILE_TYPE_UV, LZ4_FILE_TYPE_UV, MCA_FILE_TYPE_UV, NBT_FILE_TYPE_UV, SCROLLBAR_Z, SELECTION_UV, SNBT_FILE_TYPE_UV, STEAL_ANIMATION_OVERLAY_UV, UNHELD_SCROLLBAR_UV, ZLIB_FILE_TYPE_UV, ZOffset, ZSTD_FILE_TYPE_UV,
 		},
 		color::TextColor,
 		vertex_buffer_builder::VertexBufferBuilder,
 		widget::{
-			selected_text::{SaveSelectedTextError, SelectedText, SelectedTextConstructionError},
+			alert::manager::AlertManager,
+			hex_view::HexView,
+			scratch_pad::ScratchPad,
+			string_editor::StringEditor,
+			notification::{Notification, NotificationKind, manager::NotificationManager},
+			selected_text::{SaveSelectedTextError, SelectedText, SelectedTextConstructionError, SetSelectedTextError},
 			text::{TEXT_DOUBLE_CLICK_INTERVAL, get_cursor_left_jump_idx, get_cursor_right_jump_idx},
 		},
 	},
+	serialization::{
+		decoder::{BedrockHeader, Decoder, LittleEndianDecoder},
+		encoder::UncheckedBufWriter,
+		formatter::PrettyFormatter,
+	},
+	tree::{
+		actions::{add::add_element, close::close_element, decode::decode_chunk_at, expand_to_indices::expand_element_to_indices, open::open_element, remove::remove_element, replace::replace_element},
+		indices::{Indices, OwnedIndices},
+		indices_for_true,
+		navigate::NavigationInformation,
+		path::{NbtPath, NbtPathNode},
+	},
 	util::{StrExt, Timestamp, Vec2u, drop_on_separate_thread},
 	workbench::{
-		marked_line::MarkedLines,
+		boolean_overrides::BooleanOverrides,
+		marked_line::{MarkedLine, MarkedLines},
+		selection::Selection,
+		tab::loading::LoadProgress,
 		FileUpdateSubscription, HeldEntry,
 	},
 };
+#[cfg(not(target_arch = "wasm32"))]
+use crate::workbench::tab::watcher::{FileStamp, FileWatcher};
 
+pub mod loading;
 pub mod manager;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod session;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod watcher;
 
+#[allow(clippy::struct_excessive_bools)]
 pub struct Tab {
 	pub root: NbtElement,
 	pub path: FilePath,
 	pub format: NbtFileFormat,
+	/// Where the file was downloaded from, it has no file of its own to save to or reload until it's saved as one
+	pub url: Option<String>,
+	/// Every edit is refused while set, but it can still be looked through, searched, bookmarked, copied from and saved
+	pub read_only: bool,
+	/// Made by [`Tab::duplicate`] and not saved as a file of its own yet
+	pub unsaved_copy: bool,
+	/// Whether it was chosen to keep a list at the root of a format which needs a compound there, so it isn't asked again, see [`Tab::format_warning`]
+	pub root_list_confirmed: bool,
+	/// When it was last switched to, counted by [`TabManager`](manager::TabManager) for going through tabs in the order they were used
+	last_active: usize,
+	/// Its name along with the directory telling it apart from other tabs of the same name, see [`TabManager::refresh_display_names`](manager::TabManager::refresh_display_names)
+	display_name: Option<String>,
+	/// Of a tab made by [`Tab::compare`], in the order of their lines
+	pub differences: Vec<Difference>,
 
 	pub history: HistoryMananger,
 
 	pub bookmarks: MarkedLines,
+	/// The true line number of the bookmark last jumped to with [`Tab::jump_to_search_hit`] or [`Tab::jump_to_bookmark`]
+	pub last_search_hit: Option<usize>,
 	pub subscription: Option<FileUpdateSubscription>,
+	#[cfg(not(target_arch = "wasm32"))]
+	pub watcher: Option<FileWatcher>,
+	/// Of the file as this tab last read or wrote it, to tell whether another program has written to it since
+	#[cfg(not(target_arch = "wasm32"))]
+	disk_stamp: Option<FileStamp>,
+	/// Set when the file was written to by another program while there were unsaved changes to lose by reloading it
+	pub changed_on_disk: bool,
+	/// Found in the header of the region file when it was read, see [`region_integrity::check`]
+	pub region_issues: Vec<RegionIssue>,
+	/// Whether [`Tab::region_issues`] are listed in full above the banner telling of them
+	pub region_issues_open: bool,
+	/// What the cells of the grid layout are colored by, set with [`Tab::set_region_heatmap`]
+	pub region_heatmap: RegionHeatmap,
+	/// The chunk last gone to with [`Tab::go_to_chunk`] on the grid layout and when, whose cell flashes for [`Tab::CHUNK_FLASH_DURATION`]
+	pub flashed_chunk: Option<(usize, Timestamp)>,
 	pub selected_text: Option<SelectedText>,
+	pub selection: Selection,
 
 	pub held_entry: Option<HeldEntry>,
 
 	pub freehand_mode: bool,
+	/// How integers are shown in the tree and the value editor
+	pub number_base: NumberBase,
+	pub boolean_overrides: BooleanOverrides,
+	/// Shown in place of the tree while set
+	pub hex_view: Option<HexView>,
+	pub string_editor: Option<StringEditor>,
+	/// The SNBT a scratch tab's tree is parsed from, shown to the left of it
+	pub scratch_pad: Option<ScratchPad>,
 
 	pub scroll: usize,
 	pub horizontal_scroll: usize,
 
 	pub window_dims: PhysicalSize<u32>,
 
-	pub last_close_attempt: Timestamp,
 	// todo: change to own type
 	pub last_selected_text_interaction: (usize, usize, Timestamp),
 	pub last_interaction: Timestamp,
@@ -81,82 +160,346 @@ pub struct Tab {
 	pub last_double_click_interaction: (usize, Timestamp),
 	// todo: refactor to own type with OwnedIndices instead of Vec2u
 	pub steal_animation_data: Option<(Timestamp, Vec2u)>,
+	/// The numbers last nudged with the arrow keys and the history's generation right after, so nudging them again straight away is undone along with it
+	pub last_nudge: Option<(Vec<OwnedIndices>, usize)>,
+	/// The element last asked to be fully expanded while it was too big to without asking again
+	pending_expand: Option<(OwnedIndices, Timestamp)>,
+	#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+	saving: Option<SaveInProgress>,
 }
 
 impl Tab {
+	/// How many rows are left visible above a line that's been jumped to
+	pub const JUMP_ROWS_ABOVE: usize = 3;
+	/// How long the cell of a chunk gone to on the grid layout flashes for
+	pub const CHUNK_FLASH_DURATION: Duration = Duration::from_millis(1500);
 	pub const FILE_TYPE_FILTERS: &'static [(&'static str, &'static [&'static str])] = &[
 		("Uncompressed NBT File", &["nbt"]),
 		("SNBT File", &["snbt"]),
 		("Region File", &["mca", "mcr"]),
 		("Compressed NBT File", &["dat", "dat_old", "dat_new", "dat_mcr", "old", "schem", "schematic", "litematic"]),
+		("Zstandard Compressed NBT File", &["zst"]),
+		("LZ4 Compressed NBT File", &["lz4"]),
 		("Little Endian NBT File", &["nbt", "mcstructure"]),
 		("Little Endian NBT File (With Header)", &["dat"]),
 	];
+	pub const JSON_FILE_TYPE_FILTER: (&'static str, &'static [&'static str]) = ("JSON File", &["json"]);
+	pub const JSON_EXPORT_FILE_TYPE_FILTER: (&'static str, &'static [&'static str]) = ("Export as JSON", &["json"]);
 	pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
-	pub const TAB_CLOSE_DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(2_000);
 	pub const AUTOSAVE_MAXIMUM_LINES: usize = 1_000_000;
+	/// Fully expanding an element with more lines than this within has to be asked for twice
+	pub const EXPAND_WARNING_LINES: usize = 1_000_000;
+	pub const EXPAND_CONFIRM_INTERVAL: Duration = Duration::from_secs(5);
+	/// Of a file opened from a URL
+	pub const MAX_DOWNLOAD_SIZE: u64 = 256 * 1_048_576 /* 256MiB */;
 
 	pub fn new(nbt: NbtElement, path: FilePath, format: NbtFileFormat, window_dims: PhysicalSize<u32>) -> Result<Self> {
 		ensure!(nbt.is_compound() || nbt.is_list(), "Parsed NBT was not a Compound or List");
+		#[cfg(not(target_arch = "wasm32"))]
+		let disk_stamp = FileStamp::of(path.path()).ok();
+		#[cfg(not(target_arch = "wasm32"))]
+		let read_only = std::fs::metadata(path.path()).is_ok_and(|metadata| metadata.permissions().readonly());
+		#[cfg(target_arch = "wasm32")]
+		let read_only = false;
 
 		Ok(Self {
 			root: nbt,
 			path,
 			format,
+			url: None,
+			read_only,
+			unsaved_copy: false,
+			root_list_confirmed: false,
+			last_active: 0,
+			display_name: None,
+			differences: Vec::new(),
 
 			history: HistoryMananger::new(),
 
 			bookmarks: MarkedLines::new(),
+			last_search_hit: None,
 			subscription: None,
+			#[cfg(not(target_arch = "wasm32"))]
+			watcher: None,
+			#[cfg(not(target_arch = "wasm32"))]
+			disk_stamp,
+			changed_on_disk: false,
+			region_issues: Vec::new(),
+			region_issues_open: false,
+			region_heatmap: RegionHeatmap::Off,
+			flashed_chunk: None,
 			selected_text: None,
+			selection: Selection::new(),
 
 			held_entry: None,
 
 			freehand_mode: false,
+			number_base: NumberBase::Decimal,
+			boolean_overrides: BooleanOverrides::new(),
+			hex_view: None,
+			string_editor: None,
+			scratch_pad: None,
 
 			scroll: 0,
 			horizontal_scroll: 0,
 
 			window_dims,
 
-			last_close_attempt: Timestamp::UNIX_EPOCH,
 			last_selected_text_interaction: (0, 0, Timestamp::UNIX_EPOCH),
 			last_interaction: Timestamp::now(),
 			last_double_click_interaction: (0, Timestamp::UNIX_EPOCH),
 			steal_animation_data: None,
+			last_nudge: None,
+			pending_expand: None,
+			#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+			saving: None,
 		})
 	}
 
+	/// A tab for a file downloaded from `url`, named after the last segment of its path
+	pub fn from_url(nbt: NbtElement, url: String, format: NbtFileFormat, window_dims: PhysicalSize<u32>) -> Result<Self> {
+		let mut tab = Self::new(nbt, FilePath::for_url(&url), format, window_dims)?;
+		// a file in the working directory which happens to have the same name isn't this one
+		#[cfg(not(target_arch = "wasm32"))]
+		{
+			tab.disk_stamp = None;
+		}
+		tab.url = Some(url);
+		tab.read_only = true;
+		Ok(tab)
+	}
+
+	/// A copy of the tree and its bookmarks with a history of its own, named `<name> (copy)` and with no file to save to until it's saved as one
+	#[must_use]
+	pub fn duplicate(&self) -> Self {
+		let mut tab = Self::new_empty_tab(NewTabRoot::Compound, self.path.name(), self.window_dims);
+		tab.root = self.root.clone();
+		tab.path = self.path.for_copy();
+		tab.format = self.format;
+		tab.unsaved_copy = true;
+		tab.bookmarks = self.bookmarks.clone();
+		tab.boolean_overrides = self.boolean_overrides.clone();
+		tab.scroll = self.scroll;
+		tab.horizontal_scroll = self.horizontal_scroll;
+		tab
+	}
+
+	/// A read-only tab of `after` with everything only in `before` put back and the differences bookmarked, see [`diff::compare`]
+	#[must_use]
+	pub fn compare(before: &Self, after: &Self) -> Self {
+		// entries within chunks can't be compared until they're decoded
+		let decoded = |tab: &Self| {
+			let mut root = tab.root.clone();
+			root.decode_chunks(&mut MarkedLines::new());
+			root
+		};
+		let (root, differences) = diff::compare(&decoded(before), &decoded(after));
+		let mut tab = Self::new_empty_tab(NewTabRoot::Compound, &format!("{} → {}", before.path.name(), after.path.name()), after.window_dims);
+		tab.bookmarks = diff::bookmarks(&root, &differences);
+		tab.root = root;
+		tab.differences = differences;
+		tab.format = after.format;
+		tab.read_only = true;
+		tab.unsaved_copy = true;
+		tab
+	}
+
+	/// Whether there's no file to save it to or reload it from until it's saved as one, like downloaded and duplicated tabs
 	#[must_use]
-	pub fn new_empty_tab(region: bool, window_dims: PhysicalSize<u32>) -> Self {
+	pub const fn is_detached(&self) -> bool { self.url.is_some() || self.unsaved_copy }
+
+	/// See [`TabManager::new_tab_name`](manager::TabManager::new_tab_name) for a `name` no other tab has
+	#[must_use]
+	pub fn new_empty_tab(root: NewTabRoot, name: &str, window_dims: PhysicalSize<u32>) -> Self {
 		Self {
-			root: if region { NbtElement::Region(NbtRegion::default()) } else { NbtElement::Compound(NbtCompound::default()) },
-			path: FilePath::new("new.nbt").expect("Valid file path"),
-			format: if region { NbtFileFormat::Nbt } else { NbtFileFormat::Mca },
+			root: match root {
+				NewTabRoot::Compound | NewTabRoot::Scratch => NbtElement::Compound(NbtCompound::default()),
+				NewTabRoot::List => NbtElement::List(NbtList::default()),
+				NewTabRoot::Region => NbtElement::Region(NbtRegion::default()),
+			},
+			path: FilePath::new(name).expect("Valid file path"),
+			format: match root {
+				NewTabRoot::Region => NbtFileFormat::Mca,
+				NewTabRoot::Scratch => NbtFileFormat::Snbt,
+				NewTabRoot::Compound | NewTabRoot::List => NbtFileFormat::Nbt,
+			},
+			url: None,
+			read_only: false,
+			// it's saved wherever it's first saved as
+			unsaved_copy: root == NewTabRoot::Scratch,
+			root_list_confirmed: false,
+			last_active: 0,
+			display_name: None,
+			differences: Vec::new(),
 
 			history: HistoryMananger::new(),
 
 			bookmarks: MarkedLines::new(),
+			last_search_hit: None,
 			subscription: None,
+			#[cfg(not(target_arch = "wasm32"))]
+			watcher: None,
+			#[cfg(not(target_arch = "wasm32"))]
+			disk_stamp: None,
+			changed_on_disk: false,
+			region_issues: Vec::new(),
+			region_issues_open: false,
+			region_heatmap: RegionHeatmap::Off,
+			flashed_chunk: None,
 			selected_text: None,
+			selection: Selection::new(),
 
 			held_entry: None,
 
 			freehand_mode: false,
+			number_base: NumberBase::Decimal,
+			boolean_overrides: BooleanOverrides::new(),
+			hex_view: None,
+			string_editor: None,
+			scratch_pad: (root == NewTabRoot::Scratch).then(ScratchPad::new),
 
 			scroll: 0,
 			horizontal_scroll: 0,
 
 			window_dims,
 
-			last_close_attempt: Timestamp::UNIX_EPOCH,
 			last_selected_text_interaction: (0, 0, Timestamp::UNIX_EPOCH),
 			last_interaction: Timestamp::now(),
 			last_double_click_interaction: (0, Timestamp::UNIX_EPOCH),
 			steal_animation_data: None,
+			last_nudge: None,
+			pending_expand: None,
+			#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+			saving: None,
+		}
+	}
+
+	/// Any text being edited is kept first, since it couldn't be afterwards
+	///
+	/// # Errors
+	/// * If an entry is being held, or the text being edited couldn't be saved
+	pub fn set_read_only(&mut self, read_only: bool) -> Result<()> {
+		if read_only {
+			ensure!(self.held_entry.is_none(), "Drop or discard the held entry before making {} read-only", self.path.name());
+			self.save_selected_text()?;
+			self.selected_text = None;
+			// it would write the changes made in the other program to the tab
+			self.subscription = None;
+		}
+		self.read_only = read_only;
+		Ok(())
+	}
+
+	/// Passes unless the tab is read-only, in which case the edit should be dropped, which the notification says
+	pub fn ensure_writable(&self, notifications: &mut NotificationManager) -> ActionResult {
+		if self.read_only {
+			notifications.notify(Notification::new(format!("{} is read-only", self.path.name()), TextColor::White, NotificationKind::ReadOnly));
+			return ActionResult::Failure(())
+		}
+		ActionResult::Pass
+	}
+
+	/// Undoes the last action, going back to the file it was on if it was saved as another since
+	pub fn undo(&mut self) -> Result<()> { self.seek_history(self.history.position().checked_sub(1).context("No actions to undo")?) }
+
+	/// Redoes the last action undone, as with [`Tab::undo`]
+	pub fn redo(&mut self) -> Result<()> { self.seek_history(self.history.position() + 1) }
+
+	/// Undoes or redoes until there are `position` actions left to undo, which leaves it partway if one fails
+	pub fn seek_history(&mut self, position: usize) -> Result<()> {
+		#[cfg(not(target_arch = "wasm32"))]
+		let path = self.path.path().to_path_buf();
+		let mi = mutable_indices!(self);
+		let result = self.history.seek(position, &mut self.root, mi, &mut self.path, &mut self.format, &mut self.held_entry);
+		// it's compared against whichever file it's on now
+		#[cfg(not(target_arch = "wasm32"))]
+		if self.path.path() != path {
+			self.disk_stamp = FileStamp::of(self.path.path()).ok();
+			if self.watcher.is_some() {
+				self.watcher = Some(FileWatcher::new(self.path.path())?);
+			}
+		}
+		result
+	}
+
+	/// Whether the element at `indices` can be fully expanded, which past [`Tab::EXPAND_WARNING_LINES`] lines takes asking again within [`Tab::EXPAND_CONFIRM_INTERVAL`]
+	pub fn confirm_expand(&mut self, indices: &Indices, true_height: usize, notifications: &mut NotificationManager) -> bool {
+		if true_height <= Self::EXPAND_WARNING_LINES {
+			return true
+		}
+		if let Some((pending, timestamp)) = self.pending_expand.take()
+			&& pending.iter().eq(indices.iter())
+			&& timestamp.elapsed() < Self::EXPAND_CONFIRM_INTERVAL
+		{
+			return true
+		}
+		self.pending_expand = Some((indices.to_owned(), Timestamp::now()));
+		notifications.notify(Notification::new(format!("Expanding all {true_height} lines may take a while, do it again to continue"), TextColor::Yellow, NotificationKind::Expand));
+		false
+	}
+
+	/// Moves the rest of the selection to just after the element at `indices` in order, leaving whatever can't go there where it was
+	pub fn move_selection_after(&mut self, indices: OwnedIndices) -> Option<WorkbenchAction> {
+		let mut followers = self.selection.take().into_iter().map(Some).collect::<Vec<_>>();
+		let mut anchor = Some(indices);
+		let mut actions = Vec::new();
+		let mi = mutable_indices!(self);
+		// kept up to date as the others are removed and added
+		mi.temp = core::iter::once(&mut anchor).chain(&mut followers).collect();
+		for idx in 1..mi.temp.len() {
+			let (Some(anchor), Some(follower)) = (mi.temp[0].clone(), mi.temp[idx].take()) else { continue };
+			let Some((_, parent_indices)) = anchor.split_last() else { continue };
+			if follower.encompasses_or_equal(&anchor)
+				|| !self.root.navigate(&follower).is_ok_and(|NavigationInformation { element, .. }| !element.is_chunk() && self.root.navigate(parent_indices).is_ok_and(|parent| parent.element.can_insert(element)))
+			{
+				continue
+			}
+			let Ok(removed) = remove_element(&mut self.root, follower, mi) else { continue };
+			// the anchor moves back if it was after what was removed
+			let Some((anchor_idx, parent_indices)) = mi.temp[0].as_ref().and_then(|anchor| anchor.split_last()).map(|(idx, parent_indices)| (idx, parent_indices.to_owned())) else { continue };
+			let mut destination = parent_indices;
+			destination.push(anchor_idx + 1);
+			// the removal is undone by adding it back, so it needs its own copy
+			let kv = removed.kv.clone();
+			actions.push(removed.into_action());
+			match add_element(&mut self.root, kv, destination, mi) {
+				Ok(added) => {
+					*mi.temp[0] = Some(added.indices.clone());
+					actions.push(added.into_action());
+				}
+				Err(_) => break,
+			}
+		}
+		WorkbenchAction::bulk(actions)
+	}
+
+	/// Of the lock drawn in the tab bar while it's read-only
+	#[must_use]
+	pub const fn read_only_icon_width(&self) -> usize { if self.read_only { 16 } else { 0 } }
+
+	/// What it's called in the tab strip, which is just the file name unless another tab has the same one
+	/// The length of the byte array in the hex view, closing it once that's no longer there to show, such as after undoing adding it
+	pub fn hex_view_len(&mut self) -> Option<usize> {
+		let len = self.hex_view.as_ref()?.array(&self.root).map(NbtByteArray::len);
+		if len.is_none() {
+			self.hex_view = None;
 		}
+		len
+	}
+
+	#[must_use]
+	pub fn display_name(&self) -> &str { self.display_name.as_deref().unwrap_or(self.path.name()) }
+
+	/// Shown in place of the root's key, the file's name, marked as a placeholder for network NBT where the root has no name at all
+	#[must_use]
+	pub fn root_name(&self) -> Cow<'_, str> {
+		if self.format == NbtFileFormat::NetworkNbt { Cow::Owned(format!("{} (unnamed root)", self.path.name())) } else { Cow::Borrowed(self.path.name()) }
 	}
 
+	/// How wide it is along the tab strip, not counting the gap to the next tab
+	#[must_use]
+	pub fn strip_width(&self) -> usize { self.display_name().width() + self.read_only_icon_width() + 48 + 9 }
+
 	pub fn save_selected_text(&mut self) -> Result<(), SaveSelectedTextError> {
 		if let Some(action) = WorkbenchAction::bulk(self.selected_text.iter_mut().map(|text| text.save(&mut self.root, &mut self.path)).collect::<Result<Vec<WorkbenchAction>, SaveSelectedTextError>>()?) {
 			self.history.append(action);
@@ -164,21 +507,96 @@ impl Tab {
 		Ok(())
 	}
 
+	/// Moves every chunk to where its payload says it is and saves it so the file's header is rebuilt, see [`region_integrity::repair`]
+	pub fn repair_region(&mut self, alerts: &mut AlertManager) -> Result<()> {
+		let region = self.root.as_region().context("Only region files can be repaired")?;
+		let repaired = NbtElement::Region(region_integrity::repair(region));
+		self.save_selected_text()?;
+		self.selected_text = None;
+		// the chunks it was within may have moved or gone
+		self.selection.clear();
+		let action = replace_element(&mut self.root, (None, repaired), OwnedIndices::new(), mutable_indices!(self)).context("Failed to replace region")?.into_action();
+		self.history.append(action);
+		self.region_issues.clear();
+		self.region_issues_open = false;
+		self.save(false, alerts)
+	}
+
+	/// Saves the region file straight away, returning how many bytes smaller it is for it, or [`None`] if the save was cancelled at one of its prompts
+	#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+	pub fn compact_region(&mut self, alerts: &mut AlertManager) -> Result<Option<u64>> {
+		ensure!(self.root.is_region(), "Only region files can be compacted");
+		ensure!(!self.is_detached(), "{} has no file of its own to compact yet, save it as one first", self.path.name());
+		let before = std::fs::metadata(self.path.path()).with_context(|| format!("Could not read {}", self.path.name()))?.len();
+		self.save(false, alerts)?;
+		// declining to overwrite the file or closing the save dialog leaves nothing to finish
+		if self.saving.is_none() {
+			return Ok(None)
+		}
+		self.finish_save(true, alerts)?;
+		let after = std::fs::metadata(self.path.path()).with_context(|| format!("Could not read {}", self.path.name()))?.len();
+		Ok(Some(before.saturating_sub(after)))
+	}
+
+	/// Colors the cells of the grid layout by `heatmap`, decoding the chunks for [`RegionHeatmap::DataVersion`] since it's only known for them once they are
+	pub fn set_region_heatmap(&mut self, heatmap: RegionHeatmap) {
+		self.region_heatmap = heatmap;
+		if heatmap == RegionHeatmap::DataVersion && self.root.is_region() {
+			self.root.decode_chunks(&mut self.bookmarks);
+		}
+	}
+
+	/// The format after its own in the order they're cycled through, backwards if `rev`, with a region only going between MCA and SNBT as nothing else can hold it
+	#[must_use]
+	pub fn cycled_format(&self, rev: bool) -> NbtFileFormat {
+		if self.root.is_region() {
+			return if self.format == NbtFileFormat::Mca { NbtFileFormat::Snbt } else { NbtFileFormat::Mca }
+		}
+		if rev { self.format.rev_cycle() } else { self.format.cycle() }
+	}
+
+	/// Switches it to `format`
+	pub fn set_format(&mut self, format: NbtFileFormat) {
+		if self.format != format {
+			self.history.append(WorkbenchAction::Format { format: core::mem::replace(&mut self.format, format) });
+		}
+	}
+
+	/// What's wrong with writing it as `format`, which is worth asking about before it's switched to or saved as
+	#[must_use]
+	pub fn format_warning(&self, format: NbtFileFormat) -> Option<FormatWarning> {
+		if self.format == NbtFileFormat::Mca && format != NbtFileFormat::Mca {
+			Some(FormatWarning::RegionData)
+		} else if self.root.is_list() && format.needs_compound_root() && !self.root_list_confirmed {
+			Some(FormatWarning::RootList)
+		} else {
+			None
+		}
+	}
+
 	#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
-	pub fn save(&mut self, force_dialog: bool) -> Result<()> {
+	pub fn save(&mut self, force_dialog: bool, alerts: &mut AlertManager) -> Result<()> {
+		use crate::render::widget::alert::Alert;
+
+		ensure!(self.saving.is_none(), "{} is still being saved", self.path.name());
 		self.save_selected_text()?;
-		if !force_dialog {
-			std::fs::write(&self.path, self.format.encode(&self.root))?;
-			self.history.on_save();
-			Ok(())
+		// a downloaded or duplicated file has nowhere to be saved to yet
+		if !force_dialog && !self.is_detached() {
+			// overwrite, save as or cancel
+			if self.was_modified_elsewhere() && !Self::confirm(format!("{} was changed by another program since it was opened.\n\nOverwrite those changes?", self.path.name()))? {
+				return if Self::confirm("Save as a different file instead?")? { self.save(true, alerts) } else { Ok(()) }
+			}
+			self.spawn_save(self.path.path().to_path_buf(), None)
 		} else {
 			let initial_index = match self.format {
-				NbtFileFormat::Nbt => 0,
+				NbtFileFormat::Nbt | NbtFileFormat::NetworkNbt => 0,
 				NbtFileFormat::Snbt => 1,
 				NbtFileFormat::Mca => 2,
 				NbtFileFormat::Gzip | NbtFileFormat::Zlib => 3,
-				NbtFileFormat::LittleEndianNbt => 4,
-				NbtFileFormat::LittleEndianHeaderNbt => 5,
+				NbtFileFormat::Zstd => 4,
+				NbtFileFormat::Lz4 => 5,
+				NbtFileFormat::LittleEndianNbt => 6,
+				NbtFileFormat::LittleEndianHeaderNbt(_) => 7,
 			};
 			let dialog = native_dialog::FileDialogBuilder::default()
 				.add_filter(Self::FILE_TYPE_FILTERS[initial_index].0, Self::FILE_TYPE_FILTERS[initial_index].1)
@@ -191,25 +609,224 @@ impl Tab {
 						.filter(|(idx, _)| *idx != initial_index)
 						.map(|(_, x)| x),
 				)
+				.add_filter(Self::JSON_EXPORT_FILE_TYPE_FILTER.0, Self::JSON_EXPORT_FILE_TYPE_FILTER.1)
 				.save_single_file();
 			let Ok(Some(path)) = dialog.show() else { return Ok(()) };
-			std::fs::write(&path, self.format.encode(&self.root))?;
-			self.path.set_path(path)?;
+			if path == self.path.path() && self.was_modified_elsewhere() && !Self::confirm(format!("{} was changed by another program since it was opened.\n\nOverwrite those changes?", self.path.name()))? {
+				return Ok(())
+			}
+			// an export, so the tab stays on its own file
+			if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json")) {
+				if config::get_backup_on_save()
+					&& let Err(e) = Self::backup(&path)
+				{
+					alerts.alert(Alert::warning(e));
+				}
+				crate::util::write_atomic(&path, self.root.to_json(config::get_json_number_policy()).as_bytes())?;
+				if let Some(warning) = crate::serialization::json::non_finite_warning(&self.root) {
+					alerts.alert(Alert::warning(warning));
+				}
+				return Ok(())
+			}
+			// saving as `.mca` converts a McRegion file, and vice versa
+			if let NbtFileFormat::Mca = self.format
+				&& let Some(region) = self.root.as_region_mut()
+			{
+				region.set_mcregion(path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("mcr")));
+			}
+			self.spawn_save(path.clone(), Some(path))
+		}
+	}
+
+	/// Like [`Tab::save`] without any dialogs, so a file another program wrote to is skipped with a warning instead of asking whether to overwrite it
+	#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+	pub fn autosave(&mut self, alerts: &mut AlertManager) -> Result<()> {
+		use crate::render::widget::alert::Alert;
+
+		// saving it would ask where to
+		if self.is_detached() {
+			return Ok(())
+		}
+		if self.was_modified_elsewhere() {
+			// tried again once another interval passes
+			self.last_interaction = Timestamp::now();
+			alerts.alert(Alert::warning(anyhow!("Skipped autosaving {} since another program changed it, save it manually to choose what to keep", self.path.name())));
+			return Ok(())
+		}
+		self.save(false, alerts)
+	}
+
+	/// Whether it was opened from or saved to a file, unlike new and downloaded tabs
+	#[cfg(not(target_arch = "wasm32"))]
+	#[must_use]
+	pub fn has_file(&self) -> bool { self.disk_stamp.is_some() }
+
+	/// Whether another program wrote to the file since this tab last read or wrote it
+	#[cfg(not(target_arch = "wasm32"))]
+	#[must_use]
+	pub fn was_modified_elsewhere(&self) -> bool { self.disk_stamp.is_some_and(|stamp| FileStamp::of(self.path.path()).ok() != Some(stamp)) }
+
+	/// Asks whether to save, discard or keep the unsaved changes before the tab is closed, returning whether it can be
+	#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+	pub fn confirm_close(&mut self, alerts: &mut AlertManager) -> Result<bool> {
+		// a save still running may be all that's left unsaved
+		self.finish_save(true, alerts)?;
+		if !self.history.has_unsaved_changes() {
+			return Ok(true)
+		}
+		// save, discard or cancel
+		if Self::confirm(format!("{} has unsaved changes.\n\nSave them before closing?", self.path.name()))? {
+			self.save(false, alerts)?;
+			self.finish_save(true, alerts)?;
+			// unless the save was cancelled
+			return Ok(!self.history.has_unsaved_changes())
+		}
+		Self::confirm(format!("Close {} and discard its unsaved changes?", self.path.name()))
+	}
+
+	#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+	pub fn confirm(text: impl ToString) -> Result<bool> { Ok(native_dialog::MessageDialogBuilder::default().set_title("NBT Workbench").set_text(text).set_level(native_dialog::MessageLevel::Warning).confirm().show()?) }
+
+	/// Writes a snapshot of the tree on a worker thread, so edits made while it's saving aren't part of it
+	#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+	fn spawn_save(&mut self, path: PathBuf, new_path: Option<PathBuf>) -> Result<()> {
+		let root = self.root.clone();
+		let format = self.format;
+		// a scratch tab saves the text as it was typed, which the tree may not have caught up to
+		let scratch = self.scratch_pad.as_ref().map(|scratch_pad| scratch_pad.value().to_owned());
+		let backup = config::get_backup_on_save();
+		let handle = std::thread::Builder::new()
+			.name(format!("Saving {}", self.path.name()))
+			// encoding is recursive
+			.stack_size(1_048_576 * 64 /* 64MiB */)
+			.spawn(move || Self::save_to(&root, format, scratch.as_deref(), &path, backup))?;
+		self.saving = Some(SaveInProgress {
+			handle,
+			new_path,
+			generation: self.history.generation(),
+			started: Timestamp::now(),
+		});
+		Ok(())
+	}
+
+	/// Applies the result of the background save once it's done, or right away if `block`ing until then
+	#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+	pub fn finish_save(&mut self, block: bool, alerts: &mut AlertManager) -> Result<()> {
+		use crate::render::widget::alert::Alert;
+
+		let Some(saving) = self.saving.take_if(|saving| block || saving.handle.is_finished()) else { return Ok(()) };
+		let (backup, result) = saving.handle.join().map_err(|_| anyhow!("Thread panicked while saving {}", self.path.name()))?;
+		if let Err(e) = backup {
+			alerts.alert(Alert::warning(e));
+		}
+		result?;
+		// moving to the new file is part of what was saved, so it can be undone without leaving it unsaved
+		let saved = self.history.generation() == saving.generation;
+		if let Some(path) = saving.new_path {
+			let old_path = self.path.set_path(path)?;
+			if old_path != self.path.path() {
+				self.history.append(WorkbenchAction::Path { path: old_path });
+			}
+			// it has a file of its own now
+			if self.url.take().is_some() {
+				self.read_only = false;
+			}
+			self.unsaved_copy = false;
+			if self.watcher.is_some() {
+				self.watcher = Some(FileWatcher::new(self.path.path())?);
+			}
+		}
+		if let Some(watcher) = &mut self.watcher {
+			watcher.sync(self.path.path());
+		}
+		self.disk_stamp = FileStamp::of(self.path.path()).ok();
+		if saved {
 			self.history.on_save();
-			Ok(())
 		}
+		Ok(())
+	}
+
+	#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+	#[must_use]
+	pub fn saving_since(&self) -> Option<Timestamp> { self.saving.as_ref().map(|saving| saving.started) }
+
+	#[cfg(target_arch = "wasm32")]
+	#[must_use]
+	pub fn saving_since(&self) -> Option<Timestamp> { None }
+
+	#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+	fn write(root: &NbtElement, format: NbtFileFormat, path: &Path) -> Result<()> {
+		if let NbtFileFormat::Mca = format
+			&& let Some(region) = root.as_region()
+		{
+			crate::util::write_atomic_with(path, |file| {
+				let mut file = std::io::BufWriter::new(file);
+				let mut writer = UncheckedBufWriter::with_sink(&mut file);
+				let externals = region.to_be_bytes_with_external_chunks(&mut writer);
+				writer.finish_sink()?;
+				region.write_external_chunks(path, externals)
+			})?;
+		} else {
+			crate::util::write_atomic_with(path, |file| {
+				let mut file = std::io::BufWriter::new(file);
+				format.encode_to(root, &mut file)?;
+				file.flush()?;
+				Ok(())
+			})?;
+		}
+		Ok(())
+	}
+
+	/// Returns the results of the `backup` and of the write, in that order
+	#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+	fn save_to(root: &NbtElement, format: NbtFileFormat, scratch: Option<&str>, path: &Path, backup: bool) -> (Result<()>, Result<()>) {
+		let backup = if backup { Self::backup(path) } else { Ok(()) };
+		let result = match scratch {
+			Some(scratch) => crate::util::write_atomic(path, scratch.as_bytes()),
+			None => Self::write(root, format, path),
+		};
+		(backup, result)
+	}
+
+	/// Copies the file about to be overwritten to `<name>.bak`, or `<name>.dat_old` for `.dat` files
+	#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+	fn backup(path: &Path) -> Result<()> {
+		if !path.is_file() {
+			return Ok(())
+		}
+		let Some(name) = path.file_name() else { return Ok(()) };
+		let mut backup_name = name.to_owned();
+		backup_name.push(if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("dat")) { "_old" } else { ".bak" });
+		let backup_path = path.with_file_name(backup_name);
+		std::fs::copy(path, &backup_path).with_context(|| format!("Failed to back up {} to {}", path.display(), backup_path.display()))?;
+		Ok(())
 	}
 
 	#[cfg(target_arch = "wasm32")]
-	pub fn save(&mut self, _: bool) -> Result<()> {
+	pub fn save(&mut self, _: bool, _: &mut AlertManager) -> Result<()> {
 		self.save_selected_text()?;
-		let bytes = self.format.encode(&self.root);
+		let bytes = match &self.scratch_pad {
+			Some(scratch_pad) => scratch_pad.value().as_bytes().to_vec(),
+			None => self.format.encode(&self.root),
+		};
 		crate::wasm::save(self.name.as_ref(), bytes);
 		self.history.on_save();
 		Ok(())
 	}
 
 	pub fn render(&self, builder: &mut VertexBufferBuilder, ctx: &mut RenderContext, held: bool, skip_tooltips: bool, steal_delta: f32) {
+		if let Some(hex_view) = &self.hex_view
+			&& hex_view.array(&self.root).is_some()
+		{
+			hex_view.render(builder, &self.root, ctx.mouse);
+			return
+		}
+		if let Some(string_editor) = &self.string_editor {
+			string_editor.render(builder);
+		}
+		if let Some(scratch_pad) = &self.scratch_pad {
+			scratch_pad.render(builder);
+		}
 		let TabConstants { horizontal_scroll, scroll, .. } = self.consts();
 		let horizontal_scroll_before = core::mem::replace(&mut builder.horizontal_scroll, horizontal_scroll);
 		// let start = std::time::Instant::now();
@@ -218,12 +835,44 @@ impl Tab {
 			if remaining_scroll == 0 {
 				builder.draw_texture(ctx.pos() - (16, 0), CONNECTION_UV, (16, 9));
 			}
-			self.root.render(&mut remaining_scroll, builder, Some(&self.path.name()), true, ctx);
+			self.root.render(&mut remaining_scroll, builder, Some(&self.root_name()), true, ctx);
 		}
 		// println!("Tree Only: {}ms", start.elapsed().as_millis_f64());
+		if !self.differences.is_empty() && self.root.as_region().is_none_or(|region| !region.is_grid_layout()) {
+			ctx.render_differences(builder, &self.differences, skip_tooltips);
+		}
+		if !self.selection.is_empty() && self.root.as_region().is_none_or(|region| !region.is_grid_layout()) {
+			ctx.render_selection(builder, &self.selection.true_line_ranges(&self.root));
+		}
 		builder.color = TextColor::White.to_raw();
-		if self.root.as_region().is_some_and(|region| region.is_grid_layout()) {
+		if let Some(region) = self.root.as_region()
+			&& region.is_grid_layout()
+		{
 			ctx.render_grid_line_numbers(builder, &self.bookmarks);
+			if region.is_open() && self.region_heatmap != RegionHeatmap::Off {
+				// right of the grid, staying in view as it's scrolled down
+				self.region_heatmap.render_legend(builder, Vec2u::new(ctx.left_margin() + 32 + 32 * 16 + 16, HEADER_SIZE + 16), self.region_heatmap.range(region));
+			}
+			if region.is_open()
+				&& let Some((idx, since)) = self.flashed_chunk
+				&& let elapsed = since.elapsed()
+				&& elapsed < Self::CHUNK_FLASH_DURATION
+				&& elapsed.as_millis() / 250 % 2 == 0
+				&& let Some(pos) = ctx.grid_chunk_pos(builder, idx)
+			{
+				builder.draw_texture_z(pos, HOVERED_GRID_CHUNK_Z, SELECTION_UV, (16, 16));
+			}
+			if region.is_open()
+				&& !skip_tooltips
+				&& let Some((idx, pos)) = ctx.grid_chunk_at(builder)
+				&& let Some(chunk) = region.chunks[idx].as_chunk()
+			{
+				builder.draw_texture_z(pos, HOVERED_GRID_CHUNK_Z, SELECTION_UV, (16, 16));
+				let lines = chunk.grid_tooltip((idx % 32, idx / 32), NbtRegion::world_chunk_pos(self.path.path(), idx));
+				builder.color = TextColor::White.to_raw();
+				// tooltips aren't scrolled sideways with the tree
+				builder.draw_tooltip(&lines.iter().map(String::as_str).collect::<Vec<_>>(), pos - (builder.horizontal_scroll, 0), false);
+			}
 		} else {
 			ctx.render_line_numbers(builder, &self.bookmarks);
 		}
@@ -252,11 +901,26 @@ impl Tab {
 			ctx.render_scrollbar_bookmarks(builder, &self.bookmarks, &self.root);
 		}
 
+		if steal_delta > 0.0 {
+			let y = ((ctx.mouse.y - HEADER_SIZE) & !15) + HEADER_SIZE;
+			let height = (16.0 * steal_delta).round() as usize;
+			builder.draw_texture_region_z(
+				(ctx.left_margin() - 2, y + (16 - height)),
+				JUST_OVERLAPPING_BASE_Z,
+				STEAL_ANIMATION_OVERLAY_UV,
+				(builder.window_width() + 2 - ctx.left_margin(), height),
+				(16, 16),
+			);
+		}
+	}
+
+	/// The elements which can be held to be dropped into the tree, along the header rather than in the tab's pane of a split
+	pub fn render_toolbar(&self, builder: &mut VertexBufferBuilder, mouse: Vec2u, skip_tooltips: bool) {
 		// shifted one left to center between clipboard and freehand
 		builder.draw_texture_region_z((260, 22), BASE_Z, LINE_NUMBER_SEPARATOR_UV, (2, 23), (2, 16));
 
 		{
-			let mx = ((24..46).contains(&ctx.mouse.y) && ctx.mouse.x >= 16 + 16 + 4).then(|| (ctx.mouse.x - (16 + 16 + 4)) & !15);
+			let mx = ((24..46).contains(&mouse.y) && mouse.x >= 16 + 16 + 4).then(|| (mouse.x - (16 + 16 + 4)) & !15);
 			for (idx, (selected, unselected, name)) in [
 				(NbtByte::UV, NbtByte::GHOST_UV, "Byte (1)"),
 				(NbtShort::UV, NbtShort::GHOST_UV, "Short (2)"),
@@ -304,18 +968,6 @@ impl Tab {
 				builder.draw_texture((208 + 16 + 16 + 4, 26), uv, (16, 16));
 			}
 		}
-
-		if steal_delta > 0.0 {
-			let y = ((ctx.mouse.y - HEADER_SIZE) & !15) + HEADER_SIZE;
-			let height = (16.0 * steal_delta).round() as usize;
-			builder.draw_texture_region_z(
-				(ctx.left_margin() - 2, y + (16 - height)),
-				JUST_OVERLAPPING_BASE_Z,
-				STEAL_ANIMATION_OVERLAY_UV,
-				(builder.window_width() + 2 - ctx.left_margin(), height),
-				(16, 16),
-			);
-		}
 	}
 
 	pub fn draw_icon(&self, builder: &mut VertexBufferBuilder, pos: impl Into<Vec2u>, z: ZOffset) {
@@ -328,34 +980,68 @@ impl Tab {
 		}
 	}
 
-	pub fn set_selected_text_with_doubleclick(&mut self, result: Result<SelectedText, SelectedTextConstructionError>) -> Result<(), SelectedTextConstructionError> {
+	/// Selects the text from `result`, where clicking the same text again selects the word under the cursor, then the whole value.
+	/// The previously selected text is saved first.
+	///
+	/// # Errors
+	/// * If the previously selected text couldn't be saved, the new text is still selected
+	/// * If the new text couldn't be constructed, in which case nothing is left selected
+	pub fn set_selected_text_with_doubleclick(&mut self, result: Result<SelectedText, SelectedTextConstructionError>) -> Result<(), SetSelectedTextError> {
 		let now = Timestamp::now();
+		let saved = match self.selected_text.take() {
+			Some(text) if text.editable => text.save(&mut self.root, &mut self.path).map(|action| self.history.append(action)),
+			_ => Ok(()),
+		};
 		match result {
 			Ok(mut text) => {
 				let (old_y, times_clicked, timestamp) = core::mem::replace(&mut self.last_selected_text_interaction, (text.y, 0, now));
 				if timestamp.elapsed() <= TEXT_DOUBLE_CLICK_INTERVAL && old_y == text.y && !text.value.is_empty() {
 					self.last_selected_text_interaction = (text.y, times_clicked + 1, now);
-					// the previous click count was divisible by 1
-					let (left, right) = if times_clicked % 2 == 1 {
-						(0, text.value.len())
-					} else {
-						(get_cursor_left_jump_idx(text.cursor, text.value.as_bytes()), get_cursor_right_jump_idx(text.cursor, text.value.as_bytes()))
-					};
-					if right > left {
-						text.selection = Some(left);
-					}
-					text.cursor = right;
+					select_for_clicks(&mut text, times_clicked);
 				}
-				Ok(())
+				self.selected_text = Some(text);
+				self.refresh_selected_text_horizontal_scroll();
+				Ok(saved?)
 			}
 			Err(e) => {
-				self.selected_text = None;
 				self.last_selected_text_interaction = (0, 0, Timestamp::UNIX_EPOCH);
-				Err(e)
+				Err(e.into())
 			}
 		}
 	}
 
+	/// Moves the cursor of the selected text to where `mouse` is over its value, selecting up to there with `extend`, and lets it be dragged from there
+	///
+	/// Clicking it again soon after selects the word under the cursor, and once more the whole value, same as when it was first selected
+	pub fn click_selected_text(&mut self, mouse: Vec2u, extend: bool) -> bool {
+		let TabConstants { left_margin, scroll, horizontal_scroll, .. } = self.consts();
+		let Some(text) = &mut self.selected_text else { return false };
+		let x = mouse.x + horizontal_scroll;
+		let value_x = text.value_x(left_margin);
+		if !(text.y..text.y + 16).contains(&(mouse.y + scroll)) || !(value_x..=value_x + text.value.width()).contains(&x) {
+			return false
+		}
+		let now = Timestamp::now();
+		let cursor = text.cursor_idx_at(x, left_margin);
+		let (old_y, times_clicked, timestamp) = core::mem::replace(&mut self.last_selected_text_interaction, (text.y, 0, now));
+		if extend {
+			text.selection = text.selection.or(Some(text.cursor)).filter(|&selection| selection != cursor);
+			text.cursor = cursor;
+		} else if timestamp.elapsed() <= TEXT_DOUBLE_CLICK_INTERVAL && old_y == text.y && !text.value.is_empty() {
+			self.last_selected_text_interaction = (text.y, times_clicked + 1, now);
+			text.cursor = cursor;
+			text.selection = None;
+			select_for_clicks(text, times_clicked);
+		} else {
+			text.cursor = cursor;
+			text.selection = None;
+		}
+		text.set_drag_selectable(true);
+		text.interact();
+		self.refresh_selected_text_horizontal_scroll();
+		true
+	}
+
 	pub fn refresh_selected_text_horizontal_scroll(&mut self) {
 		let TabConstants { left_margin, horizontal_scroll, .. } = self.consts();
 
@@ -373,6 +1059,169 @@ impl Tab {
 		}
 	}
 
+	/// Expands every parent of the line at `true_line_number` and scrolls it into view a few rows below the header
+	///
+	/// # Errors
+	/// * If there's no such line or its parents couldn't be expanded
+	pub fn jump_to_true_line(&mut self, true_line_number: usize) -> Result<()> {
+		let indices = indices_for_true(true_line_number, &self.root).ok_or_else(|| anyhow!("There is no line #{true_line_number}"))?;
+		expand_element_to_indices(&mut self.root, &indices, &mut self.bookmarks)?;
+		let line_number = self.root.navigate(&indices)?.line_number;
+		self.modify_scroll(|_| line_number.saturating_sub(Self::JUMP_ROWS_ABOVE) * 16);
+		Ok(())
+	}
+
+	/// Expands every parent of the element at `path`, scrolls it into view a few rows below the header and selects it
+	///
+	/// # Errors
+	/// * If `path` couldn't be parsed or resolved, along with the segment that failed
+	/// * If the element couldn't be expanded to or selected
+	pub fn go_to_path(&mut self, path: &str) -> Result<()> {
+		let path = NbtPath::parse(path)?;
+		// chunks have to be decoded for their keys to be found, though only the one the path goes into when it's picked by its index
+		if self.root.is_region() {
+			match path.nodes() {
+				[NbtPathNode::Index(_), _, ..] => {
+					let chunk = path.prefix(1).resolve(&self.root)?;
+					decode_chunk_at(&mut self.root, &chunk, &mut self.bookmarks)?;
+				}
+				[NbtPathNode::MatchElement(_), ..] => self.root.decode_chunks(&mut self.bookmarks),
+				_ => {}
+			}
+		}
+		let indices = path.resolve(&self.root)?;
+		self.expand_and_select(&indices)
+	}
+
+	/// Expands to the element at `indices`, scrolls it into view a few rows below the header and selects its key
+	fn expand_and_select(&mut self, indices: &Indices) -> Result<()> {
+		expand_element_to_indices(&mut self.root, indices, &mut self.bookmarks)?;
+		let line_number = self.root.navigate(indices)?.line_number;
+		self.modify_scroll(|_| line_number.saturating_sub(Self::JUMP_ROWS_ABOVE) * 16);
+		let consts = self.consts();
+		let x = Indices::end_x_from_depth(indices.len(), consts.left_margin).saturating_sub(consts.horizontal_scroll);
+		let text = SelectedText::for_y(consts, &self.root, &self.path, &self.boolean_overrides, line_number, x, true, None);
+		self.set_selected_text_with_doubleclick(text)?;
+		Ok(())
+	}
+
+	/// The index in this tab's region of the chunk at `(x, z)`, which is its position within the region if both are in `0..32` and its position in the world otherwise
+	///
+	/// # Errors
+	/// * If the position is in the world and in a different region file than this one, with the name of the one it's in, or this file isn't named for where it is
+	pub fn chunk_idx(&self, (x, z): (i32, i32)) -> Result<usize> {
+		let (local_x, local_z) = if (0..32).contains(&x) && (0..32).contains(&z) {
+			(x, z)
+		} else {
+			let expected = (x.div_euclid(32), z.div_euclid(32));
+			match NbtRegion::region_pos(self.path.path()) {
+				Some(region_pos) => ensure!(region_pos == expected, "Chunk {x}, {z} is in r.{}.{}.mca, not {}", expected.0, expected.1, self.path.name()),
+				None => return Err(anyhow!("{} isn't named like r.<x>.<z>.mca, so chunk {x}, {z} can only be found by its position within the region", self.path.name())),
+			}
+			(x.rem_euclid(32), z.rem_euclid(32))
+		};
+		Ok(usize::try_from(local_z * 32 + local_x)?)
+	}
+
+	/// Goes to the chunk at `(x, z)`, see [`Tab::chunk_idx`].
+	///
+	/// On the grid layout its row is scrolled to and its cell flashes, otherwise it's selected like [`Tab::go_to_path`].
+	///
+	/// # Errors
+	/// * If the tab isn't of a region
+	/// * If the position isn't in this region file, see [`Tab::chunk_idx`]
+	/// * If the chunk isn't in the file
+	pub fn go_to_chunk(&mut self, pos: (i32, i32)) -> Result<()> {
+		let idx = self.chunk_idx(pos)?;
+		let Some(region) = self.root.as_region() else { return Err(anyhow!("Only region files have chunks to go to")) };
+		ensure!(region.chunks[idx].as_chunk().is_some_and(NbtChunk::is_loaded), "Chunk {}, {} isn't in {}", idx % 32, idx / 32, self.path.name());
+		if region.is_grid_layout() {
+			expand_element_to_indices(&mut self.root, &OwnedIndices::from([idx]), &mut self.bookmarks)?;
+			// below the region's own row
+			let line_number = idx / 32 + 1;
+			self.modify_scroll(|_| line_number.saturating_sub(Self::JUMP_ROWS_ABOVE) * 16);
+			self.flashed_chunk = Some((idx, Timestamp::now()));
+			Ok(())
+		} else {
+			self.expand_and_select(&OwnedIndices::from([idx]))
+		}
+	}
+
+	/// Collapses everything below the element at `indices` and scrolls it into view a few rows below the header
+	pub fn collapse_to(&mut self, indices: &Indices) -> Result<()> {
+		if !self.root.navigate(indices)?.element.is_primitive() {
+			close_element(&mut self.root, indices, &mut self.bookmarks)?;
+		}
+		let line_number = self.root.navigate(indices)?.line_number;
+		self.modify_scroll(|_| line_number.saturating_sub(Self::JUMP_ROWS_ABOVE) * 16);
+		Ok(())
+	}
+
+	/// Jumps to the bookmark after (or before) the last search hit jumped to, wrapping around at either end.
+	///
+	/// Returns the position of the hit jumped to within the bookmarks and how many there are.
+	///
+	/// # Errors
+	/// * If the hit couldn't be jumped to, see [`Tab::jump_to_true_line`]
+	pub fn jump_to_search_hit(&mut self, forwards: bool) -> Result<Option<(usize, usize)>> {
+		let lines = self.bookmarks.iter().map(|bookmark| bookmark.true_line_number()).collect::<Vec<_>>();
+		let idx = match (self.last_search_hit, forwards) {
+			(Some(last), true) => lines.iter().position(|&line| line > last).unwrap_or(0),
+			(Some(last), false) => lines.iter().rposition(|&line| line < last).unwrap_or(lines.len().wrapping_sub(1)),
+			(None, true) => 0,
+			(None, false) => lines.len().wrapping_sub(1),
+		};
+		let Some(&true_line_number) = lines.get(idx) else { return Ok(None) };
+
+		self.last_search_hit = Some(true_line_number);
+		self.jump_to_true_line(true_line_number)?;
+		Ok(Some((idx, lines.len())))
+	}
+
+	/// Jumps to the bookmark after (or before) the line a jump would have landed on at the current scroll, wrapping around at either end.
+	///
+	/// Returns the position of the bookmark jumped to within the bookmarks and how many there are.
+	///
+	/// # Errors
+	/// * If the bookmark couldn't be jumped to, see [`Tab::jump_to_true_line`]
+	pub fn jump_to_bookmark(&mut self, forwards: bool) -> Result<Option<(usize, usize)>> {
+		let TabConstants { scroll, .. } = self.consts();
+		let visible_lines = scroll / 16..(scroll + self.window_dims.height as usize).saturating_sub(HEADER_SIZE) / 16;
+		// bookmarks near the end can't be scrolled to the usual row, so carry on from the last one jumped to while it's still on screen
+		let last_jumped_to = self
+			.last_search_hit
+			.filter(|&last| indices_for_true(last, &self.root).and_then(|indices| self.root.navigate(&indices).ok()).is_some_and(|info| visible_lines.contains(&info.line_number)));
+		let current = match last_jumped_to {
+			Some(last) => last,
+			// nothing can be scrolled up to, so every bookmark on screen is still ahead
+			None if scroll == 0 && forwards => 0,
+			None => self.root.traverse(scroll / 16 + Self::JUMP_ROWS_ABOVE, None).map_or(usize::MAX, |info| info.true_line_number),
+		};
+
+		let lines = self.bookmarks.iter().map(|bookmark| bookmark.true_line_number()).collect::<Vec<_>>();
+		let idx = if forwards {
+			lines.iter().position(|&line| line > current).unwrap_or(0)
+		} else {
+			lines.iter().rposition(|&line| line < current).unwrap_or(lines.len().wrapping_sub(1))
+		};
+		let Some(&true_line_number) = lines.get(idx) else { return Ok(None) };
+
+		self.last_search_hit = Some(true_line_number);
+		self.jump_to_true_line(true_line_number)?;
+		Ok(Some((idx, lines.len())))
+	}
+
+	/// Scrolls just far enough for the line at `line_number` to be seen, if it isn't already
+	pub fn scroll_line_into_view(&mut self, line_number: usize) {
+		let TabConstants { scroll, .. } = self.consts();
+		let visible_rows = (self.window_dims.height as usize).saturating_sub(HEADER_SIZE) / 16;
+		if line_number * 16 < scroll {
+			self.modify_scroll(|_| line_number * 16);
+		} else if line_number >= scroll / 16 + visible_rows {
+			self.modify_scroll(|_| (line_number + 1).saturating_sub(visible_rows) * 16);
+		}
+	}
+
 	pub fn refresh_scrolls(&mut self) {
 		self.modify_scroll(|x| x);
 		self.modify_horizontal_scroll(|x| x);
@@ -390,12 +1239,16 @@ impl Tab {
 			left_margin: self.left_margin(),
 			scroll: self.scroll(),
 			horizontal_scroll: self.horizontal_scroll(),
+			number_base: self.number_base,
 		}
 	}
 
 	#[deprecated = "Use `Tab::consts`"]
 	#[must_use]
-	pub fn left_margin(&self) -> usize { ((self.root.true_height() + self.held_entry.as_ref().map_or(0, |held_entry| held_entry.kv.1.true_height())).ilog10() as usize + 1) * 8 + 4 + 8 }
+	pub fn left_margin(&self) -> usize {
+		let scratch_pad_width = if self.scratch_pad.is_some() { ScratchPad::width(self.window_dims.width as usize) } else { 0 };
+		scratch_pad_width + ((self.root.true_height() + self.held_entry.as_ref().map_or(0, |held_entry| held_entry.kv.1.true_height())).ilog10() as usize + 1) * 8 + 4 + 8
+	}
 
 	pub fn modify_scroll(&mut self, f: impl FnOnce(usize) -> usize) {
 		self.scroll = f(self.scroll);
@@ -407,7 +1260,7 @@ impl Tab {
 	pub fn scroll(&self) -> usize {
 		let height = self.root.height() * 16 + 32 + 15;
 		let scroll = self.scroll;
-		let max = (height + HEADER_SIZE).saturating_sub(self.window_dims.width as usize);
+		let max = (height + HEADER_SIZE).saturating_sub(self.window_dims.height as usize);
 		scroll.min(max) & !15
 	}
 
@@ -420,7 +1273,7 @@ impl Tab {
 		} else {
 			0
 		};
-		let width = self.root.end_x().max(self.path.name().width()).max(selected_text_width) + 32 + 48;
+		let width = self.root.end_x().max(self.root_name().width()).max(selected_text_width) + 32 + 48;
 		let scroll = self.horizontal_scroll;
 		let max = (width + left_margin).saturating_sub(self.window_dims.width as usize);
 		scroll.min(max)
@@ -435,7 +1288,7 @@ impl Tab {
 	pub fn end_x(&self) -> usize {
 		let TabConstants { left_margin, .. } = self.consts();
 		let selected_text_width = self.selected_text.as_ref().map_or(0, |text| text.end_x(left_margin));
-		let root_width = left_margin + self.root.end_x().max(NbtElement::TOGGLE_WIDTH + NbtElement::ICON_WIDTH + SelectedText::PREFIXING_SPACE_WIDTH + self.path.name().width());
+		let root_width = left_margin + self.root.end_x().max(NbtElement::TOGGLE_WIDTH + NbtElement::ICON_WIDTH + SelectedText::PREFIXING_SPACE_WIDTH + self.root_name().width());
 		usize::max(selected_text_width, root_width)
 	}
 
@@ -471,86 +1324,465 @@ impl Tab {
 		self.modify_horizontal_scroll(|x| x);
 	}
 
-	pub fn parse_raw(path: impl AsRef<Path>, buf: Vec<u8>) -> Result<(NbtElement, NbtFileFormat)> {
+	/// The inconsistencies in the header of `buf` if `path` is a region file, see [`region_integrity::check`]
+	#[must_use]
+	pub fn check_region(path: impl AsRef<Path>, buf: &[u8]) -> Vec<RegionIssue> {
+		if let Some("mca" | "mcr") = path.as_ref().extension().and_then(OsStr::to_str) { region_integrity::check(buf) } else { Vec::new() }
+	}
+
+	pub fn parse_raw(path: impl AsRef<Path>, buf: Vec<u8>) -> Result<(NbtElement, NbtFileFormat)> { Self::parse_raw_with_progress(path, buf, None) }
+
+	/// The file at `path` parsed as `format` rather than whichever format it looks most like, for when [`Self::parse_raw`] guesses wrong
+	pub fn parse_as(path: impl AsRef<Path>, buf: Vec<u8>, format: NbtFileFormat) -> Result<(NbtElement, NbtFileFormat)> {
 		let path = path.as_ref();
-		Ok(if let Some("mca" | "mcr") = path.extension().and_then(OsStr::to_str) {
-			(NbtElement::from_be_mca(buf.as_slice()).context("Failed to parse MCA file")?, NbtFileFormat::Mca)
-		} else if let Some(0x1F8B) = buf.first_chunk::<2>().copied().map(u16::from_be_bytes) {
-			(
-				NbtElement::from_be_file(&DeflateDecoder::new(buf.as_slice()).decode_gzip().context("Failed to decode gzip compressed NBT")?).context("Failed to parse NBT")?,
-				NbtFileFormat::Gzip,
-			)
+		let name = path.file_name().and_then(OsStr::to_str).unwrap_or_default();
+		Ok(match format {
+			NbtFileFormat::Nbt | NbtFileFormat::NetworkNbt => {
+				let (nbt, named) = NbtElement::from_be_file_named(&buf).with_context(|| format!("{name} isn't big-endian NBT"))?;
+				(nbt, NbtFileFormat::big_endian(named))
+			}
+			NbtFileFormat::LittleEndianNbt => {
+				let (nbt, header) = NbtElement::from_le_file(&buf).with_context(|| format!("{name} isn't little-endian NBT"))?;
+				(nbt, NbtFileFormat::little_endian(header))
+			}
+			NbtFileFormat::LittleEndianHeaderNbt(_) => {
+				let (nbt, header) = NbtElement::from_le_file_with_header(&buf).with_context(|| format!("{name} isn't little-endian NBT"))?;
+				(nbt, NbtFileFormat::little_endian(header))
+			}
+			NbtFileFormat::Snbt => (Self::parse_snbt(&buf).with_context(|| format!("{name} isn't SNBT"))?, NbtFileFormat::Snbt),
+			// compressed files and regions are told apart by their magic numbers and extension, which can't be mistaken
+			NbtFileFormat::Gzip | NbtFileFormat::Zlib | NbtFileFormat::Zstd | NbtFileFormat::Lz4 | NbtFileFormat::Mca => Self::parse_raw(path, buf)?,
+		})
+	}
+
+	/// Uncompressed NBT in whichever endianness parses into the more [plausible](Self::is_plausible) tree, big-endian unless `prefer_le`, or [`None`] if it's neither
+	///
+	/// Small little-endian files often parse as valid big-endian too (and the other way around), only into nonsense, so a successful parse alone doesn't settle it.
+	/// `prefer_le` is for `.mcstructure` files and those starting with the Bedrock header, where a successful little-endian parse is taken as is.
+	///
+	/// # Errors
+	/// * If the parse was cancelled
+	fn parse_uncompressed(buf: &[u8], prefer_le: bool, not_cancelled: impl Fn() -> Result<()>) -> Result<Option<(NbtElement, NbtFileFormat)>> {
+		fn be(buf: &[u8]) -> Option<(NbtElement, NbtFileFormat)> {
+			let result = NbtElement::from_be_file_named(buf).context("Tried to parse uncompressed NBT");
+			#[cfg(debug_assertions)]
+			if let Err(e) = &result {
+				crate::error!("{e:?}");
+			}
+			result.ok().map(|(nbt, named)| (nbt, NbtFileFormat::big_endian(named)))
+		}
+
+		fn le(buf: &[u8]) -> Option<(NbtElement, NbtFileFormat)> {
+			let result = NbtElement::from_le_file(buf).context("Tried to parse uncompressed little-endian NBT");
+			#[cfg(debug_assertions)]
+			if let Err(e) = &result {
+				crate::error!("{e:?}");
+			}
+			result.ok().map(|(nbt, header)| (nbt, NbtFileFormat::little_endian(header)))
+		}
+
+		type Parse = fn(&[u8]) -> Option<(NbtElement, NbtFileFormat)>;
+
+		let (first, second): (Parse, Parse) = if prefer_le { (le, be) } else { (be, le) };
+		let first = first(buf);
+		not_cancelled()?;
+		let first = match first {
+			Some(first) if prefer_le || Self::is_plausible(&first.0) => return Ok(Some(first)),
+			first => first,
+		};
+		let second = second(buf);
+		not_cancelled()?;
+		Ok(match (first, second) {
+			(Some(first), Some(second)) => Some(if Self::is_plausible(&second.0) { second } else { first }),
+			(first, second) => first.or(second),
+		})
+	}
+
+	/// Whether `nbt` looks like it was parsed in the endianness it was written in, with a root of a sensible number of entries under short printable keys
+	///
+	/// Lengths read in the wrong endianness have their bytes swapped, so a key of 3 bytes is read as one of 768.
+	#[must_use]
+	fn is_plausible(nbt: &NbtElement) -> bool {
+		const MAX_KEY_LEN: usize = 255;
+		const MAX_ENTRIES: usize = 1 << 16;
+
+		match nbt.children() {
+			Some(Ok(elements)) => elements.len() <= MAX_ENTRIES,
+			Some(Err(entries)) => entries.len() <= MAX_ENTRIES && entries.as_slice().iter().all(|entry| entry.key.len() <= MAX_KEY_LEN && !entry.key.chars().any(char::is_control)),
+			None => false,
+		}
+	}
+
+	/// The chunk in the file at `path`, as exported from a region
+	///
+	/// # Errors
+	/// * If the file isn't in any known format, or is a whole region or anything else but a compound
+	pub fn parse_chunk(path: impl AsRef<Path>, buf: Vec<u8>) -> Result<NbtCompound> {
+		let path = path.as_ref();
+		let name = path.file_name().and_then(OsStr::to_str).unwrap_or_default();
+		let (nbt, format) = Self::parse_raw(path, buf)?;
+		ensure!(format != NbtFileFormat::Mca, "{name} is a whole region, only a single chunk can be imported");
+		let display_name = nbt.display_name();
+		nbt.into_compound().ok_or_else(|| anyhow!("{name} holds a {display_name} rather than a chunk's compound"))
+	}
+
+	/// [`Self::parse_raw`], reporting how far along it is to `progress` where the format allows it
+	///
+	/// # Errors
+	/// * If the file isn't in any known format, or the parse was cancelled
+	pub fn parse_raw_with_progress(path: impl AsRef<Path>, buf: Vec<u8>, progress: Option<Arc<LoadProgress>>) -> Result<(NbtElement, NbtFileFormat)> {
+		let path = path.as_ref();
+		// other formats can't be stopped partway through, so it's checked between decompressing and parsing, and between the guesses at an uncompressed format
+		let not_cancelled = || -> Result<()> {
+			ensure!(!progress.as_deref().is_some_and(LoadProgress::is_cancelled), "Cancelled");
+			Ok(())
+		};
+		Ok(if let Some("mca" | "mcr") = path.extension().and_then(OsStr::to_str) {
+			(NbtElement::from_be_mca_with_progress(buf.as_slice(), Some(path), progress).context("Failed to parse MCA file")?, NbtFileFormat::Mca)
+		} else if let Some("json") = path.extension().and_then(OsStr::to_str) {
+			let json = core::str::from_utf8(&buf).context("JSON file was not valid UTF-8")?;
+			// there's no json format to save back to, so it's saved as snbt
+			(NbtElement::from_json(json).map_err(|idx| anyhow!("Failed to parse JSON (failed at index {})", json.len() - idx))?, NbtFileFormat::Snbt)
+		} else if let Some(0x1F8B) = buf.first_chunk::<2>().copied().map(u16::from_be_bytes) {
+			let decoded = DeflateDecoder::new(buf.as_slice()).decode_gzip().context("Failed to decode gzip compressed NBT")?;
+			not_cancelled()?;
+			(NbtElement::from_be_file(&decoded).context("Failed to parse NBT")?, NbtFileFormat::Gzip)
 		} else if let Some(0x7801 | 0x789C | 0x78DA) = buf.first_chunk::<2>().copied().map(u16::from_be_bytes) {
-			(
-				NbtElement::from_be_file(&DeflateDecoder::new(buf.as_slice()).decode_zlib().context("Failed to decode zlib compressed NBT")?).context("Failed to parse NBT")?,
-				NbtFileFormat::Zlib,
-			)
-		} else if let result = NbtElement::from_be_file(buf.as_slice()).context("Tried to parse uncompressed NBT")
-			&& {
-				#[cfg(debug_assertions)]
-				if result.is_err() {
-					crate::error!("{result:?}");
-				}
-				true
-			} && let Ok(nbt) = result
+			let decoded = DeflateDecoder::new(buf.as_slice()).decode_zlib().context("Failed to decode zlib compressed NBT")?;
+			not_cancelled()?;
+			(NbtElement::from_be_file(&decoded).context("Failed to parse NBT")?, NbtFileFormat::Zlib)
+		} else if let Some(0x28B5_2FFD) = buf.first_chunk::<4>().copied().map(u32::from_be_bytes) {
+			let mut decoded = vec![];
+			StreamingDecoder::new(buf.as_slice())
+				.context("Failed to decode zstd compressed NBT")?
+				.read_to_end(&mut decoded)
+				.context("Failed to decode zstd compressed NBT")?;
+			not_cancelled()?;
+			(NbtElement::from_be_file(&decoded).context("Failed to parse NBT")?, NbtFileFormat::Zstd)
+		} else if let Some(0x184D_2204) = buf.first_chunk::<4>().copied().map(u32::from_le_bytes) {
+			let mut decoded = vec![];
+			lz4_flex::frame::FrameDecoder::new(buf.as_slice()).read_to_end(&mut decoded).context("Failed to decode lz4 compressed NBT")?;
+			not_cancelled()?;
+			(NbtElement::from_be_file(&decoded).context("Failed to parse NBT")?, NbtFileFormat::Lz4)
+		} else if let Some(header) = BedrockHeader::of(&buf)
+			&& header.is_wildly_off(buf.len() - BedrockHeader::LEN)
 		{
-			(nbt, NbtFileFormat::Nbt)
-		} else if let result = NbtElement::from_le_file(buf.as_slice()).context("Tried to parse uncompressed little-endian NBT")
-			&& {
-				#[cfg(debug_assertions)]
-				if result.is_err() {
-					crate::error!("{result:?}");
-				}
-				true
-			} && let Ok((nbt, header)) = result
+			return Err(BedrockHeaderMismatch { declared: header.len, actual: buf.len() - BedrockHeader::LEN }.into())
+		} else if let Some(parsed) = Self::parse_uncompressed(&buf, path.extension().and_then(OsStr::to_str) == Some("mcstructure") || LittleEndianDecoder::new(&buf).has_header(), not_cancelled)? {
+			parsed
+		} else if let Some(uncompressed_len) = buf.first_chunk::<4>().copied().map(u32::from_le_bytes)
+			// lz4 can't compress better than 255:1, anything above that is definitely not a size prefix
+			&& (uncompressed_len as usize) <= buf.len().saturating_mul(255)
+			&& let Ok(decoded) = lz4_flex::decompress_size_prepended(&buf)
+			&& let Ok(nbt) = NbtElement::from_be_file(&decoded).context("Tried to parse lz4 compressed NBT")
 		{
-			(nbt, if header { NbtFileFormat::LittleEndianHeaderNbt } else { NbtFileFormat::LittleEndianNbt })
+			(nbt, NbtFileFormat::Lz4)
+		} else if let Some("snbt") = path.extension().and_then(OsStr::to_str) {
+			(Self::parse_snbt(&buf)?, NbtFileFormat::Snbt)
+		} else if core::str::from_utf8(&buf).is_ok_and(|s| !Self::trim_snbt(s).0.is_empty()) {
+			// text is only ever SNBT by now, so why it isn't is more use than not knowing the file type
+			(Self::parse_snbt(&buf)?, NbtFileFormat::Snbt)
 		} else {
-			(
-				core::str::from_utf8(&buf)
-					.ok()
-					.and_then(|s| NbtElement::from_str(s).ok())
-					.context(anyhow!("Failed to find file type for file {}", path.file_name().unwrap_or(&OsStr::new("")).to_string_lossy()))?
-					.1,
-				NbtFileFormat::Snbt,
-			)
+			bail!("Failed to find file type for file {}", path.file_name().unwrap_or(&OsStr::new("")).to_string_lossy())
 		})
 	}
 
+	/// `snbt` without the byte order mark Windows editors start it with, or the whitespace and nulls some exporters pad it with, along with how many bytes were trimmed from its start
+	fn trim_snbt(snbt: &str) -> (&str, usize) {
+		let padding = |c: char| c == '\u{FEFF}' || c == '\0' || c.is_whitespace();
+		let trimmed = snbt.trim_start_matches(padding);
+		let start = snbt.len() - trimmed.len();
+		(trimmed.trim_end_matches(padding), start)
+	}
+
+	/// The SNBT in `buf`, once [trimmed](Self::trim_snbt)
+	///
+	/// # Errors
+	/// * If it isn't UTF-8 or valid SNBT, with the line it failed on and what was expected there
+	fn parse_snbt(buf: &[u8]) -> Result<NbtElement> {
+		let text = core::str::from_utf8(buf).context("SNBT file was not valid UTF-8")?;
+		let (snbt, start) = Self::trim_snbt(text);
+		NbtElement::from_str(snbt).map(|(_, nbt)| nbt).map_err(|idx| anyhow!("Failed to parse SNBT: {}", SnbtError::of_part(text, start, snbt, idx).diagnostic()))
+	}
+
+	/// Like [`Tab::reload`], asking first if that would discard unsaved changes
+	///
+	/// # Errors
+	/// * If the dialog couldn't be shown or the file couldn't be reloaded
 	#[cfg(not(target_arch = "wasm32"))]
 	pub fn refresh(&mut self) -> Result<()> {
-		if self.history.has_unsaved_changes() && core::mem::replace(&mut self.last_close_attempt, Timestamp::now()).elapsed() > Self::TAB_CLOSE_DOUBLE_CLICK_INTERVAL {
+		ensure!(self.url.is_none(), "{} was downloaded, so there's no file to reload it from", self.path.name());
+		ensure!(!self.unsaved_copy, "{} is a copy, so there's no file to reload it from", self.path.name());
+		if self.history.has_unsaved_changes() && !Self::confirm(format!("{} has unsaved changes.\n\nDiscard them and reload the file?", self.path.name()))? {
+			return Ok(());
+		}
+
+		self.reload()
+	}
+
+	/// Parses the file anew as `format`, for when it was opened as the wrong one, asking first if that would discard unsaved changes
+	///
+	/// # Errors
+	/// * If the dialog couldn't be shown or the file isn't valid in `format`
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn reopen_as(&mut self, format: NbtFileFormat) -> Result<()> {
+		ensure!(self.url.is_none(), "{} was downloaded, so there's no file to reopen it from", self.path.name());
+		ensure!(!self.unsaved_copy, "{} is a copy, so there's no file to reopen it from", self.path.name());
+		if self.history.has_unsaved_changes() && !Self::confirm(format!("{} has unsaved changes.\n\nDiscard them and reopen the file as {format}?", self.path.name()))? {
 			return Ok(());
 		}
 
+		self.reload_as(Some(format))
+	}
+
+	/// Parses the file anew, discarding any unsaved changes
+	///
+	/// # Errors
+	/// * If the file couldn't be read or parsed, the tab is left as it was
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn reload(&mut self) -> Result<()> { self.reload_as(None) }
+
+	/// [`Self::reload`] as `format`, or whichever format the file looks most like
+	#[cfg(not(target_arch = "wasm32"))]
+	fn reload_as(&mut self, format: Option<NbtFileFormat>) -> Result<()> {
+		let disk_stamp = FileStamp::of(self.path.path()).ok();
 		let bytes = std::fs::read(&self.path)?;
-		let (value, format) = Tab::parse_raw(&self.path, bytes)?;
+		let region_issues = Tab::check_region(&self.path, &bytes);
+		let (value, format) = match format {
+			Some(format) => Tab::parse_as(&self.path, bytes, format)?,
+			None => Tab::parse_raw(&self.path, bytes)?,
+		};
+
+		self.replace_root(value);
+		self.format = format;
+		self.changed_on_disk = false;
+		self.region_issues = region_issues;
+		self.region_issues_open = false;
+		// the reloaded chunks haven't been decoded yet
+		self.set_region_heatmap(self.region_heatmap);
+		self.disk_stamp = disk_stamp;
+		if let Some(watcher) = &mut self.watcher {
+			watcher.sync(self.path.path());
+		}
+
+		Ok(())
+	}
 
+	/// Swaps the tree for `value` and starts its history over, keeping what's expanded and bookmarked where it can be found again
+	fn replace_root(&mut self, value: NbtElement) {
+		// the same kind of root can have its elements found again by path, anything else starts over from the top
+		let view = if self.root.is_region() == value.is_region() && self.root.is_list() == value.is_list() { Some(TabView::capture(self)) } else { None };
 		self.bookmarks.clear();
 		self.scroll = 0;
-		self.format = format;
 		let history = core::mem::replace(&mut self.history, HistoryMananger::new());
 		self.selected_text = None;
+		self.selection.clear();
 		self.subscription = None;
-		self.last_close_attempt = Timestamp::UNIX_EPOCH;
 		let root = core::mem::replace(&mut self.root, value);
 		drop_on_separate_thread((root, history));
+		if let Some(view) = view {
+			view.restore(self);
+		}
+	}
 
-		Ok(())
+	/// Counts typing into the scratch pad as unsaved, and takes the tree from its text once it's been left alone long enough after being changed, see [`ScratchPad::parse_if_due`]
+	pub fn reparse_scratch_pad(&mut self) {
+		let Some(scratch_pad) = &mut self.scratch_pad else { return };
+		if scratch_pad.take_edited() {
+			self.history.mark_changed();
+		}
+		if let Some(root) = scratch_pad.parse_if_due() {
+			// the text is what's saved, so a tree parsed anew doesn't make it any less unsaved
+			let unsaved = self.history.has_unsaved_changes();
+			self.replace_root(root);
+			if unsaved {
+				self.history.mark_changed();
+			}
+		}
+	}
+
+	/// Rewrites the scratch pad's text from the tree, for after the tree's been edited
+	pub fn rewrite_scratch_pad(&mut self) {
+		if let Some(scratch_pad) = &mut self.scratch_pad {
+			scratch_pad.rewrite(&self.root);
+		}
 	}
 
 	#[cfg(target_arch = "wasm32")]
 	pub fn refresh(&mut self) -> Result<()> { Ok(()) }
+
+	#[cfg(target_arch = "wasm32")]
+	pub fn reopen_as(&mut self, _format: NbtFileFormat) -> Result<()> { Ok(()) }
+
+	#[cfg(target_arch = "wasm32")]
+	pub fn reload(&mut self) -> Result<()> { Ok(()) }
+
+	/// Starts or stops watching the file for writes made by other programs, returning whether it's now being watched
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn toggle_watching(&mut self) -> Result<bool> {
+		ensure!(self.url.is_none(), "{} was downloaded, so there's no file to watch", self.path.name());
+		ensure!(!self.unsaved_copy, "{} is a copy, so there's no file to watch", self.path.name());
+		self.watcher = match self.watcher.take() {
+			Some(_) => None,
+			None => Some(FileWatcher::new(self.path.path()).with_context(|| format!("Could not watch {}", self.path.name()))?),
+		};
+		self.changed_on_disk = false;
+		Ok(self.watcher.is_some())
+	}
+
+	/// Reloads the file once another program has written to it, unless that would lose unsaved changes, which [`Tab::changed_on_disk`] asks about instead
+	///
+	/// # Errors
+	/// * If the file couldn't be reloaded, see [`Tab::reload`]
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn poll_watcher(&mut self) -> Result<()> {
+		// our own write, which is synced once it's done
+		if self.saving_since().is_some() {
+			return Ok(())
+		}
+		let Some(watcher) = &mut self.watcher else { return Ok(()) };
+		if !watcher.poll(self.path.path()) {
+			return Ok(())
+		}
+		if self.history.has_unsaved_changes() {
+			self.changed_on_disk = true;
+			Ok(())
+		} else {
+			self.reload()
+		}
+	}
+}
+
+/// Selects the word at the cursor of `text` for a second click, and all of it for a third
+fn select_for_clicks(text: &mut SelectedText, times_clicked: usize) {
+	// the previous click count was divisible by 1
+	let (left, right) = if times_clicked % 2 == 1 {
+		(0, text.value.len())
+	} else {
+		(get_cursor_left_jump_idx(text.cursor, &text.value), get_cursor_right_jump_idx(text.cursor, &text.value))
+	};
+	if right > left {
+		text.selection = Some(left);
+	}
+	text.cursor = right;
+}
+
+/// The expanded elements, bookmarks and scroll of a tab, kept by path so they can be found again after [`Tab::refresh`] parses the file anew
+struct TabView {
+	/// In the order they were found from the top, so parents are opened before their children
+	expanded: Vec<Option<NbtPath>>,
+	bookmarks: Vec<Option<NbtPath>>,
+	scroll: usize,
+	horizontal_scroll: usize,
+}
+
+impl TabView {
+	fn capture(tab: &Tab) -> Self {
+		fn collect_expanded(element: &NbtElement, indices: &mut OwnedIndices, expanded: &mut Vec<OwnedIndices>) {
+			if !element.is_open() {
+				return
+			}
+			expanded.push(indices.clone());
+			for (idx, child) in element.values().into_iter().flatten().enumerate() {
+				indices.push(idx);
+				collect_expanded(child, indices, expanded);
+				indices.pop();
+			}
+		}
+
+		// `None` is the root, its path of `{}` wouldn't match a list or region
+		let path = |indices: &Indices| if indices.is_root() { Some(None) } else { NbtPath::from_indices(&tab.root, indices).map(Some) };
+		let mut expanded_indices = vec![];
+		collect_expanded(&tab.root, &mut OwnedIndices::new(), &mut expanded_indices);
+		let TabConstants { scroll, horizontal_scroll, .. } = tab.consts();
+
+		Self {
+			expanded: expanded_indices.iter().filter_map(|indices| path(indices)).collect(),
+			bookmarks: tab.bookmarks.iter().filter_map(|bookmark| path(&indices_for_true(bookmark.true_line_number(), &tab.root)?)).collect(),
+			scroll,
+			horizontal_scroll,
+		}
+	}
+
+	/// Anything whose path no longer resolves in the new tree is left out
+	fn restore(self, tab: &mut Tab) {
+		fn resolve(path: Option<&NbtPath>, root: &NbtElement) -> Option<OwnedIndices> {
+			match path {
+				Some(path) => path.resolve(root).ok(),
+				None => Some(OwnedIndices::new()),
+			}
+		}
+
+		let Self { expanded, bookmarks, scroll, horizontal_scroll } = self;
+		// keys within chunks can't be found until they're decoded, which also shifts the lines of anything after them
+		if tab.root.is_region() && (!bookmarks.is_empty() || expanded.iter().flatten().any(|path| path.nodes().len() > 1)) {
+			tab.root.decode_chunks(&mut tab.bookmarks);
+		}
+
+		for path in &bookmarks {
+			if let Some(indices) = resolve(path.as_ref(), &tab.root)
+				&& let Ok(info) = tab.root.navigate(&indices)
+			{
+				// everything is closed in a freshly parsed tree, opening below moves these to their lines
+				let bookmark = MarkedLine::new(info.true_line_number, 0);
+				let _ = tab.bookmarks.toggle(if indices.is_root() { bookmark } else { bookmark.hidden(0) });
+			}
+		}
+		for path in &expanded {
+			if let Some(indices) = resolve(path.as_ref(), &tab.root) {
+				let _ = open_element(&mut tab.root, &indices, &mut tab.bookmarks);
+			}
+		}
+
+		tab.scroll = scroll;
+		tab.horizontal_scroll = horizontal_scroll;
+		tab.refresh_scrolls();
+	}
 }
 
+/// What a new tab starts out as, see [`Tab::new_empty_tab`]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NewTabRoot {
+	Compound,
+	List,
+	Region,
+	/// A compound parsed from SNBT typed beside it, see [`ScratchPad`]
+	Scratch,
+}
+
+/// Why writing a tab in some format would leave a file the game can't read or lose part of it, see [`Tab::format_warning`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FormatWarning {
+	/// Java Edition only reads files with a compound at the root
+	RootList,
+	/// Only a region file keeps when each chunk was last modified, how it was compressed and the chunks which couldn't be read
+	RegionData,
+}
+
+impl FormatWarning {
+	/// What goes wrong with writing `name` as `format`
+	#[must_use]
+	pub fn message(self, name: &str, format: NbtFileFormat) -> String {
+		match self {
+			Self::RootList => format!("{name} has a List at its root, which Minecraft: Java Edition can't read from a {format} file."),
+			Self::RegionData => format!("{name} is a region, and {format} only keeps the chunks which could be read and where they are in it, not when each was last modified or how it was compressed. It also can't be opened as a region again."),
+		}
+	}
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NbtFileFormat {
 	Nbt,
+	/// The format of packets since 1.20.2, with no name for the root
+	NetworkNbt,
 	Gzip,
 	Zlib,
+	Zstd,
+	Lz4,
 	Snbt,
 	LittleEndianNbt,
-	LittleEndianHeaderNbt,
+	/// With the storage version from its [`BedrockHeader`], which is kept when it's saved
+	LittleEndianHeaderNbt(u32),
 
 	Mca,
 }
@@ -559,11 +1791,14 @@ impl NbtFileFormat {
 	#[must_use]
 	pub const fn cycle(self) -> Self {
 		match self {
-			Self::Nbt => Self::Gzip,
+			Self::Nbt => Self::NetworkNbt,
+			Self::NetworkNbt => Self::Gzip,
 			Self::Gzip => Self::Zlib,
-			Self::Zlib => Self::LittleEndianNbt,
-			Self::LittleEndianNbt => Self::LittleEndianHeaderNbt,
-			Self::LittleEndianHeaderNbt => Self::Snbt,
+			Self::Zlib => Self::Zstd,
+			Self::Zstd => Self::Lz4,
+			Self::Lz4 => Self::LittleEndianNbt,
+			Self::LittleEndianNbt => Self::LittleEndianHeaderNbt(BedrockHeader::DEFAULT_VERSION),
+			Self::LittleEndianHeaderNbt(_) => Self::Snbt,
 			Self::Snbt => Self::Nbt,
 
 			Self::Mca => Self::Mca,
@@ -574,47 +1809,129 @@ impl NbtFileFormat {
 	pub const fn rev_cycle(self) -> Self {
 		match self {
 			Self::Nbt => Self::Snbt,
-			Self::Gzip => Self::Nbt,
+			Self::NetworkNbt => Self::Nbt,
+			Self::Gzip => Self::NetworkNbt,
 			Self::Zlib => Self::Gzip,
-			Self::LittleEndianNbt => Self::Zlib,
-			Self::LittleEndianHeaderNbt => Self::LittleEndianNbt,
-			Self::Snbt => Self::LittleEndianHeaderNbt,
+			Self::Zstd => Self::Zlib,
+			Self::Lz4 => Self::Zstd,
+			Self::LittleEndianNbt => Self::Lz4,
+			Self::LittleEndianHeaderNbt(_) => Self::LittleEndianNbt,
+			Self::Snbt => Self::LittleEndianHeaderNbt(BedrockHeader::DEFAULT_VERSION),
 
 			Self::Mca => Self::Mca,
 		}
 	}
 
+	/// Whether it's a file Java Edition reads, which has to have a compound at its root
+	///
+	/// Network NBT can have anything at its root since 1.20.3, so it doesn't count.
+	#[must_use]
+	pub const fn needs_compound_root(self) -> bool { matches!(self, Self::Nbt | Self::Gzip | Self::Zlib | Self::Zstd | Self::Lz4) }
+
+	/// Uncompressed big-endian NBT, in the network format if its root wasn't `named`
+	#[must_use]
+	pub const fn big_endian(named: bool) -> Self { if named { Self::Nbt } else { Self::NetworkNbt } }
+
+	/// Little-endian NBT, with `header` if it had one
+	#[must_use]
+	pub const fn little_endian(header: Option<BedrockHeader>) -> Self {
+		match header {
+			Some(header) => Self::LittleEndianHeaderNbt(header.version),
+			None => Self::LittleEndianNbt,
+		}
+	}
+
+	/// The format a file saved at `path` is written in, going by its extension as in [`Tab::FILE_TYPE_FILTERS`], with the compressed extensions written as gzip like the game does and `.nbt` as big-endian
 	#[must_use]
-	pub fn encode(self, data: &NbtElement) -> Vec<u8> {
+	pub fn from_path(path: &Path) -> Option<Self> {
+		let extension = path.extension().and_then(OsStr::to_str)?.to_ascii_lowercase();
+		Some(match extension.as_str() {
+			"nbt" => Self::Nbt,
+			"snbt" => Self::Snbt,
+			"mca" | "mcr" => Self::Mca,
+			"zst" => Self::Zstd,
+			"lz4" => Self::Lz4,
+			"mcstructure" => Self::LittleEndianNbt,
+			extension if Tab::FILE_TYPE_FILTERS[3].1.contains(&extension) => Self::Gzip,
+			_ => return None,
+		})
+	}
+
+	#[must_use]
+	pub fn encode(self, data: &NbtElement) -> Vec<u8> { self.encode_with_level(data, config::get_compression_level()) }
+
+	#[must_use]
+	pub fn encode_with_level(self, data: &NbtElement, level: CompressionLevel) -> Vec<u8> {
 		match self {
 			Self::Nbt | Self::Mca => data.to_be_file(),
+			Self::NetworkNbt => data.to_be_network_file(),
 			Self::Gzip => {
 				let mut vec = vec![];
-				let _ = flate2::read::GzEncoder::new(data.to_be_file().as_slice(), Compression::best()).read_to_end(&mut vec);
+				let _ = flate2::read::GzEncoder::new(data.to_be_file().as_slice(), level.into()).read_to_end(&mut vec);
 				vec
 			}
 			Self::Zlib => {
 				let mut vec = vec![];
-				let _ = flate2::read::ZlibEncoder::new(data.to_be_file().as_slice(), Compression::best()).read_to_end(&mut vec);
+				let _ = flate2::read::ZlibEncoder::new(data.to_be_file().as_slice(), level.into()).read_to_end(&mut vec);
 				vec
 			}
-			// Self::Lz4 => lz4_flex::compress(&data.to_be_file()),
-			Self::Snbt => data.to_string().into_bytes(),
-			format @ (Self::LittleEndianNbt | Self::LittleEndianHeaderNbt) => data.to_le_file(format == Self::LittleEndianHeaderNbt),
+			Self::Zstd => ruzstd::encoding::compress_to_vec(data.to_be_file().as_slice(), ruzstd::encoding::CompressionLevel::Fastest),
+			Self::Lz4 => {
+				let mut encoder = lz4_flex::frame::FrameEncoder::new(vec![]);
+				let _ = encoder.write_all(&data.to_be_file());
+				encoder.finish().unwrap_or_default()
+			}
+			Self::Snbt =>
+				if config::get_pretty_snbt() {
+					data.to_pretty_string(PrettyFormatter::DEFAULT_INDENT).into_bytes()
+				} else {
+					data.to_string().into_bytes()
+				},
+			Self::LittleEndianNbt => data.to_le_file(None),
+			Self::LittleEndianHeaderNbt(version) => data.to_le_file(Some(version)),
+		}
+	}
+
+	pub fn encode_to(self, data: &NbtElement, writer: &mut impl Write) -> std::io::Result<()> { self.encode_to_with_level(data, writer, config::get_compression_level()) }
+
+	/// Like [`Self::encode_with_level`], but streams the big-endian bytes through the compressor into `writer` rather than building the whole file (twice when compressed) in memory
+	pub fn encode_to_with_level(self, data: &NbtElement, writer: &mut impl Write, level: CompressionLevel) -> std::io::Result<()> {
+		match self {
+			Self::Nbt | Self::Mca => data.to_be_file_to(writer),
+			Self::NetworkNbt => data.to_be_network_file_to(writer),
+			Self::Gzip => {
+				let mut encoder = flate2::write::GzEncoder::new(writer, level.into());
+				data.to_be_file_to(&mut encoder)?;
+				encoder.try_finish()
+			}
+			Self::Zlib => {
+				let mut encoder = flate2::write::ZlibEncoder::new(writer, level.into());
+				data.to_be_file_to(&mut encoder)?;
+				encoder.try_finish()
+			}
+			// ruzstd panics on a failed write, so the frame is compressed in memory first
+			Self::Zstd => writer.write_all(&ruzstd::encoding::compress_to_vec(data.to_be_file().as_slice(), ruzstd::encoding::CompressionLevel::Fastest)),
+			Self::Lz4 => {
+				let mut encoder = lz4_flex::frame::FrameEncoder::new(writer);
+				data.to_be_file_to(&mut encoder)?;
+				encoder.finish().map(|_| ()).map_err(std::io::Error::other)
+			}
+			Self::Snbt | Self::LittleEndianNbt | Self::LittleEndianHeaderNbt(_) => writer.write_all(&self.encode_with_level(data, level)),
 		}
 	}
 
 	#[must_use]
 	pub const fn uv(self) -> Vec2u {
 		match self {
-			Self::Nbt => NBT_FILE_TYPE_UV,
+			Self::Nbt | Self::NetworkNbt => NBT_FILE_TYPE_UV,
 			Self::Gzip => GZIP_FILE_TYPE_UV,
 			Self::Zlib => ZLIB_FILE_TYPE_UV,
+			Self::Zstd => ZSTD_FILE_TYPE_UV,
 			Self::Snbt => SNBT_FILE_TYPE_UV,
 			Self::Mca => MCA_FILE_TYPE_UV,
 			Self::LittleEndianNbt => LITTLE_ENDIAN_NBT_FILE_TYPE_UV,
-			Self::LittleEndianHeaderNbt => LITTLE_ENDIAN_HEADER_NBT_FILE_TYPE_UV,
-			// Self::Lz4 => Vec2u::new(240, 240),
+			Self::LittleEndianHeaderNbt(_) => LITTLE_ENDIAN_HEADER_NBT_FILE_TYPE_UV,
+			Self::Lz4 => LZ4_FILE_TYPE_UV,
 		}
 	}
 
@@ -622,13 +1939,15 @@ impl NbtFileFormat {
 	pub const fn into_str(self) -> &'static str {
 		match self {
 			Self::Nbt => "Uncompressed",
+			Self::NetworkNbt => "Network NBT (Unnamed Root)",
 			Self::Gzip => "GZip",
 			Self::Zlib => "ZLib",
+			Self::Zstd => "Zstd",
 			Self::Snbt => "SNBT",
 			Self::Mca => "MCA",
-			// Self::Lz4 => "LZ4",
+			Self::Lz4 => "LZ4",
 			Self::LittleEndianNbt => "Little Endian NBT",
-			Self::LittleEndianHeaderNbt => "Little Endian NBT (With Header)",
+			Self::LittleEndianHeaderNbt(_) => "Little Endian NBT (With Header)",
 		}
 	}
 }
@@ -669,17 +1988,20 @@ impl ChunkFileFormat {
 	}
 
 	#[must_use]
-	pub fn encode(self, data: &NbtElement) -> Vec<u8> {
+	pub fn encode(self, data: &NbtElement) -> Vec<u8> { self.encode_with_level(data, config::get_compression_level()) }
+
+	#[must_use]
+	pub fn encode_with_level(self, data: &NbtElement, level: CompressionLevel) -> Vec<u8> {
 		match self {
 			Self::Nbt => data.to_be_file(),
 			Self::Gzip => {
 				let mut vec = vec![];
-				let _ = flate2::read::GzEncoder::new(data.to_be_file().as_slice(), Compression::best()).read_to_end(&mut vec);
+				let _ = flate2::read::GzEncoder::new(data.to_be_file().as_slice(), level.into()).read_to_end(&mut vec);
 				vec
 			}
 			Self::Zlib => {
 				let mut vec = vec![];
-				let _ = flate2::read::ZlibEncoder::new(data.to_be_file().as_slice(), Compression::best()).read_to_end(&mut vec);
+				let _ = flate2::read::ZlibEncoder::new(data.to_be_file().as_slice(), level.into()).read_to_end(&mut vec);
 				vec
 			}
 			Self::Lz4 => lz4_flex::compress(&data.to_be_file()),
@@ -692,8 +2014,7 @@ impl ChunkFileFormat {
 			Self::Nbt => NBT_FILE_TYPE_UV,
 			Self::Gzip => GZIP_FILE_TYPE_UV,
 			Self::Zlib => ZLIB_FILE_TYPE_UV,
-			// todo
-			Self::Lz4 => Vec2u::new(240, 240),
+			Self::Lz4 => LZ4_FILE_TYPE_UV,
 		}
 	}
 
@@ -708,6 +2029,35 @@ impl ChunkFileFormat {
 	}
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CompressionLevel {
+	Fast,
+	/// The level vanilla uses for region files
+	#[default]
+	Default,
+	Best,
+}
+
+impl From<CompressionLevel> for Compression {
+	fn from(level: CompressionLevel) -> Self {
+		match level {
+			CompressionLevel::Fast => Self::fast(),
+			CompressionLevel::Default => Self::default(),
+			CompressionLevel::Best => Self::best(),
+		}
+	}
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+struct SaveInProgress {
+	handle: std::thread::JoinHandle<(Result<()>, Result<()>)>,
+	/// Set when saving as, the tab only moves to the new path once it succeeds
+	new_path: Option<PathBuf>,
+	/// Of the history when the snapshot was taken
+	generation: usize,
+	started: Timestamp,
+}
+
 pub struct FilePath {
 	path: PathBuf,
 	cached_name: CompactString,
@@ -728,6 +2078,31 @@ impl FilePath {
 		})
 	}
 
+	/// Named after the last segment of the URL's path, without its query or fragment
+	#[must_use]
+	pub fn for_url(url: &str) -> Self {
+		let path = url.split(['?', '#']).next().unwrap_or(url);
+		let path = path.split_once("://").map_or(path, |(_, rest)| rest.split_once('/').map_or("", |(_, path)| path));
+		let name = path.rsplit('/').next().map(|name| percent_decode(name).replace(['/', '\\'], "_")).filter(|name| !matches!(name.as_str(), "" | "." | "..")).unwrap_or_else(|| "download".to_owned());
+		Self {
+			cached_name: CompactString::from(name.as_str()),
+			cached_path_str: name.clone(),
+			path: PathBuf::from(name),
+		}
+	}
+
+	/// In the same directory, named `<name> (copy)`
+	#[must_use]
+	pub fn for_copy(&self) -> Self {
+		let name = format!("{} (copy)", self.cached_name);
+		let path = self.path.with_file_name(&name);
+		Self {
+			cached_name: CompactString::from(name),
+			cached_path_str: path.to_string_lossy().into_owned(),
+			path,
+		}
+	}
+
 	#[must_use]
 	pub fn path(&self) -> &Path { &self.path }
 
@@ -751,6 +2126,35 @@ impl FilePath {
 	pub fn name(&self) -> &str { &self.cached_name }
 }
 
+/// `%20` to a space and so on, anything which isn't a valid escape is left as it is
+fn percent_decode(s: &str) -> String {
+	let bytes = s.as_bytes();
+	let mut decoded = Vec::with_capacity(bytes.len());
+	let mut idx = 0;
+	while idx < bytes.len() {
+		if bytes[idx] == b'%'
+			&& let Some(hex) = bytes.get(idx + 1..idx + 3)
+			&& hex.iter().all(u8::is_ascii_hexdigit)
+			&& let Ok(byte) = u8::from_str_radix(&s[idx + 1..idx + 3], 16)
+		{
+			decoded.push(byte);
+			idx += 3;
+		} else {
+			decoded.push(bytes[idx]);
+			idx += 1;
+		}
+	}
+	String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// A file starting with what looks like a [`BedrockHeader`], only with a length [wildly off](BedrockHeader::is_wildly_off) from the NBT after it, which can still be loaded anyway with [`Tab::parse_as`]
+#[derive(Error, Debug)]
+#[error("Its Bedrock header says the NBT after it is {declared} bytes long, but it's {actual} bytes")]
+pub struct BedrockHeaderMismatch {
+	pub declared: u32,
+	pub actual: usize,
+}
+
 #[derive(Error, Debug)]
 pub enum FilePathError {
 	#[error("Path {0:?} has no name")]
@@ -766,4 +2170,517 @@ pub struct TabConstants {
 	pub left_margin: usize,
 	pub scroll: usize,
 	pub horizontal_scroll: usize,
+	pub number_base: NumberBase,
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::Path;
+
+	use winit::dpi::PhysicalSize;
+
+	use super::{BedrockHeaderMismatch, ChunkFileFormat, CompressionLevel, FilePath, FormatWarning, NbtFileFormat, NewTabRoot, Tab, TabView};
+	use crate::{
+		elements::{
+			ComplexNbtElementVariant, NbtElementVariant,
+			byte::NbtByte,
+			chunk::NbtChunk,
+			compound::{CompoundEntry, NbtCompound},
+			element::NbtElement,
+			region::NbtRegion,
+			string::NbtString,
+		},
+		history::WorkbenchAction,
+		mutable_indices,
+		render::{
+			assets::HEADER_SIZE,
+			color::TextColor,
+			widget::{
+				notification::manager::NotificationManager,
+				scratch_pad::ScratchPad,
+				selected_text::{SelectedText, SelectedTextConstructionError},
+			},
+		},
+		serialization::{decoder::BedrockHeader, formatter::PrettyFormatter},
+		tree::{
+			actions::{open::open_element, replace::replace_element},
+			indices::{Indices, OwnedIndices},
+			path::NbtPath,
+		},
+		util::Vec2u,
+		workbench::{HeldEntry, marked_line::MarkedLine},
+	};
+
+	#[test]
+	fn test_zstd_round_trip() {
+		let original = NbtElement::from_be_file(include_bytes!("../../assets/test.nbt")).expect("Included test nbt contains valid data");
+
+		let (gzip, format) = Tab::parse_raw("test.dat", NbtFileFormat::Gzip.encode(&original)).expect("Valid gzip file");
+		assert_eq!(format, NbtFileFormat::Gzip);
+		let (zstd, format) = Tab::parse_raw("test.nbt.zst", NbtFileFormat::Zstd.encode(&gzip)).expect("Valid zstd file");
+		assert_eq!(format, NbtFileFormat::Zstd);
+		let (gzip, format) = Tab::parse_raw("test.dat", NbtFileFormat::Gzip.encode(&zstd)).expect("Valid gzip file");
+		assert_eq!(format, NbtFileFormat::Gzip);
+
+		assert_eq!(gzip, original);
+	}
+
+	#[test]
+	fn test_lz4_round_trip() {
+		let original = NbtElement::from_be_file(include_bytes!("../../assets/test.nbt")).expect("Included test nbt contains valid data");
+
+		let (frame, format) = Tab::parse_raw("test.nbt.lz4", NbtFileFormat::Lz4.encode(&original)).expect("Valid lz4 frame file");
+		assert_eq!(format, NbtFileFormat::Lz4);
+		assert_eq!(frame, original);
+
+		let (block, format) = Tab::parse_raw("test.nbt.lz4", lz4_flex::compress_prepend_size(&original.to_be_file())).expect("Valid lz4 block file");
+		assert_eq!(format, NbtFileFormat::Lz4);
+		assert_eq!(block, original);
+	}
+
+	#[test]
+	fn test_endianness_detection() {
+		// an empty compound is the same either way, so it's only little-endian where that's expected
+		let empty = vec![NbtCompound::ID, 0, 0, 0];
+		assert_eq!(Tab::parse_raw("test.nbt", empty.clone()).expect("Valid NBT").1, NbtFileFormat::Nbt);
+		assert_eq!(Tab::parse_raw("test.mcstructure", empty.clone()).expect("Valid NBT").1, NbtFileFormat::LittleEndianNbt);
+		assert_eq!(Tab::parse_raw("test.nbt", NbtFileFormat::Nbt.encode(&NbtElement::from_be_file(&empty).expect("Valid NBT"))).expect("Valid NBT").1, NbtFileFormat::Nbt);
+
+		// `{a:0b,b:"xx…"}` in little-endian, which reads as a single byte in big-endian under a 256 byte key taking up the rest
+		let mut le = vec![NbtCompound::ID, 0, 0, NbtByte::ID, 1, 0, b'a', 0, NbtString::ID, 1, 0, b'b', 249, 0];
+		le.extend([b'x'; 249]);
+		le.push(0);
+		assert!(NbtElement::from_be_file(&le).is_ok());
+		let (nbt, format) = Tab::parse_raw("test.dat", le.clone()).expect("Valid little-endian NBT");
+		assert_eq!(format, NbtFileFormat::LittleEndianNbt);
+		assert_eq!(nbt.len(), Some(2));
+		assert_eq!(Tab::parse_as("test.dat", le, NbtFileFormat::Nbt).expect("Valid big-endian NBT").0.len(), Some(1));
+
+		let (level, format) = Tab::parse_raw("level.dat", NbtFileFormat::LittleEndianHeaderNbt(BedrockHeader::DEFAULT_VERSION).encode(&nbt)).expect("Valid little-endian NBT");
+		assert_eq!(format, NbtFileFormat::LittleEndianHeaderNbt(BedrockHeader::DEFAULT_VERSION));
+		assert_eq!(level, nbt);
+	}
+
+	#[test]
+	fn test_snbt_padding() {
+		let (_, expected) = NbtElement::from_str("{a:1b}").expect("Valid SNBT");
+		for name in ["test.snbt", "test.txt"] {
+			let (nbt, format) = Tab::parse_raw(name, "\u{FEFF} {a:1b}\r\n\0\0".as_bytes().to_vec()).expect("Valid SNBT");
+			assert_eq!(format, NbtFileFormat::Snbt);
+			assert_eq!(nbt, expected);
+
+			// the `x`, counting the byte order mark as a column
+			let e = Tab::parse_raw(name, "\u{FEFF}{a:1b} x".as_bytes().to_vec()).expect_err("Invalid SNBT");
+			assert!(format!("{e:#}").starts_with("Failed to parse SNBT: expected the end of the text at line 1, column 9\n"));
+		}
+		assert!(Tab::parse_raw("test.bin", vec![0xFF, 0xFE, 0x00]).expect_err("Not any format").to_string().starts_with("Failed to find file type"));
+	}
+
+	#[test]
+	fn test_network_nbt_round_trip() {
+		let original = NbtElement::from_be_file(include_bytes!("../../assets/test.nbt")).expect("Included test nbt contains valid data");
+		let network = original.to_be_network_file();
+		assert_eq!(network.len(), original.to_be_file().len() - 2);
+
+		let (nbt, format) = Tab::parse_raw("packet.nbt", network.clone()).expect("Valid network NBT");
+		assert_eq!(format, NbtFileFormat::NetworkNbt);
+		assert_eq!(nbt, original);
+		assert_eq!(format.encode(&nbt), network);
+		let mut streamed = vec![];
+		format.encode_to(&nbt, &mut streamed).expect("Writes to a vec");
+		assert_eq!(streamed, network);
+		assert_eq!(Tab::parse_raw("test.nbt", original.to_be_file()).expect("Valid NBT").1, NbtFileFormat::Nbt);
+
+		let (_, empty) = NbtElement::from_str("{}").expect("Valid SNBT");
+		assert_eq!(Tab::parse_raw("packet.nbt", empty.to_be_network_file()).expect("Valid network NBT"), (empty, NbtFileFormat::NetworkNbt));
+
+		// the placeholder is only shown, the file has no name to write
+		let tab = Tab::new(nbt, FilePath::new("packet.nbt").expect("Valid path"), format, PhysicalSize::new(1920, 1080)).expect("Valid tab");
+		assert_eq!(tab.root_name(), "packet.nbt (unnamed root)");
+	}
+
+	#[test]
+	fn test_bedrock_header_round_trip() {
+		// laid out as the game writes a `level.dat`, storage version 10 then the length of the rest
+		let (_, level) = NbtElement::from_str(r#"{StorageVersion:10,LevelName:"Bedrock level",NetworkVersion:748,lastOpenedWithVersion:[1,21,50,7,0],RandomSeed:-1234567890L,abilities:{flying:0b,walkSpeed:0.1f}}"#).expect("Valid SNBT");
+		let payload = level.to_le_file(None);
+		let mut file = vec![];
+		file.extend(10_u32.to_le_bytes());
+		file.extend((payload.len() as u32).to_le_bytes());
+		file.extend(&payload);
+
+		let (nbt, format) = Tab::parse_raw("level.dat", file.clone()).expect("Valid Bedrock level.dat");
+		assert_eq!(format, NbtFileFormat::LittleEndianHeaderNbt(10));
+		assert_eq!(nbt, level);
+		assert_eq!(format.encode(&nbt), file);
+
+		// the version is kept and the length follows the tree
+		let (_, grown) = NbtElement::from_str(r#"{StorageVersion:10,LevelName:"A much longer name for the same Bedrock level",NetworkVersion:748}"#).expect("Valid SNBT");
+		let saved = format.encode(&grown);
+		assert_eq!(saved[..4], 10_u32.to_le_bytes());
+		assert_eq!(saved[4..8], ((saved.len() - BedrockHeader::LEN) as u32).to_le_bytes());
+		assert_eq!(Tab::parse_raw("level.dat", saved).expect("Valid Bedrock level.dat"), (grown, format));
+
+		// a little off is still read as the header it is, and fixed when it's saved
+		let mut slightly_off = file.clone();
+		slightly_off[4..8].copy_from_slice(&(payload.len() as u32 + 2).to_le_bytes());
+		let (nbt, format) = Tab::parse_raw("level.dat", slightly_off).expect("Valid Bedrock level.dat");
+		assert_eq!(format, NbtFileFormat::LittleEndianHeaderNbt(10));
+		assert_eq!(format.encode(&nbt), file);
+
+		let mut wildly_off = file.clone();
+		wildly_off[4..8].copy_from_slice(&(payload.len() as u32 * 4).to_le_bytes());
+		let e = Tab::parse_raw("level.dat", wildly_off.clone()).expect_err("Header length is wildly off");
+		assert!(e.downcast_ref::<BedrockHeaderMismatch>().is_some_and(|mismatch| mismatch.declared as usize == payload.len() * 4 && mismatch.actual == payload.len()));
+		let (nbt, format) = Tab::parse_as("level.dat", wildly_off, NbtFileFormat::LittleEndianHeaderNbt(BedrockHeader::DEFAULT_VERSION)).expect("Loaded anyway");
+		assert_eq!(format, NbtFileFormat::LittleEndianHeaderNbt(10));
+		assert_eq!(format.encode(&nbt), file);
+	}
+
+	#[test]
+	fn test_file_path_for_url() {
+		assert_eq!(FilePath::for_url("https://example.com/worlds/level.dat").name(), "level.dat");
+		assert_eq!(FilePath::for_url("https://example.com/r.0.0.mca?token=abc#top").name(), "r.0.0.mca");
+		assert_eq!(FilePath::for_url("https://example.com/my%20house.nbt").name(), "my house.nbt");
+		assert_eq!(FilePath::for_url("https://example.com/a%2Fb.nbt").name(), "a_b.nbt");
+		assert_eq!(FilePath::for_url("https://example.com/").name(), "download");
+		assert_eq!(FilePath::for_url("https://example.com").name(), "download");
+		assert_eq!(FilePath::for_url("https://example.com/..").name(), "download");
+	}
+
+	#[test]
+	fn test_compression_levels() {
+		let original = NbtElement::from_be_file(include_bytes!("../../assets/test.nbt")).expect("Included test nbt contains valid data");
+
+		for level in [CompressionLevel::Fast, CompressionLevel::Default, CompressionLevel::Best] {
+			let (gzip, format) = Tab::parse_raw("test.dat", NbtFileFormat::Gzip.encode_with_level(&original, level)).expect("Valid gzip file");
+			assert_eq!(format, NbtFileFormat::Gzip);
+			assert_eq!(gzip, original);
+
+			let (zlib, format) = Tab::parse_raw("test.dat", NbtFileFormat::Zlib.encode_with_level(&original, level)).expect("Valid zlib file");
+			assert_eq!(format, NbtFileFormat::Zlib);
+			assert_eq!(zlib, original);
+		}
+	}
+
+	#[test]
+	fn test_encode_to() {
+		let original = NbtElement::from_be_file(include_bytes!("../../assets/test.nbt")).expect("Included test nbt contains valid data");
+
+		for format in [NbtFileFormat::Nbt, NbtFileFormat::Gzip, NbtFileFormat::Zlib, NbtFileFormat::Zstd, NbtFileFormat::Lz4, NbtFileFormat::Snbt] {
+			let mut streamed = vec![];
+			format.encode_to_with_level(&original, &mut streamed, CompressionLevel::Fast).expect("Writing to a vec can't fail");
+			let (nbt, _) = Tab::parse_raw("test.nbt", streamed).expect("Valid streamed file");
+			assert_eq!(nbt, original);
+		}
+
+		// larger than the writer's buffer, so it has to be drained to the sink partway through
+		let large = NbtElement::from_be_file(&[&[10, 0, 0, 7, 0, 1, b'a'], &20_000_000_u32.to_be_bytes()[..], &vec![1; 20_000_000], &[0]].concat()).expect("Valid NBT");
+		let mut streamed = vec![];
+		large.to_be_file_to(&mut streamed).expect("Writing to a vec can't fail");
+		assert!(streamed == large.to_be_file());
+	}
+
+	#[test]
+	fn test_pretty_snbt_round_trip() {
+		let original = NbtElement::from_be_file(include_bytes!("../../assets/test.nbt")).expect("Included test nbt contains valid data");
+
+		let pretty = original.to_pretty_string("\t");
+		assert!(pretty.lines().count() > 1);
+		let (_, reopened) = NbtElement::from_str(&pretty).expect("Valid SNBT");
+		assert_eq!(reopened, original);
+
+		let (_, reopened) = NbtElement::from_str(&original.to_string()).expect("Valid SNBT");
+		assert_eq!(reopened, original);
+	}
+
+	#[test]
+	fn test_selected_text_clicks() {
+		let (_, nbt) = NbtElement::from_str(r#"{a:"hello world"}"#).expect("Valid SNBT");
+		let mut tab = Tab::new(nbt, FilePath::new("test.nbt").expect("Valid path"), NbtFileFormat::Nbt, PhysicalSize::new(1920, 1080)).expect("Valid tab");
+		let click = || {
+			let mut text = SelectedText::from_raw(0, 0, HEADER_SIZE + 16, Some(("a".to_owned(), TextColor::TreeKey, false)), Some(("hello world".to_owned(), TextColor::TreeString, true)), OwnedIndices::from([0]), None).expect("Valid selected text");
+			// within "hello"
+			text.cursor = 2;
+			text
+		};
+
+		tab.set_selected_text_with_doubleclick(Ok(click())).expect("Nothing to save");
+		let text = tab.selected_text.as_ref().expect("Text was selected");
+		assert_eq!((text.cursor, text.selection), (2, None));
+
+		tab.set_selected_text_with_doubleclick(Ok(click())).expect("Saved previous text");
+		let text = tab.selected_text.as_ref().expect("Text was selected");
+		assert_eq!((text.cursor, text.selection), (5, Some(0)));
+
+		tab.set_selected_text_with_doubleclick(Ok(click())).expect("Saved previous text");
+		let text = tab.selected_text.as_ref().expect("Text was selected");
+		assert_eq!((text.cursor, text.selection), (11, Some(0)));
+
+		assert!(tab.set_selected_text_with_doubleclick(Err(SelectedTextConstructionError::Region)).is_err());
+		assert!(tab.selected_text.is_none());
+	}
+
+	#[test]
+	fn test_jump_to_search_hit() {
+		let (_, nbt) = NbtElement::from_str(r#"{a:{b:"x"},c:{d:"x"}}"#).expect("Valid SNBT");
+		let mut tab = Tab::new(nbt, FilePath::new("test.nbt").expect("Valid path"), NbtFileFormat::Nbt, PhysicalSize::new(1920, 1080)).expect("Valid tab");
+		// `b` and `d`, both hidden while the root is closed
+		for true_line_number in [3, 5] {
+			tab.bookmarks.toggle(MarkedLine::new(true_line_number, 1)).expect("New bookmark");
+		}
+
+		assert_eq!(tab.jump_to_search_hit(true).expect("Valid hit"), Some((0, 2)));
+		assert!(tab.root.is_open());
+		assert!(tab.root.navigate(&OwnedIndices::from([0])).expect("Valid indices").element.is_open());
+		assert_eq!(tab.last_search_hit, Some(3));
+
+		assert_eq!(tab.jump_to_search_hit(true).expect("Valid hit"), Some((1, 2)));
+		assert_eq!(tab.jump_to_search_hit(true).expect("Valid hit"), Some((0, 2)));
+		assert_eq!(tab.jump_to_search_hit(false).expect("Valid hit"), Some((1, 2)));
+		assert_eq!(tab.last_search_hit, Some(5));
+
+		tab.bookmarks.clear();
+		assert_eq!(tab.jump_to_search_hit(true).expect("No hits"), None);
+	}
+
+	#[test]
+	fn test_scroll_is_clamped_to_window_height() {
+		let snbt = format!("{{{}}}", (0..200).map(|idx| format!("a{idx}:{idx}")).collect::<Vec<_>>().join(","));
+		let (_, nbt) = NbtElement::from_str(&snbt).expect("Valid SNBT");
+
+		for (width, height) in [(1920, 400), (400, 1200)] {
+			let mut tab = Tab::new(nbt.clone(), FilePath::new("test.nbt").expect("Valid path"), NbtFileFormat::Nbt, PhysicalSize::new(width, height)).expect("Valid tab");
+			open_element(&mut tab.root, Indices::EMPTY, &mut tab.bookmarks).expect("Root can be opened");
+
+			tab.on_scroll(f32::MAX);
+			let scroll = tab.consts().scroll;
+			let bottom = HEADER_SIZE + tab.root.height() * 16 - scroll;
+			// the last row is reachable, but the tree can't be scrolled off screen
+			assert!(bottom <= height as usize, "last row is below a {width}x{height} window");
+			assert!(bottom + 48 >= height as usize, "tree is scrolled off a {width}x{height} window");
+			assert_eq!(scroll % 16, 0);
+		}
+	}
+
+	#[test]
+	fn test_jump_to_bookmark() {
+		let snbt = format!("{{{}}}", (0..200).map(|idx| format!("a{idx}:{idx}")).collect::<Vec<_>>().join(","));
+		let (_, nbt) = NbtElement::from_str(&snbt).expect("Valid SNBT");
+		let mut tab = Tab::new(nbt, FilePath::new("test.nbt").expect("Valid path"), NbtFileFormat::Nbt, PhysicalSize::new(1920, 1080)).expect("Valid tab");
+		for true_line_number in [20, 100, 195] {
+			tab.bookmarks.toggle(MarkedLine::new(true_line_number, 1)).expect("New bookmark");
+		}
+
+		assert_eq!(tab.jump_to_bookmark(true).expect("Valid bookmark"), Some((0, 3)));
+		assert!(tab.root.is_open());
+		assert_eq!(tab.consts().scroll, (19 - Tab::JUMP_ROWS_ABOVE) * 16);
+		assert_eq!(tab.jump_to_bookmark(true).expect("Valid bookmark"), Some((1, 3)));
+		// too close to the end to be scrolled to the usual row, but the next jump still moves on from it
+		assert_eq!(tab.jump_to_bookmark(true).expect("Valid bookmark"), Some((2, 3)));
+		assert_eq!(tab.jump_to_bookmark(true).expect("Valid bookmark"), Some((0, 3)));
+		assert_eq!(tab.jump_to_bookmark(false).expect("Valid bookmark"), Some((2, 3)));
+
+		// scrolling away starts from the scroll position instead of the last bookmark
+		tab.modify_scroll(|_| 50 * 16);
+		assert_eq!(tab.jump_to_bookmark(true).expect("Valid bookmark"), Some((1, 3)));
+		tab.modify_scroll(|_| 50 * 16);
+		assert_eq!(tab.jump_to_bookmark(false).expect("Valid bookmark"), Some((0, 3)));
+
+		tab.bookmarks.clear();
+		assert_eq!(tab.jump_to_bookmark(true).expect("No bookmarks"), None);
+	}
+
+	#[test]
+	fn test_was_modified_elsewhere() {
+		let path = std::env::temp_dir().join(format!("nbtworkbench-modified-{}.snbt", std::process::id()));
+		std::fs::write(&path, "{}").expect("Temp dir is writable");
+		let (_, nbt) = NbtElement::from_str("{}").expect("Valid SNBT");
+		let mut tab = Tab::new(nbt, FilePath::new(&path).expect("Valid path"), NbtFileFormat::Snbt, PhysicalSize::new(1920, 1080)).expect("Valid tab");
+		assert!(!tab.was_modified_elsewhere());
+
+		std::fs::write(&path, "{a:1b}").expect("Temp dir is writable");
+		assert!(tab.was_modified_elsewhere());
+
+		// reading it again makes its contents the tab's own
+		tab.reload().expect("Valid SNBT file");
+		assert!(!tab.was_modified_elsewhere());
+		assert_eq!(tab.root.len(), Some(1));
+
+		let _ = std::fs::remove_file(&path);
+	}
+
+	#[test]
+	fn test_backup_on_save() {
+		let path = std::env::temp_dir().join(format!("nbtworkbench-backup-{}.snbt", std::process::id()));
+		let backup_path = path.with_file_name(format!("nbtworkbench-backup-{}.snbt.bak", std::process::id()));
+		let _ = std::fs::remove_file(&backup_path);
+		let (_, nbt) = NbtElement::from_str("{}").expect("Valid SNBT");
+
+		let (backup, result) = Tab::save_to(&nbt, NbtFileFormat::Snbt, Some("{a:1b}"), &path, true);
+		backup.expect("Nothing to back up yet");
+		result.expect("Temp dir is writable");
+		assert!(!backup_path.exists());
+
+		let (backup, result) = Tab::save_to(&nbt, NbtFileFormat::Snbt, Some("{a:2b}"), &path, true);
+		backup.expect("Temp dir is writable");
+		result.expect("Temp dir is writable");
+		assert_eq!(std::fs::read(&backup_path).expect("Backed up"), b"{a:1b}");
+		assert_eq!(std::fs::read(&path).expect("Saved"), b"{a:2b}");
+
+		let _ = std::fs::remove_file(&path);
+		let _ = std::fs::remove_file(&backup_path);
+	}
+
+	#[test]
+	fn test_read_only() {
+		let path = std::env::temp_dir().join(format!("nbtworkbench-read-only-{}.snbt", std::process::id()));
+		std::fs::write(&path, "{}").expect("Temp dir is writable");
+		let mut permissions = std::fs::metadata(&path).expect("File exists").permissions();
+		permissions.set_readonly(true);
+		std::fs::set_permissions(&path, permissions).expect("Temp file permissions can be changed");
+		let (_, nbt) = NbtElement::from_str("{}").expect("Valid SNBT");
+		let mut tab = Tab::new(nbt, FilePath::new(&path).expect("Valid path"), NbtFileFormat::Snbt, PhysicalSize::new(1920, 1080)).expect("Valid tab");
+		let _ = std::fs::remove_file(&path);
+
+		let mut notifications = NotificationManager::new();
+		assert!(!tab.ensure_writable(&mut notifications).passed());
+		tab.set_read_only(false).expect("Nothing is held");
+		assert!(tab.ensure_writable(&mut notifications).passed());
+
+		// it couldn't be dropped anywhere
+		tab.held_entry = Some(HeldEntry::from_aether((None, NbtElement::Byte(NbtByte::default()))));
+		assert!(tab.set_read_only(true).is_err());
+	}
+
+	#[test]
+	fn test_duplicate() {
+		let (_, nbt) = NbtElement::from_str("{a:1b}").expect("Valid SNBT");
+		let mut tab = Tab::new(nbt, FilePath::new("saves/level.dat").expect("Valid path"), NbtFileFormat::Gzip, PhysicalSize::new(1920, 1080)).expect("Valid tab");
+		tab.history.append(WorkbenchAction::Rename {
+			indices: OwnedIndices::new(),
+			key: None,
+			value: None,
+		});
+
+		let copy = tab.duplicate();
+		assert_eq!(copy.path.name(), "level.dat (copy)");
+		assert_eq!(copy.path.path(), Path::new("saves/level.dat (copy)"));
+		assert_eq!(copy.format, NbtFileFormat::Gzip);
+		assert_eq!(copy.root.to_string(), tab.root.to_string());
+		assert!(copy.is_detached());
+		assert!(!copy.history.has_unsaved_changes());
+	}
+
+	#[test]
+	fn test_scratch_pad() {
+		let dims = Vec2u::new(1920, 1080);
+		let mut tab = Tab::new_empty_tab(NewTabRoot::Scratch, "scratch.snbt", PhysicalSize::new(1920, 1080));
+		assert_eq!(tab.format, NbtFileFormat::Snbt);
+		assert!(tab.is_detached());
+		tab.scratch_pad.as_mut().expect("Scratch tab").insert_str("a: [1b, 2b]", dims);
+
+		// left alone for a while before it's parsed, but unsaved straight away
+		tab.reparse_scratch_pad();
+		assert_eq!(tab.root.to_string(), "{}");
+		assert!(tab.history.has_unsaved_changes());
+		std::thread::sleep(ScratchPad::PARSE_DELAY);
+		tab.reparse_scratch_pad();
+		assert_eq!(tab.root.to_string(), "{a:[1b,2b]}");
+		assert!(tab.history.has_unsaved_changes());
+
+		// the tree stays as it last parsed
+		tab.scratch_pad.as_mut().expect("Scratch tab").insert_str("x", dims);
+		std::thread::sleep(ScratchPad::PARSE_DELAY);
+		tab.reparse_scratch_pad();
+		assert_eq!(tab.root.to_string(), "{a:[1b,2b]}");
+		let error = tab.scratch_pad.as_ref().and_then(ScratchPad::error).expect("Invalid SNBT");
+		assert_eq!((error.line, error.column), (1, 13));
+
+		tab.rewrite_scratch_pad();
+		let scratch_pad = tab.scratch_pad.as_ref().expect("Scratch tab");
+		assert_eq!(scratch_pad.value(), tab.root.to_pretty_string(PrettyFormatter::DEFAULT_INDENT));
+		assert!(scratch_pad.error().is_none());
+	}
+
+	#[test]
+	fn test_refresh_keeps_view() {
+		let (_, nbt) = NbtElement::from_str(r"{a:{b:1b,c:{d:2b}},e:[1,2,3]}").expect("Valid SNBT");
+		let mut tab = Tab::new(nbt, FilePath::new("test.nbt").expect("Valid path"), NbtFileFormat::Nbt, PhysicalSize::new(1920, 1080)).expect("Valid tab");
+		let indices = |tab: &Tab, path: &str| NbtPath::parse(path).expect("Valid path").resolve(&tab.root).expect("Path exists");
+		open_element(&mut tab.root, Indices::EMPTY, &mut tab.bookmarks).expect("Root can be opened");
+		for path in ["a", "a.c"] {
+			let indices = indices(&tab, path);
+			open_element(&mut tab.root, &indices, &mut tab.bookmarks).expect("Can be opened");
+		}
+		let d = tab.root.navigate(&indices(&tab, "a.c.d")).expect("Valid indices");
+		tab.bookmarks.toggle(MarkedLine::new(d.true_line_number, d.line_number)).expect("New bookmark");
+
+		// keys moved around and added, so only paths still lead to the same elements
+		let view = TabView::capture(&tab);
+		tab.bookmarks.clear();
+		(_, tab.root) = NbtElement::from_str(r"{z:{},a:{x:1b,c:{y:3b,d:5b},b:1b},e:[1]}").expect("Valid SNBT");
+		view.restore(&mut tab);
+
+		assert!(tab.root.is_open());
+		assert!(tab.root.navigate(&indices(&tab, "a")).expect("Valid indices").element.is_open());
+		assert!(tab.root.navigate(&indices(&tab, "a.c")).expect("Valid indices").element.is_open());
+		assert!(!tab.root.navigate(&indices(&tab, "z")).expect("Valid indices").element.is_open());
+		let d = tab.root.navigate(&indices(&tab, "a.c.d")).expect("Valid indices");
+		let bookmarks = tab.bookmarks.iter().map(|bookmark| (bookmark.true_line_number(), bookmark.line_number())).collect::<Vec<_>>();
+		assert_eq!(bookmarks, [(d.true_line_number, d.line_number)]);
+	}
+
+	#[test]
+	fn test_go_to_chunk() {
+		let mut tab = Tab::new_empty_tab(NewTabRoot::Region, "r.-11.3.mca", PhysicalSize::new(1920, 1080));
+		// at -341, 127 in the world
+		tab.root.as_region_mut().expect("Region").chunks[31 * 32 + 11] = NbtElement::Chunk(NbtChunk::new(NbtCompound::default(), (11, 31), ChunkFileFormat::Zlib, 1));
+		tab.root.recache();
+
+		tab.go_to_chunk((-341, 127)).expect("Chunk is in the region");
+		assert!(tab.root.is_open());
+		assert!(tab.selected_text.as_ref().is_some_and(|text| text.indices.iter().eq([31 * 32 + 11])));
+		tab.go_to_chunk((11, 31)).expect("Chunk is in the region");
+		assert_eq!(tab.go_to_chunk((0, 0)).expect_err("Chunk isn't in the file").to_string(), "Chunk 0, 0 isn't in r.-11.3.mca");
+		assert_eq!(tab.go_to_chunk((0, 100)).expect_err("Chunk is in another region").to_string(), "Chunk 0, 100 is in r.0.3.mca, not r.-11.3.mca");
+
+		tab.root.on_style_change(&mut tab.bookmarks);
+		tab.selected_text = None;
+		tab.go_to_chunk((-341, 127)).expect("Chunk is in the region");
+		assert!(tab.selected_text.is_none());
+		assert!(tab.flashed_chunk.is_some_and(|(idx, _)| idx == 31 * 32 + 11));
+
+		assert_eq!(NbtRegion::parse_chunk_pos("x=-341, z=127"), Some((-341, 127)));
+		assert_eq!(NbtRegion::parse_chunk_pos("(3 4)"), Some((3, 4)));
+		assert_eq!(NbtRegion::parse_chunk_pos("[0].Level"), None);
+	}
+
+	#[test]
+	fn test_format_warning() {
+		let (_, nbt) = NbtElement::from_str("[1b,2b]").expect("Valid SNBT");
+		let mut tab = Tab::new(nbt, FilePath::new("list.nbt").expect("Valid path"), NbtFileFormat::Snbt, PhysicalSize::new(1920, 1080)).expect("Valid tab");
+		assert_eq!(tab.cycled_format(false), NbtFileFormat::Nbt);
+		assert_eq!(tab.format_warning(NbtFileFormat::Nbt), Some(FormatWarning::RootList));
+		assert_eq!(tab.format_warning(NbtFileFormat::Gzip), Some(FormatWarning::RootList));
+		assert_eq!(tab.format_warning(NbtFileFormat::NetworkNbt), None);
+		assert_eq!(tab.format_warning(NbtFileFormat::LittleEndianNbt), None);
+
+		// wrapped in a compound, which can be undone
+		let compound = NbtElement::Compound(NbtCompound::new(vec![CompoundEntry::new("data".into(), tab.root.clone())]));
+		tab.history.append(replace_element(&mut tab.root, (None, compound), OwnedIndices::new(), mutable_indices!(tab)).expect("A list at the root can be wrapped").into_action());
+		assert_eq!(tab.root.to_string(), "{data:[1b,2b]}");
+		assert_eq!(tab.format_warning(NbtFileFormat::Nbt), None);
+		tab.undo().expect("The wrap can be undone");
+		assert_eq!(tab.root.to_string(), "[1b,2b]");
+
+		tab.root_list_confirmed = true;
+		assert_eq!(tab.format_warning(NbtFileFormat::Nbt), None);
+
+		let region = Tab::new_empty_tab(NewTabRoot::Region, "r.0.0.mca", PhysicalSize::new(1920, 1080));
+		assert_eq!(region.cycled_format(false), NbtFileFormat::Snbt);
+		assert_eq!(region.cycled_format(true), NbtFileFormat::Snbt);
+		assert_eq!(region.format_warning(NbtFileFormat::Snbt), Some(FormatWarning::RegionData));
+		assert_eq!(region.format_warning(NbtFileFormat::Mca), None);
+	}
 }
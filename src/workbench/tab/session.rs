@@ -0,0 +1,110 @@
+use std::{path::PathBuf, sync::atomic::Ordering};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	config,
+	workbench::tab::{NbtFileFormat, manager::TabManager},
+};
+
+/// The tabs open when the app was last quit, reopened at startup unless `restore_session = false` in the config
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+pub struct Session {
+	#[serde(default)]
+	pub active_tab: usize,
+	#[serde(default)]
+	pub tabs: Vec<SessionTab>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct SessionTab {
+	pub path: PathBuf,
+	pub format: NbtFileFormat,
+	#[serde(default)]
+	pub scroll: usize,
+	#[serde(default)]
+	pub horizontal_scroll: usize,
+}
+
+impl Session {
+	fn path() -> Option<PathBuf> { dirs::config_dir().map(|config_dir| config_dir.join("nbtworkbench/session.toml")) }
+
+	/// Tabs which were never saved or were downloaded or duplicated without being saved have nothing to reopen, so they're left out
+	#[must_use]
+	pub fn capture(tabs: &TabManager) -> Self {
+		let mut active_tab = 0;
+		let tabs = tabs
+			.iter()
+			.enumerate()
+			.filter(|(_, tab)| !tab.is_detached())
+			.filter_map(|(idx, tab)| Some((idx, tab, std::fs::canonicalize(tab.path.path()).ok().filter(|path| path.is_file())?)))
+			.enumerate()
+			.map(|(session_idx, (idx, tab, path))| {
+				if idx == tabs.active_tab_idx() {
+					active_tab = session_idx;
+				}
+				let consts = tab.consts();
+				SessionTab {
+					path,
+					format: tab.format,
+					scroll: consts.scroll,
+					horizontal_scroll: consts.horizontal_scroll,
+				}
+			})
+			.collect();
+		Self { active_tab, tabs }
+	}
+
+	/// An empty session if there's none saved yet
+	///
+	/// # Errors
+	/// * If the session file couldn't be read or parsed
+	pub fn read() -> Result<Self> {
+		let Some(path) = Self::path().filter(|path| path.is_file()) else { return Ok(Self::default()) };
+		let data = std::fs::read_to_string(&path).with_context(|| format!("Could not read {}", path.display()))?;
+		toml::from_str(&data).with_context(|| format!("Could not parse {}", path.display()))
+	}
+
+	/// # Errors
+	/// * If the session file couldn't be written
+	pub fn write(&self) -> Result<()> {
+		if config::DISABLE_FILE_WRITES.load(Ordering::Relaxed) {
+			return Ok(())
+		}
+
+		let path = Self::path().context("Could not find the config directory")?;
+		if let Some(parent) = path.parent() {
+			std::fs::create_dir_all(parent)?;
+		}
+		crate::util::write_atomic(&path, toml::to_string_pretty(self)?.as_bytes())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_roundtrip() {
+		let session = Session {
+			active_tab: 1,
+			tabs: vec![
+				SessionTab {
+					path: PathBuf::from("/saves/world/level.dat"),
+					format: NbtFileFormat::Gzip,
+					scroll: 48,
+					horizontal_scroll: 0,
+				},
+				SessionTab {
+					path: PathBuf::from("/saves/world/region/r.0.0.mca"),
+					format: NbtFileFormat::Mca,
+					scroll: 0,
+					horizontal_scroll: 16,
+				},
+			],
+		};
+		let data = toml::to_string_pretty(&session).expect("Session is serializable");
+		assert_eq!(toml::from_str::<Session>(&data).expect("Session is deserializable"), session);
+	}
+}
@@ -0,0 +1,108 @@
+use std::{
+	path::Path,
+	sync::mpsc::{Receiver, channel},
+	time::SystemTime,
+};
+
+use anyhow::{Context, Result};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Enough of a file's metadata to tell whether it has been written to since
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FileStamp {
+	modified: SystemTime,
+	len: u64,
+}
+
+impl FileStamp {
+	/// # Errors
+	/// * If the file's metadata couldn't be read
+	pub fn of(path: &Path) -> std::io::Result<Self> {
+		let metadata = std::fs::metadata(path)?;
+		Ok(Self { modified: metadata.modified()?, len: metadata.len() })
+	}
+}
+
+/// Watches a tab's file for writes made by other programs
+pub struct FileWatcher {
+	_watcher: RecommendedWatcher,
+	rx: Receiver<()>,
+	/// Of the file as the tab last read or wrote it, so its own saves aren't mistaken for someone else's
+	known: Option<FileStamp>,
+}
+
+impl FileWatcher {
+	/// # Errors
+	/// * If the directory of `path` couldn't be watched
+	pub fn new(path: &Path) -> Result<Self> {
+		let (tx, rx) = channel();
+		let file_name = path.file_name().context("File has no name")?.to_owned();
+		let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+			if let Ok(notify::Event { kind: EventKind::Create(_) | EventKind::Modify(_), paths, .. }) = event
+				&& paths.iter().any(|path| path.file_name() == Some(&file_name))
+			{
+				let _ = tx.send(());
+			}
+		})?;
+		// the directory since saving replaces the file with a new one, which a watch on the file itself wouldn't follow
+		let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+		watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+		Ok(Self {
+			_watcher: watcher,
+			rx,
+			known: FileStamp::of(path).ok(),
+		})
+	}
+
+	/// Takes the file at `path` as it is now to be the tab's own
+	pub fn sync(&mut self, path: &Path) {
+		self.rx.try_iter().for_each(drop);
+		self.known = FileStamp::of(path).ok();
+	}
+
+	/// Whether the file at `path` was written to by something else since last checked
+	pub fn poll(&mut self, path: &Path) -> bool {
+		if self.rx.try_iter().count() == 0 {
+			return false
+		}
+		let stamp = FileStamp::of(path).ok();
+		stamp != core::mem::replace(&mut self.known, stamp)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::{Duration, Instant};
+
+	use super::FileWatcher;
+
+	#[test]
+	fn test_poll() {
+		let dir = std::env::temp_dir().join(format!("nbtworkbench-watcher-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).expect("Temp dir is writable");
+		let path = dir.join("level.dat");
+		std::fs::write(&path, b"a").expect("Temp dir is writable");
+		let mut watcher = FileWatcher::new(&path).expect("Temp dir can be watched");
+		let poll_for = |watcher: &mut FileWatcher| {
+			let start = Instant::now();
+			while start.elapsed() < Duration::from_secs(2) {
+				if watcher.poll(&path) {
+					return true
+				}
+				std::thread::sleep(Duration::from_millis(10));
+			}
+			false
+		};
+
+		std::fs::write(&path, b"ab").expect("Temp dir is writable");
+		assert!(poll_for(&mut watcher));
+
+		// written by the tab itself
+		std::fs::write(&path, b"abc").expect("Temp dir is writable");
+		watcher.sync(&path);
+		assert!(!poll_for(&mut watcher));
+
+		let _ = std::fs::remove_dir_all(&dir);
+	}
+}